@@ -47,6 +47,7 @@ impl From<Peer> for grpc::Peer {
         let features = peer.features.bits();
 
         let supported_protocols = peer.supported_protocols.into_iter().map(|p| p.to_vec()).collect();
+        let reputation = peer.reputation_score();
         let user_agent = peer.user_agent;
         Self {
             public_key,
@@ -60,6 +61,7 @@ impl From<Peer> for grpc::Peer {
             features,
             supported_protocols,
             user_agent,
+            reputation,
         }
     }
 }
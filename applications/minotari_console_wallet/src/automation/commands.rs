@@ -33,7 +33,7 @@ use std::{
     time::{Duration, Instant},
 };
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDateTime, Utc};
 use digest::Digest;
 use futures::FutureExt;
 use log::*;
@@ -43,12 +43,20 @@ use minotari_wallet::{
     output_manager_service::{
         handle::{OutputManagerEvent, OutputManagerHandle},
         service::UseOutput,
+        UtxoConsolidationParams,
         UtxoSelectionCriteria,
     },
     transaction_service::{
         handle::{TransactionEvent, TransactionServiceHandle},
-        storage::models::WalletTransaction,
+        storage::models::{
+            BatchPaymentRecipient,
+            PaymentSchedule,
+            TransactionHistoryFilter,
+            TransactionHistoryRecord,
+            WalletTransaction,
+        },
     },
+    util::cron::CronSchedule,
     utxo_scanner_service::handle::UtxoScannerEvent,
     TransactionStage,
     WalletConfig,
@@ -93,6 +101,7 @@ use tari_core::{
             UnblindedOutput,
             WalletOutput,
         },
+        transaction_protocol::partially_signed::{PartiallySignedTransaction, PaymentKind},
     },
 };
 use tari_crypto::{
@@ -106,7 +115,11 @@ use tari_key_manager::{
 use tari_p2p::{auto_update::AutoUpdateConfig, peer_seeds::SeedPeer, PeerSeedsConfig};
 use tari_script::{push_pubkey_script, CheckSigSchnorrSignature};
 use tari_shutdown::Shutdown;
-use tari_utilities::{hex::Hex, ByteArray, SafePassword};
+use tari_utilities::{
+    hex::{Hex, HexError},
+    ByteArray,
+    SafePassword,
+};
 use tokio::{
     sync::{broadcast, mpsc},
     time::{sleep, timeout},
@@ -139,7 +152,7 @@ use crate::{
         Step3OutputsForSelf,
         Step4OutputsForLeader,
     },
-    cli::{CliCommands, CliRecipientInfo, MakeItRainTransactionType},
+    cli::{CliCommands, CliRecipientInfo, MakeItRainTransactionType, TxHistoryFormat},
     init::init_wallet,
     recovery::{get_seed_from_seed_words, wallet_recovery},
     utils::db::{get_custom_base_node_peer_from_db, CUSTOM_BASE_NODE_ADDRESS_KEY, CUSTOM_BASE_NODE_PUBLIC_KEY_KEY},
@@ -155,15 +168,71 @@ pub(crate) const SPEND_STEP_2_SELF: &str = "step_2_for_self";
 pub(crate) const SPEND_STEP_3_SELF: &str = "step_3_for_self";
 pub(crate) const SPEND_STEP_3_PARTIES: &str = "step_3_for_parties";
 pub(crate) const SPEND_STEP_4_LEADER: &str = "step_4_for_leader_from_";
+// The number of blocks within which a transaction should be mined when its fee per gram is estimated
+const FEE_ESTIMATE_TARGET_BLOCKS: u64 = 3;
 
 #[derive(Debug)]
 pub struct SentTransaction {}
 
+/// Returns the given fee per gram or, if none was given, the base node's estimate for the transaction to be mined
+/// within `FEE_ESTIMATE_TARGET_BLOCKS` blocks. Falls back to the configured fee per gram if the estimate fails.
+async fn fee_per_gram_or_estimate(
+    mut wallet_transaction_service: TransactionServiceHandle,
+    fee_per_gram: Option<MicroMinotari>,
+    default_fee_per_gram: u64,
+) -> u64 {
+    if let Some(fee_per_gram) = fee_per_gram {
+        return fee_per_gram.as_u64();
+    }
+    match wallet_transaction_service
+        .estimate_fee(vec![FEE_ESTIMATE_TARGET_BLOCKS])
+        .await
+    {
+        Ok(estimates) => match estimates.first() {
+            Some(estimate) => {
+                debug!(
+                    target: LOG_TARGET,
+                    "Using estimated fee per gram {} for confirmation within {} blocks",
+                    estimate.fee_per_gram,
+                    estimate.target_blocks
+                );
+                estimate.fee_per_gram.as_u64()
+            },
+            None => default_fee_per_gram,
+        },
+        Err(e) => {
+            warn!(
+                target: LOG_TARGET,
+                "Could not estimate fee per gram, using the configured {}: {}", default_fee_per_gram, e
+            );
+            default_fee_per_gram
+        },
+    }
+}
+
+/// Returns the UTXO selection criteria that only spends outputs of the named account, or outputs of all accounts if
+/// no account is named
+async fn account_selection_criteria(
+    mut output_service: OutputManagerHandle,
+    account: Option<&str>,
+) -> Result<UtxoSelectionCriteria, CommandError> {
+    let account = match account {
+        Some(name) => Some(output_service.get_account_by_name(name).await?.id),
+        None => None,
+    };
+    Ok(UtxoSelectionCriteria::default().for_account(account))
+}
+
+fn parse_commitments(commitments: &[String]) -> Result<Vec<Commitment>, HexError> {
+    commitments.iter().map(|c| Commitment::from_hex(c)).collect()
+}
+
 /// Send a normal negotiated transaction to a recipient
 pub async fn send_tari(
     mut wallet_transaction_service: TransactionServiceHandle,
     fee_per_gram: u64,
     amount: MicroMinotari,
+    selection_criteria: UtxoSelectionCriteria,
     destination: TariAddress,
     message: String,
 ) -> Result<TxId, CommandError> {
@@ -171,7 +240,7 @@ pub async fn send_tari(
         .send_transaction(
             destination,
             amount,
-            UtxoSelectionCriteria::default(),
+            selection_criteria,
             OutputFeatures::default(),
             fee_per_gram * uT,
             message,
@@ -541,7 +610,15 @@ pub async fn make_it_rain(
                     // Send transaction
                     let tx_id = match transaction_type {
                         MakeItRainTransactionType::Interactive => {
-                            send_tari(tx_service, fee, amount, address.clone(), msg.clone()).await
+                            send_tari(
+                                tx_service,
+                                fee,
+                                amount,
+                                UtxoSelectionCriteria::default(),
+                                address.clone(),
+                                msg.clone(),
+                            )
+                            .await
                         },
                         MakeItRainTransactionType::StealthOneSided => {
                             send_one_sided_to_stealth_address(
@@ -1747,10 +1824,22 @@ pub async fn command_runner(
                 println!();
             },
             SendMinotari(args) => {
+                let mut selection_criteria =
+                    match account_selection_criteria(output_service.clone(), args.account.as_deref()).await {
+                        Ok(criteria) => criteria.for_labels(args.labels),
+                        Err(e) => {
+                            eprintln!("SendMinotari error! {}", e);
+                            continue;
+                        },
+                    };
+                selection_criteria.avoid_mixing_labels = args.avoid_mixing_labels;
+                let fee_per_gram =
+                    fee_per_gram_or_estimate(transaction_service.clone(), args.fee_per_gram, config.fee_per_gram).await;
                 match send_tari(
                     transaction_service.clone(),
-                    config.fee_per_gram,
+                    fee_per_gram,
                     args.amount,
+                    selection_criteria,
                     args.destination,
                     args.message,
                 )
@@ -1764,11 +1853,22 @@ pub async fn command_runner(
                 }
             },
             SendOneSidedToStealthAddress(args) => {
+                let mut selection_criteria =
+                    match account_selection_criteria(output_service.clone(), args.account.as_deref()).await {
+                        Ok(criteria) => criteria.for_labels(args.labels),
+                        Err(e) => {
+                            eprintln!("SendOneSidedToStealthAddress error! {}", e);
+                            continue;
+                        },
+                    };
+                selection_criteria.avoid_mixing_labels = args.avoid_mixing_labels;
+                let fee_per_gram =
+                    fee_per_gram_or_estimate(transaction_service.clone(), args.fee_per_gram, config.fee_per_gram).await;
                 match send_one_sided_to_stealth_address(
                     transaction_service.clone(),
-                    config.fee_per_gram,
+                    fee_per_gram,
                     args.amount,
-                    UtxoSelectionCriteria::default(),
+                    selection_criteria,
                     args.destination,
                     args.message,
                     PaymentId::Empty,
@@ -1785,6 +1885,40 @@ pub async fn command_runner(
                     Err(e) => eprintln!("SendOneSidedToStealthAddress error! {}", e),
                 }
             },
+            SendBatch(args) => {
+                let recipients = match load_batch_recipients_from_csv_file(args.input_file) {
+                    Ok(recipients) => recipients,
+                    Err(e) => {
+                        eprintln!("SendBatch error! {}", e);
+                        continue;
+                    },
+                };
+                let selection_criteria =
+                    match account_selection_criteria(output_service.clone(), args.account.as_deref()).await {
+                        Ok(criteria) => criteria,
+                        Err(e) => {
+                            eprintln!("SendBatch error! {}", e);
+                            continue;
+                        },
+                    };
+                let fee_per_gram =
+                    fee_per_gram_or_estimate(transaction_service.clone(), args.fee_per_gram, config.fee_per_gram).await;
+                let num_recipients = recipients.len();
+                let total = recipients.iter().map(|r| r.amount).sum::<MicroMinotari>();
+                match transaction_service
+                    .send_batch_transaction(recipients, selection_criteria, fee_per_gram, args.message)
+                    .await
+                {
+                    Ok(tx_id) => {
+                        println!(
+                            "Sent batch transaction {} paying {} to {} recipients",
+                            tx_id, total, num_recipients
+                        );
+                        tx_ids.push(tx_id);
+                    },
+                    Err(e) => eprintln!("SendBatch error! {}", e),
+                }
+            },
             MakeItRain(args) => {
                 let transaction_type = args.transaction_type();
                 if let Err(e) = make_it_rain(
@@ -1898,6 +2032,116 @@ pub async fn command_runner(
                     Err(e) => eprintln!("ImportTx error! {}", e),
                 };
             },
+            CreateUnsignedTx(args) => {
+                let selection_criteria =
+                    match account_selection_criteria(output_service.clone(), args.account.as_deref()).await {
+                        Ok(criteria) => criteria,
+                        Err(e) => {
+                            eprintln!("CreateUnsignedTx error! {}", e);
+                            continue;
+                        },
+                    };
+                let fee_per_gram =
+                    fee_per_gram_or_estimate(transaction_service.clone(), args.fee_per_gram, config.fee_per_gram).await;
+                let kind = if args.interactive {
+                    PaymentKind::Interactive
+                } else {
+                    PaymentKind::OneSided
+                };
+                let tx_id = match transaction_service
+                    .create_partially_signed_transaction(
+                        args.destination,
+                        args.amount,
+                        selection_criteria,
+                        fee_per_gram,
+                        args.message,
+                        PaymentId::Empty,
+                        kind,
+                    )
+                    .await
+                {
+                    Ok(tx_id) => tx_id,
+                    Err(e) => {
+                        eprintln!("CreateUnsignedTx error! {}", e);
+                        continue;
+                    },
+                };
+                debug!(target: LOG_TARGET, "create-unsigned-tx concluded with tx_id {}", tx_id);
+                match (kind, args.output_file) {
+                    (PaymentKind::OneSided, Some(file)) => {
+                        match transaction_service.get_partially_signed_transaction(tx_id).await {
+                            Ok(tx) => match write_json_file(&file, &tx) {
+                                Ok(_) => println!("Unsigned transaction {} written to {}", tx_id, file.display()),
+                                Err(e) => eprintln!("CreateUnsignedTx error! {}", e),
+                            },
+                            Err(e) => eprintln!("CreateUnsignedTx error! {}", e),
+                        }
+                    },
+                    (PaymentKind::OneSided, None) => {
+                        println!(
+                            "Created unsigned transaction {}, export it with `export-unsigned-tx`",
+                            tx_id
+                        )
+                    },
+                    (PaymentKind::Interactive, _) => println!(
+                        "Sent transaction {}, export it with `export-unsigned-tx` once the recipient has replied",
+                        tx_id
+                    ),
+                }
+            },
+            ExportUnsignedTx(args) => match transaction_service
+                .get_partially_signed_transaction(args.tx_id.into())
+                .await
+            {
+                Ok(tx) => match write_json_file(&args.output_file, &tx) {
+                    Ok(_) => println!(
+                        "Unsigned transaction {} written to {}",
+                        tx.tx_id,
+                        args.output_file.display()
+                    ),
+                    Err(e) => eprintln!("ExportUnsignedTx error! {}", e),
+                },
+                Err(e) => eprintln!("ExportUnsignedTx error! {}", e),
+            },
+            SignTx(args) => {
+                let tx = match read_json_file::<_, PartiallySignedTransaction>(&args.input_file) {
+                    Ok(tx) => tx,
+                    Err(e) => {
+                        eprintln!("SignTx error! {}", e);
+                        continue;
+                    },
+                };
+                println!("Signing {}", tx);
+                match transaction_service.sign_partially_signed_transaction(tx).await {
+                    Ok(tx) => match write_json_file(&args.output_file, &tx) {
+                        Ok(_) => println!(
+                            "Signed transaction {} written to {}",
+                            tx.tx_id,
+                            args.output_file.display()
+                        ),
+                        Err(e) => eprintln!("SignTx error! {}", e),
+                    },
+                    Err(e) => eprintln!("SignTx error! {}", e),
+                }
+            },
+            SubmitSignedTx(args) => {
+                let tx = match read_json_file::<_, PartiallySignedTransaction>(&args.input_file) {
+                    Ok(tx) => tx,
+                    Err(e) => {
+                        eprintln!("SubmitSignedTx error! {}", e);
+                        continue;
+                    },
+                };
+                let tx_id = tx.tx_id;
+                match transaction_service.submit_signed_transaction(tx).await {
+                    Ok(_) => {
+                        debug!(target: LOG_TARGET, "submit-signed-tx concluded with tx_id {}", tx_id);
+                        println!("Submitted signed transaction {}", tx_id);
+                        tx_ids.push(tx_id);
+                    },
+                    Err(e) => eprintln!("SubmitSignedTx error! {}", e),
+                }
+            },
             ExportSpentUtxos(args) => match output_service.get_spent_outputs().await {
                 Ok(utxos) => {
                     let mut unblinded_utxos: Vec<(UnblindedOutput, Commitment)> = Vec::with_capacity(utxos.len());
@@ -2003,11 +2247,21 @@ pub async fn command_runner(
                 }
             },
             InitShaAtomicSwap(args) => {
+                let selection_criteria =
+                    match account_selection_criteria(output_service.clone(), args.account.as_deref()).await {
+                        Ok(criteria) => criteria,
+                        Err(e) => {
+                            eprintln!("InitShaAtomicSwap error! {}", e);
+                            continue;
+                        },
+                    };
+                let fee_per_gram =
+                    fee_per_gram_or_estimate(transaction_service.clone(), args.fee_per_gram, config.fee_per_gram).await;
                 match init_sha_atomic_swap(
                     transaction_service.clone(),
-                    config.fee_per_gram,
+                    fee_per_gram,
                     args.amount,
-                    UtxoSelectionCriteria::default(),
+                    selection_criteria,
                     args.destination,
                     args.message,
                 )
@@ -2206,6 +2460,7 @@ pub async fn command_runner(
                             OutputManagerEvent::TxoValidationAlreadyBusy(_) => {
                                 println!("Validation already busy");
                             },
+                            OutputManagerEvent::UtxoConsolidationCreated { .. } => {},
                             _ => {
                                 println!("Validation failed");
                                 break;
@@ -2350,6 +2605,7 @@ pub async fn command_runner(
                                 OutputManagerEvent::TxoValidationAlreadyBusy(_) => {
                                     println!("Validation already busy");
                                 },
+                                OutputManagerEvent::UtxoConsolidationCreated { .. } => {},
                                 _ => {
                                     println!("Validation failed");
                                     break;
@@ -2406,6 +2662,307 @@ pub async fn command_runner(
                 println!("removing temp wallet in: {:?}", temp_path);
                 fs::remove_dir_all(temp_path)?;
             },
+            CreateAccount(args) => match output_service.create_account(args.name).await {
+                Ok(account) => {
+                    debug!(target: LOG_TARGET, "create-account concluded");
+                    println!("Created account `{}` with id {}", account.name, account.id);
+                    match output_service.get_account_address(account.id).await {
+                        Ok(address) => println!("Account address: {}", address.to_base58()),
+                        Err(e) => eprintln!("CreateAccount error! {}", e),
+                    }
+                },
+                Err(e) => eprintln!("CreateAccount error! {}", e),
+            },
+            ListAccounts => match output_service.get_accounts().await {
+                Ok(accounts) => {
+                    for account in accounts {
+                        let balance = match output_service.get_account_balance(account.id).await {
+                            Ok(balance) => balance,
+                            Err(e) => {
+                                eprintln!("ListAccounts error! {}", e);
+                                continue;
+                            },
+                        };
+                        match output_service.get_account_address(account.id).await {
+                            Ok(address) => println!(
+                                "{} ({}): {} - {}",
+                                account.name,
+                                account.id,
+                                balance,
+                                address.to_base58()
+                            ),
+                            Err(e) => eprintln!("ListAccounts error! {}", e),
+                        }
+                    }
+                },
+                Err(e) => eprintln!("ListAccounts error! {}", e),
+            },
+            MoveToAccount(args) => {
+                let commitments = match parse_commitments(&args.commitments) {
+                    Ok(commitments) => commitments,
+                    Err(e) => {
+                        eprintln!("MoveToAccount error! Invalid commitment: {}", e);
+                        continue;
+                    },
+                };
+                let account = match output_service.get_account_by_name(&args.account).await {
+                    Ok(account) => account,
+                    Err(e) => {
+                        eprintln!("MoveToAccount error! {}", e);
+                        continue;
+                    },
+                };
+                match output_service.move_outputs_to_account(commitments, account.id).await {
+                    Ok(num_moved) => println!("Moved {} output(s) to account `{}`", num_moved, account.name),
+                    Err(e) => eprintln!("MoveToAccount error! {}", e),
+                }
+            },
+            ListUtxos(args) => match output_service.get_unspent_outputs().await {
+                Ok(utxos) => {
+                    let utxos = utxos
+                        .into_iter()
+                        .filter(|o| args.label.is_none() || o.label == args.label)
+                        .filter(|o| !args.frozen || o.frozen)
+                        .collect::<Vec<_>>();
+                    for o in &utxos {
+                        println!(
+                            "{} {} label: {} account: {}{}",
+                            o.commitment.to_hex(),
+                            o.wallet_output.value,
+                            o.label.as_deref().unwrap_or("-"),
+                            o.account_id,
+                            if o.frozen { " (frozen)" } else { "" }
+                        );
+                    }
+                    println!(
+                        "{} output(s) worth {}",
+                        utxos.len(),
+                        utxos.iter().map(|o| o.wallet_output.value).sum::<MicroMinotari>()
+                    );
+                },
+                Err(e) => eprintln!("ListUtxos error! {}", e),
+            },
+            LabelUtxos(args) => {
+                let commitments = match parse_commitments(&args.commitments) {
+                    Ok(commitments) => commitments,
+                    Err(e) => {
+                        eprintln!("LabelUtxos error! Invalid commitment: {}", e);
+                        continue;
+                    },
+                };
+                let label = if args.clear { None } else { args.label };
+                match output_service.set_output_label(commitments, label).await {
+                    Ok(num_updated) => println!("Updated the label of {} output(s)", num_updated),
+                    Err(e) => eprintln!("LabelUtxos error! {}", e),
+                }
+            },
+            FreezeUtxos(args) => {
+                let commitments = match parse_commitments(&args.commitments) {
+                    Ok(commitments) => commitments,
+                    Err(e) => {
+                        eprintln!("FreezeUtxos error! Invalid commitment: {}", e);
+                        continue;
+                    },
+                };
+                match output_service.set_outputs_frozen(commitments, true).await {
+                    Ok(num_updated) => println!("Froze {} output(s)", num_updated),
+                    Err(e) => eprintln!("FreezeUtxos error! {}", e),
+                }
+            },
+            UnfreezeUtxos(args) => {
+                let commitments = match parse_commitments(&args.commitments) {
+                    Ok(commitments) => commitments,
+                    Err(e) => {
+                        eprintln!("UnfreezeUtxos error! Invalid commitment: {}", e);
+                        continue;
+                    },
+                };
+                match output_service.set_outputs_frozen(commitments, false).await {
+                    Ok(num_updated) => println!("Unfroze {} output(s)", num_updated),
+                    Err(e) => eprintln!("UnfreezeUtxos error! {}", e),
+                }
+            },
+            Consolidate(args) => {
+                let account = match args.account.as_deref() {
+                    Some(name) => match output_service.get_account_by_name(name).await {
+                        Ok(account) => Some(account.id),
+                        Err(e) => {
+                            eprintln!("Consolidate error! {}", e);
+                            continue;
+                        },
+                    },
+                    None => None,
+                };
+                let params = UtxoConsolidationParams {
+                    threshold: args.threshold,
+                    max_inputs: args.max_inputs,
+                    fee_per_gram: args.fee_per_gram,
+                    account,
+                    avoid_mixing_labels: args.mix_labels.then_some(false),
+                };
+                if args.dry_run {
+                    match output_service.preview_utxo_consolidation(params).await {
+                        Ok(preview) => {
+                            println!(
+                                "Joining {} output(s) worth {} of account {} into one output of {}",
+                                preview.num_inputs, preview.input_value, preview.account, preview.output_value
+                            );
+                            println!("Fee: {}", preview.fee);
+                            println!("Unspent outputs afterwards: {}", preview.resulting_output_count);
+                        },
+                        Err(e) => eprintln!("Consolidate error! {}", e),
+                    }
+                    continue;
+                }
+                match output_service.consolidate_utxos(params).await {
+                    Ok((consolidation, transaction)) => {
+                        match transaction_service
+                            .submit_transaction(
+                                consolidation.tx_id,
+                                transaction,
+                                consolidation.input_value,
+                                args.message,
+                            )
+                            .await
+                        {
+                            Ok(_) => {
+                                tx_ids.push(consolidation.tx_id);
+                                println!(
+                                    "Consolidated {} output(s) for a fee of {}, tx_id: {}",
+                                    consolidation.num_inputs, consolidation.fee, consolidation.tx_id
+                                );
+                            },
+                            Err(e) => eprintln!("Consolidate error! {}", e),
+                        }
+                    },
+                    Err(e) => eprintln!("Consolidate error! {}", e),
+                }
+            },
+            ListConsolidations => match output_service.get_utxo_consolidations().await {
+                Ok(consolidations) => {
+                    for c in consolidations {
+                        println!(
+                            "{}: tx_id {}, {} output(s) worth {}, fee {} ({} per gram), {}",
+                            c.created_at, c.tx_id, c.num_inputs, c.input_value, c.fee, c.fee_per_gram, c.reason
+                        );
+                    }
+                },
+                Err(e) => eprintln!("ListConsolidations error! {}", e),
+            },
+            ExportTxHistory(args) => {
+                let filter = TransactionHistoryFilter {
+                    from: args.from.map(|from| from.naive_utc()),
+                    to: args.to.map(|to| to.naive_utc()),
+                    statuses: args.status,
+                    ..Default::default()
+                };
+                match transaction_service.get_transaction_history(filter).await {
+                    Ok(records) => {
+                        let count = records.len();
+                        let rows = records.into_iter().map(TxHistoryRow::from).collect::<Vec<_>>();
+                        let result = match args.format {
+                            TxHistoryFormat::Csv => write_tx_history_to_csv_file(&rows, &args.output_file),
+                            TxHistoryFormat::Json => write_json_file(&args.output_file, &rows),
+                        };
+                        match result {
+                            Ok(()) => println!("Exported {} transaction(s) to {}", count, args.output_file.display()),
+                            Err(e) => eprintln!("ExportTxHistory error! {}", e),
+                        }
+                    },
+                    Err(e) => eprintln!("ExportTxHistory error! {}", e),
+                }
+            },
+            SchedulePayment(args) => {
+                let schedule = match (args.at, args.cron) {
+                    (Some(at), _) => PaymentSchedule::Once(at.naive_utc()),
+                    (None, Some(cron)) => match CronSchedule::from_str(&cron) {
+                        Ok(schedule) => PaymentSchedule::Recurring(schedule),
+                        Err(e) => {
+                            eprintln!("SchedulePayment error! {}", e);
+                            continue;
+                        },
+                    },
+                    (None, None) => {
+                        eprintln!("SchedulePayment error! Either --at or --cron is required");
+                        continue;
+                    },
+                };
+                let kind = if args.one_sided {
+                    PaymentKind::OneSided
+                } else {
+                    PaymentKind::Interactive
+                };
+                let fee_per_gram = args
+                    .fee_per_gram
+                    .unwrap_or_else(|| MicroMinotari::from(config.fee_per_gram));
+                let account = match args.account.as_deref() {
+                    Some(name) => match output_service.get_account_by_name(name).await {
+                        Ok(account) => Some(account.id),
+                        Err(e) => {
+                            eprintln!("SchedulePayment error! {}", e);
+                            continue;
+                        },
+                    },
+                    None => None,
+                };
+                match transaction_service
+                    .create_scheduled_payment(
+                        args.destination,
+                        args.amount,
+                        fee_per_gram,
+                        args.message,
+                        kind,
+                        schedule,
+                        account,
+                    )
+                    .await
+                {
+                    Ok(payment) => println!(
+                        "Scheduled payment {} of {} ({}), next due at {} UTC",
+                        payment.id,
+                        payment.amount,
+                        payment.schedule,
+                        payment
+                            .next_run_at
+                            .map_or_else(|| "never".to_string(), |at| at.to_string())
+                    ),
+                    Err(e) => eprintln!("SchedulePayment error! {}", e),
+                }
+            },
+            ListScheduledPayments => match transaction_service.get_scheduled_payments().await {
+                Ok(payments) => {
+                    for payment in payments {
+                        let status = if payment.cancelled {
+                            "cancelled".to_string()
+                        } else {
+                            payment
+                                .next_run_at
+                                .map_or_else(|| "finished".to_string(), |at| format!("next due at {} UTC", at))
+                        };
+                        println!(
+                            "{}: {} {} to {} ({}), {}",
+                            payment.id, payment.kind, payment.amount, payment.destination, payment.schedule, status
+                        );
+                        match transaction_service.get_scheduled_payment_runs(payment.id).await {
+                            Ok(runs) => {
+                                for run in runs {
+                                    match (run.tx_id, run.error) {
+                                        (_, Some(error)) => println!("    {} UTC: failed: {}", run.run_at, error),
+                                        (Some(tx_id), None) => println!("    {} UTC: sent as {}", run.run_at, tx_id),
+                                        (None, None) => println!("    {} UTC", run.run_at),
+                                    }
+                                }
+                            },
+                            Err(e) => eprintln!("ListScheduledPayments error! {}", e),
+                        }
+                    }
+                },
+                Err(e) => eprintln!("ListScheduledPayments error! {}", e),
+            },
+            CancelScheduledPayment(args) => match transaction_service.cancel_scheduled_payment(args.id).await {
+                Ok(_) => println!("Cancelled scheduled payment {}", args.id),
+                Err(e) => eprintln!("CancelScheduledPayment error! {}", e),
+            },
         }
     }
 
@@ -2673,7 +3230,124 @@ fn load_tx_from_csv_file(file_path: PathBuf) -> Result<Vec<WalletTransaction>, C
     Ok(results)
 }
 
-#[allow(dead_code)]
+/// Reads batch payment recipients from `address,amount[,payment_id]` lines. The optional payment id is free text.
+fn load_batch_recipients_from_csv_file(file_path: PathBuf) -> Result<Vec<BatchPaymentRecipient>, CommandError> {
+    let file_contents = fs::read_to_string(file_path).map_err(|e| CommandError::CSVFile(e.to_string()))?;
+    let mut recipients = Vec::new();
+    for (i, line) in file_contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut fields = line.splitn(3, ',').map(str::trim);
+        let (Some(address), Some(amount)) = (fields.next(), fields.next()) else {
+            return Err(CommandError::CSVFile(format!(
+                "Line {}: expected `address,amount[,payment_id]`",
+                i + 1
+            )));
+        };
+        let address = TariAddress::from_str(address)
+            .map_err(|e| CommandError::CSVFile(format!("Line {}: invalid address: {}", i + 1, e)))?;
+        let amount = MicroMinotari::from_str(amount)
+            .map_err(|e| CommandError::CSVFile(format!("Line {}: invalid amount: {}", i + 1, e)))?;
+        let payment_id = match fields.next() {
+            Some(payment_id) if !payment_id.is_empty() => PaymentId::Open(payment_id.as_bytes().to_vec()),
+            _ => PaymentId::Empty,
+        };
+        recipients.push(BatchPaymentRecipient {
+            address,
+            amount,
+            payment_id,
+        });
+    }
+    if recipients.is_empty() {
+        return Err(CommandError::CSVFile("No recipients found".to_string()));
+    }
+    Ok(recipients)
+}
+
+/// A transaction history record as exported. Amounts are in MicroMinotari and times in UTC so that the export can be
+/// valued in any currency by other tools.
+#[derive(Debug, Serialize)]
+struct TxHistoryRow {
+    tx_id: u64,
+    timestamp: String,
+    status: String,
+    transaction_status: String,
+    cancellation_reason: Option<String>,
+    direction: String,
+    amount: u64,
+    fee: u64,
+    counterparty_address: String,
+    payment_id: Option<String>,
+    mined_height: Option<u64>,
+    mined_timestamp: Option<String>,
+    confirmations: Option<u64>,
+    kernel_excess: Option<String>,
+    message: String,
+}
+
+impl From<TransactionHistoryRecord> for TxHistoryRow {
+    fn from(record: TransactionHistoryRecord) -> Self {
+        let format_utc = |t: NaiveDateTime| t.format("%Y-%m-%dT%H:%M:%SZ").to_string();
+        Self {
+            tx_id: record.tx_id.as_u64(),
+            timestamp: format_utc(record.timestamp),
+            status: record.history_status.to_string(),
+            transaction_status: record.status.to_string(),
+            cancellation_reason: record.cancellation_reason.map(|r| r.to_string()),
+            direction: record.direction.to_string(),
+            amount: record.amount.as_u64(),
+            fee: record.fee.as_u64(),
+            counterparty_address: record.counterparty_address.to_base58(),
+            payment_id: record.payment_id.map(|p| p.to_string()),
+            mined_height: record.mined_height,
+            mined_timestamp: record.mined_timestamp.map(format_utc),
+            confirmations: record.confirmations,
+            kernel_excess: record.kernel_excess.map(|k| k.to_hex()),
+            message: record.message,
+        }
+    }
+}
+
+fn write_tx_history_to_csv_file(rows: &[TxHistoryRow], file_path: &Path) -> Result<(), CommandError> {
+    fn quote<T: ToString>(value: Option<T>) -> String {
+        format!(
+            "\"{}\"",
+            value.map(|v| v.to_string()).unwrap_or_default().replace('"', "\"\"")
+        )
+    }
+
+    let file = File::create(file_path).map_err(|e| CommandError::CSVFile(e.to_string()))?;
+    let mut csv_file = LineWriter::new(file);
+    writeln!(
+        csv_file,
+        r##""tx_id","timestamp","status","transaction_status","cancellation_reason","direction","amount","fee","counterparty_address","payment_id","mined_height","mined_timestamp","confirmations","kernel_excess","message""##
+    )
+    .map_err(|e| CommandError::CSVFile(e.to_string()))?;
+    for row in rows {
+        let fields = [
+            quote(Some(row.tx_id)),
+            quote(Some(&row.timestamp)),
+            quote(Some(&row.status)),
+            quote(Some(&row.transaction_status)),
+            quote(row.cancellation_reason.as_ref()),
+            quote(Some(&row.direction)),
+            quote(Some(row.amount)),
+            quote(Some(row.fee)),
+            quote(Some(&row.counterparty_address)),
+            quote(row.payment_id.as_ref()),
+            quote(row.mined_height),
+            quote(row.mined_timestamp.as_ref()),
+            quote(row.confirmations),
+            quote(row.kernel_excess.as_ref()),
+            quote(Some(&row.message)),
+        ];
+        writeln!(csv_file, "{}", fields.join(",")).map_err(|e| CommandError::CSVFile(e.to_string()))?;
+    }
+    Ok(())
+}
+
 fn write_json_file<P: AsRef<Path>, T: Serialize>(path: P, data: &T) -> Result<(), CommandError> {
     fs::create_dir_all(path.as_ref().parent().unwrap()).map_err(|e| CommandError::JsonFile(e.to_string()))?;
     let file = File::create(path).map_err(|e| CommandError::JsonFile(e.to_string()))?;
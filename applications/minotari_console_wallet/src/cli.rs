@@ -30,6 +30,7 @@ use std::{
 use chrono::{DateTime, Utc};
 use clap::{Args, Parser, Subcommand};
 use minotari_app_utilities::{common_cli_args::CommonCliArgs, utilities::UniPublicKey};
+use minotari_wallet::transaction_service::storage::models::TransactionHistoryStatus;
 use tari_common::configuration::{ConfigOverrideProvider, Network};
 use tari_common_types::tari_address::TariAddress;
 use tari_comms::multiaddr::Multiaddr;
@@ -89,9 +90,10 @@ pub struct Cli {
     pub command2: Option<CliCommands>,
     #[clap(long, alias = "profile")]
     pub profile_with_tokio_console: bool,
-    // For read only wallets
+    /// The private view key of a new watch-only wallet, which tracks funds but cannot spend them
     #[clap(long)]
     pub view_private_key: Option<String>,
+    /// The public spend key of a new watch-only wallet
     #[clap(long)]
     pub spend_key: Option<String>,
 }
@@ -150,6 +152,10 @@ pub enum CliCommands {
     ExportUtxos(ExportUtxosArgs),
     ExportTx(ExportTxArgs),
     ImportTx(ImportTxArgs),
+    CreateUnsignedTx(CreateUnsignedTxArgs),
+    ExportUnsignedTx(ExportUnsignedTxArgs),
+    SignTx(SignTxArgs),
+    SubmitSignedTx(SubmitSignedTxArgs),
     ExportSpentUtxos(ExportUtxosArgs),
     CountUtxos,
     SetBaseNode(SetBaseNodeArgs),
@@ -164,6 +170,20 @@ pub enum CliCommands {
     Sync(SyncArgs),
     ExportViewKeyAndSpendKey(ExportViewKeyAndSpendKeyArgs),
     ImportPaperWallet(ImportPaperWalletArgs),
+    CreateAccount(CreateAccountArgs),
+    ListAccounts,
+    MoveToAccount(MoveToAccountArgs),
+    SchedulePayment(SchedulePaymentArgs),
+    ListScheduledPayments,
+    CancelScheduledPayment(CancelScheduledPaymentArgs),
+    SendBatch(SendBatchArgs),
+    ListUtxos(ListUtxosArgs),
+    LabelUtxos(LabelUtxosArgs),
+    FreezeUtxos(UtxoCommitmentsArgs),
+    UnfreezeUtxos(UtxoCommitmentsArgs),
+    Consolidate(ConsolidateArgs),
+    ListConsolidations,
+    ExportTxHistory(ExportTxHistoryArgs),
 }
 
 #[derive(Debug, Args, Clone)]
@@ -177,6 +197,19 @@ pub struct SendMinotariArgs {
     pub destination: TariAddress,
     #[clap(short, long, default_value = "<No message>")]
     pub message: String,
+    /// The fee per gram to pay. If omitted, the connected base node is asked for a fee that should see the transaction
+    /// mined within a few blocks.
+    #[clap(long)]
+    pub fee_per_gram: Option<MicroMinotari>,
+    /// The name of the account to spend from. Outputs from all accounts are spent if omitted.
+    #[clap(long)]
+    pub account: Option<String>,
+    /// Only spend outputs with this label. May be given more than once.
+    #[clap(long = "label")]
+    pub labels: Vec<String>,
+    /// Never spend outputs with different labels in the same transaction
+    #[clap(long)]
+    pub avoid_mixing_labels: bool,
 }
 
 #[derive(Debug, Args, Clone)]
@@ -405,6 +438,94 @@ pub struct ImportTxArgs {
     pub input_file: PathBuf,
 }
 
+#[derive(Debug, Args, Clone)]
+pub struct CreateUnsignedTxArgs {
+    pub amount: MicroMinotari,
+    pub destination: TariAddress,
+    #[clap(short, long, default_value = "<No message>")]
+    pub message: String,
+    /// The fee per gram to pay. If omitted, the connected base node is asked for a fee that should see the transaction
+    /// mined within a few blocks.
+    #[clap(long)]
+    pub fee_per_gram: Option<MicroMinotari>,
+    /// The name of the account to spend from. Outputs from all accounts are spent if omitted.
+    #[clap(long)]
+    pub account: Option<String>,
+    /// Create an interactive payment. The transaction can only be exported once the recipient has replied.
+    #[clap(long)]
+    pub interactive: bool,
+    /// The file the unsigned transaction is written to. Ignored for interactive payments.
+    #[clap(short, long)]
+    pub output_file: Option<PathBuf>,
+}
+
+#[derive(Debug, Args, Clone)]
+pub struct ExportUnsignedTxArgs {
+    pub tx_id: u64,
+    #[clap(short, long)]
+    pub output_file: PathBuf,
+}
+
+#[derive(Debug, Args, Clone)]
+pub struct SignTxArgs {
+    #[clap(short, long)]
+    pub input_file: PathBuf,
+    #[clap(short, long)]
+    pub output_file: PathBuf,
+}
+
+#[derive(Debug, Args, Clone)]
+pub struct SubmitSignedTxArgs {
+    #[clap(short, long)]
+    pub input_file: PathBuf,
+}
+
+#[derive(Debug, Args, Clone)]
+pub struct SchedulePaymentArgs {
+    pub amount: MicroMinotari,
+    pub destination: TariAddress,
+    #[clap(short, long, default_value = "<No message>")]
+    pub message: String,
+    /// The fee per gram to pay. If omitted, the configured fee per gram is used.
+    #[clap(long)]
+    pub fee_per_gram: Option<MicroMinotari>,
+    /// Send one-sided payments rather than interactive ones
+    #[clap(long)]
+    pub one_sided: bool,
+    /// Send the payment once at this time, e.g. `2024-11-01T09:00:00Z`
+    #[clap(long, required_unless_present = "cron", conflicts_with = "cron")]
+    pub at: Option<DateTime<Utc>>,
+    /// Send the payment every time this cron expression matches, e.g. `0 9 1 * *` for 09:00 UTC on the first of
+    /// every month. The fields are minute, hour, day-of-month, month and day-of-week.
+    #[clap(long)]
+    pub cron: Option<String>,
+    /// The name of the account to spend from. Outputs from all accounts are spent if omitted.
+    #[clap(long)]
+    pub account: Option<String>,
+}
+
+#[derive(Debug, Args, Clone)]
+pub struct CancelScheduledPaymentArgs {
+    pub id: u64,
+}
+
+#[derive(Debug, Args, Clone)]
+pub struct SendBatchArgs {
+    /// A CSV file with one `address,amount[,payment_id]` line per recipient. Blank lines and lines starting with `#`
+    /// are ignored.
+    #[clap(short, long)]
+    pub input_file: PathBuf,
+    #[clap(short, long, default_value = "<No message>")]
+    pub message: String,
+    /// The fee per gram to pay. If omitted, the connected base node is asked for a fee that should see the transaction
+    /// mined within a few blocks.
+    #[clap(long)]
+    pub fee_per_gram: Option<MicroMinotari>,
+    /// The name of the account to spend from. Outputs from all accounts are spent if omitted.
+    #[clap(long)]
+    pub account: Option<String>,
+}
+
 #[derive(Debug, Args, Clone)]
 pub struct SetBaseNodeArgs {
     pub public_key: UniPublicKey,
@@ -455,6 +576,109 @@ pub struct RegisterValidatorNodeArgs {
     pub message: String,
 }
 
+#[derive(Debug, Args, Clone)]
+pub struct CreateAccountArgs {
+    pub name: String,
+}
+
+#[derive(Debug, Args, Clone)]
+pub struct MoveToAccountArgs {
+    /// The name of the account to move the outputs to
+    pub account: String,
+    /// The commitments (hex) of the unspent outputs to move
+    #[clap(long, required = true)]
+    pub commitments: Vec<String>,
+}
+
+#[derive(Debug, Args, Clone)]
+pub struct ListUtxosArgs {
+    /// Only list outputs with this label
+    #[clap(long)]
+    pub label: Option<String>,
+    /// Only list frozen outputs
+    #[clap(long)]
+    pub frozen: bool,
+}
+
+#[derive(Debug, Args, Clone)]
+pub struct LabelUtxosArgs {
+    #[clap(required_unless_present = "clear")]
+    pub label: Option<String>,
+    /// Remove the label from the outputs
+    #[clap(long, conflicts_with = "label")]
+    pub clear: bool,
+    /// The commitments (hex) of the outputs to label
+    #[clap(long, required = true)]
+    pub commitments: Vec<String>,
+}
+
+#[derive(Debug, Args, Clone)]
+pub struct UtxoCommitmentsArgs {
+    /// The commitments (hex) of the outputs
+    #[clap(long, required = true)]
+    pub commitments: Vec<String>,
+}
+
+#[derive(Debug, Args, Clone)]
+pub struct ConsolidateArgs {
+    /// Only join outputs below this value, defaults to the configured consolidation threshold
+    #[clap(long)]
+    pub threshold: Option<MicroMinotari>,
+    /// The maximum number of outputs to join, defaults to the configured maximum
+    #[clap(long)]
+    pub max_inputs: Option<usize>,
+    #[clap(short, long, default_value = "1")]
+    pub fee_per_gram: MicroMinotari,
+    /// Join the outputs of this account, defaults to the account with the most outputs below the threshold
+    #[clap(long)]
+    pub account: Option<String>,
+    /// Join outputs with different labels, which links their sources. By default this follows the configured policy.
+    #[clap(long)]
+    pub mix_labels: bool,
+    /// Show the fee and resulting number of outputs without creating the transaction
+    #[clap(long)]
+    pub dry_run: bool,
+    #[clap(short, long, default_value = "UTXO consolidation")]
+    pub message: String,
+}
+
+#[derive(Debug, Args, Clone)]
+pub struct ExportTxHistoryArgs {
+    #[clap(short, long)]
+    pub output_file: PathBuf,
+    /// `csv` or `json`
+    #[clap(long, default_value = "csv")]
+    pub format: TxHistoryFormat,
+    /// Only export transactions created at or after this time, e.g. `2024-01-01T00:00:00Z`
+    #[clap(long)]
+    pub from: Option<DateTime<Utc>>,
+    /// Only export transactions created before this time
+    #[clap(long)]
+    pub to: Option<DateTime<Utc>>,
+    /// Only export `pending`, `completed` or `cancelled` transactions. May be given more than once, all transactions
+    /// are exported if omitted.
+    #[clap(long)]
+    pub status: Vec<TransactionHistoryStatus>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TxHistoryFormat {
+    Csv,
+    Json,
+}
+
+impl FromStr for TxHistoryFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "csv" => Ok(TxHistoryFormat::Csv),
+            "json" => Ok(TxHistoryFormat::Json),
+            _ => Err(format!("Invalid format '{}', expected 'csv' or 'json'", s)),
+        }
+    }
+}
+
 #[derive(Debug, Args, Clone)]
 pub struct SyncArgs {
     #[clap(short, long, default_value = "0")]
@@ -21,10 +21,12 @@
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 use std::{
+    collections::HashSet,
     convert::{TryFrom, TryInto},
     str::FromStr,
 };
 
+use chrono::NaiveDateTime;
 use futures::{
     channel::mpsc::{self, Sender},
     future,
@@ -35,6 +37,8 @@ use minotari_app_grpc::tari_rpc::{
     self,
     payment_recipient::PaymentType,
     wallet_server,
+    CancelScheduledPaymentRequest,
+    CancelScheduledPaymentResponse,
     CheckConnectivityResponse,
     ClaimHtlcRefundRequest,
     ClaimHtlcRefundResponse,
@@ -43,10 +47,21 @@ use minotari_app_grpc::tari_rpc::{
     CoinSplitRequest,
     CoinSplitResponse,
     CommitmentSignature,
+    CreateAccountRequest,
+    CreateAccountResponse,
     CreateBurnTransactionRequest,
     CreateBurnTransactionResponse,
+    CreatePaymentRequestRequest,
+    CreatePaymentRequestResponse,
+    CreateScheduledPaymentRequest,
+    CreateScheduledPaymentResponse,
     CreateTemplateRegistrationRequest,
     CreateTemplateRegistrationResponse,
+    ExportTransactionHistoryRequest,
+    ExportTransactionHistoryResponse,
+    FreezeUtxosRequest,
+    FreezeUtxosResponse,
+    GetAccountsResponse,
     GetAddressResponse,
     GetBalanceRequest,
     GetBalanceResponse,
@@ -55,25 +70,39 @@ use minotari_app_grpc::tari_rpc::{
     GetConnectivityRequest,
     GetIdentityRequest,
     GetIdentityResponse,
+    GetPaymentRequestsResponse,
+    GetScheduledPaymentsResponse,
     GetTransactionInfoRequest,
     GetTransactionInfoResponse,
     GetUnspentAmountsResponse,
+    GetUtxosRequest,
+    GetUtxosResponse,
     GetVersionRequest,
     GetVersionResponse,
     ImportUtxosRequest,
     ImportUtxosResponse,
+    MoveOutputsToAccountRequest,
+    MoveOutputsToAccountResponse,
+    ParsePaymentRequestRequest,
+    ParsePaymentRequestResponse,
+    PaymentRequestStatus,
     RegisterValidatorNodeRequest,
     RegisterValidatorNodeResponse,
     RevalidateRequest,
     RevalidateResponse,
+    SendBatchRequest,
+    SendBatchResponse,
     SendShaAtomicSwapRequest,
     SendShaAtomicSwapResponse,
     SetBaseNodeRequest,
     SetBaseNodeResponse,
+    SetUtxoLabelRequest,
+    SetUtxoLabelResponse,
     TransactionDirection,
     TransactionEvent,
     TransactionEventRequest,
     TransactionEventResponse,
+    TransactionHistoryStatus,
     TransactionInfo,
     TransactionStatus,
     TransferRequest,
@@ -85,17 +114,30 @@ use minotari_app_grpc::tari_rpc::{
 use minotari_wallet::{
     connectivity_service::{OnlineStatus, WalletConnectivityInterface},
     error::WalletStorageError,
-    output_manager_service::{handle::OutputManagerHandle, UtxoSelectionCriteria},
+    output_manager_service::{handle::OutputManagerHandle, storage::models::WalletAccount, UtxoSelectionCriteria},
     transaction_service::{
         handle::TransactionServiceHandle,
-        storage::models::{self, WalletTransaction},
+        payment_request::PaymentRequest,
+        storage::models::{
+            self,
+            BatchPaymentRecipient,
+            IssuedPaymentRequest,
+            PaymentSchedule,
+            ScheduledPayment,
+            ScheduledPaymentRun,
+            TransactionHistoryFilter,
+            TransactionHistoryRecord,
+            WalletTransaction,
+        },
     },
+    util::cron::CronSchedule,
     WalletSqlite,
 };
 use tari_common_types::{
     tari_address::TariAddress,
     transaction::TxId,
-    types::{BlockHash, PublicKey, Signature},
+    types::{BlockHash, Commitment, PublicKey, Signature},
+    wallet_types::AccountId,
 };
 use tari_comms::{multiaddr::Multiaddr, types::CommsPublicKey, CommsNode};
 use tari_core::{
@@ -110,6 +152,7 @@ use tari_core::{
             SideChainFeature,
             UnblindedOutput,
         },
+        transaction_protocol::partially_signed::PaymentKind,
     },
 };
 use tari_script::script;
@@ -123,6 +166,8 @@ use crate::{
 };
 
 const LOG_TARGET: &str = "wallet::ui::grpc";
+/// The number of transactions read from the database at a time when streaming the transaction history
+const TRANSACTION_HISTORY_PAGE_SIZE: usize = 100;
 
 async fn send_transaction_event(
     transaction_event: TransactionEvent,
@@ -159,6 +204,35 @@ impl WalletGrpcServer {
         self.wallet.output_manager_service.clone()
     }
 
+    /// Resolve the name of an account to its id. An empty name refers to all accounts.
+    async fn get_account_id(&self, name: &str) -> Result<Option<AccountId>, Status> {
+        if name.is_empty() {
+            return Ok(None);
+        }
+        let account = self
+            .get_output_manager_service()
+            .get_account_by_name(name)
+            .await
+            .map_err(|e| Status::not_found(e.to_string()))?;
+        Ok(Some(account.id))
+    }
+
+    async fn set_utxos_frozen(
+        &self,
+        request: FreezeUtxosRequest,
+        frozen: bool,
+    ) -> Result<Response<FreezeUtxosResponse>, Status> {
+        let commitments = parse_commitments(&request.commitments)?;
+        let num_updated = self
+            .get_output_manager_service()
+            .set_outputs_frozen(commitments, frozen)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+        Ok(Response::new(FreezeUtxosResponse {
+            num_updated: num_updated as u64,
+        }))
+    }
+
     fn comms(&self) -> &CommsNode {
         &self.wallet.comms
     }
@@ -178,6 +252,7 @@ impl WalletGrpcServer {
 
 #[tonic::async_trait]
 impl wallet_server::Wallet for WalletGrpcServer {
+    type ExportTransactionHistoryStream = mpsc::Receiver<Result<ExportTransactionHistoryResponse, Status>>;
     type GetCompletedTransactionsStream = mpsc::Receiver<Result<GetCompletedTransactionsResponse, Status>>;
     type StreamTransactionEventsStream = mpsc::Receiver<Result<TransactionEventResponse, Status>>;
 
@@ -270,9 +345,14 @@ impl wallet_server::Wallet for WalletGrpcServer {
         Ok(Response::new(SetBaseNodeResponse {}))
     }
 
-    async fn get_balance(&self, _request: Request<GetBalanceRequest>) -> Result<Response<GetBalanceResponse>, Status> {
+    async fn get_balance(&self, request: Request<GetBalanceRequest>) -> Result<Response<GetBalanceResponse>, Status> {
+        let account = self.get_account_id(&request.into_inner().account).await?;
         let mut output_service = self.get_output_manager_service();
-        let balance = match output_service.get_balance().await {
+        let balance = match account {
+            Some(account) => output_service.get_account_balance(account).await,
+            None => output_service.get_balance().await,
+        };
+        let balance = match balance {
             Ok(b) => b,
             Err(e) => return Err(Status::not_found(format!("GetBalance error! {}", e))),
         };
@@ -500,6 +580,10 @@ impl wallet_server::Wallet for WalletGrpcServer {
 
     async fn transfer(&self, request: Request<TransferRequest>) -> Result<Response<TransferResponse>, Status> {
         let message = request.into_inner();
+        let mut selection_criteria = UtxoSelectionCriteria::default()
+            .for_account(self.get_account_id(&message.account).await?)
+            .for_labels(message.labels);
+        selection_criteria.avoid_mixing_labels = message.avoid_mixing_labels;
         let recipients = message
             .recipients
             .into_iter()
@@ -526,6 +610,7 @@ impl wallet_server::Wallet for WalletGrpcServer {
                 .map_err(|_| "Invalid payment id".to_string())
                 .map_err(Status::invalid_argument)?;
             let mut transaction_service = self.get_transaction_service();
+            let selection_criteria = selection_criteria.clone();
             transfers.push(async move {
                 (
                     hex_address,
@@ -534,7 +619,7 @@ impl wallet_server::Wallet for WalletGrpcServer {
                             .send_transaction(
                                 address,
                                 amount.into(),
-                                UtxoSelectionCriteria::default(),
+                                selection_criteria,
                                 OutputFeatures::default(),
                                 fee_per_gram.into(),
                                 message,
@@ -545,7 +630,7 @@ impl wallet_server::Wallet for WalletGrpcServer {
                             .send_one_sided_transaction(
                                 address,
                                 amount.into(),
-                                UtxoSelectionCriteria::default(),
+                                selection_criteria,
                                 OutputFeatures::default(),
                                 fee_per_gram.into(),
                                 message,
@@ -557,7 +642,7 @@ impl wallet_server::Wallet for WalletGrpcServer {
                             .send_one_sided_to_stealth_address_transaction(
                                 address,
                                 amount.into(),
-                                UtxoSelectionCriteria::default(),
+                                selection_criteria,
                                 OutputFeatures::default(),
                                 fee_per_gram.into(),
                                 message,
@@ -752,17 +837,28 @@ impl wallet_server::Wallet for WalletGrpcServer {
 
     async fn get_completed_transactions(
         &self,
-        _request: Request<GetCompletedTransactionsRequest>,
+        request: Request<GetCompletedTransactionsRequest>,
     ) -> Result<Response<Self::GetCompletedTransactionsStream>, Status> {
         debug!(
             target: LOG_TARGET,
             "GetAllCompletedTransactions: Incoming GRPC request"
         );
+        let account = self.get_account_id(&request.into_inner().account).await?;
         let mut transaction_service = self.get_transaction_service();
-        let transactions = transaction_service
+        let mut transactions = transaction_service
             .get_completed_transactions()
             .await
             .map_err(|err| Status::not_found(format!("No completed transactions found: {:?}", err)))?;
+        if let Some(account) = account {
+            let account_tx_ids = self
+                .get_output_manager_service()
+                .get_account_tx_ids(account)
+                .await
+                .map_err(|err| Status::internal(err.to_string()))?
+                .into_iter()
+                .collect::<HashSet<_>>();
+            transactions.retain(|tx_id, _| account_tx_ids.contains(tx_id));
+        }
         debug!(
             target: LOG_TARGET,
             "GetAllCompletedTransactions: Found {} completed transactions",
@@ -1059,6 +1155,490 @@ impl wallet_server::Wallet for WalletGrpcServer {
         };
         Ok(Response::new(response))
     }
+
+    async fn create_account(
+        &self,
+        request: Request<CreateAccountRequest>,
+    ) -> Result<Response<CreateAccountResponse>, Status> {
+        let mut output_manager = self.get_output_manager_service();
+        let account = output_manager
+            .create_account(request.into_inner().name)
+            .await
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+        let address = output_manager
+            .get_account_address(account.id)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+        Ok(Response::new(CreateAccountResponse {
+            account: Some(convert_account(account, &address)),
+        }))
+    }
+
+    async fn get_accounts(&self, _: Request<tari_rpc::Empty>) -> Result<Response<GetAccountsResponse>, Status> {
+        let mut output_manager = self.get_output_manager_service();
+        let accounts = output_manager
+            .get_accounts()
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+        let mut converted = Vec::with_capacity(accounts.len());
+        for account in accounts {
+            let address = output_manager
+                .get_account_address(account.id)
+                .await
+                .map_err(|e| Status::internal(e.to_string()))?;
+            converted.push(convert_account(account, &address));
+        }
+        Ok(Response::new(GetAccountsResponse { accounts: converted }))
+    }
+
+    async fn move_outputs_to_account(
+        &self,
+        request: Request<MoveOutputsToAccountRequest>,
+    ) -> Result<Response<MoveOutputsToAccountResponse>, Status> {
+        let request = request.into_inner();
+        let account = self
+            .get_account_id(&request.account)
+            .await?
+            .ok_or_else(|| Status::invalid_argument("An account name is required"))?;
+        let commitments = parse_commitments(&request.commitments)?;
+        let num_moved = self
+            .get_output_manager_service()
+            .move_outputs_to_account(commitments, account)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+        Ok(Response::new(MoveOutputsToAccountResponse {
+            num_moved: num_moved as u64,
+        }))
+    }
+
+    async fn create_scheduled_payment(
+        &self,
+        request: Request<CreateScheduledPaymentRequest>,
+    ) -> Result<Response<CreateScheduledPaymentResponse>, Status> {
+        let request = request.into_inner();
+        let recipient = request
+            .recipient
+            .ok_or_else(|| Status::invalid_argument("Request is malformed"))?;
+        let address = TariAddress::from_str(&recipient.address)
+            .map_err(|_| Status::invalid_argument("Destination address is malformed"))?;
+        let kind = match PaymentType::from_i32(recipient.payment_type) {
+            Some(PaymentType::StandardMimblewimble) => PaymentKind::Interactive,
+            Some(PaymentType::OneSided) => PaymentKind::OneSided,
+            _ => return Err(Status::invalid_argument("Unsupported payment type")),
+        };
+        let schedule = match (request.run_at, request.cron_expression.as_str()) {
+            (run_at, "") if run_at > 0 => PaymentSchedule::Once(
+                NaiveDateTime::from_timestamp_opt(run_at as i64, 0)
+                    .ok_or_else(|| Status::invalid_argument("Run time is out of range"))?,
+            ),
+            (0, expression) if !expression.is_empty() => PaymentSchedule::Recurring(
+                CronSchedule::from_str(expression).map_err(|e| Status::invalid_argument(e.to_string()))?,
+            ),
+            _ => {
+                return Err(Status::invalid_argument(
+                    "Exactly one of a run time or a cron expression is required",
+                ))
+            },
+        };
+
+        let account = self.get_account_id(&request.account).await?;
+
+        let mut transaction_service = self.get_transaction_service();
+        let payment = transaction_service
+            .create_scheduled_payment(
+                address,
+                recipient.amount.into(),
+                recipient.fee_per_gram.into(),
+                recipient.message,
+                kind,
+                schedule,
+                account,
+            )
+            .await
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+        Ok(Response::new(CreateScheduledPaymentResponse {
+            payment: Some(convert_scheduled_payment(payment, Vec::new(), request.account)),
+        }))
+    }
+
+    async fn get_scheduled_payments(
+        &self,
+        _: Request<tari_rpc::Empty>,
+    ) -> Result<Response<GetScheduledPaymentsResponse>, Status> {
+        let mut transaction_service = self.get_transaction_service();
+        let scheduled_payments = transaction_service
+            .get_scheduled_payments()
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+        let accounts = self
+            .get_output_manager_service()
+            .get_accounts()
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+        let mut payments = Vec::with_capacity(scheduled_payments.len());
+        for payment in scheduled_payments {
+            let runs = transaction_service
+                .get_scheduled_payment_runs(payment.id)
+                .await
+                .map_err(|e| Status::internal(e.to_string()))?;
+            let account = payment
+                .account
+                .and_then(|id| accounts.iter().find(|a| a.id == id))
+                .map(|a| a.name.clone())
+                .unwrap_or_default();
+            payments.push(convert_scheduled_payment(payment, runs, account));
+        }
+        Ok(Response::new(GetScheduledPaymentsResponse { payments }))
+    }
+
+    async fn cancel_scheduled_payment(
+        &self,
+        request: Request<CancelScheduledPaymentRequest>,
+    ) -> Result<Response<CancelScheduledPaymentResponse>, Status> {
+        let mut transaction_service = self.get_transaction_service();
+        transaction_service
+            .cancel_scheduled_payment(request.into_inner().id)
+            .await
+            .map_err(|e| Status::not_found(e.to_string()))?;
+        Ok(Response::new(CancelScheduledPaymentResponse {}))
+    }
+
+    async fn send_batch(&self, request: Request<SendBatchRequest>) -> Result<Response<SendBatchResponse>, Status> {
+        let message = request.into_inner();
+        let selection_criteria =
+            UtxoSelectionCriteria::default().for_account(self.get_account_id(&message.account).await?);
+        let recipients = message
+            .recipients
+            .into_iter()
+            .enumerate()
+            .map(|(idx, recipient)| -> Result<_, String> {
+                Ok(BatchPaymentRecipient {
+                    address: TariAddress::from_str(&recipient.address)
+                        .map_err(|_| format!("Destination address at index {} is malformed", idx))?,
+                    amount: recipient.amount.into(),
+                    payment_id: PaymentId::from_bytes(&recipient.payment_id)
+                        .map_err(|_| format!("Payment id at index {} is invalid", idx))?,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(Status::invalid_argument)?;
+
+        let mut transaction_service = self.get_transaction_service();
+        let response = match transaction_service
+            .send_batch_transaction(
+                recipients,
+                selection_criteria,
+                message.fee_per_gram.into(),
+                message.message,
+            )
+            .await
+        {
+            Ok(tx_id) => SendBatchResponse {
+                transaction_id: tx_id.as_u64(),
+                is_success: true,
+                failure_message: Default::default(),
+            },
+            Err(e) => {
+                warn!(target: LOG_TARGET, "Failed to send batch transaction: {}", e);
+                SendBatchResponse {
+                    is_success: false,
+                    failure_message: e.to_string(),
+                    ..Default::default()
+                }
+            },
+        };
+
+        Ok(Response::new(response))
+    }
+
+    async fn get_utxos(&self, request: Request<GetUtxosRequest>) -> Result<Response<GetUtxosResponse>, Status> {
+        let label = request.into_inner().label;
+        let utxos = self
+            .get_output_manager_service()
+            .get_unspent_outputs()
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?
+            .into_iter()
+            .filter(|o| label.is_empty() || o.label.as_ref() == Some(&label))
+            .map(|o| tari_rpc::WalletUtxo {
+                commitment: o.commitment.to_vec(),
+                value: o.wallet_output.value.as_u64(),
+                label: o.label.unwrap_or_default(),
+                frozen: o.frozen,
+                account_id: u64::from(o.account_id),
+                mined_height: o.mined_height.unwrap_or_default(),
+            })
+            .collect();
+        Ok(Response::new(GetUtxosResponse { utxos }))
+    }
+
+    async fn set_utxo_label(
+        &self,
+        request: Request<SetUtxoLabelRequest>,
+    ) -> Result<Response<SetUtxoLabelResponse>, Status> {
+        let request = request.into_inner();
+        let commitments = parse_commitments(&request.commitments)?;
+        let label = Some(request.label).filter(|l| !l.is_empty());
+        let num_updated = self
+            .get_output_manager_service()
+            .set_output_label(commitments, label)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+        Ok(Response::new(SetUtxoLabelResponse {
+            num_updated: num_updated as u64,
+        }))
+    }
+
+    async fn freeze_utxos(
+        &self,
+        request: Request<FreezeUtxosRequest>,
+    ) -> Result<Response<FreezeUtxosResponse>, Status> {
+        self.set_utxos_frozen(request.into_inner(), true).await
+    }
+
+    async fn unfreeze_utxos(
+        &self,
+        request: Request<FreezeUtxosRequest>,
+    ) -> Result<Response<FreezeUtxosResponse>, Status> {
+        self.set_utxos_frozen(request.into_inner(), false).await
+    }
+
+    async fn export_transaction_history(
+        &self,
+        request: Request<ExportTransactionHistoryRequest>,
+    ) -> Result<Response<Self::ExportTransactionHistoryStream>, Status> {
+        let message = request.into_inner();
+        let to_time = |timestamp: u64| -> Result<Option<NaiveDateTime>, Status> {
+            if timestamp == 0 {
+                return Ok(None);
+            }
+            i64::try_from(timestamp)
+                .ok()
+                .and_then(|t| NaiveDateTime::from_timestamp_opt(t, 0))
+                .map(Some)
+                .ok_or_else(|| Status::invalid_argument("Timestamp is out of range"))
+        };
+        let statuses = message
+            .statuses
+            .iter()
+            .map(|status| match TransactionHistoryStatus::from_i32(*status) {
+                Some(TransactionHistoryStatus::Pending) => Ok(models::TransactionHistoryStatus::Pending),
+                Some(TransactionHistoryStatus::Completed) => Ok(models::TransactionHistoryStatus::Completed),
+                Some(TransactionHistoryStatus::Cancelled) => Ok(models::TransactionHistoryStatus::Cancelled),
+                None => Err(Status::invalid_argument("Unknown transaction history status")),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut filter = TransactionHistoryFilter {
+            from: to_time(message.from_timestamp)?,
+            to: to_time(message.to_timestamp)?,
+            statuses,
+            after: None,
+            limit: Some(TRANSACTION_HISTORY_PAGE_SIZE),
+        };
+
+        // The history is read and sent a page at a time, so the next page is only read once the client has taken
+        // most of the previous one
+        let mut transaction_service = self.get_transaction_service();
+        let (mut sender, receiver) = mpsc::channel(TRANSACTION_HISTORY_PAGE_SIZE);
+        task::spawn(async move {
+            let mut num_sent = 0usize;
+            loop {
+                let records = match transaction_service.get_transaction_history(filter.clone()).await {
+                    Ok(records) => records,
+                    Err(e) => {
+                        warn!(target: LOG_TARGET, "Error reading the transaction history: {}", e);
+                        let _ = sender.send(Err(Status::internal(e.to_string()))).await;
+                        return;
+                    },
+                };
+                let is_last_page = records.len() < TRANSACTION_HISTORY_PAGE_SIZE;
+                let last_position = records.last().map(|r| r.position());
+                for record in records {
+                    let response = ExportTransactionHistoryResponse {
+                        record: Some(convert_transaction_history_record(record)),
+                    };
+                    if let Err(err) = sender.send(Ok(response)).await {
+                        warn!(target: LOG_TARGET, "Error sending transaction history via GRPC: {}", err);
+                        return;
+                    }
+                    num_sent += 1;
+                }
+                match last_position {
+                    Some(position) if !is_last_page => {
+                        filter = filter.page_after(position, TRANSACTION_HISTORY_PAGE_SIZE);
+                    },
+                    _ => break,
+                }
+            }
+            debug!(target: LOG_TARGET, "ExportTransactionHistory: Sent {} transactions", num_sent);
+        });
+        Ok(Response::new(receiver))
+    }
+
+    async fn create_payment_request(
+        &self,
+        request: Request<CreatePaymentRequestRequest>,
+    ) -> Result<Response<CreatePaymentRequestResponse>, Status> {
+        let message = request.into_inner();
+        let payment_id = if message.payment_id.is_empty() {
+            None
+        } else {
+            Some(
+                PaymentId::from_bytes(&message.payment_id)
+                    .map_err(|_| Status::invalid_argument("Payment id is invalid"))?,
+            )
+        };
+        let expires_at = match message.expires_at {
+            0 => None,
+            expires_at => Some(
+                i64::try_from(expires_at)
+                    .ok()
+                    .and_then(|t| NaiveDateTime::from_timestamp_opt(t, 0))
+                    .ok_or_else(|| Status::invalid_argument("Expiry time is out of range"))?,
+            ),
+        };
+        let request = self
+            .get_transaction_service()
+            .create_payment_request(message.amount.into(), message.memo, payment_id, expires_at)
+            .await
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+        Ok(Response::new(CreatePaymentRequestResponse {
+            request: Some(convert_issued_payment_request(request)),
+        }))
+    }
+
+    async fn get_payment_requests(
+        &self,
+        _: Request<tari_rpc::Empty>,
+    ) -> Result<Response<GetPaymentRequestsResponse>, Status> {
+        let requests = self
+            .get_transaction_service()
+            .get_payment_requests()
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?
+            .into_iter()
+            .map(convert_issued_payment_request)
+            .collect();
+        Ok(Response::new(GetPaymentRequestsResponse { requests }))
+    }
+
+    async fn parse_payment_request(
+        &self,
+        request: Request<ParsePaymentRequestRequest>,
+    ) -> Result<Response<ParsePaymentRequestResponse>, Status> {
+        let request =
+            PaymentRequest::from_str(&request.into_inner().uri).map_err(|e| Status::invalid_argument(e.to_string()))?;
+        Ok(Response::new(ParsePaymentRequestResponse {
+            request: Some(convert_payment_request(request)),
+        }))
+    }
+}
+
+fn parse_commitments(commitments: &[Vec<u8>]) -> Result<Vec<Commitment>, Status> {
+    commitments
+        .iter()
+        .map(|c| Commitment::from_canonical_bytes(c))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| Status::invalid_argument("Commitment is malformed"))
+}
+
+fn convert_payment_request(request: PaymentRequest) -> tari_rpc::PaymentRequest {
+    tari_rpc::PaymentRequest {
+        uri: request.to_uri(),
+        address: request.address.to_base58(),
+        amount: request.amount.as_u64(),
+        payment_id: request.payment_id.to_bytes(),
+        expires_at: request.expires_at.map(|t| t.timestamp() as u64).unwrap_or_default(),
+        memo: request.memo,
+        ..Default::default()
+    }
+}
+
+fn convert_issued_payment_request(issued: IssuedPaymentRequest) -> tari_rpc::PaymentRequest {
+    tari_rpc::PaymentRequest {
+        id: issued.id,
+        status: match issued.status {
+            models::PaymentRequestStatus::Open => PaymentRequestStatus::Open,
+            models::PaymentRequestStatus::Paid => PaymentRequestStatus::Paid,
+            models::PaymentRequestStatus::Underpaid => PaymentRequestStatus::Underpaid,
+            models::PaymentRequestStatus::Expired => PaymentRequestStatus::Expired,
+        } as i32,
+        amount_received: issued.amount_received.as_u64(),
+        created_at: issued.created_at.timestamp() as u64,
+        ..convert_payment_request(issued.request)
+    }
+}
+
+fn convert_account(account: WalletAccount, address: &TariAddress) -> tari_rpc::WalletAccount {
+    tari_rpc::WalletAccount {
+        id: account.id,
+        name: account.name,
+        created_at: account.created_at.timestamp() as u64,
+        address: address.to_base58(),
+    }
+}
+
+fn convert_scheduled_payment(
+    payment: ScheduledPayment,
+    runs: Vec<ScheduledPaymentRun>,
+    account: String,
+) -> tari_rpc::ScheduledPayment {
+    let (run_at, cron_expression) = match payment.schedule {
+        PaymentSchedule::Once(at) => (at.timestamp() as u64, String::new()),
+        PaymentSchedule::Recurring(schedule) => (0, schedule.to_string()),
+    };
+    tari_rpc::ScheduledPayment {
+        id: payment.id,
+        address: payment.destination.to_base58(),
+        amount: payment.amount.as_u64(),
+        fee_per_gram: payment.fee_per_gram.as_u64(),
+        message: payment.message,
+        payment_type: match payment.kind {
+            PaymentKind::Interactive => PaymentType::StandardMimblewimble,
+            PaymentKind::OneSided => PaymentType::OneSided,
+        } as i32,
+        run_at,
+        cron_expression,
+        next_run_at: payment.next_run_at.map_or(0, |at| at.timestamp() as u64),
+        created_at: payment.created_at.timestamp() as u64,
+        cancelled: payment.cancelled,
+        runs: runs
+            .into_iter()
+            .map(|run| tari_rpc::ScheduledPaymentRun {
+                run_at: run.run_at.timestamp() as u64,
+                transaction_id: run.tx_id.map_or(0, |tx_id| tx_id.as_u64()),
+                error: run.error.unwrap_or_default(),
+            })
+            .collect(),
+        account,
+    }
+}
+
+fn convert_transaction_history_record(record: TransactionHistoryRecord) -> tari_rpc::TransactionHistoryRecord {
+    tari_rpc::TransactionHistoryRecord {
+        tx_id: record.tx_id.as_u64(),
+        timestamp: record.timestamp.timestamp() as u64,
+        history_status: match record.history_status {
+            models::TransactionHistoryStatus::Pending => TransactionHistoryStatus::Pending,
+            models::TransactionHistoryStatus::Completed => TransactionHistoryStatus::Completed,
+            models::TransactionHistoryStatus::Cancelled => TransactionHistoryStatus::Cancelled,
+        } as i32,
+        status: TransactionStatus::from(record.status) as i32,
+        cancellation_reason: record
+            .cancellation_reason
+            .map(|reason| reason.to_string())
+            .unwrap_or_default(),
+        direction: TransactionDirection::from(record.direction) as i32,
+        amount: record.amount.as_u64(),
+        fee: record.fee.as_u64(),
+        counterparty_address: record.counterparty_address.to_vec(),
+        payment_id: record.payment_id.map(|id| id.to_bytes()).unwrap_or_default(),
+        mined_height: record.mined_height.unwrap_or_default(),
+        mined_timestamp: record.mined_timestamp.map_or(0, |at| at.timestamp() as u64),
+        confirmations: record.confirmations.unwrap_or_default(),
+        kernel_excess: record.kernel_excess.map(|k| k.to_vec()).unwrap_or_default(),
+        message: record.message,
+    }
 }
 
 async fn handle_completed_tx(
@@ -35,7 +35,7 @@ use minotari_wallet::{
     output_manager_service::storage::database::OutputManagerDatabase,
     storage::{
         database::{WalletBackend, WalletDatabase},
-        sqlite_utilities::initialize_sqlite_database_backends,
+        sqlite_utilities::{change_wallet_passphrase, initialize_sqlite_database_backends},
     },
     wallet::{derive_comms_secret_key, read_or_create_master_seed, read_or_create_wallet_type},
     Wallet,
@@ -257,35 +257,34 @@ fn prompt_password(prompt: &str) -> Result<SafePassword, ExitError> {
 }
 
 /// Allows the user to change the password of the wallet.
-pub async fn change_password(
-    config: &ApplicationConfig,
-    existing: SafePassword,
-    shutdown_signal: ShutdownSignal,
-    non_interactive_mode: bool,
-) -> Result<(), ExitError> {
-    let mut wallet = init_wallet(
-        &config.wallet,
-        config.auto_update.clone(),
-        config.peer_seeds.clone(),
-        existing.clone(),
-        None,
-        None,
-        shutdown_signal,
-        non_interactive_mode,
-        None,
-    )
-    .await?;
+pub fn change_password(config: &ApplicationConfig, existing: SafePassword) -> Result<(), ExitError> {
+    if !config.wallet.db_file.exists() {
+        return Err(ExitError::new(
+            ExitCode::WalletError,
+            format!("No wallet database found at {}", config.wallet.db_file.display()),
+        ));
+    }
 
     // Get a new passphrase
     let new = get_new_passphrase("New wallet passphrase: ", "Confirm new passphrase: ")?;
 
-    // Use the existing and new passphrases to attempt to change the wallet passphrase
-    wallet.db.change_passphrase(&existing, &new).map_err(|e| match e {
-        WalletStorageError::InvalidPassphrase => {
-            ExitError::new(ExitCode::IncorrectOrEmptyPassword, "Your password was not changed.")
-        },
-        _ => ExitError::new(ExitCode::DatabaseError, "Your password was not changed."),
-    })
+    // Use the existing and new passphrases to re-encrypt the wallet database under a new key
+    let num_updated = change_wallet_passphrase(&config.wallet.db_file, existing, &new).map_err(|e| {
+        error!(target: LOG_TARGET, "Could not change the wallet passphrase: {}", e);
+        match e {
+            WalletStorageError::InvalidPassphrase => {
+                ExitError::new(ExitCode::IncorrectOrEmptyPassword, "Your password was not changed.")
+            },
+            _ => ExitError::new(ExitCode::DatabaseError, "Your password was not changed."),
+        }
+    })?;
+
+    info!(
+        target: LOG_TARGET,
+        "Wallet passphrase changed, {} values were re-encrypted", num_updated
+    );
+    println!("Your password was changed.");
+    Ok(())
 }
 
 /// Populates the PeerConfig struct from:
@@ -774,7 +773,7 @@ fn boot(cli: &Cli, wallet_config: &WalletConfig) -> Result<WalletBoot, ExitError
         loop {
             println!("1. Create a new wallet.");
             println!("2. Recover wallet from seed words or hardware device.");
-            println!("3. Create a read-only wallet using a view key.");
+            println!("3. Create a watch-only wallet using a view key and public spend key.");
             let readline = rl.readline(">> ");
             match readline {
                 Ok(line) => {
@@ -875,12 +874,8 @@ pub fn prompt_wallet_type(
                 prompt_public_key("Enter spend key: ").expect("Spend key provided was invalid")
             };
 
-            Some(WalletType::ProvidedKeys(ProvidedKeysWallet {
-                view_key,
-                public_spend_key: spend_key,
-                private_spend_key: None,
-                private_comms_key: None,
-            }))
+            let wallet = ProvidedKeysWallet::watch_only(spend_key, view_key);
+            Some(WalletType::ProvidedKeys(wallet))
         },
         WalletBoot::New | WalletBoot::Recovery => {
             #[cfg(not(feature = "ledger"))]
@@ -153,12 +153,7 @@ pub fn run_wallet_with_cli(
 
     if cli.change_password {
         info!(target: LOG_TARGET, "Change password requested.");
-        return runtime.block_on(change_password(
-            config,
-            password,
-            shutdown_signal,
-            cli.non_interactive_mode,
-        ));
+        return change_password(config, password);
     }
 
     // Run our own Tor instance, if configured
@@ -1,22 +1,43 @@
 // Copyright 2022 The Tari Project
 // SPDX-License-Identifier: BSD-3-Clause
 
+use minotari_wallet::transaction_service::storage::models::IssuedPaymentRequest;
+use tari_core::transactions::tari_amount::MicroMinotari;
+use tokio::runtime::Handle;
 use tui::{
     backend::Backend,
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Span, Spans},
-    widgets::{Block, Borders, Paragraph},
+    widgets::{Block, Borders, Clear, ListItem, Paragraph, Wrap},
     Frame,
 };
+use unicode_width::UnicodeWidthStr;
 
-use crate::ui::{components::Component, state::AppState};
+use crate::ui::{
+    components::Component,
+    state::AppState,
+    widgets::{centered_rect_absolute, draw_dialog, MultiColumnList, WindowedListState},
+    MAX_WIDTH,
+};
 
-pub struct ReceiveTab {}
+pub struct ReceiveTab {
+    request_input_mode: PaymentRequestInputMode,
+    amount_field: String,
+    memo_field: String,
+    error_message: Option<String>,
+    payment_requests_list_state: WindowedListState,
+}
 
 impl ReceiveTab {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            request_input_mode: PaymentRequestInputMode::None,
+            amount_field: String::new(),
+            memo_field: String::new(),
+            error_message: None,
+            payment_requests_list_state: WindowedListState::new(),
+        }
     }
 
     fn draw_whoami<B>(&self, f: &mut Frame<B>, area: Rect, app_state: &AppState)
@@ -135,24 +156,254 @@ impl ReceiveTab {
         let paragraph = Paragraph::new(emoji_id_text).block(Block::default());
         f.render_widget(paragraph, details_chunks[5]);
     }
+
+    fn draw_payment_requests<B>(&mut self, f: &mut Frame<B>, area: Rect, app_state: &AppState)
+    where B: Backend {
+        let block = Block::default().borders(Borders::ALL).title(Span::styled(
+            "Payment Requests",
+            Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
+        ));
+        f.render_widget(block, area);
+        let list_areas = Layout::default()
+            .constraints([Constraint::Length(1), Constraint::Min(4), Constraint::Length(4)].as_ref())
+            .margin(1)
+            .split(area);
+
+        let instructions = Paragraph::new(Spans::from(vec![
+            Span::raw("Press "),
+            Span::styled("R", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" to create a payment (r)equest, use "),
+            Span::styled("Up↑/Down↓ Keys", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" to select a request and show the URI to give to the payer."),
+        ]))
+        .wrap(Wrap { trim: true });
+        f.render_widget(instructions, list_areas[0]);
+
+        let payment_requests = app_state.get_payment_requests();
+        self.payment_requests_list_state.set_num_items(payment_requests.len());
+        let mut list_state = self
+            .payment_requests_list_state
+            .update_list_state((list_areas[1].height as usize).saturating_sub(1));
+        let (start, end) = self.payment_requests_list_state.get_start_end();
+        let windowed_view = payment_requests.get(start..end).unwrap_or_default();
+        let column_list = ReceiveTab::create_column_view(windowed_view);
+        column_list.render(f, list_areas[1], &mut list_state);
+
+        let uri = self
+            .payment_requests_list_state
+            .selected()
+            .and_then(|i| payment_requests.get(i))
+            .map(|r| r.request.to_uri())
+            .unwrap_or_default();
+        let uri = Paragraph::new(uri)
+            .wrap(Wrap { trim: false })
+            .block(Block::default().borders(Borders::TOP).title("URI:"));
+        f.render_widget(uri, list_areas[2]);
+    }
+
+    // Helper function to create the column list to be rendered
+    fn create_column_view(windowed_view: &[IssuedPaymentRequest]) -> MultiColumnList<Vec<ListItem>> {
+        let mut column0_items = Vec::new();
+        let mut column1_items = Vec::new();
+        let mut column2_items = Vec::new();
+        let mut column3_items = Vec::new();
+        let mut column4_items = Vec::new();
+        let mut column5_items = Vec::new();
+        for r in windowed_view {
+            column0_items.push(ListItem::new(Span::raw(r.id.to_string())));
+            column1_items.push(ListItem::new(Span::raw(r.request.amount.to_string())));
+            column2_items.push(ListItem::new(Span::raw(r.amount_received.to_string())));
+            column3_items.push(ListItem::new(Span::raw(r.status.to_string())));
+            column4_items.push(ListItem::new(Span::raw(
+                r.request
+                    .expires_at
+                    .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+                    .unwrap_or_else(|| "Never".to_string()),
+            )));
+            column5_items.push(ListItem::new(Span::raw(r.request.memo.clone())));
+        }
+        MultiColumnList::new()
+            .highlight_style(Style::default().add_modifier(Modifier::BOLD).fg(Color::Magenta))
+            .heading_style(Style::default().fg(Color::Magenta))
+            .max_width(MAX_WIDTH)
+            .add_column(Some("Id"), Some(6), column0_items)
+            .add_column(None, Some(1), Vec::new())
+            .add_column(Some("Amount"), Some(18), column1_items)
+            .add_column(None, Some(1), Vec::new())
+            .add_column(Some("Received"), Some(18), column2_items)
+            .add_column(None, Some(1), Vec::new())
+            .add_column(Some("Status"), Some(10), column3_items)
+            .add_column(None, Some(1), Vec::new())
+            .add_column(Some("Expires (UTC)"), Some(16), column4_items)
+            .add_column(None, Some(1), Vec::new())
+            .add_column(Some("Memo"), None, column5_items)
+    }
+
+    // casting here is okay as we only use it to draw widths
+    #[allow(clippy::cast_possible_truncation)]
+    fn draw_create_payment_request<B>(&mut self, f: &mut Frame<B>, area: Rect)
+    where B: Backend {
+        let popup_area = centered_rect_absolute(120, 10, area);
+
+        f.render_widget(Clear, popup_area);
+
+        let block = Block::default().borders(Borders::ALL).title(Span::styled(
+            "Create Payment Request",
+            Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
+        ));
+        f.render_widget(block, popup_area);
+        let vert_chunks = Layout::default()
+            .constraints([Constraint::Length(2), Constraint::Length(3), Constraint::Length(3)].as_ref())
+            .margin(1)
+            .split(popup_area);
+
+        let instructions = Paragraph::new(Spans::from(vec![
+            Span::raw("Press "),
+            Span::styled("Enter", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" to move to the "),
+            Span::styled("Memo", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" field and again to create the request, "),
+            Span::styled("Esc", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" to cancel."),
+        ]))
+        .block(Block::default());
+        f.render_widget(instructions, vert_chunks[0]);
+
+        let amount_input = Paragraph::new(self.amount_field.as_ref())
+            .style(match self.request_input_mode {
+                PaymentRequestInputMode::Amount => Style::default().fg(Color::Magenta),
+                _ => Style::default(),
+            })
+            .block(Block::default().borders(Borders::ALL).title("Amount (uT or T):"));
+        f.render_widget(amount_input, vert_chunks[1]);
+
+        let memo_input = Paragraph::new(self.memo_field.as_ref())
+            .style(match self.request_input_mode {
+                PaymentRequestInputMode::Memo => Style::default().fg(Color::Magenta),
+                _ => Style::default(),
+            })
+            .block(Block::default().borders(Borders::ALL).title("Memo:"));
+        f.render_widget(memo_input, vert_chunks[2]);
+
+        match self.request_input_mode {
+            PaymentRequestInputMode::None => (),
+            PaymentRequestInputMode::Amount => f.set_cursor(
+                // Put cursor past the end of the input text
+                vert_chunks[1].x + self.amount_field.width() as u16 + 1,
+                // Move one line down, from the border to the input line
+                vert_chunks[1].y + 1,
+            ),
+            PaymentRequestInputMode::Memo => f.set_cursor(
+                // Put cursor past the end of the input text
+                vert_chunks[2].x + self.memo_field.width() as u16 + 1,
+                // Move one line down, from the border to the input line
+                vert_chunks[2].y + 1,
+            ),
+        }
+    }
+
+    fn create_payment_request(&mut self, app_state: &mut AppState) {
+        self.request_input_mode = PaymentRequestInputMode::None;
+        let amount = match self.amount_field.parse::<MicroMinotari>() {
+            Ok(amount) => amount,
+            Err(_) => {
+                self.error_message =
+                    Some("Amount should be a valid amount of Minotari\nPress Enter to continue.".to_string());
+                return;
+            },
+        };
+        match Handle::current().block_on(app_state.create_payment_request(amount, self.memo_field.clone())) {
+            Ok(_) => {
+                // Requests are listed newest first
+                self.payment_requests_list_state.select(Some(0));
+            },
+            Err(e) => self.error_message = Some(e.to_string() + "\nPress Enter to continue."),
+        }
+        self.amount_field = String::new();
+        self.memo_field = String::new();
+    }
 }
 
 impl<B: Backend> Component<B> for ReceiveTab {
     fn draw(&mut self, f: &mut Frame<B>, area: Rect, app_state: &AppState) {
         let areas = Layout::default()
-            .constraints([Constraint::Min(42)].as_ref())
+            .constraints([Constraint::Length(33), Constraint::Min(10)].as_ref())
             .split(area);
 
         self.draw_whoami(f, areas[0], app_state);
+        self.draw_payment_requests(f, areas[1], app_state);
+        if self.request_input_mode != PaymentRequestInputMode::None {
+            self.draw_create_payment_request(f, area);
+        }
+
+        if let Some(msg) = self.error_message.clone() {
+            draw_dialog(f, area, "Error!".to_string(), msg, Color::Red, 120, 9);
+        }
+    }
+
+    fn on_key(&mut self, app_state: &mut AppState, c: char) {
+        if self.error_message.is_some() {
+            if '\n' == c {
+                self.error_message = None;
+            }
+            return;
+        }
+
+        match self.request_input_mode {
+            PaymentRequestInputMode::None => {
+                if c == 'r' {
+                    self.request_input_mode = PaymentRequestInputMode::Amount;
+                }
+            },
+            PaymentRequestInputMode::Amount => match c {
+                '\n' | '\t' => self.request_input_mode = PaymentRequestInputMode::Memo,
+                c => self.amount_field.push(c),
+            },
+            PaymentRequestInputMode::Memo => match c {
+                '\n' => self.create_payment_request(app_state),
+                c => self.memo_field.push(c),
+            },
+        }
     }
 
-    fn on_key(&mut self, _app_state: &mut AppState, _c: char) {}
+    fn on_up(&mut self, app_state: &mut AppState) {
+        self.payment_requests_list_state
+            .set_num_items(app_state.get_payment_requests().len());
+        self.payment_requests_list_state.previous();
+    }
 
-    fn on_up(&mut self, _app_state: &mut AppState) {}
+    fn on_down(&mut self, app_state: &mut AppState) {
+        self.payment_requests_list_state
+            .set_num_items(app_state.get_payment_requests().len());
+        self.payment_requests_list_state.next();
+    }
 
-    fn on_down(&mut self, _app_state: &mut AppState) {}
+    fn on_esc(&mut self, _: &mut AppState) {
+        if self.request_input_mode == PaymentRequestInputMode::None {
+            self.payment_requests_list_state.select(None);
+        } else {
+            self.request_input_mode = PaymentRequestInputMode::None;
+            self.amount_field = String::new();
+            self.memo_field = String::new();
+        }
+    }
 
-    fn on_esc(&mut self, _: &mut AppState) {}
+    fn on_backspace(&mut self, _app_state: &mut AppState) {
+        match self.request_input_mode {
+            PaymentRequestInputMode::Amount => {
+                let _ = self.amount_field.pop();
+            },
+            PaymentRequestInputMode::Memo => {
+                let _ = self.memo_field.pop();
+            },
+            PaymentRequestInputMode::None => {},
+        }
+    }
+}
 
-    fn on_backspace(&mut self, _app_state: &mut AppState) {}
+#[derive(PartialEq, Debug)]
+pub enum PaymentRequestInputMode {
+    None,
+    Amount,
+    Memo,
 }
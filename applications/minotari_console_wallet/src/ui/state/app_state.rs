@@ -36,7 +36,7 @@ use minotari_wallet::{
     output_manager_service::{handle::OutputManagerEventReceiver, service::Balance, UtxoSelectionCriteria},
     transaction_service::{
         handle::TransactionEventReceiver,
-        storage::models::{CompletedTransaction, TxCancellationReason},
+        storage::models::{CompletedTransaction, IssuedPaymentRequest, TxCancellationReason},
     },
     util::wallet_identity::WalletIdentity,
     utxo_scanner_service::handle::UtxoScannerHandle,
@@ -272,6 +272,21 @@ impl AppState {
         Ok(())
     }
 
+    /// Issues a payment request to the one-sided address of this wallet and returns its URI
+    pub async fn create_payment_request(&mut self, amount: MicroMinotari, memo: String) -> Result<String, UiError> {
+        let mut inner = self.inner.write().await;
+        let request = inner
+            .wallet
+            .transaction_service
+            .create_payment_request(amount, memo, None, None)
+            .await?;
+
+        inner.refresh_payment_requests_state().await?;
+        drop(inner);
+        self.update_cache().await;
+        Ok(request.request.to_uri())
+    }
+
     pub async fn delete_burnt_proof(&mut self, proof_id: u32) -> Result<(), UiError> {
         let mut inner = self.inner.write().await;
 
@@ -475,6 +490,10 @@ impl AppState {
         self.cached_data.burnt_proofs.get(idx)
     }
 
+    pub fn get_payment_requests(&self) -> &[IssuedPaymentRequest] {
+        self.cached_data.payment_requests.as_slice()
+    }
+
     pub fn get_contacts(&self) -> &[UiContact] {
         self.cached_data.contacts.as_slice()
     }
@@ -788,6 +807,7 @@ impl AppStateInner {
                     .map_err(|e| UiError::TransactionError(e.to_string()))
             })
             .collect::<Result<Vec<_>, _>>()?;
+        self.refresh_payment_requests_state().await?;
         self.updated = true;
         Ok(())
     }
@@ -853,6 +873,7 @@ impl AppStateInner {
                     // dont care
                 }
 
+                let is_inbound = tx.direction == TransactionDirection::Inbound;
                 if let Some(index) = self.data.completed_txs.iter().position(|i| i.tx_id == tx_id) {
                     self.data.completed_txs[index] = tx;
                 } else {
@@ -863,12 +884,25 @@ impl AppStateInner {
                         .partial_cmp(&a.timestamp)
                         .expect("Should be able to compare timestamps")
                 });
+                if is_inbound {
+                    // A received payment may settle one of our payment requests
+                    self.refresh_payment_requests_state().await?;
+                }
             },
         }
         self.updated = true;
         Ok(())
     }
 
+    /// Newest first
+    pub async fn refresh_payment_requests_state(&mut self) -> Result<(), UiError> {
+        let mut payment_requests = self.wallet.transaction_service.get_payment_requests().await?;
+        payment_requests.reverse();
+        self.data.payment_requests = payment_requests;
+        self.updated = true;
+        Ok(())
+    }
+
     pub async fn refresh_contacts_state(&mut self) -> Result<(), UiError> {
         let db_contacts = self.wallet.contacts_service.get_contacts().await?;
         let mut ui_contacts: Vec<UiContact> = vec![];
@@ -1256,6 +1290,7 @@ struct AppStateData {
     confirmations: HashMap<TxId, u64>,
     my_identity: MyIdentity,
     contacts: Vec<UiContact>,
+    payment_requests: Vec<IssuedPaymentRequest>,
     burnt_proofs: Vec<UiBurntProof>,
     connected_peers: Vec<Peer>,
     balance: Balance,
@@ -1336,6 +1371,7 @@ impl AppStateData {
             confirmations: HashMap::new(),
             my_identity: identity,
             contacts: Vec::new(),
+            payment_requests: Vec::new(),
             burnt_proofs: vec![],
             connected_peers: Vec::new(),
             balance: Balance::zero(),
@@ -521,7 +521,13 @@ async fn run_grpc(
 mod test {
     use std::path::Path;
 
-    use crate::{cli::CliCommands, wallet_modes::parse_command_file};
+    use minotari_wallet::transaction_service::storage::models::TransactionHistoryStatus;
+    use tari_core::transactions::tari_amount::MicroMinotari;
+
+    use crate::{
+        cli::{CliCommands, TxHistoryFormat},
+        wallet_modes::parse_command_file,
+    };
 
     #[test]
     #[allow(clippy::too_many_lines)]
@@ -573,6 +579,30 @@ mod test {
 
             import-tx --input-file pie_this_message.txt
 
+            create-account savings
+
+            send-minotari --account savings 1T \
+             f425UWsDp714RiN53c1G6ek57rfFnotB5NCMyrn4iDgbR8i2sXVHa4xSsedd66o9KmkRgErQnyDdCaAdNLzcKrj7eUb
+
+            sign-tx --input-file unsigned.json --output-file signed.json
+
+            schedule-payment --at 2024-11-01T09:00:00Z 1T \
+             f425UWsDp714RiN53c1G6ek57rfFnotB5NCMyrn4iDgbR8i2sXVHa4xSsedd66o9KmkRgErQnyDdCaAdNLzcKrj7eUb
+
+            send-batch --input-file payroll.csv --fee-per-gram 5
+
+            label-utxos exchange --commitments 5a7c3e4ce6c8e1d24b6b8f4a1e2f3c6d7e8f9a0b1c2d3e4f5a6b7c8d9e0f1a2b
+
+            freeze-utxos --commitments 5a7c3e4ce6c8e1d24b6b8f4a1e2f3c6d7e8f9a0b1c2d3e4f5a6b7c8d9e0f1a2b
+
+            send-minotari --label exchange --avoid-mixing-labels 1T \
+             f425UWsDp714RiN53c1G6ek57rfFnotB5NCMyrn4iDgbR8i2sXVHa4xSsedd66o9KmkRgErQnyDdCaAdNLzcKrj7eUb
+
+            consolidate --threshold 1T --max-inputs 100 --mix-labels --dry-run
+
+            export-tx-history --output-file history.json --format json --from 2024-01-01T00:00:00Z --status completed \
+             --status cancelled
+
             # End of script file
             "
             .to_string();
@@ -594,10 +624,28 @@ mod test {
         let mut export_tx = false;
         let mut import_tx = false;
         let mut whois = false;
+        let mut create_account = false;
+        let mut send_from_account = false;
+        let mut sign_tx = false;
+        let mut schedule_payment = false;
+        let mut send_batch = false;
+        let mut label_utxos = false;
+        let mut freeze_utxos = false;
+        let mut send_from_label = false;
+        let mut consolidate = false;
+        let mut export_tx_history = false;
         for command in commands {
             match command {
                 CliCommands::GetBalance => get_balance = true,
-                CliCommands::SendMinotari(_) => send_tari = true,
+                CliCommands::SendMinotari(args) => {
+                    if args.account.as_deref() == Some("savings") {
+                        send_from_account = true
+                    }
+                    if args.labels == ["exchange"] && args.avoid_mixing_labels {
+                        send_from_label = true
+                    }
+                    send_tari = true
+                },
                 CliCommands::BurnMinotari(_) => burn_tari = true,
                 CliCommands::PreMineSpendGetOutputStatus => pre_mine_spend_get_output_status = true,
                 CliCommands::PreMineSpendSessionInfo(_) => pre_mine_spend_session_info = true,
@@ -636,6 +684,47 @@ mod test {
                 CliCommands::PreMineSpendBackupUtxo(_) => {},
                 CliCommands::Sync(_) => {},
                 CliCommands::ExportViewKeyAndSpendKey(_) => {},
+                CliCommands::CreateAccount(args) => create_account = args.name == "savings",
+                CliCommands::ListAccounts => {},
+                CliCommands::MoveToAccount(_) => {},
+                CliCommands::CreateUnsignedTx(_) => {},
+                CliCommands::ExportUnsignedTx(_) => {},
+                CliCommands::SignTx(args) => {
+                    sign_tx =
+                        args.input_file == Path::new("unsigned.json") && args.output_file == Path::new("signed.json")
+                },
+                CliCommands::SubmitSignedTx(_) => {},
+                CliCommands::SchedulePayment(args) => {
+                    schedule_payment = args.at.map(|at| at.to_rfc3339()) ==
+                        Some("2024-11-01T09:00:00+00:00".to_string()) &&
+                        args.cron.is_none()
+                },
+                CliCommands::ListScheduledPayments => {},
+                CliCommands::CancelScheduledPayment(_) => {},
+                CliCommands::SendBatch(args) => {
+                    send_batch =
+                        args.input_file == Path::new("payroll.csv") && args.fee_per_gram == Some(MicroMinotari::from(5))
+                },
+                CliCommands::ListUtxos(_) => {},
+                CliCommands::LabelUtxos(args) => {
+                    label_utxos =
+                        args.label.as_deref() == Some("exchange") && !args.clear && args.commitments.len() == 1
+                },
+                CliCommands::FreezeUtxos(args) => freeze_utxos = args.commitments.len() == 1,
+                CliCommands::UnfreezeUtxos(_) => {},
+                CliCommands::Consolidate(args) => {
+                    consolidate = args.threshold == Some(MicroMinotari::from(1_000_000)) &&
+                        args.max_inputs == Some(100) &&
+                        args.mix_labels &&
+                        args.dry_run
+                },
+                CliCommands::ListConsolidations => {},
+                CliCommands::ExportTxHistory(args) => {
+                    export_tx_history = args.format == TxHistoryFormat::Json &&
+                        args.from.is_some() &&
+                        args.to.is_none() &&
+                        args.status == vec![TransactionHistoryStatus::Completed, TransactionHistoryStatus::Cancelled]
+                },
             }
         }
         assert!(
@@ -653,7 +742,17 @@ mod test {
                 discover_peer &&
                 whois &&
                 export_tx &&
-                import_tx
+                import_tx &&
+                create_account &&
+                send_from_account &&
+                sign_tx &&
+                schedule_payment &&
+                send_batch &&
+                label_utxos &&
+                freeze_utxos &&
+                send_from_label &&
+                consolidate &&
+                export_tx_history
         );
     }
 }
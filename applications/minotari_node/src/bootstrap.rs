@@ -31,7 +31,7 @@ use tari_common::{
 use tari_comms::{
     multiaddr::{Error as MultiaddrError, Multiaddr},
     peer_manager::Peer,
-    protocol::rpc::RpcServer,
+    protocol::rpc::{middleware::RpcMiddlewareExt, RpcServer},
     tor::TorIdentity,
     NodeIdentity,
     UnspawnedCommsNode,
@@ -140,7 +140,6 @@ where B: BlockchainBackend + 'static
                 self.rules.clone(),
                 base_node_config.messaging_request_timeout,
                 self.randomx_factory.clone(),
-                base_node_config.state_machine.clone(),
             ))
             .add_initializer(MempoolServiceInitializer::new(
                 self.mempool.clone(),
@@ -235,18 +234,21 @@ where B: BlockchainBackend + 'static
         // Add your RPC services here ‍🏴‍☠️️☮️🌊
         let rpc_server = rpc_server
             .add_service(dht.rpc_service())
-            .add_service(base_node::create_base_node_sync_rpc_service(
-                db.clone(),
-                base_node_service,
-            ))
+            .add_service(
+                base_node::create_base_node_sync_rpc_service(db.clone(), base_node_service)
+                    .with_middleware(base_node::base_node_sync_rpc_middleware()),
+            )
             .add_service(mempool::create_mempool_rpc_service(
                 handles.expect_handle::<MempoolHandle>(),
             ))
-            .add_service(base_node::rpc::create_base_node_wallet_rpc_service(
-                db,
-                handles.expect_handle::<MempoolHandle>(),
-                handles.expect_handle::<StateMachineHandle>(),
-            ));
+            .add_service(
+                base_node::rpc::create_base_node_wallet_rpc_service(
+                    db,
+                    handles.expect_handle::<MempoolHandle>(),
+                    handles.expect_handle::<StateMachineHandle>(),
+                )
+                .with_middleware(base_node::rpc::base_node_wallet_rpc_middleware()),
+            );
 
         handles.register(rpc_server.get_handle());
 
@@ -31,7 +31,7 @@ use tari_comms::{peer_manager::NodeIdentity, protocol::rpc::RpcServerHandle, Com
 use tari_comms_dht::Dht;
 use tari_core::{
     base_node::{state_machine_service::states::StatusInfo, LocalNodeCommsInterface, StateMachineHandle},
-    chain_storage::{create_lmdb_database, BlockchainDatabase, ChainStorageError, LMDBDatabase, Validators},
+    chain_storage::{BlockchainDatabase, ChainStorageError, Validators},
     consensus::ConsensusManager,
     mempool::{service::LocalMempoolService, Mempool},
     proof_of_work::randomx_factory::RandomXFactory,
@@ -49,7 +49,7 @@ use tari_service_framework::ServiceHandles;
 use tari_shutdown::ShutdownSignal;
 use tokio::sync::watch;
 
-use crate::{bootstrap::BaseNodeBootstrapper, ApplicationConfig, DatabaseType};
+use crate::{bootstrap::BaseNodeBootstrapper, database::ChainBackend, ApplicationConfig};
 
 const LOG_TARGET: &str = "c::bn::initialization";
 
@@ -59,7 +59,7 @@ const LOG_TARGET: &str = "c::bn::initialization";
 pub struct BaseNodeContext {
     config: Arc<ApplicationConfig>,
     consensus_rules: ConsensusManager,
-    blockchain_db: BlockchainDatabase<LMDBDatabase>,
+    blockchain_db: BlockchainDatabase<ChainBackend>,
     base_node_comms: CommsNode,
     base_node_dht: Dht,
     base_node_handles: ServiceHandles,
@@ -127,7 +127,7 @@ impl BaseNodeContext {
     }
 
     /// Returns a BlockchainDatabase handle
-    pub fn blockchain_db(&self) -> BlockchainDatabase<LMDBDatabase> {
+    pub fn blockchain_db(&self) -> BlockchainDatabase<ChainBackend> {
         self.blockchain_db.clone()
     }
 
@@ -166,21 +166,12 @@ pub async fn configure_and_initialize_node(
     node_identity: Arc<NodeIdentity>,
     interrupt_signal: ShutdownSignal,
 ) -> Result<BaseNodeContext, ExitError> {
-    let result = match &app_config.base_node.db_type {
-        DatabaseType::Lmdb => {
-            let rules = ConsensusManager::builder(app_config.base_node.network)
-                .build()
-                .map_err(|e| ExitError::new(ExitCode::UnknownError, e))?;
-            let backend = create_lmdb_database(
-                app_config.base_node.lmdb_path.as_path(),
-                app_config.base_node.lmdb.clone(),
-                rules,
-            )
-            .map_err(|e| ExitError::new(ExitCode::DatabaseError, e))?;
-            build_node_context(backend, app_config, node_identity, interrupt_signal).await?
-        },
-    };
-    Ok(result)
+    let rules = ConsensusManager::builder(app_config.base_node.network)
+        .build()
+        .map_err(|e| ExitError::new(ExitCode::UnknownError, e))?;
+    let backend =
+        ChainBackend::open(&app_config.base_node, rules).map_err(|e| ExitError::new(ExitCode::DatabaseError, e))?;
+    build_node_context(backend, app_config, node_identity, interrupt_signal).await
 }
 
 /// Constructs the base node context, this includes setting up the consensus manager, mempool, base node
@@ -195,7 +186,7 @@ pub async fn configure_and_initialize_node(
 /// ## Returns
 /// Result containing the BaseNodeContext, String will contain the reason on error
 async fn build_node_context(
-    backend: LMDBDatabase,
+    backend: ChainBackend,
     app_config: Arc<ApplicationConfig>,
     base_node_identity: Arc<NodeIdentity>,
     interrupt_signal: ShutdownSignal,
@@ -20,6 +20,8 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+use std::path::PathBuf;
+
 use clap::Parser;
 use minotari_app_utilities::common_cli_args::CommonCliArgs;
 use tari_common::configuration::{ConfigOverrideProvider, Network};
@@ -37,6 +39,13 @@ pub struct Cli {
     /// This will rebuild the db, adding block for block in
     #[clap(long, alias = "rebuild_db")]
     pub rebuild_db: bool,
+    /// Import a chain snapshot created with the `export-snapshot` command into an empty pruned node, then exit
+    #[clap(long, alias = "import_snapshot")]
+    pub import_snapshot: Option<PathBuf>,
+    /// Copy the LMDB chain database into a new redb database at `base_node.redb_path`, then exit
+    #[cfg(feature = "redb")]
+    #[clap(long, alias = "migrate_to_redb")]
+    pub migrate_to_redb: bool,
     /// Run in non-interactive mode, with no UI.
     #[clap(short, long, alias = "non-interactive", env = "TARI_NON_INTERACTIVE")]
     pub non_interactive_mode: bool,
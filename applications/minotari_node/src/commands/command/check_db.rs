@@ -20,22 +20,44 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use anyhow::Error;
+use anyhow::{anyhow, Error};
 use async_trait::async_trait;
 use clap::Parser;
-use tokio::io::{self, AsyncWriteExt};
+use tari_core::chain_storage::check_chain_consistency;
+use tokio::{
+    io::{self, AsyncWriteExt},
+    task,
+};
 
 use super::{CommandContext, HandleCommand};
 use crate::LOG_TARGET;
 
 /// Checks the blockchain database for missing blocks and headers
 #[derive(Debug, Parser)]
-pub struct Args {}
+pub struct Args {
+    /// Recompute the kernel MMR and output SMT roots and the spend state of every block, and compare them against the
+    /// stored headers
+    #[clap(long)]
+    deep: bool,
+    /// The first height to check with --deep. Defaults to the genesis block.
+    #[clap(long, requires = "deep")]
+    from: Option<u64>,
+    /// The last height to check with --deep. Defaults to the current tip.
+    #[clap(long, requires = "deep")]
+    to: Option<u64>,
+    /// Rewind the blockchain to the last consistent height if --deep finds an inconsistency
+    #[clap(long, requires = "deep")]
+    rewind: bool,
+}
 
 #[async_trait]
 impl HandleCommand<Args> for CommandContext {
-    async fn handle_command(&mut self, _: Args) -> Result<(), Error> {
-        self.check_db().await
+    async fn handle_command(&mut self, args: Args) -> Result<(), Error> {
+        if args.deep {
+            self.check_db_deep(args.from, args.to, args.rewind).await
+        } else {
+            self.check_db().await
+        }
     }
 }
 
@@ -82,4 +104,39 @@ impl CommandContext {
         }
         Ok(())
     }
+
+    /// Function to process the check-db --deep command
+    pub async fn check_db_deep(&self, from: Option<u64>, to: Option<u64>, rewind: bool) -> Result<(), Error> {
+        let start_height = from.unwrap_or(0);
+        let end_height = match to {
+            Some(height) => height,
+            None => self.blockchain_db.get_chain_metadata().await?.best_block_height(),
+        };
+        println!("Checking blocks {} to {}...", start_height, end_height);
+        let db = self.blockchain_db.inner().clone();
+        let report =
+            task::spawn_blocking(move || check_chain_consistency(&db, start_height, end_height).map_err(Error::from))
+                .await??;
+        if report.is_consistent() {
+            println!("Blocks {} to {} are consistent", report.start_height, report.end_height);
+            return Ok(());
+        }
+        for inconsistency in &report.inconsistencies {
+            println!("{}", inconsistency);
+        }
+        let last_good_height = report
+            .last_good_height()
+            .ok_or_else(|| anyhow!("The genesis block is inconsistent, the database must be resynced"))?;
+        if rewind {
+            println!("Rewinding the blockchain to height {}", last_good_height);
+            self.rewind_blockchain(last_good_height).await?;
+        } else {
+            println!(
+                "The last consistent height is {}. Run `check-db --deep --rewind` or `rewind-blockchain {}` to rewind \
+                 the blockchain to it.",
+                last_good_height, last_good_height
+            );
+        }
+        Ok(())
+    }
 }
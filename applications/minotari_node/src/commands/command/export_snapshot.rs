@@ -0,0 +1,69 @@
+//  Copyright 2024, The Tari Project
+//
+//  Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+//  following conditions are met:
+//
+//  1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+//  disclaimer.
+//
+//  2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+//  following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+//  3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+//  products derived from this software without specific prior written permission.
+//
+//  THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+//  INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+//  DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+//  SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+//  SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+//  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+//  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::{fs::File, io::BufWriter, path::PathBuf};
+
+use anyhow::{anyhow, Error};
+use async_trait::async_trait;
+use clap::Parser;
+use tari_core::chain_storage::export_snapshot;
+use tokio::task;
+
+use super::{CommandContext, HandleCommand};
+
+/// Exports a snapshot of the chain that can be used to bootstrap a pruned node with `--import-snapshot`
+#[derive(Debug, Parser)]
+pub struct Args {
+    /// The file to write the snapshot to
+    path: PathBuf,
+    /// The height of the snapshot. Defaults to the current tip.
+    #[clap(long)]
+    height: Option<u64>,
+}
+
+#[async_trait]
+impl HandleCommand<Args> for CommandContext {
+    async fn handle_command(&mut self, args: Args) -> Result<(), Error> {
+        self.export_snapshot(args.path, args.height).await
+    }
+}
+
+impl CommandContext {
+    pub async fn export_snapshot(&self, path: PathBuf, height: Option<u64>) -> Result<(), Error> {
+        let height = match height {
+            Some(height) => height,
+            None => self.blockchain_db.get_chain_metadata().await?.best_block_height(),
+        };
+        if path.exists() {
+            return Err(anyhow!("{} already exists", path.display()));
+        }
+        println!("Exporting snapshot at height {} to {}...", height, path.display());
+        let db = self.blockchain_db.inner().clone();
+        let summary = task::spawn_blocking(move || {
+            let writer = BufWriter::new(File::create(&path)?);
+            export_snapshot(&db, height, writer).map_err(Error::from)
+        })
+        .await??;
+        println!("{}", summary);
+        Ok(())
+    }
+}
@@ -23,9 +23,9 @@
 use anyhow::Error;
 use async_trait::async_trait;
 use clap::Parser;
+use tari_comms::connection_manager::BandwidthStats;
 
 use super::{CommandContext, HandleCommand};
-#[cfg(feature = "metrics")]
 use crate::table::Table;
 
 /// Displays network stats
@@ -35,11 +35,42 @@ pub struct Args {}
 #[async_trait]
 impl HandleCommand<Args> for CommandContext {
     async fn handle_command(&mut self, _: Args) -> Result<(), Error> {
+        self.get_bandwidth_usage().await?;
+        println!();
         self.get_network_stats()
     }
 }
 
 impl CommandContext {
+    /// Prints the global, per-protocol and per-peer bandwidth usage and limits
+    pub async fn get_bandwidth_usage(&mut self) -> Result<(), Error> {
+        let usage = self.comms.connection_manager_requester().get_bandwidth_usage().await?;
+
+        let mut table = Table::new();
+        table.set_titles(vec![
+            "Budget",
+            "Upload Limit",
+            "Upload Rate",
+            "Uploaded",
+            "Download Limit",
+            "Download Rate",
+            "Downloaded",
+        ]);
+        table.add_row(bandwidth_row("Global".to_string(), &usage.global));
+        let mut per_protocol = usage.per_protocol;
+        per_protocol.sort_by(|(a, _), (b, _)| a.cmp(b));
+        for (protocol, stats) in &per_protocol {
+            table.add_row(bandwidth_row(String::from_utf8_lossy(protocol).to_string(), stats));
+        }
+        let mut per_peer = usage.per_peer;
+        per_peer.sort_by(|(_, a), (_, b)| (b.upload_rate + b.download_rate).cmp(&(a.upload_rate + a.download_rate)));
+        for (node_id, stats) in &per_peer {
+            table.add_row(bandwidth_row(node_id.short_str(), stats));
+        }
+        table.print_stdout();
+        Ok(())
+    }
+
     #[cfg(not(feature = "metrics"))]
     pub fn get_network_stats(&self) -> Result<(), Error> {
         println!(
@@ -92,3 +123,36 @@ impl CommandContext {
         Ok(())
     }
 }
+
+fn bandwidth_row(name: String, stats: &BandwidthStats) -> Vec<String> {
+    vec![
+        name,
+        format_limit(stats.limit.upload_bytes_per_sec),
+        format!("{}/s", format_bytes(stats.upload_rate)),
+        format_bytes(stats.bytes_uploaded),
+        format_limit(stats.limit.download_bytes_per_sec),
+        format!("{}/s", format_bytes(stats.download_rate)),
+        format_bytes(stats.bytes_downloaded),
+    ]
+}
+
+fn format_limit(limit: Option<u64>) -> String {
+    limit
+        .map(|l| format!("{}/s", format_bytes(l)))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.2} {}", value, UNITS[unit])
+}
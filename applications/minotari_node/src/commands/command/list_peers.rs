@@ -58,7 +58,7 @@ impl CommandContext {
         let num_peers = peers.len();
         println!();
         let mut table = Table::new();
-        table.set_titles(vec!["NodeId", "Public Key", "Role", "User Agent", "Info"]);
+        table.set_titles(vec!["NodeId", "Public Key", "Role", "User Agent", "Reputation", "Info"]);
 
         peers.sort_by(|a, b| a.node_id.cmp(&b.node_id));
         for peer in peers {
@@ -105,6 +105,7 @@ impl CommandContext {
                     s.join(", ")
                 }
             };
+            let reputation = peer.reputation_score();
             let ua = peer.user_agent;
             table.add_row(row![
                 peer.node_id,
@@ -123,6 +124,7 @@ impl CommandContext {
                         ua.as_ref()
                     }
                 },
+                reputation,
                 info_str,
             ]);
         }
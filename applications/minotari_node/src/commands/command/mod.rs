@@ -28,6 +28,7 @@ mod check_for_updates;
 mod create_tls_certs;
 mod dial_peer;
 mod discover_peer;
+mod export_snapshot;
 mod get_block;
 mod get_chain_metadata;
 mod get_db_stats;
@@ -76,7 +77,7 @@ use tari_comms_dht::{DhtDiscoveryRequester, MetricsCollectorHandle};
 use tari_core::{
     base_node::{state_machine_service::states::StatusInfo, LocalNodeCommsInterface},
     blocks::ChainHeader,
-    chain_storage::{async_db::AsyncBlockchainDb, LMDBDatabase},
+    chain_storage::async_db::AsyncBlockchainDb,
     consensus::ConsensusManager,
     mempool::service::LocalMempoolService,
 };
@@ -88,6 +89,7 @@ pub use watch_command::WatchCommand;
 use crate::{
     builder::BaseNodeContext,
     commands::{nom_parser::ParsedCommand, parser::FromHex},
+    database::ChainBackend,
     ApplicationConfig,
 };
 
@@ -119,6 +121,7 @@ pub enum Command {
     ListConnections(list_connections::Args),
     ListHeaders(list_headers::Args),
     CheckDb(check_db::Args),
+    ExportSnapshot(export_snapshot::Args),
     PeriodStats(period_stats::Args),
     HeaderStats(header_stats::Args),
     BlockTiming(block_timing::Args),
@@ -154,7 +157,7 @@ pub trait HandleCommand<T> {
 pub struct CommandContext {
     pub config: Arc<ApplicationConfig>,
     consensus_rules: ConsensusManager,
-    blockchain_db: AsyncBlockchainDb<LMDBDatabase>,
+    blockchain_db: AsyncBlockchainDb<ChainBackend>,
     discovery_service: DhtDiscoveryRequester,
     dht_metrics_collector: MetricsCollectorHandle,
     rpc_server: RpcServerHandle,
@@ -235,6 +238,8 @@ impl CommandContext {
                 Command::Exit(_) => 30,
                 // These commands involve intense blockchain db operations and needs a lot of time to complete
                 Command::CheckDb(_) | Command::PeriodStats(_) | Command::RewindBlockchain(_) => 600,
+                // Exporting a snapshot reads the entire chain up to the snapshot height
+                Command::ExportSnapshot(_) => 3600,
             };
             let fut = self.handle_command(args.command);
             if let Err(e) = time::timeout(Duration::from_secs(time_out), fut).await? {
@@ -279,6 +284,7 @@ impl HandleCommand<Command> for CommandContext {
             Command::UnbanAllPeers(args) => self.handle_command(args).await,
             Command::ListHeaders(args) => self.handle_command(args).await,
             Command::CheckDb(args) => self.handle_command(args).await,
+            Command::ExportSnapshot(args) => self.handle_command(args).await,
             Command::PeriodStats(args) => self.handle_command(args).await,
             Command::HeaderStats(args) => self.handle_command(args).await,
             Command::BlockTiming(args) => self.handle_command(args).await,
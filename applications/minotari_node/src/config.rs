@@ -115,6 +115,8 @@ pub struct BaseNodeConfig {
     pub config_dir: PathBuf,
     /// The relative path to store the lmbd data
     pub lmdb_path: PathBuf,
+    /// The relative path to store the redb data, used when `db_type` is `redb`
+    pub redb_path: PathBuf,
     /// The maximum amount of VMs that RandomX will be use
     pub max_randomx_vms: usize,
     /// Bypass range proof verification to speed up validation
@@ -169,6 +171,7 @@ impl Default for BaseNodeConfig {
             data_dir: PathBuf::from("data/base_node"),
             config_dir: PathBuf::from("config/base_node"),
             lmdb_path: PathBuf::from("db"),
+            redb_path: PathBuf::from("redb"),
             max_randomx_vms: 5,
             bypass_range_proof_verification: false,
             force_sync_peers: StringList::default(),
@@ -207,6 +210,9 @@ impl BaseNodeConfig {
         if !self.lmdb_path.is_absolute() {
             self.lmdb_path = self.data_dir.join(self.lmdb_path.as_path());
         }
+        if !self.redb_path.is_absolute() {
+            self.redb_path = self.data_dir.join(self.redb_path.as_path());
+        }
         self.p2p.set_base_path(base_path);
     }
 }
@@ -215,4 +221,7 @@ impl BaseNodeConfig {
 #[serde(rename_all = "snake_case")]
 pub enum DatabaseType {
     Lmdb,
+    /// Requires the `redb` feature
+    #[cfg(feature = "redb")]
+    Redb,
 }
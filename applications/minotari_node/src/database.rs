@@ -0,0 +1,297 @@
+//  Copyright 2024, The Tari Project
+//
+//  Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+//  following conditions are met:
+//
+//  1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+//  disclaimer.
+//
+//  2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+//  following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+//  3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+//  products derived from this software without specific prior written permission.
+//
+//  THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+//  INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+//  DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+//  SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+//  SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+//  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+//  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use tari_common_types::{
+    chain_metadata::ChainMetadata,
+    types::{Commitment, HashOutput, PublicKey, Signature},
+};
+#[cfg(feature = "redb")]
+use tari_core::chain_storage::{create_redb_database, RedbDatabase};
+use tari_core::{
+    blocks::{Block, BlockAccumulatedData, BlockHeader, BlockHeaderAccumulatedData, ChainBlock, ChainHeader},
+    chain_storage::{
+        create_lmdb_database,
+        BlockchainBackend,
+        ChainStorageError,
+        DbBasicStats,
+        DbKey,
+        DbTotalSizeStats,
+        DbTransaction,
+        DbValue,
+        HorizonData,
+        InputMinedInfo,
+        LMDBDatabase,
+        MmrTree,
+        OutputIndex,
+        OutputMinedInfo,
+        OutputSmtDelta,
+        Reorg,
+        TemplateRegistrationEntry,
+    },
+    consensus::ConsensusManager,
+    transactions::transaction_components::{TransactionInput, TransactionKernel, TransactionOutput},
+    OutputSmt,
+};
+
+use crate::{BaseNodeConfig, DatabaseType};
+
+/// The chain storage backend selected by `base_node.db_type`
+pub enum ChainBackend {
+    Lmdb(LMDBDatabase),
+    #[cfg(feature = "redb")]
+    Redb(RedbDatabase),
+}
+
+impl ChainBackend {
+    /// Opens the chain database configured in `config`, creating it if it does not exist
+    pub fn open(config: &BaseNodeConfig, rules: ConsensusManager) -> Result<Self, ChainStorageError> {
+        match config.db_type {
+            DatabaseType::Lmdb => Ok(ChainBackend::Lmdb(create_lmdb_database(
+                &config.lmdb_path,
+                config.lmdb.clone(),
+                rules,
+            )?)),
+            #[cfg(feature = "redb")]
+            DatabaseType::Redb => Ok(ChainBackend::Redb(create_redb_database(&config.redb_path, rules)?)),
+        }
+    }
+}
+
+macro_rules! dispatch {
+    ($self:ident, $db:ident => $call:expr) => {
+        match $self {
+            ChainBackend::Lmdb($db) => $call,
+            #[cfg(feature = "redb")]
+            ChainBackend::Redb($db) => $call,
+        }
+    };
+}
+
+impl BlockchainBackend for ChainBackend {
+    fn write(&mut self, tx: DbTransaction) -> Result<(), ChainStorageError> {
+        dispatch!(self, db => db.write(tx))
+    }
+
+    fn fetch(&self, key: &DbKey) -> Result<Option<DbValue>, ChainStorageError> {
+        dispatch!(self, db => db.fetch(key))
+    }
+
+    fn contains(&self, key: &DbKey) -> Result<bool, ChainStorageError> {
+        dispatch!(self, db => db.contains(key))
+    }
+
+    fn fetch_chain_header_by_height(&self, height: u64) -> Result<ChainHeader, ChainStorageError> {
+        dispatch!(self, db => db.fetch_chain_header_by_height(height))
+    }
+
+    fn fetch_header_accumulated_data(
+        &self,
+        hash: &HashOutput,
+    ) -> Result<Option<BlockHeaderAccumulatedData>, ChainStorageError> {
+        dispatch!(self, db => db.fetch_header_accumulated_data(hash))
+    }
+
+    fn fetch_chain_header_in_all_chains(&self, hash: &HashOutput) -> Result<ChainHeader, ChainStorageError> {
+        dispatch!(self, db => db.fetch_chain_header_in_all_chains(hash))
+    }
+
+    fn fetch_header_containing_kernel_mmr(&self, mmr_position: u64) -> Result<ChainHeader, ChainStorageError> {
+        dispatch!(self, db => db.fetch_header_containing_kernel_mmr(mmr_position))
+    }
+
+    fn is_empty(&self) -> Result<bool, ChainStorageError> {
+        dispatch!(self, db => db.is_empty())
+    }
+
+    fn fetch_block_accumulated_data(
+        &self,
+        header_hash: &HashOutput,
+    ) -> Result<Option<BlockAccumulatedData>, ChainStorageError> {
+        dispatch!(self, db => db.fetch_block_accumulated_data(header_hash))
+    }
+
+    fn fetch_block_accumulated_data_by_height(
+        &self,
+        height: u64,
+    ) -> Result<Option<BlockAccumulatedData>, ChainStorageError> {
+        dispatch!(self, db => db.fetch_block_accumulated_data_by_height(height))
+    }
+
+    fn fetch_kernels_in_block(&self, header_hash: &HashOutput) -> Result<Vec<TransactionKernel>, ChainStorageError> {
+        dispatch!(self, db => db.fetch_kernels_in_block(header_hash))
+    }
+
+    fn fetch_kernel_by_excess_sig(
+        &self,
+        excess_sig: &Signature,
+    ) -> Result<Option<(TransactionKernel, HashOutput)>, ChainStorageError> {
+        dispatch!(self, db => db.fetch_kernel_by_excess_sig(excess_sig))
+    }
+
+    fn fetch_outputs_in_block_with_spend_state(
+        &self,
+        header_hash: &HashOutput,
+        spend_status_at_header: Option<&HashOutput>,
+    ) -> Result<Vec<(TransactionOutput, bool)>, ChainStorageError> {
+        dispatch!(self, db => db.fetch_outputs_in_block_with_spend_state(header_hash, spend_status_at_header))
+    }
+
+    fn fetch_output(&self, output_hash: &HashOutput) -> Result<Option<OutputMinedInfo>, ChainStorageError> {
+        dispatch!(self, db => db.fetch_output(output_hash))
+    }
+
+    fn fetch_input(&self, output_hash: &HashOutput) -> Result<Option<InputMinedInfo>, ChainStorageError> {
+        dispatch!(self, db => db.fetch_input(output_hash))
+    }
+
+    fn fetch_unspent_output_hash_by_commitment(
+        &self,
+        commitment: &Commitment,
+    ) -> Result<Option<HashOutput>, ChainStorageError> {
+        dispatch!(self, db => db.fetch_unspent_output_hash_by_commitment(commitment))
+    }
+
+    fn fetch_outputs_in_block(&self, header_hash: &HashOutput) -> Result<Vec<TransactionOutput>, ChainStorageError> {
+        dispatch!(self, db => db.fetch_outputs_in_block(header_hash))
+    }
+
+    fn fetch_inputs_in_block(&self, header_hash: &HashOutput) -> Result<Vec<TransactionInput>, ChainStorageError> {
+        dispatch!(self, db => db.fetch_inputs_in_block(header_hash))
+    }
+
+    fn fetch_mmr_size(&self, tree: MmrTree) -> Result<u64, ChainStorageError> {
+        dispatch!(self, db => db.fetch_mmr_size(tree))
+    }
+
+    fn orphan_count(&self) -> Result<usize, ChainStorageError> {
+        dispatch!(self, db => db.orphan_count())
+    }
+
+    fn fetch_last_header(&self) -> Result<BlockHeader, ChainStorageError> {
+        dispatch!(self, db => db.fetch_last_header())
+    }
+
+    fn clear_all_pending_headers(&self) -> Result<usize, ChainStorageError> {
+        dispatch!(self, db => db.clear_all_pending_headers())
+    }
+
+    fn fetch_last_chain_header(&self) -> Result<ChainHeader, ChainStorageError> {
+        dispatch!(self, db => db.fetch_last_chain_header())
+    }
+
+    fn fetch_tip_header(&self) -> Result<ChainHeader, ChainStorageError> {
+        dispatch!(self, db => db.fetch_tip_header())
+    }
+
+    fn fetch_chain_metadata(&self) -> Result<ChainMetadata, ChainStorageError> {
+        dispatch!(self, db => db.fetch_chain_metadata())
+    }
+
+    fn utxo_count(&self) -> Result<usize, ChainStorageError> {
+        dispatch!(self, db => db.utxo_count())
+    }
+
+    fn kernel_count(&self) -> Result<usize, ChainStorageError> {
+        dispatch!(self, db => db.kernel_count())
+    }
+
+    fn fetch_orphan_chain_tip_by_hash(&self, hash: &HashOutput) -> Result<Option<ChainHeader>, ChainStorageError> {
+        dispatch!(self, db => db.fetch_orphan_chain_tip_by_hash(hash))
+    }
+
+    fn fetch_strongest_orphan_chain_tips(&self) -> Result<Vec<ChainHeader>, ChainStorageError> {
+        dispatch!(self, db => db.fetch_strongest_orphan_chain_tips())
+    }
+
+    fn fetch_orphan_children_of(&self, hash: HashOutput) -> Result<Vec<Block>, ChainStorageError> {
+        dispatch!(self, db => db.fetch_orphan_children_of(hash))
+    }
+
+    fn fetch_orphan_chain_block(&self, hash: HashOutput) -> Result<Option<ChainBlock>, ChainStorageError> {
+        dispatch!(self, db => db.fetch_orphan_chain_block(hash))
+    }
+
+    fn delete_oldest_orphans(
+        &mut self,
+        horizon_height: u64,
+        orphan_storage_capacity: usize,
+    ) -> Result<(), ChainStorageError> {
+        dispatch!(self, db => db.delete_oldest_orphans(horizon_height, orphan_storage_capacity))
+    }
+
+    fn fetch_monero_seed_first_seen_height(&self, seed: &[u8]) -> Result<u64, ChainStorageError> {
+        dispatch!(self, db => db.fetch_monero_seed_first_seen_height(seed))
+    }
+
+    fn fetch_horizon_data(&self) -> Result<Option<HorizonData>, ChainStorageError> {
+        dispatch!(self, db => db.fetch_horizon_data())
+    }
+
+    fn get_stats(&self) -> Result<DbBasicStats, ChainStorageError> {
+        dispatch!(self, db => db.get_stats())
+    }
+
+    fn fetch_total_size_stats(&self) -> Result<DbTotalSizeStats, ChainStorageError> {
+        dispatch!(self, db => db.fetch_total_size_stats())
+    }
+
+    fn bad_block_exists(&self, block_hash: HashOutput) -> Result<(bool, String), ChainStorageError> {
+        dispatch!(self, db => db.bad_block_exists(block_hash))
+    }
+
+    fn fetch_all_reorgs(&self) -> Result<Vec<Reorg>, ChainStorageError> {
+        dispatch!(self, db => db.fetch_all_reorgs())
+    }
+
+    fn fetch_active_validator_nodes(&self, height: u64) -> Result<Vec<(PublicKey, [u8; 32])>, ChainStorageError> {
+        dispatch!(self, db => db.fetch_active_validator_nodes(height))
+    }
+
+    fn get_shard_key(&self, height: u64, public_key: PublicKey) -> Result<Option<[u8; 32]>, ChainStorageError> {
+        dispatch!(self, db => db.get_shard_key(height, public_key))
+    }
+
+    fn fetch_template_registrations(
+        &self,
+        start_height: u64,
+        end_height: u64,
+    ) -> Result<Vec<TemplateRegistrationEntry>, ChainStorageError> {
+        dispatch!(self, db => db.fetch_template_registrations(start_height, end_height))
+    }
+
+    fn fetch_outputs_by_index(
+        &self,
+        index: &OutputIndex,
+        start_height: u64,
+        end_height: u64,
+    ) -> Result<Vec<OutputMinedInfo>, ChainStorageError> {
+        dispatch!(self, db => db.fetch_outputs_by_index(index, start_height, end_height))
+    }
+
+    fn fetch_output_smt_delta(&self, height: u64) -> Result<Option<OutputSmtDelta>, ChainStorageError> {
+        dispatch!(self, db => db.fetch_output_smt_delta(height))
+    }
+
+    fn calculate_tip_smt(&self) -> Result<OutputSmt, ChainStorageError> {
+        dispatch!(self, db => db.calculate_tip_smt())
+    }
+}
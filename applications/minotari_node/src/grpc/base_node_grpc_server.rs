@@ -49,10 +49,10 @@ use tari_core::{
         StateMachineHandle,
     },
     blocks::{Block, BlockHeader, NewBlockTemplate},
-    chain_storage::ChainStorageError,
+    chain_storage::{ChainStorageError, OutputIndex},
     consensus::{emission::Emission, ConsensusManager, NetworkConsensus},
     iterators::NonOverlappingIntegerPairIter,
-    mempool::{service::LocalMempoolService, TxStorageResponse},
+    mempool::{service::LocalMempoolService, TxStorageResponse, DEFAULT_FEE_ESTIMATE_TARGETS, MAX_FEE_ESTIMATE_TARGET},
     proof_of_work::PowAlgorithm,
     transactions::{
         generate_coinbase_with_wallet_output,
@@ -61,7 +61,9 @@ use tari_core::{
             encrypted_data::PaymentId,
             CoinBaseExtra,
             KernelBuilder,
+            OutputType,
             RangeProofType,
+            SideChainFeatureType,
             Transaction,
             TransactionKernel,
             TransactionKernelVersion,
@@ -78,6 +80,7 @@ use crate::{
     builder::BaseNodeContext,
     grpc::{
         blocks::{block_fees, block_heights, block_size, GET_BLOCKS_MAX_HEIGHTS, GET_BLOCKS_PAGE_SIZE},
+        chain_events::{ChainEventStreamer, ResumeFrom},
         hash_rate::HashRateMovingAverage,
         helpers::{mean, median},
     },
@@ -103,6 +106,8 @@ const LIST_HEADERS_PAGE_SIZE: usize = 10;
 const LIST_HEADERS_DEFAULT_NUM_HEADERS: u64 = 10;
 
 const BLOCK_TIMING_MAX_BLOCKS: u64 = 10_000;
+// The number of block heights to query the output indexes for at a time. The results are then streamed to the client.
+const GET_OUTPUTS_BY_INDEX_PAGE_SIZE: usize = 1_000;
 
 pub struct BaseNodeGrpcServer {
     node_service: LocalNodeCommsInterface,
@@ -183,6 +188,90 @@ impl BaseNodeGrpcServer {
         }
         Ok(())
     }
+
+    /// Streams all outputs matching `index` that were mined between `start_height` and `end_height` (inclusive). An
+    /// `end_height` of zero streams up to the current tip.
+    async fn stream_outputs_by_index(
+        &self,
+        index: OutputIndex,
+        start_height: u64,
+        end_height: u64,
+    ) -> Result<mpsc::Receiver<Result<tari_rpc::IndexedOutputResponse, Status>>, Status> {
+        let report_error_flag = self.report_error_flag();
+        let mut node_service = self.node_service.clone();
+
+        let end_height = if end_height == 0 {
+            node_service
+                .get_metadata()
+                .await
+                .map_err(|err| obscure_error_if_true(report_error_flag, Status::internal(err.to_string())))?
+                .best_block_height()
+        } else {
+            end_height
+        };
+        if start_height > end_height {
+            return Err(obscure_error_if_true(
+                report_error_flag,
+                Status::invalid_argument("Start height is greater than end height"),
+            ));
+        }
+
+        let page_iter = NonOverlappingIntegerPairIter::new(
+            start_height,
+            end_height.saturating_add(1),
+            GET_OUTPUTS_BY_INDEX_PAGE_SIZE,
+        )
+        .map_err(|e| obscure_error_if_true(report_error_flag, Status::invalid_argument(e)))?;
+
+        let (mut tx, rx) = mpsc::channel(10);
+        task::spawn(async move {
+            for (start, end) in page_iter {
+                let outputs = match node_service.fetch_outputs_by_index(index.clone(), start, end).await {
+                    Ok(outputs) => outputs,
+                    Err(err) => {
+                        warn!(target: LOG_TARGET, "Base node service error: {}", err);
+                        let _ignore = tx
+                            .send(Err(obscure_error_if_true(
+                                report_error_flag,
+                                Status::internal(err.to_string()),
+                            )))
+                            .await;
+                        return;
+                    },
+                };
+
+                for info in outputs {
+                    let output = match tari_rpc::TransactionOutput::try_from(info.output) {
+                        Ok(output) => output,
+                        Err(err) => {
+                            let _ignore = tx
+                                .send(Err(obscure_error_if_true(
+                                    report_error_flag,
+                                    Status::internal(format!("Error converting output: {}", err)),
+                                )))
+                                .await;
+                            return;
+                        },
+                    };
+                    let resp = tari_rpc::IndexedOutputResponse {
+                        output: Some(output),
+                        mined_height: info.mined_height,
+                        header_hash: info.header_hash.to_vec(),
+                        mined_timestamp: info.mined_timestamp,
+                    };
+                    if tx.send(Ok(resp)).await.is_err() {
+                        trace!(
+                            target: LOG_TARGET,
+                            "[stream_outputs_by_index] Client has disconnected before stream completed"
+                        );
+                        return;
+                    }
+                }
+            }
+        });
+
+        Ok(rx)
+    }
 }
 
 pub fn obscure_error_if_true(report: bool, status: Status) -> Status {
@@ -209,6 +298,10 @@ impl tari_rpc::base_node_server::BaseNode for BaseNodeGrpcServer {
     type GetBlocksStream = mpsc::Receiver<Result<tari_rpc::HistoricalBlock, Status>>;
     type GetMempoolTransactionsStream = mpsc::Receiver<Result<tari_rpc::GetMempoolTransactionsResponse, Status>>;
     type GetNetworkDifficultyStream = mpsc::Receiver<Result<tari_rpc::NetworkDifficultyResponse, Status>>;
+    type GetOutputsByOutputTypeStream = mpsc::Receiver<Result<tari_rpc::IndexedOutputResponse, Status>>;
+    type GetOutputsByScriptHashStream = mpsc::Receiver<Result<tari_rpc::IndexedOutputResponse, Status>>;
+    type GetOutputsBySenderOffsetPublicKeyStream = mpsc::Receiver<Result<tari_rpc::IndexedOutputResponse, Status>>;
+    type GetOutputsBySideChainFeatureStream = mpsc::Receiver<Result<tari_rpc::IndexedOutputResponse, Status>>;
     type GetPeersStream = mpsc::Receiver<Result<tari_rpc::GetPeersResponse, Status>>;
     type GetSideChainUtxosStream = mpsc::Receiver<Result<tari_rpc::GetSideChainUtxosResponse, Status>>;
     type GetTemplateRegistrationsStream = mpsc::Receiver<Result<tari_rpc::GetTemplateRegistrationResponse, Status>>;
@@ -216,6 +309,7 @@ impl tari_rpc::base_node_server::BaseNode for BaseNodeGrpcServer {
     type ListHeadersStream = mpsc::Receiver<Result<tari_rpc::BlockHeaderResponse, Status>>;
     type SearchKernelsStream = mpsc::Receiver<Result<tari_rpc::HistoricalBlock, Status>>;
     type SearchUtxosStream = mpsc::Receiver<Result<tari_rpc::HistoricalBlock, Status>>;
+    type SubscribeChainEventsStream = mpsc::Receiver<Result<tari_rpc::ChainEvent, Status>>;
 
     #[allow(clippy::too_many_lines)]
     async fn get_network_difficulty(
@@ -1430,6 +1524,8 @@ impl tari_rpc::base_node_server::BaseNode for BaseNodeGrpcServer {
             TxStorageResponse::NotStoredOrphan |
             TxStorageResponse::NotStoredConsensus |
             TxStorageResponse::NotStoredFeeTooLow |
+            TxStorageResponse::NotStoredReplacementRejected |
+            TxStorageResponse::NotStoredPackageTooLarge |
             TxStorageResponse::NotStoredTimeLocked => tari_rpc::SubmitTransactionResponse {
                 result: tari_rpc::SubmitTransactionResult::Rejected.into(),
             },
@@ -1513,6 +1609,8 @@ impl tari_rpc::base_node_server::BaseNode for BaseNodeGrpcServer {
             TxStorageResponse::NotStoredConsensus |
             TxStorageResponse::NotStoredOrphan |
             TxStorageResponse::NotStoredFeeTooLow |
+            TxStorageResponse::NotStoredReplacementRejected |
+            TxStorageResponse::NotStoredPackageTooLarge |
             TxStorageResponse::NotStoredTimeLocked |
             TxStorageResponse::NotStoredAlreadyMined => tari_rpc::TransactionStateResponse {
                 result: tari_rpc::TransactionLocation::NotStored.into(),
@@ -2535,6 +2633,276 @@ impl tari_rpc::base_node_server::BaseNode for BaseNodeGrpcServer {
         );
         Ok(Response::new(rx))
     }
+
+    async fn get_outputs_by_script_hash(
+        &self,
+        request: Request<tari_rpc::GetOutputsByScriptHashRequest>,
+    ) -> Result<Response<Self::GetOutputsByScriptHashStream>, Status> {
+        self.check_method_enabled(GrpcMethod::GetOutputsByScriptHash)?;
+        let request = request.into_inner();
+        trace!(target: LOG_TARGET, "Incoming GRPC request for GetOutputsByScriptHash");
+        let script_hash = FixedHash::try_from(request.script_hash).map_err(|e| {
+            obscure_error_if_true(
+                self.report_error_flag(),
+                Status::invalid_argument(format!("Invalid script_hash '{}'", e)),
+            )
+        })?;
+
+        let rx = self
+            .stream_outputs_by_index(
+                OutputIndex::ScriptHash(script_hash),
+                request.start_height,
+                request.end_height,
+            )
+            .await?;
+        Ok(Response::new(rx))
+    }
+
+    async fn get_outputs_by_output_type(
+        &self,
+        request: Request<tari_rpc::GetOutputsByOutputTypeRequest>,
+    ) -> Result<Response<Self::GetOutputsByOutputTypeStream>, Status> {
+        self.check_method_enabled(GrpcMethod::GetOutputsByOutputType)?;
+        let request = request.into_inner();
+        trace!(target: LOG_TARGET, "Incoming GRPC request for GetOutputsByOutputType");
+        let output_type = u8::try_from(request.output_type)
+            .ok()
+            .and_then(OutputType::from_byte)
+            .ok_or_else(|| {
+                obscure_error_if_true(
+                    self.report_error_flag(),
+                    Status::invalid_argument(format!("Invalid output_type '{}'", request.output_type)),
+                )
+            })?;
+
+        let rx = self
+            .stream_outputs_by_index(
+                OutputIndex::OutputType(output_type),
+                request.start_height,
+                request.end_height,
+            )
+            .await?;
+        Ok(Response::new(rx))
+    }
+
+    async fn get_outputs_by_side_chain_feature(
+        &self,
+        request: Request<tari_rpc::GetOutputsBySideChainFeatureRequest>,
+    ) -> Result<Response<Self::GetOutputsBySideChainFeatureStream>, Status> {
+        self.check_method_enabled(GrpcMethod::GetOutputsBySideChainFeature)?;
+        let request = request.into_inner();
+        trace!(target: LOG_TARGET, "Incoming GRPC request for GetOutputsBySideChainFeature");
+        let feature_type = u8::try_from(request.feature_type)
+            .ok()
+            .and_then(SideChainFeatureType::from_byte)
+            .ok_or_else(|| {
+                obscure_error_if_true(
+                    self.report_error_flag(),
+                    Status::invalid_argument(format!("Invalid feature_type '{}'", request.feature_type)),
+                )
+            })?;
+
+        let rx = self
+            .stream_outputs_by_index(
+                OutputIndex::SideChainFeature(feature_type),
+                request.start_height,
+                request.end_height,
+            )
+            .await?;
+        Ok(Response::new(rx))
+    }
+
+    async fn get_outputs_by_sender_offset_public_key(
+        &self,
+        request: Request<tari_rpc::GetOutputsBySenderOffsetPublicKeyRequest>,
+    ) -> Result<Response<Self::GetOutputsBySenderOffsetPublicKeyStream>, Status> {
+        self.check_method_enabled(GrpcMethod::GetOutputsBySenderOffsetPublicKey)?;
+        let request = request.into_inner();
+        trace!(
+            target: LOG_TARGET,
+            "Incoming GRPC request for GetOutputsBySenderOffsetPublicKey"
+        );
+        let sender_offset_public_key =
+            PublicKey::from_canonical_bytes(&request.sender_offset_public_key).map_err(|e| {
+                obscure_error_if_true(
+                    self.report_error_flag(),
+                    Status::invalid_argument(format!("Invalid sender_offset_public_key '{}'", e)),
+                )
+            })?;
+
+        let rx = self
+            .stream_outputs_by_index(
+                OutputIndex::SenderOffsetPublicKey(sender_offset_public_key),
+                request.start_height,
+                request.end_height,
+            )
+            .await?;
+        Ok(Response::new(rx))
+    }
+
+    async fn subscribe_chain_events(
+        &self,
+        request: Request<tari_rpc::SubscribeChainEventsRequest>,
+    ) -> Result<Response<Self::SubscribeChainEventsStream>, Status> {
+        self.check_method_enabled(GrpcMethod::SubscribeChainEvents)?;
+        let request = request.into_inner();
+        trace!(
+            target: LOG_TARGET,
+            "Incoming GRPC request for SubscribeChainEvents: resume: {} resume_from_height: {} include_orphans: {}",
+            request.resume,
+            request.resume_from_height,
+            request.include_orphans
+        );
+        let last_seen_block_hash = if request.last_seen_block_hash.is_empty() {
+            None
+        } else {
+            Some(FixedHash::try_from(request.last_seen_block_hash).map_err(|e| {
+                obscure_error_if_true(
+                    self.report_error_flag(),
+                    Status::invalid_argument(format!("Invalid last_seen_block_hash '{}'", e)),
+                )
+            })?)
+        };
+        let resume = request.resume.then_some(ResumeFrom {
+            height: request.resume_from_height,
+            last_seen_block_hash,
+        });
+
+        let (tx, rx) = mpsc::channel(100);
+        let streamer = ChainEventStreamer::new(
+            self.node_service.clone(),
+            tx,
+            request.include_orphans,
+            self.report_error_flag(),
+        );
+        task::spawn(streamer.run(resume));
+        Ok(Response::new(rx))
+    }
+
+    async fn get_output_at_height(
+        &self,
+        request: Request<tari_rpc::GetOutputAtHeightRequest>,
+    ) -> Result<Response<tari_rpc::GetOutputAtHeightResponse>, Status> {
+        self.check_method_enabled(GrpcMethod::GetOutputAtHeight)?;
+        let report_error_flag = self.report_error_flag();
+        let request = request.into_inner();
+        trace!(
+            target: LOG_TARGET,
+            "Incoming GRPC request for GetOutputAtHeight at height {}",
+            request.height
+        );
+        let commitment = Commitment::from_canonical_bytes(&request.commitment)
+            .map_err(|_| obscure_error_if_true(report_error_flag, Status::invalid_argument("Invalid commitment")))?;
+
+        let mut node_service = self.node_service.clone();
+        let state = node_service
+            .fetch_output_at_height(commitment, request.height)
+            .await
+            .map_err(|e| obscure_error_if_true(report_error_flag, Status::internal(e.to_string())))?;
+        let state = match state {
+            Some(state) => state,
+            None => return Ok(Response::new(tari_rpc::GetOutputAtHeightResponse::default())),
+        };
+        let output = tari_rpc::TransactionOutput::try_from(state.output.output).map_err(|e| {
+            obscure_error_if_true(
+                report_error_flag,
+                Status::internal(format!("Error converting output: {}", e)),
+            )
+        })?;
+        Ok(Response::new(tari_rpc::GetOutputAtHeightResponse {
+            found: true,
+            output: Some(tari_rpc::IndexedOutputResponse {
+                output: Some(output),
+                mined_height: state.output.mined_height,
+                header_hash: state.output.header_hash.to_vec(),
+                mined_timestamp: state.output.mined_timestamp,
+            }),
+            spent: state.spent.is_some(),
+            spent_height: state.spent.as_ref().map(|i| i.spent_height).unwrap_or_default(),
+            spent_header_hash: state.spent.map(|i| i.header_hash.to_vec()).unwrap_or_default(),
+        }))
+    }
+
+    async fn get_output_smt_root_at_height(
+        &self,
+        request: Request<tari_rpc::GetOutputSmtRootAtHeightRequest>,
+    ) -> Result<Response<tari_rpc::GetOutputSmtRootAtHeightResponse>, Status> {
+        self.check_method_enabled(GrpcMethod::GetOutputSmtRootAtHeight)?;
+        let report_error_flag = self.report_error_flag();
+        let request = request.into_inner();
+        trace!(
+            target: LOG_TARGET,
+            "Incoming GRPC request for GetOutputSmtRootAtHeight at height {}",
+            request.height
+        );
+        let mut node_service = self.node_service.clone();
+        let (root, size) = node_service
+            .fetch_output_smt_root_at_height(request.height)
+            .await
+            .map_err(|e| obscure_error_if_true(report_error_flag, Status::internal(e.to_string())))?;
+        Ok(Response::new(tari_rpc::GetOutputSmtRootAtHeightResponse {
+            height: request.height,
+            output_smt_root: root.to_vec(),
+            output_smt_size: size,
+        }))
+    }
+
+    async fn estimate_fee(
+        &self,
+        request: Request<tari_rpc::EstimateFeeRequest>,
+    ) -> Result<Response<tari_rpc::EstimateFeeResponse>, Status> {
+        self.check_method_enabled(GrpcMethod::EstimateFee)?;
+        let report_error_flag = self.report_error_flag();
+        let request = request.into_inner();
+        trace!(
+            target: LOG_TARGET,
+            "Incoming GRPC request for EstimateFee: {:?}",
+            request.target_blocks
+        );
+        let target_blocks = if request.target_blocks.is_empty() {
+            DEFAULT_FEE_ESTIMATE_TARGETS.to_vec()
+        } else {
+            request.target_blocks
+        };
+        if target_blocks.len() as u64 > MAX_FEE_ESTIMATE_TARGET ||
+            target_blocks
+                .iter()
+                .any(|target| *target == 0 || *target > MAX_FEE_ESTIMATE_TARGET)
+        {
+            return Err(Status::invalid_argument(format!(
+                "At most {} targets between 1 and {} blocks may be requested",
+                MAX_FEE_ESTIMATE_TARGET, MAX_FEE_ESTIMATE_TARGET
+            )));
+        }
+
+        let mut node_service = self.node_service.clone();
+        let tip_height = node_service
+            .get_metadata()
+            .await
+            .map_err(|e| obscure_error_if_true(report_error_flag, Status::internal(e.to_string())))?
+            .best_block_height();
+        let mut mempool_handle = self.mempool_service.clone();
+        let estimates = mempool_handle
+            .estimate_fee(target_blocks, tip_height)
+            .await
+            .map_err(|e| {
+                error!(target: LOG_TARGET, "Error estimating fee: {}", e);
+                obscure_error_if_true(report_error_flag, Status::internal(e.to_string()))
+            })?;
+
+        Ok(Response::new(tari_rpc::EstimateFeeResponse {
+            estimates: estimates
+                .into_iter()
+                .map(|estimate| tari_rpc::FeeEstimate {
+                    target_blocks: estimate.target_blocks,
+                    fee_per_gram: estimate.fee_per_gram.as_u64(),
+                    mempool_fee_per_gram: estimate.mempool_fee_per_gram.as_u64(),
+                    history_fee_per_gram: estimate.history_fee_per_gram.map(|f| f.as_u64()).unwrap_or_default(),
+                    num_samples: estimate.num_samples,
+                })
+                .collect(),
+        }))
+    }
 }
 
 enum BlockGroupType {
@@ -0,0 +1,486 @@
+//  Copyright 2024, The Tari Project
+//
+//  Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+//  following conditions are met:
+//
+//  1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+//  disclaimer.
+//
+//  2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+//  following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+//  3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+//  products derived from this software without specific prior written permission.
+//
+//  THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+//  INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+//  DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+//  SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+//  SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+//  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+//  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::{cmp, sync::Arc};
+
+use futures::{channel::mpsc, SinkExt};
+use log::*;
+use minotari_app_grpc::tari_rpc;
+use tari_common_types::types::FixedHash;
+use tari_core::{
+    base_node::{
+        comms_interface::{BlockEvent, BlockEventReceiver},
+        LocalNodeCommsInterface,
+    },
+    blocks::{BlockHeader, ChainBlock},
+    chain_storage::BlockAddResult,
+};
+use tari_utilities::hex::Hex;
+use tokio::sync::broadcast::error::RecvError;
+use tonic::Status;
+
+use crate::grpc::base_node_grpc_server::obscure_error_if_true;
+
+const LOG_TARGET: &str = "minotari::base_node::grpc::chain_events";
+// The number of headers to request via the local interface at a time when replaying BlockAdded events
+const REPLAY_PAGE_SIZE: u64 = 100;
+// The maximum number of blocks to walk back from the client's last seen block to find where it forked from the main
+// chain
+const MAX_RESUME_REORG_DEPTH: usize = 1000;
+
+pub type ChainEventSender = mpsc::Sender<Result<tari_rpc::ChainEvent, Status>>;
+
+/// Where a resumed subscription continues from
+#[derive(Debug, Clone, Copy)]
+pub struct ResumeFrom {
+    /// The height of the first block the client has not seen
+    pub height: u64,
+    /// The hash of the last block the client saw, at `height - 1`
+    pub last_seen_block_hash: Option<FixedHash>,
+}
+
+/// Forwards block events from the local node to a `SubscribeChainEvents` client until the client disconnects
+pub struct ChainEventStreamer {
+    node_service: LocalNodeCommsInterface,
+    block_events: BlockEventReceiver,
+    tx: ChainEventSender,
+    include_orphans: bool,
+    report_error_flag: bool,
+    /// The height of the last block sent in a BlockAdded or Reorg event. Used to avoid sending a block twice when
+    /// replayed blocks overlap with live events.
+    last_sent_height: Option<u64>,
+}
+
+impl ChainEventStreamer {
+    pub fn new(
+        node_service: LocalNodeCommsInterface,
+        tx: ChainEventSender,
+        include_orphans: bool,
+        report_error_flag: bool,
+    ) -> Self {
+        // Subscribe before replaying any blocks, so that no events are missed between the replay and the live stream
+        let block_events = node_service.get_block_event_stream();
+        Self {
+            node_service,
+            block_events,
+            tx,
+            include_orphans,
+            report_error_flag,
+            last_sent_height: None,
+        }
+    }
+
+    /// Streams events until the client disconnects. If `resume` is set, BlockAdded events are first sent for every
+    /// block from the resume height up to the current tip, preceded by a Reorg event if the client's last seen block
+    /// is no longer on the main chain.
+    pub async fn run(mut self, resume: Option<ResumeFrom>) {
+        if let Some(resume) = resume {
+            if let Err(status) = self.resume(resume).await {
+                self.send_error(status).await;
+                return;
+            }
+        }
+
+        loop {
+            let event = match self.block_events.recv().await {
+                Ok(event) => event,
+                Err(RecvError::Lagged(n)) => {
+                    warn!(target: LOG_TARGET, "Chain event subscriber lagged by {} events", n);
+                    self.send_error(Status::data_loss(format!(
+                        "{} chain events were missed. Resubscribe with resume_from_height set to recover.",
+                        n
+                    )))
+                    .await;
+                    return;
+                },
+                Err(RecvError::Closed) => return,
+            };
+            if let Err(status) = self.handle_event(&event).await {
+                self.send_error(status).await;
+                return;
+            }
+        }
+    }
+
+    async fn handle_event(&mut self, event: &BlockEvent) -> Result<(), Status> {
+        match event {
+            BlockEvent::ValidBlockAdded(_, BlockAddResult::Ok(block)) => {
+                self.send_block_added(block.height(), block.header()).await
+            },
+            BlockEvent::ValidBlockAdded(_, BlockAddResult::ChainReorg { added, removed }) => {
+                self.send_reorg(added, removed).await
+            },
+            BlockEvent::ValidBlockAdded(block, BlockAddResult::OrphanBlock) if self.include_orphans => {
+                self.send(tari_rpc::chain_event::Event::OrphanAdded(tari_rpc::OrphanAddedEvent {
+                    height: block.header.height,
+                    block_hash: block.hash().to_vec(),
+                    prev_hash: block.header.prev_hash.to_vec(),
+                }))
+                .await
+            },
+            BlockEvent::BlockSyncRewind(removed) => self.send_reorg(&[], removed).await,
+            // Blocks added during block sync are not published individually
+            BlockEvent::BlockSyncComplete(_, starting_height) => self.replay_from(*starting_height).await,
+            _ => Ok(()),
+        }
+    }
+
+    async fn resume(&mut self, resume: ResumeFrom) -> Result<(), Status> {
+        self.last_sent_height = resume.height.checked_sub(1);
+        let mut start_height = resume.height;
+        if let (Some(last_seen_height), Some(last_seen_hash)) = (self.last_sent_height, resume.last_seen_block_hash) {
+            start_height = self.send_reorg_since(last_seen_height, last_seen_hash).await? + 1;
+        }
+        self.replay_from(start_height).await
+    }
+
+    /// Sends a Reorg event if the client's last seen block is no longer on the main chain, walking back through the
+    /// orphan pool to find where the client's chain forked. Returns the height of the last block the client has seen
+    /// on the main chain.
+    async fn send_reorg_since(&mut self, last_seen_height: u64, last_seen_hash: FixedHash) -> Result<u64, Status> {
+        let header = self
+            .node_service
+            .get_header(last_seen_height)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+        if header.map_or(false, |h| *h.hash() == last_seen_hash) {
+            return Ok(last_seen_height);
+        }
+
+        let mut removed_block_hashes = Vec::new();
+        let mut hash = last_seen_hash;
+        let fork_header = loop {
+            let main_chain_header = self
+                .node_service
+                .get_header_by_hash(hash)
+                .await
+                .map_err(|e| Status::internal(e.to_string()))?;
+            if let Some(header) = main_chain_header {
+                break header;
+            }
+            if removed_block_hashes.len() >= MAX_RESUME_REORG_DEPTH {
+                return Err(Status::failed_precondition(format!(
+                    "The last seen block {} forked more than {} blocks ago. Resubscribe from an earlier height.",
+                    last_seen_hash.to_hex(),
+                    MAX_RESUME_REORG_DEPTH
+                )));
+            }
+            let block = self
+                .node_service
+                .get_block_from_all_chains(hash)
+                .await
+                .map_err(|e| Status::internal(e.to_string()))?
+                .ok_or_else(|| {
+                    Status::failed_precondition(format!(
+                        "The last seen block {} is not known to this node. Resubscribe from an earlier height.",
+                        last_seen_hash.to_hex()
+                    ))
+                })?;
+            removed_block_hashes.push(hash.to_vec());
+            hash = block.header.prev_hash;
+        };
+
+        let fork_height = fork_header.height();
+        if !removed_block_hashes.is_empty() {
+            self.send(tari_rpc::chain_event::Event::Reorg(tari_rpc::ReorgEvent {
+                fork_height,
+                removed_block_hashes,
+                added_block_hashes: Vec::new(),
+                tip_height: fork_height,
+                tip_hash: fork_header.hash().to_vec(),
+            }))
+            .await?;
+        }
+        self.last_sent_height = Some(fork_height);
+        Ok(fork_height)
+    }
+
+    /// Sends a BlockAdded event for each block from `start_height` to the current tip that has not been sent yet
+    async fn replay_from(&mut self, start_height: u64) -> Result<(), Status> {
+        let mut height = match self.last_sent_height {
+            Some(last) => cmp::max(start_height, last + 1),
+            None => start_height,
+        };
+        let tip_height = self
+            .node_service
+            .get_metadata()
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?
+            .best_block_height();
+        while height <= tip_height {
+            let end = cmp::min(height + REPLAY_PAGE_SIZE - 1, tip_height);
+            let headers = self
+                .node_service
+                .get_headers(height..=end)
+                .await
+                .map_err(|e| Status::internal(e.to_string()))?;
+            for header in headers {
+                self.send_block_added(header.height(), header.header()).await?;
+            }
+            height = end + 1;
+        }
+        Ok(())
+    }
+
+    async fn send_block_added(&mut self, height: u64, header: &BlockHeader) -> Result<(), Status> {
+        if self.last_sent_height.map_or(false, |last| height <= last) {
+            return Ok(());
+        }
+        self.send(tari_rpc::chain_event::Event::BlockAdded(tari_rpc::BlockAddedEvent {
+            height,
+            block_hash: header.hash().to_vec(),
+            prev_hash: header.prev_hash.to_vec(),
+            timestamp: header.timestamp.as_u64(),
+        }))
+        .await?;
+        self.last_sent_height = Some(height);
+        Ok(())
+    }
+
+    /// Sends a Reorg event. `added` is ordered from lowest to highest height and `removed` from highest to lowest.
+    async fn send_reorg(&mut self, added: &[Arc<ChainBlock>], removed: &[Arc<ChainBlock>]) -> Result<(), Status> {
+        let fork_block = match (added.first(), removed.last()) {
+            (Some(block), _) | (None, Some(block)) => block,
+            (None, None) => return Ok(()),
+        };
+        let fork_height = fork_block.height().saturating_sub(1);
+        let (tip_height, tip_hash) = match added.last() {
+            Some(tip) => (tip.height(), tip.hash().to_vec()),
+            None => (fork_height, fork_block.header().prev_hash.to_vec()),
+        };
+        self.send(tari_rpc::chain_event::Event::Reorg(tari_rpc::ReorgEvent {
+            fork_height,
+            removed_block_hashes: removed.iter().map(|b| b.hash().to_vec()).collect(),
+            added_block_hashes: added.iter().map(|b| b.hash().to_vec()).collect(),
+            tip_height,
+            tip_hash,
+        }))
+        .await?;
+        self.last_sent_height = Some(tip_height);
+        Ok(())
+    }
+
+    async fn send(&mut self, event: tari_rpc::chain_event::Event) -> Result<(), Status> {
+        self.tx
+            .send(Ok(tari_rpc::ChainEvent { event: Some(event) }))
+            .await
+            .map_err(|_| Status::cancelled("Client disconnected"))
+    }
+
+    async fn send_error(&mut self, status: Status) {
+        if status.code() == tonic::Code::Cancelled {
+            debug!(target: LOG_TARGET, "Chain event subscriber disconnected");
+            return;
+        }
+        let _ignore = self
+            .tx
+            .send(Err(obscure_error_if_true(self.report_error_flag, status)))
+            .await;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use futures::StreamExt;
+    use tari_common_types::chain_metadata::ChainMetadata;
+    use tari_core::{
+        base_node::comms_interface::{NodeCommsRequest, NodeCommsResponse},
+        blocks::{Block, BlockHeaderAccumulatedData, ChainHeader},
+        transactions::aggregated_body::AggregateBody,
+    };
+    use tari_service_framework::reply_channel;
+    use tokio::{sync::broadcast, task};
+
+    use super::*;
+
+    fn create_header(prev: Option<&ChainHeader>, nonce: u64) -> ChainHeader {
+        let mut header = BlockHeader::new(0);
+        if let Some(prev) = prev {
+            header.height = prev.height() + 1;
+            header.prev_hash = *prev.hash();
+        }
+        header.nonce = nonce;
+        let accumulated_data = BlockHeaderAccumulatedData {
+            hash: header.hash(),
+            ..Default::default()
+        };
+        ChainHeader::try_construct(header, accumulated_data).unwrap()
+    }
+
+    /// Creates `len` headers on top of `prev`, using `nonce` to tell competing chains apart
+    fn create_chain(prev: Option<&ChainHeader>, len: usize, nonce: u64) -> Vec<ChainHeader> {
+        let mut chain: Vec<ChainHeader> = Vec::with_capacity(len);
+        for _ in 0..len {
+            let header = create_header(chain.last().or(prev), nonce);
+            chain.push(header);
+        }
+        chain
+    }
+
+    fn to_block(header: &ChainHeader) -> Block {
+        Block::new(header.header().clone(), AggregateBody::empty())
+    }
+
+    /// Serves the requests made by the streamer from a main chain and a set of orphan blocks
+    fn create_node(
+        main_chain: Vec<ChainHeader>,
+        orphans: Vec<Block>,
+    ) -> (LocalNodeCommsInterface, broadcast::Sender<Arc<BlockEvent>>) {
+        let (request_tx, mut request_rx) = reply_channel::unbounded();
+        let (block_tx, _) = reply_channel::unbounded();
+        let (block_event_tx, _) = broadcast::channel(10);
+        task::spawn(async move {
+            while let Some(request) = request_rx.next().await {
+                let (request, reply_tx) = request.split();
+                let response = match request {
+                    NodeCommsRequest::GetChainMetadata => {
+                        let tip = main_chain.last().unwrap();
+                        NodeCommsResponse::ChainMetadata(
+                            ChainMetadata::new(tip.height(), *tip.hash(), 0, 0, Default::default(), 0).unwrap(),
+                        )
+                    },
+                    NodeCommsRequest::FetchHeaders(range) => NodeCommsResponse::BlockHeaders(
+                        main_chain
+                            .iter()
+                            .filter(|h| range.contains(&h.height()))
+                            .cloned()
+                            .collect(),
+                    ),
+                    NodeCommsRequest::GetHeaderByHash(hash) => {
+                        NodeCommsResponse::BlockHeader(main_chain.iter().find(|h| *h.hash() == hash).cloned())
+                    },
+                    NodeCommsRequest::GetBlockFromAllChains(hash) => {
+                        NodeCommsResponse::Block(Box::new(orphans.iter().find(|b| b.hash() == hash).cloned()))
+                    },
+                    request => panic!("Unexpected request {}", request),
+                };
+                let _ignore = reply_tx.send(Ok(response));
+            }
+        });
+        (
+            LocalNodeCommsInterface::new(request_tx, block_tx, block_event_tx.clone()),
+            block_event_tx,
+        )
+    }
+
+    async fn next_event(rx: &mut mpsc::Receiver<Result<tari_rpc::ChainEvent, Status>>) -> tari_rpc::chain_event::Event {
+        rx.next().await.unwrap().unwrap().event.unwrap()
+    }
+
+    fn block_added_height(event: tari_rpc::chain_event::Event) -> u64 {
+        match event {
+            tari_rpc::chain_event::Event::BlockAdded(event) => event.height,
+            event => panic!("Expected a BlockAdded event, got {:?}", event),
+        }
+    }
+
+    #[tokio::test]
+    async fn it_replays_blocks_from_the_resume_height() {
+        let main_chain = create_chain(None, 6, 0);
+        let (node_service, _block_event_tx) = create_node(main_chain.clone(), vec![]);
+        let (tx, mut rx) = mpsc::channel(10);
+        let streamer = ChainEventStreamer::new(node_service, tx, false, true);
+        task::spawn(streamer.run(Some(ResumeFrom {
+            height: 3,
+            last_seen_block_hash: Some(*main_chain[2].hash()),
+        })));
+
+        for height in 3..=5 {
+            assert_eq!(block_added_height(next_event(&mut rx).await), height);
+        }
+    }
+
+    #[tokio::test]
+    async fn it_sends_a_reorg_if_the_last_seen_block_was_reorged_out() {
+        let main_chain = create_chain(None, 6, 0);
+        // The client saw blocks 3 and 4 of a chain that forked off after block 2
+        let old_chain = create_chain(Some(&main_chain[2]), 2, 1);
+        let orphans = old_chain.iter().map(to_block).collect();
+        let (node_service, _block_event_tx) = create_node(main_chain.clone(), orphans);
+        let (tx, mut rx) = mpsc::channel(10);
+        let streamer = ChainEventStreamer::new(node_service, tx, false, true);
+        task::spawn(streamer.run(Some(ResumeFrom {
+            height: 5,
+            last_seen_block_hash: Some(*old_chain[1].hash()),
+        })));
+
+        match next_event(&mut rx).await {
+            tari_rpc::chain_event::Event::Reorg(reorg) => {
+                assert_eq!(reorg.fork_height, 2);
+                assert_eq!(reorg.removed_block_hashes, vec![
+                    old_chain[1].hash().to_vec(),
+                    old_chain[0].hash().to_vec()
+                ]);
+                assert!(reorg.added_block_hashes.is_empty());
+                assert_eq!(reorg.tip_height, 2);
+                assert_eq!(reorg.tip_hash, main_chain[2].hash().to_vec());
+            },
+            event => panic!("Expected a Reorg event, got {:?}", event),
+        }
+        for height in 3..=5 {
+            assert_eq!(block_added_height(next_event(&mut rx).await), height);
+        }
+    }
+
+    #[tokio::test]
+    async fn it_rejects_an_unknown_last_seen_block() {
+        let main_chain = create_chain(None, 6, 0);
+        let unknown_chain = create_chain(Some(&main_chain[2]), 2, 1);
+        let (node_service, _block_event_tx) = create_node(main_chain, vec![]);
+        let (tx, mut rx) = mpsc::channel(10);
+        let streamer = ChainEventStreamer::new(node_service, tx, false, false);
+        task::spawn(streamer.run(Some(ResumeFrom {
+            height: 5,
+            last_seen_block_hash: Some(*unknown_chain[1].hash()),
+        })));
+
+        let status = rx.next().await.unwrap().unwrap_err();
+        assert_eq!(status.code(), tonic::Code::FailedPrecondition);
+    }
+
+    #[tokio::test]
+    async fn it_does_not_resend_replayed_blocks() {
+        let main_chain = create_chain(None, 7, 0);
+        let (node_service, block_event_tx) = create_node(main_chain[..6].to_vec(), vec![]);
+        let (tx, mut rx) = mpsc::channel(10);
+        let streamer = ChainEventStreamer::new(node_service, tx, false, true);
+        // Both blocks were added after the streamer subscribed, but block 5 is also replayed
+        for header in &main_chain[5..] {
+            let block = Arc::new(to_block(header));
+            let chain_block = ChainBlock::try_construct(block.clone(), header.accumulated_data().clone()).unwrap();
+            block_event_tx
+                .send(Arc::new(BlockEvent::ValidBlockAdded(
+                    block,
+                    BlockAddResult::Ok(Arc::new(chain_block)),
+                )))
+                .unwrap();
+        }
+        task::spawn(streamer.run(Some(ResumeFrom {
+            height: 4,
+            last_seen_block_hash: None,
+        })));
+
+        for height in 4..=6 {
+            assert_eq!(block_added_height(next_event(&mut rx).await), height);
+        }
+    }
+}
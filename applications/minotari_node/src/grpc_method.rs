@@ -65,11 +65,19 @@ pub enum GrpcMethod {
     GetShardKey,
     GetTemplateRegistrations,
     GetSideChainUtxos,
+    GetOutputsByScriptHash,
+    GetOutputsByOutputType,
+    GetOutputsBySideChainFeature,
+    GetOutputsBySenderOffsetPublicKey,
+    SubscribeChainEvents,
+    GetOutputAtHeight,
+    GetOutputSmtRootAtHeight,
+    EstimateFee,
 }
 
 impl GrpcMethod {
     /// All the GRPC methods as a fixed array
-    pub const ALL_VARIANTS: [GrpcMethod; 36] = [
+    pub const ALL_VARIANTS: [GrpcMethod; 44] = [
         GrpcMethod::ListHeaders,
         GrpcMethod::GetHeaderByHash,
         GrpcMethod::GetBlocks,
@@ -106,11 +114,19 @@ impl GrpcMethod {
         GrpcMethod::GetShardKey,
         GrpcMethod::GetTemplateRegistrations,
         GrpcMethod::GetSideChainUtxos,
+        GrpcMethod::GetOutputsByScriptHash,
+        GrpcMethod::GetOutputsByOutputType,
+        GrpcMethod::GetOutputsBySideChainFeature,
+        GrpcMethod::GetOutputsBySenderOffsetPublicKey,
+        GrpcMethod::SubscribeChainEvents,
+        GrpcMethod::GetOutputAtHeight,
+        GrpcMethod::GetOutputSmtRootAtHeight,
+        GrpcMethod::EstimateFee,
     ];
 }
 
 impl IntoIterator for GrpcMethod {
-    type IntoIter = std::array::IntoIter<GrpcMethod, 36>;
+    type IntoIter = std::array::IntoIter<GrpcMethod, 43>;
     type Item = GrpcMethod;
 
     fn into_iter(self) -> Self::IntoIter {
@@ -161,6 +177,14 @@ impl FromStr for GrpcMethod {
             "get_shard_key" => Ok(GrpcMethod::GetShardKey),
             "get_template_registrations" => Ok(GrpcMethod::GetTemplateRegistrations),
             "get_side_chain_utxos" => Ok(GrpcMethod::GetSideChainUtxos),
+            "get_outputs_by_script_hash" => Ok(GrpcMethod::GetOutputsByScriptHash),
+            "get_outputs_by_output_type" => Ok(GrpcMethod::GetOutputsByOutputType),
+            "get_outputs_by_side_chain_feature" => Ok(GrpcMethod::GetOutputsBySideChainFeature),
+            "get_outputs_by_sender_offset_public_key" => Ok(GrpcMethod::GetOutputsBySenderOffsetPublicKey),
+            "subscribe_chain_events" => Ok(GrpcMethod::SubscribeChainEvents),
+            "get_output_at_height" => Ok(GrpcMethod::GetOutputAtHeight),
+            "get_output_smt_root_at_height" => Ok(GrpcMethod::GetOutputSmtRootAtHeight),
+            "estimate_fee" => Ok(GrpcMethod::EstimateFee),
             _ => Err(format!("'{}' not supported", s)),
         }
     }
@@ -256,6 +280,14 @@ mod tests {
                 GrpcMethod::GetShardKey => count += 1,
                 GrpcMethod::GetTemplateRegistrations => count += 1,
                 GrpcMethod::GetSideChainUtxos => count += 1,
+                GrpcMethod::GetOutputsByScriptHash => count += 1,
+                GrpcMethod::GetOutputsByOutputType => count += 1,
+                GrpcMethod::GetOutputsBySideChainFeature => count += 1,
+                GrpcMethod::GetOutputsBySenderOffsetPublicKey => count += 1,
+                GrpcMethod::SubscribeChainEvents => count += 1,
+                GrpcMethod::GetOutputAtHeight => count += 1,
+                GrpcMethod::GetOutputSmtRootAtHeight => count += 1,
+                GrpcMethod::EstimateFee => count += 1,
             }
         }
         assert_eq!(count, GrpcMethod::ALL_VARIANTS.len());
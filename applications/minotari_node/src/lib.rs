@@ -31,11 +31,15 @@ mod builder;
 pub mod cli;
 mod commands;
 pub mod config;
+mod database;
 mod grpc;
 mod grpc_method;
 #[cfg(feature = "metrics")]
 mod metrics;
+#[cfg(feature = "redb")]
+mod migrate;
 mod recovery;
+mod snapshot;
 mod utils;
 use std::{process, sync::Arc};
 
@@ -84,6 +88,9 @@ pub async fn run_base_node(
         },
         init: true,
         rebuild_db: false,
+        import_snapshot: None,
+        #[cfg(feature = "redb")]
+        migrate_to_redb: false,
         non_interactive_mode: true,
         watch: None,
         profile_with_tokio_console: false,
@@ -127,6 +134,23 @@ pub async fn run_base_node_with_cli(
         return Ok(());
     };
 
+    #[cfg(feature = "redb")]
+    if cli.migrate_to_redb {
+        info!(target: LOG_TARGET, "Migrating the chain database from LMDB to redb");
+        migrate::run_migrate_to_redb(&config.base_node)
+            .await
+            .map_err(|e| ExitError::new(ExitCode::DatabaseError, e))?;
+        return Ok(());
+    }
+
+    if let Some(path) = cli.import_snapshot {
+        info!(target: LOG_TARGET, "Importing chain snapshot from {}", path.display());
+        snapshot::run_import_snapshot(&config.base_node, path)
+            .await
+            .map_err(|e| ExitError::new(ExitCode::DatabaseError, e))?;
+        return Ok(());
+    }
+
     // Build, node, build!
     let ctx = builder::configure_and_initialize_node(config.clone(), node_identity, shutdown.to_signal()).await?;
 
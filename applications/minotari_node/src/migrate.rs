@@ -0,0 +1,69 @@
+//  Copyright 2024, The Tari Project
+//
+//  Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+//  following conditions are met:
+//
+//  1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+//  disclaimer.
+//
+//  2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+//  following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+//  3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+//  products derived from this software without specific prior written permission.
+//
+//  THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+//  INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+//  DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+//  SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+//  SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+//  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+//  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use anyhow::anyhow;
+use log::*;
+use tari_core::{
+    chain_storage::{create_lmdb_database, create_redb_database, migrate_lmdb_to_redb, redb_database_file},
+    consensus::ConsensusManager,
+};
+use tokio::task;
+
+use crate::BaseNodeConfig;
+
+const LOG_TARGET: &str = "base_node::app::migrate";
+
+/// Copies the LMDB chain database at `base_node.lmdb_path` into a new redb database at `base_node.redb_path`. The LMDB
+/// database is left untouched.
+pub async fn run_migrate_to_redb(node_config: &BaseNodeConfig) -> Result<(), anyhow::Error> {
+    if redb_database_file(&node_config.redb_path).exists() {
+        return Err(anyhow!(
+            "A redb database already exists at {}. Remove it before migrating.",
+            node_config.redb_path.display()
+        ));
+    }
+    println!(
+        "Migrating chain database from {} to {}",
+        node_config.lmdb_path.display(),
+        node_config.redb_path.display()
+    );
+    let rules = ConsensusManager::builder(node_config.network).build().map_err(|e| {
+        error!(target: LOG_TARGET, "Error configuring consensus manager: {}", e);
+        anyhow!("Could not configure consensus manager: {}", e)
+    })?;
+    let lmdb = create_lmdb_database(&node_config.lmdb_path, node_config.lmdb.clone(), rules.clone()).map_err(|e| {
+        error!(target: LOG_TARGET, "Error opening db: {}", e);
+        anyhow!("Could not open LMDB database: {}", e)
+    })?;
+    let redb = create_redb_database(&node_config.redb_path, rules).map_err(|e| {
+        error!(target: LOG_TARGET, "Error creating redb db: {}", e);
+        anyhow!("Could not create redb database: {}", e)
+    })?;
+
+    let num_entries = task::spawn_blocking(move || migrate_lmdb_to_redb(&lmdb, &redb)).await??;
+    info!(target: LOG_TARGET, "Migrated {} entries to redb", num_entries);
+    println!(
+        "Migrated {} entries. Set `base_node.db_type = \"redb\"` in your config to use the new database.",
+        num_entries
+    );
+    Ok(())
+}
@@ -69,6 +69,13 @@ pub fn initiate_recover_db(config: &BaseNodeConfig) -> Result<(), ExitError> {
                 ExitError::new(ExitCode::UnknownError, err)
             })?;
         },
+        #[cfg(feature = "redb")]
+        DatabaseType::Redb => {
+            return Err(ExitError::new(
+                ExitCode::RecoveryError,
+                "Recovery mode is only supported for the LMDB database",
+            ));
+        },
     };
     Ok(())
 }
@@ -94,6 +101,8 @@ pub async fn run_recovery(node_config: &BaseNodeConfig) -> Result<(), anyhow::Er
             })?;
             (temp, backend, temp_path)
         },
+        #[cfg(feature = "redb")]
+        DatabaseType::Redb => return Err(anyhow!("Recovery mode is only supported for the LMDB database")),
     };
     let factories = CryptoFactories::default();
     let randomx_factory = RandomXFactory::new(node_config.max_randomx_vms);
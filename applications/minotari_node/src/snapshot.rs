@@ -0,0 +1,82 @@
+//  Copyright 2024, The Tari Project
+//
+//  Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+//  following conditions are met:
+//
+//  1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+//  disclaimer.
+//
+//  2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+//  following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+//  3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+//  products derived from this software without specific prior written permission.
+//
+//  THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+//  INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+//  DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+//  SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+//  SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+//  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+//  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::{
+    fs::File,
+    io::BufReader,
+    path::PathBuf,
+    sync::{Arc, RwLock},
+};
+
+use anyhow::anyhow;
+use log::*;
+use tari_core::{
+    chain_storage::{import_snapshot, BlockchainDatabase, Validators},
+    consensus::ConsensusManager,
+    proof_of_work::randomx_factory::RandomXFactory,
+    transactions::CryptoFactories,
+    validation::{mocks::MockValidator, DifficultyCalculator},
+    OutputSmt,
+};
+use tokio::task;
+
+use crate::{database::ChainBackend, BaseNodeConfig};
+
+const LOG_TARGET: &str = "base_node::app::snapshot";
+
+/// Imports the chain snapshot at `path` into the node's database. The database must be a pruned node database that
+/// only contains the genesis block.
+pub async fn run_import_snapshot(node_config: &BaseNodeConfig, path: PathBuf) -> Result<(), anyhow::Error> {
+    println!("Importing snapshot from {}", path.display());
+    let rules = ConsensusManager::builder(node_config.network).build().map_err(|e| {
+        error!(target: LOG_TARGET, "Error configuring consensus manager: {}", e);
+        anyhow!("Could not configure consensus manager: {}", e)
+    })?;
+    let backend = ChainBackend::open(node_config, rules.clone()).map_err(|e| {
+        error!(target: LOG_TARGET, "Error opening db: {}", e);
+        anyhow!("Could not open DB: {}", e)
+    })?;
+    // No blocks are added during the import, so the validators are never used
+    let validators = Validators::new(
+        MockValidator::new(true),
+        MockValidator::new(true),
+        MockValidator::new(true),
+    );
+    let randomx_factory = RandomXFactory::new(node_config.max_randomx_vms);
+    let db = BlockchainDatabase::new(
+        backend,
+        rules.clone(),
+        validators,
+        node_config.storage,
+        DifficultyCalculator::new(rules, randomx_factory),
+        Arc::new(RwLock::new(OutputSmt::new())),
+    )?;
+
+    let summary = task::spawn_blocking(move || {
+        let reader = BufReader::new(File::open(&path)?);
+        import_snapshot(&db, reader, CryptoFactories::default()).map_err(anyhow::Error::from)
+    })
+    .await??;
+    info!(target: LOG_TARGET, "Imported {}", summary);
+    println!("Imported {}", summary);
+    Ok(())
+}
@@ -23,7 +23,10 @@
 use minotari_ledger_wallet_common::common_types::Branch;
 use strum_macros::EnumIter;
 
-use crate::WALLET_COMMS_AND_SPEND_KEY_BRANCH;
+use crate::{
+    wallet_types::{AccountId, DEFAULT_ACCOUNT_ID},
+    WALLET_COMMS_AND_SPEND_KEY_BRANCH,
+};
 
 #[repr(u8)]
 #[derive(Clone, Copy, EnumIter, Eq, PartialEq, Debug)]
@@ -70,6 +73,17 @@ impl TransactionKeyManagerBranch {
         }
     }
 
+    /// The branch used to derive the keys of the given wallet account. The default account uses the branch itself so
+    /// that wallets created before accounts existed keep deriving the same keys.
+    /// Warning: Changing this format will affect the backwards compatibility of the wallet with older databases.
+    pub fn get_account_branch_key(self, account_id: AccountId) -> String {
+        if account_id == DEFAULT_ACCOUNT_ID {
+            self.get_branch_key()
+        } else {
+            format!("{} account {}", self.get_branch_key(), account_id)
+        }
+    }
+
     pub fn from_key(key: &str) -> Self {
         match key {
             DATA_ENCRYPTION => TransactionKeyManagerBranch::DataEncryption,
@@ -252,4 +266,12 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn test_account_branch_keys() {
+        let branch = TransactionKeyManagerBranch::CommitmentMask;
+        assert_eq!(branch.get_account_branch_key(0), COMMITMENT_MASK);
+        assert_eq!(branch.get_account_branch_key(3), "commitment mask account 3");
+        assert_ne!(branch.get_account_branch_key(3), branch.get_account_branch_key(4));
+    }
 }
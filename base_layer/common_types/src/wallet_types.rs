@@ -31,6 +31,12 @@ use tari_crypto::keys::PublicKey as PublicKeyTrait;
 
 use crate::types::{PrivateKey, PublicKey};
 
+/// Identifies a named account within a wallet. Outputs, balances and transaction history are partitioned by account.
+pub type AccountId = u32;
+
+/// The account that every wallet has, and that received funds are credited to
+pub const DEFAULT_ACCOUNT_ID: AccountId = 0;
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default, Eq, PartialEq)]
 pub enum WalletType {
     #[default]
@@ -39,11 +45,24 @@ pub enum WalletType {
     ProvidedKeys(ProvidedKeysWallet),
 }
 
+impl WalletType {
+    /// Returns true if the wallet can track funds but cannot spend them, because it does not hold the private spend key
+    pub fn is_watch_only(&self) -> bool {
+        match self {
+            WalletType::DerivedKeys | WalletType::Ledger(_) => false,
+            WalletType::ProvidedKeys(provided_keys_wallet) => provided_keys_wallet.is_watch_only(),
+        }
+    }
+}
+
 impl Display for WalletType {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
             WalletType::DerivedKeys => write!(f, "Derived wallet"),
             WalletType::Ledger(ledger_wallet) => write!(f, "Ledger({ledger_wallet})"),
+            WalletType::ProvidedKeys(provided_keys_wallet) if provided_keys_wallet.is_watch_only() => {
+                write!(f, "Watch-only ({provided_keys_wallet})")
+            },
             WalletType::ProvidedKeys(provided_keys_wallet) => write!(f, "Provided Keys ({provided_keys_wallet})"),
         }
     }
@@ -57,6 +76,23 @@ pub struct ProvidedKeysWallet {
     pub view_key: PrivateKey,
 }
 
+impl ProvidedKeysWallet {
+    /// A wallet that tracks incoming funds, balances and spends using only the private view key and the public spend
+    /// key. It cannot sign transactions.
+    pub fn watch_only(public_spend_key: PublicKey, view_key: PrivateKey) -> Self {
+        Self {
+            public_spend_key,
+            private_spend_key: None,
+            private_comms_key: None,
+            view_key,
+        }
+    }
+
+    pub fn is_watch_only(&self) -> bool {
+        self.private_spend_key.is_none()
+    }
+}
+
 impl Display for ProvidedKeysWallet {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(f, "public spend key {}", self.public_spend_key)?;
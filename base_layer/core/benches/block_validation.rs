@@ -0,0 +1,114 @@
+//  Copyright 2024. The Tari Project
+//
+//  Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+//  following conditions are met:
+//
+//  1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+//  disclaimer.
+//
+//  2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+//  following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+//  3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+//  products derived from this software without specific prior written permission.
+//
+//  THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+//  INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+//  DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+//  SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+//  SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+//  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+//  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+#[cfg(not(feature = "benches"))]
+mod benches {
+    pub fn main() {
+        println!("Enable the `benches` feature to run benches");
+    }
+}
+
+#[cfg(feature = "benches")]
+mod benches {
+    use std::slice;
+
+    use criterion::{criterion_group, BenchmarkId, Criterion};
+    use tari_common::configuration::Network;
+    use tari_core::{
+        blocks::{Block, BlockHeader},
+        chain_storage::LMDBDatabase,
+        consensus::ConsensusManager,
+        transactions::{
+            key_manager::create_memory_db_key_manager,
+            tari_amount::{uT, T},
+        },
+        tx,
+        validation::block_body::{verify_stateless_concurrently, BlockBodyFullValidator},
+    };
+    use tokio::runtime::Runtime;
+
+    const NUM_BLOCKS: usize = 32;
+    const OUTPUTS_PER_BLOCK: usize = 20;
+
+    async fn generate_blocks(num_blocks: usize, num_outputs: usize) -> std::io::Result<Vec<Block>> {
+        let key_manager = create_memory_db_key_manager().unwrap();
+        let mut blocks = Vec::with_capacity(num_blocks);
+        for height in 1..=num_blocks {
+            let (tx, _, _) = tx!(10 * T, fee: 25 * uT, inputs: 1, outputs: num_outputs, &key_manager)?;
+            let mut header = BlockHeader::new(0);
+            header.height = height as u64;
+            blocks.push(Block::new(header, tx.body));
+        }
+        Ok(blocks)
+    }
+
+    /// Compares verifying the signatures and range proofs of each block on its own, as block sync used to do, with
+    /// verifying a batch of blocks on multiple threads
+    pub fn block_validation_perf_test(c: &mut Criterion) {
+        let runtime = Runtime::new().unwrap();
+        let rules = ConsensusManager::builder(Network::LocalNet).build().unwrap();
+        let validator = BlockBodyFullValidator::new(rules, false);
+        eprintln!(
+            "Generating {} blocks with {} outputs each...",
+            NUM_BLOCKS, OUTPUTS_PER_BLOCK
+        );
+        let blocks = runtime
+            .block_on(generate_blocks(NUM_BLOCKS, OUTPUTS_PER_BLOCK))
+            .expect("Failed to generate blocks");
+
+        let mut group = c.benchmark_group("Block body stateless verification");
+        group.bench_function("Sequential", |b| {
+            b.iter(|| {
+                for block in &blocks {
+                    validator.verify_stateless(slice::from_ref(block)).unwrap();
+                }
+            });
+        });
+        for concurrency in [1, 2, 4, 8] {
+            group.bench_with_input(
+                BenchmarkId::new("Batched", concurrency),
+                &concurrency,
+                |b, &concurrency| {
+                    b.iter(|| {
+                        verify_stateless_concurrently::<LMDBDatabase, _>(&validator, &blocks, concurrency).unwrap();
+                    });
+                },
+            );
+        }
+        group.finish();
+    }
+
+    criterion_group!(
+        name = block_validation_perf;
+        config = Criterion::default().sample_size(10);
+        targets = block_validation_perf_test
+    );
+
+    pub fn main() {
+        block_validation_perf();
+        criterion::Criterion::default().configure_from_args().final_summary();
+    }
+}
+
+fn main() {
+    benches::main();
+}
@@ -29,7 +29,11 @@ use serde::{Deserialize, Serialize};
 use tari_common_types::types::{BlockHash, Commitment, HashOutput, PrivateKey, PublicKey, Signature};
 use tari_utilities::hex::Hex;
 
-use crate::{blocks::NewBlockTemplate, chain_storage::MmrTree, proof_of_work::PowAlgorithm};
+use crate::{
+    blocks::NewBlockTemplate,
+    chain_storage::{MmrTree, OutputIndex},
+    proof_of_work::PowAlgorithm,
+};
 
 /// A container for the parameters required for a FetchMmrState request.
 #[derive(Debug, Serialize, Deserialize)]
@@ -46,7 +50,10 @@ pub enum NodeCommsRequest {
     FetchHeaders(RangeInclusive<u64>),
     FetchHeadersByHashes(Vec<HashOutput>),
     FetchMatchingUtxos(Vec<HashOutput>),
-    FetchMatchingBlocks { range: RangeInclusive<u64>, compact: bool },
+    FetchMatchingBlocks {
+        range: RangeInclusive<u64>,
+        compact: bool,
+    },
     FetchBlocksByKernelExcessSigs(Vec<Signature>),
     FetchBlocksByUtxos(Vec<Commitment>),
     GetHeaderByHash(HashOutput),
@@ -55,11 +62,35 @@ pub enum NodeCommsRequest {
     GetNewBlock(NewBlockTemplate),
     GetBlockFromAllChains(HashOutput),
     FetchKernelByExcessSig(Signature),
-    FetchMempoolTransactionsByExcessSigs { excess_sigs: Vec<PrivateKey> },
-    FetchValidatorNodesKeys { height: u64 },
-    GetShardKey { height: u64, public_key: PublicKey },
-    FetchTemplateRegistrations { start_height: u64, end_height: u64 },
-    FetchUnspentUtxosInBlock { block_hash: BlockHash },
+    FetchMempoolTransactionsByExcessSigs {
+        excess_sigs: Vec<PrivateKey>,
+    },
+    FetchValidatorNodesKeys {
+        height: u64,
+    },
+    GetShardKey {
+        height: u64,
+        public_key: PublicKey,
+    },
+    FetchTemplateRegistrations {
+        start_height: u64,
+        end_height: u64,
+    },
+    FetchUnspentUtxosInBlock {
+        block_hash: BlockHash,
+    },
+    FetchOutputsByIndex {
+        index: OutputIndex,
+        start_height: u64,
+        end_height: u64,
+    },
+    FetchOutputAtHeight {
+        commitment: Commitment,
+        height: u64,
+    },
+    FetchOutputSmtRootAtHeight {
+        height: u64,
+    },
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -113,6 +144,17 @@ impl Display for NodeCommsRequest {
             FetchUnspentUtxosInBlock { block_hash } => {
                 write!(f, "FetchUnspentUtxosInBlock ({})", block_hash)
             },
+            FetchOutputsByIndex {
+                index,
+                start_height,
+                end_height,
+            } => {
+                write!(f, "FetchOutputsByIndex ({}, {}..={})", index, start_height, end_height)
+            },
+            FetchOutputAtHeight { commitment, height } => {
+                write!(f, "FetchOutputAtHeight ({}, {})", commitment.to_hex(), height)
+            },
+            FetchOutputSmtRootAtHeight { height } => write!(f, "FetchOutputSmtRootAtHeight ({})", height),
         }
     }
 }
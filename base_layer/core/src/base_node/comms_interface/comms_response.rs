@@ -32,7 +32,7 @@ use tari_common_types::{
 
 use crate::{
     blocks::{Block, ChainHeader, HistoricalBlock, NewBlockTemplate},
-    chain_storage::TemplateRegistrationEntry,
+    chain_storage::{HistoricalOutputState, OutputMinedInfo, TemplateRegistrationEntry},
     proof_of_work::Difficulty,
     transactions::transaction_components::{Transaction, TransactionKernel, TransactionOutput},
 };
@@ -61,6 +61,10 @@ pub enum NodeCommsResponse {
     FetchValidatorNodesKeysResponse(Vec<(PublicKey, [u8; 32])>),
     GetShardKeyResponse(Option<[u8; 32]>),
     FetchTemplateRegistrationsResponse(Vec<TemplateRegistrationEntry>),
+    FetchOutputsByIndexResponse(Vec<OutputMinedInfo>),
+    FetchOutputAtHeightResponse(Option<HistoricalOutputState>),
+    /// The output SMT root and size
+    FetchOutputSmtRootAtHeightResponse(HashOutput, u64),
 }
 
 impl Display for NodeCommsResponse {
@@ -98,6 +102,11 @@ impl Display for NodeCommsResponse {
             FetchValidatorNodesKeysResponse(_) => write!(f, "FetchValidatorNodesKeysResponse"),
             GetShardKeyResponse(_) => write!(f, "GetShardKeyResponse"),
             FetchTemplateRegistrationsResponse(_) => write!(f, "FetchTemplateRegistrationsResponse"),
+            FetchOutputsByIndexResponse(outputs) => write!(f, "FetchOutputsByIndexResponse({})", outputs.len()),
+            FetchOutputAtHeightResponse(_) => write!(f, "FetchOutputAtHeightResponse"),
+            FetchOutputSmtRootAtHeightResponse(root, size) => {
+                write!(f, "FetchOutputSmtRootAtHeightResponse({}, {})", root, size)
+            },
         }
     }
 }
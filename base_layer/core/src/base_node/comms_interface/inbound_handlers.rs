@@ -431,6 +431,26 @@ where B: BlockchainBackend + 'static
                 let utxos = self.blockchain_db.fetch_outputs_in_block(block_hash).await?;
                 Ok(NodeCommsResponse::TransactionOutputs(utxos))
             },
+            NodeCommsRequest::FetchOutputsByIndex {
+                index,
+                start_height,
+                end_height,
+            } => {
+                let outputs = self
+                    .blockchain_db
+                    .fetch_outputs_by_index(index, start_height..=end_height)
+                    .await?;
+                Ok(NodeCommsResponse::FetchOutputsByIndexResponse(outputs))
+            },
+            NodeCommsRequest::FetchOutputAtHeight { commitment, height } => {
+                let output = self.blockchain_db.fetch_output_at_height(commitment, height).await?;
+                Ok(NodeCommsResponse::FetchOutputAtHeightResponse(output))
+            },
+            NodeCommsRequest::FetchOutputSmtRootAtHeight { height } => {
+                let smt = self.blockchain_db.calculate_output_smt_at_height(height).await?;
+                let root = HashOutput::try_from(smt.hash().as_slice()).map_err(ChainStorageError::from)?;
+                Ok(NodeCommsResponse::FetchOutputSmtRootAtHeightResponse(root, smt.size()))
+            },
         }
     }
 
@@ -38,7 +38,7 @@ use crate::{
         NodeCommsResponse,
     },
     blocks::{Block, ChainHeader, HistoricalBlock, NewBlockTemplate},
-    chain_storage::TemplateRegistrationEntry,
+    chain_storage::{HistoricalOutputState, OutputIndex, OutputMinedInfo, TemplateRegistrationEntry},
     proof_of_work::PowAlgorithm,
     transactions::transaction_components::{TransactionKernel, TransactionOutput},
 };
@@ -266,6 +266,19 @@ impl LocalNodeCommsInterface {
         }
     }
 
+    /// Return the block matching the given hash from the main chain or the orphan pool. If the block cannot be found
+    /// `Ok(None)` is returned.
+    pub async fn get_block_from_all_chains(&mut self, hash: HashOutput) -> Result<Option<Block>, CommsInterfaceError> {
+        match self
+            .request_sender
+            .call(NodeCommsRequest::GetBlockFromAllChains(hash))
+            .await??
+        {
+            NodeCommsResponse::Block(block) => Ok(*block),
+            _ => Err(CommsInterfaceError::UnexpectedApiResponse),
+        }
+    }
+
     /// Searches for a kernel via the excess sig
     pub async fn get_kernel_by_excess_sig(
         &mut self,
@@ -342,4 +355,58 @@ impl LocalNodeCommsInterface {
             _ => Err(CommsInterfaceError::UnexpectedApiResponse),
         }
     }
+
+    /// Fetches the outputs matching the given secondary index that were mined between `start_height` and `end_height`
+    /// (inclusive). The node must have output indexing enabled.
+    pub async fn fetch_outputs_by_index(
+        &mut self,
+        index: OutputIndex,
+        start_height: u64,
+        end_height: u64,
+    ) -> Result<Vec<OutputMinedInfo>, CommsInterfaceError> {
+        match self
+            .request_sender
+            .call(NodeCommsRequest::FetchOutputsByIndex {
+                index,
+                start_height,
+                end_height,
+            })
+            .await??
+        {
+            NodeCommsResponse::FetchOutputsByIndexResponse(outputs) => Ok(outputs),
+            _ => Err(CommsInterfaceError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Fetches the most recent output with the given commitment mined at or below `height`, and its spend state at that
+    /// height. The node must have output indexing enabled.
+    pub async fn fetch_output_at_height(
+        &mut self,
+        commitment: Commitment,
+        height: u64,
+    ) -> Result<Option<HistoricalOutputState>, CommsInterfaceError> {
+        match self
+            .request_sender
+            .call(NodeCommsRequest::FetchOutputAtHeight { commitment, height })
+            .await??
+        {
+            NodeCommsResponse::FetchOutputAtHeightResponse(output) => Ok(output),
+            _ => Err(CommsInterfaceError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Fetches the root and size of the output SMT at the given height
+    pub async fn fetch_output_smt_root_at_height(
+        &mut self,
+        height: u64,
+    ) -> Result<(HashOutput, u64), CommsInterfaceError> {
+        match self
+            .request_sender
+            .call(NodeCommsRequest::FetchOutputSmtRootAtHeight { height })
+            .await??
+        {
+            NodeCommsResponse::FetchOutputSmtRootAtHeightResponse(root, size) => Ok((root, size)),
+            _ => Err(CommsInterfaceError::UnexpectedApiResponse),
+        }
+    }
 }
@@ -55,7 +55,7 @@ pub mod sync;
 
 #[cfg(feature = "base_node")]
 pub use sync::{
-    rpc::{create_base_node_sync_rpc_service, BaseNodeSyncService},
+    rpc::{base_node_sync_rpc_middleware, create_base_node_sync_rpc_service, BaseNodeSyncService},
     BlockchainSyncConfig,
     SyncValidators,
 };
@@ -24,7 +24,11 @@ use std::convert::{TryFrom, TryInto};
 
 use tari_utilities::ByteArray;
 
-use crate::{blocks::Block, mempool::FeePerGramStat, proto::base_node as proto};
+use crate::{
+    blocks::Block,
+    mempool::{FeeEstimate, FeePerGramStat},
+    proto::base_node as proto,
+};
 
 impl TryFrom<Block> for proto::BlockBodyResponse {
     type Error = String;
@@ -55,3 +59,26 @@ impl From<FeePerGramStat> for proto::MempoolFeePerGramStat {
         }
     }
 }
+
+impl From<Vec<FeeEstimate>> for proto::EstimateFeeResponse {
+    fn from(estimates: Vec<FeeEstimate>) -> Self {
+        Self {
+            estimates: estimates.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<FeeEstimate> for proto::FeeEstimate {
+    fn from(estimate: FeeEstimate) -> Self {
+        Self {
+            target_blocks: estimate.target_blocks,
+            fee_per_gram: estimate.fee_per_gram.as_u64(),
+            mempool_fee_per_gram: estimate.mempool_fee_per_gram.as_u64(),
+            history_fee_per_gram: estimate
+                .history_fee_per_gram
+                .map(|fee| fee.as_u64())
+                .unwrap_or_default(),
+            num_samples: estimate.num_samples,
+        }
+    }
+}
@@ -25,8 +25,21 @@ mod service;
 #[cfg(feature = "base_node")]
 pub mod sync_utxos_by_block_task;
 
+#[cfg(feature = "base_node")]
+use std::time::Duration;
+
 #[cfg(feature = "base_node")]
 pub use service::BaseNodeWalletRpcService;
+#[cfg(feature = "base_node")]
+use tari_comms::protocol::rpc::middleware::{
+    ConcurrencyLimit,
+    LatencyHistogram,
+    RateLimit,
+    RequestConcurrencyLimit,
+    RequestLogger,
+    RequestRateLimit,
+    RpcMiddlewareStack,
+};
 use tari_comms::protocol::rpc::{Request, Response, RpcStatus, Streaming};
 use tari_comms_rpc_macros::tari_rpc;
 
@@ -41,6 +54,8 @@ use crate::{
     proto,
     proto::{
         base_node::{
+            EstimateFeeRequest,
+            EstimateFeeResponse,
             FetchMatchingUtxos,
             FetchUtxosResponse,
             GetMempoolFeePerGramStatsRequest,
@@ -119,6 +134,12 @@ pub trait BaseNodeWalletService: Send + Sync + 'static {
         &self,
         request: Request<GetMempoolFeePerGramStatsRequest>,
     ) -> Result<Response<GetMempoolFeePerGramStatsResponse>, RpcStatus>;
+
+    #[rpc(method = 13)]
+    async fn estimate_fee(
+        &self,
+        request: Request<EstimateFeeRequest>,
+    ) -> Result<Response<EstimateFeeResponse>, RpcStatus>;
 }
 
 #[cfg(feature = "base_node")]
@@ -129,3 +150,25 @@ pub fn create_base_node_wallet_rpc_service<B: BlockchainBackend + 'static>(
 ) -> BaseNodeWalletRpcServer<BaseNodeWalletRpcService<B>> {
     BaseNodeWalletRpcServer::new(BaseNodeWalletRpcService::new(db, mempool, state_machine))
 }
+
+/// The middleware applied to the base node wallet RPC service. Wallets scan for outputs with the UTXO queries, so
+/// these are limited per peer to prevent a single wallet from monopolising the database.
+#[cfg(feature = "base_node")]
+pub fn base_node_wallet_rpc_middleware() -> RpcMiddlewareStack {
+    use base_node_wallet_service_methods::{FETCH_MATCHING_UTXOS, QUERY_DELETED, SYNC_UTXOS_BY_BLOCK, UTXO_QUERY};
+    const ONE_MINUTE: Duration = Duration::from_secs(60);
+
+    let rate_limit = RateLimit::new()
+        .with_method_limit(SYNC_UTXOS_BY_BLOCK, RequestRateLimit::per_peer(30, ONE_MINUTE))
+        .with_method_limit(FETCH_MATCHING_UTXOS, RequestRateLimit::per_peer(120, ONE_MINUTE))
+        .with_method_limit(UTXO_QUERY, RequestRateLimit::per_peer(300, ONE_MINUTE))
+        .with_method_limit(QUERY_DELETED, RequestRateLimit::per_peer(300, ONE_MINUTE));
+    let concurrency_limit =
+        ConcurrencyLimit::new().with_method_limit(SYNC_UTXOS_BY_BLOCK, RequestConcurrencyLimit::per_peer(2));
+
+    RpcMiddlewareStack::new()
+        .layer(RequestLogger::new())
+        .layer(LatencyHistogram::new())
+        .layer(rate_limit)
+        .layer(concurrency_limit)
+}
@@ -36,10 +36,12 @@ use crate::{
         StateMachineHandle,
     },
     chain_storage::{async_db::AsyncBlockchainDb, BlockchainBackend},
-    mempool::{service::MempoolHandle, TxStorageResponse},
+    mempool::{service::MempoolHandle, TxStorageResponse, DEFAULT_FEE_ESTIMATE_TARGETS, MAX_FEE_ESTIMATE_TARGET},
     proto,
     proto::{
         base_node::{
+            EstimateFeeRequest,
+            EstimateFeeResponse,
             FetchMatchingUtxos,
             FetchUtxosResponse,
             GetMempoolFeePerGramStatsRequest,
@@ -161,6 +163,8 @@ impl<B: BlockchainBackend + 'static> BaseNodeWalletRpcService<B> {
             TxStorageResponse::NotStoredConsensus |
             TxStorageResponse::NotStored |
             TxStorageResponse::NotStoredFeeTooLow |
+            TxStorageResponse::NotStoredReplacementRejected |
+            TxStorageResponse::NotStoredPackageTooLarge |
             TxStorageResponse::NotStoredAlreadyMined => TxQueryResponse {
                 location: TxLocation::NotStored as i32,
                 best_block_hash: vec![],
@@ -209,17 +213,21 @@ impl<B: BlockchainBackend + 'static> BaseNodeWalletService for BaseNodeWalletRpc
                 rejection_reason: TxSubmissionRejectionReason::Orphan.into(),
                 is_synced,
             },
-            TxStorageResponse::NotStoredFeeTooLow => TxSubmissionResponse {
-                accepted: false,
-                rejection_reason: TxSubmissionRejectionReason::FeeTooLow.into(),
-                is_synced,
+            TxStorageResponse::NotStoredFeeTooLow | TxStorageResponse::NotStoredReplacementRejected => {
+                TxSubmissionResponse {
+                    accepted: false,
+                    rejection_reason: TxSubmissionRejectionReason::FeeTooLow.into(),
+                    is_synced,
+                }
             },
             TxStorageResponse::NotStoredTimeLocked => TxSubmissionResponse {
                 accepted: false,
                 rejection_reason: TxSubmissionRejectionReason::TimeLocked.into(),
                 is_synced,
             },
-            TxStorageResponse::NotStoredConsensus | TxStorageResponse::NotStored => TxSubmissionResponse {
+            TxStorageResponse::NotStoredConsensus |
+            TxStorageResponse::NotStored |
+            TxStorageResponse::NotStoredPackageTooLarge => TxSubmissionResponse {
                 accepted: false,
                 rejection_reason: TxSubmissionRejectionReason::ValidationFailed.into(),
                 is_synced,
@@ -677,4 +685,39 @@ impl<B: BlockchainBackend + 'static> BaseNodeWalletService for BaseNodeWalletRpc
 
         Ok(Response::new(stats.into()))
     }
+
+    async fn estimate_fee(
+        &self,
+        request: Request<EstimateFeeRequest>,
+    ) -> Result<Response<EstimateFeeResponse>, RpcStatus> {
+        let req = request.into_message();
+        let target_blocks = if req.target_blocks.is_empty() {
+            DEFAULT_FEE_ESTIMATE_TARGETS.to_vec()
+        } else {
+            req.target_blocks
+        };
+        if target_blocks.len() as u64 > MAX_FEE_ESTIMATE_TARGET ||
+            target_blocks
+                .iter()
+                .any(|target| *target == 0 || *target > MAX_FEE_ESTIMATE_TARGET)
+        {
+            return Err(RpcStatus::bad_request(&format!(
+                "At most {} targets between 1 and {} blocks may be requested",
+                MAX_FEE_ESTIMATE_TARGET, MAX_FEE_ESTIMATE_TARGET
+            )));
+        }
+
+        let metadata = self
+            .db
+            .get_chain_metadata()
+            .await
+            .rpc_status_internal_error(LOG_TARGET)?;
+        let estimates = self
+            .mempool()
+            .estimate_fee(target_blocks, metadata.best_block_height())
+            .await
+            .rpc_status_internal_error(LOG_TARGET)?;
+
+        Ok(Response::new(estimates.into()))
+    }
 }
@@ -20,7 +20,7 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::{cmp::Ordering, time::Instant};
+use std::time::Instant;
 
 use log::*;
 use tari_common_types::chain_metadata::ChainMetadata;
@@ -48,14 +48,8 @@ pub struct HeaderSyncState {
 
 impl HeaderSyncState {
     pub fn new(mut sync_peers: Vec<SyncPeer>, local_metadata: ChainMetadata) -> Self {
-        // Sort by latency lowest to highest
-        sync_peers.sort_by(|a, b| match (a.latency(), b.latency()) {
-            (None, None) => Ordering::Equal,
-            // No latency goes to the end
-            (Some(_), None) => Ordering::Less,
-            (None, Some(_)) => Ordering::Greater,
-            (Some(la), Some(lb)) => la.cmp(&lb),
-        });
+        // Sort by reputation highest to lowest, then latency lowest to highest
+        sync_peers.sort_by(SyncPeer::cmp_reputation_and_latency);
         Self {
             sync_peers,
             is_synced: false,
@@ -107,6 +101,24 @@ impl HeaderSyncState {
             Err(e) => return StateEvent::FatalError(format!("{}", e)),
         }
 
+        // Prefer sync peers with a better reputation
+        for sync_peer in &mut self.sync_peers {
+            match shared.peer_manager.get_reputation_score(sync_peer.node_id()).await {
+                Ok(score) => {
+                    sync_peer.set_reputation(score);
+                },
+                Err(err) => {
+                    debug!(
+                        target: LOG_TARGET,
+                        "Unable to get reputation for sync peer {}: {}",
+                        sync_peer.node_id(),
+                        err
+                    );
+                },
+            }
+        }
+        self.sync_peers.sort_by(SyncPeer::cmp_reputation_and_latency);
+
         let mut synchronizer = HeaderSynchronizer::new(
             shared.config.blockchain_sync_config.clone(),
             shared.db.clone(),
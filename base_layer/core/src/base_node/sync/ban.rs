@@ -20,16 +20,18 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::time::Duration;
-
 use log::*;
-use tari_comms::{connectivity::ConnectivityRequester, peer_manager::NodeId};
+use tari_comms::{
+    connectivity::ConnectivityRequester,
+    peer_manager::{NodeId, ReputationEvent},
+};
 
-use crate::base_node::BlockchainSyncConfig;
+use crate::{base_node::BlockchainSyncConfig, common::BanReason};
 
 const LOG_TARGET: &str = "c::bn::sync";
 
-// Sync peers are banned if there exists a ban reason for the error and the peer is not on the allow list for sync.
+// Sync peer behaviour is reported to the connectivity manager, which adjusts the peer's reputation and bans the peer
+// once its score falls below the ban threshold. Peers on the allow list for sync are never penalised.
 
 pub struct PeerBanManager {
     config: BlockchainSyncConfig,
@@ -41,25 +43,57 @@ impl PeerBanManager {
         Self { config, connectivity }
     }
 
-    pub async fn ban_peer_if_required(&mut self, node_id: &NodeId, ban_reason: String, ban_duration: Duration) {
+    /// Penalises the peer's reputation according to the severity of the ban reason
+    pub async fn ban_peer_if_required(&mut self, node_id: &NodeId, ban_reason: BanReason) {
         if self.config.forced_sync_peers.contains(node_id) {
             debug!(
                 target: LOG_TARGET,
-                "Not banning peer that is on the allow list for sync. Ban reason = {}", ban_reason
+                "Not penalising peer that is on the allow list for sync. Ban reason = {}", ban_reason.reason
             );
             return;
         }
-        debug!(target: LOG_TARGET, "Sync peer {} removed from the sync peer list because {}", node_id, ban_reason);
-
+        let event = ban_reason.reputation_event();
         match self
             .connectivity
-            .ban_peer_until(node_id.clone(), ban_duration, ban_reason.clone())
+            .report_peer(node_id.clone(), event, ban_reason.reason.clone())
             .await
         {
             Ok(_) => {
-                warn!(target: LOG_TARGET, "Banned sync peer {} for {:?} because {}", node_id, ban_duration, ban_reason)
+                warn!(
+                    target: LOG_TARGET,
+                    "Reported sync peer {} for {} because {}", node_id, event, ban_reason.reason
+                )
             },
-            Err(err) => error!(target: LOG_TARGET, "Failed to ban sync peer {}: {}", node_id, err),
+            Err(err) => error!(target: LOG_TARGET, "Failed to report sync peer {}: {}", node_id, err),
+        }
+    }
+
+    /// Penalises the peer's reputation for responding too slowly
+    pub async fn report_slow_response(&mut self, node_id: &NodeId, reason: String) {
+        if self.config.forced_sync_peers.contains(node_id) {
+            debug!(
+                target: LOG_TARGET,
+                "Not penalising peer that is on the allow list for sync. Reason = {}", reason
+            );
+            return;
+        }
+        if let Err(err) = self
+            .connectivity
+            .report_peer(node_id.clone(), ReputationEvent::SlowResponse, reason)
+            .await
+        {
+            error!(target: LOG_TARGET, "Failed to report sync peer {}: {}", node_id, err);
+        }
+    }
+
+    /// Rewards the peer's reputation after a successful sync
+    pub async fn reward_peer(&mut self, node_id: &NodeId) {
+        if let Err(err) = self
+            .connectivity
+            .report_peer(node_id.clone(), ReputationEvent::GoodBehaviour, "Successful sync")
+            .await
+        {
+            error!(target: LOG_TARGET, "Failed to report sync peer {}: {}", node_id, err);
         }
     }
 }
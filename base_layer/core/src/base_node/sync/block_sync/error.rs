@@ -105,8 +105,26 @@ impl BlockSyncError {
 }
 
 impl BlockSyncError {
+    /// Returns true if the error was caused by the peer responding too slowly. Slow peers are penalised less severely
+    /// than peers that send invalid data.
+    pub fn is_slow_response(&self) -> bool {
+        match self {
+            BlockSyncError::MaxLatencyExceeded { .. } => true,
+            BlockSyncError::RpcError(err) => err.is_timeout(),
+            BlockSyncError::RpcRequestError(status) => status.is_timeout(),
+            _ => false,
+        }
+    }
+
     pub fn get_ban_reason(&self) -> Option<BanReason> {
         match self {
+            // slow responses are penalised separately and do not result in a ban
+            _ if self.is_slow_response() => None,
+            BlockSyncError::MaxLatencyExceeded { .. } => None,
+            // the peer is too busy to serve us and may be retried later
+            BlockSyncError::RpcError(err) if err.is_busy() => None,
+            BlockSyncError::RpcRequestError(status) if status.is_busy() => None,
+
             // no ban
             BlockSyncError::AsyncTaskFailed(_) |
             BlockSyncError::ConnectivityError(_) |
@@ -117,7 +135,6 @@ impl BlockSyncError {
             BlockSyncError::SyncRoundFailed => None,
             BlockSyncError::ChainStorageError(e) => e.get_ban_reason(),
             // short ban
-            err @ BlockSyncError::MaxLatencyExceeded { .. } |
             err @ BlockSyncError::PeerDidNotSupplyAllClaimedBlocks(_) |
             err @ BlockSyncError::RpcError(_) |
             err @ BlockSyncError::RpcRequestError(_) => Some(BanReason {
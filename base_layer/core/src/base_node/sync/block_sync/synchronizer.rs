@@ -22,13 +22,19 @@
 
 use std::{
     convert::{TryFrom, TryInto},
+    iter,
     sync::Arc,
     time::{Duration, Instant},
 };
 
 use futures::StreamExt;
 use log::*;
-use tari_comms::{connectivity::ConnectivityRequester, peer_manager::NodeId, protocol::rpc::RpcClient, PeerConnection};
+use tari_comms::{
+    connectivity::ConnectivityRequester,
+    peer_manager::NodeId,
+    protocol::rpc::{ClientStreaming, ResumptionToken, RpcClientBuilder, RpcStatus},
+    PeerConnection,
+};
 use tari_utilities::hex::Hex;
 use tokio::task;
 
@@ -40,15 +46,21 @@ use crate::{
     },
     blocks::{Block, ChainBlock},
     chain_storage::{async_db::AsyncBlockchainDb, BlockchainBackend},
-    common::{rolling_avg::RollingAverageTime, BanPeriod},
-    proto::base_node::SyncBlocksRequest,
+    common::rolling_avg::RollingAverageTime,
+    proto::base_node::{BlockBodyResponse, SyncBlocksRequest},
     transactions::aggregated_body::AggregateBody,
-    validation::{BlockBodyValidator, ValidationError},
+    validation::{
+        block_body::{verify_stateless_concurrently, ConcurrentVerificationError},
+        BlockBodyValidator,
+        ValidationError,
+    },
 };
 
 const LOG_TARGET: &str = "c::bn::block_sync";
 
 const MAX_LATENCY_INCREASES: usize = 5;
+/// The number of times an interrupted block stream is resumed before the sync attempt fails
+const MAX_BLOCK_STREAM_RESUMPTIONS: usize = 2;
 
 pub struct BlockSynchronizer<'a, B> {
     config: BlockchainSyncConfig,
@@ -143,7 +155,10 @@ impl<'a, B: BlockchainBackend + 'static> BlockSynchronizer<'a, B> {
         );
         let mut latency_counter = 0usize;
         for node_id in sync_peer_node_ids {
-            let peer_index = self.get_sync_peer_index(&node_id).ok_or(BlockSyncError::PeerNotFound)?;
+            // The peer is removed if it failed after an interrupted stream was resumed on it
+            let Some(peer_index) = self.get_sync_peer_index(&node_id) else {
+                continue;
+            };
             let sync_peer = &self.sync_peers[peer_index];
             self.hooks.call_on_starting_hook(sync_peer);
             let mut conn = match self.connect_to_sync_peer(node_id.clone()).await {
@@ -157,13 +172,7 @@ impl<'a, B: BlockchainBackend + 'static> BlockSynchronizer<'a, B> {
                     continue;
                 },
             };
-            let config = RpcClient::builder()
-                .with_deadline(self.config.rpc_deadline)
-                .with_deadline_grace_period(Duration::from_secs(5));
-            let mut client = match conn
-                .connect_rpc_using_builder::<rpc::BaseNodeSyncRpcClient>(config)
-                .await
-            {
+            let mut client = match conn.connect_rpc_using_builder(self.rpc_client_builder()).await {
                 Ok(val) => val,
                 Err(e) => {
                     warn!(
@@ -178,26 +187,31 @@ impl<'a, B: BlockchainBackend + 'static> BlockSynchronizer<'a, B> {
                 .get_last_request_latency()
                 .expect("unreachable panic: last request latency must be set after connect");
             self.sync_peers[peer_index].set_latency(latency);
-            let sync_peer = self.sync_peers[peer_index].clone();
+            let mut sync_peer = self.sync_peers[peer_index].clone();
             info!(
                 target: LOG_TARGET,
                 "Attempting to synchronize blocks with `{}` latency: {:.2?}", node_id, latency
             );
-            match self.synchronize_blocks(sync_peer, client, max_latency).await {
-                Ok(_) => return Ok(()),
+            let result = self.synchronize_blocks(&mut sync_peer, client, max_latency).await;
+            // An interrupted stream may have been resumed on another sync peer, which is then responsible for the
+            // result
+            let node_id = sync_peer.node_id().clone();
+            match result {
+                Ok(_) => {
+                    self.peer_ban_manager.reward_peer(&node_id).await;
+                    return Ok(());
+                },
                 Err(err) => {
                     warn!(target: LOG_TARGET, "{}", err);
-                    let ban_reason = BlockSyncError::get_ban_reason(&err);
-                    if let Some(reason) = ban_reason {
-                        let duration = match reason.ban_duration {
-                            BanPeriod::Short => self.config.short_ban_period,
-                            BanPeriod::Long => self.config.ban_period,
-                        };
-                        warn!(target: LOG_TARGET, "{}", err);
+                    if err.is_slow_response() {
                         self.peer_ban_manager
-                            .ban_peer_if_required(&node_id, reason.reason, duration)
+                            .report_slow_response(&node_id, err.to_string())
                             .await;
                     }
+                    if let Some(reason) = BlockSyncError::get_ban_reason(&err) {
+                        warn!(target: LOG_TARGET, "{}", err);
+                        self.peer_ban_manager.ban_peer_if_required(&node_id, reason).await;
+                    }
                     if let BlockSyncError::MaxLatencyExceeded { .. } = err {
                         latency_counter += 1;
                     } else {
@@ -221,10 +235,82 @@ impl<'a, B: BlockchainBackend + 'static> BlockSynchronizer<'a, B> {
         Ok(connection)
     }
 
+    fn rpc_client_builder(&self) -> RpcClientBuilder<rpc::BaseNodeSyncRpcClient> {
+        rpc::BaseNodeSyncRpcClient::builder()
+            .with_deadline(self.config.rpc_deadline)
+            .with_deadline_grace_period(Duration::from_secs(5))
+    }
+
+    /// Continues an interrupted block stream from the last block that was received, keeping the blocks that have
+    /// already been received. The stream is resumed from the same peer if possible, otherwise from another sync peer,
+    /// in which case `sync_peer` and `client` are replaced with that peer.
+    async fn resume_block_stream(
+        &self,
+        sync_peer: &mut SyncPeer,
+        client: &mut rpc::BaseNodeSyncRpcClient,
+        token: ResumptionToken<BlockBodyResponse>,
+        status: RpcStatus,
+    ) -> Result<ClientStreaming<BlockBodyResponse>, BlockSyncError> {
+        warn!(
+            target: LOG_TARGET,
+            "Block stream from peer `{}` was interrupted after {} block(s) ({}). Resuming.",
+            sync_peer.node_id(),
+            token.position(),
+            status
+        );
+        // Every peer returns the same blocks for the request, so any sync peer can continue the stream
+        let candidates = iter::once(sync_peer.clone())
+            .chain(
+                self.sync_peers
+                    .iter()
+                    .filter(|p| p.node_id() != sync_peer.node_id())
+                    .cloned(),
+            )
+            .collect::<Vec<_>>();
+        for candidate in candidates {
+            match self.try_resume_block_stream(candidate.node_id(), token.clone()).await {
+                Ok((new_client, block_stream)) => {
+                    if candidate.node_id() != sync_peer.node_id() {
+                        info!(
+                            target: LOG_TARGET,
+                            "Resumed block stream from peer `{}` on peer `{}`",
+                            sync_peer.node_id(),
+                            candidate.node_id()
+                        );
+                        *sync_peer = candidate;
+                    }
+                    *client = new_client;
+                    return Ok(block_stream);
+                },
+                Err(err) => {
+                    warn!(
+                        target: LOG_TARGET,
+                        "Failed to resume block stream on peer `{}`: {}",
+                        candidate.node_id(),
+                        err
+                    );
+                },
+            }
+        }
+
+        Err(status.into())
+    }
+
+    async fn try_resume_block_stream(
+        &self,
+        node_id: &NodeId,
+        token: ResumptionToken<BlockBodyResponse>,
+    ) -> Result<(rpc::BaseNodeSyncRpcClient, ClientStreaming<BlockBodyResponse>), BlockSyncError> {
+        let mut conn = self.connect_to_sync_peer(node_id.clone()).await?;
+        let mut client = conn.connect_rpc_using_builder(self.rpc_client_builder()).await?;
+        let block_stream = client.resume_stream(token).await?;
+        Ok((client, block_stream))
+    }
+
     #[allow(clippy::too_many_lines)]
     async fn synchronize_blocks(
         &mut self,
-        mut sync_peer: SyncPeer,
+        sync_peer: &mut SyncPeer,
         mut client: rpc::BaseNodeSyncRpcClient,
         max_latency: Duration,
     ) -> Result<(), BlockSyncError> {
@@ -267,151 +353,227 @@ impl<'a, B: BlockchainBackend + 'static> BlockSynchronizer<'a, B> {
         let mut current_block = None;
         let mut last_sync_timer = Instant::now();
         let mut avg_latency = RollingAverageTime::new(20);
-        while let Some(block_result) = block_stream.next().await {
-            let latency = last_sync_timer.elapsed();
-            avg_latency.add_sample(latency);
-            let block_body_response = block_result?;
-
-            let header = self
-                .db
-                .fetch_chain_header_by_block_hash(block_body_response.hash.clone().try_into()?)
-                .await?
-                .ok_or_else(|| {
-                    BlockSyncError::UnknownHeaderHash(format!(
-                        "Peer sent hash ({}) for block header we do not have",
-                        block_body_response.hash.to_hex()
-                    ))
-                })?;
-
-            let current_height = header.height();
-            let header_hash = *header.hash();
-            let timestamp = header.timestamp();
-
-            if header.header().prev_hash != prev_hash {
-                return Err(BlockSyncError::BlockWithoutParent {
-                    expected: prev_hash.to_hex(),
-                    got: header.header().prev_hash.to_hex(),
-                });
-            }
-
-            prev_hash = header_hash;
+        let batch_size = self.config.validation_batch_size.max(1);
+        let mut stream_ended = false;
+        let mut num_resumptions = 0;
+        while !stream_ended {
+            // Download a batch of blocks so that their signatures and range proofs can be verified together
+            let batch_timer = Instant::now();
+            let mut blocks = Vec::with_capacity(batch_size);
+            let mut pending = Vec::with_capacity(batch_size);
+            while blocks.len() < batch_size {
+                let Some(block_result) = block_stream.next().await else {
+                    stream_ended = true;
+                    break;
+                };
+                let block_body_response = match block_result {
+                    Err(status) if status.is_resumable() && num_resumptions < MAX_BLOCK_STREAM_RESUMPTIONS => {
+                        let Some(token) = block_stream.resumption_token() else {
+                            return Err(status.into());
+                        };
+                        num_resumptions += 1;
+                        block_stream = self.resume_block_stream(sync_peer, &mut client, token, status).await?;
+                        last_sync_timer = Instant::now();
+                        continue;
+                    },
+                    block_result => block_result?,
+                };
+                let latency = last_sync_timer.elapsed();
+                avg_latency.add_sample(latency);
+                last_sync_timer = Instant::now();
+
+                let header = self
+                    .db
+                    .fetch_chain_header_by_block_hash(block_body_response.hash.clone().try_into()?)
+                    .await?
+                    .ok_or_else(|| {
+                        BlockSyncError::UnknownHeaderHash(format!(
+                            "Peer sent hash ({}) for block header we do not have",
+                            block_body_response.hash.to_hex()
+                        ))
+                    })?;
+
+                if header.header().prev_hash != prev_hash {
+                    return Err(BlockSyncError::BlockWithoutParent {
+                        expected: prev_hash.to_hex(),
+                        got: header.header().prev_hash.to_hex(),
+                    });
+                }
 
-            let body = block_body_response
-                .body
-                .map(AggregateBody::try_from)
-                .ok_or_else(|| BlockSyncError::InvalidBlockBody("Peer sent empty block".to_string()))?
-                .map_err(BlockSyncError::InvalidBlockBody)?;
+                prev_hash = *header.hash();
+
+                let body = block_body_response
+                    .body
+                    .map(AggregateBody::try_from)
+                    .ok_or_else(|| BlockSyncError::InvalidBlockBody("Peer sent empty block".to_string()))?
+                    .map_err(BlockSyncError::InvalidBlockBody)?;
+
+                debug!(
+                    target: LOG_TARGET,
+                    "Received block body #{} (PoW = {}, {}, latency: {:.2?})",
+                    header.height(),
+                    header.header().pow_algo(),
+                    body.to_counts_string(),
+                    latency
+                );
+
+                let (header, header_accum_data) = header.into_parts();
+                blocks.push(Block::new(header, body));
+                pending.push((header_accum_data, latency));
+            }
 
-            debug!(
-                target: LOG_TARGET,
-                "Validating block body #{} (PoW = {}, {}, latency: {:.2?})",
-                current_height,
-                header.header().pow_algo(),
-                body.to_counts_string(),
-                latency
-            );
+            if blocks.is_empty() {
+                break;
+            }
 
+            // Verify the signatures and range proofs of the batch on the validation threads
             let timer = Instant::now();
-            let (header, header_accum_data) = header.into_parts();
-            let block = Block::new(header, body);
-
-            // Validate the block inside a tokio task
-            let task_block = block.clone();
-            let db = self.db.inner().clone();
             let validator = self.block_validator.clone();
-            let res = task::spawn_blocking(move || {
-                let txn = db.db_read_access()?;
-                let smt = db.smt().clone();
-                validator.validate_body(&*txn, &task_block, smt)
+            let concurrency = self.config.validation_concurrency;
+            let (blocks, stateless_result) = task::spawn_blocking(move || {
+                let res = verify_stateless_concurrently(&*validator, &blocks, concurrency);
+                (blocks, res)
             })
             .await?;
-
-            let block = match res {
-                Ok(block) => block,
-                Err(err @ ValidationError::BadBlockFound { .. }) | Err(err @ ValidationError::FatalStorageError(_)) => {
-                    return Err(err.into());
-                },
-                Err(err) => {
-                    // Add to bad blocks
-                    if let Err(err) = self
-                        .db
-                        .write_transaction()
-                        .delete_orphan(header_hash)
-                        .insert_bad_block(header_hash, current_height, err.to_string())
-                        .commit()
-                        .await
-                    {
-                        error!(target: LOG_TARGET, "Failed to insert bad block: {}", err);
-                    }
-                    return Err(err.into());
-                },
-            };
-
-            let block = ChainBlock::try_construct(Arc::new(block), header_accum_data)
-                .map(Arc::new)
-                .ok_or(BlockSyncError::FailedToConstructChainBlock)?;
-
             debug!(
                 target: LOG_TARGET,
-                "Validated in {:.0?}. Storing block body #{} (PoW = {}, {})",
-                timer.elapsed(),
-                block.header().height,
-                block.header().pow_algo(),
-                block.block().body.to_counts_string(),
+                "Verified signatures and range proofs of {} block(s) in {:.0?}",
+                blocks.len(),
+                timer.elapsed()
             );
-            trace!(
-                target: LOG_TARGET,
-                "{}",block
-            );
-
-            let timer = Instant::now();
-            self.db
-                .write_transaction()
-                .delete_orphan(header_hash)
-                .insert_tip_block_body(block.clone(), self.db.inner().smt())
-                .set_best_block(
+            // A block is only marked as bad if it failed verification on its own. If the failure of a batch could not
+            // be attributed to a block, every block in the batch is fully validated instead.
+            let (mut stateless_failure, verify_each_block) = match stateless_result {
+                Ok(()) => (None, false),
+                Err(ConcurrentVerificationError::InvalidBlock { index, error }) => (Some((index, error)), false),
+                Err(ConcurrentVerificationError::Inconclusive { error, .. }) => {
+                    warn!(
+                        target: LOG_TARGET,
+                        "Batch verification failed ({}) but no block failed on its own. Fully validating each block.",
+                        error
+                    );
+                    (None, true)
+                },
+            };
+            let num_blocks = u32::try_from(blocks.len()).unwrap_or(u32::MAX);
+            let batch_time_per_block = batch_timer.elapsed() / num_blocks;
+
+            // Validate the rest of each block against the chain and store it, strictly in order
+            for (i, (block, (header_accum_data, latency))) in blocks.into_iter().zip(pending).enumerate() {
+                let block_timer = Instant::now();
+                let current_height = block.header.height;
+                let header_hash = block.hash();
+                let timestamp = block.header.timestamp;
+
+                let res = match stateless_failure.take() {
+                    Some((index, err)) if index == i => Err(err),
+                    failure => {
+                        stateless_failure = failure;
+                        // Validate the block inside a tokio task
+                        let db = self.db.inner().clone();
+                        let validator = self.block_validator.clone();
+                        task::spawn_blocking(move || {
+                            let txn = db.db_read_access()?;
+                            let smt = db.smt().clone();
+                            if verify_each_block {
+                                validator.validate_body(&*txn, &block, smt)
+                            } else {
+                                validator.validate_pre_verified_body(&*txn, &block, smt)
+                            }
+                        })
+                        .await?
+                    },
+                };
+
+                let block = match res {
+                    Ok(block) => block,
+                    Err(err @ ValidationError::BadBlockFound { .. }) |
+                    Err(err @ ValidationError::FatalStorageError(_)) => {
+                        return Err(err.into());
+                    },
+                    Err(err) => {
+                        // Add to bad blocks
+                        if let Err(err) = self
+                            .db
+                            .write_transaction()
+                            .delete_orphan(header_hash)
+                            .insert_bad_block(header_hash, current_height, err.to_string())
+                            .commit()
+                            .await
+                        {
+                            error!(target: LOG_TARGET, "Failed to insert bad block: {}", err);
+                        }
+                        return Err(err.into());
+                    },
+                };
+
+                let block = ChainBlock::try_construct(Arc::new(block), header_accum_data)
+                    .map(Arc::new)
+                    .ok_or(BlockSyncError::FailedToConstructChainBlock)?;
+
+                debug!(
+                    target: LOG_TARGET,
+                    "Validated in {:.0?}. Storing block body #{} (PoW = {}, {})",
+                    block_timer.elapsed(),
+                    block.header().height,
+                    block.header().pow_algo(),
+                    block.block().body.to_counts_string(),
+                );
+                trace!(
+                    target: LOG_TARGET,
+                    "{}",block
+                );
+
+                let timer = Instant::now();
+                self.db
+                    .write_transaction()
+                    .delete_orphan(header_hash)
+                    .insert_tip_block_body(block.clone(), self.db.inner().smt())
+                    .set_best_block(
+                        block.height(),
+                        header_hash,
+                        block.accumulated_data().total_accumulated_difficulty,
+                        block.header().prev_hash,
+                        timestamp,
+                    )
+                    .commit()
+                    .await?;
+
+                // Average time between receiving blocks from the peer - used to detect a slow sync peer
+                let last_avg_latency = avg_latency.calculate_average_with_min_samples(5);
+                if let Some(latency) = last_avg_latency {
+                    sync_peer.set_latency(latency);
+                }
+                // Includes this block's share of the batch download and verification time and the time to add the
+                // block to the database, used to show blocks/s on status line
+                sync_peer.add_sample(batch_time_per_block + block_timer.elapsed());
+                self.hooks
+                    .call_on_progress_block_hooks(block.clone(), tip_height, sync_peer);
+
+                debug!(
+                    target: LOG_TARGET,
+                    "Block body #{} added in {:.0?}, Tot_acc_diff {}, Monero {}, SHA3 {}, latency: {:.2?}",
                     block.height(),
-                    header_hash,
-                    block.accumulated_data().total_accumulated_difficulty,
-                    block.header().prev_hash,
-                    timestamp,
-                )
-                .commit()
-                .await?;
-
-            // Average time between receiving blocks from the peer - used to detect a slow sync peer
-            let last_avg_latency = avg_latency.calculate_average_with_min_samples(5);
-            if let Some(latency) = last_avg_latency {
-                sync_peer.set_latency(latency);
-            }
-            // Includes time to add block to database, used to show blocks/s on status line
-            sync_peer.add_sample(last_sync_timer.elapsed());
-            self.hooks
-                .call_on_progress_block_hooks(block.clone(), tip_height, &sync_peer);
-
-            debug!(
-                target: LOG_TARGET,
-                "Block body #{} added in {:.0?}, Tot_acc_diff {}, Monero {}, SHA3 {}, latency: {:.2?}",
-                block.height(),
-                timer.elapsed(),
-                block
-                    .accumulated_data()
-                    .total_accumulated_difficulty,
-                block.accumulated_data().accumulated_randomx_difficulty,
-                block.accumulated_data().accumulated_sha3x_difficulty,
-                latency
-            );
-            if let Some(avg_latency) = last_avg_latency {
-                if avg_latency > max_latency {
-                    return Err(BlockSyncError::MaxLatencyExceeded {
-                        peer: sync_peer.node_id().clone(),
-                        latency: avg_latency,
-                        max_latency,
-                    });
+                    timer.elapsed(),
+                    block
+                        .accumulated_data()
+                        .total_accumulated_difficulty,
+                    block.accumulated_data().accumulated_randomx_difficulty,
+                    block.accumulated_data().accumulated_sha3x_difficulty,
+                    latency
+                );
+                if let Some(avg_latency) = last_avg_latency {
+                    if avg_latency > max_latency {
+                        return Err(BlockSyncError::MaxLatencyExceeded {
+                            peer: sync_peer.node_id().clone(),
+                            latency: avg_latency,
+                            max_latency,
+                        });
+                    }
                 }
-            }
 
-            current_block = Some(block);
+                current_block = Some(block);
+            }
             last_sync_timer = Instant::now();
         }
 
@@ -37,17 +37,16 @@ pub struct BlockchainSyncConfig {
     /// If all sync peers exceed latency, increase allowed latency by this value
     #[serde(with = "serializers::seconds")]
     pub max_latency_increase: Duration,
-    /// Longer ban period for potentially malicious infractions (protocol violations etc.)
-    #[serde(with = "serializers::seconds")]
-    pub ban_period: Duration,
-    /// Short ban period for infractions that are likely not malicious (slow to respond, spotty connections etc)
-    #[serde(with = "serializers::seconds")]
-    pub short_ban_period: Duration,
     /// An allowlist of sync peers from which to sync. No other peers will be selected for sync. If empty, sync peers
     /// are chosen based on their advertised chain metadata.
     pub forced_sync_peers: Vec<NodeId>,
-    /// Number of threads to use for validation
+    /// Number of threads to use for validation. During block sync, the signatures and range proofs of a batch of
+    /// downloaded blocks are verified on this many threads.
     pub validation_concurrency: usize,
+    /// The maximum number of downloaded blocks whose signatures and range proofs are verified together during block
+    /// sync. The blocks are still validated against the chain and stored one at a time, in order. Set to 1 to
+    /// validate each block as soon as it is received.
+    pub validation_batch_size: usize,
     /// The RPC deadline to set on sync clients. If this deadline is reached, a new sync peer will be selected for
     /// sync.
     #[serde(with = "serializers::seconds")]
@@ -59,10 +58,9 @@ impl Default for BlockchainSyncConfig {
         Self {
             initial_max_sync_latency: Duration::from_secs(240), // Syncing many full blocks over tor require this
             max_latency_increase: Duration::from_secs(10),      // Syncing many full blocks over tor require this
-            ban_period: Duration::from_secs(60 * 60 * 2),       // 2 hours
-            short_ban_period: Duration::from_secs(240),         // 4 mins
             forced_sync_peers: Default::default(),
             validation_concurrency: 6,
+            validation_batch_size: 32,
             rpc_deadline: Duration::from_secs(240), // Syncing many full blocks over tor require this
         }
     }
@@ -100,8 +100,26 @@ pub enum BlockHeaderSyncError {
 }
 
 impl BlockHeaderSyncError {
+    /// Returns true if the error was caused by the peer responding too slowly. Slow peers are penalised less severely
+    /// than peers that send invalid data.
+    pub fn is_slow_response(&self) -> bool {
+        match self {
+            BlockHeaderSyncError::MaxLatencyExceeded { .. } => true,
+            BlockHeaderSyncError::RpcError(err) => err.is_timeout(),
+            BlockHeaderSyncError::RpcRequestError(status) => status.is_timeout(),
+            _ => false,
+        }
+    }
+
     pub fn get_ban_reason(&self) -> Option<BanReason> {
         match self {
+            // slow responses are penalised separately and do not result in a ban
+            _ if self.is_slow_response() => None,
+            BlockHeaderSyncError::MaxLatencyExceeded { .. } => None,
+            // the peer is too busy to serve us and may be retried later
+            BlockHeaderSyncError::RpcError(err) if err.is_busy() => None,
+            BlockHeaderSyncError::RpcRequestError(status) if status.is_busy() => None,
+
             // no ban
             BlockHeaderSyncError::NoMoreSyncPeers(_) |
             BlockHeaderSyncError::SyncFailedAllPeers |
@@ -113,12 +131,12 @@ impl BlockHeaderSyncError {
             BlockHeaderSyncError::ChainStorageError(e) => e.get_ban_reason(),
 
             // short ban
-            err @ BlockHeaderSyncError::MaxLatencyExceeded { .. } |
-            err @ BlockHeaderSyncError::RpcError { .. } |
-            err @ BlockHeaderSyncError::RpcRequestError { .. } => Some(BanReason {
-                reason: format!("{}", err),
-                ban_duration: BanPeriod::Short,
-            }),
+            err @ BlockHeaderSyncError::RpcError { .. } | err @ BlockHeaderSyncError::RpcRequestError { .. } => {
+                Some(BanReason {
+                    reason: format!("{}", err),
+                    ban_duration: BanPeriod::Short,
+                })
+            },
 
             // long ban
             err @ BlockHeaderSyncError::ReceivedInvalidHeader(_) |
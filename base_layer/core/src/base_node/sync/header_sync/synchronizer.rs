@@ -32,7 +32,7 @@ use tari_common_types::{chain_metadata::ChainMetadata, types::HashOutput};
 use tari_comms::{
     connectivity::ConnectivityRequester,
     peer_manager::NodeId,
-    protocol::rpc::{RpcClient, RpcError},
+    protocol::rpc::{ClientStreaming, RpcClientBuilder, RpcError, RpcStatus},
     PeerConnection,
 };
 use tari_utilities::hex::Hex;
@@ -49,7 +49,7 @@ use crate::{
     },
     blocks::{BlockHeader, ChainBlock, ChainHeader},
     chain_storage::{async_db::AsyncBlockchainDb, BlockchainBackend, ChainStorageError},
-    common::{rolling_avg::RollingAverageTime, BanPeriod},
+    common::rolling_avg::RollingAverageTime,
     consensus::ConsensusManager,
     proof_of_work::randomx_factory::RandomXFactory,
     proto::{
@@ -61,6 +61,8 @@ use crate::{
 const LOG_TARGET: &str = "c::bn::header_sync";
 
 const MAX_LATENCY_INCREASES: usize = 5;
+/// The number of times an interrupted header stream is resumed from the same peer before the sync attempt fails
+const MAX_HEADER_STREAM_RESUMPTIONS: usize = 2;
 
 pub struct HeaderSynchronizer<'a, B> {
     config: BlockchainSyncConfig,
@@ -154,19 +156,21 @@ impl<'a, B: BlockchainBackend + 'static> HeaderSynchronizer<'a, B> {
         let mut latency_counter = 0usize;
         for node_id in sync_peer_node_ids {
             match self.connect_and_attempt_sync(&node_id, max_latency).await {
-                Ok((peer, sync_result)) => return Ok((peer, sync_result)),
+                Ok((peer, sync_result)) => {
+                    self.peer_ban_manager.reward_peer(&node_id).await;
+                    return Ok((peer, sync_result));
+                },
                 Err(err) => {
-                    let ban_reason = BlockHeaderSyncError::get_ban_reason(&err);
-                    if let Some(reason) = ban_reason {
+                    if err.is_slow_response() {
                         warn!(target: LOG_TARGET, "{}", err);
-                        let duration = match reason.ban_duration {
-                            BanPeriod::Short => self.config.short_ban_period,
-                            BanPeriod::Long => self.config.ban_period,
-                        };
                         self.peer_ban_manager
-                            .ban_peer_if_required(&node_id, reason.reason, duration)
+                            .report_slow_response(&node_id, err.to_string())
                             .await;
                     }
+                    if let Some(reason) = BlockHeaderSyncError::get_ban_reason(&err) {
+                        warn!(target: LOG_TARGET, "{}", err);
+                        self.peer_ban_manager.ban_peer_if_required(&node_id, reason).await;
+                    }
                     if let BlockHeaderSyncError::MaxLatencyExceeded { .. } = err {
                         latency_counter += 1;
                     } else {
@@ -202,12 +206,7 @@ impl<'a, B: BlockchainBackend + 'static> HeaderSynchronizer<'a, B> {
             "Attempting to synchronize headers with `{}`", node_id
         );
 
-        let config = RpcClient::builder()
-            .with_deadline(self.config.rpc_deadline)
-            .with_deadline_grace_period(Duration::from_secs(5));
-        let mut client = conn
-            .connect_rpc_using_builder::<rpc::BaseNodeSyncRpcClient>(config)
-            .await?;
+        let mut client = conn.connect_rpc_using_builder(self.rpc_client_builder()).await?;
 
         let latency = client
             .get_last_request_latency()
@@ -227,6 +226,12 @@ impl<'a, B: BlockchainBackend + 'static> HeaderSynchronizer<'a, B> {
         Ok((sync_peer, sync_result))
     }
 
+    fn rpc_client_builder(&self) -> RpcClientBuilder<rpc::BaseNodeSyncRpcClient> {
+        rpc::BaseNodeSyncRpcClient::builder()
+            .with_deadline(self.config.rpc_deadline)
+            .with_deadline_grace_period(Duration::from_secs(5))
+    }
+
     async fn dial_sync_peer(&self, node_id: &NodeId) -> Result<PeerConnection, BlockHeaderSyncError> {
         let timer = Instant::now();
         debug!(target: LOG_TARGET, "Dialing {} sync peer", node_id);
@@ -634,10 +639,22 @@ impl<'a, B: BlockchainBackend + 'static> HeaderSynchronizer<'a, B> {
         let mut last_total_accumulated_difficulty = U256::zero();
         let mut avg_latency = RollingAverageTime::new(20);
         let mut prev_height: Option<u64> = None;
+        let mut num_resumptions = 0;
         while let Some(header) = header_stream.next().await {
+            let header = match header {
+                Err(status) if status.is_resumable() && num_resumptions < MAX_HEADER_STREAM_RESUMPTIONS => {
+                    num_resumptions += 1;
+                    header_stream = self
+                        .resume_header_stream(sync_peer.node_id(), client, &header_stream, status)
+                        .await?;
+                    last_sync_timer = Instant::now();
+                    continue;
+                },
+                header => header?,
+            };
             let latency = last_sync_timer.elapsed();
             avg_latency.add_sample(latency);
-            let header = BlockHeader::try_from(header?).map_err(BlockHeaderSyncError::ReceivedInvalidHeader)?;
+            let header = BlockHeader::try_from(header).map_err(BlockHeaderSyncError::ReceivedInvalidHeader)?;
             debug!(
                 target: LOG_TARGET,
                 "Validating header #{} (Pow: {}) with hash: ({}). Latency: {:.2?}",
@@ -773,6 +790,31 @@ impl<'a, B: BlockchainBackend + 'static> HeaderSynchronizer<'a, B> {
         Ok(())
     }
 
+    /// Reconnects to the sync peer and continues the header stream from the last header that was received, keeping the
+    /// headers that have already been validated.
+    async fn resume_header_stream(
+        &self,
+        node_id: &NodeId,
+        client: &mut rpc::BaseNodeSyncRpcClient,
+        header_stream: &ClientStreaming<ProtoBlockHeader>,
+        status: RpcStatus,
+    ) -> Result<ClientStreaming<ProtoBlockHeader>, BlockHeaderSyncError> {
+        let Some(token) = header_stream.resumption_token() else {
+            return Err(status.into());
+        };
+        warn!(
+            target: LOG_TARGET,
+            "Header stream from peer `{}` was interrupted after {} header(s) ({}). Resuming.",
+            node_id,
+            token.position(),
+            status
+        );
+        let mut conn = self.dial_sync_peer(node_id).await?;
+        *client = conn.connect_rpc_using_builder(self.rpc_client_builder()).await?;
+        let header_stream = client.resume_stream(token).await?;
+        Ok(header_stream)
+    }
+
     async fn commit_pending_headers(&mut self) -> Result<ChainHeader, BlockHeaderSyncError> {
         let chain_headers = self.header_validator.take_valid_headers();
         let num_headers = chain_headers.len();
@@ -121,8 +121,26 @@ impl From<RangeProofError> for HorizonSyncError {
 }
 
 impl HorizonSyncError {
+    /// Returns true if the error was caused by the peer responding too slowly. Slow peers are penalised less severely
+    /// than peers that send invalid data.
+    pub fn is_slow_response(&self) -> bool {
+        match self {
+            HorizonSyncError::MaxLatencyExceeded { .. } => true,
+            HorizonSyncError::RpcError(err) => err.is_timeout(),
+            HorizonSyncError::RpcStatus(status) => status.is_timeout(),
+            _ => false,
+        }
+    }
+
     pub fn get_ban_reason(&self) -> Option<BanReason> {
         match self {
+            // slow responses are penalised separately and do not result in a ban
+            _ if self.is_slow_response() => None,
+            HorizonSyncError::MaxLatencyExceeded { .. } => None,
+            // the peer is too busy to serve us and may be retried later
+            HorizonSyncError::RpcError(err) if err.is_busy() => None,
+            HorizonSyncError::RpcStatus(status) if status.is_busy() => None,
+
             // no ban
             HorizonSyncError::ChainStorageError(e) => e.get_ban_reason(),
             HorizonSyncError::NoSyncPeers |
@@ -134,9 +152,7 @@ impl HorizonSyncError {
             HorizonSyncError::JoinError(_) => None,
 
             // short ban
-            err @ HorizonSyncError::MaxLatencyExceeded { .. } |
-            err @ HorizonSyncError::RpcError { .. } |
-            err @ HorizonSyncError::RpcStatus { .. } => Some(BanReason {
+            err @ HorizonSyncError::RpcError { .. } | err @ HorizonSyncError::RpcStatus { .. } => Some(BanReason {
                 reason: format!("{}", err),
                 ban_duration: BanPeriod::Short,
             }),
@@ -23,6 +23,7 @@
 use std::{
     cmp,
     convert::{TryFrom, TryInto},
+    iter,
     sync::Arc,
     time::{Duration, Instant},
 };
@@ -30,7 +31,12 @@ use std::{
 use futures::StreamExt;
 use log::*;
 use tari_common_types::types::{Commitment, FixedHash, RangeProofService};
-use tari_comms::{connectivity::ConnectivityRequester, peer_manager::NodeId, protocol::rpc::RpcClient, PeerConnection};
+use tari_comms::{
+    connectivity::ConnectivityRequester,
+    peer_manager::NodeId,
+    protocol::rpc::{ClientStreaming, ResumptionToken, RpcClientBuilder, RpcStatus},
+    PeerConnection,
+};
 use tari_crypto::commitment::HomomorphicCommitment;
 use tari_mmr::sparse_merkle_tree::{DeleteResult, NodeKey, ValueHash};
 use tari_utilities::{hex::Hex, ByteArray};
@@ -49,7 +55,7 @@ use crate::{
     },
     blocks::{BlockHeader, ChainHeader, UpdateBlockAccumulatedData},
     chain_storage::{async_db::AsyncBlockchainDb, BlockchainBackend, ChainStorageError, MmrTree},
-    common::{rolling_avg::RollingAverageTime, BanPeriod},
+    common::rolling_avg::RollingAverageTime,
     consensus::ConsensusManager,
     proto::base_node::{sync_utxos_response::Txo, SyncKernelsRequest, SyncUtxosRequest, SyncUtxosResponse},
     transactions::transaction_components::{
@@ -66,6 +72,8 @@ use crate::{
 const LOG_TARGET: &str = "c::bn::state_machine_service::states::horizon_state_sync";
 
 const MAX_LATENCY_INCREASES: usize = 5;
+/// The number of times an interrupted output stream is resumed before the sync attempt fails
+const MAX_OUTPUT_STREAM_RESUMPTIONS: usize = 2;
 
 pub struct HorizonStateSynchronization<'a, B> {
     config: BlockchainSyncConfig,
@@ -179,22 +187,28 @@ impl<'a, B: BlockchainBackend + 'static> HorizonStateSynchronization<'a, B> {
         );
         let mut latency_counter = 0usize;
         for node_id in sync_peer_node_ids {
-            match self.connect_and_attempt_sync(&node_id, to_header).await {
-                Ok(_) => return Ok(()),
+            // An interrupted output stream may be resumed on another sync peer, which is then responsible for the
+            // result
+            let mut responsible_peer = node_id;
+            let result = self.connect_and_attempt_sync(&mut responsible_peer, to_header).await;
+            let node_id = responsible_peer;
+            match result {
+                Ok(_) => {
+                    self.peer_ban_manager.reward_peer(&node_id).await;
+                    return Ok(());
+                },
                 // Try another peer
                 Err(err) => {
-                    let ban_reason = HorizonSyncError::get_ban_reason(&err);
-
-                    if let Some(reason) = ban_reason {
-                        let duration = match reason.ban_duration {
-                            BanPeriod::Short => self.config.short_ban_period,
-                            BanPeriod::Long => self.config.ban_period,
-                        };
+                    if err.is_slow_response() {
                         warn!(target: LOG_TARGET, "{}", err);
                         self.peer_ban_manager
-                            .ban_peer_if_required(&node_id, reason.reason, duration)
+                            .report_slow_response(&node_id, err.to_string())
                             .await;
                     }
+                    if let Some(reason) = HorizonSyncError::get_ban_reason(&err) {
+                        warn!(target: LOG_TARGET, "{}", err);
+                        self.peer_ban_manager.ban_peer_if_required(&node_id, reason).await;
+                    }
                     if let HorizonSyncError::MaxLatencyExceeded { .. } = err {
                         latency_counter += 1;
                     } else {
@@ -213,19 +227,24 @@ impl<'a, B: BlockchainBackend + 'static> HorizonStateSynchronization<'a, B> {
         }
     }
 
+    /// Syncs the horizon state from the peer. If an interrupted output stream is resumed on another sync peer,
+    /// `node_id` is set to that peer.
     async fn connect_and_attempt_sync(
         &mut self,
-        node_id: &NodeId,
+        node_id: &mut NodeId,
         to_header: &BlockHeader,
     ) -> Result<(), HorizonSyncError> {
         // Connect
-        let (mut client, sync_peer) = self.connect_sync_peer(node_id).await?;
+        let (mut client, mut sync_peer) = self.connect_sync_peer(node_id).await?;
 
         // Perform horizon sync
         debug!(target: LOG_TARGET, "Check if pruning is needed");
         self.prune_if_needed().await?;
-        self.sync_kernels_and_outputs(sync_peer.clone(), &mut client, to_header)
-            .await?;
+        let result = self
+            .sync_kernels_and_outputs(&mut sync_peer, &mut client, to_header)
+            .await;
+        *node_id = sync_peer.node_id().clone();
+        result?;
 
         // Validate and finalize horizon sync
         self.finalize_horizon_sync(&sync_peer).await?;
@@ -249,13 +268,7 @@ impl<'a, B: BlockchainBackend + 'static> HorizonStateSynchronization<'a, B> {
             "Attempting to synchronize horizon state with `{}`", node_id
         );
 
-        let config = RpcClient::builder()
-            .with_deadline(self.config.rpc_deadline)
-            .with_deadline_grace_period(Duration::from_secs(5));
-
-        let mut client = conn
-            .connect_rpc_using_builder::<rpc::BaseNodeSyncRpcClient>(config)
-            .await?;
+        let mut client = conn.connect_rpc_using_builder(self.rpc_client_builder()).await?;
 
         let latency = client
             .get_last_request_latency()
@@ -286,9 +299,15 @@ impl<'a, B: BlockchainBackend + 'static> HorizonStateSynchronization<'a, B> {
         Ok(conn)
     }
 
+    fn rpc_client_builder(&self) -> RpcClientBuilder<BaseNodeSyncRpcClient> {
+        BaseNodeSyncRpcClient::builder()
+            .with_deadline(self.config.rpc_deadline)
+            .with_deadline_grace_period(Duration::from_secs(5))
+    }
+
     async fn sync_kernels_and_outputs(
         &mut self,
-        sync_peer: SyncPeer,
+        sync_peer: &mut SyncPeer,
         client: &mut rpc::BaseNodeSyncRpcClient,
         to_header: &BlockHeader,
     ) -> Result<(), HorizonSyncError> {
@@ -561,11 +580,82 @@ impl<'a, B: BlockchainBackend + 'static> HorizonStateSynchronization<'a, B> {
         Ok(())
     }
 
+    /// Continues an interrupted output stream from the last output that was received, keeping the outputs that have
+    /// already been received. The stream is resumed from the same peer if possible, otherwise from another sync peer,
+    /// in which case `sync_peer` and `client` are replaced with that peer.
+    async fn resume_output_stream(
+        &self,
+        sync_peer: &mut SyncPeer,
+        client: &mut BaseNodeSyncRpcClient,
+        token: ResumptionToken<SyncUtxosResponse>,
+        status: RpcStatus,
+    ) -> Result<ClientStreaming<SyncUtxosResponse>, HorizonSyncError> {
+        warn!(
+            target: LOG_TARGET,
+            "Output stream from peer `{}` was interrupted after {} TXO(s) ({}). Resuming.",
+            sync_peer.node_id(),
+            token.position(),
+            status
+        );
+        // Pruned peers omit spent outputs that have been pruned, so only peers with the same pruned height return the
+        // same stream of outputs
+        let pruned_height = sync_peer.claimed_chain_metadata().pruned_height();
+        let candidates = iter::once(sync_peer.clone())
+            .chain(
+                self.sync_peers
+                    .iter()
+                    .filter(|p| {
+                        p.node_id() != sync_peer.node_id() &&
+                            p.claimed_chain_metadata().pruned_height() == pruned_height
+                    })
+                    .cloned(),
+            )
+            .collect::<Vec<_>>();
+        for candidate in candidates {
+            match self.try_resume_output_stream(candidate.node_id(), token.clone()).await {
+                Ok((new_client, output_stream)) => {
+                    if candidate.node_id() != sync_peer.node_id() {
+                        info!(
+                            target: LOG_TARGET,
+                            "Resumed output stream from peer `{}` on peer `{}`",
+                            sync_peer.node_id(),
+                            candidate.node_id()
+                        );
+                        *sync_peer = candidate;
+                    }
+                    *client = new_client;
+                    return Ok(output_stream);
+                },
+                Err(err) => {
+                    warn!(
+                        target: LOG_TARGET,
+                        "Failed to resume output stream on peer `{}`: {}",
+                        candidate.node_id(),
+                        err
+                    );
+                },
+            }
+        }
+
+        Err(status.into())
+    }
+
+    async fn try_resume_output_stream(
+        &self,
+        node_id: &NodeId,
+        token: ResumptionToken<SyncUtxosResponse>,
+    ) -> Result<(BaseNodeSyncRpcClient, ClientStreaming<SyncUtxosResponse>), HorizonSyncError> {
+        let mut conn = self.dial_sync_peer(node_id).await?;
+        let mut client = conn.connect_rpc_using_builder(self.rpc_client_builder()).await?;
+        let output_stream = client.resume_stream(token).await?;
+        Ok((client, output_stream))
+    }
+
     // Synchronize outputs, returning true if any keys were deleted from the output SMT.
     #[allow(clippy::too_many_lines)]
     async fn synchronize_outputs(
         &mut self,
-        mut sync_peer: SyncPeer,
+        sync_peer: &mut SyncPeer,
         client: &mut rpc::BaseNodeSyncRpcClient,
         to_header: &BlockHeader,
     ) -> Result<(), HorizonSyncError> {
@@ -626,10 +716,22 @@ impl<'a, B: BlockchainBackend + 'static> HorizonStateSynchronization<'a, B> {
         let mut avg_latency = RollingAverageTime::new(20);
 
         let mut inputs_to_delete = Vec::new();
+        let mut num_resumptions = 0;
         while let Some(response) = output_stream.next().await {
+            let res: SyncUtxosResponse = match response {
+                Err(status) if status.is_resumable() && num_resumptions < MAX_OUTPUT_STREAM_RESUMPTIONS => {
+                    let Some(token) = output_stream.resumption_token() else {
+                        return Err(status.into());
+                    };
+                    num_resumptions += 1;
+                    output_stream = self.resume_output_stream(sync_peer, client, token, status).await?;
+                    last_sync_timer = Instant::now();
+                    continue;
+                },
+                response => response?,
+            };
             let latency = last_sync_timer.elapsed();
             avg_latency.add_sample(latency);
-            let res: SyncUtxosResponse = response?;
 
             let output_header_hash = FixedHash::try_from(res.mined_header)
                 .map_err(|_| HorizonSyncError::IncorrectResponse("Peer sent no mined header".into()))?;
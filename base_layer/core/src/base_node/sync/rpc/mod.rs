@@ -31,7 +31,25 @@ pub use service::BaseNodeSyncRpcService;
 #[cfg(test)]
 mod tests;
 
+#[cfg(feature = "base_node")]
+use std::time::Duration;
+
 use tari_comms::protocol::rpc::{Request, Response, RpcStatus, Streaming};
+#[cfg(feature = "base_node")]
+use tari_comms::{
+    peer_manager::PeerFeatures,
+    protocol::rpc::middleware::{
+        AccessControl,
+        ConcurrencyLimit,
+        LatencyHistogram,
+        RateLimit,
+        RequestConcurrencyLimit,
+        RequestLogger,
+        RequestRateLimit,
+        RpcAccessPolicy,
+        RpcMiddlewareStack,
+    },
+};
 use tari_comms_rpc_macros::tari_rpc;
 
 #[cfg(feature = "base_node")]
@@ -101,3 +119,37 @@ pub fn create_base_node_sync_rpc_service<B: BlockchainBackend + 'static>(
 ) -> BaseNodeSyncRpcServer<BaseNodeSyncRpcService<B>> {
     BaseNodeSyncRpcServer::new(BaseNodeSyncRpcService::new(db, base_node_service))
 }
+
+/// The middleware applied to the base node sync RPC service.
+///
+/// Only base nodes may sync blocks, headers, kernels and UTXOs or find a chain split. The limits are deliberately
+/// generous: an honest node syncing from us makes a handful of these requests per sync round and streams from one
+/// method at a time. Requests over a limit are rejected as busy, so the syncing peer tries again later or syncs from
+/// another peer without penalising us.
+#[cfg(feature = "base_node")]
+pub fn base_node_sync_rpc_middleware() -> RpcMiddlewareStack {
+    use base_node_sync_service_methods::{FIND_CHAIN_SPLIT, SYNC_BLOCKS, SYNC_HEADERS, SYNC_KERNELS, SYNC_UTXOS};
+    const ONE_MINUTE: Duration = Duration::from_secs(60);
+
+    let base_node_only = RpcAccessPolicy::new()
+        .require_features(PeerFeatures::COMMUNICATION_NODE)
+        .for_methods([SYNC_BLOCKS, SYNC_HEADERS, FIND_CHAIN_SPLIT, SYNC_KERNELS, SYNC_UTXOS]);
+    let rate_limit = [SYNC_BLOCKS, SYNC_HEADERS, SYNC_KERNELS, SYNC_UTXOS]
+        .into_iter()
+        .fold(RateLimit::new(), |rate_limit, method| {
+            rate_limit.with_method_limit(method, RequestRateLimit::per_peer(30, ONE_MINUTE))
+        })
+        .with_method_limit(FIND_CHAIN_SPLIT, RequestRateLimit::per_peer(60, ONE_MINUTE));
+    let concurrency_limit = [SYNC_BLOCKS, SYNC_HEADERS, SYNC_KERNELS, SYNC_UTXOS]
+        .into_iter()
+        .fold(ConcurrencyLimit::new(), |concurrency_limit, method| {
+            concurrency_limit.with_method_limit(method, RequestConcurrencyLimit::per_peer(2))
+        });
+
+    RpcMiddlewareStack::new()
+        .layer(RequestLogger::new())
+        .layer(LatencyHistogram::new())
+        .layer(AccessControl::new(base_node_only))
+        .layer(rate_limit)
+        .layer(concurrency_limit)
+}
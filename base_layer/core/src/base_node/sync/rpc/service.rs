@@ -93,7 +93,7 @@ impl<B: BlockchainBackend + 'static> BaseNodeSyncRpcService<B> {
         debug!(target: LOG_TARGET, "Number of active sync sessions: {}", lock.len());
 
         if lock.iter().any(|p| p.upgrade().filter(|p| **p == peer).is_some()) {
-            return Err(RpcStatus::forbidden(
+            return Err(RpcStatus::busy(
                 "Existing sync session found for this client. Only a single session is permitted",
             ));
         }
@@ -116,6 +116,8 @@ impl<B: BlockchainBackend + 'static> BaseNodeSyncService for BaseNodeSyncRpcServ
         request: Request<SyncBlocksRequest>,
     ) -> Result<Streaming<proto::base_node::BlockBodyResponse>, RpcStatus> {
         let peer_node_id = request.context().peer_node_id().clone();
+        // Blocks are streamed in height order, so a resumed stream can start from the next height
+        let resume_from = request.resume_from();
         let message = request.into_message();
         let mut block_event_stream = self.base_node_service.get_block_event_stream();
 
@@ -165,6 +167,10 @@ impl<B: BlockchainBackend + 'static> BaseNodeSyncService for BaseNodeSyncRpcServ
                 start_height, end_height
             )));
         }
+        let start_height = start_height.saturating_add(resume_from);
+        if start_height > end_height {
+            return Ok(Streaming::empty().with_offset(resume_from));
+        }
 
         debug!(
             target: LOG_TARGET,
@@ -270,7 +276,7 @@ impl<B: BlockchainBackend + 'static> BaseNodeSyncService for BaseNodeSyncRpcServ
             .instrument(span),
         );
 
-        Ok(Streaming::new(rx))
+        Ok(Streaming::new(rx).with_offset(resume_from))
     }
 
     #[instrument(level = "trace", name = "sync_rpc::sync_headers", skip(self), err)]
@@ -281,6 +287,8 @@ impl<B: BlockchainBackend + 'static> BaseNodeSyncService for BaseNodeSyncRpcServ
     ) -> Result<Streaming<proto::core::BlockHeader>, RpcStatus> {
         let db = self.db();
         let peer_node_id = request.context().peer_node_id().clone();
+        // Headers are streamed in height order, so a resumed stream can start from the next height
+        let resume_from = request.resume_from();
         let message = request.into_message();
         let hash = message
             .start_hash
@@ -297,9 +305,11 @@ impl<B: BlockchainBackend + 'static> BaseNodeSyncService for BaseNodeSyncRpcServ
             let tip_header = db.fetch_tip_header().await.rpc_status_internal_error(LOG_TARGET)?;
             count = tip_header.height().saturating_sub(start_header.height);
         }
+        count = count.saturating_sub(resume_from);
+        let start_height = start_header.height.saturating_add(resume_from);
         // if its the start(tip_header == tip), return empty
         if count == 0 {
-            return Ok(Streaming::empty());
+            return Ok(Streaming::empty().with_offset(resume_from));
         }
 
         #[allow(clippy::cast_possible_truncation)]
@@ -308,7 +318,7 @@ impl<B: BlockchainBackend + 'static> BaseNodeSyncService for BaseNodeSyncRpcServ
             target: LOG_TARGET,
             "Initiating header sync with peer `{}` from height {} to {} (chunk_size={})",
             peer_node_id,
-            start_header.height,
+            start_height,
             count,
             chunk_size
         );
@@ -317,8 +327,8 @@ impl<B: BlockchainBackend + 'static> BaseNodeSyncService for BaseNodeSyncRpcServ
         let (tx, rx) = mpsc::channel(chunk_size);
         let span = span!(Level::TRACE, "sync_rpc::sync_headers::inner_worker");
         let iter = NonOverlappingIntegerPairIter::new(
-            start_header.height.saturating_add(1),
-            start_header.height.saturating_add(count).saturating_add(1),
+            start_height.saturating_add(1),
+            start_height.saturating_add(count).saturating_add(1),
             chunk_size,
         )
         .map_err(|e| RpcStatus::bad_request(&e))?;
@@ -371,7 +381,7 @@ impl<B: BlockchainBackend + 'static> BaseNodeSyncService for BaseNodeSyncRpcServ
             .instrument(span),
         );
 
-        Ok(Streaming::new(rx))
+        Ok(Streaming::new(rx).with_offset(resume_from))
     }
 
     #[instrument(level = "trace", skip(self), err)]
@@ -607,10 +617,12 @@ impl<B: BlockchainBackend + 'static> BaseNodeSyncService for BaseNodeSyncRpcServ
         );
 
         let session_token = self.try_add_exclusive_session(peer_node_id.clone()).await?;
+        // The task skips the TXOs that were received before the stream was interrupted
+        let resume_from = request.resume_from();
         let (tx, rx) = mpsc::channel(200);
         let task = SyncUtxosTask::new(self.db(), session_token);
         task.run(request, tx).await?;
 
-        Ok(Streaming::new(rx))
+        Ok(Streaming::new(rx).with_offset(resume_from))
     }
 }
@@ -63,6 +63,7 @@ where B: BlockchainBackend + 'static
         request: Request<SyncUtxosRequest>,
         mut tx: mpsc::Sender<Result<SyncUtxosResponse, RpcStatus>>,
     ) -> Result<(), RpcStatus> {
+        let resume_from = request.resume_from();
         let msg = request.into_message();
         let start_hash = msg
             .start_header_hash
@@ -101,7 +102,10 @@ where B: BlockchainBackend + 'static
                 target: LOG_TARGET,
                 "Starting UTXO stream for peer '{}'", self.peer_node_id
             );
-            if let Err(err) = self.start_streaming(&mut tx, start_header, end_header).await {
+            if let Err(err) = self
+                .start_streaming(&mut tx, start_header, end_header, resume_from)
+                .await
+            {
                 debug!(
                     target: LOG_TARGET,
                     "UTXO stream errored for peer '{}': {}", self.peer_node_id, err
@@ -125,6 +129,7 @@ where B: BlockchainBackend + 'static
         tx: &mut mpsc::Sender<Result<SyncUtxosResponse, RpcStatus>>,
         mut current_header: BlockHeader,
         end_header: BlockHeader,
+        mut num_to_skip: u64,
     ) -> Result<(), RpcStatus> {
         debug!(
             target: LOG_TARGET,
@@ -293,7 +298,10 @@ where B: BlockchainBackend + 'static
                 );
                 txos.append(&mut pruned_genesis_block_outputs);
             }
-            let txos = txos.into_iter();
+            // Skip the TXOs that the peer received before its stream was interrupted
+            let num_skipped = usize::try_from(num_to_skip).unwrap_or(usize::MAX).min(txos.len());
+            num_to_skip = num_to_skip.saturating_sub(num_skipped as u64);
+            let txos = txos.into_iter().skip(num_skipped);
 
             // Ensure task stops if the peer prematurely stops their RPC session
             let txos_len = txos.len();
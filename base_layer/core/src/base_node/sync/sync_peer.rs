@@ -27,7 +27,7 @@ use std::{
 };
 
 use tari_common_types::chain_metadata::ChainMetadata;
-use tari_comms::peer_manager::NodeId;
+use tari_comms::peer_manager::{NodeId, PeerReputation};
 
 use crate::{base_node::chain_metadata_service::PeerChainMetadata, common::rolling_avg::RollingAverageTime};
 
@@ -35,6 +35,7 @@ use crate::{base_node::chain_metadata_service::PeerChainMetadata, common::rollin
 pub struct SyncPeer {
     peer_metadata: PeerChainMetadata,
     avg_latency: RollingAverageTime,
+    reputation: i32,
 }
 
 impl SyncPeer {
@@ -67,6 +68,29 @@ impl SyncPeer {
     pub fn calc_avg_latency(&self) -> Option<Duration> {
         self.avg_latency.calculate_average()
     }
+
+    /// The reputation score of the peer at the time it was selected for sync
+    pub fn reputation(&self) -> i32 {
+        self.reputation
+    }
+
+    pub(crate) fn set_reputation(&mut self, reputation: i32) -> &mut Self {
+        self.reputation = reputation;
+        self
+    }
+
+    /// Orders peers with a better reputation first, then by lowest latency. Peers without a latency go to the end.
+    pub fn cmp_reputation_and_latency(&self, other: &Self) -> Ordering {
+        PeerReputation::score_tier(other.reputation)
+            .cmp(&PeerReputation::score_tier(self.reputation))
+            .then_with(|| match (self.latency(), other.latency()) {
+                (None, None) => Ordering::Equal,
+                // No latency goes to the end
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(la), Some(lb)) => la.cmp(&lb),
+            })
+    }
 }
 
 impl From<PeerChainMetadata> for SyncPeer {
@@ -74,6 +98,7 @@ impl From<PeerChainMetadata> for SyncPeer {
         Self {
             peer_metadata,
             avg_latency: RollingAverageTime::new(20),
+            reputation: 0,
         }
     }
 }
@@ -82,12 +107,13 @@ impl Display for SyncPeer {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "Node ID: {}, Chain metadata: {}, Latency: {}",
+            "Node ID: {}, Chain metadata: {}, Latency: {}, Reputation: {}",
             self.node_id(),
             self.claimed_chain_metadata(),
             self.latency()
                 .map(|d| format!("{:.2?}", d))
-                .unwrap_or_else(|| "--".to_string())
+                .unwrap_or_else(|| "--".to_string()),
+            self.reputation
         )
     }
 }
@@ -101,21 +127,11 @@ impl Eq for SyncPeer {}
 
 impl Ord for SyncPeer {
     fn cmp(&self, other: &Self) -> Ordering {
-        let mut result = self
-            .peer_metadata
+        self.peer_metadata
             .claimed_chain_metadata()
             .accumulated_difficulty()
-            .cmp(&other.peer_metadata.claimed_chain_metadata().accumulated_difficulty());
-        if result == Ordering::Equal {
-            match (self.latency(), other.latency()) {
-                (None, None) => result = Ordering::Equal,
-                // No latency goes to the end
-                (Some(_), None) => result = Ordering::Less,
-                (None, Some(_)) => result = Ordering::Greater,
-                (Some(la), Some(lb)) => result = la.cmp(&lb),
-            }
-        }
-        result
+            .cmp(&other.peer_metadata.claimed_chain_metadata().accumulated_difficulty())
+            .then_with(|| self.cmp_reputation_and_latency(other))
     }
 }
 
@@ -180,5 +196,26 @@ mod test {
                 assert_eq!(peers.pop().unwrap().latency(), None);
             }
         }
+
+        #[test]
+        fn it_sorts_by_reputation_before_latency() {
+            let mut peers = vec![
+                generate_peer(Some(1)),
+                generate_peer(Some(2)),
+                generate_peer(Some(3)),
+                generate_peer(None),
+            ];
+            peers[1].set_reputation(-60);
+            peers[2].set_reputation(50);
+            peers[3].set_reputation(10);
+
+            peers.sort();
+
+            let order = peers
+                .iter()
+                .map(|p| (p.reputation(), p.latency().map(|l| l.as_millis())))
+                .collect::<Vec<_>>();
+            assert_eq!(order, vec![(50, Some(3)), (0, Some(1)), (10, None), (-60, Some(2))]);
+        }
     }
 }
@@ -39,7 +39,7 @@ use crate::{
 
 const LOG_TARGET: &str = "c::bn::acc_data";
 
-#[derive(Default, Debug, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
 pub struct BlockAccumulatedData {
     pub(crate) kernels: PrunedHashSet,
     pub(crate) kernel_sum: Commitment,
@@ -50,7 +50,7 @@ use crate::{
     },
     chain_storage::{
         blockchain_database::MmrRoots,
-        utxo_mined_info::{InputMinedInfo, OutputMinedInfo},
+        utxo_mined_info::{HistoricalOutputState, InputMinedInfo, OutputMinedInfo},
         BlockAddResult,
         BlockchainBackend,
         BlockchainDatabase,
@@ -60,6 +60,7 @@ use crate::{
         DbTransaction,
         HorizonData,
         MmrTree,
+        OutputIndex,
         TargetDifficulties,
     },
     common::rolling_vec::RollingVec,
@@ -286,6 +287,12 @@ impl<B: BlockchainBackend + 'static> AsyncBlockchainDb<B> {
 
     make_async_fn!(fetch_template_registrations<T: RangeBounds<u64>>(range: T) -> Vec<TemplateRegistrationEntry>, "fetch_template_registrations");
 
+    make_async_fn!(fetch_outputs_by_index<T: RangeBounds<u64>>(index: OutputIndex, range: T) -> Vec<OutputMinedInfo>, "fetch_outputs_by_index");
+
+    make_async_fn!(fetch_output_at_height(commitment: Commitment, height: u64) -> Option<HistoricalOutputState>, "fetch_output_at_height");
+
+    make_async_fn!(calculate_output_smt_at_height(height: u64) -> OutputSmt, "calculate_output_smt_at_height");
+
     make_async_fn!(swap_to_highest_pow_chain() -> (), "swap to highest proof-of-work chain");
 }
 
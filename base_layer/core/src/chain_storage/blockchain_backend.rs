@@ -19,7 +19,9 @@ use crate::{
         HorizonData,
         InputMinedInfo,
         MmrTree,
+        OutputIndex,
         OutputMinedInfo,
+        OutputSmtDelta,
         Reorg,
     },
     transactions::transaction_components::{TransactionInput, TransactionKernel, TransactionOutput},
@@ -181,6 +183,17 @@ pub trait BlockchainBackend: Send + Sync {
         start_height: u64,
         end_height: u64,
     ) -> Result<Vec<TemplateRegistrationEntry>, ChainStorageError>;
+    /// Returns all outputs in the given output index that were mined within (inclusive) the given height range, ordered
+    /// by height. Returns an error if output indexing is not enabled.
+    fn fetch_outputs_by_index(
+        &self,
+        index: &OutputIndex,
+        start_height: u64,
+        end_height: u64,
+    ) -> Result<Vec<OutputMinedInfo>, ChainStorageError>;
+    /// Returns the changes that the block at the given height made to the output SMT, if they were stored. Deltas are
+    /// only stored while `archival` is enabled.
+    fn fetch_output_smt_delta(&self, height: u64) -> Result<Option<OutputSmtDelta>, ChainStorageError>;
     /// Calculates the tip utxo smt
     fn calculate_tip_smt(&self) -> Result<OutputSmt, ChainStorageError>;
 }
@@ -63,13 +63,14 @@ use crate::{
     },
     chain_storage::{
         consts::{
+            BLOCKCHAIN_DATABASE_MAX_OUTPUT_SMT_REWIND_DEPTH,
             BLOCKCHAIN_DATABASE_ORPHAN_STORAGE_CAPACITY,
             BLOCKCHAIN_DATABASE_PRUNED_MODE_PRUNING_INTERVAL,
             BLOCKCHAIN_DATABASE_PRUNING_HORIZON,
         },
         db_transaction::{DbKey, DbTransaction, DbValue},
         error::ChainStorageError,
-        utxo_mined_info::OutputMinedInfo,
+        utxo_mined_info::{HistoricalOutputState, OutputMinedInfo},
         BlockAddResult,
         BlockchainBackend,
         DbBasicStats,
@@ -79,6 +80,7 @@ use crate::{
         MmrTree,
         Optional,
         OrNotFound,
+        OutputIndex,
         Reorg,
         TargetDifficulties,
     },
@@ -106,6 +108,11 @@ use crate::{
 };
 
 const LOG_TARGET: &str = "c::cs::database";
+// The number of blocks to undo while holding the read lock when calculating a historical output SMT
+#[cfg(not(test))]
+const OUTPUT_SMT_REWIND_BATCH_SIZE: u64 = 100;
+#[cfg(test)]
+const OUTPUT_SMT_REWIND_BATCH_SIZE: u64 = 2;
 
 /// Configuration for the BlockchainDatabase.
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
@@ -116,6 +123,15 @@ pub struct BlockchainDatabaseConfig {
     pub pruning_interval: u64,
     pub track_reorgs: bool,
     pub cleanup_orphans_at_startup: bool,
+    /// Maintain secondary indexes of outputs by script hash, output type, sidechain feature and sender offset public
+    /// key
+    pub index_outputs: bool,
+    /// The maximum number of blocks below the tip that the output SMT can be calculated at. Each block is undone on a
+    /// copy of the tip SMT, so deeper queries are rejected. Archival nodes are not limited.
+    pub max_output_smt_rewind_depth: u64,
+    /// Store the changes that every block makes to the output SMT, so that the output SMT can be calculated at any
+    /// height. Requires a `pruning_horizon` of 0.
+    pub archival: bool,
 }
 
 impl Default for BlockchainDatabaseConfig {
@@ -126,6 +142,9 @@ impl Default for BlockchainDatabaseConfig {
             pruning_interval: BLOCKCHAIN_DATABASE_PRUNED_MODE_PRUNING_INTERVAL,
             track_reorgs: false,
             cleanup_orphans_at_startup: false,
+            index_outputs: false,
+            max_output_smt_rewind_depth: BLOCKCHAIN_DATABASE_MAX_OUTPUT_SMT_REWIND_DEPTH,
+            archival: false,
         }
     }
 }
@@ -225,6 +244,13 @@ where B: BlockchainBackend
         smt: Arc<RwLock<OutputSmt>>,
     ) -> Result<Self, ChainStorageError> {
         debug!(target: LOG_TARGET, "BlockchainDatabase config: {:?}", config);
+        if config.archival && config.pruning_horizon > 0 {
+            return Err(ChainStorageError::InvalidArguments {
+                func: "BlockchainDatabase::new",
+                arg: "config",
+                message: "An archival node cannot have a pruning horizon".to_string(),
+            });
+        }
         let is_empty = db.is_empty()?;
         let blockchain_db = BlockchainDatabase {
             db: Arc::new(RwLock::new(db)),
@@ -301,6 +327,9 @@ where B: BlockchainBackend
             blockchain_db.clear_all_reorgs()?;
         }
 
+        blockchain_db.store_output_indexing(config.index_outputs)?;
+        blockchain_db.store_archival(config.archival)?;
+
         Ok(blockchain_db)
     }
 
@@ -1089,6 +1118,20 @@ where B: BlockchainBackend
         store_pruning_horizon(&mut *db, pruning_horizon)
     }
 
+    fn store_output_indexing(&self, enabled: bool) -> Result<(), ChainStorageError> {
+        let mut db = self.db_write_access()?;
+        let mut txn = DbTransaction::new();
+        txn.set_output_indexing(enabled);
+        db.write(txn)
+    }
+
+    fn store_archival(&self, enabled: bool) -> Result<(), ChainStorageError> {
+        let mut db = self.db_write_access()?;
+        let mut txn = DbTransaction::new();
+        txn.set_archival(enabled);
+        db.write(txn)
+    }
+
     /// Prunes the blockchain up to and including the given height
     pub fn prune_to_height(&self, height: u64) -> Result<(), ChainStorageError> {
         let mut db = self.db_write_access()?;
@@ -1292,6 +1335,149 @@ where B: BlockchainBackend
         let (start, end) = (start.unwrap_or(0), end.unwrap());
         db.fetch_template_registrations(start, end)
     }
+
+    /// Returns the outputs in the given output index that were mined in the height range, ordered by height. Returns
+    /// an error if `index_outputs` is not enabled.
+    pub fn fetch_outputs_by_index<T: RangeBounds<u64>>(
+        &self,
+        index: OutputIndex,
+        range: T,
+    ) -> Result<Vec<OutputMinedInfo>, ChainStorageError> {
+        let db = self.db_read_access()?;
+        let (start, mut end) = convert_to_option_bounds(range);
+        if end.is_none() {
+            end = Some(db.fetch_chain_metadata()?.best_block_height());
+        }
+        let (start, end) = (start.unwrap_or(0), end.unwrap());
+        db.fetch_outputs_by_index(&index, start, end)
+    }
+
+    /// Returns the most recent output with the given commitment that was mined at or below `height`, along with the
+    /// input that spent it if it was spent at or below `height`. Returns an error if `index_outputs` is not enabled.
+    pub fn fetch_output_at_height(
+        &self,
+        commitment: Commitment,
+        height: u64,
+    ) -> Result<Option<HistoricalOutputState>, ChainStorageError> {
+        let db = self.db_read_access()?;
+        check_historical_height(&db.fetch_chain_metadata()?, height, "fetch_output_at_height")?;
+        let output = match db
+            .fetch_outputs_by_index(&OutputIndex::Commitment(commitment), 0, height)?
+            .pop()
+        {
+            Some(output) => output,
+            None => return Ok(None),
+        };
+        let spent = db
+            .fetch_input(&output.output.hash())?
+            .filter(|input| input.spent_height <= height);
+        Ok(Some(HistoricalOutputState { output, spent }))
+    }
+
+    /// Calculates the output SMT as it was at the given height by undoing the blocks above it on a copy of the tip SMT.
+    /// Blocks are undone from their stored output SMT deltas, or from the block contents if no delta was stored. The
+    /// cost grows with the distance from the tip, so unless `archival` is enabled, heights more than
+    /// `max_output_smt_rewind_depth` blocks below the tip are rejected. The blocks are undone in batches and the read
+    /// lock is only held for a batch at a time.
+    pub fn calculate_output_smt_at_height(&self, height: u64) -> Result<OutputSmt, ChainStorageError> {
+        let (tip_header, mut smt) = {
+            let db = self.db_read_access()?;
+            let metadata = db.fetch_chain_metadata()?;
+            check_historical_height(&metadata, height, "calculate_output_smt_at_height")?;
+            let depth = metadata.best_block_height() - height;
+            if !self.config.archival && depth > self.config.max_output_smt_rewind_depth {
+                return Err(ChainStorageError::InvalidArguments {
+                    func: "calculate_output_smt_at_height",
+                    arg: "height",
+                    message: format!(
+                        "Height {} is {} blocks below the tip, more than the maximum output SMT rewind depth of {}",
+                        height, depth, self.config.max_output_smt_rewind_depth
+                    ),
+                });
+            }
+            let tip_header = db.fetch_chain_header_by_height(metadata.best_block_height())?;
+            let smt = self.smt_read_access()?.clone();
+            (tip_header, smt)
+        };
+
+        let mut batch_end = tip_header.height();
+        while batch_end > height {
+            let batch_start = cmp::max(batch_end.saturating_sub(OUTPUT_SMT_REWIND_BATCH_SIZE - 1), height + 1);
+            let db = self.db_read_access()?;
+            // The SMT copy is only valid for the chain it was taken from
+            if db.fetch_chain_header_by_height(tip_header.height())?.hash() != tip_header.hash() {
+                return Err(ChainStorageError::InvalidOperation(format!(
+                    "The chain was reorganized while calculating the output SMT at height {}",
+                    height
+                )));
+            }
+            for h in (batch_start..=batch_end).rev() {
+                match db.fetch_output_smt_delta(h)? {
+                    Some(delta) => delta.undo(&mut smt)?,
+                    None => undo_block_output_smt_changes(&*db, h, &mut smt)?,
+                }
+            }
+            batch_end = batch_start - 1;
+        }
+
+        let header = self.fetch_chain_header(height)?;
+        let root = FixedHash::try_from(smt.hash().as_slice())?;
+        if root != header.header().output_mr {
+            return Err(ChainStorageError::DataInconsistencyDetected {
+                function: "calculate_output_smt_at_height",
+                details: format!(
+                    "Calculated output SMT root {} does not match the root of the header at height {}",
+                    root, height
+                ),
+            });
+        }
+        Ok(smt)
+    }
+}
+
+/// Reverts the changes that the block at the given height made to the output SMT, reading them from the block
+fn undo_block_output_smt_changes<T: BlockchainBackend>(
+    db: &T,
+    height: u64,
+    smt: &mut OutputSmt,
+) -> Result<(), ChainStorageError> {
+    let header = db.fetch_chain_header_by_height(height)?;
+    for input in db.fetch_inputs_in_block(header.hash())? {
+        let spent = db
+            .fetch_output(&input.output_hash())?
+            .ok_or_else(|| ChainStorageError::ValueNotFound {
+                entity: "Output",
+                field: "hash",
+                value: input.output_hash().to_hex(),
+            })?;
+        let smt_key = NodeKey::try_from(spent.output.commitment.as_bytes())?;
+        let smt_node = ValueHash::try_from(spent.output.smt_hash(spent.mined_height).as_slice())?;
+        smt.insert(smt_key, smt_node)?;
+    }
+    for output in db.fetch_outputs_in_block(header.hash())? {
+        if !output.is_burned() {
+            smt.delete(&NodeKey::try_from(output.commitment.as_bytes())?)?;
+        }
+    }
+    Ok(())
+}
+
+/// Historical queries need the block bodies above the queried height, which pruned nodes only keep above the pruned
+/// height
+fn check_historical_height(metadata: &ChainMetadata, height: u64, func: &'static str) -> Result<(), ChainStorageError> {
+    if height > metadata.best_block_height() || height < metadata.pruned_height() {
+        return Err(ChainStorageError::InvalidArguments {
+            func,
+            arg: "height",
+            message: format!(
+                "Height {} is not between the pruned height {} and the tip height {}",
+                height,
+                metadata.pruned_height(),
+                metadata.best_block_height()
+            ),
+        });
+    }
+    Ok(())
 }
 
 fn unexpected_result<T>(request: DbKey, response: DbValue) -> Result<T, ChainStorageError> {
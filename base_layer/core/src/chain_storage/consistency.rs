@@ -0,0 +1,453 @@
+// Copyright 2024 The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+//! Deep consistency checks of the stored chain.
+//!
+//! The check recomputes the kernel MMR root, the output SMT root and the spend state of the inputs and outputs of each
+//! block from storage, and compares them against the stored headers and [BlockAccumulatedData]. The output SMT is
+//! rebuilt incrementally from the start of the range, so the check stops at the first inconsistent block: every block
+//! after it would be reported as inconsistent too.
+//!
+//! Blocks are read in batches and the database read lock is only held for a batch at a time, so that blocks can still
+//! be added while a long range is checked.
+
+use std::{
+    cmp,
+    fmt::{Display, Formatter},
+    time::Instant,
+};
+
+use log::*;
+use tari_common_types::types::{Commitment, FixedHash, HashOutput};
+use tari_mmr::sparse_merkle_tree::{DeleteResult, NodeKey, ValueHash};
+use tari_utilities::{hex::Hex, ByteArray};
+
+use crate::{
+    blocks::{BlockAccumulatedData, ChainHeader},
+    chain_storage::{BlockchainBackend, BlockchainDatabase, ChainStorageError},
+    OutputSmt,
+    PrunedKernelMmr,
+};
+
+const LOG_TARGET: &str = "c::cs::consistency";
+// The number of blocks to read while holding the database read lock
+#[cfg(not(test))]
+const CHECK_BATCH_SIZE: u64 = 100;
+#[cfg(test)]
+const CHECK_BATCH_SIZE: u64 = 2;
+
+/// A single inconsistency found at a block height
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChainInconsistency {
+    pub height: u64,
+    pub kind: InconsistencyKind,
+}
+
+impl Display for ChainInconsistency {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Height {}: {}", self.height, self.kind)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InconsistencyKind {
+    /// The header or its accumulated data could not be loaded
+    InvalidHeader(String),
+    /// The header's `prev_hash` does not match the hash of the header below it
+    BrokenHeaderLink { expected: HashOutput, actual: HashOutput },
+    /// The block accumulated data is missing
+    MissingAccumulatedData,
+    /// The kernel MMR root recomputed from the stored kernels does not match the header
+    KernelMmrRootMismatch { expected: FixedHash, actual: FixedHash },
+    /// The kernel MMR root of the stored accumulated kernel hash set does not match the header
+    AccumulatedKernelRootMismatch { expected: FixedHash, actual: FixedHash },
+    /// The number of kernels in the block does not match the header's kernel MMR size
+    KernelMmrSizeMismatch { expected: u64, actual: u64 },
+    /// The stored kernel sum does not match the sum of the kernels in the block
+    KernelSumMismatch,
+    /// The output SMT root recomputed from the stored outputs and inputs does not match the header
+    OutputSmtRootMismatch { expected: FixedHash, actual: FixedHash },
+    /// The output SMT size recomputed from the stored outputs and inputs does not match the header
+    OutputSmtSizeMismatch { expected: u64, actual: u64 },
+    /// An output is stored in the block but cannot be found by its hash, or is indexed at a different block
+    OutputNotIndexed(HashOutput),
+    /// An unspent output is missing from the commitment index
+    UnspentOutputNotIndexed(HashOutput),
+    /// An output commitment is already in the SMT
+    DuplicateCommitment(HashOutput),
+    /// An input is stored in the block but its spend is not indexed, or is indexed at a different block
+    InputNotIndexed(HashOutput),
+    /// An input spends an output that does not exist, is mined after the input or is not in the SMT
+    InvalidSpentOutput(HashOutput),
+}
+
+impl Display for InconsistencyKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        #[allow(clippy::enum_glob_use)]
+        use InconsistencyKind::*;
+        match self {
+            InvalidHeader(details) => write!(f, "invalid header ({})", details),
+            BrokenHeaderLink { expected, actual } => {
+                write!(f, "header links to {} but the previous header is {}", actual, expected)
+            },
+            MissingAccumulatedData => write!(f, "block accumulated data is missing"),
+            KernelMmrRootMismatch { expected, actual } => write!(
+                f,
+                "kernel MMR root of the stored kernels is {} but the header has {}",
+                actual, expected
+            ),
+            AccumulatedKernelRootMismatch { expected, actual } => write!(
+                f,
+                "kernel MMR root of the accumulated data is {} but the header has {}",
+                actual, expected
+            ),
+            KernelMmrSizeMismatch { expected, actual } => {
+                write!(f, "kernel MMR size is {} but the header has {}", actual, expected)
+            },
+            KernelSumMismatch => write!(f, "stored kernel sum does not match the kernels in the block"),
+            OutputSmtRootMismatch { expected, actual } => write!(
+                f,
+                "output SMT root of the stored outputs is {} but the header has {}",
+                actual, expected
+            ),
+            OutputSmtSizeMismatch { expected, actual } => {
+                write!(f, "output SMT size is {} but the header has {}", actual, expected)
+            },
+            OutputNotIndexed(hash) => write!(f, "output {} is not indexed at this block", hash),
+            UnspentOutputNotIndexed(hash) => write!(f, "unspent output {} is not in the commitment index", hash),
+            DuplicateCommitment(hash) => write!(f, "commitment of output {} is already in the output SMT", hash),
+            InputNotIndexed(hash) => write!(f, "spend of output {} is not indexed at this block", hash),
+            InvalidSpentOutput(hash) => write!(f, "input spends output {} that is missing or not spendable", hash),
+        }
+    }
+}
+
+/// The result of [check_chain_consistency]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConsistencyReport {
+    pub start_height: u64,
+    /// The last height that was checked. This is lower than the requested end height if an inconsistency was found.
+    pub end_height: u64,
+    /// The inconsistencies found in the block at `end_height`
+    pub inconsistencies: Vec<ChainInconsistency>,
+}
+
+impl ConsistencyReport {
+    pub fn is_consistent(&self) -> bool {
+        self.inconsistencies.is_empty()
+    }
+
+    /// The height of the first inconsistent block, if any
+    pub fn first_inconsistent_height(&self) -> Option<u64> {
+        self.inconsistencies.iter().map(|i| i.height).min()
+    }
+
+    /// The highest height below the first inconsistent block, which the chain can be rewound to. `None` if the chain
+    /// is consistent or the genesis block is inconsistent.
+    pub fn last_good_height(&self) -> Option<u64> {
+        self.first_inconsistent_height().and_then(|h| h.checked_sub(1))
+    }
+}
+
+/// Checks the blocks from `start_height` to `end_height` (inclusive) against their headers.
+///
+/// Headers and kernels are checked at every height. Block bodies and kernel sums are only checked above the pruned
+/// height, since pruned nodes do not store them below it. Building the output SMT at the start of the range requires
+/// reading all blocks below it. The database read lock is taken for each batch of blocks, and the check fails if the
+/// blocks that were already checked are reorged out of the main chain before it completes.
+pub fn check_chain_consistency<B: BlockchainBackend>(
+    db: &BlockchainDatabase<B>,
+    start_height: u64,
+    end_height: u64,
+) -> Result<ConsistencyReport, ChainStorageError> {
+    let timer = Instant::now();
+    let metadata = db.get_chain_metadata()?;
+    if start_height > end_height || end_height > metadata.best_block_height() {
+        return Err(ChainStorageError::InvalidArguments {
+            func: "check_chain_consistency",
+            arg: "end_height",
+            message: format!(
+                "The range {}..={} is not within the chain (tip height {})",
+                start_height,
+                end_height,
+                metadata.best_block_height()
+            ),
+        });
+    }
+    let pruned_height = metadata.pruned_height();
+    let first_body_height = if pruned_height == 0 {
+        start_height
+    } else {
+        start_height.max(pruned_height + 1)
+    };
+
+    let mut output_smt = if first_body_height <= end_height {
+        build_output_smt(db, first_body_height)?
+    } else {
+        OutputSmt::new()
+    };
+    let mut prev_header = match start_height.checked_sub(1) {
+        Some(h) => Some(db.fetch_chain_header(h)?),
+        None => None,
+    };
+
+    let mut batch_start = start_height;
+    while batch_start <= end_height {
+        let batch_end = cmp::min(batch_start.saturating_add(CHECK_BATCH_SIZE - 1), end_height);
+        let backend = db.db_read_access()?;
+        if let Some(prev_header) = &prev_header {
+            check_on_main_chain(&*backend, prev_header)?;
+        }
+
+        for height in batch_start..=batch_end {
+            let mut checker = BlockChecker {
+                backend: &*backend,
+                height,
+                inconsistencies: Vec::new(),
+            };
+            let has_body = height >= first_body_height;
+            let header = checker.check_header(prev_header.as_ref());
+            if let Some(header) = &header {
+                checker.check_kernels(header, prev_header.as_ref(), has_body)?;
+                if has_body {
+                    checker.check_body(header, &mut output_smt)?;
+                }
+            }
+
+            if !checker.inconsistencies.is_empty() {
+                warn!(
+                    target: LOG_TARGET,
+                    "Found {} inconsistencies at height {}",
+                    checker.inconsistencies.len(),
+                    height
+                );
+                return Ok(ConsistencyReport {
+                    start_height,
+                    end_height: height,
+                    inconsistencies: checker.inconsistencies,
+                });
+            }
+            if height % 1000 == 0 {
+                debug!(target: LOG_TARGET, "Checked {} of {} block(s)", height, end_height);
+            }
+            prev_header = header;
+        }
+        batch_start = batch_end + 1;
+    }
+
+    info!(
+        target: LOG_TARGET,
+        "Blocks {} to {} are consistent ({:.2?})",
+        start_height,
+        end_height,
+        timer.elapsed()
+    );
+    Ok(ConsistencyReport {
+        start_height,
+        end_height,
+        inconsistencies: Vec::new(),
+    })
+}
+
+/// Builds the output SMT as at the block below `height` from the stored outputs and their spend state
+fn build_output_smt<B: BlockchainBackend>(
+    db: &BlockchainDatabase<B>,
+    height: u64,
+) -> Result<OutputSmt, ChainStorageError> {
+    let mut smt = OutputSmt::new();
+    let at_height = match height.checked_sub(1) {
+        Some(h) => h,
+        None => return Ok(smt),
+    };
+    let at_header = db.fetch_chain_header(at_height)?;
+    let mut batch_start = 0;
+    while batch_start <= at_height {
+        let batch_end = cmp::min(batch_start.saturating_add(CHECK_BATCH_SIZE - 1), at_height);
+        let backend = db.db_read_access()?;
+        check_on_main_chain(&*backend, &at_header)?;
+        for h in batch_start..=batch_end {
+            let header = backend.fetch_chain_header_by_height(h)?;
+            for (output, spent) in
+                backend.fetch_outputs_in_block_with_spend_state(header.hash(), Some(at_header.hash()))?
+            {
+                if !spent && !output.is_burned() {
+                    let smt_key = NodeKey::try_from(output.commitment.as_bytes())?;
+                    let smt_node = ValueHash::try_from(output.smt_hash(h).as_slice())?;
+                    smt.insert(smt_key, smt_node)?;
+                }
+            }
+        }
+        batch_start = batch_end + 1;
+    }
+    Ok(smt)
+}
+
+/// Checks that the block of `header` is still part of the main chain, since the read lock is released between batches
+fn check_on_main_chain<B: BlockchainBackend>(backend: &B, header: &ChainHeader) -> Result<(), ChainStorageError> {
+    if backend.fetch_chain_header_by_height(header.height())?.hash() != header.hash() {
+        return Err(ChainStorageError::InvalidOperation(format!(
+            "Block {} at height {} was reorged out of the main chain during the consistency check",
+            header.hash(),
+            header.height()
+        )));
+    }
+    Ok(())
+}
+
+struct BlockChecker<'a, B> {
+    backend: &'a B,
+    height: u64,
+    inconsistencies: Vec<ChainInconsistency>,
+}
+
+impl<B: BlockchainBackend> BlockChecker<'_, B> {
+    fn report(&mut self, kind: InconsistencyKind) {
+        self.inconsistencies.push(ChainInconsistency {
+            height: self.height,
+            kind,
+        });
+    }
+
+    fn check_header(&mut self, prev_header: Option<&ChainHeader>) -> Option<ChainHeader> {
+        let header = match self.backend.fetch_chain_header_by_height(self.height) {
+            Ok(header) => header,
+            Err(e) => {
+                self.report(InconsistencyKind::InvalidHeader(e.to_string()));
+                return None;
+            },
+        };
+        if let Some(prev_header) = prev_header {
+            if header.header().prev_hash != *prev_header.hash() {
+                self.report(InconsistencyKind::BrokenHeaderLink {
+                    expected: *prev_header.hash(),
+                    actual: header.header().prev_hash,
+                });
+            }
+        }
+        Some(header)
+    }
+
+    fn check_kernels(
+        &mut self,
+        header: &ChainHeader,
+        prev_header: Option<&ChainHeader>,
+        check_kernel_sum: bool,
+    ) -> Result<(), ChainStorageError> {
+        let prev_accumulated_data = match self.height.checked_sub(1) {
+            Some(h) => self.backend.fetch_block_accumulated_data_by_height(h)?,
+            None => Some(BlockAccumulatedData::default()),
+        };
+        let accumulated_data = self.backend.fetch_block_accumulated_data_by_height(self.height)?;
+        let (prev_accumulated_data, accumulated_data) = match (prev_accumulated_data, accumulated_data) {
+            (Some(prev), Some(current)) => (prev, current),
+            _ => {
+                self.report(InconsistencyKind::MissingAccumulatedData);
+                return Ok(());
+            },
+        };
+        let kernels = self.backend.fetch_kernels_in_block(header.hash())?;
+
+        let prev_kernel_mmr_size = prev_header.map(|h| h.header().kernel_mmr_size).unwrap_or(0);
+        let kernel_mmr_size = prev_kernel_mmr_size + kernels.len() as u64;
+        if kernel_mmr_size != header.header().kernel_mmr_size {
+            self.report(InconsistencyKind::KernelMmrSizeMismatch {
+                expected: header.header().kernel_mmr_size,
+                actual: kernel_mmr_size,
+            });
+        }
+
+        let mut kernel_mmr = PrunedKernelMmr::new(prev_accumulated_data.dissolve());
+        let mut kernel_sum = Commitment::default();
+        for kernel in &kernels {
+            kernel_mmr.push(kernel.hash().to_vec())?;
+            kernel_sum = &kernel_sum + &kernel.excess;
+        }
+        let root = FixedHash::try_from(kernel_mmr.get_merkle_root()?)?;
+        if root != header.header().kernel_mr {
+            self.report(InconsistencyKind::KernelMmrRootMismatch {
+                expected: header.header().kernel_mr,
+                actual: root,
+            });
+        }
+
+        if check_kernel_sum && kernel_sum != *accumulated_data.kernel_sum() {
+            self.report(InconsistencyKind::KernelSumMismatch);
+        }
+
+        let stored_root = FixedHash::try_from(PrunedKernelMmr::new(accumulated_data.dissolve()).get_merkle_root()?)?;
+        if stored_root != header.header().kernel_mr {
+            self.report(InconsistencyKind::AccumulatedKernelRootMismatch {
+                expected: header.header().kernel_mr,
+                actual: stored_root,
+            });
+        }
+        Ok(())
+    }
+
+    fn check_body(&mut self, header: &ChainHeader, output_smt: &mut OutputSmt) -> Result<(), ChainStorageError> {
+        let block_hash = *header.hash();
+        for output in self.backend.fetch_outputs_in_block(&block_hash)? {
+            let output_hash = output.hash();
+            match self.backend.fetch_output(&output_hash)? {
+                Some(info) if info.mined_height == self.height && info.header_hash == block_hash => {},
+                _ => self.report(InconsistencyKind::OutputNotIndexed(output_hash)),
+            }
+            if output.is_burned() {
+                continue;
+            }
+            if self.backend.fetch_input(&output_hash)?.is_none() &&
+                self.backend
+                    .fetch_unspent_output_hash_by_commitment(&output.commitment)? !=
+                    Some(output_hash)
+            {
+                self.report(InconsistencyKind::UnspentOutputNotIndexed(output_hash));
+            }
+            let smt_key = NodeKey::try_from(output.commitment.as_bytes())?;
+            let smt_node = ValueHash::try_from(output.smt_hash(self.height).as_slice())?;
+            if output_smt.insert(smt_key, smt_node).is_err() {
+                self.report(InconsistencyKind::DuplicateCommitment(output_hash));
+            }
+        }
+
+        for input in self.backend.fetch_inputs_in_block(&block_hash)? {
+            let output_hash = input.output_hash();
+            match self.backend.fetch_input(&output_hash)? {
+                Some(info) if info.spent_height == self.height && info.header_hash == block_hash => {},
+                _ => self.report(InconsistencyKind::InputNotIndexed(output_hash)),
+            }
+            let spent_output = match self.backend.fetch_output(&output_hash)? {
+                Some(info) if info.mined_height <= self.height => info.output,
+                _ => {
+                    self.report(InconsistencyKind::InvalidSpentOutput(output_hash));
+                    continue;
+                },
+            };
+            let smt_key = NodeKey::try_from(spent_output.commitment.as_bytes())?;
+            if let DeleteResult::KeyNotFound = output_smt.delete(&smt_key)? {
+                self.report(InconsistencyKind::InvalidSpentOutput(output_hash));
+            }
+        }
+
+        let root = FixedHash::try_from(output_smt.hash().as_slice())?;
+        if root != header.header().output_mr {
+            self.report(InconsistencyKind::OutputSmtRootMismatch {
+                expected: header.header().output_mr,
+                actual: root,
+            });
+        }
+        let smt_size = output_smt.size();
+        if smt_size != header.header().output_smt_size {
+            self.report(InconsistencyKind::OutputSmtSizeMismatch {
+                expected: header.header().output_smt_size,
+                actual: smt_size,
+            });
+        }
+        debug!(
+            target: LOG_TARGET,
+            "Checked block {} ({})",
+            self.height,
+            block_hash.to_hex()
+        );
+        Ok(())
+    }
+}
@@ -26,3 +26,5 @@ pub const BLOCKCHAIN_DATABASE_ORPHAN_STORAGE_CAPACITY: usize = 720;
 pub const BLOCKCHAIN_DATABASE_PRUNING_HORIZON: u64 = 0;
 /// The chain height interval used to determine when a pruned node should perform pruning.
 pub const BLOCKCHAIN_DATABASE_PRUNED_MODE_PRUNING_INTERVAL: u64 = 50;
+/// The maximum number of blocks below the tip that the output SMT can be calculated at.
+pub const BLOCKCHAIN_DATABASE_MAX_OUTPUT_SMT_REWIND_DEPTH: u64 = 1000;
@@ -279,6 +279,20 @@ impl DbTransaction {
         self.operations.push(WriteOperation::ClearAllReorgs);
         self
     }
+
+    /// Enables or disables the secondary output indexes. Enabling them indexes all the outputs that are already
+    /// stored, disabling them removes all the index entries.
+    pub fn set_output_indexing(&mut self, enabled: bool) -> &mut Self {
+        self.operations.push(WriteOperation::SetOutputIndexingConfig(enabled));
+        self
+    }
+
+    /// Enables or disables storing the output SMT delta of every block that is added. Disabling it keeps the deltas
+    /// that are already stored.
+    pub fn set_archival(&mut self, enabled: bool) -> &mut Self {
+        self.operations.push(WriteOperation::SetArchivalConfig(enabled));
+        self
+    }
 }
 
 #[derive(Debug)]
@@ -352,6 +366,8 @@ pub enum WriteOperation {
         reorg: Reorg,
     },
     ClearAllReorgs,
+    SetOutputIndexingConfig(bool),
+    SetArchivalConfig(bool),
 }
 
 impl fmt::Display for WriteOperation {
@@ -447,6 +463,8 @@ impl fmt::Display for WriteOperation {
             SetHorizonData { .. } => write!(f, "Set horizon data"),
             InsertReorg { .. } => write!(f, "Insert reorg"),
             ClearAllReorgs => write!(f, "Clear all reorgs"),
+            SetOutputIndexingConfig(enabled) => write!(f, "Set config: output indexing to {}", enabled),
+            SetArchivalConfig(enabled) => write!(f, "Set config: archival to {}", enabled),
         }
     }
 }
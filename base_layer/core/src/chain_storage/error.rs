@@ -219,6 +219,38 @@ impl From<lmdb_zero::Error> for ChainStorageError {
     }
 }
 
+#[cfg(feature = "redb")]
+impl From<redb::DatabaseError> for ChainStorageError {
+    fn from(err: redb::DatabaseError) -> Self {
+        match err {
+            redb::DatabaseError::DatabaseAlreadyOpen => ChainStorageError::CannotAcquireFileLock,
+            _ => ChainStorageError::AccessError(err.to_string()),
+        }
+    }
+}
+
+#[cfg(feature = "redb")]
+macro_rules! impl_from_redb_error {
+    ($($err:ty),+ $(,)?) => {
+        $(
+            impl From<$err> for ChainStorageError {
+                fn from(err: $err) -> Self {
+                    ChainStorageError::AccessError(err.to_string())
+                }
+            }
+        )+
+    };
+}
+
+#[cfg(feature = "redb")]
+impl_from_redb_error!(
+    redb::Error,
+    redb::TransactionError,
+    redb::TableError,
+    redb::StorageError,
+    redb::CommitError,
+);
+
 pub trait Optional<U> {
     fn optional(self) -> Result<Option<U>, ChainStorageError>;
 }
@@ -390,6 +390,70 @@ where
     }
 }
 
+/// Fetches the values of all the keys between `start_key` and `end_key` (inclusive), in key order
+pub fn lmdb_fetch_range<V>(
+    txn: &ConstTransaction<'_>,
+    db: &Database,
+    start_key: &[u8],
+    end_key: &[u8],
+) -> Result<Vec<V>, ChainStorageError>
+where
+    V: DeserializeOwned,
+{
+    let access = txn.access();
+    let mut cursor = txn.cursor(db).map_err(|e| {
+        error!(target: LOG_TARGET, "Could not get read cursor from lmdb: {:?}", e);
+        ChainStorageError::AccessError(e.to_string())
+    })?;
+
+    let mut row = cursor.seek_range_k::<[u8], [u8]>(&access, start_key).to_opt()?;
+    let mut result = vec![];
+    while let Some((k, v)) = row {
+        if k > end_key {
+            break;
+        }
+        result.push(deserialize::<V>(v)?);
+        row = cursor.next::<[u8], [u8]>(&access).to_opt()?;
+    }
+    Ok(result)
+}
+
+/// Fetches up to `limit` entries in key order, starting after the key `after`, or at the first key if `after` is
+/// `None`. This allows a database to be iterated over in batches while writing to other databases in between, which a
+/// cursor does not allow.
+pub fn lmdb_fetch_batch_after<V>(
+    txn: &ConstTransaction<'_>,
+    db: &Database,
+    after: Option<&[u8]>,
+    limit: usize,
+) -> Result<Vec<(Vec<u8>, V)>, ChainStorageError>
+where
+    V: DeserializeOwned,
+{
+    let access = txn.access();
+    let mut cursor = txn.cursor(db).map_err(|e| {
+        error!(target: LOG_TARGET, "Could not get read cursor from lmdb: {:?}", e);
+        ChainStorageError::AccessError(e.to_string())
+    })?;
+
+    let mut row = match after {
+        Some(key) => match cursor.seek_range_k::<[u8], [u8]>(&access, key).to_opt()? {
+            Some((k, _)) if k == key => cursor.next::<[u8], [u8]>(&access).to_opt()?,
+            row => row,
+        },
+        None => cursor.first::<[u8], [u8]>(&access).to_opt()?,
+    };
+    let mut result = Vec::with_capacity(limit);
+    while let Some((k, v)) = row {
+        result.push((k.to_vec(), deserialize::<V>(v)?));
+        if result.len() >= limit {
+            break;
+        }
+        row = cursor.next::<[u8], [u8]>(&access).to_opt()?;
+    }
+    Ok(result)
+}
+
 /// Filter the values matching the fn
 pub fn lmdb_filter_map_values<F, V, R>(
     txn: &ConstTransaction<'_>,
@@ -439,6 +503,18 @@ pub fn fetch_db_entry_sizes(txn: &ConstTransaction<'_>, db: &Database) -> Result
     Ok((num_entries, total_key_size, total_value_size))
 }
 
+/// Calls `f` with the raw key and value of every entry in the database, in key order
+#[cfg(feature = "redb")]
+pub fn lmdb_for_each<F>(txn: &ConstTransaction<'_>, db: &Database, mut f: F) -> Result<(), ChainStorageError>
+where F: FnMut(&[u8], &[u8]) -> Result<(), ChainStorageError> {
+    let access = txn.access();
+    let mut cursor = txn.cursor(db)?;
+    while let Some((key, value)) = cursor.next::<[u8], [u8]>(&access).to_opt()? {
+        f(key, value)?;
+    }
+    Ok(())
+}
+
 /// deletes entries using the filter Fn
 pub fn lmdb_delete_each_where<K, V, F>(
     txn: &WriteTransaction<'_>,
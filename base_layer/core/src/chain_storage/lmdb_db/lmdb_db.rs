@@ -73,7 +73,9 @@ use crate::{
                 lmdb_delete_key_value,
                 lmdb_delete_keys_starting_with,
                 lmdb_exists,
+                lmdb_fetch_batch_after,
                 lmdb_fetch_matching_after,
+                lmdb_fetch_range,
                 lmdb_filter_map_values,
                 lmdb_first_after,
                 lmdb_get,
@@ -99,6 +101,8 @@ use crate::{
         HorizonData,
         InputMinedInfo,
         MmrTree,
+        OutputIndex,
+        OutputSmtDelta,
         Reorg,
         TemplateRegistrationEntry,
         ValidatorNodeEntry,
@@ -123,6 +127,9 @@ type DatabaseRef = Arc<Database<'static>>;
 
 pub const LOG_TARGET: &str = "c::cs::lmdb_db::lmdb_db";
 
+/// The number of outputs read at a time when indexing the outputs that are already stored
+const OUTPUT_INDEX_BACKFILL_BATCH_SIZE: usize = 1000;
+
 const LMDB_DB_METADATA: &str = "metadata";
 const LMDB_DB_HEADERS: &str = "headers";
 const LMDB_DB_HEADER_ACCUMULATED_DATA: &str = "header_accumulated_data";
@@ -149,6 +156,8 @@ const LMDB_DB_REORGS: &str = "reorgs";
 const LMDB_DB_VALIDATOR_NODES: &str = "validator_nodes";
 const LMDB_DB_VALIDATOR_NODES_MAPPING: &str = "validator_nodes_mapping";
 const LMDB_DB_TEMPLATE_REGISTRATIONS: &str = "template_registrations";
+const LMDB_DB_OUTPUT_INDEXES: &str = "output_indexes";
+const LMDB_DB_OUTPUT_SMT_DELTAS: &str = "output_smt_deltas";
 
 /// HeaderHash(32), mmr_pos(8), hash(32)
 type KernelKey = CompositeKey<72>;
@@ -198,6 +207,8 @@ pub fn create_lmdb_database<P: AsRef<Path>>(
         .add_database(LMDB_DB_VALIDATOR_NODES, flags)
         .add_database(LMDB_DB_VALIDATOR_NODES_MAPPING, flags)
         .add_database(LMDB_DB_TEMPLATE_REGISTRATIONS, flags | db::DUPSORT)
+        .add_database(LMDB_DB_OUTPUT_INDEXES, flags)
+        .add_database(LMDB_DB_OUTPUT_SMT_DELTAS, flags | db::INTEGERKEY)
         .build()
         .map_err(|err| ChainStorageError::CriticalError(format!("Could not create LMDB store:{}", err)))?;
     debug!(target: LOG_TARGET, "LMDB database creation successful");
@@ -260,6 +271,10 @@ pub struct LMDBDatabase {
     validator_nodes_mapping: DatabaseRef,
     /// Maps CodeTemplateRegistration <block_height, hash> -> TemplateRegistration
     template_registrations: DatabaseRef,
+    /// Maps <OutputIndex, mined height, output hash> -> output hash
+    output_indexes: DatabaseRef,
+    /// Maps height -> OutputSmtDelta
+    output_smt_deltas: DatabaseRef,
     _file_lock: Arc<File>,
     consensus_manager: ConsensusManager,
 }
@@ -299,6 +314,8 @@ impl LMDBDatabase {
             validator_nodes: get_database(store, LMDB_DB_VALIDATOR_NODES)?,
             validator_nodes_mapping: get_database(store, LMDB_DB_VALIDATOR_NODES_MAPPING)?,
             template_registrations: get_database(store, LMDB_DB_TEMPLATE_REGISTRATIONS)?,
+            output_indexes: get_database(store, LMDB_DB_OUTPUT_INDEXES)?,
+            output_smt_deltas: get_database(store, LMDB_DB_OUTPUT_SMT_DELTAS)?,
             env,
             env_config: store.env_config(),
             _file_lock: Arc::new(file_lock),
@@ -487,6 +504,12 @@ impl LMDBDatabase {
                 ClearAllReorgs => {
                     lmdb_clear(&write_txn, &self.reorgs)?;
                 },
+                SetOutputIndexingConfig(enabled) => {
+                    self.set_output_indexing(&write_txn, *enabled)?;
+                },
+                SetArchivalConfig(enabled) => {
+                    self.set_metadata(&write_txn, MetadataKey::Archival, &MetadataValue::Archival(*enabled))?;
+                },
             }
         }
         write_txn.commit()?;
@@ -494,7 +517,7 @@ impl LMDBDatabase {
         Ok(())
     }
 
-    fn all_dbs(&self) -> [(&'static str, &DatabaseRef); 26] {
+    fn all_dbs(&self) -> [(&'static str, &DatabaseRef); 28] {
         [
             (LMDB_DB_METADATA, &self.metadata_db),
             (LMDB_DB_HEADERS, &self.headers_db),
@@ -528,9 +551,25 @@ impl LMDBDatabase {
             (LMDB_DB_VALIDATOR_NODES, &self.validator_nodes),
             (LMDB_DB_VALIDATOR_NODES_MAPPING, &self.validator_nodes_mapping),
             (LMDB_DB_TEMPLATE_REGISTRATIONS, &self.template_registrations),
+            (LMDB_DB_OUTPUT_INDEXES, &self.output_indexes),
+            (LMDB_DB_OUTPUT_SMT_DELTAS, &self.output_smt_deltas),
         ]
     }
 
+    /// Calls `f` with the raw key and value of every entry in the named database, in key order. Used to copy the
+    /// chain database into a different storage backend.
+    #[cfg(feature = "redb")]
+    pub(crate) fn for_each_raw_entry<F>(&self, db_name: &str, f: F) -> Result<(), ChainStorageError>
+    where F: FnMut(&[u8], &[u8]) -> Result<(), ChainStorageError> {
+        let (_, db) = self
+            .all_dbs()
+            .into_iter()
+            .find(|(name, _)| *name == db_name)
+            .ok_or_else(|| ChainStorageError::InvalidQuery(format!("Database {} does not exist", db_name)))?;
+        let txn = self.read_transaction()?;
+        super::lmdb::lmdb_for_each(&txn, db, f)
+    }
+
     fn insert_output(
         &self,
         txn: &WriteTransaction<'_>,
@@ -574,9 +613,111 @@ impl LMDBDatabase {
             LMDB_DB_UTXOS,
         )?;
 
+        if fetch_output_indexing(txn, &self.metadata_db)? {
+            self.insert_output_indexes(txn, output, header_height, &output_hash)?;
+        }
+
         Ok(())
     }
 
+    fn insert_output_indexes(
+        &self,
+        txn: &WriteTransaction<'_>,
+        output: &TransactionOutput,
+        mined_height: u64,
+        output_hash: &HashOutput,
+    ) -> Result<(), ChainStorageError> {
+        for index in OutputIndex::all_for_output(output) {
+            lmdb_insert(
+                txn,
+                &self.output_indexes,
+                index.storage_key(mined_height, output_hash).as_slice(),
+                output_hash,
+                LMDB_DB_OUTPUT_INDEXES,
+            )?;
+        }
+        Ok(())
+    }
+
+    fn delete_output_indexes(
+        &self,
+        txn: &WriteTransaction<'_>,
+        output: &TransactionOutput,
+        mined_height: u64,
+        output_hash: &HashOutput,
+    ) -> Result<(), ChainStorageError> {
+        for index in OutputIndex::all_for_output(output) {
+            lmdb_delete(
+                txn,
+                &self.output_indexes,
+                index.storage_key(mined_height, output_hash).as_slice(),
+                LMDB_DB_OUTPUT_INDEXES,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Enables or disables the output indexes. Enabling indexes all outputs that are already stored, disabling removes
+    /// all index entries. Indexes that were built for a different [OutputIndex::VERSION] are rebuilt.
+    fn set_output_indexing(&self, txn: &WriteTransaction<'_>, enabled: bool) -> Result<(), ChainStorageError> {
+        let is_enabled = fetch_output_indexing(txn, &self.metadata_db)?;
+        if !enabled {
+            if is_enabled {
+                let num_deleted = lmdb_clear(txn, &self.output_indexes)?;
+                info!(target: LOG_TARGET, "Removed {} output index entries", num_deleted);
+                self.set_metadata(txn, MetadataKey::OutputIndexing, &MetadataValue::OutputIndexing(false))?;
+            }
+            return Ok(());
+        }
+
+        let version = fetch_output_index_version(txn, &self.metadata_db)?;
+        if is_enabled {
+            if version == OutputIndex::VERSION {
+                return Ok(());
+            }
+            info!(
+                target: LOG_TARGET,
+                "Output indexes are at version {}, rebuilding them for version {}",
+                version,
+                OutputIndex::VERSION
+            );
+            lmdb_clear(txn, &self.output_indexes)?;
+        }
+
+        let timer = Instant::now();
+        let mut num_indexed = 0;
+        let mut last_key = None;
+        loop {
+            let rows = lmdb_fetch_batch_after::<TransactionOutputRowData>(
+                txn,
+                &self.utxos_db,
+                last_key.as_deref(),
+                OUTPUT_INDEX_BACKFILL_BATCH_SIZE,
+            )?;
+            match rows.last() {
+                Some((key, _)) => last_key = Some(key.clone()),
+                None => break,
+            }
+            for (_, row) in &rows {
+                self.insert_output_indexes(txn, &row.output, row.mined_height, &row.hash)?;
+            }
+            num_indexed += rows.len();
+        }
+        info!(
+            target: LOG_TARGET,
+            "Indexed {} existing output(s) in {:.2?}",
+            num_indexed,
+            timer.elapsed()
+        );
+
+        self.set_metadata(
+            txn,
+            MetadataKey::OutputIndexVersion,
+            &MetadataValue::OutputIndexVersion(OutputIndex::VERSION),
+        )?;
+        self.set_metadata(txn, MetadataKey::OutputIndexing, &MetadataValue::OutputIndexing(true))
+    }
+
     fn insert_kernel(
         &self,
         txn: &WriteTransaction<'_>,
@@ -917,6 +1058,10 @@ impl LMDBDatabase {
         })?;
 
         self.delete_block_inputs_outputs(write_txn, block_hash.as_slice(), &mut output_smt)?;
+        // The delta is deleted even if archival has since been disabled, so that a stale delta is never stored
+        if lmdb_exists(write_txn, &self.output_smt_deltas, &height)? {
+            lmdb_delete(write_txn, &self.output_smt_deltas, &height, LMDB_DB_OUTPUT_SMT_DELTAS)?;
+        }
 
         let new_tip_header = self.fetch_chain_header_by_height(prev_height)?;
         let root = FixedHash::try_from(output_smt.hash().as_slice())?;
@@ -948,6 +1093,7 @@ impl LMDBDatabase {
         let inputs = lmdb_delete_keys_starting_with::<TransactionInputRowData>(txn, &self.inputs_db, block_hash)?;
         debug!(target: LOG_TARGET, "Deleted {} input(s)...", inputs.len());
 
+        let output_indexing = fetch_output_indexing(txn, &self.metadata_db)?;
         for utxo in &output_rows {
             trace!(target: LOG_TARGET, "Deleting UTXO `{}`", to_hex(utxo.hash.as_slice()));
             lmdb_delete(
@@ -956,6 +1102,9 @@ impl LMDBDatabase {
                 utxo.hash.as_slice(),
                 "txos_hash_to_index_db",
             )?;
+            if output_indexing {
+                self.delete_output_indexes(txn, &utxo.output, utxo.mined_height, &utxo.hash)?;
+            }
 
             let output_hash = utxo.output.hash();
             // if an output was already spent in the block, it was never created as unspent, so dont delete it as it
@@ -1232,6 +1381,7 @@ impl LMDBDatabase {
         } = data;
 
         let mut kernel_mmr = PrunedKernelMmr::new(pruned_kernel_set);
+        let mut smt_delta = OutputSmtDelta::default();
 
         for kernel in kernels {
             total_kernel_sum = &total_kernel_sum + &kernel.excess;
@@ -1255,6 +1405,7 @@ impl LMDBDatabase {
             if !output.is_burned() {
                 let smt_key = NodeKey::try_from(output.commitment.as_bytes())?;
                 let smt_node = ValueHash::try_from(output.smt_hash(header.height).as_slice())?;
+                smt_delta.record_insert(&output.commitment, &smt_node)?;
                 if let Err(e) = output_smt.insert(smt_key, smt_node) {
                     error!(
                         target: LOG_TARGET,
@@ -1297,7 +1448,9 @@ impl LMDBDatabase {
             let input_with_output_data = self.input_with_output_data(txn, input)?;
             let smt_key = NodeKey::try_from(input_with_output_data.commitment()?.as_bytes())?;
             match output_smt.delete(&smt_key)? {
-                DeleteResult::Deleted(_value_hash) => {},
+                DeleteResult::Deleted(value_hash) => {
+                    smt_delta.record_delete(input_with_output_data.commitment()?, &value_hash)?;
+                },
                 DeleteResult::KeyNotFound => {
                     error!(
                         target: LOG_TARGET,
@@ -1340,6 +1493,9 @@ impl LMDBDatabase {
             header.height,
             &BlockAccumulatedData::new(kernel_mmr.get_pruned_hash_set()?, total_kernel_sum),
         )?;
+        if fetch_archival(txn, &self.metadata_db)? {
+            lmdb_replace(txn, &self.output_smt_deltas, &header.height, &smt_delta, None)?;
+        }
 
         Ok(())
     }
@@ -1469,6 +1625,7 @@ impl LMDBDatabase {
     ) -> Result<(), ChainStorageError> {
         let inputs =
             lmdb_fetch_matching_after::<TransactionInputRowData>(write_txn, &self.inputs_db, block_hash.as_slice())?;
+        let output_indexing = fetch_output_indexing(write_txn, &self.metadata_db)?;
 
         for input_data in inputs {
             let input = input_data.input;
@@ -1489,6 +1646,9 @@ impl LMDBDatabase {
                 let mut buffer = [0u8; 32];
                 buffer.copy_from_slice(&key_bytes[0..32]);
                 let key = OutputKey::new(&FixedHash::from(buffer), &input.output_hash())?;
+                if output_indexing {
+                    self.prune_output_indexes(write_txn, &key)?;
+                }
                 debug!(target: LOG_TARGET, "Pruning output from 'utxos_db': key '{}'", key.0);
                 lmdb_delete(write_txn, &self.utxos_db, &key.convert_to_comp_key(), LMDB_DB_UTXOS)?;
             };
@@ -1538,6 +1698,9 @@ impl LMDBDatabase {
                 let mut buffer = [0u8; 32];
                 buffer.copy_from_slice(&key_bytes[0..32]);
                 let key = OutputKey::new(&FixedHash::from(buffer), output_hash)?;
+                if fetch_output_indexing(write_txn, &self.metadata_db)? {
+                    self.prune_output_indexes(write_txn, &key)?;
+                }
                 debug!(target: LOG_TARGET, "Pruning output from 'utxos_db': key '{}'", key.0);
                 lmdb_delete(write_txn, &self.utxos_db, &key.convert_to_comp_key(), LMDB_DB_UTXOS)?;
             },
@@ -1547,6 +1710,16 @@ impl LMDBDatabase {
         Ok(())
     }
 
+    fn prune_output_indexes(&self, write_txn: &WriteTransaction<'_>, key: &OutputKey) -> Result<(), ChainStorageError> {
+        let row: Option<TransactionOutputRowData> =
+            lmdb_get(write_txn, &self.utxos_db, &key.clone().convert_to_comp_key())?;
+        if let Some(row) = row {
+            debug!(target: LOG_TARGET, "Pruning output from 'output_indexes': output '{}'", row.hash);
+            self.delete_output_indexes(write_txn, &row.output, row.mined_height, &row.hash)?;
+        }
+        Ok(())
+    }
+
     fn delete_all_kernels_in_block(
         &self,
         txn: &WriteTransaction<'_>,
@@ -2500,6 +2673,39 @@ impl BlockchainBackend for LMDBDatabase {
         Ok(result)
     }
 
+    fn fetch_outputs_by_index(
+        &self,
+        index: &OutputIndex,
+        start_height: u64,
+        end_height: u64,
+    ) -> Result<Vec<OutputMinedInfo>, ChainStorageError> {
+        let txn = self.read_transaction()?;
+        if !fetch_output_indexing(&txn, &self.metadata_db)? {
+            return Err(ChainStorageError::InvalidOperation(
+                "Output indexing is not enabled".to_string(),
+            ));
+        }
+
+        let (start_key, end_key) = index.storage_key_range(start_height, end_height);
+        let output_hashes = lmdb_fetch_range::<HashOutput>(&txn, &self.output_indexes, &start_key, &end_key)?;
+        output_hashes
+            .iter()
+            .map(|output_hash| {
+                self.fetch_output_in_txn(&txn, output_hash.as_slice())?.ok_or_else(|| {
+                    ChainStorageError::DataInconsistencyDetected {
+                        function: "fetch_outputs_by_index",
+                        details: format!("Indexed output {} ({}) does not exist", output_hash, index),
+                    }
+                })
+            })
+            .collect()
+    }
+
+    fn fetch_output_smt_delta(&self, height: u64) -> Result<Option<OutputSmtDelta>, ChainStorageError> {
+        let txn = self.read_transaction()?;
+        lmdb_get(&txn, &self.output_smt_deltas, &height)
+    }
+
     fn calculate_tip_smt(&self) -> Result<OutputSmt, ChainStorageError> {
         let start = Instant::now();
         let metadata = self.fetch_chain_metadata()?;
@@ -2643,6 +2849,37 @@ fn fetch_pruning_horizon(txn: &ConstTransaction<'_>, db: &Database) -> Result<u6
     }
 }
 
+// Fetches whether the output indexes are maintained from the provided metadata db.
+fn fetch_output_indexing(txn: &ConstTransaction<'_>, db: &Database) -> Result<bool, ChainStorageError> {
+    let k = MetadataKey::OutputIndexing;
+    let val: Option<MetadataValue> = lmdb_get(txn, db, &k.as_u32())?;
+    match val {
+        Some(MetadataValue::OutputIndexing(enabled)) => Ok(enabled),
+        _ => Ok(false),
+    }
+}
+
+// Fetches whether the output SMT delta of every block is stored from the provided metadata db.
+fn fetch_archival(txn: &ConstTransaction<'_>, db: &Database) -> Result<bool, ChainStorageError> {
+    let k = MetadataKey::Archival;
+    let val: Option<MetadataValue> = lmdb_get(txn, db, &k.as_u32())?;
+    match val {
+        Some(MetadataValue::Archival(enabled)) => Ok(enabled),
+        _ => Ok(false),
+    }
+}
+
+// Fetches the version of the output indexes from the provided metadata db. Indexes built before the index set was
+// versioned are at version 0.
+fn fetch_output_index_version(txn: &ConstTransaction<'_>, db: &Database) -> Result<u32, ChainStorageError> {
+    let k = MetadataKey::OutputIndexVersion;
+    let val: Option<MetadataValue> = lmdb_get(txn, db, &k.as_u32())?;
+    match val {
+        Some(MetadataValue::OutputIndexVersion(version)) => Ok(version),
+        _ => Ok(0),
+    }
+}
+
 fn get_database(store: &LMDBStore, name: &str) -> Result<DatabaseRef, ChainStorageError> {
     let handle = store
         .get_handle(name)
@@ -2660,6 +2897,9 @@ enum MetadataKey {
     HorizonData,
     BestBlockTimestamp,
     MigrationVersion,
+    OutputIndexing,
+    OutputIndexVersion,
+    Archival,
 }
 
 impl MetadataKey {
@@ -2680,6 +2920,9 @@ impl fmt::Display for MetadataKey {
             MetadataKey::HorizonData => write!(f, "Database info"),
             MetadataKey::BestBlockTimestamp => write!(f, "Chain tip block timestamp"),
             MetadataKey::MigrationVersion => write!(f, "Migration version"),
+            MetadataKey::OutputIndexing => write!(f, "Output indexing"),
+            MetadataKey::OutputIndexVersion => write!(f, "Output index version"),
+            MetadataKey::Archival => write!(f, "Archival"),
         }
     }
 }
@@ -2695,6 +2938,9 @@ enum MetadataValue {
     HorizonData(HorizonData),
     BestBlockTimestamp(u64),
     MigrationVersion(u64),
+    OutputIndexing(bool),
+    OutputIndexVersion(u32),
+    Archival(bool),
 }
 
 impl fmt::Display for MetadataValue {
@@ -2708,6 +2954,9 @@ impl fmt::Display for MetadataValue {
             MetadataValue::HorizonData(_) => write!(f, "Horizon data"),
             MetadataValue::BestBlockTimestamp(timestamp) => write!(f, "Chain tip block timestamp is {}", timestamp),
             MetadataValue::MigrationVersion(n) => write!(f, "Migration version {}", n),
+            MetadataValue::OutputIndexing(enabled) => write!(f, "Output indexing is enabled: {}", enabled),
+            MetadataValue::OutputIndexVersion(version) => write!(f, "Output index version {}", version),
+            MetadataValue::Archival(enabled) => write!(f, "Archival is enabled: {}", enabled),
         }
     }
 }
@@ -2744,3 +2993,53 @@ fn run_migrations(db: &LMDBDatabase) -> Result<(), ChainStorageError> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{chain_storage::BlockchainDatabaseConfig, test_helpers::blockchain::create_new_blockchain_with_config};
+
+    #[test]
+    fn it_rebuilds_output_indexes_built_for_another_version() {
+        let db = create_new_blockchain_with_config(BlockchainDatabaseConfig {
+            index_outputs: true,
+            ..Default::default()
+        });
+        let genesis = db.fetch_block(0, true).unwrap().into_block();
+        let output = genesis.body.outputs()[0].clone();
+        let index = OutputIndex::SenderOffsetPublicKey(output.sender_offset_public_key.clone());
+        let is_indexed = || {
+            db.fetch_outputs_by_index(index.clone(), ..)
+                .unwrap()
+                .iter()
+                .any(|info| info.output.hash() == output.hash())
+        };
+        assert!(is_indexed());
+
+        // Simulate indexes that were built before the sender offset index was added
+        {
+            let lmdb = db.db_read_access().unwrap();
+            let txn = lmdb.write_transaction().unwrap();
+            lmdb_delete(
+                &txn,
+                &lmdb.output_indexes,
+                index.storage_key(0, &output.hash()).as_slice(),
+                LMDB_DB_OUTPUT_INDEXES,
+            )
+            .unwrap();
+            lmdb.set_metadata(
+                &txn,
+                MetadataKey::OutputIndexVersion,
+                &MetadataValue::OutputIndexVersion(0),
+            )
+            .unwrap();
+            txn.commit().unwrap();
+        }
+        assert!(!is_indexed());
+
+        let mut txn = DbTransaction::new();
+        txn.set_output_indexing(true);
+        db.write(txn).unwrap();
+        assert!(is_indexed());
+    }
+}
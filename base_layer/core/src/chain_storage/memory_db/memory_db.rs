@@ -0,0 +1,2517 @@
+// Copyright 2024 The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    convert::TryFrom,
+    fmt::Debug,
+    hash::Hash,
+    mem,
+    ops::Deref,
+    sync::{Arc, RwLock, RwLockReadGuard},
+    time::Instant,
+};
+
+use log::*;
+use primitive_types::U256;
+use serde::Serialize;
+use tari_common_types::{
+    chain_metadata::ChainMetadata,
+    epoch::VnEpoch,
+    types::{BlockHash, Commitment, FixedHash, HashOutput, PublicKey, Signature},
+};
+use tari_mmr::sparse_merkle_tree::{DeleteResult, NodeKey, SMTError, ValueHash};
+use tari_utilities::{hex::Hex, ByteArray};
+
+use crate::{
+    blocks::{
+        Block,
+        BlockAccumulatedData,
+        BlockHeader,
+        BlockHeaderAccumulatedData,
+        ChainBlock,
+        ChainHeader,
+        UpdateBlockAccumulatedData,
+    },
+    chain_storage::{
+        db_transaction::{DbKey, DbTransaction, DbValue, WriteOperation},
+        error::ChainStorageError,
+        BlockchainBackend,
+        ChainTipData,
+        DbBasicStats,
+        DbSize,
+        DbTotalSizeStats,
+        HorizonData,
+        InputMinedInfo,
+        MmrTree,
+        OutputIndex,
+        OutputMinedInfo,
+        OutputSmtDelta,
+        Reorg,
+        TemplateRegistrationEntry,
+        ValidatorNodeEntry,
+    },
+    consensus::{ConsensusConstants, ConsensusManager},
+    transactions::{
+        aggregated_body::AggregateBody,
+        transaction_components::{
+            OutputType,
+            SpentOutput,
+            TransactionInput,
+            TransactionKernel,
+            TransactionOutput,
+            ValidatorNodeRegistration,
+        },
+    },
+    OutputSmt,
+    PrunedKernelMmr,
+};
+
+const LOG_TARGET: &str = "c::cs::memory_db::memory_db";
+
+// Table names match those of the LMDB backend so that stats can be compared between backends
+const DB_HEADERS: &str = "headers";
+const DB_HEADER_ACCUMULATED_DATA: &str = "header_accumulated_data";
+const DB_BLOCK_ACCUMULATED_DATA: &str = "mmr_peak_data";
+const DB_BLOCK_HASHES: &str = "block_hashes";
+const DB_UTXOS: &str = "utxos";
+const DB_INPUTS: &str = "inputs";
+const DB_TXOS_HASH_TO_INDEX: &str = "txos_hash_to_index";
+const DB_KERNELS: &str = "kernels";
+const DB_KERNEL_EXCESS_INDEX: &str = "kernel_excess_index";
+const DB_KERNEL_EXCESS_SIG_INDEX: &str = "kernel_excess_sig_index";
+const DB_KERNEL_MMR_SIZE_INDEX: &str = "kernel_mmr_size_index";
+const DB_DELETED_TXO_HASH_TO_HEADER_INDEX: &str = "deleted_txo_hash_to_header_index";
+const DB_UTXO_COMMITMENT_INDEX: &str = "utxo_commitment_index";
+const DB_ORPHANS: &str = "orphans";
+const DB_MONERO_SEED_HEIGHT: &str = "monero_seed_height";
+const DB_ORPHAN_HEADER_ACCUMULATED_DATA: &str = "orphan_accumulated_data";
+const DB_ORPHAN_CHAIN_TIPS: &str = "orphan_chain_tips";
+const DB_ORPHAN_PARENT_MAP_INDEX: &str = "orphan_parent_map_index";
+const DB_BAD_BLOCK_LIST: &str = "bad_blocks";
+const DB_REORGS: &str = "reorgs";
+const DB_VALIDATOR_NODES: &str = "validator_nodes";
+const DB_VALIDATOR_NODES_MAPPING: &str = "validator_nodes_mapping";
+const DB_TEMPLATE_REGISTRATIONS: &str = "template_registrations";
+const DB_OUTPUT_INDEXES: &str = "output_indexes";
+const DB_OUTPUT_SMT_DELTAS: &str = "output_smt_deltas";
+
+type ShardKey = [u8; 32];
+/// Block hash, mmr position, kernel hash
+type KernelKey = (HashOutput, u64, HashOutput);
+/// Block hash, output hash
+type OutputKey = (HashOutput, HashOutput);
+/// Block hash, input hash
+type InputKey = (HashOutput, HashOutput);
+
+/// Creates a new, empty in-memory blockchain database.
+pub fn create_memory_database(consensus_manager: ConsensusManager) -> MemoryDatabase {
+    debug!(target: LOG_TARGET, "Creating in-memory blockchain database");
+    MemoryDatabase::new(consensus_manager)
+}
+
+/// A blockchain database that keeps the entire chain state in memory. It is intended for unit tests, fuzzers and
+/// short-lived nodes that do not need to persist state between runs.
+///
+/// The tables and their semantics mirror those of [LMDBDatabase](crate::chain_storage::LMDBDatabase). Every
+/// [DbTransaction] is applied in place. Every table records the value each key held before it was changed, and every
+/// change made to the output SMT, which is shared with the caller, is recorded in the same way. If an operation fails,
+/// the changes made by the earlier operations in the transaction are undone, so a failed write leaves both the database
+/// and the SMT untouched.
+pub struct MemoryDatabase {
+    db: RwLock<MemoryDbInner>,
+    consensus_manager: ConsensusManager,
+}
+
+impl MemoryDatabase {
+    pub fn new(consensus_manager: ConsensusManager) -> Self {
+        Self {
+            db: RwLock::new(MemoryDbInner::default()),
+            consensus_manager,
+        }
+    }
+
+    fn read_access(&self) -> Result<RwLockReadGuard<'_, MemoryDbInner>, ChainStorageError> {
+        self.db.read().map_err(|e| {
+            error!(target: LOG_TARGET, "An attempt to get a read lock on the memory db failed. {:?}", e);
+            ChainStorageError::AccessError("Read lock on memory db failed".into())
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct TransactionOutputRowData {
+    output: TransactionOutput,
+    header_hash: HashOutput,
+    hash: HashOutput,
+    mined_height: u64,
+    mined_timestamp: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct TransactionInputRowData {
+    input: TransactionInput,
+    header_hash: HashOutput,
+    spent_timestamp: u64,
+    spent_height: u64,
+    hash: HashOutput,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct TransactionKernelRowData {
+    kernel: TransactionKernel,
+    header_hash: HashOutput,
+    mmr_position: u64,
+    hash: HashOutput,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Metadata {
+    chain_height: Option<u64>,
+    best_block: Option<BlockHash>,
+    accumulated_work: Option<U256>,
+    pruning_horizon: u64,
+    pruned_height: u64,
+    horizon_data: Option<HorizonData>,
+    best_block_timestamp: Option<u64>,
+    output_indexing: bool,
+    archival: bool,
+}
+
+#[derive(Debug, Default)]
+struct MemoryDbInner {
+    metadata: Metadata,
+    /// Maps height -> BlockHeader
+    headers: OrderedTable<u64, BlockHeader>,
+    /// Maps height -> BlockHeaderAccumulatedData
+    header_accumulated_data: OrderedTable<u64, BlockHeaderAccumulatedData>,
+    /// Maps height -> BlockAccumulatedData
+    block_accumulated_data: OrderedTable<u64, BlockAccumulatedData>,
+    /// Maps block_hash -> height
+    block_hashes: HashTable<HashOutput, u64>,
+    /// Maps OutputKey -> TransactionOutputRowData
+    utxos: OrderedTable<OutputKey, TransactionOutputRowData>,
+    /// Maps InputKey -> TransactionInputRowData
+    inputs: OrderedTable<InputKey, TransactionInputRowData>,
+    /// Maps output_hash -> block_hash
+    txos_hash_to_index: HashTable<HashOutput, HashOutput>,
+    /// Maps KernelKey -> TransactionKernelRowData
+    kernels: OrderedTable<KernelKey, TransactionKernelRowData>,
+    /// Maps excess -> KernelKey
+    kernel_excess_index: HashTable<Vec<u8>, KernelKey>,
+    /// Maps excess_sig -> KernelKey
+    kernel_excess_sig_index: HashTable<Vec<u8>, KernelKey>,
+    /// Maps kernel_mmr_size -> height
+    kernel_mmr_size_index: OrderedTable<u64, u64>,
+    /// Maps commitment -> output_hash
+    utxo_commitment_index: HashTable<Vec<u8>, HashOutput>,
+    /// Maps output hash -> InputKey
+    deleted_txo_hash_to_header_index: HashTable<HashOutput, InputKey>,
+    /// Maps block_hash -> Block
+    orphans: HashTable<HashOutput, Block>,
+    /// Maps randomx_seed -> height
+    monero_seed_height: HashTable<Vec<u8>, u64>,
+    /// Maps block_hash -> BlockHeaderAccumulatedData
+    orphan_header_accumulated_data: HashTable<HashOutput, BlockHeaderAccumulatedData>,
+    /// Stores the orphan tip block hashes
+    orphan_chain_tips: HashTable<HashOutput, ChainTipData>,
+    /// Maps parent_block_hash -> block_hash
+    orphan_parent_map_index: HashTable<HashOutput, BTreeSet<HashOutput>>,
+    /// Maps block_hash -> (height, reason)
+    bad_blocks: HashTable<HashOutput, (u64, String)>,
+    /// Stores reorgs by epochtime
+    reorgs: OrderedTable<i64, Reorg>,
+    /// Maps <Height, VN PK, commitment> -> ValidatorNodeEntry
+    validator_nodes: OrderedTable<(u64, Vec<u8>, Vec<u8>), ValidatorNodeEntry>,
+    /// Maps <VN PK, Height, commitment> -> VN Shard Key
+    validator_nodes_mapping: OrderedTable<(Vec<u8>, u64, Vec<u8>), ShardKey>,
+    /// Maps <block_height, output_hash> -> TemplateRegistrationEntry
+    template_registrations: OrderedTable<(u64, HashOutput), TemplateRegistrationEntry>,
+    /// Maps <OutputIndex, mined height, output hash> -> output hash
+    output_indexes: OrderedTable<Vec<u8>, HashOutput>,
+    /// Maps height -> OutputSmtDelta
+    output_smt_deltas: OrderedTable<u64, OutputSmtDelta>,
+    /// The changes made to output SMTs by the transaction being applied, in the order they were made
+    smt_undo_log: Vec<SmtChange>,
+}
+
+/// A change made to an output SMT, along with the value the key held before the change
+#[derive(Clone)]
+struct SmtChange {
+    smt: Arc<RwLock<OutputSmt>>,
+    key: NodeKey,
+    previous: Option<ValueHash>,
+}
+
+impl Debug for SmtChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SmtChange")
+            .field("key", &self.key)
+            .field("previous", &self.previous)
+            .finish()
+    }
+}
+
+impl MemoryDbInner {
+    /// Runs `apply` against the database, undoing every change it made to the tables, metadata and output SMTs if it
+    /// fails
+    fn atomically<T, F>(&mut self, apply: F) -> Result<T, ChainStorageError>
+    where F: FnOnce(&mut Self) -> Result<T, ChainStorageError> {
+        let metadata = self.metadata.clone();
+        match apply(self) {
+            Ok(value) => {
+                self.smt_undo_log.clear();
+                for table in self.tables_mut() {
+                    table.commit();
+                }
+                Ok(value)
+            },
+            Err(e) => {
+                self.revert_smt_changes();
+                for table in self.tables_mut() {
+                    table.revert();
+                }
+                self.metadata = metadata;
+                Err(e)
+            },
+        }
+    }
+
+    fn tables_mut(&mut self) -> [&mut dyn UndoLog; 25] {
+        [
+            &mut self.headers,
+            &mut self.header_accumulated_data,
+            &mut self.block_accumulated_data,
+            &mut self.block_hashes,
+            &mut self.utxos,
+            &mut self.inputs,
+            &mut self.txos_hash_to_index,
+            &mut self.kernels,
+            &mut self.kernel_excess_index,
+            &mut self.kernel_excess_sig_index,
+            &mut self.kernel_mmr_size_index,
+            &mut self.utxo_commitment_index,
+            &mut self.deleted_txo_hash_to_header_index,
+            &mut self.orphans,
+            &mut self.monero_seed_height,
+            &mut self.orphan_header_accumulated_data,
+            &mut self.orphan_chain_tips,
+            &mut self.orphan_parent_map_index,
+            &mut self.bad_blocks,
+            &mut self.reorgs,
+            &mut self.validator_nodes,
+            &mut self.validator_nodes_mapping,
+            &mut self.template_registrations,
+            &mut self.output_indexes,
+            &mut self.output_smt_deltas,
+        ]
+    }
+
+    #[allow(clippy::too_many_lines)]
+    fn apply_db_transaction(
+        &mut self,
+        txn: &DbTransaction,
+        consensus_manager: &ConsensusManager,
+    ) -> Result<(), ChainStorageError> {
+        #[allow(clippy::enum_glob_use)]
+        use WriteOperation::*;
+
+        let number_of_operations = txn.operations().len();
+        for (i, op) in txn.operations().iter().enumerate() {
+            trace!(target: LOG_TARGET, "[apply_db_transaction] WriteOperation: {} ({} of {})", op, i + 1, number_of_operations);
+            match op {
+                InsertOrphanBlock(block) => self.insert_orphan_block(block)?,
+                InsertChainHeader { header } => {
+                    self.insert_header(header.header(), header.accumulated_data())?;
+                },
+                InsertTipBlockBody { block, smt } => {
+                    self.insert_tip_block_body(
+                        block.header(),
+                        block.block().body.clone(),
+                        smt.clone(),
+                        consensus_manager,
+                    )?;
+                },
+                InsertKernel {
+                    header_hash,
+                    kernel,
+                    mmr_position,
+                } => {
+                    self.insert_kernel(header_hash, kernel, *mmr_position)?;
+                },
+                InsertOutput {
+                    header_hash,
+                    header_height,
+                    timestamp,
+                    output,
+                } => {
+                    self.insert_output(header_hash, *header_height, *timestamp, output)?;
+                },
+                DeleteHeader(height) => {
+                    self.delete_header(*height)?;
+                },
+                DeleteOrphan(hash) => {
+                    self.delete_orphan(hash)?;
+                },
+                DeleteOrphanChainTip(hash) => {
+                    remove(&mut self.orphan_chain_tips, hash, DB_ORPHAN_CHAIN_TIPS)?;
+                },
+                InsertOrphanChainTip(hash, total_accumulated_difficulty) => {
+                    insert(
+                        &mut self.orphan_chain_tips,
+                        *hash,
+                        ChainTipData {
+                            hash: *hash,
+                            total_accumulated_difficulty: *total_accumulated_difficulty,
+                        },
+                        DB_ORPHAN_CHAIN_TIPS,
+                    )?;
+                },
+                DeleteTipBlock(hash, smt) => {
+                    self.delete_tip_block_body(hash, smt.clone())?;
+                },
+                InsertMoneroSeedHeight(data, height) => {
+                    self.insert_monero_seed_height(data, *height);
+                },
+                SetAccumulatedDataForOrphan(accumulated_data) => {
+                    self.set_accumulated_data_for_orphan(accumulated_data)?;
+                },
+                InsertChainOrphanBlock(chain_block) => {
+                    self.insert_orphan_block(chain_block.block())?;
+                    self.set_accumulated_data_for_orphan(chain_block.accumulated_data())?;
+                },
+                UpdateBlockAccumulatedData { header_hash, values } => {
+                    self.update_block_accumulated_data(header_hash, values.clone())?;
+                },
+                PruneOutputsSpentAtHash { block_hash } => {
+                    self.prune_outputs_spent_at_hash(block_hash)?;
+                },
+                PruneOutputFromAllDbs {
+                    output_hash,
+                    commitment,
+                    output_type,
+                } => {
+                    self.prune_output_from_all_dbs(output_hash, commitment, *output_type)?;
+                },
+                DeleteAllKernelsInBlock { block_hash } => {
+                    self.delete_block_kernels(block_hash)?;
+                    debug!(target: LOG_TARGET, "Deleted kernels in block {}", block_hash.to_hex());
+                },
+                DeleteAllInputsInBlock { block_hash } => {
+                    let inputs = drain_block_rows(&mut self.inputs, (*block_hash, FixedHash::zero()), |(h, _)| {
+                        h == block_hash
+                    });
+                    debug!(target: LOG_TARGET, "Deleted {} input(s)", inputs.len());
+                },
+                SetBestBlock {
+                    height,
+                    hash,
+                    accumulated_difficulty,
+                    expected_prev_best_block,
+                    timestamp,
+                } => {
+                    // The same checks as the LMDB backend: the best block must exist, and the previous value must
+                    // match unless this is the first best block being set.
+                    if *height > 0 {
+                        let prev = self.fetch_best_block()?;
+                        if *expected_prev_best_block != prev {
+                            return Err(ChainStorageError::InvalidOperation(format!(
+                                "There was a change in best_block, the best block is suppose to be: ({}), but it \
+                                 currently is: ({})",
+                                expected_prev_best_block.to_hex(),
+                                prev.to_hex(),
+                            )));
+                        };
+                    }
+                    if !self.block_hashes.contains_key(hash) {
+                        return Err(ChainStorageError::InvalidOperation(format!(
+                            "There is no Blockheader hash ({}) in db",
+                            expected_prev_best_block.to_hex(),
+                        )));
+                    };
+                    self.metadata.chain_height = Some(*height);
+                    self.metadata.best_block = Some(*hash);
+                    self.metadata.accumulated_work = Some(*accumulated_difficulty);
+                    self.metadata.best_block_timestamp = Some(*timestamp);
+                },
+                SetPruningHorizonConfig(pruning_horizon) => {
+                    self.metadata.pruning_horizon = *pruning_horizon;
+                },
+                SetPrunedHeight { height } => {
+                    self.metadata.pruned_height = *height;
+                },
+                SetHorizonData { horizon_data } => {
+                    self.metadata.horizon_data = Some(horizon_data.clone());
+                },
+                InsertBadBlock { hash, height, reason } => {
+                    self.insert_bad_block_and_cleanup(hash, *height, reason.to_string())?;
+                },
+                InsertReorg { reorg } => {
+                    self.reorgs.put(reorg.local_time.timestamp(), reorg.clone());
+                },
+                ClearAllReorgs => {
+                    self.reorgs.clear();
+                },
+                SetOutputIndexingConfig(enabled) => {
+                    self.set_output_indexing(*enabled)?;
+                },
+                SetArchivalConfig(enabled) => {
+                    self.metadata.archival = *enabled;
+                },
+            }
+        }
+
+        Ok(())
+    }
+
+    fn insert_output(
+        &mut self,
+        header_hash: &HashOutput,
+        header_height: u64,
+        header_timestamp: u64,
+        output: &TransactionOutput,
+    ) -> Result<(), ChainStorageError> {
+        let output_hash = output.hash();
+
+        if !output.is_burned() {
+            insert(
+                &mut self.utxo_commitment_index,
+                output.commitment.to_vec(),
+                output_hash,
+                DB_UTXO_COMMITMENT_INDEX,
+            )?;
+        }
+
+        insert(
+            &mut self.txos_hash_to_index,
+            output_hash,
+            *header_hash,
+            DB_TXOS_HASH_TO_INDEX,
+        )?;
+        insert(
+            &mut self.utxos,
+            (*header_hash, output_hash),
+            TransactionOutputRowData {
+                output: output.clone(),
+                header_hash: *header_hash,
+                hash: output_hash,
+                mined_height: header_height,
+                mined_timestamp: header_timestamp,
+            },
+            DB_UTXOS,
+        )?;
+
+        if self.metadata.output_indexing {
+            self.insert_output_indexes(output, header_height, &output_hash)?;
+        }
+        Ok(())
+    }
+
+    fn insert_output_indexes(
+        &mut self,
+        output: &TransactionOutput,
+        mined_height: u64,
+        output_hash: &HashOutput,
+    ) -> Result<(), ChainStorageError> {
+        for index in OutputIndex::all_for_output(output) {
+            insert(
+                &mut self.output_indexes,
+                index.storage_key(mined_height, output_hash),
+                *output_hash,
+                DB_OUTPUT_INDEXES,
+            )?;
+        }
+        Ok(())
+    }
+
+    fn delete_output_indexes(
+        &mut self,
+        output: &TransactionOutput,
+        mined_height: u64,
+        output_hash: &HashOutput,
+    ) -> Result<(), ChainStorageError> {
+        if !self.metadata.output_indexing {
+            return Ok(());
+        }
+        for index in OutputIndex::all_for_output(output) {
+            remove(
+                &mut self.output_indexes,
+                &index.storage_key(mined_height, output_hash),
+                DB_OUTPUT_INDEXES,
+            )?;
+        }
+        Ok(())
+    }
+
+    fn set_output_indexing(&mut self, enabled: bool) -> Result<(), ChainStorageError> {
+        if self.metadata.output_indexing == enabled {
+            return Ok(());
+        }
+
+        if enabled {
+            let outputs = self
+                .utxos
+                .values()
+                .map(|row| (row.output.clone(), row.mined_height, row.hash))
+                .collect::<Vec<_>>();
+            for (output, mined_height, output_hash) in outputs {
+                self.insert_output_indexes(&output, mined_height, &output_hash)?;
+            }
+        } else {
+            self.output_indexes.clear();
+        }
+        self.metadata.output_indexing = enabled;
+        Ok(())
+    }
+
+    fn insert_kernel(
+        &mut self,
+        header_hash: &HashOutput,
+        kernel: &TransactionKernel,
+        mmr_position: u64,
+    ) -> Result<(), ChainStorageError> {
+        let hash = kernel.hash();
+        let key = (*header_hash, mmr_position, hash);
+
+        insert(
+            &mut self.kernel_excess_index,
+            kernel.excess.to_vec(),
+            key,
+            DB_KERNEL_EXCESS_INDEX,
+        )?;
+        insert(
+            &mut self.kernel_excess_sig_index,
+            excess_sig_key(&kernel.excess_sig),
+            key,
+            DB_KERNEL_EXCESS_SIG_INDEX,
+        )?;
+        insert(
+            &mut self.kernels,
+            key,
+            TransactionKernelRowData {
+                kernel: kernel.clone(),
+                header_hash: *header_hash,
+                mmr_position,
+                hash,
+            },
+            DB_KERNELS,
+        )
+    }
+
+    fn input_with_output_data(&self, input: TransactionInput) -> Result<TransactionInput, ChainStorageError> {
+        let input_with_output_data = match input.spent_output {
+            SpentOutput::OutputData { .. } => input,
+            SpentOutput::OutputHash(output_hash) => match self.fetch_output(&output_hash) {
+                Some(utxo_mined_info) => TransactionInput {
+                    version: input.version,
+                    spent_output: SpentOutput::create_from_output(utxo_mined_info.output),
+                    input_data: input.input_data,
+                    script_signature: input.script_signature,
+                },
+                None => {
+                    error!(
+                        target: LOG_TARGET,
+                        "Could not retrieve output data from input's output_hash `{}`",
+                        output_hash.to_hex()
+                    );
+                    return Err(ChainStorageError::ValueNotFound {
+                        entity: "UTXO",
+                        field: "hash",
+                        value: output_hash.to_hex(),
+                    });
+                },
+            },
+        };
+        Ok(input_with_output_data)
+    }
+
+    fn insert_input(
+        &mut self,
+        height: u64,
+        header_timestamp: u64,
+        header_hash: &HashOutput,
+        input: TransactionInput,
+    ) -> Result<(), ChainStorageError> {
+        let input_with_output_data = self.input_with_output_data(input)?;
+        remove(
+            &mut self.utxo_commitment_index,
+            &input_with_output_data.commitment()?.to_vec(),
+            DB_UTXO_COMMITMENT_INDEX,
+        )?;
+
+        let hash = input_with_output_data.canonical_hash();
+        let output_hash = input_with_output_data.output_hash();
+        let key = (*header_hash, hash);
+        insert(
+            &mut self.deleted_txo_hash_to_header_index,
+            output_hash,
+            key,
+            DB_DELETED_TXO_HASH_TO_HEADER_INDEX,
+        )?;
+
+        insert(
+            &mut self.inputs,
+            key,
+            TransactionInputRowData {
+                input: input_with_output_data.to_compact(),
+                header_hash: *header_hash,
+                spent_timestamp: header_timestamp,
+                spent_height: height,
+                hash,
+            },
+            DB_INPUTS,
+        )
+    }
+
+    fn insert_orphan_block(&mut self, block: &Block) -> Result<(), ChainStorageError> {
+        let k = block.hash();
+        let mut children = self
+            .orphan_parent_map_index
+            .get(&block.header.prev_hash)
+            .cloned()
+            .unwrap_or_default();
+        children.insert(k);
+        self.orphan_parent_map_index.put(block.header.prev_hash, children);
+        insert(&mut self.orphans, k, block.clone(), DB_ORPHANS)
+    }
+
+    fn set_accumulated_data_for_orphan(
+        &mut self,
+        accumulated_data: &BlockHeaderAccumulatedData,
+    ) -> Result<(), ChainStorageError> {
+        if !self.orphans.contains_key(&accumulated_data.hash) {
+            return Err(ChainStorageError::InvalidOperation(format!(
+                "set_accumulated_data_for_orphan: orphan {} does not exist",
+                accumulated_data.hash.to_hex()
+            )));
+        }
+
+        insert(
+            &mut self.orphan_header_accumulated_data,
+            accumulated_data.hash,
+            accumulated_data.clone(),
+            DB_ORPHAN_HEADER_ACCUMULATED_DATA,
+        )
+    }
+
+    /// Inserts the header and header accumulated data.
+    fn insert_header(
+        &mut self,
+        header: &BlockHeader,
+        accum_data: &BlockHeaderAccumulatedData,
+    ) -> Result<(), ChainStorageError> {
+        if let Some(current_header_at_height) = self.headers.get(&header.height) {
+            let hash = current_header_at_height.hash();
+            if hash != accum_data.hash {
+                return Err(ChainStorageError::InvalidOperation(format!(
+                    "There is a different header stored at height {} already. New header ({}), current header: ({})",
+                    header.height,
+                    accum_data.hash.to_hex(),
+                    hash.to_hex(),
+                )));
+            }
+            return Err(ChainStorageError::InvalidOperation(format!(
+                "The header at height {} already exists. Existing header hash: {}",
+                header.height,
+                hash.to_hex()
+            )));
+        }
+
+        if let Some(last_header) = self.fetch_last_header() {
+            if last_header.height != header.height.saturating_sub(1) {
+                return Err(ChainStorageError::InvalidOperation(format!(
+                    "Attempted to insert a header out of order. The last header height is {} but attempted to insert \
+                     a header with height {}",
+                    last_header.height, header.height,
+                )));
+            }
+
+            let hash = last_header.hash();
+            if hash != header.prev_hash {
+                return Err(ChainStorageError::InvalidOperation(format!(
+                    "Attempted to insert a block header at height {} that didn't form a chain. Previous block \
+                     hash:{}, new block's previous hash:{}",
+                    header.height,
+                    hash.to_hex(),
+                    header.prev_hash.to_hex()
+                )));
+            }
+        } else if header.height != 0 {
+            return Err(ChainStorageError::InvalidOperation(format!(
+                "The first header inserted must have height 0. Height provided: {}",
+                header.height
+            )));
+        } else {
+            // we can continue
+        }
+
+        insert(
+            &mut self.header_accumulated_data,
+            header.height,
+            accum_data.clone(),
+            DB_HEADER_ACCUMULATED_DATA,
+        )?;
+        insert(&mut self.block_hashes, header.hash(), header.height, DB_BLOCK_HASHES)?;
+        insert(&mut self.headers, header.height, header.clone(), DB_HEADERS)?;
+        insert(
+            &mut self.kernel_mmr_size_index,
+            header.kernel_mmr_size,
+            header.height,
+            DB_KERNEL_MMR_SIZE_INDEX,
+        )?;
+        Ok(())
+    }
+
+    fn delete_header(&mut self, height: u64) -> Result<(), ChainStorageError> {
+        if self.block_accumulated_data.contains_key(&height) {
+            return Err(ChainStorageError::InvalidOperation(format!(
+                "Attempted to delete header at height {} while block accumulated data still exists",
+                height
+            )));
+        }
+
+        let header = self
+            .fetch_last_header()
+            .cloned()
+            .ok_or_else(|| ChainStorageError::ValueNotFound {
+                entity: "BlockHeader",
+                field: "height",
+                value: "last_header".to_string(),
+            })?;
+        if header.height != height {
+            return Err(ChainStorageError::InvalidOperation(format!(
+                "Attempted to delete a header at height {} that was not the last header (which is at height {}). \
+                 Headers must be deleted in reverse order.",
+                height, header.height
+            )));
+        }
+
+        let hash = header.hash();
+
+        // Check that there are no utxos or kernels linked to this.
+        if self.kernels_in_block(&hash).next().is_some() {
+            return Err(ChainStorageError::InvalidOperation(format!(
+                "Cannot delete header {} ({}) because there are kernels linked to it",
+                header.height,
+                hash.to_hex()
+            )));
+        }
+        if self.outputs_in_block(&hash).next().is_some() {
+            return Err(ChainStorageError::InvalidOperation(format!(
+                "Cannot delete header at height {} ({}) because there are UTXOs linked to it",
+                height,
+                hash.to_hex()
+            )));
+        }
+
+        remove(&mut self.block_hashes, &hash, DB_BLOCK_HASHES)?;
+        remove(&mut self.headers, &height, DB_HEADERS)?;
+        remove(&mut self.header_accumulated_data, &height, DB_HEADER_ACCUMULATED_DATA)?;
+        remove(
+            &mut self.kernel_mmr_size_index,
+            &header.kernel_mmr_size,
+            DB_KERNEL_MMR_SIZE_INDEX,
+        )?;
+
+        Ok(())
+    }
+
+    fn delete_tip_block_body(
+        &mut self,
+        block_hash: &HashOutput,
+        smt: Arc<RwLock<OutputSmt>>,
+    ) -> Result<(), ChainStorageError> {
+        let hash_hex = block_hash.to_hex();
+        debug!(target: LOG_TARGET, "Deleting block `{}`", hash_hex);
+        let height = *self
+            .block_hashes
+            .get(block_hash)
+            .ok_or_else(|| ChainStorageError::ValueNotFound {
+                entity: "Block",
+                field: "hash",
+                value: hash_hex,
+            })?;
+        let next_height = height.saturating_add(1);
+        let prev_height = height.saturating_sub(1);
+        if self.block_accumulated_data.contains_key(&next_height) {
+            return Err(ChainStorageError::InvalidOperation(format!(
+                "Attempted to delete block at height {} while next block still exists",
+                height
+            )));
+        }
+
+        remove(&mut self.block_accumulated_data, &height, DB_BLOCK_ACCUMULATED_DATA)?;
+
+        let mut output_smt = smt.write().map_err(|e| {
+            error!(
+                target: LOG_TARGET,
+                "delete_tip_block_body could not get a write lock on the smt. {:?}", e
+            );
+            ChainStorageError::AccessError("write lock on smt".into())
+        })?;
+
+        self.delete_block_inputs_outputs(block_hash, &smt, &mut output_smt)?;
+        // The delta is deleted even if archival has since been disabled, so that a stale delta is never stored
+        self.output_smt_deltas.take(&height);
+
+        let new_tip_header = self.fetch_chain_header_by_height(prev_height)?;
+        let root = FixedHash::try_from(output_smt.hash().as_slice())?;
+        if root != new_tip_header.header().output_mr {
+            error!(
+                target: LOG_TARGET,
+                "Deleting block, new smt root(#{}) did not match expected (#{}) smt root",
+                    root.to_hex(),
+                    new_tip_header.header().output_mr.to_hex(),
+            );
+            return Err(ChainStorageError::InvalidOperation(
+                "Deleting block, new smt root did not match expected smt root".to_string(),
+            ));
+        }
+
+        self.delete_block_kernels(block_hash)?;
+
+        Ok(())
+    }
+
+    fn delete_block_inputs_outputs(
+        &mut self,
+        block_hash: &HashOutput,
+        smt: &Arc<RwLock<OutputSmt>>,
+        output_smt: &mut OutputSmt,
+    ) -> Result<(), ChainStorageError> {
+        let output_rows = drain_block_rows(&mut self.utxos, (*block_hash, FixedHash::zero()), |(h, _)| {
+            h == block_hash
+        });
+        debug!(target: LOG_TARGET, "Deleted {} outputs...", output_rows.len());
+        let inputs = drain_block_rows(&mut self.inputs, (*block_hash, FixedHash::zero()), |(h, _)| {
+            h == block_hash
+        });
+        debug!(target: LOG_TARGET, "Deleted {} input(s)...", inputs.len());
+
+        for utxo in &output_rows {
+            trace!(target: LOG_TARGET, "Deleting UTXO `{}`", utxo.hash.to_hex());
+            remove(&mut self.txos_hash_to_index, &utxo.hash, DB_TXOS_HASH_TO_INDEX)?;
+            self.delete_output_indexes(&utxo.output, utxo.mined_height, &utxo.hash)?;
+
+            let output_hash = utxo.output.hash();
+            // if an output was already spent in the block, it was never created as unspent, so dont delete it as it
+            // does not exist here
+            if inputs.iter().any(|r| r.input.output_hash() == output_hash) {
+                continue;
+            }
+            // if an output was burned, it was never created as an unspent utxo
+            if utxo.output.is_burned() {
+                continue;
+            }
+            let smt_key = NodeKey::try_from(utxo.output.commitment.as_bytes())?;
+            match self.smt_delete(smt, output_smt, smt_key)? {
+                DeleteResult::Deleted(_value_hash) => {},
+                DeleteResult::KeyNotFound => {
+                    error!(
+                        target: LOG_TARGET,
+                        "Could not find input({}) in SMT",
+                        utxo.output.commitment.to_hex(),
+                    );
+                    return Err(ChainStorageError::UnspendableInput);
+                },
+            };
+            remove(
+                &mut self.utxo_commitment_index,
+                &utxo.output.commitment.to_vec(),
+                DB_UTXO_COMMITMENT_INDEX,
+            )?;
+        }
+        // Move inputs in this block back into the unspent set, any outputs spent within this block they will be removed
+        // by deleting all the block's outputs above
+        for row in inputs {
+            let output_hash = row.input.output_hash();
+
+            remove(
+                &mut self.deleted_txo_hash_to_header_index,
+                &output_hash,
+                DB_DELETED_TXO_HASH_TO_HEADER_INDEX,
+            )?;
+            // If input spends an output in this block, don't add it to the utxo set
+            if output_rows.iter().any(|r| r.hash == output_hash) {
+                continue;
+            }
+
+            let mut input = row.input;
+
+            let utxo_mined_info = self
+                .fetch_output(&output_hash)
+                .ok_or_else(|| ChainStorageError::ValueNotFound {
+                    entity: "UTXO",
+                    field: "hash",
+                    value: output_hash.to_hex(),
+                })?;
+
+            let rp_hash = match utxo_mined_info.output.proof {
+                Some(proof) => proof.hash(),
+                None => FixedHash::zero(),
+            };
+            input.add_output_data(
+                utxo_mined_info.output.version,
+                utxo_mined_info.output.features,
+                utxo_mined_info.output.commitment,
+                utxo_mined_info.output.script,
+                utxo_mined_info.output.sender_offset_public_key,
+                utxo_mined_info.output.covenant,
+                utxo_mined_info.output.encrypted_data,
+                utxo_mined_info.output.metadata_signature,
+                rp_hash,
+                utxo_mined_info.output.minimum_value_promise,
+            );
+            let smt_key = NodeKey::try_from(input.commitment()?.as_bytes())?;
+            let smt_node = ValueHash::try_from(input.smt_hash(utxo_mined_info.mined_height).as_slice())?;
+            if let Err(e) = self.smt_insert(smt, output_smt, smt_key, smt_node) {
+                error!(
+                    target: LOG_TARGET,
+                    "Output commitment({}) already in SMT",
+                    input.commitment()?.to_hex(),
+                );
+                return Err(e.into());
+            }
+
+            trace!(target: LOG_TARGET, "Input moved to UTXO set: {}", input);
+            insert(
+                &mut self.utxo_commitment_index,
+                input.commitment()?.to_vec(),
+                input.output_hash(),
+                DB_UTXO_COMMITMENT_INDEX,
+            )?;
+        }
+        Ok(())
+    }
+
+    fn smt_insert(
+        &mut self,
+        smt: &Arc<RwLock<OutputSmt>>,
+        output_smt: &mut OutputSmt,
+        key: NodeKey,
+        value: ValueHash,
+    ) -> Result<(), SMTError> {
+        output_smt.insert(key.clone(), value)?;
+        self.smt_undo_log.push(SmtChange {
+            smt: smt.clone(),
+            key,
+            previous: None,
+        });
+        Ok(())
+    }
+
+    fn smt_delete(
+        &mut self,
+        smt: &Arc<RwLock<OutputSmt>>,
+        output_smt: &mut OutputSmt,
+        key: NodeKey,
+    ) -> Result<DeleteResult, SMTError> {
+        let result = output_smt.delete(&key)?;
+        if let DeleteResult::Deleted(value) = &result {
+            self.smt_undo_log.push(SmtChange {
+                smt: smt.clone(),
+                key,
+                previous: Some(value.clone()),
+            });
+        }
+        Ok(result)
+    }
+
+    /// Reverts the changes recorded in the SMT undo log, newest first
+    fn revert_smt_changes(&mut self) {
+        for change in self.smt_undo_log.drain(..).rev() {
+            let mut output_smt = match change.smt.write() {
+                Ok(smt) => smt,
+                Err(e) => {
+                    error!(target: LOG_TARGET, "Could not get a write lock on the smt to revert a change. {:?}", e);
+                    return;
+                },
+            };
+            let result = match change.previous {
+                Some(value) => output_smt.upsert(change.key, value).map(|_| ()),
+                None => output_smt.delete(&change.key).map(|_| ()),
+            };
+            if let Err(e) = result {
+                error!(target: LOG_TARGET, "Failed to revert smt change: {}", e);
+            }
+        }
+    }
+
+    fn delete_block_kernels(&mut self, block_hash: &HashOutput) -> Result<(), ChainStorageError> {
+        let kernels = drain_block_rows(&mut self.kernels, (*block_hash, 0, FixedHash::zero()), |(h, _, _)| {
+            h == block_hash
+        });
+        debug!(target: LOG_TARGET, "Deleted {} kernels...", kernels.len());
+        for row in kernels {
+            trace!(
+                target: LOG_TARGET,
+                "Deleting excess `{}`",
+                row.kernel.excess.to_hex()
+            );
+            remove(
+                &mut self.kernel_excess_index,
+                &row.kernel.excess.to_vec(),
+                DB_KERNEL_EXCESS_INDEX,
+            )?;
+            remove(
+                &mut self.kernel_excess_sig_index,
+                &excess_sig_key(&row.kernel.excess_sig),
+                DB_KERNEL_EXCESS_SIG_INDEX,
+            )?;
+        }
+        Ok(())
+    }
+
+    fn delete_orphan(&mut self, hash: &HashOutput) -> Result<(), ChainStorageError> {
+        let parent_hash = match self.orphans.get(hash) {
+            Some(orphan) => orphan.header.prev_hash,
+            None => {
+                // delete_orphan is idempotent
+                debug!(
+                    target: LOG_TARGET,
+                    "delete_orphan: request to delete orphan block {} that was not found.",
+                    hash.to_hex()
+                );
+                return Ok(());
+            },
+        };
+
+        if let Some(mut children) = self.orphan_parent_map_index.take(&parent_hash) {
+            children.remove(hash);
+            if !children.is_empty() {
+                self.orphan_parent_map_index.put(parent_hash, children);
+            }
+        }
+
+        // Orphan is a tip hash
+        if self.orphan_chain_tips.take(hash).is_some() {
+            // If an orphan parent exists, it must be promoted
+            match (
+                self.orphans.contains_key(&parent_hash),
+                self.orphan_header_accumulated_data.get(&parent_hash),
+            ) {
+                (true, Some(parent_accum)) => {
+                    // Parent becomes a tip hash
+                    let tip = ChainTipData {
+                        hash: parent_hash,
+                        total_accumulated_difficulty: parent_accum.total_accumulated_difficulty,
+                    };
+                    insert(&mut self.orphan_chain_tips, parent_hash, tip, DB_ORPHAN_CHAIN_TIPS)?;
+                },
+                (false, None) => {
+                    // No entries, nothing here
+                },
+                (has_orphan, has_accum) => {
+                    warn!(
+                        target: LOG_TARGET,
+                        "'orphans' ({}) and 'orphan_header_accumulated_data' ({}) out of sync, missing parent hash '{}' \
+                         entry",
+                        has_orphan,
+                        has_accum.is_some(),
+                        parent_hash.to_hex()
+                    );
+                },
+            }
+        }
+
+        self.orphan_header_accumulated_data.take(hash);
+        remove(&mut self.orphans, hash, DB_ORPHANS)?;
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_lines)]
+    fn insert_tip_block_body(
+        &mut self,
+        header: &BlockHeader,
+        body: AggregateBody,
+        smt: Arc<RwLock<OutputSmt>>,
+        consensus_manager: &ConsensusManager,
+    ) -> Result<(), ChainStorageError> {
+        let mut output_smt = smt.write().map_err(|e| {
+            error!(
+                target: LOG_TARGET,
+                "insert_tip_block_body could not get a write lock on the smt. {:?}", e
+            );
+            ChainStorageError::AccessError("write lock on smt".into())
+        })?;
+        if self.block_accumulated_data.contains_key(&(header.height + 1)) {
+            return Err(ChainStorageError::InvalidOperation(format!(
+                "Attempted to insert block at height {} while next block already exists",
+                header.height
+            )));
+        }
+        let block_hash = header.hash();
+        debug!(
+            target: LOG_TARGET,
+            "Inserting block body for header `{}`: {}",
+            block_hash.to_hex(),
+            body.to_counts_string()
+        );
+
+        // The header we are inserting for must match the header at that height
+        let current_header_at_height =
+            self.headers
+                .get(&header.height)
+                .cloned()
+                .ok_or_else(|| ChainStorageError::ValueNotFound {
+                    entity: "BlockHeader",
+                    field: "height",
+                    value: header.height.to_string(),
+                })?;
+        let hash = current_header_at_height.hash();
+        if hash != block_hash {
+            return Err(ChainStorageError::InvalidOperation(format!(
+                "Could not insert this block body because there is a different header stored at height {}. New header \
+                 ({}), current header: ({})",
+                header.height,
+                hash.to_hex(),
+                block_hash.to_hex()
+            )));
+        }
+
+        let (inputs, outputs, kernels) = body.dissolve();
+
+        let pruned_kernel_set = if header.height == 0 {
+            BlockAccumulatedData::default()
+        } else {
+            self.block_accumulated_data
+                .get(&(header.height - 1))
+                .cloned()
+                .ok_or_else(|| ChainStorageError::ValueNotFound {
+                    entity: "BlockAccumulatedData",
+                    field: "height",
+                    value: (header.height - 1).to_string(),
+                })?
+        }
+        .dissolve();
+
+        let mut total_kernel_sum = Commitment::default();
+        let mut kernel_mmr = PrunedKernelMmr::new(pruned_kernel_set);
+        let mut smt_delta = OutputSmtDelta::default();
+
+        for kernel in kernels {
+            total_kernel_sum = &total_kernel_sum + &kernel.excess;
+            let pos =
+                u64::try_from(kernel_mmr.push(kernel.hash().to_vec())?).map_err(|_| ChainStorageError::OutOfRange)?;
+            trace!(
+                target: LOG_TARGET,
+                "Inserting kernel `{}`",
+                kernel.excess_sig.get_signature().to_hex()
+            );
+            self.insert_kernel(&block_hash, &kernel, pos)?;
+        }
+
+        for output in outputs {
+            trace!(
+                target: LOG_TARGET,
+                "Inserting output (`{}`, `{}`)",
+                output.commitment.to_hex(),
+                output.hash()
+            );
+            if !output.is_burned() {
+                let smt_key = NodeKey::try_from(output.commitment.as_bytes())?;
+                let smt_node = ValueHash::try_from(output.smt_hash(header.height).as_slice())?;
+                smt_delta.record_insert(&output.commitment, &smt_node)?;
+                if let Err(e) = self.smt_insert(&smt, &mut output_smt, smt_key, smt_node) {
+                    error!(
+                        target: LOG_TARGET,
+                        "Output commitment({}) already in SMT",
+                        output.commitment.to_hex(),
+                    );
+                    return Err(e.into());
+                }
+            }
+
+            let output_hash = output.hash();
+            if let Some(vn_reg) = output
+                .features
+                .sidechain_feature
+                .as_ref()
+                .and_then(|f| f.validator_node_registration())
+            {
+                self.insert_validator_node(header, &output.commitment, vn_reg, consensus_manager)?;
+            }
+            if let Some(template_reg) = output
+                .features
+                .sidechain_feature
+                .as_ref()
+                .and_then(|f| f.code_template_registration())
+            {
+                let record = TemplateRegistrationEntry {
+                    registration_data: template_reg.clone(),
+                    output_hash,
+                    block_height: header.height,
+                    block_hash,
+                };
+
+                insert(
+                    &mut self.template_registrations,
+                    (header.height, output_hash),
+                    record,
+                    DB_TEMPLATE_REGISTRATIONS,
+                )?;
+            }
+            self.insert_output(&block_hash, header.height, header.timestamp().as_u64(), &output)?;
+        }
+
+        for input in inputs {
+            let input_with_output_data = self.input_with_output_data(input)?;
+            let smt_key = NodeKey::try_from(input_with_output_data.commitment()?.as_bytes())?;
+            match self.smt_delete(&smt, &mut output_smt, smt_key)? {
+                DeleteResult::Deleted(value_hash) => {
+                    smt_delta.record_delete(input_with_output_data.commitment()?, &value_hash)?;
+                },
+                DeleteResult::KeyNotFound => {
+                    error!(
+                        target: LOG_TARGET,
+                        "Could not find input({}) in SMT",
+                        input_with_output_data.commitment()?.to_hex(),
+                    );
+                    return Err(ChainStorageError::UnspendableInput);
+                },
+            };
+
+            let features = input_with_output_data.features()?;
+            if let Some(vn_reg) = features
+                .sidechain_feature
+                .as_ref()
+                .and_then(|f| f.validator_node_registration())
+            {
+                let commitment = input_with_output_data.commitment()?;
+                remove(
+                    &mut self.validator_nodes,
+                    &(header.height, vn_reg.public_key().to_vec(), commitment.to_vec()),
+                    DB_VALIDATOR_NODES,
+                )?;
+                remove(
+                    &mut self.validator_nodes_mapping,
+                    &(vn_reg.public_key().to_vec(), header.height, commitment.to_vec()),
+                    DB_VALIDATOR_NODES_MAPPING,
+                )?;
+            }
+            trace!(
+                target: LOG_TARGET,
+                "Inserting input (`{}`, `{}`)",
+                input_with_output_data.commitment()?.to_hex(),
+                input_with_output_data.output_hash().to_hex()
+            );
+            self.insert_input(
+                current_header_at_height.height,
+                current_header_at_height.timestamp.as_u64(),
+                &block_hash,
+                input_with_output_data,
+            )?;
+        }
+
+        if self.metadata.archival {
+            self.output_smt_deltas.put(header.height, smt_delta);
+        }
+        insert(
+            &mut self.block_accumulated_data,
+            header.height,
+            BlockAccumulatedData::new(kernel_mmr.get_pruned_hash_set()?, total_kernel_sum),
+            DB_BLOCK_ACCUMULATED_DATA,
+        )
+    }
+
+    fn insert_validator_node(
+        &mut self,
+        header: &BlockHeader,
+        commitment: &Commitment,
+        vn_reg: &ValidatorNodeRegistration,
+        consensus_manager: &ConsensusManager,
+    ) -> Result<(), ChainStorageError> {
+        let constants = consensus_manager.consensus_constants(header.height);
+        let current_epoch = constants.block_height_to_epoch(header.height);
+
+        let prev_shard_key = self.get_shard_key(
+            current_epoch
+                .as_u64()
+                .saturating_sub(constants.validator_node_validity_period_epochs().as_u64()) *
+                constants.epoch_length(),
+            current_epoch.as_u64() * constants.epoch_length(),
+            vn_reg.public_key(),
+        );
+        let shard_key = vn_reg.derive_shard_key(
+            prev_shard_key,
+            current_epoch,
+            constants.validator_node_registration_shuffle_interval(),
+            &header.prev_hash,
+        );
+
+        let next_epoch = constants.block_height_to_epoch(header.height) + VnEpoch(1);
+        let validator_node = ValidatorNodeEntry {
+            shard_key,
+            start_epoch: next_epoch,
+            end_epoch: next_epoch + constants.validator_node_validity_period_epochs(),
+            public_key: vn_reg.public_key().clone(),
+            commitment: commitment.clone(),
+        };
+
+        insert(
+            &mut self.validator_nodes_mapping,
+            (
+                validator_node.public_key.to_vec(),
+                header.height,
+                validator_node.commitment.to_vec(),
+            ),
+            validator_node.shard_key,
+            DB_VALIDATOR_NODES_MAPPING,
+        )?;
+        insert(
+            &mut self.validator_nodes,
+            (
+                header.height,
+                validator_node.public_key.to_vec(),
+                validator_node.commitment.to_vec(),
+            ),
+            validator_node,
+            DB_VALIDATOR_NODES,
+        )
+    }
+
+    fn update_block_accumulated_data(
+        &mut self,
+        header_hash: &HashOutput,
+        values: UpdateBlockAccumulatedData,
+    ) -> Result<(), ChainStorageError> {
+        let height = self
+            .block_hashes
+            .get(header_hash)
+            .copied()
+            .ok_or_else(|| ChainStorageError::ValueNotFound {
+                entity: "BlockHash",
+                field: "hash",
+                value: header_hash.to_hex(),
+            })?;
+
+        let mut block_accum_data = self.block_accumulated_data.get(&height).cloned().unwrap_or_default();
+        if let Some(kernel_sum) = values.kernel_sum {
+            block_accum_data.kernel_sum = kernel_sum;
+        }
+        if let Some(kernel_hash_set) = values.kernel_hash_set {
+            block_accum_data.kernels = kernel_hash_set;
+        }
+        self.block_accumulated_data.put(height, block_accum_data);
+        Ok(())
+    }
+
+    fn insert_monero_seed_height(&mut self, seed: &[u8], height: u64) {
+        let current_height = self.monero_seed_height.get(seed).copied().unwrap_or(u64::MAX);
+        if height < current_height {
+            self.monero_seed_height.put(seed.to_vec(), height);
+        }
+    }
+
+    fn prune_outputs_spent_at_hash(&mut self, block_hash: &HashOutput) -> Result<(), ChainStorageError> {
+        let inputs = self
+            .inputs_in_block(block_hash)
+            .map(|row| row.input.clone())
+            .collect::<Vec<_>>();
+
+        for input in inputs {
+            if let SpentOutput::OutputData { commitment, .. } = &input.spent_output {
+                debug!(target: LOG_TARGET, "Pruning output from 'utxo_commitment_index': key '{}'", commitment.to_hex());
+                remove(
+                    &mut self.utxo_commitment_index,
+                    &commitment.to_vec(),
+                    DB_UTXO_COMMITMENT_INDEX,
+                )?;
+            }
+            let output_hash = input.output_hash();
+            if let Some(header_hash) = self.txos_hash_to_index.get(&output_hash).copied() {
+                debug!(target: LOG_TARGET, "Pruning output from 'utxos': key '{}'", output_hash.to_hex());
+                let row = remove(&mut self.utxos, &(header_hash, output_hash), DB_UTXOS)?;
+                self.delete_output_indexes(&row.output, row.mined_height, &row.hash)?;
+            };
+            debug!(
+                target: LOG_TARGET,
+                "Pruning output from 'txos_hash_to_index': key '{}'",
+                output_hash.to_hex()
+            );
+            remove(&mut self.txos_hash_to_index, &output_hash, DB_TXOS_HASH_TO_INDEX)?;
+        }
+
+        Ok(())
+    }
+
+    fn prune_output_from_all_dbs(
+        &mut self,
+        output_hash: &HashOutput,
+        commitment: &Commitment,
+        output_type: OutputType,
+    ) -> Result<(), ChainStorageError> {
+        let header_hash = self
+            .txos_hash_to_index
+            .get(output_hash)
+            .copied()
+            .ok_or_else(|| ChainStorageError::InvalidOperation("Output key not found".to_string()))?;
+        if !matches!(output_type, OutputType::Burn) {
+            debug!(target: LOG_TARGET, "Pruning output from 'utxo_commitment_index': key '{}'", commitment.to_hex());
+            remove(
+                &mut self.utxo_commitment_index,
+                &commitment.to_vec(),
+                DB_UTXO_COMMITMENT_INDEX,
+            )?;
+        }
+        debug!(target: LOG_TARGET, "Pruning output from 'txos_hash_to_index': key '{}'", output_hash.to_hex());
+        remove(&mut self.txos_hash_to_index, output_hash, DB_TXOS_HASH_TO_INDEX)?;
+        let row = remove(&mut self.utxos, &(header_hash, *output_hash), DB_UTXOS)?;
+        self.delete_output_indexes(&row.output, row.mined_height, &row.hash)?;
+        Ok(())
+    }
+
+    fn insert_bad_block_and_cleanup(
+        &mut self,
+        hash: &HashOutput,
+        height: u64,
+        reason: String,
+    ) -> Result<(), ChainStorageError> {
+        #[cfg(test)]
+        const CLEAN_BAD_BLOCKS_BEFORE_REL_HEIGHT: u64 = 10000;
+        #[cfg(not(test))]
+        const CLEAN_BAD_BLOCKS_BEFORE_REL_HEIGHT: u64 = 0;
+
+        self.bad_blocks.put(*hash, (height, reason));
+        // Clean up bad blocks that are far from the tip
+        let metadata = self.fetch_chain_metadata()?;
+        let deleted_before_height = metadata
+            .best_block_height()
+            .saturating_sub(CLEAN_BAD_BLOCKS_BEFORE_REL_HEIGHT);
+        if deleted_before_height == 0 {
+            return Ok(());
+        }
+
+        let stale_bad_blocks = self
+            .bad_blocks
+            .iter()
+            .filter(|(_, (h, _))| *h < deleted_before_height)
+            .map(|(hash, _)| *hash)
+            .collect::<Vec<_>>();
+        for hash in &stale_bad_blocks {
+            self.bad_blocks.take(hash);
+        }
+        debug!(
+            target: LOG_TARGET,
+            "Cleaned out {} stale bad blocks",
+            stale_bad_blocks.len()
+        );
+
+        Ok(())
+    }
+
+    fn fetch_best_block(&self) -> Result<BlockHash, ChainStorageError> {
+        self.metadata
+            .best_block
+            .ok_or_else(|| ChainStorageError::ValueNotFound {
+                entity: "ChainMetadata",
+                field: "BestBlock",
+                value: "".to_string(),
+            })
+    }
+
+    fn fetch_chain_metadata(&self) -> Result<ChainMetadata, ChainStorageError> {
+        let not_found = |field| ChainStorageError::ValueNotFound {
+            entity: "ChainMetadata",
+            field,
+            value: "".to_string(),
+        };
+        Ok(ChainMetadata::new(
+            self.metadata.chain_height.ok_or_else(|| not_found("ChainHeight"))?,
+            self.fetch_best_block()?,
+            self.metadata.pruning_horizon,
+            self.metadata.pruned_height,
+            self.metadata
+                .accumulated_work
+                .ok_or_else(|| not_found("AccumulatedWork"))?,
+            self.metadata
+                .best_block_timestamp
+                .ok_or_else(|| not_found("BestBlockTimestamp"))?,
+        )?)
+    }
+
+    fn fetch_last_header(&self) -> Option<&BlockHeader> {
+        self.headers.values().next_back()
+    }
+
+    fn fetch_chain_header_by_height(&self, height: u64) -> Result<ChainHeader, ChainStorageError> {
+        let header = self
+            .headers
+            .get(&height)
+            .cloned()
+            .ok_or_else(|| ChainStorageError::ValueNotFound {
+                entity: "BlockHeader",
+                field: "height",
+                value: height.to_string(),
+            })?;
+
+        let accum_data =
+            self.header_accumulated_data
+                .get(&height)
+                .cloned()
+                .ok_or_else(|| ChainStorageError::ValueNotFound {
+                    entity: "BlockHeaderAccumulatedData",
+                    field: "height",
+                    value: height.to_string(),
+                })?;
+
+        ChainHeader::try_construct(header, accum_data).ok_or_else(|| ChainStorageError::DataInconsistencyDetected {
+            function: "fetch_chain_header_by_height",
+            details: format!("Mismatch in accumulated data at height #{}", height),
+        })
+    }
+
+    fn fetch_orphan_chain_header(&self, hash: &HashOutput) -> Result<ChainHeader, ChainStorageError> {
+        let orphan = self.orphans.get(hash).ok_or_else(|| ChainStorageError::ValueNotFound {
+            entity: "Orphan",
+            field: "hash",
+            value: hash.to_hex(),
+        })?;
+        let accumulated_data =
+            self.orphan_header_accumulated_data
+                .get(hash)
+                .ok_or_else(|| ChainStorageError::ValueNotFound {
+                    entity: "Orphan accumulated data",
+                    field: "hash",
+                    value: hash.to_hex(),
+                })?;
+
+        let height = orphan.header.height;
+        ChainHeader::try_construct(orphan.header.clone(), accumulated_data.clone()).ok_or_else(|| {
+            ChainStorageError::DataInconsistencyDetected {
+                function: "fetch_orphan_chain_header",
+                details: format!("Accumulated data mismatch at height #{}", height),
+            }
+        })
+    }
+
+    fn fetch_output(&self, output_hash: &HashOutput) -> Option<OutputMinedInfo> {
+        let header_hash = self.txos_hash_to_index.get(output_hash)?;
+        self.utxos
+            .get(&(*header_hash, *output_hash))
+            .map(|row| OutputMinedInfo {
+                output: row.output.clone(),
+                mined_height: row.mined_height,
+                header_hash: row.header_hash,
+                mined_timestamp: row.mined_timestamp,
+            })
+    }
+
+    fn fetch_input(&self, output_hash: &HashOutput) -> Option<InputMinedInfo> {
+        let key = self.deleted_txo_hash_to_header_index.get(output_hash)?;
+        self.inputs.get(key).map(|row| InputMinedInfo {
+            input: row.input.clone(),
+            spent_height: row.spent_height,
+            header_hash: row.header_hash,
+            spent_timestamp: row.spent_timestamp,
+        })
+    }
+
+    fn fetch_outputs_in_block_with_spend_state(
+        &self,
+        header_hash: &HashOutput,
+        spend_status_at_header: Option<&HashOutput>,
+    ) -> Result<Vec<(TransactionOutput, bool)>, ChainStorageError> {
+        let mut outputs = self
+            .outputs_in_block(header_hash)
+            .map(|row| (row.output.clone(), false))
+            .collect::<Vec<_>>();
+        if let Some(header_hash) = spend_status_at_header {
+            let header_height =
+                self.block_hashes
+                    .get(header_hash)
+                    .copied()
+                    .ok_or_else(|| ChainStorageError::ValueNotFound {
+                        entity: "Header",
+                        field: "hash",
+                        value: header_hash.to_hex(),
+                    })?;
+            for output in &mut outputs {
+                let hash = output.0.hash();
+                if let Some(key) = self.deleted_txo_hash_to_header_index.get(&hash) {
+                    let input = self.inputs.get(key).ok_or_else(|| ChainStorageError::ValueNotFound {
+                        entity: "input",
+                        field: "hash",
+                        value: header_hash.to_hex(),
+                    })?;
+                    if input.spent_height <= header_height {
+                        // we know its spend at the header height specified as optional in the fn
+                        output.1 = true;
+                    }
+                }
+            }
+        }
+
+        Ok(outputs)
+    }
+
+    /// Returns the shard key of the last registration of `public_key` in the given (inclusive) height range.
+    fn get_shard_key(&self, start_height: u64, end_height: u64, public_key: &PublicKey) -> Option<ShardKey> {
+        let public_key = public_key.to_vec();
+        self.validator_nodes_mapping
+            .range((public_key.clone(), start_height, Vec::new())..)
+            .take_while(|((pk, height, _), _)| *pk == public_key && *height <= end_height)
+            .last()
+            .map(|(_, shard_key)| *shard_key)
+    }
+
+    /// Returns a set of <public key, shard id> tuples ordered by shard key. If a duplicate registration is found, the
+    /// last registration is included.
+    fn get_vn_set(&self, start_height: u64, end_height: u64) -> Vec<(PublicKey, ShardKey)> {
+        // Public key does not mutate once compressed and will always produce the same hash
+        #[allow(clippy::mutable_key_type)]
+        let mut dedup_map = HashMap::new();
+        let mut nodes = Vec::new();
+        for (_, vn) in self
+            .validator_nodes
+            .range((start_height, Vec::new(), Vec::new())..)
+            .take_while(|((height, _, _), _)| *height <= end_height)
+        {
+            if let Some(dup_idx) = dedup_map.insert(vn.public_key.clone(), nodes.len()) {
+                // Remove duplicate registrations within the set without changing index order
+                nodes[dup_idx] = None;
+            }
+            nodes.push(Some((vn.public_key.clone(), vn.shard_key)));
+        }
+
+        let mut vn_set = nodes.into_iter().flatten().collect::<Vec<_>>();
+        vn_set.sort_by(|(_, a), (_, b)| a.cmp(b));
+        vn_set
+    }
+
+    fn outputs_in_block<'a>(
+        &'a self,
+        block_hash: &'a HashOutput,
+    ) -> impl Iterator<Item = &'a TransactionOutputRowData> + 'a {
+        self.utxos
+            .range((*block_hash, FixedHash::zero())..)
+            .take_while(move |((h, _), _)| h == block_hash)
+            .map(|(_, row)| row)
+    }
+
+    fn inputs_in_block<'a>(
+        &'a self,
+        block_hash: &'a HashOutput,
+    ) -> impl Iterator<Item = &'a TransactionInputRowData> + 'a {
+        self.inputs
+            .range((*block_hash, FixedHash::zero())..)
+            .take_while(move |((h, _), _)| h == block_hash)
+            .map(|(_, row)| row)
+    }
+
+    fn kernels_in_block<'a>(
+        &'a self,
+        block_hash: &'a HashOutput,
+    ) -> impl Iterator<Item = &'a TransactionKernelRowData> + 'a {
+        self.kernels
+            .range((*block_hash, 0, FixedHash::zero())..)
+            .take_while(move |((h, _, _), _)| h == block_hash)
+            .map(|(_, row)| row)
+    }
+
+    fn entry_counts(&self) -> [(&'static str, usize); 25] {
+        [
+            (DB_HEADERS, self.headers.len()),
+            (DB_HEADER_ACCUMULATED_DATA, self.header_accumulated_data.len()),
+            (DB_BLOCK_ACCUMULATED_DATA, self.block_accumulated_data.len()),
+            (DB_BLOCK_HASHES, self.block_hashes.len()),
+            (DB_UTXOS, self.utxos.len()),
+            (DB_INPUTS, self.inputs.len()),
+            (DB_TXOS_HASH_TO_INDEX, self.txos_hash_to_index.len()),
+            (DB_KERNELS, self.kernels.len()),
+            (DB_KERNEL_EXCESS_INDEX, self.kernel_excess_index.len()),
+            (DB_KERNEL_EXCESS_SIG_INDEX, self.kernel_excess_sig_index.len()),
+            (DB_KERNEL_MMR_SIZE_INDEX, self.kernel_mmr_size_index.len()),
+            (DB_UTXO_COMMITMENT_INDEX, self.utxo_commitment_index.len()),
+            (
+                DB_DELETED_TXO_HASH_TO_HEADER_INDEX,
+                self.deleted_txo_hash_to_header_index.len(),
+            ),
+            (DB_ORPHANS, self.orphans.len()),
+            (
+                DB_ORPHAN_HEADER_ACCUMULATED_DATA,
+                self.orphan_header_accumulated_data.len(),
+            ),
+            (DB_MONERO_SEED_HEIGHT, self.monero_seed_height.len()),
+            (DB_ORPHAN_CHAIN_TIPS, self.orphan_chain_tips.len()),
+            (
+                DB_ORPHAN_PARENT_MAP_INDEX,
+                self.orphan_parent_map_index.values().map(BTreeSet::len).sum(),
+            ),
+            (DB_BAD_BLOCK_LIST, self.bad_blocks.len()),
+            (DB_REORGS, self.reorgs.len()),
+            (DB_VALIDATOR_NODES, self.validator_nodes.len()),
+            (DB_VALIDATOR_NODES_MAPPING, self.validator_nodes_mapping.len()),
+            (DB_TEMPLATE_REGISTRATIONS, self.template_registrations.len()),
+            (DB_OUTPUT_INDEXES, self.output_indexes.len()),
+            (DB_OUTPUT_SMT_DELTAS, self.output_smt_deltas.len()),
+        ]
+    }
+
+    fn total_size_stats(&self) -> DbTotalSizeStats {
+        vec![
+            table_size(DB_HEADERS, self.headers.iter()),
+            table_size(DB_HEADER_ACCUMULATED_DATA, self.header_accumulated_data.iter()),
+            table_size(DB_BLOCK_ACCUMULATED_DATA, self.block_accumulated_data.iter()),
+            table_size(DB_BLOCK_HASHES, self.block_hashes.iter()),
+            table_size(DB_UTXOS, self.utxos.iter()),
+            table_size(DB_INPUTS, self.inputs.iter()),
+            table_size(DB_TXOS_HASH_TO_INDEX, self.txos_hash_to_index.iter()),
+            table_size(DB_KERNELS, self.kernels.iter()),
+            table_size(DB_KERNEL_EXCESS_INDEX, self.kernel_excess_index.iter()),
+            table_size(DB_KERNEL_EXCESS_SIG_INDEX, self.kernel_excess_sig_index.iter()),
+            table_size(DB_KERNEL_MMR_SIZE_INDEX, self.kernel_mmr_size_index.iter()),
+            table_size(DB_UTXO_COMMITMENT_INDEX, self.utxo_commitment_index.iter()),
+            table_size(
+                DB_DELETED_TXO_HASH_TO_HEADER_INDEX,
+                self.deleted_txo_hash_to_header_index.iter(),
+            ),
+            table_size(DB_ORPHANS, self.orphans.iter()),
+            table_size(
+                DB_ORPHAN_HEADER_ACCUMULATED_DATA,
+                self.orphan_header_accumulated_data.iter(),
+            ),
+            table_size(DB_MONERO_SEED_HEIGHT, self.monero_seed_height.iter()),
+            table_size(DB_ORPHAN_CHAIN_TIPS, self.orphan_chain_tips.iter()),
+            table_size(
+                DB_ORPHAN_PARENT_MAP_INDEX,
+                self.orphan_parent_map_index
+                    .iter()
+                    .flat_map(|(parent, children)| children.iter().map(move |child| (parent, child))),
+            ),
+            table_size(DB_BAD_BLOCK_LIST, self.bad_blocks.iter()),
+            table_size(DB_REORGS, self.reorgs.iter()),
+            table_size(DB_VALIDATOR_NODES, self.validator_nodes.iter()),
+            table_size(DB_VALIDATOR_NODES_MAPPING, self.validator_nodes_mapping.iter()),
+            table_size(DB_TEMPLATE_REGISTRATIONS, self.template_registrations.iter()),
+            table_size(DB_OUTPUT_INDEXES, self.output_indexes.iter()),
+            table_size(DB_OUTPUT_SMT_DELTAS, self.output_smt_deltas.iter()),
+        ]
+        .into()
+    }
+}
+
+/// Minimal map abstraction so that the same insert/remove semantics as the LMDB helpers can be applied to both ordered
+/// and unordered tables.
+trait Table<K, V> {
+    fn contains(&self, key: &K) -> bool;
+    fn put(&mut self, key: K, value: V) -> Option<V>;
+    fn take(&mut self, key: &K) -> Option<V>;
+    fn take_all(&mut self) -> Vec<(K, V)>;
+}
+
+impl<K: Ord, V> Table<K, V> for BTreeMap<K, V> {
+    fn contains(&self, key: &K) -> bool {
+        self.contains_key(key)
+    }
+
+    fn put(&mut self, key: K, value: V) -> Option<V> {
+        self.insert(key, value)
+    }
+
+    fn take(&mut self, key: &K) -> Option<V> {
+        self.remove(key)
+    }
+
+    fn take_all(&mut self) -> Vec<(K, V)> {
+        mem::take(self).into_iter().collect()
+    }
+}
+
+impl<K: Eq + Hash, V> Table<K, V> for HashMap<K, V> {
+    fn contains(&self, key: &K) -> bool {
+        self.contains_key(key)
+    }
+
+    fn put(&mut self, key: K, value: V) -> Option<V> {
+        self.insert(key, value)
+    }
+
+    fn take(&mut self, key: &K) -> Option<V> {
+        self.remove(key)
+    }
+
+    fn take_all(&mut self) -> Vec<(K, V)> {
+        self.drain().collect()
+    }
+}
+
+type OrderedTable<K, V> = UndoTable<BTreeMap<K, V>, K, V>;
+type HashTable<K, V> = UndoTable<HashMap<K, V>, K, V>;
+
+/// A table that records the value each key held before it was changed by the transaction being applied. The rows are
+/// read through [Deref] and can only be changed through the methods that record the change.
+#[derive(Debug)]
+struct UndoTable<M, K, V> {
+    rows: M,
+    undo_log: Vec<(K, Option<V>)>,
+}
+
+impl<M: Default, K, V> Default for UndoTable<M, K, V> {
+    fn default() -> Self {
+        Self {
+            rows: M::default(),
+            undo_log: Vec::new(),
+        }
+    }
+}
+
+impl<M, K, V> Deref for UndoTable<M, K, V> {
+    type Target = M;
+
+    fn deref(&self) -> &Self::Target {
+        &self.rows
+    }
+}
+
+impl<M: Table<K, V>, K: Clone, V: Clone> UndoTable<M, K, V> {
+    /// Sets the value of a key, replacing any existing value
+    fn put(&mut self, key: K, value: V) {
+        let previous = self.rows.put(key.clone(), value);
+        self.undo_log.push((key, previous));
+    }
+
+    /// Removes and returns the value of a key, if present
+    fn take(&mut self, key: &K) -> Option<V> {
+        let value = self.rows.take(key)?;
+        self.undo_log.push((key.clone(), Some(value.clone())));
+        Some(value)
+    }
+
+    fn clear(&mut self) {
+        let rows = self.rows.take_all();
+        self.undo_log
+            .extend(rows.into_iter().map(|(key, value)| (key, Some(value))));
+    }
+}
+
+/// Undoes or keeps the changes recorded by an [UndoTable] once the transaction being applied has failed or succeeded
+trait UndoLog {
+    fn revert(&mut self);
+    fn commit(&mut self);
+}
+
+impl<M: Table<K, V>, K, V> UndoLog for UndoTable<M, K, V> {
+    /// Restores the previous values, newest change first
+    fn revert(&mut self) {
+        for (key, previous) in self.undo_log.drain(..).rev() {
+            match previous {
+                Some(value) => {
+                    self.rows.put(key, value);
+                },
+                None => {
+                    self.rows.take(&key);
+                },
+            }
+        }
+    }
+
+    fn commit(&mut self) {
+        self.undo_log.clear();
+    }
+}
+
+/// Inserts a new value, returning a `KeyExists` error if the key is already present (the equivalent of `lmdb_insert`)
+fn insert<M: Table<K, V>, K: Debug + Clone, V: Clone>(
+    table: &mut UndoTable<M, K, V>,
+    key: K,
+    value: V,
+    table_name: &'static str,
+) -> Result<(), ChainStorageError> {
+    if table.rows.contains(&key) {
+        error!(
+            target: LOG_TARGET,
+            "Could not insert value with key '{:?}' into '{}' (key exists)", key, table_name
+        );
+        return Err(ChainStorageError::KeyExists {
+            table_name,
+            key: format!("{:?}", key),
+        });
+    }
+    table.put(key, value);
+    Ok(())
+}
+
+/// Removes a value, returning a `ValueNotFound` error if the key is not present (the equivalent of `lmdb_delete`)
+fn remove<M: Table<K, V>, K: Debug + Clone, V: Clone>(
+    table: &mut UndoTable<M, K, V>,
+    key: &K,
+    table_name: &'static str,
+) -> Result<V, ChainStorageError> {
+    table.take(key).ok_or_else(|| ChainStorageError::ValueNotFound {
+        entity: table_name,
+        field: "<unknown>",
+        value: format!("{:?}", key),
+    })
+}
+
+/// Removes and returns all the rows in key order, starting from `start` while `is_in_block` holds.
+fn drain_block_rows<K: Ord + Clone, V: Clone, F: Fn(&K) -> bool>(
+    table: &mut OrderedTable<K, V>,
+    start: K,
+    is_in_block: F,
+) -> Vec<V> {
+    let keys = table
+        .range(start..)
+        .map(|(k, _)| k)
+        .take_while(|k| is_in_block(k))
+        .cloned()
+        .collect::<Vec<_>>();
+    keys.iter().filter_map(|k| table.take(k)).collect()
+}
+
+fn excess_sig_key(excess_sig: &Signature) -> Vec<u8> {
+    let mut key = Vec::<u8>::with_capacity(32 * 2);
+    key.extend(excess_sig.get_public_nonce().as_bytes());
+    key.extend(excess_sig.get_signature().as_bytes());
+    key
+}
+
+fn table_size<'a, K: Serialize + 'a, V: Serialize + 'a, I: Iterator<Item = (&'a K, &'a V)>>(
+    name: &'static str,
+    entries: I,
+) -> DbSize {
+    let mut size = DbSize {
+        name,
+        num_entries: 0,
+        total_key_size: 0,
+        total_value_size: 0,
+    };
+    for (k, v) in entries {
+        size.num_entries += 1;
+        size.total_key_size += bincode::serialized_size(k).unwrap_or(0);
+        size.total_value_size += bincode::serialized_size(v).unwrap_or(0);
+    }
+    size
+}
+
+impl BlockchainBackend for MemoryDatabase {
+    fn write(&mut self, txn: DbTransaction) -> Result<(), ChainStorageError> {
+        if txn.operations().is_empty() {
+            return Ok(());
+        }
+
+        let mark = Instant::now();
+        let num_operations = txn.operations().len();
+        let db = self.db.get_mut().map_err(|e| {
+            error!(target: LOG_TARGET, "An attempt to get a write lock on the memory db failed. {:?}", e);
+            ChainStorageError::AccessError("Write lock on memory db failed".into())
+        })?;
+        if let Err(e) = db.atomically(|db| db.apply_db_transaction(&txn, &self.consensus_manager)) {
+            error!(target: LOG_TARGET, "Failed to apply DB transaction: {:?}", e);
+            return Err(e);
+        }
+        trace!(
+            target: LOG_TARGET,
+            "Database completed {} operation(s) in {:.0?}",
+            num_operations,
+            mark.elapsed()
+        );
+        Ok(())
+    }
+
+    fn fetch(&self, key: &DbKey) -> Result<Option<DbValue>, ChainStorageError> {
+        let db = self.read_access()?;
+        let res = match key {
+            DbKey::HeaderHeight(k) => db
+                .headers
+                .get(k)
+                .map(|val| DbValue::HeaderHeight(Box::new(val.clone()))),
+            DbKey::HeaderHash(hash) => db
+                .block_hashes
+                .get(hash)
+                .and_then(|k| db.headers.get(k))
+                .map(|val| DbValue::HeaderHash(Box::new(val.clone()))),
+            DbKey::OrphanBlock(k) => db.orphans.get(k).map(|val| DbValue::OrphanBlock(Box::new(val.clone()))),
+        };
+        Ok(res)
+    }
+
+    fn contains(&self, key: &DbKey) -> Result<bool, ChainStorageError> {
+        let db = self.read_access()?;
+        Ok(match key {
+            DbKey::HeaderHeight(k) => db.headers.contains_key(k),
+            DbKey::HeaderHash(h) => db.block_hashes.contains_key(h),
+            DbKey::OrphanBlock(k) => db.orphans.contains_key(k),
+        })
+    }
+
+    fn fetch_chain_header_by_height(&self, height: u64) -> Result<ChainHeader, ChainStorageError> {
+        self.read_access()?.fetch_chain_header_by_height(height)
+    }
+
+    fn fetch_header_accumulated_data(
+        &self,
+        hash: &HashOutput,
+    ) -> Result<Option<BlockHeaderAccumulatedData>, ChainStorageError> {
+        let db = self.read_access()?;
+        Ok(db
+            .block_hashes
+            .get(hash)
+            .and_then(|h| db.header_accumulated_data.get(h))
+            .cloned())
+    }
+
+    fn fetch_chain_header_in_all_chains(&self, hash: &HashOutput) -> Result<ChainHeader, ChainStorageError> {
+        let db = self.read_access()?;
+        if let Some(height) = db.block_hashes.get(hash) {
+            return db.fetch_chain_header_by_height(*height);
+        }
+
+        if let Some(accum) = db.orphan_header_accumulated_data.get(hash) {
+            let orphan = db
+                .orphans
+                .get(hash)
+                .ok_or_else(|| ChainStorageError::DataInconsistencyDetected {
+                    function: "fetch_chain_header_in_all_chains",
+                    details: format!(
+                        "Orphan accumulated data exists but the corresponding orphan header {} does not",
+                        hash.to_hex()
+                    ),
+                })?;
+            return ChainHeader::try_construct(orphan.header.clone(), accum.clone()).ok_or_else(|| {
+                ChainStorageError::DataInconsistencyDetected {
+                    function: "fetch_chain_header_in_all_chains",
+                    details: format!("accumulated data mismatch for orphan header {}", hash.to_hex()),
+                }
+            });
+        }
+
+        Err(ChainStorageError::ValueNotFound {
+            entity: "chain header (in chain_header_in_all_chains)",
+            field: "hash",
+            value: hash.to_hex(),
+        })
+    }
+
+    fn fetch_header_containing_kernel_mmr(&self, mmr_position: u64) -> Result<ChainHeader, ChainStorageError> {
+        let db = self.read_access()?;
+        // The index is keyed by kernel MMR size, so we have to offset the position by 1 so that the mmr_position arg
+        // is an index starting from 0
+        let mmr_position = mmr_position + 1;
+        let height = db
+            .kernel_mmr_size_index
+            .range(mmr_position..)
+            .next()
+            .map(|(_, height)| *height)
+            .ok_or_else(|| ChainStorageError::ValueNotFound {
+                entity: "kernel_mmr_size_index",
+                field: "mmr_position",
+                value: mmr_position.to_string(),
+            })?;
+        db.fetch_chain_header_by_height(height)
+    }
+
+    fn is_empty(&self) -> Result<bool, ChainStorageError> {
+        Ok(self.read_access()?.headers.is_empty())
+    }
+
+    fn fetch_block_accumulated_data(
+        &self,
+        header_hash: &HashOutput,
+    ) -> Result<Option<BlockAccumulatedData>, ChainStorageError> {
+        let db = self.read_access()?;
+        Ok(db
+            .block_hashes
+            .get(header_hash)
+            .and_then(|height| db.block_accumulated_data.get(height))
+            .cloned())
+    }
+
+    fn fetch_block_accumulated_data_by_height(
+        &self,
+        height: u64,
+    ) -> Result<Option<BlockAccumulatedData>, ChainStorageError> {
+        Ok(self.read_access()?.block_accumulated_data.get(&height).cloned())
+    }
+
+    fn fetch_kernels_in_block(&self, header_hash: &HashOutput) -> Result<Vec<TransactionKernel>, ChainStorageError> {
+        let db = self.read_access()?;
+        Ok(db.kernels_in_block(header_hash).map(|row| row.kernel.clone()).collect())
+    }
+
+    fn fetch_kernel_by_excess_sig(
+        &self,
+        excess_sig: &Signature,
+    ) -> Result<Option<(TransactionKernel, HashOutput)>, ChainStorageError> {
+        let db = self.read_access()?;
+        Ok(db
+            .kernel_excess_sig_index
+            .get(&excess_sig_key(excess_sig))
+            .and_then(|key| db.kernels.get(key))
+            .map(|row| (row.kernel.clone(), row.header_hash)))
+    }
+
+    fn fetch_outputs_in_block_with_spend_state(
+        &self,
+        header_hash: &HashOutput,
+        spend_status_at_header: Option<&HashOutput>,
+    ) -> Result<Vec<(TransactionOutput, bool)>, ChainStorageError> {
+        self.read_access()?
+            .fetch_outputs_in_block_with_spend_state(header_hash, spend_status_at_header)
+    }
+
+    fn fetch_output(&self, output_hash: &HashOutput) -> Result<Option<OutputMinedInfo>, ChainStorageError> {
+        Ok(self.read_access()?.fetch_output(output_hash))
+    }
+
+    fn fetch_input(&self, output_hash: &HashOutput) -> Result<Option<InputMinedInfo>, ChainStorageError> {
+        Ok(self.read_access()?.fetch_input(output_hash))
+    }
+
+    fn fetch_unspent_output_hash_by_commitment(
+        &self,
+        commitment: &Commitment,
+    ) -> Result<Option<HashOutput>, ChainStorageError> {
+        Ok(self
+            .read_access()?
+            .utxo_commitment_index
+            .get(commitment.as_bytes())
+            .copied())
+    }
+
+    fn fetch_outputs_in_block(&self, header_hash: &HashOutput) -> Result<Vec<TransactionOutput>, ChainStorageError> {
+        let db = self.read_access()?;
+        Ok(db.outputs_in_block(header_hash).map(|row| row.output.clone()).collect())
+    }
+
+    fn fetch_inputs_in_block(&self, header_hash: &HashOutput) -> Result<Vec<TransactionInput>, ChainStorageError> {
+        let db = self.read_access()?;
+        Ok(db.inputs_in_block(header_hash).map(|row| row.input.clone()).collect())
+    }
+
+    fn fetch_mmr_size(&self, tree: MmrTree) -> Result<u64, ChainStorageError> {
+        let db = self.read_access()?;
+        match tree {
+            MmrTree::Kernel => Ok(db.kernels.len() as u64),
+        }
+    }
+
+    fn orphan_count(&self) -> Result<usize, ChainStorageError> {
+        Ok(self.read_access()?.orphans.len())
+    }
+
+    fn fetch_last_header(&self) -> Result<BlockHeader, ChainStorageError> {
+        self.read_access()?.fetch_last_header().cloned().ok_or_else(|| {
+            ChainStorageError::InvalidOperation("Cannot fetch last header because database is empty".to_string())
+        })
+    }
+
+    fn clear_all_pending_headers(&self) -> Result<usize, ChainStorageError> {
+        let mut db = self.db.write().map_err(|e| {
+            error!(target: LOG_TARGET, "An attempt to get a write lock on the memory db failed. {:?}", e);
+            ChainStorageError::AccessError("Write lock on memory db failed".into())
+        })?;
+        let last_height = match db.fetch_last_header() {
+            Some(h) => h.height,
+            None => {
+                return Ok(0);
+            },
+        };
+        let metadata = db.fetch_chain_metadata()?;
+
+        if metadata.best_block_height() == last_height {
+            return Ok(0);
+        }
+
+        let start = metadata.best_block_height() + 1;
+        db.atomically(|db| {
+            let mut num_deleted = 0;
+            for h in (start..=last_height).rev() {
+                db.delete_header(h)?;
+                num_deleted += 1;
+            }
+            Ok(num_deleted)
+        })
+    }
+
+    fn fetch_last_chain_header(&self) -> Result<ChainHeader, ChainStorageError> {
+        let db = self.read_access()?;
+        let height = db.fetch_last_header().map(|h| h.height).ok_or_else(|| {
+            ChainStorageError::InvalidOperation("Cannot fetch last header because database is empty".to_string())
+        })?;
+        db.fetch_chain_header_by_height(height)
+    }
+
+    fn fetch_tip_header(&self) -> Result<ChainHeader, ChainStorageError> {
+        let db = self.read_access()?;
+        let metadata = db.fetch_chain_metadata()?;
+        db.fetch_chain_header_by_height(metadata.best_block_height())
+    }
+
+    fn fetch_chain_metadata(&self) -> Result<ChainMetadata, ChainStorageError> {
+        self.read_access()?.fetch_chain_metadata()
+    }
+
+    fn utxo_count(&self) -> Result<usize, ChainStorageError> {
+        Ok(self.read_access()?.utxo_commitment_index.len())
+    }
+
+    fn kernel_count(&self) -> Result<usize, ChainStorageError> {
+        Ok(self.read_access()?.kernels.len())
+    }
+
+    fn fetch_orphan_chain_tip_by_hash(&self, hash: &HashOutput) -> Result<Option<ChainHeader>, ChainStorageError> {
+        trace!(target: LOG_TARGET, "Call to fetch_orphan_chain_tips()");
+        let db = self.read_access()?;
+        if !db.orphan_chain_tips.contains_key(hash) {
+            return Ok(None);
+        }
+        db.fetch_orphan_chain_header(hash).map(Some)
+    }
+
+    fn fetch_strongest_orphan_chain_tips(&self) -> Result<Vec<ChainHeader>, ChainStorageError> {
+        trace!(target: LOG_TARGET, "Call to fetch_strongest_orphan_chain_tips() ...");
+        let db = self.read_access()?;
+        let max_value = match db
+            .orphan_chain_tips
+            .values()
+            .map(|tip| tip.total_accumulated_difficulty)
+            .max()
+        {
+            Some(val) => val,
+            None => return Ok(Vec::new()),
+        };
+
+        db.orphan_chain_tips
+            .values()
+            .filter(|tip| tip.total_accumulated_difficulty == max_value)
+            .map(|tip| db.fetch_orphan_chain_header(&tip.hash))
+            .collect()
+    }
+
+    fn fetch_orphan_children_of(&self, parent_hash: HashOutput) -> Result<Vec<Block>, ChainStorageError> {
+        trace!(
+            target: LOG_TARGET,
+            "Call to fetch_orphan_children_of({})",
+            parent_hash.to_hex()
+        );
+        let db = self.read_access()?;
+        let orphan_hashes = match db.orphan_parent_map_index.get(&parent_hash) {
+            Some(hashes) => hashes,
+            None => return Ok(Vec::new()),
+        };
+        orphan_hashes
+            .iter()
+            .map(|hash| {
+                db.orphans
+                    .get(hash)
+                    .cloned()
+                    .ok_or_else(|| ChainStorageError::ValueNotFound {
+                        entity: "Orphan",
+                        field: "hash",
+                        value: hash.to_hex(),
+                    })
+            })
+            .collect()
+    }
+
+    fn fetch_orphan_chain_block(&self, hash: HashOutput) -> Result<Option<ChainBlock>, ChainStorageError> {
+        let db = self.read_access()?;
+        match (db.orphans.get(&hash), db.orphan_header_accumulated_data.get(&hash)) {
+            (Some(block), Some(accumulated_data)) => {
+                let chain_block = ChainBlock::try_construct(Arc::new(block.clone()), accumulated_data.clone())
+                    .ok_or_else(|| ChainStorageError::DataInconsistencyDetected {
+                        function: "fetch_orphan_chain_block",
+                        details: format!("Accumulated data mismatch for hash {}", hash.to_hex()),
+                    })?;
+                Ok(Some(chain_block))
+            },
+            _ => Ok(None),
+        }
+    }
+
+    fn delete_oldest_orphans(
+        &mut self,
+        horizon_height: u64,
+        orphan_storage_capacity: usize,
+    ) -> Result<(), ChainStorageError> {
+        let orphan_count = self.orphan_count()?;
+        let num_over_limit = orphan_count.saturating_sub(orphan_storage_capacity);
+        if num_over_limit == 0 {
+            return Ok(());
+        }
+        debug!(
+            target: LOG_TARGET,
+            "Orphan block storage limit of {} reached, performing cleanup of {} entries.",
+            orphan_storage_capacity,
+            num_over_limit,
+        );
+
+        let mut orphans = self
+            .read_access()?
+            .orphans
+            .values()
+            .map(|block| (block.header.height, block.hash()))
+            .collect::<Vec<_>>();
+
+        // Sort the orphans by age, oldest first
+        orphans.sort_by(|a, b| a.0.cmp(&b.0));
+        let mut txn = DbTransaction::new();
+        for (removed_count, (height, block_hash)) in orphans.into_iter().enumerate() {
+            if height > horizon_height && removed_count >= num_over_limit {
+                break;
+            }
+            debug!(
+                target: LOG_TARGET,
+                "Discarding orphan block #{} ({}).",
+                height,
+                block_hash.to_hex()
+            );
+            txn.delete_orphan(block_hash);
+        }
+        self.write(txn)
+    }
+
+    fn fetch_monero_seed_first_seen_height(&self, seed: &[u8]) -> Result<u64, ChainStorageError> {
+        Ok(self.read_access()?.monero_seed_height.get(seed).copied().unwrap_or(0))
+    }
+
+    fn fetch_horizon_data(&self) -> Result<Option<HorizonData>, ChainStorageError> {
+        let db = self.read_access()?;
+        db.metadata
+            .horizon_data
+            .clone()
+            .map(Some)
+            .ok_or_else(|| ChainStorageError::ValueNotFound {
+                entity: "HorizonData",
+                field: "metadata",
+                value: "".to_string(),
+            })
+    }
+
+    fn get_stats(&self) -> Result<DbBasicStats, ChainStorageError> {
+        let db = self.read_access()?;
+        Ok(DbBasicStats::from_entry_counts(db.entry_counts()))
+    }
+
+    fn fetch_total_size_stats(&self) -> Result<DbTotalSizeStats, ChainStorageError> {
+        Ok(self.read_access()?.total_size_stats())
+    }
+
+    fn bad_block_exists(&self, block_hash: HashOutput) -> Result<(bool, String), ChainStorageError> {
+        Ok(match self.read_access()?.bad_blocks.get(&block_hash) {
+            Some((_height, reason)) => (true, reason.clone()),
+            None => (false, "".to_string()),
+        })
+    }
+
+    fn fetch_all_reorgs(&self) -> Result<Vec<Reorg>, ChainStorageError> {
+        Ok(self.read_access()?.reorgs.values().cloned().collect())
+    }
+
+    fn fetch_active_validator_nodes(&self, height: u64) -> Result<Vec<(PublicKey, [u8; 32])>, ChainStorageError> {
+        let db = self.read_access()?;
+        let constants = self.get_consensus_constants(height);
+
+        // Get the current epoch for the height
+        let end_epoch = constants.block_height_to_epoch(height);
+        // Subtract the registration validaty period to get the start epoch
+        let start_epoch = end_epoch.saturating_sub(constants.validator_node_validity_period_epochs());
+        // Convert these back to height as validators regs are indexed by height
+        let start_height = start_epoch.as_u64() * constants.epoch_length();
+        let end_height = end_epoch.as_u64() * constants.epoch_length();
+        Ok(db.get_vn_set(start_height, end_height))
+    }
+
+    fn get_shard_key(&self, height: u64, public_key: PublicKey) -> Result<Option<[u8; 32]>, ChainStorageError> {
+        let db = self.read_access()?;
+        let constants = self.get_consensus_constants(height);
+
+        // Get the epoch height boundaries for our query
+        let current_epoch = constants.block_height_to_epoch(height);
+        let start_epoch = current_epoch.saturating_sub(constants.validator_node_validity_period_epochs());
+        let start_height = start_epoch.as_u64() * constants.epoch_length();
+        let end_height = current_epoch.as_u64() * constants.epoch_length();
+        Ok(db.get_shard_key(start_height, end_height, &public_key))
+    }
+
+    fn fetch_template_registrations(
+        &self,
+        start_height: u64,
+        end_height: u64,
+    ) -> Result<Vec<TemplateRegistrationEntry>, ChainStorageError> {
+        let db = self.read_access()?;
+        Ok(db
+            .template_registrations
+            .range((start_height, FixedHash::zero())..)
+            .take_while(|((height, _), _)| *height <= end_height)
+            .map(|(_, entry)| entry.clone())
+            .collect())
+    }
+
+    fn fetch_outputs_by_index(
+        &self,
+        index: &OutputIndex,
+        start_height: u64,
+        end_height: u64,
+    ) -> Result<Vec<OutputMinedInfo>, ChainStorageError> {
+        let db = self.read_access()?;
+        if !db.metadata.output_indexing {
+            return Err(ChainStorageError::InvalidOperation(
+                "Output indexing is not enabled".to_string(),
+            ));
+        }
+
+        let (start_key, end_key) = index.storage_key_range(start_height, end_height);
+        db.output_indexes
+            .range(start_key..=end_key)
+            .map(|(_, output_hash)| {
+                db.fetch_output(output_hash)
+                    .ok_or_else(|| ChainStorageError::DataInconsistencyDetected {
+                        function: "fetch_outputs_by_index",
+                        details: format!("Indexed output {} ({}) does not exist", output_hash, index),
+                    })
+            })
+            .collect()
+    }
+
+    fn fetch_output_smt_delta(&self, height: u64) -> Result<Option<OutputSmtDelta>, ChainStorageError> {
+        let db = self.read_access()?;
+        Ok(db.output_smt_deltas.get(&height).cloned())
+    }
+
+    fn calculate_tip_smt(&self) -> Result<OutputSmt, ChainStorageError> {
+        let start = Instant::now();
+        let db = self.read_access()?;
+        let metadata = db.fetch_chain_metadata()?;
+        let mut smt = OutputSmt::new();
+        trace!(
+            target: LOG_TARGET,
+            "Calculating new smt at height: #{}",
+            metadata.pruned_height(),
+        );
+        for height in 0..=metadata.best_block_height() {
+            let header = db.fetch_chain_header_by_height(height)?;
+            let outputs =
+                db.fetch_outputs_in_block_with_spend_state(header.hash(), Some(metadata.best_block_hash()))?;
+            for (output, spent) in outputs {
+                if !spent && !output.is_burned() {
+                    let smt_key = NodeKey::try_from(output.commitment.as_bytes())?;
+                    let smt_node = ValueHash::try_from(output.smt_hash(header.height()).as_slice())?;
+                    if let Err(e) = smt.insert(smt_key, smt_node) {
+                        error!(
+                            target: LOG_TARGET,
+                            "Output commitment({}) already in SMT",
+                            output.commitment.to_hex(),
+                        );
+                        return Err(e.into());
+                    }
+                }
+            }
+        }
+        trace!(
+            target: LOG_TARGET,
+            "Finished calculating new smt (size: {}), took: {:.2?}",
+            smt.size(),
+            start.elapsed()
+        );
+        Ok(smt)
+    }
+}
+
+impl MemoryDatabase {
+    fn get_consensus_constants(&self, height: u64) -> &ConsensusConstants {
+        self.consensus_manager.consensus_constants(height)
+    }
+}
@@ -0,0 +1,7 @@
+// Copyright 2024 The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+pub use memory_db::{create_memory_database, MemoryDatabase};
+
+#[allow(clippy::module_inception)]
+mod memory_db;
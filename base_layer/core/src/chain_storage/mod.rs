@@ -70,9 +70,29 @@ pub use horizon_data::HorizonData;
 mod reorg;
 pub use reorg::Reorg;
 
+mod snapshot;
+pub use snapshot::{export_snapshot, import_snapshot, SnapshotError, SnapshotSummary};
+
+mod consistency;
+pub use consistency::{check_chain_consistency, ChainInconsistency, ConsistencyReport, InconsistencyKind};
+
+mod output_index;
+pub use output_index::OutputIndex;
+
+mod output_smt_delta;
+pub use output_smt_delta::OutputSmtDelta;
+
 mod lmdb_db;
 pub use lmdb_db::{create_lmdb_database, create_recovery_lmdb_database, LMDBDatabase};
 
+mod memory_db;
+pub use memory_db::{create_memory_database, MemoryDatabase};
+
+#[cfg(feature = "redb")]
+mod redb_db;
+#[cfg(feature = "redb")]
+pub use redb_db::{create_redb_database, migrate_lmdb_to_redb, redb_database_file, RedbDatabase};
+
 mod stats;
 pub use stats::{DbBasicStats, DbSize, DbStat, DbTotalSizeStats};
 
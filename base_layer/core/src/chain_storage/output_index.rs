@@ -0,0 +1,170 @@
+//  Copyright 2024, The Tari Project
+//
+//  Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+//  following conditions are met:
+//
+//  1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+//  disclaimer.
+//
+//  2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+//  following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+//  3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+//  products derived from this software without specific prior written permission.
+//
+//  THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+//  INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+//  DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+//  SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+//  SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+//  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+//  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::fmt::{Display, Formatter};
+
+use blake2::Blake2b;
+use digest::{consts::U32, Digest};
+use serde::{Deserialize, Serialize};
+use tari_common_types::types::{Commitment, FixedHash, HashOutput, PublicKey};
+use tari_script::TariScript;
+use tari_utilities::{hex::Hex, ByteArray};
+
+use crate::transactions::transaction_components::{OutputType, SideChainFeatureType, TransactionOutput};
+
+const SCRIPT_HASH_TAG: u8 = 0;
+const OUTPUT_TYPE_TAG: u8 = 1;
+const SIDECHAIN_FEATURE_TAG: u8 = 2;
+const COMMITMENT_TAG: u8 = 3;
+const SENDER_OFFSET_PUBLIC_KEY_TAG: u8 = 4;
+
+/// A secondary index over the outputs in the blockchain. These indexes are only maintained by the backend when
+/// `index_outputs` is enabled in the [BlockchainDatabaseConfig](crate::chain_storage::BlockchainDatabaseConfig).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OutputIndex {
+    /// Outputs locked by the script with this hash, see [OutputIndex::script_hash]
+    ScriptHash(FixedHash),
+    /// Outputs with this output type
+    OutputType(OutputType),
+    /// Outputs carrying a sidechain feature of this type
+    SideChainFeature(SideChainFeatureType),
+    /// Outputs with this commitment. Unlike the unspent commitment index, spent outputs are kept in this index, so it
+    /// can be used to look up the state of a commitment at past heights.
+    Commitment(Commitment),
+    /// Outputs with this sender offset public key
+    SenderOffsetPublicKey(PublicKey),
+}
+
+impl OutputIndex {
+    /// The version of the set of indexes returned by [OutputIndex::all_for_output]. This must be incremented whenever
+    /// that set changes, so that backends rebuild the indexes of outputs that are already stored.
+    pub const VERSION: u32 = 2;
+
+    /// Returns the hash used to index a script. This is the same Blake2b-256 hash of the serialized script that wallets
+    /// use to identify scripts.
+    pub fn script_hash(script: &TariScript) -> FixedHash {
+        FixedHash::from(Blake2b::<U32>::digest(script.to_bytes()))
+    }
+
+    /// Returns all the indexes the output should be stored under
+    pub fn all_for_output(output: &TransactionOutput) -> Vec<Self> {
+        let mut indexes = vec![
+            OutputIndex::ScriptHash(Self::script_hash(&output.script)),
+            OutputIndex::OutputType(output.features.output_type),
+            OutputIndex::Commitment(output.commitment.clone()),
+            OutputIndex::SenderOffsetPublicKey(output.sender_offset_public_key.clone()),
+        ];
+        if let Some(feature) = output.features.sidechain_feature.as_ref() {
+            indexes.push(OutputIndex::SideChainFeature(feature.feature_type()));
+        }
+        indexes
+    }
+
+    /// The storage key for an output in this index: the index prefix, followed by the big-endian mined height and the
+    /// output hash, so that entries for an index are ordered by height.
+    pub(crate) fn storage_key(&self, height: u64, output_hash: &HashOutput) -> Vec<u8> {
+        let mut key = self.key_prefix();
+        key.extend_from_slice(&height.to_be_bytes());
+        key.extend_from_slice(output_hash.as_slice());
+        key
+    }
+
+    /// Returns the (inclusive) storage key bounds that contain every entry mined between the given heights
+    pub(crate) fn storage_key_range(&self, start_height: u64, end_height: u64) -> (Vec<u8>, Vec<u8>) {
+        (
+            self.storage_key(start_height, &FixedHash::zero()),
+            self.storage_key(end_height, &FixedHash::from([0xffu8; FixedHash::byte_size()])),
+        )
+    }
+
+    fn key_prefix(&self) -> Vec<u8> {
+        match self {
+            OutputIndex::ScriptHash(hash) => {
+                let mut prefix = Vec::with_capacity(1 + FixedHash::byte_size());
+                prefix.push(SCRIPT_HASH_TAG);
+                prefix.extend_from_slice(hash.as_slice());
+                prefix
+            },
+            OutputIndex::OutputType(output_type) => vec![OUTPUT_TYPE_TAG, output_type.as_byte()],
+            OutputIndex::SideChainFeature(feature_type) => vec![SIDECHAIN_FEATURE_TAG, feature_type.as_byte()],
+            OutputIndex::Commitment(commitment) => {
+                let mut prefix = Vec::with_capacity(1 + commitment.as_bytes().len());
+                prefix.push(COMMITMENT_TAG);
+                prefix.extend_from_slice(commitment.as_bytes());
+                prefix
+            },
+            OutputIndex::SenderOffsetPublicKey(public_key) => {
+                let mut prefix = Vec::with_capacity(1 + public_key.as_bytes().len());
+                prefix.push(SENDER_OFFSET_PUBLIC_KEY_TAG);
+                prefix.extend_from_slice(public_key.as_bytes());
+                prefix
+            },
+        }
+    }
+}
+
+impl Display for OutputIndex {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OutputIndex::ScriptHash(hash) => write!(f, "ScriptHash({})", hash.to_hex()),
+            OutputIndex::OutputType(output_type) => write!(f, "OutputType({})", output_type),
+            OutputIndex::SideChainFeature(feature_type) => write!(f, "SideChainFeature({:?})", feature_type),
+            OutputIndex::Commitment(commitment) => write!(f, "Commitment({})", commitment.to_hex()),
+            OutputIndex::SenderOffsetPublicKey(public_key) => {
+                write!(f, "SenderOffsetPublicKey({})", public_key.to_hex())
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_orders_keys_by_height_within_an_index() {
+        let index = OutputIndex::OutputType(OutputType::Standard);
+        let hash = FixedHash::from([1u8; 32]);
+        let (start, end) = index.storage_key_range(5, 10);
+        assert!(index.storage_key(4, &hash) < start);
+        assert!(index.storage_key(5, &hash) >= start);
+        assert!(index.storage_key(10, &hash) <= end);
+        assert!(index.storage_key(11, &FixedHash::zero()) > end);
+    }
+
+    #[test]
+    fn it_separates_indexes() {
+        let hash = FixedHash::from([1u8; 32]);
+        let (start, end) = OutputIndex::OutputType(OutputType::Coinbase).storage_key_range(0, u64::MAX);
+        for other in [
+            OutputIndex::OutputType(OutputType::Standard),
+            OutputIndex::OutputType(OutputType::Burn),
+            OutputIndex::SideChainFeature(SideChainFeatureType::ValidatorNodeRegistration),
+            OutputIndex::ScriptHash(hash),
+            OutputIndex::Commitment(Commitment::default()),
+            OutputIndex::SenderOffsetPublicKey(PublicKey::default()),
+        ] {
+            let key = other.storage_key(1, &hash);
+            assert!(key < start || key > end);
+        }
+    }
+}
@@ -0,0 +1,116 @@
+//  Copyright 2024, The Tari Project
+//
+//  Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+//  following conditions are met:
+//
+//  1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+//  disclaimer.
+//
+//  2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+//  following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+//  3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+//  products derived from this software without specific prior written permission.
+//
+//  THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+//  INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+//  DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+//  SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+//  SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+//  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+//  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use serde::{Deserialize, Serialize};
+use tari_common_types::types::{Commitment, FixedHash};
+use tari_mmr::sparse_merkle_tree::{NodeKey, ValueHash};
+use tari_utilities::ByteArray;
+
+use crate::{chain_storage::ChainStorageError, OutputSmt};
+
+/// The changes that a block made to the output SMT. When `archival` is enabled in the
+/// [BlockchainDatabaseConfig](crate::chain_storage::BlockchainDatabaseConfig), the backend stores the delta of every
+/// block by height, so that the output SMT at a past height can be calculated without reading the blocks above it.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutputSmtDelta {
+    /// The leaves of the outputs created in the block
+    pub inserted: Vec<(Commitment, FixedHash)>,
+    /// The leaves of the outputs spent in the block
+    pub deleted: Vec<(Commitment, FixedHash)>,
+}
+
+impl OutputSmtDelta {
+    pub fn record_insert(&mut self, commitment: &Commitment, value: &ValueHash) -> Result<(), ChainStorageError> {
+        self.inserted
+            .push((commitment.clone(), FixedHash::try_from(value.as_slice())?));
+        Ok(())
+    }
+
+    pub fn record_delete(&mut self, commitment: &Commitment, value: &ValueHash) -> Result<(), ChainStorageError> {
+        self.deleted
+            .push((commitment.clone(), FixedHash::try_from(value.as_slice())?));
+        Ok(())
+    }
+
+    /// Reverts the changes that the block made to the SMT
+    pub fn undo(&self, smt: &mut OutputSmt) -> Result<(), ChainStorageError> {
+        // An output that was created and spent in the same block is in both lists, so the spent outputs are put back
+        // before the created outputs are removed
+        for (commitment, value) in &self.deleted {
+            smt.insert(
+                NodeKey::try_from(commitment.as_bytes())?,
+                ValueHash::try_from(value.as_slice())?,
+            )?;
+        }
+        for (commitment, _) in &self.inserted {
+            smt.delete(&NodeKey::try_from(commitment.as_bytes())?)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::rngs::OsRng;
+    use tari_common_types::types::{PrivateKey, PublicKey};
+    use tari_crypto::keys::{PublicKey as PublicKeyTrait, SecretKey};
+
+    use super::*;
+
+    fn leaf(n: u8) -> (Commitment, ValueHash) {
+        let public_key = PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng));
+        (Commitment::from_public_key(&public_key), ValueHash::from([n; 32]))
+    }
+
+    #[test]
+    fn it_undoes_the_changes_of_a_block() {
+        let mut smt = OutputSmt::new();
+        let (spent, spent_value) = leaf(1);
+        let (kept, kept_value) = leaf(2);
+        smt.insert(NodeKey::try_from(spent.as_bytes()).unwrap(), spent_value.clone())
+            .unwrap();
+        smt.insert(NodeKey::try_from(kept.as_bytes()).unwrap(), kept_value)
+            .unwrap();
+        let root_before = smt.hash().clone();
+
+        let mut delta = OutputSmtDelta::default();
+        let (created, created_value) = leaf(3);
+        let (created_and_spent, created_and_spent_value) = leaf(4);
+        for (commitment, value) in [
+            (&created, &created_value),
+            (&created_and_spent, &created_and_spent_value),
+        ] {
+            smt.insert(NodeKey::try_from(commitment.as_bytes()).unwrap(), value.clone())
+                .unwrap();
+            delta.record_insert(commitment, value).unwrap();
+        }
+        for (commitment, value) in [(&spent, &spent_value), (&created_and_spent, &created_and_spent_value)] {
+            smt.delete(&NodeKey::try_from(commitment.as_bytes()).unwrap()).unwrap();
+            delta.record_delete(commitment, value).unwrap();
+        }
+        assert_ne!(smt.hash(), &root_before);
+
+        delta.undo(&mut smt).unwrap();
+        assert_eq!(smt.hash(), &root_before);
+        assert_eq!(smt.size(), 2);
+    }
+}
@@ -0,0 +1,179 @@
+// Copyright 2024 The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+use std::{convert::TryInto, time::Instant};
+
+use log::*;
+use redb::{ReadableTableMetadata, WriteTransaction};
+
+use super::{
+    redb_db::{
+        ALL_TABLES,
+        BLOCK_ACCUMULATED_DATA,
+        HEADERS,
+        HEADER_ACCUMULATED_DATA,
+        METADATA,
+        ORPHAN_PARENT_MAP_INDEX,
+        OUTPUT_SMT_DELTAS,
+        REORGS,
+        TEMPLATE_REGISTRATIONS,
+    },
+    RedbDatabase,
+};
+use crate::chain_storage::{ChainStorageError, LMDBDatabase};
+
+const LOG_TARGET: &str = "c::cs::redb_db::migrate";
+
+/// Copies every table of an LMDB chain database into an empty redb database.
+///
+/// Values are copied as-is, since both backends use the same encoding. Integer keys that LMDB stores in native byte
+/// order are converted to big-endian. All tables are copied in a single write transaction, so an interrupted migration
+/// leaves the redb database empty and can simply be run again. The source database is only read, so it can be kept as
+/// a backup until the node has been started successfully with the new backend.
+pub fn migrate_lmdb_to_redb(lmdb: &LMDBDatabase, redb: &RedbDatabase) -> Result<u64, ChainStorageError> {
+    let timer = Instant::now();
+    {
+        let txn = redb.database().begin_read()?;
+        if txn.open_table(HEADERS)?.len()? > 0 {
+            return Err(ChainStorageError::InvalidOperation(
+                "Cannot migrate into a redb database that already contains blocks".to_string(),
+            ));
+        }
+    }
+
+    let txn = redb.database().begin_write()?;
+    let total = copy_tables(lmdb, &txn)?;
+    txn.commit()?;
+
+    info!(
+        target: LOG_TARGET,
+        "Copied {} entries from LMDB to redb in {:.2?}",
+        total,
+        timer.elapsed()
+    );
+    Ok(total)
+}
+
+/// Copies the entries of all tables into the redb write transaction, returning the number of entries copied
+fn copy_tables(lmdb: &LMDBDatabase, txn: &WriteTransaction) -> Result<u64, ChainStorageError> {
+    let mut total = 0;
+    for table in ALL_TABLES {
+        let mut redb_table = txn.open_table(table)?;
+        let num_copied = copy_table(lmdb, table.name(), |key, value| {
+            let key = convert_key(table.name(), key)?;
+            // Entries are replaced rather than inserted, since the migration version is written to the metadata table
+            // when the redb database is created
+            redb_table.insert(key.as_slice(), value)?;
+            Ok(())
+        })?;
+        debug!(target: LOG_TARGET, "Copied {} entries from '{}'", num_copied, table.name());
+        total += num_copied;
+    }
+
+    let mut redb_table = txn.open_multimap_table(ORPHAN_PARENT_MAP_INDEX)?;
+    let num_copied = copy_table(lmdb, ORPHAN_PARENT_MAP_INDEX.name(), |key, value| {
+        redb_table.insert(key, value)?;
+        Ok(())
+    })?;
+    debug!(
+        target: LOG_TARGET,
+        "Copied {} entries from '{}'",
+        num_copied,
+        ORPHAN_PARENT_MAP_INDEX.name()
+    );
+    Ok(total + num_copied)
+}
+
+/// Copies all entries of the named LMDB database using `insert`
+fn copy_table<F>(lmdb: &LMDBDatabase, name: &str, mut insert: F) -> Result<u64, ChainStorageError>
+where F: FnMut(&[u8], &[u8]) -> Result<(), ChainStorageError> {
+    let mut num_copied = 0u64;
+    lmdb.for_each_raw_entry(name, |key, value| {
+        insert(key, value)?;
+        num_copied += 1;
+        Ok(())
+    })?;
+    Ok(num_copied)
+}
+
+/// Converts LMDB keys that are stored in native byte order to the big-endian keys used by the redb backend
+fn convert_key(table_name: &str, key: &[u8]) -> Result<Vec<u8>, ChainStorageError> {
+    let invalid_key = || ChainStorageError::DataInconsistencyDetected {
+        function: "migrate_lmdb_to_redb",
+        details: format!("Invalid key length {} in '{}'", key.len(), table_name),
+    };
+    let converted = match table_name {
+        n if n == METADATA.name() => u32::from_ne_bytes(key.try_into().map_err(|_| invalid_key())?)
+            .to_be_bytes()
+            .to_vec(),
+        n if n == HEADERS.name() ||
+            n == HEADER_ACCUMULATED_DATA.name() ||
+            n == BLOCK_ACCUMULATED_DATA.name() ||
+            n == OUTPUT_SMT_DELTAS.name() =>
+        {
+            u64::from_ne_bytes(key.try_into().map_err(|_| invalid_key())?)
+                .to_be_bytes()
+                .to_vec()
+        },
+        n if n == REORGS.name() => i64::from_ne_bytes(key.try_into().map_err(|_| invalid_key())?)
+            .to_be_bytes()
+            .to_vec(),
+        n if n == TEMPLATE_REGISTRATIONS.name() => {
+            if key.len() < 8 {
+                return Err(invalid_key());
+            }
+            let (height, output_hash) = key.split_at(8);
+            let height = u64::from_le_bytes(height.try_into().map_err(|_| invalid_key())?);
+            [height.to_be_bytes().as_slice(), output_hash].concat()
+        },
+        _ => key.to_vec(),
+    };
+    Ok(converted)
+}
+
+#[cfg(test)]
+mod test {
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::{
+        chain_storage::create_redb_database,
+        test_helpers::{blockchain::create_new_blockchain, create_consensus_rules},
+    };
+
+    #[test]
+    fn it_reruns_an_interrupted_migration() {
+        let lmdb = create_new_blockchain();
+        let lmdb = lmdb.db_read_access().unwrap();
+        let dir = tempdir().unwrap();
+        let redb = create_redb_database(dir.path(), create_consensus_rules()).unwrap();
+
+        // The process stops after the tables have been copied but before the transaction is committed
+        let txn = redb.database().begin_write().unwrap();
+        let num_copied = copy_tables(lmdb.db(), &txn).unwrap();
+        assert!(num_copied > 0);
+        drop(txn);
+        assert_eq!(
+            redb.database()
+                .begin_read()
+                .unwrap()
+                .open_table(HEADERS)
+                .unwrap()
+                .len()
+                .unwrap(),
+            0
+        );
+
+        assert_eq!(migrate_lmdb_to_redb(lmdb.db(), &redb).unwrap(), num_copied);
+        assert_eq!(
+            redb.database()
+                .begin_read()
+                .unwrap()
+                .open_table(HEADERS)
+                .unwrap()
+                .len()
+                .unwrap(),
+            1
+        );
+    }
+}
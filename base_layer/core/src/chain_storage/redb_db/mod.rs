@@ -0,0 +1,11 @@
+// Copyright 2024 The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+mod migrate;
+pub use migrate::migrate_lmdb_to_redb;
+
+mod redb;
+
+#[allow(clippy::module_inception)]
+mod redb_db;
+pub use redb_db::{create_redb_database, redb_database_file, RedbDatabase};
@@ -0,0 +1,391 @@
+// Copyright 2024 The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+use std::ops::Bound;
+
+use log::*;
+use redb::{
+    MultimapTableDefinition,
+    ReadOnlyTable,
+    ReadTransaction,
+    ReadableMultimapTable,
+    ReadableTable,
+    ReadableTableMetadata,
+    Table,
+    TableDefinition,
+    WriteTransaction,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use tari_utilities::hex::to_hex;
+
+use crate::chain_storage::ChainStorageError;
+
+pub const LOG_TARGET: &str = "c::cs::redb_db::redb";
+
+/// All tables map byte keys to bincode encoded values. Integer keys are stored big-endian so that they sort
+/// numerically.
+pub type TableDef = TableDefinition<'static, &'static [u8], &'static [u8]>;
+pub type MultimapTableDef = MultimapTableDefinition<'static, &'static [u8], &'static [u8]>;
+
+/// Opens tables in either a read or a write transaction, so that the read helpers can be used with both.
+pub trait RedbTransaction {
+    type Table<'a>: ReadableTable<&'static [u8], &'static [u8]> + ReadableTableMetadata
+    where Self: 'a;
+
+    fn open(&self, table: TableDef) -> Result<Self::Table<'_>, ChainStorageError>;
+}
+
+impl RedbTransaction for ReadTransaction {
+    type Table<'a> = ReadOnlyTable<&'static [u8], &'static [u8]>;
+
+    fn open(&self, table: TableDef) -> Result<Self::Table<'_>, ChainStorageError> {
+        Ok(self.open_table(table)?)
+    }
+}
+
+impl RedbTransaction for WriteTransaction {
+    type Table<'a> = Table<'a, &'static [u8], &'static [u8]>;
+
+    fn open(&self, table: TableDef) -> Result<Self::Table<'_>, ChainStorageError> {
+        Ok(self.open_table(table)?)
+    }
+}
+
+pub fn serialize<V: Serialize + ?Sized>(value: &V) -> Result<Vec<u8>, ChainStorageError> {
+    bincode::serialize(value).map_err(|e| {
+        error!(target: LOG_TARGET, "Could not serialize value for redb: {:?}", e);
+        ChainStorageError::AccessError(e.to_string())
+    })
+}
+
+pub fn deserialize<V: DeserializeOwned>(bytes: &[u8]) -> Result<V, ChainStorageError> {
+    bincode::deserialize(bytes).map_err(|e| {
+        error!(target: LOG_TARGET, "Could not deserialize value from redb: {:?}", e);
+        ChainStorageError::AccessError(e.to_string())
+    })
+}
+
+/// Makes an insertion into the table, will error if the key already exists
+pub fn redb_insert<V: Serialize + ?Sized>(
+    txn: &WriteTransaction,
+    table: TableDef,
+    key: &[u8],
+    value: &V,
+    table_name: &'static str,
+) -> Result<(), ChainStorageError> {
+    let mut table = txn.open_table(table)?;
+    if table.get(key)?.is_some() {
+        return Err(ChainStorageError::KeyExists {
+            table_name,
+            key: to_hex(key),
+        });
+    }
+    let value = serialize(value)?;
+    table.insert(key, value.as_slice())?;
+    Ok(())
+}
+
+/// Inserts or replaces the item at the given key
+pub fn redb_replace<V: Serialize + ?Sized>(
+    txn: &WriteTransaction,
+    table: TableDef,
+    key: &[u8],
+    value: &V,
+) -> Result<(), ChainStorageError> {
+    let mut table = txn.open_table(table)?;
+    let value = serialize(value)?;
+    table.insert(key, value.as_slice())?;
+    Ok(())
+}
+
+/// Deletes the given key. An error is returned if the key does not exist
+pub fn redb_delete(
+    txn: &WriteTransaction,
+    table: TableDef,
+    key: &[u8],
+    table_name: &'static str,
+) -> Result<(), ChainStorageError> {
+    let mut table = txn.open_table(table)?;
+    let removed = table.remove(key)?.is_some();
+    if !removed {
+        return Err(ChainStorageError::ValueNotFound {
+            entity: table_name,
+            field: "<unknown>",
+            value: to_hex(key),
+        });
+    }
+    Ok(())
+}
+
+/// Deletes all keys starting with `prefix`, returning the deleted values
+pub fn redb_delete_keys_starting_with<V: DeserializeOwned>(
+    txn: &WriteTransaction,
+    table: TableDef,
+    prefix: &[u8],
+) -> Result<Vec<V>, ChainStorageError> {
+    let mut table = txn.open_table(table)?;
+    let mut keys = Vec::new();
+    let mut result = Vec::new();
+    for entry in table.range::<&[u8]>(prefix..)? {
+        let (k, v) = entry?;
+        if !k.value().starts_with(prefix) {
+            break;
+        }
+        keys.push(k.value().to_vec());
+        result.push(deserialize(v.value())?);
+    }
+    for key in keys {
+        table.remove(key.as_slice())?;
+    }
+    Ok(result)
+}
+
+/// Deletes entries for which the predicate returns `Some(true)`. Iteration stops when the predicate returns `None`.
+pub fn redb_delete_each_where<V, F>(
+    txn: &WriteTransaction,
+    table: TableDef,
+    mut predicate: F,
+) -> Result<usize, ChainStorageError>
+where
+    V: DeserializeOwned,
+    F: FnMut(&[u8], V) -> Option<bool>,
+{
+    let mut table = txn.open_table(table)?;
+    let mut keys = Vec::new();
+    for entry in table.iter()? {
+        let (k, v) = entry?;
+        match predicate(k.value(), deserialize(v.value())?) {
+            Some(true) => keys.push(k.value().to_vec()),
+            Some(false) => continue,
+            None => break,
+        }
+    }
+    for key in &keys {
+        table.remove(key.as_slice())?;
+    }
+    Ok(keys.len())
+}
+
+/// Deletes all entries in the table, returning the number of deleted entries
+pub fn redb_clear(txn: &WriteTransaction, table: TableDef) -> Result<usize, ChainStorageError> {
+    let mut table = txn.open_table(table)?;
+    let mut keys = Vec::new();
+    for entry in table.iter()? {
+        keys.push(entry?.0.value().to_vec());
+    }
+    for key in &keys {
+        table.remove(key.as_slice())?;
+    }
+    Ok(keys.len())
+}
+
+/// Retrieves the value at the given key
+pub fn redb_get<T, V>(txn: &T, table: TableDef, key: &[u8]) -> Result<Option<V>, ChainStorageError>
+where
+    T: RedbTransaction,
+    V: DeserializeOwned,
+{
+    let table = txn.open(table)?;
+    let value = match table.get(key)? {
+        Some(v) => Some(deserialize(v.value())?),
+        None => None,
+    };
+    Ok(value)
+}
+
+/// Checks if the key exists in the table
+pub fn redb_exists<T: RedbTransaction>(txn: &T, table: TableDef, key: &[u8]) -> Result<bool, ChainStorageError> {
+    let table = txn.open(table)?;
+    let exists = table.get(key)?.is_some();
+    Ok(exists)
+}
+
+/// Returns the number of entries in the table
+pub fn redb_len<T: RedbTransaction>(txn: &T, table: TableDef) -> Result<usize, ChainStorageError> {
+    let table = txn.open(table)?;
+    let len = table.len()?;
+    usize::try_from(len).map_err(|_| ChainStorageError::OutOfRange)
+}
+
+/// Retrieves the value with the greatest key in the table
+pub fn redb_last<T, V>(txn: &T, table: TableDef) -> Result<Option<V>, ChainStorageError>
+where
+    T: RedbTransaction,
+    V: DeserializeOwned,
+{
+    let table = txn.open(table)?;
+    let value = match table.last()? {
+        Some((_, v)) => Some(deserialize(v.value())?),
+        None => None,
+    };
+    Ok(value)
+}
+
+/// Retrieves the value of the first key that is greater than or equal to the given key
+pub fn redb_first_after<T, V>(txn: &T, table: TableDef, key: &[u8]) -> Result<Option<V>, ChainStorageError>
+where
+    T: RedbTransaction,
+    V: DeserializeOwned,
+{
+    let table = txn.open(table)?;
+    let value = match table.range::<&[u8]>(key..)?.next() {
+        Some(entry) => Some(deserialize(entry?.1.value())?),
+        None => None,
+    };
+    Ok(value)
+}
+
+/// Retrieves all values with keys starting with `prefix`, in key order
+pub fn redb_fetch_matching_after<T, V>(txn: &T, table: TableDef, prefix: &[u8]) -> Result<Vec<V>, ChainStorageError>
+where
+    T: RedbTransaction,
+    V: DeserializeOwned,
+{
+    let table = txn.open(table)?;
+    let mut result = Vec::new();
+    for entry in table.range::<&[u8]>(prefix..)? {
+        let (k, v) = entry?;
+        if !k.value().starts_with(prefix) {
+            break;
+        }
+        result.push(deserialize(v.value())?);
+    }
+    Ok(result)
+}
+
+/// Retrieves all values with keys between `start_key` and `end_key` inclusive, in key order
+pub fn redb_fetch_range<T, V>(
+    txn: &T,
+    table: TableDef,
+    start_key: &[u8],
+    end_key: &[u8],
+) -> Result<Vec<V>, ChainStorageError>
+where
+    T: RedbTransaction,
+    V: DeserializeOwned,
+{
+    let table = txn.open(table)?;
+    let mut result = Vec::new();
+    for entry in table.range::<&[u8]>(start_key..=end_key)? {
+        result.push(deserialize(entry?.1.value())?);
+    }
+    Ok(result)
+}
+
+/// Fetches up to `limit` entries in key order, starting after the key `after`, or at the first key if `after` is
+/// `None`. This allows a table to be iterated over in batches while writing to other tables in between.
+pub fn redb_fetch_batch_after<T, V>(
+    txn: &T,
+    table: TableDef,
+    after: Option<&[u8]>,
+    limit: usize,
+) -> Result<Vec<(Vec<u8>, V)>, ChainStorageError>
+where
+    T: RedbTransaction,
+    V: DeserializeOwned,
+{
+    let table = txn.open(table)?;
+    let start = after.map_or(Bound::Unbounded, Bound::Excluded);
+    let mut result = Vec::with_capacity(limit);
+    for entry in table.range::<&[u8]>((start, Bound::Unbounded))?.take(limit) {
+        let (k, v) = entry?;
+        result.push((k.value().to_vec(), deserialize(v.value())?));
+    }
+    Ok(result)
+}
+
+/// Applies `f` to every value in the table, returning the values for which it returns `Some`
+pub fn redb_filter_map_values<T, V, R, F>(txn: &T, table: TableDef, f: F) -> Result<Vec<R>, ChainStorageError>
+where
+    T: RedbTransaction,
+    V: DeserializeOwned,
+    F: Fn(V) -> Option<R>,
+{
+    let table = txn.open(table)?;
+    let mut result = Vec::new();
+    for entry in table.iter()? {
+        if let Some(r) = f(deserialize(entry?.1.value())?) {
+            result.push(r);
+        }
+    }
+    Ok(result)
+}
+
+/// Fetches the size of all key/values in the given table. Returns the number of entries, the total size of all the
+/// keys and values in bytes.
+pub fn fetch_table_entry_sizes<T: RedbTransaction>(
+    txn: &T,
+    table: TableDef,
+) -> Result<(u64, u64, u64), ChainStorageError> {
+    let table = txn.open(table)?;
+    let mut num_entries = 0;
+    let mut total_key_size = 0;
+    let mut total_value_size = 0;
+    for entry in table.iter()? {
+        let (k, v) = entry?;
+        num_entries += 1;
+        total_key_size += k.value().len() as u64;
+        total_value_size += v.value().len() as u64;
+    }
+    Ok((num_entries, total_key_size, total_value_size))
+}
+
+/// Adds a value to the set of values stored at the given key
+pub fn redb_insert_dup<V: Serialize + ?Sized>(
+    txn: &WriteTransaction,
+    table: MultimapTableDef,
+    key: &[u8],
+    value: &V,
+) -> Result<(), ChainStorageError> {
+    let mut table = txn.open_multimap_table(table)?;
+    let value = serialize(value)?;
+    table.insert(key, value.as_slice())?;
+    Ok(())
+}
+
+/// Removes a value from the set of values stored at the given key
+pub fn redb_delete_key_value<V: Serialize + ?Sized>(
+    txn: &WriteTransaction,
+    table: MultimapTableDef,
+    key: &[u8],
+    value: &V,
+) -> Result<(), ChainStorageError> {
+    let mut table = txn.open_multimap_table(table)?;
+    let value = serialize(value)?;
+    table.remove(key, value.as_slice())?;
+    Ok(())
+}
+
+/// Retrieves all values stored at the given key
+pub fn redb_get_multiple<V: DeserializeOwned>(
+    txn: &ReadTransaction,
+    table: MultimapTableDef,
+    key: &[u8],
+) -> Result<Vec<V>, ChainStorageError> {
+    let table = txn.open_multimap_table(table)?;
+    let mut result = Vec::new();
+    for value in table.get(key)? {
+        result.push(deserialize(value?.value())?);
+    }
+    Ok(result)
+}
+
+/// Returns the number of key/value pairs and the total size of all keys and values in the given multimap table
+pub fn fetch_multimap_entry_sizes(
+    txn: &ReadTransaction,
+    table: MultimapTableDef,
+) -> Result<(u64, u64, u64), ChainStorageError> {
+    let table = txn.open_multimap_table(table)?;
+    let mut num_entries = 0;
+    let mut total_key_size = 0;
+    let mut total_value_size = 0;
+    for entry in table.iter()? {
+        let (k, values) = entry?;
+        for v in values {
+            num_entries += 1;
+            total_key_size += k.value().len() as u64;
+            total_value_size += v?.value().len() as u64;
+        }
+    }
+    Ok((num_entries, total_key_size, total_value_size))
+}
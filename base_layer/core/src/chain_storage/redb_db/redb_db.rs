@@ -0,0 +1,2415 @@
+// Copyright 2024 The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    fmt,
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+    time::Instant,
+};
+
+use log::*;
+use primitive_types::U256;
+use redb::{Database, MultimapTableDefinition, ReadTransaction, ReadableTable, TableDefinition, WriteTransaction};
+use serde::{Deserialize, Serialize};
+use tari_common_types::{
+    chain_metadata::ChainMetadata,
+    epoch::VnEpoch,
+    types::{BlockHash, Commitment, FixedHash, HashOutput, PublicKey, Signature},
+};
+use tari_mmr::sparse_merkle_tree::{DeleteResult, NodeKey, ValueHash};
+use tari_utilities::{
+    hex::{to_hex, Hex},
+    ByteArray,
+};
+
+use super::redb::{
+    fetch_multimap_entry_sizes,
+    fetch_table_entry_sizes,
+    redb_clear,
+    redb_delete,
+    redb_delete_each_where,
+    redb_delete_key_value,
+    redb_delete_keys_starting_with,
+    redb_exists,
+    redb_fetch_batch_after,
+    redb_fetch_matching_after,
+    redb_fetch_range,
+    redb_filter_map_values,
+    redb_first_after,
+    redb_get,
+    redb_get_multiple,
+    redb_insert,
+    redb_insert_dup,
+    redb_last,
+    redb_len,
+    redb_replace,
+    MultimapTableDef,
+    RedbTransaction,
+    TableDef,
+};
+use crate::{
+    blocks::{
+        Block,
+        BlockAccumulatedData,
+        BlockHeader,
+        BlockHeaderAccumulatedData,
+        ChainBlock,
+        ChainHeader,
+        UpdateBlockAccumulatedData,
+    },
+    chain_storage::{
+        db_transaction::{DbKey, DbTransaction, DbValue, WriteOperation},
+        error::{ChainStorageError, OrNotFound},
+        lmdb_db::{
+            TransactionInputRowData,
+            TransactionInputRowDataRef,
+            TransactionKernelRowData,
+            TransactionOutputRowData,
+        },
+        stats::DbTotalSizeStats,
+        utxo_mined_info::OutputMinedInfo,
+        BlockchainBackend,
+        ChainTipData,
+        DbBasicStats,
+        DbSize,
+        HorizonData,
+        InputMinedInfo,
+        MmrTree,
+        OutputIndex,
+        OutputSmtDelta,
+        Reorg,
+        TemplateRegistrationEntry,
+        ValidatorNodeEntry,
+    },
+    consensus::{ConsensusConstants, ConsensusManager},
+    transactions::{
+        aggregated_body::AggregateBody,
+        transaction_components::{
+            OutputType,
+            SpentOutput,
+            TransactionInput,
+            TransactionKernel,
+            TransactionOutput,
+            ValidatorNodeRegistration,
+        },
+    },
+    OutputSmt,
+    PrunedKernelMmr,
+};
+
+const LOG_TARGET: &str = "c::cs::redb_db::redb_db";
+
+/// The name of the database file within the database directory
+pub const REDB_DATABASE_FILE: &str = "chain.redb";
+
+/// The number of outputs read at a time when indexing the outputs that are already stored
+const OUTPUT_INDEX_BACKFILL_BATCH_SIZE: usize = 1000;
+
+// Table names and value encodings match those of the LMDB backend, so that an LMDB database can be copied table by
+// table. Integer keys are stored big-endian instead of in native byte order.
+pub(super) const METADATA: TableDef = TableDefinition::new("metadata");
+pub(super) const HEADERS: TableDef = TableDefinition::new("headers");
+pub(super) const HEADER_ACCUMULATED_DATA: TableDef = TableDefinition::new("header_accumulated_data");
+pub(super) const BLOCK_ACCUMULATED_DATA: TableDef = TableDefinition::new("mmr_peak_data");
+pub(super) const BLOCK_HASHES: TableDef = TableDefinition::new("block_hashes");
+pub(super) const UTXOS: TableDef = TableDefinition::new("utxos");
+pub(super) const INPUTS: TableDef = TableDefinition::new("inputs");
+pub(super) const TXOS_HASH_TO_INDEX: TableDef = TableDefinition::new("txos_hash_to_index");
+pub(super) const KERNELS: TableDef = TableDefinition::new("kernels");
+pub(super) const KERNEL_EXCESS_INDEX: TableDef = TableDefinition::new("kernel_excess_index");
+pub(super) const KERNEL_EXCESS_SIG_INDEX: TableDef = TableDefinition::new("kernel_excess_sig_index");
+pub(super) const KERNEL_MMR_SIZE_INDEX: TableDef = TableDefinition::new("kernel_mmr_size_index");
+pub(super) const DELETED_TXO_HASH_TO_HEADER_INDEX: TableDef = TableDefinition::new("deleted_txo_hash_to_header_index");
+pub(super) const UTXO_COMMITMENT_INDEX: TableDef = TableDefinition::new("utxo_commitment_index");
+pub(super) const ORPHANS: TableDef = TableDefinition::new("orphans");
+pub(super) const MONERO_SEED_HEIGHT: TableDef = TableDefinition::new("monero_seed_height");
+pub(super) const ORPHAN_HEADER_ACCUMULATED_DATA: TableDef = TableDefinition::new("orphan_accumulated_data");
+pub(super) const ORPHAN_CHAIN_TIPS: TableDef = TableDefinition::new("orphan_chain_tips");
+pub(super) const ORPHAN_PARENT_MAP_INDEX: MultimapTableDef = MultimapTableDefinition::new("orphan_parent_map_index");
+pub(super) const BAD_BLOCK_LIST: TableDef = TableDefinition::new("bad_blocks");
+pub(super) const REORGS: TableDef = TableDefinition::new("reorgs");
+pub(super) const VALIDATOR_NODES: TableDef = TableDefinition::new("validator_nodes");
+pub(super) const VALIDATOR_NODES_MAPPING: TableDef = TableDefinition::new("validator_nodes_mapping");
+pub(super) const TEMPLATE_REGISTRATIONS: TableDef = TableDefinition::new("template_registrations");
+pub(super) const OUTPUT_INDEXES: TableDef = TableDefinition::new("output_indexes");
+pub(super) const OUTPUT_SMT_DELTAS: TableDef = TableDefinition::new("output_smt_deltas");
+
+/// All tables except `orphan_parent_map_index`, which is a multimap table
+pub(super) const ALL_TABLES: [TableDef; 25] = [
+    METADATA,
+    HEADERS,
+    HEADER_ACCUMULATED_DATA,
+    BLOCK_ACCUMULATED_DATA,
+    BLOCK_HASHES,
+    UTXOS,
+    INPUTS,
+    TXOS_HASH_TO_INDEX,
+    KERNELS,
+    KERNEL_EXCESS_INDEX,
+    KERNEL_EXCESS_SIG_INDEX,
+    KERNEL_MMR_SIZE_INDEX,
+    DELETED_TXO_HASH_TO_HEADER_INDEX,
+    UTXO_COMMITMENT_INDEX,
+    ORPHANS,
+    MONERO_SEED_HEIGHT,
+    ORPHAN_HEADER_ACCUMULATED_DATA,
+    ORPHAN_CHAIN_TIPS,
+    BAD_BLOCK_LIST,
+    REORGS,
+    VALIDATOR_NODES,
+    VALIDATOR_NODES_MAPPING,
+    TEMPLATE_REGISTRATIONS,
+    OUTPUT_INDEXES,
+    OUTPUT_SMT_DELTAS,
+];
+
+/// Creates or opens the redb database in the given directory
+pub fn create_redb_database<P: AsRef<Path>>(
+    path: P,
+    consensus_manager: ConsensusManager,
+) -> Result<RedbDatabase, ChainStorageError> {
+    let file = redb_database_file(path.as_ref());
+    debug!(target: LOG_TARGET, "Creating redb database at {}", file.display());
+    fs::create_dir_all(path)?;
+    let db = Database::create(&file)?;
+    debug!(target: LOG_TARGET, "redb database creation successful");
+    RedbDatabase::new(db, consensus_manager)
+}
+
+/// Returns the path of the database file in the given database directory
+pub fn redb_database_file(path: &Path) -> PathBuf {
+    path.join(REDB_DATABASE_FILE)
+}
+
+/// A blockchain database backed by [redb](https://docs.rs/redb), an embedded copy-on-write B-tree store.
+///
+/// Unlike LMDB, redb grows its file as required, so there is no map size to configure or resize. The file is locked
+/// while it is open, so only one process can use the database at a time.
+pub struct RedbDatabase {
+    db: Database,
+    consensus_manager: ConsensusManager,
+}
+
+impl RedbDatabase {
+    pub fn new(db: Database, consensus_manager: ConsensusManager) -> Result<Self, ChainStorageError> {
+        let db = Self { db, consensus_manager };
+        db.create_tables()?;
+        run_migrations(&db)?;
+        Ok(db)
+    }
+
+    pub(super) fn database(&self) -> &Database {
+        &self.db
+    }
+
+    fn read_transaction(&self) -> Result<ReadTransaction, ChainStorageError> {
+        Ok(self.db.begin_read()?)
+    }
+
+    fn write_transaction(&self) -> Result<WriteTransaction, ChainStorageError> {
+        Ok(self.db.begin_write()?)
+    }
+
+    /// Tables are created when first opened in a write transaction, so all tables are opened up front to allow reads
+    /// from an empty database.
+    fn create_tables(&self) -> Result<(), ChainStorageError> {
+        let txn = self.write_transaction()?;
+        for table in ALL_TABLES {
+            txn.open_table(table)?;
+        }
+        txn.open_multimap_table(ORPHAN_PARENT_MAP_INDEX)?;
+        txn.commit()?;
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_lines)]
+    fn apply_db_transaction(&mut self, txn: &DbTransaction) -> Result<(), ChainStorageError> {
+        #[allow(clippy::enum_glob_use)]
+        use WriteOperation::*;
+
+        let number_of_operations = txn.operations().len();
+        let write_txn = self.write_transaction()?;
+        for (i, op) in txn.operations().iter().enumerate() {
+            trace!(target: LOG_TARGET, "[apply_db_transaction] WriteOperation: {} ({} of {})", op, i + 1, number_of_operations);
+            match op {
+                InsertOrphanBlock(block) => self.insert_orphan_block(&write_txn, block)?,
+                InsertChainHeader { header } => {
+                    self.insert_header(&write_txn, header.header(), header.accumulated_data())?;
+                },
+                InsertTipBlockBody { block, smt } => {
+                    self.insert_tip_block_body(&write_txn, block.header(), block.block().body.clone(), smt.clone())?;
+                },
+                InsertKernel {
+                    header_hash,
+                    kernel,
+                    mmr_position,
+                } => {
+                    self.insert_kernel(&write_txn, header_hash, kernel, *mmr_position)?;
+                },
+                InsertOutput {
+                    header_hash,
+                    header_height,
+                    timestamp,
+                    output,
+                } => {
+                    self.insert_output(&write_txn, header_hash, *header_height, *timestamp, output)?;
+                },
+                DeleteHeader(height) => {
+                    self.delete_header(&write_txn, *height)?;
+                },
+                DeleteOrphan(hash) => {
+                    self.delete_orphan(&write_txn, hash)?;
+                },
+                DeleteOrphanChainTip(hash) => {
+                    redb_delete(&write_txn, ORPHAN_CHAIN_TIPS, hash.as_slice(), "orphan_chain_tips")?;
+                },
+                InsertOrphanChainTip(hash, total_accumulated_difficulty) => {
+                    redb_insert(
+                        &write_txn,
+                        ORPHAN_CHAIN_TIPS,
+                        hash.as_slice(),
+                        &ChainTipData {
+                            hash: *hash,
+                            total_accumulated_difficulty: *total_accumulated_difficulty,
+                        },
+                        "orphan_chain_tips",
+                    )?;
+                },
+                DeleteTipBlock(hash, smt) => {
+                    self.delete_tip_block_body(&write_txn, hash, smt.clone())?;
+                },
+                InsertMoneroSeedHeight(data, height) => {
+                    self.insert_monero_seed_height(&write_txn, data, *height)?;
+                },
+                SetAccumulatedDataForOrphan(accumulated_data) => {
+                    self.set_accumulated_data_for_orphan(&write_txn, accumulated_data)?;
+                },
+                InsertChainOrphanBlock(chain_block) => {
+                    self.insert_orphan_block(&write_txn, chain_block.block())?;
+                    self.set_accumulated_data_for_orphan(&write_txn, chain_block.accumulated_data())?;
+                },
+                UpdateBlockAccumulatedData { header_hash, values } => {
+                    self.update_block_accumulated_data(&write_txn, header_hash, values.clone())?;
+                },
+                PruneOutputsSpentAtHash { block_hash } => {
+                    self.prune_outputs_spent_at_hash(&write_txn, block_hash)?;
+                },
+                PruneOutputFromAllDbs {
+                    output_hash,
+                    commitment,
+                    output_type,
+                } => {
+                    self.prune_output_from_all_dbs(&write_txn, output_hash, commitment, *output_type)?;
+                },
+                DeleteAllKernelsInBlock { block_hash } => {
+                    self.delete_block_kernels(&write_txn, block_hash.as_slice())?;
+                    debug!(target: LOG_TARGET, "Deleted kernels in block {}", block_hash.to_hex());
+                },
+                DeleteAllInputsInBlock { block_hash } => {
+                    let inputs = redb_delete_keys_starting_with::<TransactionInputRowData>(
+                        &write_txn,
+                        INPUTS,
+                        block_hash.as_slice(),
+                    )?;
+                    debug!(target: LOG_TARGET, "Deleted {} input(s)", inputs.len());
+                },
+                SetBestBlock {
+                    height,
+                    hash,
+                    accumulated_difficulty,
+                    expected_prev_best_block,
+                    timestamp,
+                } => {
+                    // for security we check that the best block does exist, and we check the previous value
+                    // we dont want to check this if the prev block has never been set, this means a empty hash of 32
+                    // bytes.
+                    if *height > 0 {
+                        let prev = fetch_best_block(&write_txn)?;
+                        if *expected_prev_best_block != prev {
+                            return Err(ChainStorageError::InvalidOperation(format!(
+                                "There was a change in best_block, the best block is suppose to be: ({}), but it \
+                                 currently is: ({})",
+                                expected_prev_best_block.to_hex(),
+                                prev.to_hex(),
+                            )));
+                        };
+                    }
+                    if !redb_exists(&write_txn, BLOCK_HASHES, hash.as_slice())? {
+                        return Err(ChainStorageError::InvalidOperation(format!(
+                            "There is no Blockheader hash ({}) in db",
+                            expected_prev_best_block.to_hex(),
+                        )));
+                    };
+                    set_metadata(
+                        &write_txn,
+                        MetadataKey::ChainHeight,
+                        &MetadataValue::ChainHeight(*height),
+                    )?;
+                    set_metadata(&write_txn, MetadataKey::BestBlock, &MetadataValue::BestBlock(*hash))?;
+                    set_metadata(
+                        &write_txn,
+                        MetadataKey::AccumulatedWork,
+                        &MetadataValue::AccumulatedWork(*accumulated_difficulty),
+                    )?;
+                    set_metadata(
+                        &write_txn,
+                        MetadataKey::BestBlockTimestamp,
+                        &MetadataValue::BestBlockTimestamp(*timestamp),
+                    )?;
+                },
+                SetPruningHorizonConfig(pruning_horizon) => {
+                    set_metadata(
+                        &write_txn,
+                        MetadataKey::PruningHorizon,
+                        &MetadataValue::PruningHorizon(*pruning_horizon),
+                    )?;
+                },
+                SetPrunedHeight { height } => {
+                    set_metadata(
+                        &write_txn,
+                        MetadataKey::PrunedHeight,
+                        &MetadataValue::PrunedHeight(*height),
+                    )?;
+                },
+                SetHorizonData { horizon_data } => {
+                    set_metadata(
+                        &write_txn,
+                        MetadataKey::HorizonData,
+                        &MetadataValue::HorizonData(horizon_data.clone()),
+                    )?;
+                },
+                InsertBadBlock { hash, height, reason } => {
+                    self.insert_bad_block_and_cleanup(&write_txn, hash, *height, reason.to_string())?;
+                },
+                InsertReorg { reorg } => {
+                    redb_replace(&write_txn, REORGS, &reorg.local_time.timestamp().to_be_bytes(), &reorg)?;
+                },
+                ClearAllReorgs => {
+                    redb_clear(&write_txn, REORGS)?;
+                },
+                SetOutputIndexingConfig(enabled) => {
+                    self.set_output_indexing(&write_txn, *enabled)?;
+                },
+                SetArchivalConfig(enabled) => {
+                    set_metadata(&write_txn, MetadataKey::Archival, &MetadataValue::Archival(*enabled))?;
+                },
+            }
+        }
+        write_txn.commit()?;
+
+        Ok(())
+    }
+
+    fn insert_output(
+        &self,
+        txn: &WriteTransaction,
+        header_hash: &HashOutput,
+        header_height: u64,
+        header_timestamp: u64,
+        output: &TransactionOutput,
+    ) -> Result<(), ChainStorageError> {
+        let output_hash = output.hash();
+        let output_key = txo_key(header_hash, &output_hash);
+
+        if !output.is_burned() {
+            redb_insert(
+                txn,
+                UTXO_COMMITMENT_INDEX,
+                output.commitment.as_bytes(),
+                &output_hash,
+                "utxo_commitment_index",
+            )?;
+        }
+        redb_insert(
+            txn,
+            TXOS_HASH_TO_INDEX,
+            output_hash.as_slice(),
+            &output_key,
+            "txos_hash_to_index",
+        )?;
+        redb_insert(
+            txn,
+            UTXOS,
+            &output_key,
+            &TransactionOutputRowData {
+                output: output.clone(),
+                header_hash: *header_hash,
+                hash: output_hash,
+                mined_height: header_height,
+                mined_timestamp: header_timestamp,
+            },
+            "utxos",
+        )?;
+
+        if fetch_output_indexing(txn)? {
+            self.insert_output_indexes(txn, output, header_height, &output_hash)?;
+        }
+
+        Ok(())
+    }
+
+    fn insert_output_indexes(
+        &self,
+        txn: &WriteTransaction,
+        output: &TransactionOutput,
+        mined_height: u64,
+        output_hash: &HashOutput,
+    ) -> Result<(), ChainStorageError> {
+        for index in OutputIndex::all_for_output(output) {
+            redb_insert(
+                txn,
+                OUTPUT_INDEXES,
+                index.storage_key(mined_height, output_hash).as_slice(),
+                output_hash,
+                "output_indexes",
+            )?;
+        }
+        Ok(())
+    }
+
+    fn delete_output_indexes(
+        &self,
+        txn: &WriteTransaction,
+        output: &TransactionOutput,
+        mined_height: u64,
+        output_hash: &HashOutput,
+    ) -> Result<(), ChainStorageError> {
+        for index in OutputIndex::all_for_output(output) {
+            redb_delete(
+                txn,
+                OUTPUT_INDEXES,
+                index.storage_key(mined_height, output_hash).as_slice(),
+                "output_indexes",
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Enables or disables the output indexes. Enabling indexes all outputs that are already stored, disabling removes
+    /// all index entries. Indexes that were built for a different [OutputIndex::VERSION] are rebuilt.
+    fn set_output_indexing(&self, txn: &WriteTransaction, enabled: bool) -> Result<(), ChainStorageError> {
+        let is_enabled = fetch_output_indexing(txn)?;
+        if !enabled {
+            if is_enabled {
+                let num_deleted = redb_clear(txn, OUTPUT_INDEXES)?;
+                info!(target: LOG_TARGET, "Removed {} output index entries", num_deleted);
+                set_metadata(txn, MetadataKey::OutputIndexing, &MetadataValue::OutputIndexing(false))?;
+            }
+            return Ok(());
+        }
+
+        let version = fetch_output_index_version(txn)?;
+        if is_enabled {
+            if version == OutputIndex::VERSION {
+                return Ok(());
+            }
+            info!(
+                target: LOG_TARGET,
+                "Output indexes are at version {}, rebuilding them for version {}",
+                version,
+                OutputIndex::VERSION
+            );
+            redb_clear(txn, OUTPUT_INDEXES)?;
+        }
+
+        let timer = Instant::now();
+        let mut num_indexed = 0;
+        let mut last_key = None;
+        loop {
+            let rows = redb_fetch_batch_after::<_, TransactionOutputRowData>(
+                txn,
+                UTXOS,
+                last_key.as_deref(),
+                OUTPUT_INDEX_BACKFILL_BATCH_SIZE,
+            )?;
+            match rows.last() {
+                Some((key, _)) => last_key = Some(key.clone()),
+                None => break,
+            }
+            for (_, row) in &rows {
+                self.insert_output_indexes(txn, &row.output, row.mined_height, &row.hash)?;
+            }
+            num_indexed += rows.len();
+        }
+        info!(
+            target: LOG_TARGET,
+            "Indexed {} existing output(s) in {:.2?}",
+            num_indexed,
+            timer.elapsed()
+        );
+
+        set_metadata(
+            txn,
+            MetadataKey::OutputIndexVersion,
+            &MetadataValue::OutputIndexVersion(OutputIndex::VERSION),
+        )?;
+        set_metadata(txn, MetadataKey::OutputIndexing, &MetadataValue::OutputIndexing(true))
+    }
+
+    fn insert_kernel(
+        &self,
+        txn: &WriteTransaction,
+        header_hash: &HashOutput,
+        kernel: &TransactionKernel,
+        mmr_position: u64,
+    ) -> Result<(), ChainStorageError> {
+        let hash = kernel.hash();
+        let key = kernel_key(header_hash, mmr_position, &hash);
+
+        redb_insert(
+            txn,
+            KERNEL_EXCESS_INDEX,
+            kernel.excess.as_bytes(),
+            &(*header_hash, mmr_position, hash),
+            "kernel_excess_index",
+        )?;
+        redb_insert(
+            txn,
+            KERNEL_EXCESS_SIG_INDEX,
+            &excess_sig_key(&kernel.excess_sig),
+            &(*header_hash, mmr_position, hash),
+            "kernel_excess_sig_index",
+        )?;
+        redb_insert(
+            txn,
+            KERNELS,
+            &key,
+            &TransactionKernelRowData {
+                kernel: kernel.clone(),
+                header_hash: *header_hash,
+                mmr_position,
+                hash,
+            },
+            "kernels",
+        )
+    }
+
+    fn input_with_output_data(
+        &self,
+        txn: &WriteTransaction,
+        input: TransactionInput,
+    ) -> Result<TransactionInput, ChainStorageError> {
+        match input.spent_output {
+            SpentOutput::OutputData { .. } => Ok(input),
+            SpentOutput::OutputHash(output_hash) => {
+                let utxo_mined_info = self.fetch_output_in_txn(txn, output_hash.as_slice())?.ok_or_else(|| {
+                    error!(
+                        target: LOG_TARGET,
+                        "Could not retrieve output data from input's output_hash `{}`",
+                        output_hash.to_hex()
+                    );
+                    ChainStorageError::ValueNotFound {
+                        entity: "UTXO",
+                        field: "hash",
+                        value: output_hash.to_hex(),
+                    }
+                })?;
+                Ok(TransactionInput {
+                    version: input.version,
+                    spent_output: SpentOutput::create_from_output(utxo_mined_info.output),
+                    input_data: input.input_data,
+                    script_signature: input.script_signature,
+                })
+            },
+        }
+    }
+
+    fn insert_input(
+        &self,
+        txn: &WriteTransaction,
+        height: u64,
+        header_timestamp: u64,
+        header_hash: &HashOutput,
+        input: TransactionInput,
+    ) -> Result<(), ChainStorageError> {
+        let input_with_output_data = self.input_with_output_data(txn, input)?;
+        redb_delete(
+            txn,
+            UTXO_COMMITMENT_INDEX,
+            input_with_output_data.commitment()?.as_bytes(),
+            "utxo_commitment_index",
+        )?;
+
+        let hash = input_with_output_data.canonical_hash();
+        let output_hash = input_with_output_data.output_hash();
+        let key = txo_key(header_hash, &hash);
+        redb_insert(
+            txn,
+            DELETED_TXO_HASH_TO_HEADER_INDEX,
+            output_hash.as_slice(),
+            &key,
+            "deleted_txo_hash_to_header_index",
+        )?;
+        redb_insert(
+            txn,
+            INPUTS,
+            &key,
+            &TransactionInputRowDataRef {
+                input: &input_with_output_data.to_compact(),
+                header_hash,
+                spent_timestamp: header_timestamp,
+                spent_height: height,
+                hash: &hash,
+            },
+            "inputs",
+        )
+    }
+
+    fn insert_orphan_block(&self, txn: &WriteTransaction, block: &Block) -> Result<(), ChainStorageError> {
+        let k = block.hash();
+        redb_insert_dup(txn, ORPHAN_PARENT_MAP_INDEX, block.header.prev_hash.as_slice(), &k)?;
+        redb_insert(txn, ORPHANS, k.as_slice(), block, "orphans")
+    }
+
+    fn set_accumulated_data_for_orphan(
+        &self,
+        txn: &WriteTransaction,
+        accumulated_data: &BlockHeaderAccumulatedData,
+    ) -> Result<(), ChainStorageError> {
+        if !redb_exists(txn, ORPHANS, accumulated_data.hash.as_slice())? {
+            return Err(ChainStorageError::InvalidOperation(format!(
+                "set_accumulated_data_for_orphan: orphan {} does not exist",
+                accumulated_data.hash.to_hex()
+            )));
+        }
+
+        redb_insert(
+            txn,
+            ORPHAN_HEADER_ACCUMULATED_DATA,
+            accumulated_data.hash.as_slice(),
+            accumulated_data,
+            "orphan_accumulated_data",
+        )
+    }
+
+    /// Inserts the header and header accumulated data.
+    fn insert_header(
+        &self,
+        txn: &WriteTransaction,
+        header: &BlockHeader,
+        accum_data: &BlockHeaderAccumulatedData,
+    ) -> Result<(), ChainStorageError> {
+        let height_key = header.height.to_be_bytes();
+        if let Some(current_header_at_height) = redb_get::<_, BlockHeader>(txn, HEADERS, &height_key)? {
+            let hash = current_header_at_height.hash();
+            if hash != accum_data.hash {
+                return Err(ChainStorageError::InvalidOperation(format!(
+                    "There is a different header stored at height {} already. New header ({}), current header: ({})",
+                    header.height,
+                    accum_data.hash.to_hex(),
+                    hash.to_hex(),
+                )));
+            }
+            return Err(ChainStorageError::InvalidOperation(format!(
+                "The header at height {} already exists. Existing header hash: {}",
+                header.height,
+                hash.to_hex()
+            )));
+        }
+
+        // Check that the current height is still header.height - 1 and that no other threads have inserted
+        if let Some(last_header) = fetch_last_header_in_txn(txn)? {
+            if last_header.height != header.height.saturating_sub(1) {
+                return Err(ChainStorageError::InvalidOperation(format!(
+                    "Attempted to insert a header out of order. The last header height is {} but attempted to insert \
+                     a header with height {}",
+                    last_header.height, header.height,
+                )));
+            }
+
+            let hash = last_header.hash();
+            if hash != header.prev_hash {
+                return Err(ChainStorageError::InvalidOperation(format!(
+                    "Attempted to insert a block header at height {} that didn't form a chain. Previous block \
+                     hash:{}, new block's previous hash:{}",
+                    header.height,
+                    hash.to_hex(),
+                    header.prev_hash.to_hex()
+                )));
+            }
+        } else if header.height != 0 {
+            return Err(ChainStorageError::InvalidOperation(format!(
+                "The first header inserted must have height 0. Height provided: {}",
+                header.height
+            )));
+        } else {
+            // we can continue
+        }
+
+        redb_insert(
+            txn,
+            HEADER_ACCUMULATED_DATA,
+            &height_key,
+            accum_data,
+            "header_accumulated_data",
+        )?;
+        redb_insert(
+            txn,
+            BLOCK_HASHES,
+            header.hash().as_slice(),
+            &header.height,
+            "block_hashes",
+        )?;
+        redb_insert(txn, HEADERS, &height_key, header, "headers")?;
+        redb_insert(
+            txn,
+            KERNEL_MMR_SIZE_INDEX,
+            &header.kernel_mmr_size.to_be_bytes(),
+            &header.height,
+            "kernel_mmr_size_index",
+        )
+    }
+
+    fn delete_header(&self, txn: &WriteTransaction, height: u64) -> Result<(), ChainStorageError> {
+        if fetch_block_accumulated_data(txn, height)?.is_some() {
+            return Err(ChainStorageError::InvalidOperation(format!(
+                "Attempted to delete header at height {} while block accumulated data still exists",
+                height
+            )));
+        }
+
+        let header = fetch_last_header_in_txn(txn).or_not_found("BlockHeader", "height", "last_header".to_string())?;
+        if header.height != height {
+            return Err(ChainStorageError::InvalidOperation(format!(
+                "Attempted to delete a header at height {} that was not the last header (which is at height {}). \
+                 Headers must be deleted in reverse order.",
+                height, header.height
+            )));
+        }
+
+        let hash = header.hash();
+
+        // Check that there are no utxos or kernels linked to this.
+        if !redb_fetch_matching_after::<_, TransactionKernelRowData>(txn, KERNELS, hash.as_slice())?.is_empty() {
+            return Err(ChainStorageError::InvalidOperation(format!(
+                "Cannot delete header {} ({}) because there are kernels linked to it",
+                header.height,
+                hash.to_hex()
+            )));
+        }
+        if !redb_fetch_matching_after::<_, TransactionOutputRowData>(txn, UTXOS, hash.as_slice())?.is_empty() {
+            return Err(ChainStorageError::InvalidOperation(format!(
+                "Cannot delete header at height {} ({}) because there are UTXOs linked to it",
+                height,
+                hash.to_hex()
+            )));
+        }
+
+        let height_key = height.to_be_bytes();
+        redb_delete(txn, BLOCK_HASHES, hash.as_slice(), "block_hashes")?;
+        redb_delete(txn, HEADERS, &height_key, "headers")?;
+        redb_delete(txn, HEADER_ACCUMULATED_DATA, &height_key, "header_accumulated_data")?;
+        redb_delete(
+            txn,
+            KERNEL_MMR_SIZE_INDEX,
+            &header.kernel_mmr_size.to_be_bytes(),
+            "kernel_mmr_size_index",
+        )
+    }
+
+    fn delete_tip_block_body(
+        &self,
+        txn: &WriteTransaction,
+        block_hash: &HashOutput,
+        smt: Arc<RwLock<OutputSmt>>,
+    ) -> Result<(), ChainStorageError> {
+        let hash_hex = block_hash.to_hex();
+        debug!(target: LOG_TARGET, "Deleting block `{}`", hash_hex);
+        let height = fetch_height_from_hash(txn, block_hash).or_not_found("Block", "hash", hash_hex)?;
+        let prev_height = height.saturating_sub(1);
+        if fetch_block_accumulated_data(txn, height.saturating_add(1))?.is_some() {
+            return Err(ChainStorageError::InvalidOperation(format!(
+                "Attempted to delete block at height {} while next block still exists",
+                height
+            )));
+        }
+
+        redb_delete(txn, BLOCK_ACCUMULATED_DATA, &height.to_be_bytes(), "mmr_peak_data")?;
+
+        let mut output_smt = smt.write().map_err(|e| {
+            error!(
+                target: LOG_TARGET,
+                "delete_tip_block_body could not get a write lock on the smt. {:?}", e
+            );
+            ChainStorageError::AccessError("write lock on smt".into())
+        })?;
+
+        self.delete_block_inputs_outputs(txn, block_hash.as_slice(), &mut output_smt)?;
+        // The delta is deleted even if archival has since been disabled, so that a stale delta is never stored
+        if redb_exists(txn, OUTPUT_SMT_DELTAS, &height.to_be_bytes())? {
+            redb_delete(txn, OUTPUT_SMT_DELTAS, &height.to_be_bytes(), "output_smt_deltas")?;
+        }
+
+        let new_tip_header = fetch_chain_header_by_height(txn, prev_height)?;
+        let root = FixedHash::try_from(output_smt.hash().as_slice())?;
+        if root != new_tip_header.header().output_mr {
+            error!(
+                target: LOG_TARGET,
+                "Deleting block, new smt root(#{}) did not match expected (#{}) smt root",
+                root.to_hex(),
+                new_tip_header.header().output_mr.to_hex(),
+            );
+            return Err(ChainStorageError::InvalidOperation(
+                "Deleting block, new smt root did not match expected smt root".to_string(),
+            ));
+        }
+
+        self.delete_block_kernels(txn, block_hash.as_slice())
+    }
+
+    fn delete_block_inputs_outputs(
+        &self,
+        txn: &WriteTransaction,
+        block_hash: &[u8],
+        output_smt: &mut OutputSmt,
+    ) -> Result<(), ChainStorageError> {
+        let output_rows = redb_delete_keys_starting_with::<TransactionOutputRowData>(txn, UTXOS, block_hash)?;
+        debug!(target: LOG_TARGET, "Deleted {} outputs...", output_rows.len());
+        let inputs = redb_delete_keys_starting_with::<TransactionInputRowData>(txn, INPUTS, block_hash)?;
+        debug!(target: LOG_TARGET, "Deleted {} input(s)...", inputs.len());
+
+        let output_indexing = fetch_output_indexing(txn)?;
+        for utxo in &output_rows {
+            trace!(target: LOG_TARGET, "Deleting UTXO `{}`", utxo.hash);
+            redb_delete(txn, TXOS_HASH_TO_INDEX, utxo.hash.as_slice(), "txos_hash_to_index")?;
+            if output_indexing {
+                self.delete_output_indexes(txn, &utxo.output, utxo.mined_height, &utxo.hash)?;
+            }
+
+            let output_hash = utxo.output.hash();
+            // if an output was already spent in the block, it was never created as unspent, so dont delete it as it
+            // does not exist here
+            if inputs.iter().any(|r| r.input.output_hash() == output_hash) {
+                continue;
+            }
+            // if an output was burned, it was never created as an unspent utxo
+            if utxo.output.is_burned() {
+                continue;
+            }
+            let smt_key = NodeKey::try_from(utxo.output.commitment.as_bytes())?;
+            match output_smt.delete(&smt_key)? {
+                DeleteResult::Deleted(_value_hash) => {},
+                DeleteResult::KeyNotFound => {
+                    error!(
+                        target: LOG_TARGET,
+                        "Could not find input({}) in SMT",
+                        utxo.output.commitment.to_hex(),
+                    );
+                    return Err(ChainStorageError::UnspendableInput);
+                },
+            };
+            redb_delete(
+                txn,
+                UTXO_COMMITMENT_INDEX,
+                utxo.output.commitment.as_bytes(),
+                "utxo_commitment_index",
+            )?;
+        }
+        // Move inputs in this block back into the unspent set, any outputs spent within this block they will be removed
+        // by deleting all the block's outputs below
+        for row in inputs {
+            let output_hash = row.input.output_hash();
+            redb_delete(
+                txn,
+                DELETED_TXO_HASH_TO_HEADER_INDEX,
+                output_hash.as_slice(),
+                "deleted_txo_hash_to_header_index",
+            )?;
+            // If input spends an output in this block, don't add it to the utxo set
+            if output_rows.iter().any(|r| r.hash == output_hash) {
+                continue;
+            }
+
+            let mut input = row.input.clone();
+            let utxo_mined_info = self.fetch_output_in_txn(txn, output_hash.as_slice())?.ok_or_else(|| {
+                ChainStorageError::ValueNotFound {
+                    entity: "UTXO",
+                    field: "hash",
+                    value: output_hash.to_hex(),
+                }
+            })?;
+
+            let rp_hash = match utxo_mined_info.output.proof {
+                Some(proof) => proof.hash(),
+                None => FixedHash::zero(),
+            };
+            input.add_output_data(
+                utxo_mined_info.output.version,
+                utxo_mined_info.output.features,
+                utxo_mined_info.output.commitment,
+                utxo_mined_info.output.script,
+                utxo_mined_info.output.sender_offset_public_key,
+                utxo_mined_info.output.covenant,
+                utxo_mined_info.output.encrypted_data,
+                utxo_mined_info.output.metadata_signature,
+                rp_hash,
+                utxo_mined_info.output.minimum_value_promise,
+            );
+            let smt_key = NodeKey::try_from(input.commitment()?.as_bytes())?;
+            let smt_node = ValueHash::try_from(input.smt_hash(utxo_mined_info.mined_height).as_slice())?;
+            if let Err(e) = output_smt.insert(smt_key, smt_node) {
+                error!(
+                    target: LOG_TARGET,
+                    "Output commitment({}) already in SMT",
+                    input.commitment()?.to_hex(),
+                );
+                return Err(e.into());
+            }
+
+            trace!(target: LOG_TARGET, "Input moved to UTXO set: {}", input);
+            redb_insert(
+                txn,
+                UTXO_COMMITMENT_INDEX,
+                input.commitment()?.as_bytes(),
+                &input.output_hash(),
+                "utxo_commitment_index",
+            )?;
+        }
+        Ok(())
+    }
+
+    fn delete_block_kernels(&self, txn: &WriteTransaction, block_hash: &[u8]) -> Result<(), ChainStorageError> {
+        let kernels = redb_delete_keys_starting_with::<TransactionKernelRowData>(txn, KERNELS, block_hash)?;
+        debug!(target: LOG_TARGET, "Deleted {} kernels...", kernels.len());
+        for row in kernels {
+            trace!(target: LOG_TARGET, "Deleting excess `{}`", row.kernel.excess.to_hex());
+            redb_delete(
+                txn,
+                KERNEL_EXCESS_INDEX,
+                row.kernel.excess.as_bytes(),
+                "kernel_excess_index",
+            )?;
+            let excess_sig_key = excess_sig_key(&row.kernel.excess_sig);
+            trace!(
+                target: LOG_TARGET,
+                "Deleting excess signature `{}`",
+                to_hex(&excess_sig_key)
+            );
+            redb_delete(txn, KERNEL_EXCESS_SIG_INDEX, &excess_sig_key, "kernel_excess_sig_index")?;
+        }
+        Ok(())
+    }
+
+    fn delete_orphan(&self, txn: &WriteTransaction, hash: &HashOutput) -> Result<(), ChainStorageError> {
+        let orphan = match redb_get::<_, Block>(txn, ORPHANS, hash.as_slice())? {
+            Some(orphan) => orphan,
+            None => {
+                // delete_orphan is idempotent
+                debug!(
+                    target: LOG_TARGET,
+                    "delete_orphan: request to delete orphan block {} that was not found.",
+                    hash.to_hex()
+                );
+                return Ok(());
+            },
+        };
+
+        let parent_hash = orphan.header.prev_hash;
+        redb_delete_key_value(txn, ORPHAN_PARENT_MAP_INDEX, parent_hash.as_slice(), hash)?;
+
+        // Orphan is a tip hash
+        if redb_exists(txn, ORPHAN_CHAIN_TIPS, hash.as_slice())? {
+            // We get rid of the orphan tip
+            redb_delete(txn, ORPHAN_CHAIN_TIPS, hash.as_slice(), "orphan_chain_tips")?;
+            // If an orphan parent exists, it must be promoted
+            let parent_exists = redb_exists(txn, ORPHANS, parent_hash.as_slice())?;
+            let parent_accum =
+                redb_get::<_, BlockHeaderAccumulatedData>(txn, ORPHAN_HEADER_ACCUMULATED_DATA, parent_hash.as_slice())?;
+            match (parent_exists, parent_accum) {
+                (true, Some(accum)) => {
+                    // Parent becomes a tip hash
+                    redb_insert(
+                        txn,
+                        ORPHAN_CHAIN_TIPS,
+                        parent_hash.as_slice(),
+                        &ChainTipData {
+                            hash: parent_hash,
+                            total_accumulated_difficulty: accum.total_accumulated_difficulty,
+                        },
+                        "orphan_chain_tips",
+                    )?;
+                },
+                (false, None) => {
+                    // No entries, nothing here
+                },
+                (parent_exists, parent_accum) => {
+                    // Some previous database operations were not atomic
+                    warn!(
+                        target: LOG_TARGET,
+                        "'orphans' ({}) and 'orphan_accumulated_data' ({}) out of sync, missing parent hash '{}' entry",
+                        parent_exists,
+                        parent_accum.is_some(),
+                        parent_hash.to_hex()
+                    );
+                },
+            }
+        }
+
+        if redb_exists(txn, ORPHAN_HEADER_ACCUMULATED_DATA, hash.as_slice())? {
+            redb_delete(
+                txn,
+                ORPHAN_HEADER_ACCUMULATED_DATA,
+                hash.as_slice(),
+                "orphan_accumulated_data",
+            )?;
+        }
+        redb_delete(txn, ORPHANS, hash.as_slice(), "orphans")
+    }
+
+    #[allow(clippy::too_many_lines)]
+    fn insert_tip_block_body(
+        &self,
+        txn: &WriteTransaction,
+        header: &BlockHeader,
+        body: AggregateBody,
+        smt: Arc<RwLock<OutputSmt>>,
+    ) -> Result<(), ChainStorageError> {
+        let mut output_smt = smt.write().map_err(|e| {
+            error!(
+                target: LOG_TARGET,
+                "insert_tip_block_body could not get a write lock on the smt. {:?}", e
+            );
+            ChainStorageError::AccessError("write lock on smt".into())
+        })?;
+        if fetch_block_accumulated_data(txn, header.height + 1)?.is_some() {
+            return Err(ChainStorageError::InvalidOperation(format!(
+                "Attempted to insert block at height {} while next block already exists",
+                header.height
+            )));
+        }
+        let block_hash = header.hash();
+        debug!(
+            target: LOG_TARGET,
+            "Inserting block body for header `{}`: {}",
+            block_hash.to_hex(),
+            body.to_counts_string()
+        );
+
+        // Check that the header we are inserting for matches the header at that height
+        let current_header_at_height = redb_get::<_, BlockHeader>(txn, HEADERS, &header.height.to_be_bytes())
+            .or_not_found("BlockHeader", "height", header.height.to_string())?;
+        let hash = current_header_at_height.hash();
+        if hash != block_hash {
+            return Err(ChainStorageError::InvalidOperation(format!(
+                "Could not insert this block body because there is a different header stored at height {}. New header \
+                 ({}), current header: ({})",
+                header.height,
+                hash.to_hex(),
+                block_hash.to_hex()
+            )));
+        }
+
+        let (inputs, outputs, kernels) = body.dissolve();
+
+        let data = if header.height == 0 {
+            BlockAccumulatedData::default()
+        } else {
+            fetch_block_accumulated_data(txn, header.height - 1)?.ok_or_else(|| ChainStorageError::ValueNotFound {
+                entity: "BlockAccumulatedData",
+                field: "height",
+                value: (header.height - 1).to_string(),
+            })?
+        };
+
+        let mut total_kernel_sum = Commitment::default();
+        let mut kernel_mmr = PrunedKernelMmr::new(data.kernels);
+        let mut smt_delta = OutputSmtDelta::default();
+
+        for kernel in kernels {
+            total_kernel_sum = &total_kernel_sum + &kernel.excess;
+            let pos =
+                u64::try_from(kernel_mmr.push(kernel.hash().to_vec())?).map_err(|_| ChainStorageError::OutOfRange)?;
+            trace!(
+                target: LOG_TARGET,
+                "Inserting kernel `{}`",
+                kernel.excess_sig.get_signature().to_hex()
+            );
+            self.insert_kernel(txn, &block_hash, &kernel, pos)?;
+        }
+
+        for output in outputs {
+            trace!(
+                target: LOG_TARGET,
+                "Inserting output (`{}`, `{}`)",
+                output.commitment.to_hex(),
+                output.hash()
+            );
+            if !output.is_burned() {
+                let smt_key = NodeKey::try_from(output.commitment.as_bytes())?;
+                let smt_node = ValueHash::try_from(output.smt_hash(header.height).as_slice())?;
+                smt_delta.record_insert(&output.commitment, &smt_node)?;
+                if let Err(e) = output_smt.insert(smt_key, smt_node) {
+                    error!(
+                        target: LOG_TARGET,
+                        "Output commitment({}) already in SMT",
+                        output.commitment.to_hex(),
+                    );
+                    return Err(e.into());
+                }
+            }
+
+            let output_hash = output.hash();
+            if let Some(vn_reg) = output
+                .features
+                .sidechain_feature
+                .as_ref()
+                .and_then(|f| f.validator_node_registration())
+            {
+                self.insert_validator_node(txn, header, &output.commitment, vn_reg)?;
+            }
+            if let Some(template_reg) = output
+                .features
+                .sidechain_feature
+                .as_ref()
+                .and_then(|f| f.code_template_registration())
+            {
+                let record = TemplateRegistrationEntry {
+                    registration_data: template_reg.clone(),
+                    output_hash,
+                    block_height: header.height,
+                    block_hash,
+                };
+                redb_insert(
+                    txn,
+                    TEMPLATE_REGISTRATIONS,
+                    &template_registration_key(record.block_height, &record.output_hash),
+                    &record,
+                    "template_registrations",
+                )?;
+            }
+            self.insert_output(txn, &block_hash, header.height, header.timestamp().as_u64(), &output)?;
+        }
+
+        for input in inputs {
+            let input_with_output_data = self.input_with_output_data(txn, input)?;
+            let smt_key = NodeKey::try_from(input_with_output_data.commitment()?.as_bytes())?;
+            match output_smt.delete(&smt_key)? {
+                DeleteResult::Deleted(value_hash) => {
+                    smt_delta.record_delete(input_with_output_data.commitment()?, &value_hash)?;
+                },
+                DeleteResult::KeyNotFound => {
+                    error!(
+                        target: LOG_TARGET,
+                        "Could not find input({}) in SMT",
+                        input_with_output_data.commitment()?.to_hex(),
+                    );
+                    return Err(ChainStorageError::UnspendableInput);
+                },
+            };
+
+            let features = input_with_output_data.features()?;
+            if let Some(vn_reg) = features
+                .sidechain_feature
+                .as_ref()
+                .and_then(|f| f.validator_node_registration())
+            {
+                delete_validator_node(
+                    txn,
+                    header.height,
+                    vn_reg.public_key(),
+                    input_with_output_data.commitment()?,
+                )?;
+            }
+            trace!(
+                target: LOG_TARGET,
+                "Inserting input (`{}`, `{}`)",
+                input_with_output_data.commitment()?.to_hex(),
+                input_with_output_data.output_hash().to_hex()
+            );
+            self.insert_input(
+                txn,
+                current_header_at_height.height,
+                current_header_at_height.timestamp.as_u64(),
+                &block_hash,
+                input_with_output_data,
+            )?;
+        }
+
+        if fetch_archival(txn)? {
+            redb_replace(txn, OUTPUT_SMT_DELTAS, &header.height.to_be_bytes(), &smt_delta)?;
+        }
+        redb_insert(
+            txn,
+            BLOCK_ACCUMULATED_DATA,
+            &header.height.to_be_bytes(),
+            &BlockAccumulatedData::new(kernel_mmr.get_pruned_hash_set()?, total_kernel_sum),
+            "mmr_peak_data",
+        )
+    }
+
+    fn insert_validator_node(
+        &self,
+        txn: &WriteTransaction,
+        header: &BlockHeader,
+        commitment: &Commitment,
+        vn_reg: &ValidatorNodeRegistration,
+    ) -> Result<(), ChainStorageError> {
+        let constants = self.get_consensus_constants(header.height);
+        let current_epoch = constants.block_height_to_epoch(header.height);
+
+        let prev_shard_key = get_shard_key(
+            txn,
+            current_epoch
+                .as_u64()
+                .saturating_sub(constants.validator_node_validity_period_epochs().as_u64()) *
+                constants.epoch_length(),
+            current_epoch.as_u64() * constants.epoch_length(),
+            vn_reg.public_key(),
+        )?;
+        let shard_key = vn_reg.derive_shard_key(
+            prev_shard_key,
+            current_epoch,
+            constants.validator_node_registration_shuffle_interval(),
+            &header.prev_hash,
+        );
+
+        let next_epoch = constants.block_height_to_epoch(header.height) + VnEpoch(1);
+        let validator_node = ValidatorNodeEntry {
+            shard_key,
+            start_epoch: next_epoch,
+            end_epoch: next_epoch + constants.validator_node_validity_period_epochs(),
+            public_key: vn_reg.public_key().clone(),
+            commitment: commitment.clone(),
+        };
+
+        redb_insert(
+            txn,
+            VALIDATOR_NODES,
+            &[
+                header.height.to_be_bytes().as_slice(),
+                validator_node.public_key.as_bytes(),
+                validator_node.commitment.as_bytes(),
+            ]
+            .concat(),
+            &validator_node,
+            "validator_nodes",
+        )?;
+        redb_insert(
+            txn,
+            VALIDATOR_NODES_MAPPING,
+            &[
+                validator_node.public_key.as_bytes(),
+                header.height.to_be_bytes().as_slice(),
+                validator_node.commitment.as_bytes(),
+            ]
+            .concat(),
+            &validator_node.shard_key,
+            "validator_nodes_mapping",
+        )
+    }
+
+    fn update_block_accumulated_data(
+        &self,
+        txn: &WriteTransaction,
+        header_hash: &HashOutput,
+        values: UpdateBlockAccumulatedData,
+    ) -> Result<(), ChainStorageError> {
+        let height =
+            fetch_height_from_hash(txn, header_hash).or_not_found("BlockHash", "hash", header_hash.to_hex())?;
+        let mut block_accum_data = fetch_block_accumulated_data(txn, height)?.unwrap_or_default();
+
+        if let Some(kernel_sum) = values.kernel_sum {
+            block_accum_data.kernel_sum = kernel_sum;
+        }
+        if let Some(kernel_hash_set) = values.kernel_hash_set {
+            block_accum_data.kernels = kernel_hash_set;
+        }
+
+        redb_replace(txn, BLOCK_ACCUMULATED_DATA, &height.to_be_bytes(), &block_accum_data)
+    }
+
+    fn insert_monero_seed_height(
+        &self,
+        txn: &WriteTransaction,
+        seed: &[u8],
+        height: u64,
+    ) -> Result<(), ChainStorageError> {
+        let current_height = redb_get(txn, MONERO_SEED_HEIGHT, seed)?.unwrap_or(u64::MAX);
+        if height < current_height {
+            redb_replace(txn, MONERO_SEED_HEIGHT, seed, &height)?;
+        };
+        Ok(())
+    }
+
+    fn prune_outputs_spent_at_hash(
+        &self,
+        txn: &WriteTransaction,
+        block_hash: &HashOutput,
+    ) -> Result<(), ChainStorageError> {
+        let inputs = redb_fetch_matching_after::<_, TransactionInputRowData>(txn, INPUTS, block_hash.as_slice())?;
+        for input_data in inputs {
+            let input = input_data.input;
+            if let SpentOutput::OutputData { commitment, .. } = &input.spent_output {
+                debug!(target: LOG_TARGET, "Pruning output from 'utxo_commitment_index': key '{}'", commitment.to_hex());
+                redb_delete(
+                    txn,
+                    UTXO_COMMITMENT_INDEX,
+                    commitment.as_bytes(),
+                    "utxo_commitment_index",
+                )?;
+            }
+            let output_hash = input.output_hash();
+            if let Some(key) = redb_get::<_, Vec<u8>>(txn, TXOS_HASH_TO_INDEX, output_hash.as_slice())? {
+                self.prune_output(txn, &key)?;
+            }
+            debug!(
+                target: LOG_TARGET,
+                "Pruning output from 'txos_hash_to_index': key '{}'",
+                output_hash.to_hex()
+            );
+            redb_delete(txn, TXOS_HASH_TO_INDEX, output_hash.as_slice(), "txos_hash_to_index")?;
+        }
+
+        Ok(())
+    }
+
+    fn prune_output_from_all_dbs(
+        &self,
+        txn: &WriteTransaction,
+        output_hash: &HashOutput,
+        commitment: &Commitment,
+        output_type: OutputType,
+    ) -> Result<(), ChainStorageError> {
+        let key = redb_get::<_, Vec<u8>>(txn, TXOS_HASH_TO_INDEX, output_hash.as_slice())?
+            .ok_or_else(|| ChainStorageError::InvalidOperation("Output key not found".to_string()))?;
+        if !matches!(output_type, OutputType::Burn) {
+            debug!(target: LOG_TARGET, "Pruning output from 'utxo_commitment_index': key '{}'", commitment.to_hex());
+            redb_delete(
+                txn,
+                UTXO_COMMITMENT_INDEX,
+                commitment.as_bytes(),
+                "utxo_commitment_index",
+            )?;
+        }
+        debug!(target: LOG_TARGET, "Pruning output from 'txos_hash_to_index': key '{}'", output_hash.to_hex());
+        redb_delete(txn, TXOS_HASH_TO_INDEX, output_hash.as_slice(), "txos_hash_to_index")?;
+        self.prune_output(txn, &key)
+    }
+
+    /// Removes the output row at `key`, and its index entries if output indexing is enabled
+    fn prune_output(&self, txn: &WriteTransaction, key: &[u8]) -> Result<(), ChainStorageError> {
+        if fetch_output_indexing(txn)? {
+            if let Some(row) = redb_get::<_, TransactionOutputRowData>(txn, UTXOS, key)? {
+                debug!(target: LOG_TARGET, "Pruning output from 'output_indexes': output '{}'", row.hash);
+                self.delete_output_indexes(txn, &row.output, row.mined_height, &row.hash)?;
+            }
+        }
+        debug!(target: LOG_TARGET, "Pruning output from 'utxos': key '{}'", to_hex(key));
+        redb_delete(txn, UTXOS, key, "utxos")
+    }
+
+    fn insert_bad_block_and_cleanup(
+        &self,
+        txn: &WriteTransaction,
+        hash: &HashOutput,
+        height: u64,
+        reason: String,
+    ) -> Result<(), ChainStorageError> {
+        #[cfg(test)]
+        const CLEAN_BAD_BLOCKS_BEFORE_REL_HEIGHT: u64 = 10000;
+        #[cfg(not(test))]
+        const CLEAN_BAD_BLOCKS_BEFORE_REL_HEIGHT: u64 = 0;
+
+        redb_replace(txn, BAD_BLOCK_LIST, hash.as_slice(), &(height, reason))?;
+        // Clean up bad blocks that are far from the tip
+        let metadata = fetch_metadata(txn)?;
+        let deleted_before_height = metadata
+            .best_block_height()
+            .saturating_sub(CLEAN_BAD_BLOCKS_BEFORE_REL_HEIGHT);
+        if deleted_before_height == 0 {
+            return Ok(());
+        }
+
+        let num_deleted = redb_delete_each_where::<(u64, String), _>(txn, BAD_BLOCK_LIST, |_, (v, _)| {
+            Some(v < deleted_before_height)
+        })?;
+        debug!(target: LOG_TARGET, "Cleaned out {} stale bad blocks", num_deleted);
+
+        Ok(())
+    }
+
+    fn fetch_output_in_txn<T: RedbTransaction>(
+        &self,
+        txn: &T,
+        output_hash: &[u8],
+    ) -> Result<Option<OutputMinedInfo>, ChainStorageError> {
+        let key = match redb_get::<_, Vec<u8>>(txn, TXOS_HASH_TO_INDEX, output_hash)? {
+            Some(key) => key,
+            None => return Ok(None),
+        };
+        Ok(
+            redb_get::<_, TransactionOutputRowData>(txn, UTXOS, &key)?.map(|row| OutputMinedInfo {
+                output: row.output,
+                mined_height: row.mined_height,
+                header_hash: row.header_hash,
+                mined_timestamp: row.mined_timestamp,
+            }),
+        )
+    }
+
+    fn fetch_input_in_txn<T: RedbTransaction>(
+        &self,
+        txn: &T,
+        output_hash: &[u8],
+    ) -> Result<Option<InputMinedInfo>, ChainStorageError> {
+        let key = match redb_get::<_, Vec<u8>>(txn, DELETED_TXO_HASH_TO_HEADER_INDEX, output_hash)? {
+            Some(key) => key,
+            None => return Ok(None),
+        };
+        Ok(
+            redb_get::<_, TransactionInputRowData>(txn, INPUTS, &key)?.map(|row| InputMinedInfo {
+                input: row.input,
+                spent_height: row.spent_height,
+                header_hash: row.header_hash,
+                spent_timestamp: row.spent_timestamp,
+            }),
+        )
+    }
+
+    fn fetch_orphan_chain_header<T: RedbTransaction>(
+        &self,
+        txn: &T,
+        hash: &HashOutput,
+        function: &'static str,
+    ) -> Result<ChainHeader, ChainStorageError> {
+        let orphan: Block =
+            redb_get(txn, ORPHANS, hash.as_slice())?.ok_or_else(|| ChainStorageError::ValueNotFound {
+                entity: "Orphan",
+                field: "hash",
+                value: hash.to_hex(),
+            })?;
+        let accumulated_data = redb_get(txn, ORPHAN_HEADER_ACCUMULATED_DATA, hash.as_slice())?.ok_or_else(|| {
+            ChainStorageError::ValueNotFound {
+                entity: "Orphan accumulated data",
+                field: "hash",
+                value: hash.to_hex(),
+            }
+        })?;
+        let height = orphan.header.height;
+        ChainHeader::try_construct(orphan.header, accumulated_data).ok_or_else(|| {
+            ChainStorageError::DataInconsistencyDetected {
+                function,
+                details: format!("Accumulated data mismatch at height #{}", height),
+            }
+        })
+    }
+
+    fn get_consensus_constants(&self, height: u64) -> &ConsensusConstants {
+        self.consensus_manager.consensus_constants(height)
+    }
+}
+
+impl BlockchainBackend for RedbDatabase {
+    fn write(&mut self, txn: DbTransaction) -> Result<(), ChainStorageError> {
+        if txn.operations().is_empty() {
+            return Ok(());
+        }
+
+        let mark = Instant::now();
+        let num_operations = txn.operations().len();
+        match self.apply_db_transaction(&txn) {
+            Ok(()) => {
+                trace!(
+                    target: LOG_TARGET,
+                    "Database completed {} operation(s) in {:.0?}",
+                    num_operations,
+                    mark.elapsed()
+                );
+                Ok(())
+            },
+            Err(e) => {
+                error!(target: LOG_TARGET, "Failed to apply DB transaction: {:?}", e);
+                Err(e)
+            },
+        }
+    }
+
+    fn fetch(&self, key: &DbKey) -> Result<Option<DbValue>, ChainStorageError> {
+        let txn = self.read_transaction()?;
+        let res = match key {
+            DbKey::HeaderHeight(k) => redb_get::<_, BlockHeader>(&txn, HEADERS, &k.to_be_bytes())?
+                .map(|val| DbValue::HeaderHeight(Box::new(val))),
+            DbKey::HeaderHash(hash) => match fetch_height_from_hash(&txn, hash)? {
+                Some(k) => redb_get::<_, BlockHeader>(&txn, HEADERS, &k.to_be_bytes())?
+                    .map(|val| DbValue::HeaderHash(Box::new(val))),
+                None => None,
+            },
+            DbKey::OrphanBlock(k) => {
+                redb_get::<_, Block>(&txn, ORPHANS, k.as_slice())?.map(|val| DbValue::OrphanBlock(Box::new(val)))
+            },
+        };
+        Ok(res)
+    }
+
+    fn contains(&self, key: &DbKey) -> Result<bool, ChainStorageError> {
+        let txn = self.read_transaction()?;
+        match key {
+            DbKey::HeaderHeight(k) => redb_exists(&txn, HEADERS, &k.to_be_bytes()),
+            DbKey::HeaderHash(h) => redb_exists(&txn, BLOCK_HASHES, h.as_slice()),
+            DbKey::OrphanBlock(k) => redb_exists(&txn, ORPHANS, k.as_slice()),
+        }
+    }
+
+    fn fetch_chain_header_by_height(&self, height: u64) -> Result<ChainHeader, ChainStorageError> {
+        let txn = self.read_transaction()?;
+        fetch_chain_header_by_height(&txn, height)
+    }
+
+    fn fetch_header_accumulated_data(
+        &self,
+        hash: &HashOutput,
+    ) -> Result<Option<BlockHeaderAccumulatedData>, ChainStorageError> {
+        let txn = self.read_transaction()?;
+        match fetch_height_from_hash(&txn, hash)? {
+            Some(height) => redb_get(&txn, HEADER_ACCUMULATED_DATA, &height.to_be_bytes()),
+            None => Ok(None),
+        }
+    }
+
+    fn fetch_chain_header_in_all_chains(&self, hash: &HashOutput) -> Result<ChainHeader, ChainStorageError> {
+        let txn = self.read_transaction()?;
+        if let Some(height) = fetch_height_from_hash(&txn, hash)? {
+            return fetch_chain_header_by_height(&txn, height);
+        }
+
+        if redb_exists(&txn, ORPHAN_HEADER_ACCUMULATED_DATA, hash.as_slice())? {
+            return self.fetch_orphan_chain_header(&txn, hash, "fetch_chain_header_in_all_chains");
+        }
+
+        Err(ChainStorageError::ValueNotFound {
+            entity: "chain header (in chain_header_in_all_chains)",
+            field: "hash",
+            value: hash.to_hex(),
+        })
+    }
+
+    fn fetch_header_containing_kernel_mmr(&self, mmr_position: u64) -> Result<ChainHeader, ChainStorageError> {
+        let txn = self.read_transaction()?;
+        // The index returns the height at the position, so we have to offset the position by 1 so that the
+        // mmr_position arg is an index starting from 0
+        let mmr_position = mmr_position + 1;
+
+        let height =
+            redb_first_after::<_, u64>(&txn, KERNEL_MMR_SIZE_INDEX, &mmr_position.to_be_bytes())?.ok_or_else(|| {
+                ChainStorageError::ValueNotFound {
+                    entity: "kernel_mmr_size_index",
+                    field: "mmr_position",
+                    value: mmr_position.to_string(),
+                }
+            })?;
+        fetch_chain_header_by_height(&txn, height)
+    }
+
+    fn is_empty(&self) -> Result<bool, ChainStorageError> {
+        let txn = self.read_transaction()?;
+        Ok(redb_len(&txn, HEADERS)? == 0)
+    }
+
+    fn fetch_block_accumulated_data(
+        &self,
+        header_hash: &HashOutput,
+    ) -> Result<Option<BlockAccumulatedData>, ChainStorageError> {
+        let txn = self.read_transaction()?;
+        match fetch_height_from_hash(&txn, header_hash)? {
+            Some(height) => fetch_block_accumulated_data(&txn, height),
+            None => Ok(None),
+        }
+    }
+
+    fn fetch_block_accumulated_data_by_height(
+        &self,
+        height: u64,
+    ) -> Result<Option<BlockAccumulatedData>, ChainStorageError> {
+        let txn = self.read_transaction()?;
+        fetch_block_accumulated_data(&txn, height)
+    }
+
+    fn fetch_kernels_in_block(&self, header_hash: &HashOutput) -> Result<Vec<TransactionKernel>, ChainStorageError> {
+        let txn = self.read_transaction()?;
+        Ok(
+            redb_fetch_matching_after::<_, TransactionKernelRowData>(&txn, KERNELS, header_hash.as_slice())?
+                .into_iter()
+                .map(|row| row.kernel)
+                .collect(),
+        )
+    }
+
+    fn fetch_kernel_by_excess_sig(
+        &self,
+        excess_sig: &Signature,
+    ) -> Result<Option<(TransactionKernel, HashOutput)>, ChainStorageError> {
+        let txn = self.read_transaction()?;
+        match redb_get::<_, (HashOutput, u64, HashOutput)>(&txn, KERNEL_EXCESS_SIG_INDEX, &excess_sig_key(excess_sig))?
+        {
+            Some((header_hash, mmr_position, hash)) => Ok(redb_get::<_, TransactionKernelRowData>(
+                &txn,
+                KERNELS,
+                &kernel_key(&header_hash, mmr_position, &hash),
+            )?
+            .map(|row| (row.kernel, header_hash))),
+            None => Ok(None),
+        }
+    }
+
+    fn fetch_outputs_in_block_with_spend_state(
+        &self,
+        header_hash: &HashOutput,
+        spend_status_at_header: Option<&HashOutput>,
+    ) -> Result<Vec<(TransactionOutput, bool)>, ChainStorageError> {
+        let txn = self.read_transaction()?;
+        let mut outputs =
+            redb_fetch_matching_after::<_, TransactionOutputRowData>(&txn, UTXOS, header_hash.as_slice())?
+                .into_iter()
+                .map(|row| (row.output, false))
+                .collect::<Vec<_>>();
+        if let Some(header_hash) = spend_status_at_header {
+            let header_height =
+                fetch_height_from_hash(&txn, header_hash)?.ok_or_else(|| ChainStorageError::ValueNotFound {
+                    entity: "Header",
+                    field: "hash",
+                    value: header_hash.to_hex(),
+                })?;
+            for output in &mut outputs {
+                if let Some(input) = self.fetch_input_in_txn(&txn, output.0.hash().as_slice())? {
+                    if input.spent_height <= header_height {
+                        // we know its spend at the header height specified as optional in the fn
+                        output.1 = true;
+                    }
+                }
+            }
+        }
+
+        Ok(outputs)
+    }
+
+    fn fetch_output(&self, output_hash: &HashOutput) -> Result<Option<OutputMinedInfo>, ChainStorageError> {
+        let txn = self.read_transaction()?;
+        self.fetch_output_in_txn(&txn, output_hash.as_slice())
+    }
+
+    fn fetch_input(&self, output_hash: &HashOutput) -> Result<Option<InputMinedInfo>, ChainStorageError> {
+        let txn = self.read_transaction()?;
+        self.fetch_input_in_txn(&txn, output_hash.as_slice())
+    }
+
+    fn fetch_unspent_output_hash_by_commitment(
+        &self,
+        commitment: &Commitment,
+    ) -> Result<Option<HashOutput>, ChainStorageError> {
+        let txn = self.read_transaction()?;
+        redb_get(&txn, UTXO_COMMITMENT_INDEX, commitment.as_bytes())
+    }
+
+    fn fetch_outputs_in_block(&self, header_hash: &HashOutput) -> Result<Vec<TransactionOutput>, ChainStorageError> {
+        let txn = self.read_transaction()?;
+        Ok(
+            redb_fetch_matching_after::<_, TransactionOutputRowData>(&txn, UTXOS, header_hash.as_slice())?
+                .into_iter()
+                .map(|row| row.output)
+                .collect(),
+        )
+    }
+
+    fn fetch_inputs_in_block(&self, header_hash: &HashOutput) -> Result<Vec<TransactionInput>, ChainStorageError> {
+        let txn = self.read_transaction()?;
+        Ok(
+            redb_fetch_matching_after::<_, TransactionInputRowData>(&txn, INPUTS, header_hash.as_slice())?
+                .into_iter()
+                .map(|row| row.input)
+                .collect(),
+        )
+    }
+
+    fn fetch_mmr_size(&self, tree: MmrTree) -> Result<u64, ChainStorageError> {
+        let txn = self.read_transaction()?;
+        match tree {
+            MmrTree::Kernel => Ok(redb_len(&txn, KERNELS)? as u64),
+        }
+    }
+
+    fn orphan_count(&self) -> Result<usize, ChainStorageError> {
+        let txn = self.read_transaction()?;
+        redb_len(&txn, ORPHANS)
+    }
+
+    fn fetch_last_header(&self) -> Result<BlockHeader, ChainStorageError> {
+        let txn = self.read_transaction()?;
+        fetch_last_header_in_txn(&txn)?.ok_or_else(|| {
+            ChainStorageError::InvalidOperation("Cannot fetch last header because database is empty".to_string())
+        })
+    }
+
+    fn clear_all_pending_headers(&self) -> Result<usize, ChainStorageError> {
+        let txn = self.write_transaction()?;
+        let last_header = match fetch_last_header_in_txn(&txn)? {
+            Some(h) => h,
+            None => return Ok(0),
+        };
+        let metadata = fetch_metadata(&txn)?;
+        if metadata.best_block_height() == last_header.height {
+            return Ok(0);
+        }
+
+        let start = metadata.best_block_height() + 1;
+        let end = last_header.height;
+        let mut num_deleted = 0;
+        for h in (start..=end).rev() {
+            self.delete_header(&txn, h)?;
+            num_deleted += 1;
+        }
+        txn.commit()?;
+        Ok(num_deleted)
+    }
+
+    fn fetch_last_chain_header(&self) -> Result<ChainHeader, ChainStorageError> {
+        let txn = self.read_transaction()?;
+        let header = fetch_last_header_in_txn(&txn)?.ok_or_else(|| {
+            ChainStorageError::InvalidOperation("Cannot fetch last header because database is empty".to_string())
+        })?;
+        fetch_chain_header_by_height(&txn, header.height)
+    }
+
+    fn fetch_tip_header(&self) -> Result<ChainHeader, ChainStorageError> {
+        let txn = self.read_transaction()?;
+        let height = fetch_metadata(&txn)?.best_block_height();
+        fetch_chain_header_by_height(&txn, height)
+    }
+
+    fn fetch_chain_metadata(&self) -> Result<ChainMetadata, ChainStorageError> {
+        let txn = self.read_transaction()?;
+        fetch_metadata(&txn)
+    }
+
+    fn utxo_count(&self) -> Result<usize, ChainStorageError> {
+        let txn = self.read_transaction()?;
+        redb_len(&txn, UTXO_COMMITMENT_INDEX)
+    }
+
+    fn kernel_count(&self) -> Result<usize, ChainStorageError> {
+        let txn = self.read_transaction()?;
+        redb_len(&txn, KERNELS)
+    }
+
+    fn fetch_orphan_chain_tip_by_hash(&self, hash: &HashOutput) -> Result<Option<ChainHeader>, ChainStorageError> {
+        let txn = self.read_transaction()?;
+        if !redb_exists(&txn, ORPHAN_CHAIN_TIPS, hash.as_slice())? {
+            return Ok(None);
+        }
+        self.fetch_orphan_chain_header(&txn, hash, "fetch_orphan_chain_tip_by_hash")
+            .map(Some)
+    }
+
+    fn fetch_strongest_orphan_chain_tips(&self) -> Result<Vec<ChainHeader>, ChainStorageError> {
+        let txn = self.read_transaction()?;
+        let tips: Vec<ChainTipData> = redb_filter_map_values(&txn, ORPHAN_CHAIN_TIPS, Some)?;
+        let max_value = match tips.iter().map(|tip| tip.total_accumulated_difficulty).max() {
+            Some(val) => val,
+            None => return Ok(Vec::new()),
+        };
+
+        tips.iter()
+            .filter(|tip| tip.total_accumulated_difficulty == max_value)
+            .map(|tip| self.fetch_orphan_chain_header(&txn, &tip.hash, "fetch_strongest_orphan_chain_tips"))
+            .collect()
+    }
+
+    fn fetch_orphan_children_of(&self, parent_hash: HashOutput) -> Result<Vec<Block>, ChainStorageError> {
+        let txn = self.read_transaction()?;
+        let orphan_hashes: Vec<HashOutput> = redb_get_multiple(&txn, ORPHAN_PARENT_MAP_INDEX, parent_hash.as_slice())?;
+        orphan_hashes
+            .iter()
+            .map(|hash| {
+                redb_get(&txn, ORPHANS, hash.as_slice())?.ok_or_else(|| ChainStorageError::ValueNotFound {
+                    entity: "Orphan",
+                    field: "hash",
+                    value: hash.to_hex(),
+                })
+            })
+            .collect()
+    }
+
+    fn fetch_orphan_chain_block(&self, hash: HashOutput) -> Result<Option<ChainBlock>, ChainStorageError> {
+        let txn = self.read_transaction()?;
+        let block = match redb_get::<_, Block>(&txn, ORPHANS, hash.as_slice())? {
+            Some(block) => block,
+            None => return Ok(None),
+        };
+        match redb_get::<_, BlockHeaderAccumulatedData>(&txn, ORPHAN_HEADER_ACCUMULATED_DATA, hash.as_slice())? {
+            Some(accumulated_data) => {
+                let chain_block = ChainBlock::try_construct(Arc::new(block), accumulated_data).ok_or_else(|| {
+                    ChainStorageError::DataInconsistencyDetected {
+                        function: "fetch_orphan_chain_block",
+                        details: format!("Accumulated data mismatch for hash {}", hash.to_hex()),
+                    }
+                })?;
+                Ok(Some(chain_block))
+            },
+            None => Ok(None),
+        }
+    }
+
+    fn delete_oldest_orphans(
+        &mut self,
+        horizon_height: u64,
+        orphan_storage_capacity: usize,
+    ) -> Result<(), ChainStorageError> {
+        let orphan_count = self.orphan_count()?;
+        let num_over_limit = orphan_count.saturating_sub(orphan_storage_capacity);
+        if num_over_limit == 0 {
+            return Ok(());
+        }
+        debug!(
+            target: LOG_TARGET,
+            "Orphan block storage limit of {} reached, performing cleanup of {} entries.",
+            orphan_storage_capacity,
+            num_over_limit,
+        );
+
+        let mut orphans = {
+            let txn = self.read_transaction()?;
+            redb_filter_map_values(&txn, ORPHANS, |block: Block| Some((block.header.height, block.hash())))?
+        };
+
+        // Sort the orphans by age, oldest first
+        orphans.sort_by(|a, b| a.0.cmp(&b.0));
+        let mut txn = DbTransaction::new();
+        for (removed_count, (height, block_hash)) in orphans.into_iter().enumerate() {
+            if height > horizon_height && removed_count >= num_over_limit {
+                break;
+            }
+            debug!(
+                target: LOG_TARGET,
+                "Discarding orphan block #{} ({}).",
+                height,
+                block_hash.to_hex()
+            );
+            txn.delete_orphan(block_hash);
+        }
+        self.write(txn)
+    }
+
+    fn fetch_monero_seed_first_seen_height(&self, seed: &[u8]) -> Result<u64, ChainStorageError> {
+        let txn = self.read_transaction()?;
+        Ok(redb_get(&txn, MONERO_SEED_HEIGHT, seed)?.unwrap_or(0))
+    }
+
+    fn fetch_horizon_data(&self) -> Result<Option<HorizonData>, ChainStorageError> {
+        let txn = self.read_transaction()?;
+        match redb_get(&txn, METADATA, &MetadataKey::HorizonData.as_key())? {
+            Some(MetadataValue::HorizonData(data)) => Ok(Some(data)),
+            None => Err(ChainStorageError::ValueNotFound {
+                entity: "HorizonData",
+                field: "metadata",
+                value: "".to_string(),
+            }),
+            Some(k) => Err(ChainStorageError::DataInconsistencyDetected {
+                function: "fetch_horizon_data",
+                details: format!("Received incorrect value {:?} for key horizon data", k),
+            }),
+        }
+    }
+
+    fn get_stats(&self) -> Result<DbBasicStats, ChainStorageError> {
+        let sizes = self.fetch_total_size_stats()?;
+        Ok(DbBasicStats::from_entry_counts(
+            sizes.sizes().iter().map(|size| (size.name, size.num_entries as usize)),
+        ))
+    }
+
+    fn fetch_total_size_stats(&self) -> Result<DbTotalSizeStats, ChainStorageError> {
+        let txn = self.read_transaction()?;
+        let mut sizes = ALL_TABLES
+            .iter()
+            .map(|table| {
+                fetch_table_entry_sizes(&txn, *table).map(|(num_entries, total_key_size, total_value_size)| DbSize {
+                    name: table_name(table.name()),
+                    num_entries,
+                    total_key_size,
+                    total_value_size,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let (num_entries, total_key_size, total_value_size) =
+            fetch_multimap_entry_sizes(&txn, ORPHAN_PARENT_MAP_INDEX)?;
+        sizes.push(DbSize {
+            name: "orphan_parent_map_index",
+            num_entries,
+            total_key_size,
+            total_value_size,
+        });
+        Ok(sizes.into_iter().collect())
+    }
+
+    fn bad_block_exists(&self, block_hash: HashOutput) -> Result<(bool, String), ChainStorageError> {
+        let txn = self.read_transaction()?;
+        Ok(
+            match redb_get::<_, (u64, String)>(&txn, BAD_BLOCK_LIST, block_hash.as_slice())? {
+                Some((_height, reason)) => (true, reason),
+                None => (false, "".to_string()),
+            },
+        )
+    }
+
+    fn fetch_all_reorgs(&self) -> Result<Vec<Reorg>, ChainStorageError> {
+        let txn = self.read_transaction()?;
+        redb_filter_map_values(&txn, REORGS, Some)
+    }
+
+    fn fetch_active_validator_nodes(&self, height: u64) -> Result<Vec<(PublicKey, [u8; 32])>, ChainStorageError> {
+        let txn = self.read_transaction()?;
+        let constants = self.get_consensus_constants(height);
+
+        // Get the current epoch for the height
+        let end_epoch = constants.block_height_to_epoch(height);
+        // Subtract the registration validaty period to get the start epoch
+        let start_epoch = end_epoch.saturating_sub(constants.validator_node_validity_period_epochs());
+        // Convert these back to height as validators regs are indexed by height
+        let start_height = start_epoch.as_u64() * constants.epoch_length();
+        let end_height = end_epoch.as_u64() * constants.epoch_length();
+        get_vn_set(&txn, start_height, end_height)
+    }
+
+    fn get_shard_key(&self, height: u64, public_key: PublicKey) -> Result<Option<[u8; 32]>, ChainStorageError> {
+        let txn = self.read_transaction()?;
+        let constants = self.get_consensus_constants(height);
+
+        // Get the epoch height boundaries for our query
+        let current_epoch = constants.block_height_to_epoch(height);
+        let start_epoch = current_epoch.saturating_sub(constants.validator_node_validity_period_epochs());
+        let start_height = start_epoch.as_u64() * constants.epoch_length();
+        let end_height = current_epoch.as_u64() * constants.epoch_length();
+        get_shard_key(&txn, start_height, end_height, &public_key)
+    }
+
+    fn fetch_template_registrations(
+        &self,
+        start_height: u64,
+        end_height: u64,
+    ) -> Result<Vec<TemplateRegistrationEntry>, ChainStorageError> {
+        let txn = self.read_transaction()?;
+        redb_fetch_range(
+            &txn,
+            TEMPLATE_REGISTRATIONS,
+            &template_registration_key(start_height, &FixedHash::zero()),
+            &template_registration_key(end_height, &FixedHash::from([0xff; 32])),
+        )
+    }
+
+    fn fetch_outputs_by_index(
+        &self,
+        index: &OutputIndex,
+        start_height: u64,
+        end_height: u64,
+    ) -> Result<Vec<OutputMinedInfo>, ChainStorageError> {
+        let txn = self.read_transaction()?;
+        if !fetch_output_indexing(&txn)? {
+            return Err(ChainStorageError::InvalidOperation(
+                "Output indexing is not enabled".to_string(),
+            ));
+        }
+
+        let (start_key, end_key) = index.storage_key_range(start_height, end_height);
+        let output_hashes = redb_fetch_range::<_, HashOutput>(&txn, OUTPUT_INDEXES, &start_key, &end_key)?;
+        output_hashes
+            .iter()
+            .map(|output_hash| {
+                self.fetch_output_in_txn(&txn, output_hash.as_slice())?.ok_or_else(|| {
+                    ChainStorageError::DataInconsistencyDetected {
+                        function: "fetch_outputs_by_index",
+                        details: format!("Indexed output {} ({}) does not exist", output_hash, index),
+                    }
+                })
+            })
+            .collect()
+    }
+
+    fn fetch_output_smt_delta(&self, height: u64) -> Result<Option<OutputSmtDelta>, ChainStorageError> {
+        let txn = self.read_transaction()?;
+        redb_get(&txn, OUTPUT_SMT_DELTAS, &height.to_be_bytes())
+    }
+
+    fn calculate_tip_smt(&self) -> Result<OutputSmt, ChainStorageError> {
+        let start = Instant::now();
+        let metadata = self.fetch_chain_metadata()?;
+        let mut smt = OutputSmt::new();
+        for height in 0..=metadata.best_block_height() {
+            let header = self.fetch_chain_header_by_height(height)?;
+            let outputs =
+                self.fetch_outputs_in_block_with_spend_state(header.hash(), Some(metadata.best_block_hash()))?;
+            for (output, spent) in outputs {
+                if !spent && !output.is_burned() {
+                    let smt_key = NodeKey::try_from(output.commitment.as_bytes())?;
+                    let smt_node = ValueHash::try_from(output.smt_hash(header.height()).as_slice())?;
+                    if let Err(e) = smt.insert(smt_key, smt_node) {
+                        error!(
+                            target: LOG_TARGET,
+                            "Output commitment({}) already in SMT",
+                            output.commitment.to_hex(),
+                        );
+                        return Err(e.into());
+                    }
+                }
+            }
+        }
+        trace!(
+            target: LOG_TARGET,
+            "Finished calculating new smt (size: {}), took: {:.2?}",
+            smt.size(),
+            start.elapsed()
+        );
+        Ok(smt)
+    }
+}
+
+/// <header_hash, txo_hash>, used as the key of the `utxos` and `inputs` tables
+fn txo_key(header_hash: &HashOutput, txo_hash: &HashOutput) -> Vec<u8> {
+    [header_hash.as_slice(), txo_hash.as_slice()].concat()
+}
+
+/// <header_hash, mmr_position, kernel_hash>
+fn kernel_key(header_hash: &HashOutput, mmr_position: u64, hash: &HashOutput) -> Vec<u8> {
+    [
+        header_hash.as_slice(),
+        mmr_position.to_be_bytes().as_slice(),
+        hash.as_slice(),
+    ]
+    .concat()
+}
+
+/// <public_nonce, signature>
+fn excess_sig_key(excess_sig: &Signature) -> Vec<u8> {
+    [
+        excess_sig.get_public_nonce().as_bytes(),
+        excess_sig.get_signature().as_bytes(),
+    ]
+    .concat()
+}
+
+/// <block_height, output_hash>
+pub(super) fn template_registration_key(block_height: u64, output_hash: &HashOutput) -> Vec<u8> {
+    [block_height.to_be_bytes().as_slice(), output_hash.as_slice()].concat()
+}
+
+/// Maps a table name back to the static name used for stats
+fn table_name(name: &str) -> &'static str {
+    ALL_TABLES
+        .iter()
+        .map(|table| table.name())
+        .find(|n| *n == name)
+        .unwrap_or("unknown")
+}
+
+fn fetch_height_from_hash<T: RedbTransaction>(
+    txn: &T,
+    header_hash: &HashOutput,
+) -> Result<Option<u64>, ChainStorageError> {
+    redb_get(txn, BLOCK_HASHES, header_hash.as_slice())
+}
+
+fn fetch_block_accumulated_data<T: RedbTransaction>(
+    txn: &T,
+    height: u64,
+) -> Result<Option<BlockAccumulatedData>, ChainStorageError> {
+    redb_get(txn, BLOCK_ACCUMULATED_DATA, &height.to_be_bytes())
+}
+
+fn fetch_last_header_in_txn<T: RedbTransaction>(txn: &T) -> Result<Option<BlockHeader>, ChainStorageError> {
+    redb_last(txn, HEADERS)
+}
+
+fn fetch_chain_header_by_height<T: RedbTransaction>(txn: &T, height: u64) -> Result<ChainHeader, ChainStorageError> {
+    let header: BlockHeader =
+        redb_get(txn, HEADERS, &height.to_be_bytes())?.ok_or_else(|| ChainStorageError::ValueNotFound {
+            entity: "BlockHeader",
+            field: "height",
+            value: height.to_string(),
+        })?;
+    let accum_data = redb_get(txn, HEADER_ACCUMULATED_DATA, &height.to_be_bytes())?.ok_or_else(|| {
+        ChainStorageError::ValueNotFound {
+            entity: "BlockHeaderAccumulatedData",
+            field: "height",
+            value: height.to_string(),
+        }
+    })?;
+    ChainHeader::try_construct(header, accum_data).ok_or_else(|| ChainStorageError::DataInconsistencyDetected {
+        function: "fetch_chain_header_by_height",
+        details: format!("Mismatch in accumulated data at height #{}", height),
+    })
+}
+
+fn delete_validator_node(
+    txn: &WriteTransaction,
+    height: u64,
+    public_key: &PublicKey,
+    commitment: &Commitment,
+) -> Result<(), ChainStorageError> {
+    redb_delete(
+        txn,
+        VALIDATOR_NODES,
+        &[
+            height.to_be_bytes().as_slice(),
+            public_key.as_bytes(),
+            commitment.as_bytes(),
+        ]
+        .concat(),
+        "validator_nodes",
+    )?;
+    redb_delete(
+        txn,
+        VALIDATOR_NODES_MAPPING,
+        &[
+            public_key.as_bytes(),
+            height.to_be_bytes().as_slice(),
+            commitment.as_bytes(),
+        ]
+        .concat(),
+        "validator_nodes_mapping",
+    )
+}
+
+/// Returns a set of <public key, shard key> tuples of the validator nodes registered between the given heights,
+/// ordered by shard key. If a validator node registered more than once, only the last registration is included.
+fn get_vn_set<T: RedbTransaction>(
+    txn: &T,
+    start_height: u64,
+    end_height: u64,
+) -> Result<Vec<(PublicKey, [u8; 32])>, ChainStorageError> {
+    let table = txn.open(VALIDATOR_NODES)?;
+    let mut nodes = Vec::new();
+    // Public key does not mutate once compressed and will always produce the same hash
+    #[allow(clippy::mutable_key_type)]
+    let mut dedup_map = HashMap::new();
+    for entry in table.range::<&[u8]>(start_height.to_be_bytes().as_slice()..)? {
+        let (key, value) = entry?;
+        if height_from_key(&key.value()[0..8]) > end_height {
+            break;
+        }
+        let vn: ValidatorNodeEntry = super::redb::deserialize(value.value())?;
+        if let Some(dup_idx) = dedup_map.insert(vn.public_key.clone(), nodes.len()) {
+            // Remove duplicate registrations within the set without changing index order
+            nodes[dup_idx] = None;
+        }
+        nodes.push(Some((vn.public_key, vn.shard_key)));
+    }
+
+    let mut vn_set = nodes.into_iter().flatten().collect::<Vec<_>>();
+    vn_set.sort_by(|(_, a), (_, b)| a.cmp(b));
+    Ok(vn_set)
+}
+
+/// Returns the most recent shard key of the validator node registered between the given heights
+fn get_shard_key<T: RedbTransaction>(
+    txn: &T,
+    start_height: u64,
+    end_height: u64,
+    public_key: &PublicKey,
+) -> Result<Option<[u8; 32]>, ChainStorageError> {
+    let table = txn.open(VALIDATOR_NODES_MAPPING)?;
+    let start_key = [public_key.as_bytes(), start_height.to_be_bytes().as_slice()].concat();
+    let mut shard_key = None;
+    for entry in table.range::<&[u8]>(start_key.as_slice()..)? {
+        let (key, value) = entry?;
+        let key = key.value();
+        if key[0..32] != *public_key.as_bytes() || height_from_key(&key[32..40]) > end_height {
+            break;
+        }
+        shard_key = Some(super::redb::deserialize(value.value())?);
+    }
+    Ok(shard_key)
+}
+
+fn height_from_key(bytes: &[u8]) -> u64 {
+    let mut buf = [0u8; 8];
+    buf.copy_from_slice(bytes);
+    u64::from_be_bytes(buf)
+}
+
+fn set_metadata(txn: &WriteTransaction, k: MetadataKey, v: &MetadataValue) -> Result<(), ChainStorageError> {
+    redb_replace(txn, METADATA, &k.as_key(), v)
+}
+
+fn fetch_metadata<T: RedbTransaction>(txn: &T) -> Result<ChainMetadata, ChainStorageError> {
+    Ok(ChainMetadata::new(
+        fetch_chain_height(txn)?,
+        fetch_best_block(txn)?,
+        fetch_pruning_horizon(txn)?,
+        fetch_pruned_height(txn)?,
+        fetch_accumulated_work(txn)?,
+        fetch_best_block_timestamp(txn)?,
+    )?)
+}
+
+fn fetch_metadata_value<T: RedbTransaction>(
+    txn: &T,
+    k: MetadataKey,
+) -> Result<Option<MetadataValue>, ChainStorageError> {
+    redb_get(txn, METADATA, &k.as_key())
+}
+
+fn fetch_chain_height<T: RedbTransaction>(txn: &T) -> Result<u64, ChainStorageError> {
+    match fetch_metadata_value(txn, MetadataKey::ChainHeight)? {
+        Some(MetadataValue::ChainHeight(height)) => Ok(height),
+        _ => Err(ChainStorageError::ValueNotFound {
+            entity: "ChainMetadata",
+            field: "ChainHeight",
+            value: "".to_string(),
+        }),
+    }
+}
+
+fn fetch_pruned_height<T: RedbTransaction>(txn: &T) -> Result<u64, ChainStorageError> {
+    match fetch_metadata_value(txn, MetadataKey::PrunedHeight)? {
+        Some(MetadataValue::PrunedHeight(height)) => Ok(height),
+        _ => Ok(0),
+    }
+}
+
+fn fetch_best_block<T: RedbTransaction>(txn: &T) -> Result<BlockHash, ChainStorageError> {
+    match fetch_metadata_value(txn, MetadataKey::BestBlock)? {
+        Some(MetadataValue::BestBlock(best_block)) => Ok(best_block),
+        _ => Err(ChainStorageError::ValueNotFound {
+            entity: "ChainMetadata",
+            field: "BestBlock",
+            value: "".to_string(),
+        }),
+    }
+}
+
+fn fetch_best_block_timestamp<T: RedbTransaction>(txn: &T) -> Result<u64, ChainStorageError> {
+    match fetch_metadata_value(txn, MetadataKey::BestBlockTimestamp)? {
+        Some(MetadataValue::BestBlockTimestamp(timestamp)) => Ok(timestamp),
+        _ => Err(ChainStorageError::ValueNotFound {
+            entity: "ChainMetadata",
+            field: "BestBlockTimestamp",
+            value: "".to_string(),
+        }),
+    }
+}
+
+fn fetch_accumulated_work<T: RedbTransaction>(txn: &T) -> Result<U256, ChainStorageError> {
+    match fetch_metadata_value(txn, MetadataKey::AccumulatedWork)? {
+        Some(MetadataValue::AccumulatedWork(accumulated_difficulty)) => Ok(accumulated_difficulty),
+        _ => Err(ChainStorageError::ValueNotFound {
+            entity: "ChainMetadata",
+            field: "AccumulatedWork",
+            value: "".to_string(),
+        }),
+    }
+}
+
+fn fetch_pruning_horizon<T: RedbTransaction>(txn: &T) -> Result<u64, ChainStorageError> {
+    match fetch_metadata_value(txn, MetadataKey::PruningHorizon)? {
+        Some(MetadataValue::PruningHorizon(pruning_horizon)) => Ok(pruning_horizon),
+        _ => Ok(0),
+    }
+}
+
+fn fetch_output_indexing<T: RedbTransaction>(txn: &T) -> Result<bool, ChainStorageError> {
+    match fetch_metadata_value(txn, MetadataKey::OutputIndexing)? {
+        Some(MetadataValue::OutputIndexing(enabled)) => Ok(enabled),
+        _ => Ok(false),
+    }
+}
+
+fn fetch_archival<T: RedbTransaction>(txn: &T) -> Result<bool, ChainStorageError> {
+    match fetch_metadata_value(txn, MetadataKey::Archival)? {
+        Some(MetadataValue::Archival(enabled)) => Ok(enabled),
+        _ => Ok(false),
+    }
+}
+
+/// Indexes built before the index set was versioned are at version 0
+fn fetch_output_index_version<T: RedbTransaction>(txn: &T) -> Result<u32, ChainStorageError> {
+    match fetch_metadata_value(txn, MetadataKey::OutputIndexVersion)? {
+        Some(MetadataValue::OutputIndexVersion(version)) => Ok(version),
+        _ => Ok(0),
+    }
+}
+
+/// The metadata keys and values must match those of the LMDB backend, so that metadata can be migrated from LMDB.
+#[derive(Debug, Clone, PartialEq, Eq, Copy)]
+enum MetadataKey {
+    ChainHeight,
+    BestBlock,
+    AccumulatedWork,
+    PruningHorizon,
+    PrunedHeight,
+    HorizonData,
+    BestBlockTimestamp,
+    MigrationVersion,
+    OutputIndexing,
+    OutputIndexVersion,
+    Archival,
+}
+
+impl MetadataKey {
+    fn as_key(self) -> [u8; 4] {
+        (self as u32).to_be_bytes()
+    }
+}
+
+impl fmt::Display for MetadataKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MetadataKey::ChainHeight => write!(f, "Current chain height"),
+            MetadataKey::AccumulatedWork => write!(f, "Total accumulated work"),
+            MetadataKey::PruningHorizon => write!(f, "Pruning horizon"),
+            MetadataKey::PrunedHeight => write!(f, "Effective pruned height"),
+            MetadataKey::BestBlock => write!(f, "Chain tip block hash"),
+            MetadataKey::HorizonData => write!(f, "Database info"),
+            MetadataKey::BestBlockTimestamp => write!(f, "Chain tip block timestamp"),
+            MetadataKey::MigrationVersion => write!(f, "Migration version"),
+            MetadataKey::OutputIndexing => write!(f, "Output indexing"),
+            MetadataKey::OutputIndexVersion => write!(f, "Output index version"),
+            MetadataKey::Archival => write!(f, "Archival"),
+        }
+    }
+}
+
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
+enum MetadataValue {
+    ChainHeight(u64),
+    BestBlock(BlockHash),
+    AccumulatedWork(U256),
+    PruningHorizon(u64),
+    PrunedHeight(u64),
+    HorizonData(HorizonData),
+    BestBlockTimestamp(u64),
+    MigrationVersion(u64),
+    OutputIndexing(bool),
+    OutputIndexVersion(u32),
+    Archival(bool),
+}
+
+fn run_migrations(db: &RedbDatabase) -> Result<(), ChainStorageError> {
+    const MIGRATION_VERSION: u64 = 1;
+    let txn = db.read_transaction()?;
+    let n = match fetch_metadata_value(&txn, MetadataKey::MigrationVersion)? {
+        Some(MetadataValue::MigrationVersion(n)) => n,
+        Some(_) | None => 0,
+    };
+    info!(
+        target: LOG_TARGET,
+        "Blockchain database is at v{} (required version: {})", n, MIGRATION_VERSION
+    );
+    drop(txn);
+
+    if n < MIGRATION_VERSION {
+        // Add migrations here
+        info!(target: LOG_TARGET, "Migrated database to version {}", MIGRATION_VERSION);
+        let txn = db.write_transaction()?;
+        set_metadata(
+            &txn,
+            MetadataKey::MigrationVersion,
+            &MetadataValue::MigrationVersion(MIGRATION_VERSION),
+        )?;
+        txn.commit()?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,56 @@
+// Copyright 2024 The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+use std::io;
+
+use tari_mmr::{error::MerkleMountainRangeError, sparse_merkle_tree::SMTError};
+use thiserror::Error;
+
+use crate::{
+    chain_storage::ChainStorageError,
+    transactions::transaction_components::TransactionError,
+    validation::ValidationError,
+};
+
+#[derive(Debug, Error)]
+pub enum SnapshotError {
+    #[error("Chain storage error: {0}")]
+    ChainStorageError(#[from] ChainStorageError),
+    #[error("IO error: {0}")]
+    IoError(#[from] io::Error),
+    #[error("Serialization error: {0}")]
+    SerializationError(#[from] bincode::Error),
+    #[error("Snapshot checksum does not match its contents")]
+    ChecksumMismatch,
+    #[error("Unsupported snapshot version {0}")]
+    UnsupportedVersion(u32),
+    #[error("Snapshot was taken on a different network. Expected genesis block {expected}, got {actual}")]
+    GenesisMismatch { expected: String, actual: String },
+    #[error("Invalid snapshot height: {0}")]
+    InvalidHeight(String),
+    #[error("A snapshot can only be imported into an empty database, but the chain has {0} header(s)")]
+    DatabaseNotEmpty(u64),
+    #[error("The block at the snapshot height {0} was reorged out of the main chain during the export")]
+    TargetReorged(u64),
+    #[error("A snapshot can only be imported by a pruned node")]
+    NotPrunedNode,
+    #[error("Invalid snapshot data: {0}")]
+    InvalidData(String),
+    #[error(
+        "Merkle root did not match for {mr_tree} at height {at_height}. Expected {actual_hex} to equal {expected_hex}"
+    )]
+    InvalidMrRoot {
+        mr_tree: String,
+        at_height: u64,
+        expected_hex: String,
+        actual_hex: String,
+    },
+    #[error("An invalid transaction has been encountered: {0}")]
+    TransactionError(#[from] TransactionError),
+    #[error("Validation error: {0}")]
+    ValidationError(#[from] ValidationError),
+    #[error("MerkleMountainRangeError: {0}")]
+    MerkleMountainRangeError(#[from] MerkleMountainRangeError),
+    #[error("Sparse Merkle Tree error: {0}")]
+    SMTError(#[from] SMTError),
+}
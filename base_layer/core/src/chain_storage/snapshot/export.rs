@@ -0,0 +1,185 @@
+// Copyright 2024 The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+use std::{cmp, io::Write, ops::RangeInclusive, time::Instant};
+
+use log::*;
+use tari_common_types::chain_metadata::ChainMetadata;
+use tari_mmr::sparse_merkle_tree::{NodeKey, ValueHash};
+use tari_utilities::{
+    hex::{to_hex, Hex},
+    ByteArray,
+};
+
+use super::{
+    ChecksumWriter,
+    SnapshotBlock,
+    SnapshotError,
+    SnapshotMetadata,
+    SnapshotSummary,
+    SNAPSHOT_MAGIC,
+    SNAPSHOT_VERSION,
+};
+use crate::{
+    blocks::{BlockHeader, ChainHeader},
+    chain_storage::{BlockchainBackend, BlockchainDatabase},
+    transactions::transaction_components::TransactionOutput,
+    OutputSmt,
+};
+
+const LOG_TARGET: &str = "c::cs::snapshot::export";
+// The number of blocks to read from the database while holding the read lock
+const EXPORT_BATCH_SIZE: u64 = 100;
+
+/// Writes a snapshot of the chain at `height` to `writer`.
+///
+/// Blocks are read in batches and the database read lock is only held while a batch is read, so new blocks can be added
+/// during the export. Every output is exported with its spend state as of `height`, so the snapshot stays consistent as
+/// long as the block at `height` remains part of the main chain, which is checked for every batch. The snapshot is
+/// checked against the `output_mr` of the header at `height` before it is finalized.
+pub fn export_snapshot<B: BlockchainBackend, W: Write>(
+    db: &BlockchainDatabase<B>,
+    height: u64,
+    writer: W,
+) -> Result<SnapshotSummary, SnapshotError> {
+    let timer = Instant::now();
+    let (genesis, target, genesis_spent_outputs, mut output_smt) = {
+        let backend = db.db_read_access()?;
+        let metadata = backend.fetch_chain_metadata()?;
+        if height == 0 || height > metadata.best_block_height() {
+            return Err(SnapshotError::InvalidHeight(format!(
+                "{} is not between 1 and the tip height {}",
+                height,
+                metadata.best_block_height()
+            )));
+        }
+        check_not_pruned(&metadata, height)?;
+
+        let genesis = backend.fetch_chain_header_by_height(0)?;
+        let target = backend.fetch_chain_header_by_height(height)?;
+        let mut output_smt = OutputSmt::new();
+        let mut genesis_spent_outputs = Vec::new();
+        for (output, spent) in backend.fetch_outputs_in_block_with_spend_state(genesis.hash(), Some(target.hash()))? {
+            if spent {
+                genesis_spent_outputs.push(output.hash());
+            } else if !output.is_burned() {
+                insert_into_smt(&mut output_smt, &output, 0)?;
+            }
+        }
+        (genesis, target, genesis_spent_outputs, output_smt)
+    };
+
+    let mut writer = ChecksumWriter::new(writer);
+    writer.write_all(&SNAPSHOT_MAGIC)?;
+    bincode::serialize_into(&mut writer, &SNAPSHOT_VERSION)?;
+    bincode::serialize_into(&mut writer, &SnapshotMetadata {
+        genesis_hash: *genesis.hash(),
+        height,
+        best_block_hash: *target.hash(),
+        genesis_spent_outputs,
+    })?;
+
+    let mut num_kernels = 0u64;
+    let mut num_outputs = 0u64;
+    let mut start = 1;
+    while start <= height {
+        let end = cmp::min(start.saturating_add(EXPORT_BATCH_SIZE - 1), height);
+        for block in fetch_snapshot_blocks(db, &target, start..=end)? {
+            for output in &block.outputs {
+                insert_into_smt(&mut output_smt, output, block.header.height)?;
+            }
+            num_kernels += block.kernels.len() as u64;
+            num_outputs += block.outputs.len() as u64;
+            bincode::serialize_into(&mut writer, &block)?;
+        }
+        debug!(target: LOG_TARGET, "Exported {} of {} block(s)", end, height);
+        start = end + 1;
+    }
+
+    check_output_smt_root(&mut output_smt, target.header())?;
+    bincode::serialize_into(&mut writer, &output_smt)?;
+    let checksum = writer.finish()?;
+
+    info!(
+        target: LOG_TARGET,
+        "Exported snapshot at height {} with checksum {} in {:.2?}",
+        height,
+        checksum,
+        timer.elapsed()
+    );
+    Ok(SnapshotSummary {
+        height,
+        best_block_hash: *target.hash(),
+        num_kernels,
+        num_outputs,
+    })
+}
+
+/// Reads the blocks at `heights` with the outputs that are unspent at the `target` block, holding the read lock for the
+/// duration of the batch
+fn fetch_snapshot_blocks<B: BlockchainBackend>(
+    db: &BlockchainDatabase<B>,
+    target: &ChainHeader,
+    heights: RangeInclusive<u64>,
+) -> Result<Vec<SnapshotBlock>, SnapshotError> {
+    let backend = db.db_read_access()?;
+    check_not_pruned(&backend.fetch_chain_metadata()?, target.height())?;
+    if backend.fetch_chain_header_by_height(target.height())?.hash() != target.hash() {
+        return Err(SnapshotError::TargetReorged(target.height()));
+    }
+
+    heights
+        .map(|h| {
+            let chain_header = backend.fetch_chain_header_by_height(h)?;
+            let kernels = backend.fetch_kernels_in_block(chain_header.hash())?;
+            let outputs = backend
+                .fetch_outputs_in_block_with_spend_state(chain_header.hash(), Some(target.hash()))?
+                .into_iter()
+                .filter(|(output, spent)| !*spent && !output.is_burned())
+                .map(|(output, _)| output)
+                .collect();
+            let (header, accumulated_data) = chain_header.into_parts();
+            Ok(SnapshotBlock {
+                header,
+                accumulated_data,
+                kernels,
+                outputs,
+            })
+        })
+        .collect()
+}
+
+fn check_not_pruned(metadata: &ChainMetadata, height: u64) -> Result<(), SnapshotError> {
+    if height < metadata.pruned_height() {
+        return Err(SnapshotError::InvalidHeight(format!(
+            "{} is below the pruned height {} of this node",
+            height,
+            metadata.pruned_height()
+        )));
+    }
+    Ok(())
+}
+
+pub(super) fn insert_into_smt(
+    output_smt: &mut OutputSmt,
+    output: &TransactionOutput,
+    mined_height: u64,
+) -> Result<(), SnapshotError> {
+    let smt_key = NodeKey::try_from(output.commitment.as_bytes())?;
+    let smt_node = ValueHash::try_from(output.smt_hash(mined_height).as_slice())?;
+    output_smt.insert(smt_key, smt_node)?;
+    Ok(())
+}
+
+pub(super) fn check_output_smt_root(output_smt: &mut OutputSmt, header: &BlockHeader) -> Result<(), SnapshotError> {
+    let root = output_smt.hash();
+    if root.as_slice() != header.output_mr.as_slice() {
+        return Err(SnapshotError::InvalidMrRoot {
+            mr_tree: "UTXO SMT".to_string(),
+            at_height: header.height,
+            expected_hex: header.output_mr.to_hex(),
+            actual_hex: to_hex(root.as_slice()),
+        });
+    }
+    Ok(())
+}
@@ -0,0 +1,357 @@
+// Copyright 2024 The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+use std::{
+    collections::HashMap,
+    io::{BufReader, Read, Seek},
+    mem,
+    time::Instant,
+};
+
+use log::*;
+use tari_common_types::types::Commitment;
+use tari_crypto::commitment::HomomorphicCommitment;
+use tari_mmr::{pruned_hashset::PrunedHashSet, sparse_merkle_tree::NodeKey};
+use tari_utilities::{hex::Hex, ByteArray};
+
+use super::{
+    export::{check_output_smt_root, insert_into_smt},
+    verify_checksum,
+    SnapshotBlock,
+    SnapshotError,
+    SnapshotReader,
+    SnapshotSummary,
+};
+use crate::{
+    blocks::{ChainHeader, UpdateBlockAccumulatedData},
+    chain_storage::{BlockchainBackend, BlockchainDatabase, DbTransaction, MmrTree},
+    transactions::{
+        transaction_components::{TransactionKernel, TransactionOutput},
+        CryptoFactories,
+    },
+    validation::ChainBalanceValidator,
+    OutputSmt,
+    PrunedKernelMmr,
+};
+
+const LOG_TARGET: &str = "c::cs::snapshot::import";
+// The number of blocks to write to the database in a single transaction
+#[cfg(not(test))]
+const IMPORT_BATCH_SIZE: u64 = 100;
+#[cfg(test)]
+const IMPORT_BATCH_SIZE: u64 = 2;
+
+/// Imports a snapshot created with [export_snapshot](super::export_snapshot) into a pruned node's database that only
+/// contains the genesis block.
+///
+/// Blocks are written in batches and the chain tip is only moved to the snapshot tip once all of them have been
+/// written. If an import is interrupted, importing the same snapshot again resumes after the last block that was
+/// written.
+///
+/// The snapshot is fully validated before anything is written: the headers must link to the local genesis block, the
+/// kernels of each block must match the header's `kernel_mr`, the unspent outputs must match the `output_mr` of the
+/// snapshot tip and the chain must balance. Range proofs and header proof of work are not verified, so snapshots
+/// should only be imported from a trusted source.
+pub fn import_snapshot<B: BlockchainBackend, R: Read + Seek>(
+    db: &BlockchainDatabase<B>,
+    mut reader: R,
+    factories: CryptoFactories,
+) -> Result<SnapshotSummary, SnapshotError> {
+    let timer = Instant::now();
+    let metadata = db.get_chain_metadata()?;
+    if !metadata.is_pruned_node() {
+        return Err(SnapshotError::NotPrunedNode);
+    }
+    let resume_height = interrupted_import_height(db, metadata.best_block_height())?;
+
+    let body_len = verify_checksum(&mut reader)?;
+    let state = validate_snapshot(db, BufReader::new(&mut reader), body_len, factories)?;
+    info!(
+        target: LOG_TARGET,
+        "Snapshot at height {} is valid ({:.2?}). Importing.",
+        state.tip.height(),
+        timer.elapsed()
+    );
+    if resume_height > 0 {
+        info!(
+            target: LOG_TARGET,
+            "Resuming an interrupted import after height {}", resume_height
+        );
+    }
+
+    reader.rewind()?;
+    let mut snapshot = SnapshotReader::open(BufReader::new(&mut reader), body_len)?;
+    let mut txn = DbTransaction::new();
+    let mut summary = SnapshotSummary {
+        height: state.tip.height(),
+        best_block_hash: *state.tip.hash(),
+        num_kernels: 0,
+        num_outputs: 0,
+    };
+    read_blocks(db, &mut snapshot, |block| {
+        let header_hash = *block.header.hash();
+        let height = block.header.height();
+        let timestamp = block.header.timestamp();
+        summary.num_kernels += block.kernels.len() as u64;
+        summary.num_outputs += block.outputs.len() as u64;
+        if height <= resume_height {
+            if db.fetch_header(height)?.map(|header| header.hash()) != Some(header_hash) {
+                return Err(SnapshotError::InvalidData(format!(
+                    "The block imported at height {} by an earlier import does not match the snapshot",
+                    height
+                )));
+            }
+            return Ok(());
+        }
+
+        txn.insert_chain_header(block.header);
+        for (mmr_position, kernel) in (block.first_kernel_mmr_position..).zip(block.kernels) {
+            txn.insert_kernel(kernel, header_hash, mmr_position);
+        }
+        txn.update_block_accumulated_data(header_hash, UpdateBlockAccumulatedData {
+            kernel_hash_set: Some(block.kernel_hash_set),
+            ..Default::default()
+        });
+        for output in block.outputs {
+            txn.insert_utxo(output, header_hash, height, timestamp);
+        }
+
+        if height % IMPORT_BATCH_SIZE == 0 {
+            db.write(mem::take(&mut txn))?;
+            debug!(target: LOG_TARGET, "Imported {} of {} block(s)", height, summary.height);
+        }
+        Ok(())
+    })?;
+
+    for output in &state.genesis_spent_outputs {
+        txn.prune_output_from_all_dbs(output.hash(), output.commitment.clone(), output.features.output_type);
+    }
+    txn.set_best_block(
+        state.tip.height(),
+        *state.tip.hash(),
+        state.tip.accumulated_data().total_accumulated_difficulty,
+        *metadata.best_block_hash(),
+        state.tip.timestamp(),
+    )
+    .set_pruned_height(state.tip.height())
+    .set_horizon_data(state.kernel_sum, state.utxo_sum);
+    db.write(txn)?;
+    *db.smt_write_access()? = state.output_smt;
+
+    info!(
+        target: LOG_TARGET,
+        "Imported snapshot at height {} in {:.2?}",
+        summary.height,
+        timer.elapsed()
+    );
+    Ok(summary)
+}
+
+/// Returns the height of the last block written by an interrupted import, or 0 if there was no such import.
+///
+/// Each batch written by an import contains the headers and bodies of whole blocks, and the chain tip stays at the
+/// genesis block until the import completes. Headers downloaded by header sync have no bodies, so a database whose tip
+/// is the genesis block and whose last header has a body was left behind by an interrupted import.
+fn interrupted_import_height<B: BlockchainBackend>(
+    db: &BlockchainDatabase<B>,
+    best_block_height: u64,
+) -> Result<u64, SnapshotError> {
+    let last_header = db.fetch_last_header()?;
+    if last_header.height == 0 {
+        return Ok(0);
+    }
+    if best_block_height == 0 && !db.fetch_kernels_in_block(last_header.hash())?.is_empty() {
+        return Ok(last_header.height);
+    }
+    Err(SnapshotError::DatabaseNotEmpty(last_header.height + 1))
+}
+
+/// The chain state at the snapshot tip, as calculated while validating the snapshot
+struct SnapshotState {
+    tip: ChainHeader,
+    output_smt: OutputSmt,
+    utxo_sum: Commitment,
+    kernel_sum: Commitment,
+    genesis_spent_outputs: Vec<TransactionOutput>,
+}
+
+fn validate_snapshot<B: BlockchainBackend, R: Read>(
+    db: &BlockchainDatabase<B>,
+    reader: R,
+    body_len: u64,
+    factories: CryptoFactories,
+) -> Result<SnapshotState, SnapshotError> {
+    let mut snapshot = SnapshotReader::open(reader, body_len)?;
+    let genesis = db.fetch_chain_header(0)?;
+    if snapshot.metadata().genesis_hash != *genesis.hash() {
+        return Err(SnapshotError::GenesisMismatch {
+            expected: genesis.hash().to_hex(),
+            actual: snapshot.metadata().genesis_hash.to_hex(),
+        });
+    }
+
+    let mut utxo_sum = HomomorphicCommitment::default();
+    let mut kernel_sum = HomomorphicCommitment::default();
+    let mut burned_sum = HomomorphicCommitment::default();
+    for kernel in db.fetch_kernels_in_block(*genesis.hash())? {
+        kernel_sum = &kernel.excess + &kernel_sum;
+        if kernel.is_burned() {
+            burned_sum = kernel.get_burn_commitment()? + &burned_sum;
+        }
+    }
+
+    // Start from the genesis block outputs and remove the ones that are spent in the snapshot
+    let mut output_smt = db.smt_read_access()?.clone();
+    let mut genesis_outputs = db
+        .fetch_outputs_in_block(*genesis.hash())?
+        .into_iter()
+        .map(|output| (output.hash(), output))
+        .collect::<HashMap<_, _>>();
+    let mut genesis_spent_outputs = Vec::with_capacity(snapshot.metadata().genesis_spent_outputs.len());
+    for output_hash in &snapshot.metadata().genesis_spent_outputs {
+        let output = genesis_outputs.remove(output_hash).ok_or_else(|| {
+            SnapshotError::InvalidData(format!("Spent genesis output {} does not exist", output_hash))
+        })?;
+        output_smt.delete(&NodeKey::try_from(output.commitment.as_bytes())?)?;
+        genesis_spent_outputs.push(output);
+    }
+    for output in genesis_outputs.values().filter(|output| !output.is_burned()) {
+        utxo_sum = &output.commitment + &utxo_sum;
+    }
+
+    let tip = read_blocks(db, &mut snapshot, |block| {
+        for kernel in &block.kernels {
+            kernel.verify_signature()?;
+            kernel_sum = &kernel.excess + &kernel_sum;
+            if kernel.is_burned() {
+                burned_sum = kernel.get_burn_commitment()? + &burned_sum;
+            }
+        }
+        for output in &block.outputs {
+            if output.is_burned() {
+                return Err(SnapshotError::InvalidData(format!(
+                    "Burned output {} at height {} cannot be part of the unspent set",
+                    output.hash(),
+                    block.header.height()
+                )));
+            }
+            insert_into_smt(&mut output_smt, output, block.header.height())?;
+            utxo_sum = &output.commitment + &utxo_sum;
+        }
+        Ok(())
+    })?;
+
+    check_output_smt_root(&mut output_smt, tip.header())?;
+    let mut snapshot_smt = snapshot.read_output_smt()?;
+    if snapshot_smt.hash() != output_smt.hash() {
+        return Err(SnapshotError::InvalidData(
+            "The output SMT does not match the unspent outputs in the snapshot".to_string(),
+        ));
+    }
+
+    ChainBalanceValidator::<B>::new(db.rules().clone(), factories).validate_with_offset(
+        tip.height(),
+        &tip.accumulated_data().total_kernel_offset,
+        &utxo_sum,
+        &kernel_sum,
+        &burned_sum,
+    )?;
+
+    Ok(SnapshotState {
+        tip,
+        output_smt,
+        utxo_sum,
+        kernel_sum,
+        genesis_spent_outputs,
+    })
+}
+
+/// A block read from the snapshot that has been checked against its header
+struct ImportBlock {
+    header: ChainHeader,
+    kernels: Vec<TransactionKernel>,
+    outputs: Vec<TransactionOutput>,
+    first_kernel_mmr_position: u64,
+    kernel_hash_set: PrunedHashSet,
+}
+
+/// Reads all blocks in the snapshot, checking that they form a chain from the local genesis block and that their
+/// kernels match the kernel MMR roots, and passes each one to `f`. Returns the snapshot tip.
+fn read_blocks<B, R, F>(
+    db: &BlockchainDatabase<B>,
+    snapshot: &mut SnapshotReader<R>,
+    mut f: F,
+) -> Result<ChainHeader, SnapshotError>
+where
+    B: BlockchainBackend,
+    R: Read,
+    F: FnMut(ImportBlock) -> Result<(), SnapshotError>,
+{
+    let mut prev_header = db.fetch_chain_header(0)?;
+    let mut kernel_hash_set = db.fetch_block_accumulated_data(*prev_header.hash())?.dissolve();
+    let height = snapshot.metadata().height;
+    let best_block_hash = snapshot.metadata().best_block_hash;
+
+    for h in 1..=height {
+        let SnapshotBlock {
+            header,
+            accumulated_data,
+            kernels,
+            outputs,
+        } = snapshot.read_block()?;
+        if header.height != h || header.prev_hash != *prev_header.hash() {
+            return Err(SnapshotError::InvalidData(format!(
+                "Header at height {} does not link to the previous header",
+                h
+            )));
+        }
+        let header = ChainHeader::try_construct(header, accumulated_data).ok_or_else(|| {
+            SnapshotError::InvalidData(format!("Accumulated data at height {} does not match the header", h))
+        })?;
+
+        let first_kernel_mmr_position = prev_header.header().kernel_mmr_size;
+        if first_kernel_mmr_position + kernels.len() as u64 != header.header().kernel_mmr_size {
+            return Err(SnapshotError::InvalidData(format!(
+                "Expected {} kernel(s) at height {} but got {}",
+                header
+                    .header()
+                    .kernel_mmr_size
+                    .saturating_sub(first_kernel_mmr_position),
+                h,
+                kernels.len()
+            )));
+        }
+        let mut kernel_mmr = PrunedKernelMmr::new(kernel_hash_set);
+        for kernel in &kernels {
+            kernel_mmr.push(kernel.hash().to_vec())?;
+        }
+        let mmr_root = kernel_mmr.get_merkle_root()?;
+        if mmr_root.as_slice() != header.header().kernel_mr.as_slice() {
+            return Err(SnapshotError::InvalidMrRoot {
+                mr_tree: MmrTree::Kernel.to_string(),
+                at_height: h,
+                expected_hex: header.header().kernel_mr.to_hex(),
+                actual_hex: mmr_root.to_hex(),
+            });
+        }
+        kernel_hash_set = kernel_mmr.get_pruned_hash_set()?;
+
+        f(ImportBlock {
+            header: header.clone(),
+            kernels,
+            outputs,
+            first_kernel_mmr_position,
+            kernel_hash_set: kernel_hash_set.clone(),
+        })?;
+        prev_header = header;
+    }
+
+    if *prev_header.hash() != best_block_hash {
+        return Err(SnapshotError::InvalidData(format!(
+            "Snapshot tip {} does not match the expected hash {}",
+            prev_header.hash(),
+            best_block_hash
+        )));
+    }
+    Ok(prev_header)
+}
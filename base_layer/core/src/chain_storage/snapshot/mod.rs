@@ -0,0 +1,193 @@
+// Copyright 2024 The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+//! Chain snapshots allow a pruned node to be bootstrapped from a file instead of performing horizon sync against a
+//! peer.
+//!
+//! A snapshot taken at height `h` contains every header (with its accumulated data) and kernel up to `h`, the outputs
+//! that are unspent at `h` and the output SMT at `h`. The file layout is:
+//!
+//! ```text
+//! magic | version | SnapshotMetadata | SnapshotBlock (heights 1..=h) | OutputSmt | checksum
+//! ```
+//!
+//! All records are bincode encoded. The checksum is the Blake2b-256 hash of all preceding bytes.
+
+mod error;
+pub use error::SnapshotError;
+
+mod export;
+pub use export::export_snapshot;
+
+mod import;
+use std::{
+    fmt::{Display, Formatter},
+    io,
+    io::{Read, Seek, SeekFrom, Write},
+};
+
+use blake2::Blake2b;
+use digest::{consts::U32, Digest};
+pub use import::import_snapshot;
+use serde::{Deserialize, Serialize};
+use tari_common_types::types::{FixedHash, HashOutput};
+
+use crate::{
+    blocks::{BlockHeader, BlockHeaderAccumulatedData},
+    transactions::transaction_components::{TransactionKernel, TransactionOutput},
+    OutputSmt,
+};
+
+const SNAPSHOT_MAGIC: [u8; 8] = *b"TARISNAP";
+const SNAPSHOT_VERSION: u32 = 1;
+const CHECKSUM_SIZE: u64 = 32;
+
+/// Describes the chain state contained in a snapshot.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SnapshotMetadata {
+    /// The genesis block hash of the network the snapshot was taken on
+    genesis_hash: HashOutput,
+    /// The height of the snapshot
+    height: u64,
+    /// The hash of the block at the snapshot height
+    best_block_hash: HashOutput,
+    /// Genesis block outputs that were spent at the snapshot height. The genesis block is not part of the snapshot, so
+    /// these outputs are removed from the importing node's genesis block.
+    genesis_spent_outputs: Vec<HashOutput>,
+}
+
+/// A single block in the snapshot. Only the outputs that are still unspent at the snapshot height are included.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SnapshotBlock {
+    header: BlockHeader,
+    accumulated_data: BlockHeaderAccumulatedData,
+    kernels: Vec<TransactionKernel>,
+    outputs: Vec<TransactionOutput>,
+}
+
+/// A summary of an exported or imported snapshot
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SnapshotSummary {
+    pub height: u64,
+    pub best_block_hash: HashOutput,
+    pub num_kernels: u64,
+    pub num_outputs: u64,
+}
+
+impl Display for SnapshotSummary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Snapshot at height {} ({}): {} kernel(s), {} unspent output(s)",
+            self.height, self.best_block_hash, self.num_kernels, self.num_outputs
+        )
+    }
+}
+
+/// Writes to the inner writer while hashing everything that is written, so that the checksum can be appended once
+/// the snapshot is complete.
+struct ChecksumWriter<W> {
+    inner: W,
+    hasher: Blake2b<U32>,
+}
+
+impl<W: Write> ChecksumWriter<W> {
+    fn new(inner: W) -> Self {
+        Self {
+            inner,
+            hasher: Blake2b::<U32>::new(),
+        }
+    }
+
+    /// Appends the checksum and flushes the inner writer
+    fn finish(mut self) -> io::Result<FixedHash> {
+        let checksum = FixedHash::from(self.hasher.finalize());
+        self.inner.write_all(checksum.as_slice())?;
+        self.inner.flush()?;
+        Ok(checksum)
+    }
+}
+
+impl<W: Write> Write for ChecksumWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.hasher.update(&buf[..n]);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Checks the snapshot checksum and rewinds the reader to the start. Returns the length of the snapshot excluding the
+/// checksum.
+fn verify_checksum<R: Read + Seek>(reader: &mut R) -> Result<u64, SnapshotError> {
+    let len = reader.seek(SeekFrom::End(0))?;
+    let body_len = len
+        .checked_sub(CHECKSUM_SIZE)
+        .ok_or_else(|| SnapshotError::InvalidData("Snapshot file is too short".to_string()))?;
+    reader.seek(SeekFrom::Start(0))?;
+
+    let mut hasher = Blake2b::<U32>::new();
+    let mut body = reader.by_ref().take(body_len);
+    let mut buf = vec![0u8; 64 * 1024];
+    loop {
+        let n = body.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    let mut expected = [0u8; CHECKSUM_SIZE as usize];
+    reader.read_exact(&mut expected)?;
+    if hasher.finalize().as_slice() != expected.as_slice() {
+        return Err(SnapshotError::ChecksumMismatch);
+    }
+
+    reader.seek(SeekFrom::Start(0))?;
+    Ok(body_len)
+}
+
+/// Reads the records of a snapshot in order.
+struct SnapshotReader<R> {
+    reader: io::Take<R>,
+    metadata: SnapshotMetadata,
+}
+
+impl<R: Read> SnapshotReader<R> {
+    /// Reads the snapshot preamble. `body_len` is the length of the snapshot excluding the checksum.
+    fn open(reader: R, body_len: u64) -> Result<Self, SnapshotError> {
+        let mut reader = reader.take(body_len);
+        let mut magic = [0u8; SNAPSHOT_MAGIC.len()];
+        reader.read_exact(&mut magic)?;
+        if magic != SNAPSHOT_MAGIC {
+            return Err(SnapshotError::InvalidData("Not a snapshot file".to_string()));
+        }
+        let version: u32 = bincode::deserialize_from(&mut reader)?;
+        if version != SNAPSHOT_VERSION {
+            return Err(SnapshotError::UnsupportedVersion(version));
+        }
+        let metadata = bincode::deserialize_from(&mut reader)?;
+        Ok(Self { reader, metadata })
+    }
+
+    fn metadata(&self) -> &SnapshotMetadata {
+        &self.metadata
+    }
+
+    fn read_block(&mut self) -> Result<SnapshotBlock, SnapshotError> {
+        Ok(bincode::deserialize_from(&mut self.reader)?)
+    }
+
+    /// Reads the output SMT, which is the final record in the snapshot
+    fn read_output_smt(&mut self) -> Result<OutputSmt, SnapshotError> {
+        let smt = bincode::deserialize_from(&mut self.reader)?;
+        if self.reader.limit() != 0 {
+            return Err(SnapshotError::InvalidData(
+                "Unexpected data after the output SMT".to_string(),
+            ));
+        }
+        Ok(smt)
+    }
+}
@@ -47,6 +47,25 @@ impl DbBasicStats {
         }
     }
 
+    /// Creates stats for a backend that has no notion of pages or a memory map, only the number of entries per table.
+    pub(super) fn from_entry_counts<I: IntoIterator<Item = (&'static str, usize)>>(db_entries: I) -> Self {
+        let db_stats = db_entries
+            .into_iter()
+            .map(|(name, entries)| DbStat::from_entry_count(name, entries))
+            .collect::<Vec<_>>();
+        Self {
+            root: DbStat::from_entry_count("[root]", db_stats.len()),
+            env_info: EnvInfo {
+                mapsize: 0,
+                last_pgno: 0,
+                last_txnid: 0,
+                maxreaders: 0,
+                numreaders: 0,
+            },
+            db_stats,
+        }
+    }
+
     pub fn root(&self) -> &DbStat {
         &self.root
     }
@@ -90,6 +109,18 @@ pub struct DbStat {
 }
 
 impl DbStat {
+    fn from_entry_count(name: &'static str, entries: usize) -> Self {
+        Self {
+            name,
+            psize: 0,
+            depth: 0,
+            branch_pages: 0,
+            leaf_pages: 0,
+            overflow_pages: 0,
+            entries,
+        }
+    }
+
     /// Returns the total size in bytes of all pages
     pub fn total_page_size(&self) -> usize {
         self.psize as usize * (self.leaf_pages + self.branch_pages + self.overflow_pages)
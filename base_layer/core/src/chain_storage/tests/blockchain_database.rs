@@ -20,26 +20,16 @@
 // CAUSED AND ON ANY THEORY OF LIABILITY,  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR
 // OTHERWISE) ARISING IN ANY WAY OUT OF THE  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH
 // DAMAGE.
-use std::sync::Arc;
-
-use tari_common_types::tari_address::TariAddress;
-
+use super::{add_many_chained_blocks, create_next_block};
 use crate::{
-    blocks::{Block, BlockHeader, BlockHeaderAccumulatedData, ChainHeader, NewBlockTemplate},
+    blocks::{BlockHeader, BlockHeaderAccumulatedData, ChainHeader, NewBlockTemplate},
     chain_storage::{BlockchainDatabase, ChainStorageError},
     proof_of_work::{AchievedTargetDifficulty, Difficulty, PowAlgorithm},
     test_helpers::{
         blockchain::{create_new_blockchain, TempDatabase},
-        create_block,
         default_coinbase_entities,
-        BlockSpec,
-    },
-    transactions::{
-        key_manager::{MemoryDbKeyManager, TariKeyId},
-        tari_amount::T,
-        test_helpers::schema_to_transaction,
-        transaction_components::{Transaction, WalletOutput},
     },
+    transactions::{tari_amount::T, test_helpers::schema_to_transaction},
     txn_schema,
 };
 
@@ -47,72 +37,6 @@ fn setup() -> BlockchainDatabase<TempDatabase> {
     create_new_blockchain()
 }
 
-async fn create_next_block(
-    db: &BlockchainDatabase<TempDatabase>,
-    prev_block: &Block,
-    transactions: Vec<Arc<Transaction>>,
-    key_manager: &MemoryDbKeyManager,
-    script_key_id: &TariKeyId,
-    wallet_payment_address: &TariAddress,
-) -> (Arc<Block>, WalletOutput) {
-    let rules = db.rules();
-    let (block, output) = create_block(
-        rules,
-        prev_block,
-        BlockSpec::new()
-            .with_transactions(transactions.into_iter().map(|t| (*t).clone()).collect())
-            .finish(),
-        key_manager,
-        script_key_id,
-        wallet_payment_address,
-        None,
-    )
-    .await;
-    let block = apply_mmr_to_block(db, block);
-    (Arc::new(block), output)
-}
-
-fn apply_mmr_to_block(db: &BlockchainDatabase<TempDatabase>, block: Block) -> Block {
-    let (mut block, mmr_roots) = db.calculate_mmr_roots(block).unwrap();
-    block.header.input_mr = mmr_roots.input_mr;
-    block.header.output_mr = mmr_roots.output_mr;
-    block.header.output_smt_size = mmr_roots.output_smt_size;
-    block.header.kernel_mr = mmr_roots.kernel_mr;
-    block.header.kernel_mmr_size = mmr_roots.kernel_mmr_size;
-    block.header.validator_node_mr = mmr_roots.validator_node_mr;
-    block.header.validator_node_size = mmr_roots.validator_node_size;
-    block
-}
-
-async fn add_many_chained_blocks(
-    size: usize,
-    db: &BlockchainDatabase<TempDatabase>,
-    key_manager: &MemoryDbKeyManager,
-) -> (Vec<Arc<Block>>, Vec<WalletOutput>) {
-    let last_header = db.fetch_last_header().unwrap();
-    let mut prev_block = Arc::new(db.fetch_block(last_header.height, true).unwrap().into_block());
-    let mut blocks = Vec::with_capacity(size);
-    let mut outputs = Vec::with_capacity(size);
-    let (script_key_id, wallet_payment_address) = default_coinbase_entities(key_manager).await;
-    for _ in 1..=size {
-        let (block, coinbase_utxo) = create_next_block(
-            db,
-            &prev_block,
-            vec![],
-            key_manager,
-            &script_key_id,
-            &wallet_payment_address,
-        )
-        .await;
-
-        db.add_block(block.clone()).unwrap().assert_added();
-        prev_block = block.clone();
-        blocks.push(block);
-        outputs.push(coinbase_utxo);
-    }
-    (blocks, outputs)
-}
-
 mod fetch_blocks {
     use super::*;
     use crate::transactions::key_manager::create_memory_db_key_manager;
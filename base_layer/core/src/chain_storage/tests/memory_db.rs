@@ -0,0 +1,70 @@
+//  Copyright 2024, The Tari Project
+//
+//  Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+//  following conditions are met:
+//
+//  1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+//  disclaimer.
+//
+//  2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+//  following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+//  3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+//  products derived from this software without specific prior written permission.
+//
+//  THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+//  INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+//  DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+//  SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+//  SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+//  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+//  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use super::{add_many_chained_blocks, assert_blocks_stored};
+use crate::{
+    chain_storage::DbTransaction,
+    test_helpers::blockchain::create_new_memory_blockchain,
+    transactions::key_manager::create_memory_db_key_manager,
+};
+
+mod write {
+    use super::*;
+
+    #[tokio::test]
+    async fn it_leaves_the_db_unchanged_if_an_operation_fails() {
+        let db = create_new_memory_blockchain();
+        let key_manager = create_memory_db_key_manager().unwrap();
+        add_many_chained_blocks(1, &db, &key_manager).await;
+        let metadata = db.get_chain_metadata().unwrap();
+
+        let mut txn = DbTransaction::new();
+        txn.clear_all_reorgs();
+        // Deleting the genesis header is invalid while block 1 still exists
+        txn.delete_header(0);
+        assert!(db.commit(txn).is_err());
+        assert_eq!(db.get_chain_metadata().unwrap(), metadata);
+        assert_eq!(db.fetch_last_header().unwrap().height, 1);
+    }
+
+    #[tokio::test]
+    async fn it_reverts_changes_to_the_output_smt_if_an_operation_fails() {
+        let db = create_new_memory_blockchain();
+        let key_manager = create_memory_db_key_manager().unwrap();
+        let (blocks, _) = add_many_chained_blocks(2, &db, &key_manager).await;
+        let smt_root = db.smt_read_access().unwrap().clone().hash().clone();
+        assert_eq!(smt_root.as_slice(), blocks[1].header.output_mr.as_slice());
+
+        let mut txn = DbTransaction::new();
+        // Deleting the tip block removes its outputs from the SMT before the invalid header deletion fails
+        txn.delete_tip_block(blocks[1].hash(), db.smt());
+        txn.delete_header(0);
+        assert!(db.commit(txn).is_err());
+
+        assert_eq!(db.fetch_tip_header().unwrap().hash(), &blocks[1].hash());
+        assert_blocks_stored(&db, &blocks);
+        assert_eq!(db.smt_read_access().unwrap().clone().hash(), &smt_root);
+        // The chain can still be extended from the tip, which requires the SMT to match the tip
+        let (blocks, _) = add_many_chained_blocks(1, &db, &key_manager).await;
+        assert_eq!(db.fetch_tip_header().unwrap().hash(), &blocks[0].hash());
+    }
+}
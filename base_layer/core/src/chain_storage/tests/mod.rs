@@ -20,5 +20,109 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+use std::sync::Arc;
+
+use tari_common_types::tari_address::TariAddress;
+
+use crate::{
+    blocks::Block,
+    chain_storage::{BlockchainBackend, BlockchainDatabase},
+    test_helpers::{create_block, default_coinbase_entities, BlockSpec},
+    transactions::{
+        key_manager::{MemoryDbKeyManager, TariKeyId},
+        transaction_components::{Transaction, WalletOutput},
+    },
+};
+
 mod blockchain_database;
+mod memory_db;
+#[cfg(feature = "redb")]
+mod redb_db;
 pub mod temp_db;
+
+async fn create_next_block<B: BlockchainBackend>(
+    db: &BlockchainDatabase<B>,
+    prev_block: &Block,
+    transactions: Vec<Arc<Transaction>>,
+    key_manager: &MemoryDbKeyManager,
+    script_key_id: &TariKeyId,
+    wallet_payment_address: &TariAddress,
+) -> (Arc<Block>, WalletOutput) {
+    let rules = db.rules();
+    let (block, output) = create_block(
+        rules,
+        prev_block,
+        BlockSpec::new()
+            .with_transactions(transactions.into_iter().map(|t| (*t).clone()).collect())
+            .finish(),
+        key_manager,
+        script_key_id,
+        wallet_payment_address,
+        None,
+    )
+    .await;
+    let block = apply_mmr_to_block(db, block);
+    (Arc::new(block), output)
+}
+
+fn apply_mmr_to_block<B: BlockchainBackend>(db: &BlockchainDatabase<B>, block: Block) -> Block {
+    let (mut block, mmr_roots) = db.calculate_mmr_roots(block).unwrap();
+    block.header.input_mr = mmr_roots.input_mr;
+    block.header.output_mr = mmr_roots.output_mr;
+    block.header.output_smt_size = mmr_roots.output_smt_size;
+    block.header.kernel_mr = mmr_roots.kernel_mr;
+    block.header.kernel_mmr_size = mmr_roots.kernel_mmr_size;
+    block.header.validator_node_mr = mmr_roots.validator_node_mr;
+    block.header.validator_node_size = mmr_roots.validator_node_size;
+    block
+}
+
+async fn add_many_chained_blocks<B: BlockchainBackend>(
+    size: usize,
+    db: &BlockchainDatabase<B>,
+    key_manager: &MemoryDbKeyManager,
+) -> (Vec<Arc<Block>>, Vec<WalletOutput>) {
+    let last_header = db.fetch_last_header().unwrap();
+    let mut prev_block = Arc::new(db.fetch_block(last_header.height, true).unwrap().into_block());
+    let mut blocks = Vec::with_capacity(size);
+    let mut outputs = Vec::with_capacity(size);
+    let (script_key_id, wallet_payment_address) = default_coinbase_entities(key_manager).await;
+    for _ in 1..=size {
+        let (block, coinbase_utxo) = create_next_block(
+            db,
+            &prev_block,
+            vec![],
+            key_manager,
+            &script_key_id,
+            &wallet_payment_address,
+        )
+        .await;
+
+        db.add_block(block.clone()).unwrap().assert_added();
+        prev_block = block.clone();
+        blocks.push(block);
+        outputs.push(coinbase_utxo);
+    }
+    (blocks, outputs)
+}
+
+fn assert_blocks_stored<B: BlockchainBackend>(db: &BlockchainDatabase<B>, blocks: &[Arc<Block>]) {
+    for block in blocks {
+        let header = db.fetch_header_by_block_hash(block.hash()).unwrap().unwrap();
+        assert_eq!(header.height, block.header.height);
+        let output = &block.body.outputs()[0];
+        let mined_info = db.fetch_output(output.hash()).unwrap().unwrap();
+        assert_eq!(mined_info.mined_height, block.header.height);
+        assert_eq!(
+            db.fetch_unspent_output_hash_by_commitment(output.commitment.clone())
+                .unwrap(),
+            Some(output.hash())
+        );
+        let kernel = &block.body.kernels()[0];
+        let (_, header_hash) = db
+            .fetch_kernel_by_excess_sig(kernel.excess_sig.clone())
+            .unwrap()
+            .unwrap();
+        assert_eq!(header_hash, block.hash());
+    }
+}
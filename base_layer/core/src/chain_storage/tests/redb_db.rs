@@ -0,0 +1,158 @@
+//  Copyright 2024, The Tari Project
+//
+//  Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+//  following conditions are met:
+//
+//  1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+//  disclaimer.
+//
+//  2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+//  following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+//  3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+//  products derived from this software without specific prior written permission.
+//
+//  THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+//  INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+//  DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+//  SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+//  SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+//  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+//  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::{
+    path::Path,
+    sync::{Arc, RwLock},
+};
+
+use tari_common::configuration::Network;
+use tempfile::tempdir;
+
+use super::{add_many_chained_blocks, assert_blocks_stored};
+use crate::{
+    chain_storage::{
+        create_redb_database,
+        migrate_lmdb_to_redb,
+        BlockchainDatabase,
+        BlockchainDatabaseConfig,
+        ChainStorageError,
+        DbTransaction,
+        RedbDatabase,
+        Validators,
+    },
+    consensus::{chain_strength_comparer::ChainStrengthComparerBuilder, ConsensusConstantsBuilder, ConsensusManager},
+    test_helpers::blockchain::create_new_blockchain,
+    transactions::key_manager::create_memory_db_key_manager,
+    validation::{mocks::MockValidator, DifficultyCalculator},
+    OutputSmt,
+};
+
+fn consensus_manager() -> ConsensusManager {
+    let network = Network::LocalNet;
+    ConsensusManager::builder(network)
+        .add_consensus_constants(ConsensusConstantsBuilder::new(network).build())
+        .on_ties(ChainStrengthComparerBuilder::new().by_height().build())
+        .build()
+        .unwrap()
+}
+
+fn open_blockchain(backend: RedbDatabase, config: BlockchainDatabaseConfig) -> BlockchainDatabase<RedbDatabase> {
+    let rules = consensus_manager();
+    let validators = Validators::new(
+        MockValidator::new(true),
+        MockValidator::new(true),
+        MockValidator::new(true),
+    );
+    BlockchainDatabase::new(
+        backend,
+        rules.clone(),
+        validators,
+        config,
+        DifficultyCalculator::new(rules, Default::default()),
+        Arc::new(RwLock::new(OutputSmt::new())),
+    )
+    .unwrap()
+}
+
+fn setup(path: &Path) -> BlockchainDatabase<RedbDatabase> {
+    open_blockchain(
+        create_redb_database(path, consensus_manager()).unwrap(),
+        BlockchainDatabaseConfig::default(),
+    )
+}
+
+mod persistence {
+    use super::*;
+
+    #[tokio::test]
+    async fn it_persists_blocks_across_restarts() {
+        let dir = tempdir().unwrap();
+        let key_manager = create_memory_db_key_manager().unwrap();
+        let (blocks, _) = {
+            let db = setup(dir.path());
+            add_many_chained_blocks(2, &db, &key_manager).await
+        };
+
+        let db = setup(dir.path());
+        assert_eq!(db.fetch_tip_header().unwrap().hash(), &blocks[1].hash());
+        assert_blocks_stored(&db, &blocks);
+        let (blocks, _) = add_many_chained_blocks(1, &db, &key_manager).await;
+        assert_eq!(db.fetch_tip_header().unwrap().hash(), &blocks[0].hash());
+    }
+
+    #[test]
+    fn it_locks_the_database_file() {
+        let dir = tempdir().unwrap();
+        let _db = create_redb_database(dir.path(), consensus_manager()).unwrap();
+        assert!(matches!(
+            create_redb_database(dir.path(), consensus_manager()),
+            Err(ChainStorageError::CannotAcquireFileLock)
+        ));
+    }
+}
+
+mod migration {
+    use super::*;
+
+    #[tokio::test]
+    async fn it_copies_the_chain_from_lmdb() {
+        let lmdb = create_new_blockchain();
+        let key_manager = create_memory_db_key_manager().unwrap();
+        let (blocks, _) = add_many_chained_blocks(3, &lmdb, &key_manager).await;
+        let mut txn = DbTransaction::new();
+        txn.insert_bad_block(blocks[0].hash(), 1, "test".to_string());
+        lmdb.commit(txn).unwrap();
+
+        let dir = tempdir().unwrap();
+        let redb = create_redb_database(dir.path(), consensus_manager()).unwrap();
+        let num_copied = migrate_lmdb_to_redb(lmdb.db_read_access().unwrap().db(), &redb).unwrap();
+        assert!(num_copied > 0);
+
+        let db = open_blockchain(redb, BlockchainDatabaseConfig::default());
+        assert_eq!(db.get_chain_metadata().unwrap(), lmdb.get_chain_metadata().unwrap());
+        assert_blocks_stored(&db, &blocks);
+        assert!(db.bad_block_exists(blocks[0].hash()).unwrap().0);
+        assert_eq!(
+            db.db_read_access()
+                .unwrap()
+                .fetch_total_size_stats()
+                .unwrap()
+                .sizes()
+                .len(),
+            25
+        );
+
+        // The migrated chain can be extended
+        let (blocks, _) = add_many_chained_blocks(1, &db, &key_manager).await;
+        assert_eq!(db.fetch_tip_header().unwrap().hash(), &blocks[0].hash());
+    }
+
+    #[tokio::test]
+    async fn it_refuses_to_overwrite_an_existing_chain() {
+        let lmdb = create_new_blockchain();
+        let dir = tempdir().unwrap();
+        let redb = setup(dir.path());
+        let result = migrate_lmdb_to_redb(lmdb.db_read_access().unwrap().db(), &redb.db_read_access().unwrap());
+        assert!(matches!(result, Err(ChainStorageError::InvalidOperation(_))));
+    }
+}
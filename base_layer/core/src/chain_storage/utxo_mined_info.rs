@@ -40,3 +40,17 @@ pub struct InputMinedInfo {
     pub header_hash: BlockHash,
     pub spent_timestamp: u64,
 }
+
+/// The state of an output at a past height
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoricalOutputState {
+    pub output: OutputMinedInfo,
+    /// The input that spent the output, if it was spent at or below the queried height
+    pub spent: Option<InputMinedInfo>,
+}
+
+impl HistoricalOutputState {
+    pub fn is_unspent(&self) -> bool {
+        self.spent.is_none()
+    }
+}
@@ -22,6 +22,7 @@
 
 use blake2::Blake2b;
 use digest::consts::U64;
+use tari_comms::peer_manager::ReputationEvent;
 use tari_hashing::ConfidentialOutputHashDomain;
 #[cfg(feature = "base_node")]
 use tari_max_size::MaxSizeVec;
@@ -66,6 +67,14 @@ impl BanReason {
     pub fn ban_duration(&self) -> BanPeriod {
         self.ban_duration
     }
+
+    /// The reputation event to report for the offending peer
+    pub fn reputation_event(&self) -> ReputationEvent {
+        match self.ban_duration {
+            BanPeriod::Short => ReputationEvent::ProtocolViolation,
+            BanPeriod::Long => ReputationEvent::InvalidBlock,
+        }
+    }
 }
 
 /// AuxChainHashes is a vector of limited size
@@ -23,7 +23,11 @@
 use serde::{Deserialize, Serialize};
 use tari_common::SubConfigPath;
 
-use crate::mempool::{reorg_pool::ReorgPoolConfig, unconfirmed_pool::UnconfirmedPoolConfig};
+use crate::mempool::{
+    fee_estimator::FeeEstimatorConfig,
+    reorg_pool::ReorgPoolConfig,
+    unconfirmed_pool::UnconfirmedPoolConfig,
+};
 
 /// Configuration for the Mempool.
 #[derive(Clone, Deserialize, Serialize, Default, Debug)]
@@ -33,6 +37,7 @@ pub struct MempoolConfig {
     pub unconfirmed_pool: UnconfirmedPoolConfig,
     pub reorg_pool: ReorgPoolConfig,
     pub service: MempoolServiceConfig,
+    pub fee_estimator: FeeEstimatorConfig,
 }
 
 impl SubConfigPath for MempoolConfig {
@@ -0,0 +1,291 @@
+// Copyright 2024 The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+use std::collections::VecDeque;
+
+use log::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    consensus::ConsensusConstants,
+    mempool::{FeeEstimate, FeePerGramStat},
+    proof_of_work::PowAlgorithm,
+    transactions::tari_amount::MicroMinotari,
+};
+
+pub const LOG_TARGET: &str = "c::mp::fee_estimator";
+
+/// The confirmation targets (in blocks) that are estimated when none are requested
+pub const DEFAULT_FEE_ESTIMATE_TARGETS: [u64; 3] = [1, 3, 10];
+/// The largest confirmation target that can be estimated
+pub const MAX_FEE_ESTIMATE_TARGET: u64 = 20;
+/// The lowest fee per gram that is ever recommended
+const MIN_FEE_PER_GRAM: MicroMinotari = MicroMinotari(1);
+/// The percentage of recently mined transactions paying at least the recommended fee that must have been mined within
+/// the confirmation target
+const SUCCESS_THRESHOLD_PERCENT: u64 = 85;
+
+/// Configuration for the FeeEstimator
+#[derive(Clone, Copy, Serialize, Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct FeeEstimatorConfig {
+    /// The number of recent blocks whose mined mempool transactions are used to estimate fees
+    pub history_blocks: usize,
+    /// The minimum number of mined transactions that must have been observed before the inclusion history is used
+    pub min_samples: usize,
+}
+
+impl Default for FeeEstimatorConfig {
+    fn default() -> Self {
+        Self {
+            history_blocks: 30,
+            min_samples: 10,
+        }
+    }
+}
+
+/// The fee per gram of a transaction from the unconfirmed pool that was included in a block, and the time at which it
+/// was added to the pool
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MinedTransactionFee {
+    pub fee_per_gram: MicroMinotari,
+    pub inserted_at: u64,
+}
+
+struct BlockFeeRecord {
+    height: u64,
+    timestamp: u64,
+    /// The fee per gram of each mined transaction and the number of seconds it spent in the mempool
+    transactions: Vec<(MicroMinotari, u64)>,
+}
+
+/// Estimates fees from a snapshot of the unconfirmed pool combined with how long transactions paying different fees
+/// waited in the mempool before being mined in recent blocks.
+pub struct FeeEstimator {
+    config: FeeEstimatorConfig,
+    blocks: VecDeque<BlockFeeRecord>,
+}
+
+impl FeeEstimator {
+    pub fn new(config: FeeEstimatorConfig) -> Self {
+        Self {
+            config,
+            blocks: VecDeque::with_capacity(config.history_blocks),
+        }
+    }
+
+    /// Records the mempool transactions that were mined in the block at `height`. `now` is the unix timestamp at which
+    /// the block was received and is used to calculate how long each transaction spent in the mempool.
+    pub fn add_block(&mut self, height: u64, timestamp: u64, now: u64, mined: &[MinedTransactionFee]) {
+        // After a reorg, the new block replaces any blocks recorded at the same height or above
+        self.remove_blocks_from(height);
+        let transactions = mined
+            .iter()
+            .map(|tx| (tx.fee_per_gram, now.saturating_sub(tx.inserted_at)))
+            .collect();
+        self.blocks.push_back(BlockFeeRecord {
+            height,
+            timestamp,
+            transactions,
+        });
+        while self.blocks.len() > self.config.history_blocks {
+            self.blocks.pop_front();
+        }
+    }
+
+    /// Removes the records of all blocks at or above the given height
+    pub fn remove_blocks_from(&mut self, height: u64) {
+        while self.blocks.back().is_some_and(|b| b.height >= height) {
+            self.blocks.pop_back();
+        }
+    }
+
+    /// Estimates the fee per gram needed to be mined within `target_blocks` blocks. `mempool_stats` must contain the
+    /// fee per gram stats of at least `target_blocks` blocks if the mempool contains that many blocks of transactions.
+    pub fn estimate(
+        &self,
+        target_blocks: u64,
+        mempool_stats: &[FeePerGramStat],
+        constants: &ConsensusConstants,
+    ) -> FeeEstimate {
+        let target_blocks = target_blocks.max(1);
+        // To be included in the target block, a transaction must outbid the lowest paying transaction that would
+        // currently be included in it. If the mempool does not fill that many blocks, any fee will do.
+        let mempool_fee_per_gram = usize::try_from(target_blocks - 1)
+            .ok()
+            .and_then(|i| mempool_stats.get(i))
+            .map(|stat| stat.min_fee_per_gram + MIN_FEE_PER_GRAM)
+            .unwrap_or(MIN_FEE_PER_GRAM);
+
+        let block_interval = self
+            .average_block_interval()
+            .unwrap_or_else(|| expected_block_interval(constants));
+        let max_wait = target_blocks.saturating_mul(block_interval);
+        let (history_fee_per_gram, num_samples) = self.estimate_from_history(max_wait);
+        trace!(
+            target: LOG_TARGET,
+            "Fee estimate for {} block(s): mempool {}, history {:?} ({} samples, max wait {}s)",
+            target_blocks,
+            mempool_fee_per_gram,
+            history_fee_per_gram,
+            num_samples,
+            max_wait
+        );
+
+        FeeEstimate {
+            target_blocks,
+            fee_per_gram: mempool_fee_per_gram
+                .max(history_fee_per_gram.unwrap_or(MIN_FEE_PER_GRAM))
+                .max(MIN_FEE_PER_GRAM),
+            mempool_fee_per_gram,
+            history_fee_per_gram,
+            num_samples,
+        }
+    }
+
+    /// Returns the lowest fee per gram at which at least `SUCCESS_THRESHOLD_PERCENT` of the recently mined
+    /// transactions paying that fee or more were mined within `max_wait` seconds, along with the number of samples
+    fn estimate_from_history(&self, max_wait: u64) -> (Option<MicroMinotari>, u64) {
+        let mut samples = self
+            .blocks
+            .iter()
+            .flat_map(|b| b.transactions.iter().copied())
+            .collect::<Vec<_>>();
+        let num_samples = samples.len() as u64;
+        if samples.is_empty() || samples.len() < self.config.min_samples {
+            return (None, num_samples);
+        }
+        // Highest fee first
+        samples.sort_unstable_by(|a, b| b.0.cmp(&a.0));
+
+        let mut estimate = None;
+        let mut num_in_time = 0u64;
+        for (i, (fee_per_gram, wait)) in samples.iter().enumerate() {
+            if *wait <= max_wait {
+                num_in_time += 1;
+            }
+            let num_paying_at_least = i as u64 + 1;
+            if num_in_time * 100 >= num_paying_at_least * SUCCESS_THRESHOLD_PERCENT {
+                estimate = Some(*fee_per_gram);
+            }
+        }
+        // If transactions were not mined in time at any fee, recommend the highest fee that was paid
+        (estimate.or_else(|| samples.first().map(|s| s.0)), num_samples)
+    }
+
+    /// The average number of seconds between the recorded blocks
+    fn average_block_interval(&self) -> Option<u64> {
+        let first = self.blocks.front()?;
+        let last = self.blocks.back()?;
+        let num_blocks = last.height.checked_sub(first.height).filter(|n| *n > 0)?;
+        Some((last.timestamp.saturating_sub(first.timestamp) / num_blocks).max(1))
+    }
+}
+
+/// The target number of seconds between blocks of any proof of work algorithm
+fn expected_block_interval(constants: &ConsensusConstants) -> u64 {
+    // Each algorithm produces blocks at its own rate, so the combined interval is the reciprocal of the summed rates
+    [PowAlgorithm::RandomX, PowAlgorithm::Sha3x]
+        .iter()
+        .map(|algo| constants.pow_target_block_interval(*algo))
+        .filter(|target_time| *target_time > 0)
+        .reduce(|a, b| a.saturating_mul(b) / a.saturating_add(b))
+        .unwrap_or(1)
+        .max(1)
+}
+
+#[cfg(test)]
+mod test {
+    use tari_common::configuration::Network;
+
+    use super::*;
+    use crate::consensus::ConsensusManager;
+
+    fn mined(fee_per_gram: u64, inserted_at: u64) -> MinedTransactionFee {
+        MinedTransactionFee {
+            fee_per_gram: fee_per_gram.into(),
+            inserted_at,
+        }
+    }
+
+    fn stat(order: u64, min_fee_per_gram: u64) -> FeePerGramStat {
+        FeePerGramStat {
+            order,
+            min_fee_per_gram: min_fee_per_gram.into(),
+            avg_fee_per_gram: min_fee_per_gram.into(),
+            max_fee_per_gram: min_fee_per_gram.into(),
+        }
+    }
+
+    fn constants() -> ConsensusConstants {
+        ConsensusManager::builder(Network::LocalNet)
+            .build()
+            .unwrap()
+            .consensus_constants(0)
+            .clone()
+    }
+
+    #[test]
+    fn it_recommends_the_minimum_without_history_or_backlog() {
+        let estimator = FeeEstimator::new(FeeEstimatorConfig::default());
+        let estimate = estimator.estimate(3, &[], &constants());
+        assert_eq!(estimate.fee_per_gram, MIN_FEE_PER_GRAM);
+        assert_eq!(estimate.history_fee_per_gram, None);
+        assert_eq!(estimate.num_samples, 0);
+    }
+
+    #[test]
+    fn it_outbids_the_target_block_in_the_mempool() {
+        let estimator = FeeEstimator::new(FeeEstimatorConfig::default());
+        let stats = [stat(0, 50), stat(1, 20), stat(2, 10)];
+        assert_eq!(estimator.estimate(1, &stats, &constants()).fee_per_gram, 51.into());
+        assert_eq!(estimator.estimate(3, &stats, &constants()).fee_per_gram, 11.into());
+        // The mempool does not fill 10 blocks
+        assert_eq!(
+            estimator.estimate(10, &stats, &constants()).fee_per_gram,
+            MIN_FEE_PER_GRAM
+        );
+    }
+
+    #[test]
+    fn it_uses_the_inclusion_history() {
+        let mut estimator = FeeEstimator::new(FeeEstimatorConfig {
+            history_blocks: 10,
+            min_samples: 4,
+        });
+        // Blocks are 100s apart. High fee transactions are mined after 50s, low fee transactions after 1000s.
+        for height in 1..=5u64 {
+            let timestamp = height * 100;
+            estimator.add_block(height, timestamp, timestamp, &[
+                mined(100, timestamp - 50),
+                mined(5, timestamp - 1000),
+            ]);
+        }
+        let estimate = estimator.estimate(1, &[], &constants());
+        assert_eq!(estimate.num_samples, 10);
+        assert_eq!(estimate.history_fee_per_gram, Some(100.into()));
+        assert_eq!(estimate.fee_per_gram, 100.into());
+
+        // Waiting 10 blocks, the low fee transactions are mined in time
+        let estimate = estimator.estimate(10, &[], &constants());
+        assert_eq!(estimate.history_fee_per_gram, Some(5.into()));
+    }
+
+    #[test]
+    fn it_replaces_blocks_after_a_reorg_and_limits_history() {
+        let mut estimator = FeeEstimator::new(FeeEstimatorConfig {
+            history_blocks: 3,
+            min_samples: 1,
+        });
+        for height in 1..=5u64 {
+            estimator.add_block(height, height * 100, height * 100, &[mined(height, 0)]);
+        }
+        assert_eq!(estimator.blocks.len(), 3);
+        assert_eq!(estimator.blocks.front().unwrap().height, 3);
+
+        estimator.add_block(4, 450, 450, &[]);
+        assert_eq!(estimator.blocks.len(), 2);
+        assert_eq!(estimator.blocks.back().unwrap().height, 4);
+        assert!(estimator.blocks.back().unwrap().transactions.is_empty());
+    }
+}
@@ -32,6 +32,7 @@ use crate::{
     mempool::{
         error::MempoolError,
         mempool_storage::MempoolStorage,
+        FeeEstimate,
         FeePerGramStat,
         MempoolConfig,
         StateResponse,
@@ -188,6 +189,16 @@ impl Mempool {
             .await
     }
 
+    /// Estimates the fee per gram needed for a transaction to be mined within each of the given number of blocks
+    pub async fn estimate_fees(
+        &self,
+        target_blocks: Vec<u64>,
+        tip_height: u64,
+    ) -> Result<Vec<FeeEstimate>, MempoolError> {
+        self.with_read_access(move |storage| storage.estimate_fees(&target_blocks, tip_height))
+            .await
+    }
+
     async fn with_read_access<F, T>(&self, callback: F) -> Result<T, MempoolError>
     where
         F: FnOnce(&MempoolStorage) -> Result<T, MempoolError> + Send + 'static,
@@ -20,10 +20,13 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::{sync::Arc, time::Instant};
+use std::{
+    sync::Arc,
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
 
 use log::*;
-use tari_common_types::types::{FixedHash, PrivateKey, Signature};
+use tari_common_types::types::{FixedHash, HashOutput, PrivateKey, Signature};
 use tari_utilities::hex::Hex;
 
 use crate::{
@@ -31,8 +34,10 @@ use crate::{
     consensus::ConsensusManager,
     mempool::{
         error::MempoolError,
+        fee_estimator::FeeEstimator,
         reorg_pool::ReorgPool,
-        unconfirmed_pool::{RetrieveResults, TransactionKey, UnconfirmedPool, UnconfirmedPoolError},
+        unconfirmed_pool::{ReplacementCheck, RetrieveResults, TransactionKey, UnconfirmedPool, UnconfirmedPoolError},
+        FeeEstimate,
         FeePerGramStat,
         MempoolConfig,
         StateResponse,
@@ -54,6 +59,7 @@ pub const LOG_TARGET: &str = "c::mp::mempool_storage";
 pub struct MempoolStorage {
     pub(crate) unconfirmed_pool: UnconfirmedPool,
     reorg_pool: ReorgPool,
+    fee_estimator: FeeEstimator,
     validator: Box<dyn TransactionValidator>,
     rules: ConsensusManager,
     last_seen_height: u64,
@@ -66,6 +72,7 @@ impl MempoolStorage {
         Self {
             unconfirmed_pool: UnconfirmedPool::new(config.unconfirmed_pool),
             reorg_pool: ReorgPool::new(config.reorg_pool),
+            fee_estimator: FeeEstimator::new(config.fee_estimator),
             validator,
             rules,
             last_seen_height: 0,
@@ -104,21 +111,19 @@ impl MempoolStorage {
                     timer.elapsed()
                 );
                 let timer = Instant::now();
-                let weight = self.get_transaction_weighting();
-                self.unconfirmed_pool.insert(tx, None, &weight)?;
+                let response = self.insert_into_unconfirmed_pool(tx, None)?;
                 debug!(
                     target: LOG_TARGET,
-                    "Transaction {} inserted in {:.2?}",
+                    "Transaction {} processed in {:.2?}: {}",
                     tx_id,
-                    timer.elapsed()
+                    timer.elapsed(),
+                    response
                 );
-                Ok(TxStorageResponse::UnconfirmedPool)
+                Ok(response)
             },
             Err(ValidationError::UnknownInputs(dependent_outputs)) => {
                 if self.unconfirmed_pool.contains_all_outputs(&dependent_outputs) {
-                    let weight = self.get_transaction_weighting();
-                    self.unconfirmed_pool.insert(tx, Some(dependent_outputs), &weight)?;
-                    Ok(TxStorageResponse::UnconfirmedPool)
+                    self.insert_into_unconfirmed_pool(tx, Some(dependent_outputs))
                 } else {
                     warn!(target: LOG_TARGET, "Validation failed due to unknown inputs");
                     Ok(TxStorageResponse::NotStoredOrphan)
@@ -151,6 +156,56 @@ impl MempoolStorage {
         }
     }
 
+    /// Inserts a validated transaction into the unconfirmed pool, replacing any pool transactions that spend the same
+    /// inputs if the transaction meets the replace-by-fee rules.
+    fn insert_into_unconfirmed_pool(
+        &mut self,
+        tx: Arc<Transaction>,
+        dependent_outputs: Option<Vec<HashOutput>>,
+    ) -> Result<TxStorageResponse, UnconfirmedPoolError> {
+        let weight = self.get_transaction_weighting();
+        let replaced = match self
+            .unconfirmed_pool
+            .check_replacement(&tx, dependent_outputs.as_deref(), &weight)?
+        {
+            ReplacementCheck::NoConflicts => Vec::new(),
+            ReplacementCheck::Replace(keys) => keys,
+            ReplacementCheck::Reject(reason) => {
+                debug!(target: LOG_TARGET, "Replacement rejected: {}", reason);
+                return Ok(TxStorageResponse::NotStoredReplacementRejected);
+            },
+        };
+        if self
+            .unconfirmed_pool
+            .exceeds_package_limit(dependent_outputs.as_deref(), &replaced)
+        {
+            debug!(
+                target: LOG_TARGET,
+                "Transaction {} exceeds the unconfirmed pool package size limit of {}",
+                tx.first_kernel_excess_sig()
+                    .map(|s| s.get_signature().to_hex())
+                    .unwrap_or_else(|| "None?!".into()),
+                self.unconfirmed_pool.config.max_package_size
+            );
+            return Ok(TxStorageResponse::NotStoredPackageTooLarge);
+        }
+        if !replaced.is_empty() {
+            debug!(
+                target: LOG_TARGET,
+                "Transaction {} replaces {} transaction(s) in the unconfirmed pool",
+                tx.first_kernel_excess_sig()
+                    .map(|s| s.get_signature().to_hex())
+                    .unwrap_or_else(|| "None?!".into()),
+                replaced.len()
+            );
+            for key in replaced {
+                self.unconfirmed_pool.remove_transaction(key)?;
+            }
+        }
+        self.unconfirmed_pool.insert(tx, dependent_outputs, &weight)?;
+        Ok(TxStorageResponse::UnconfirmedPool)
+    }
+
     fn get_transaction_weighting(&self) -> TransactionWeight {
         *self
             .rules
@@ -193,6 +248,18 @@ impl MempoolStorage {
             published_block.body.to_counts_string()
         );
         let timer = Instant::now();
+        // Record how long the published txs waited in the mempool before they are removed
+        let mined_fees = self.unconfirmed_pool.get_published_transaction_fees(published_block)?;
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        self.fee_estimator.add_block(
+            published_block.header.height,
+            published_block.header.timestamp.as_u64(),
+            now,
+            &mined_fees,
+        );
         // Move published txs to ReOrgPool and discard double spends
         let removed_transactions = self
             .unconfirmed_pool
@@ -259,6 +326,10 @@ impl MempoolStorage {
     ) -> Result<(), MempoolError> {
         debug!(target: LOG_TARGET, "Mempool processing reorg");
 
+        if let Some(height) = removed_blocks.iter().map(|b| b.header.height).min() {
+            self.fee_estimator.remove_blocks_from(height);
+        }
+
         // Clear out all transactions from the unconfirmed pool and re-submit them to the unconfirmed mempool for
         // validation. This is important as invalid transactions that have not been mined yet may remain in the mempool
         // after a reorg.
@@ -404,4 +475,16 @@ impl MempoolStorage {
         let stats = self.unconfirmed_pool.get_fee_per_gram_stats(count, target_weight)?;
         Ok(stats)
     }
+
+    /// Estimates the fee per gram needed for a transaction to be mined within each of the given number of blocks
+    pub fn estimate_fees(&self, target_blocks: &[u64], tip_height: u64) -> Result<Vec<FeeEstimate>, MempoolError> {
+        let max_target = target_blocks.iter().copied().max().unwrap_or(0);
+        let count = usize::try_from(max_target).map_err(|e| MempoolError::InternalError(e.to_string()))?;
+        let stats = self.get_fee_per_gram_stats(count, tip_height)?;
+        let constants = self.rules.consensus_constants(tip_height);
+        Ok(target_blocks
+            .iter()
+            .map(|target| self.fee_estimator.estimate(*target, &stats, constants))
+            .collect())
+    }
 }
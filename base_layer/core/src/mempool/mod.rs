@@ -28,6 +28,8 @@ mod config;
 #[cfg(feature = "base_node")]
 mod error;
 #[cfg(feature = "base_node")]
+mod fee_estimator;
+#[cfg(feature = "base_node")]
 #[allow(clippy::module_inception)]
 mod mempool;
 #[cfg(feature = "base_node")]
@@ -53,6 +55,8 @@ mod unconfirmed_pool;
 #[cfg(feature = "base_node")]
 pub use error::MempoolError;
 #[cfg(feature = "base_node")]
+pub use fee_estimator::{FeeEstimatorConfig, DEFAULT_FEE_ESTIMATE_TARGETS, MAX_FEE_ESTIMATE_TARGET};
+#[cfg(feature = "base_node")]
 pub use mempool::Mempool;
 
 #[cfg(feature = "base_node")]
@@ -115,6 +119,8 @@ pub enum TxStorageResponse {
     NotStored,
     NotStoredAlreadyMined,
     NotStoredFeeTooLow,
+    NotStoredReplacementRejected,
+    NotStoredPackageTooLarge,
 }
 
 impl TxStorageResponse {
@@ -135,6 +141,12 @@ impl Display for TxStorageResponse {
             TxStorageResponse::NotStored => "Not stored",
             TxStorageResponse::NotStoredAlreadyMined => "Not stored tx already mined",
             TxStorageResponse::NotStoredFeeTooLow => "Not stored tx fee is below the minimum accepted by this mempool",
+            TxStorageResponse::NotStoredReplacementRejected => {
+                "Not stored tx conflicts with mempool transactions and does not meet the replace-by-fee rules"
+            },
+            TxStorageResponse::NotStoredPackageTooLarge => {
+                "Not stored tx has too many unconfirmed ancestors or descendants in the mempool"
+            },
         };
         fmt.write_str(storage)
     }
@@ -158,3 +170,30 @@ impl From<base_node_proto::MempoolFeePerGramStat> for FeePerGramStat {
         }
     }
 }
+
+/// A recommended fee per gram for a transaction to be mined within `target_blocks` blocks
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FeeEstimate {
+    pub target_blocks: u64,
+    /// The recommended fee per gram, the greater of the mempool and history estimates
+    pub fee_per_gram: MicroMinotari,
+    /// The fee per gram needed to be included within the target number of blocks given the current mempool
+    pub mempool_fee_per_gram: MicroMinotari,
+    /// The fee per gram that recently mined transactions needed to be mined within the target, if enough
+    /// transactions have been observed
+    pub history_fee_per_gram: Option<MicroMinotari>,
+    /// The number of mined transactions used for the history estimate
+    pub num_samples: u64,
+}
+
+impl From<base_node_proto::FeeEstimate> for FeeEstimate {
+    fn from(value: base_node_proto::FeeEstimate) -> Self {
+        Self {
+            target_blocks: value.target_blocks,
+            fee_per_gram: value.fee_per_gram.into(),
+            mempool_fee_per_gram: value.mempool_fee_per_gram.into(),
+            history_fee_per_gram: Some(value.history_fee_per_gram).filter(|fee| *fee > 0).map(Into::into),
+            num_samples: value.num_samples,
+        }
+    }
+}
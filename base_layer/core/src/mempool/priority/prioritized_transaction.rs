@@ -49,6 +49,12 @@ impl FeePriority {
             .saturating_mul(1000)
             .checked_div(weight)
             .ok_or(TransactionError::ZeroWeight)?;
+        Ok(Self::with_fee_per_byte(transaction, insert_epoch, fee_per_byte))
+    }
+
+    /// Create a priority for the transaction using the given fee per byte rather than the transaction's own. This is
+    /// used to raise the priority of a transaction whose unconfirmed descendants pay a higher fee.
+    pub fn with_fee_per_byte(transaction: &Transaction, insert_epoch: u64, fee_per_byte: u64) -> Self {
         // Big-endian used here, the MSB is in the starting index. The ordering for Vec<u8> is taken from elements left
         // to right and the unconfirmed pool expects the lowest priority to be sorted lowest to highest in the
         // BTreeMap
@@ -71,7 +77,7 @@ impl FeePriority {
             );
         priority[16..48].copy_from_slice(agg_sig.as_bytes());
         priority[48..80].copy_from_slice(agg_nonce.as_bytes());
-        Ok(Self(priority))
+        Self(priority)
     }
 }
 
@@ -84,6 +90,8 @@ pub struct PrioritizedTransaction {
     pub fee_per_byte: u64,
     pub weight: u64,
     pub dependent_output_hashes: Vec<HashOutput>,
+    /// The unix timestamp (in seconds) at which the transaction was added to the pool
+    pub inserted_at: u64,
 }
 
 impl PrioritizedTransaction {
@@ -111,6 +119,7 @@ impl PrioritizedTransaction {
             weight,
             transaction,
             dependent_output_hashes: dependent_outputs.unwrap_or_default(),
+            inserted_at: insert_epoch,
         })
     }
 }
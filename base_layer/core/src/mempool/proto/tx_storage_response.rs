@@ -52,6 +52,8 @@ impl From<TxStorageResponse> for proto::TxStorageResponse {
             NotStoredConsensus => proto::TxStorageResponse::NotStored,
             NotStoredAlreadyMined => proto::TxStorageResponse::NotStored,
             NotStoredFeeTooLow => proto::TxStorageResponse::NotStored,
+            NotStoredReplacementRejected => proto::TxStorageResponse::NotStored,
+            NotStoredPackageTooLarge => proto::TxStorageResponse::NotStored,
         }
     }
 }
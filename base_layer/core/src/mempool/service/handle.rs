@@ -26,6 +26,7 @@ use tari_service_framework::{reply_channel::TrySenderService, Service};
 use crate::{
     mempool::{
         service::{MempoolRequest, MempoolResponse},
+        FeeEstimate,
         FeePerGramStat,
         MempoolServiceError,
         StateResponse,
@@ -97,4 +98,22 @@ impl MempoolHandle {
             _ => Err(MempoolServiceError::InvalidResponse("Incorrect response".to_string())),
         }
     }
+
+    pub async fn estimate_fee(
+        &mut self,
+        target_blocks: Vec<u64>,
+        tip_height: u64,
+    ) -> Result<Vec<FeeEstimate>, MempoolServiceError> {
+        match self
+            .inner
+            .call(MempoolRequest::EstimateFee {
+                target_blocks,
+                tip_height,
+            })
+            .await??
+        {
+            MempoolResponse::FeeEstimates(estimates) => Ok(estimates),
+            _ => Err(MempoolServiceError::InvalidResponse("Incorrect response".to_string())),
+        }
+    }
 }
@@ -26,6 +26,7 @@ use tari_service_framework::{reply_channel::SenderService, Service};
 use crate::{
     mempool::{
         service::{MempoolRequest, MempoolResponse, MempoolServiceError},
+        FeeEstimate,
         StateResponse,
         StatsResponse,
         TxStorageResponse,
@@ -100,6 +101,25 @@ impl LocalMempoolService {
             _ => Err(MempoolServiceError::UnexpectedApiResponse),
         }
     }
+
+    /// Returns the recommended fee per gram for a transaction to be mined within each of the given number of blocks
+    pub async fn estimate_fee(
+        &mut self,
+        target_blocks: Vec<u64>,
+        tip_height: u64,
+    ) -> Result<Vec<FeeEstimate>, MempoolServiceError> {
+        match self
+            .request_sender
+            .call(MempoolRequest::EstimateFee {
+                target_blocks,
+                tip_height,
+            })
+            .await??
+        {
+            MempoolResponse::FeeEstimates(s) => Ok(s),
+            _ => Err(MempoolServiceError::UnexpectedApiResponse),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -37,6 +37,7 @@ pub enum MempoolRequest {
     GetTxStateByExcessSig(Signature),
     SubmitTransaction(Transaction),
     GetFeePerGramStats { count: usize, tip_height: u64 },
+    EstimateFee { target_blocks: Vec<u64>, tip_height: u64 },
 }
 
 impl Display for MempoolRequest {
@@ -57,6 +58,16 @@ impl Display for MempoolRequest {
             MempoolRequest::GetFeePerGramStats { count, tip_height } => {
                 write!(f, "GetFeePerGramStats(count: {}, tip_height: {})", *count, *tip_height)
             },
+            MempoolRequest::EstimateFee {
+                target_blocks,
+                tip_height,
+            } => {
+                write!(
+                    f,
+                    "EstimateFee(targets: {:?}, tip_height: {})",
+                    target_blocks, *tip_height
+                )
+            },
         }
     }
 }
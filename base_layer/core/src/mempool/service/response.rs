@@ -24,7 +24,7 @@ use std::{fmt, fmt::Formatter};
 
 use crate::{
     common::waiting_requests::RequestKey,
-    mempool::{FeePerGramStat, StateResponse, StatsResponse, TxStorageResponse},
+    mempool::{FeeEstimate, FeePerGramStat, StateResponse, StatsResponse, TxStorageResponse},
 };
 
 /// API Response enum for Mempool responses.
@@ -34,16 +34,18 @@ pub enum MempoolResponse {
     State(StateResponse),
     TxStorage(TxStorageResponse),
     FeePerGramStats { response: Vec<FeePerGramStat> },
+    FeeEstimates(Vec<FeeEstimate>),
 }
 
 impl fmt::Display for MempoolResponse {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        use MempoolResponse::{FeePerGramStats, State, Stats, TxStorage};
+        use MempoolResponse::{FeeEstimates, FeePerGramStats, State, Stats, TxStorage};
         match &self {
             Stats(_) => write!(f, "Stats"),
             State(_) => write!(f, "State"),
             TxStorage(_) => write!(f, "TxStorage"),
             FeePerGramStats { response } => write!(f, "FeePerGramStats({} item(s))", response.len()),
+            FeeEstimates(estimates) => write!(f, "FeeEstimates({} item(s))", estimates.len()),
         }
     }
 }
@@ -33,6 +33,7 @@ use tokio::time::Instant;
 use crate::{
     blocks::Block,
     mempool::{
+        fee_estimator::MinedTransactionFee,
         priority::{FeePriority, PrioritizedTransaction},
         shrink_hashmap::shrink_hashmap,
         unconfirmed_pool::UnconfirmedPoolError,
@@ -61,6 +62,15 @@ pub struct UnconfirmedPoolConfig {
     pub weight_tx_skip_count: usize,
     /// The minimum fee accepted by this mempool
     pub min_fee: u64,
+    /// The amount (in µT per gram of the replacement) by which the fee of a replacement transaction must exceed the
+    /// combined fees of the transactions it replaces
+    pub min_replacement_fee_per_gram_increase: u64,
+    /// The maximum number of transactions, including their descendants, that a single replacement transaction may
+    /// evict from the pool
+    pub max_replaced_transactions: usize,
+    /// The maximum number of transactions in a package, i.e. a transaction together with its unconfirmed ancestors or
+    /// a transaction together with its unconfirmed descendants
+    pub max_package_size: usize,
 }
 
 impl Default for UnconfirmedPoolConfig {
@@ -69,6 +79,9 @@ impl Default for UnconfirmedPoolConfig {
             storage_capacity: 40_000,
             weight_tx_skip_count: 20,
             min_fee: 0,
+            min_replacement_fee_per_gram_increase: 1,
+            max_replaced_transactions: 100,
+            max_package_size: 25,
         }
     }
 }
@@ -80,7 +93,8 @@ impl Default for UnconfirmedPoolConfig {
 /// transactions in the pool according to TXPriority, it allows transactions to be inserted in sorted order by their
 /// priority. The txs_by_priority BTreeMap makes it easier to select the set of highest priority transactions that can
 /// be included in a block. The excess_sig of a transaction is used as a key to uniquely identify a specific transaction
-/// in these containers.
+/// in these containers. The txs_by_input HashMap is used to find the transactions that conflict with a new transaction
+/// and the unconfirmed descendants of a transaction.
 pub struct UnconfirmedPool {
     pub(crate) config: UnconfirmedPoolConfig,
     key_counter: usize,
@@ -88,6 +102,7 @@ pub struct UnconfirmedPool {
     txs_by_signature: HashMap<PrivateKey, Vec<TransactionKey>>,
     tx_by_priority: BTreeMap<FeePriority, TransactionKey>,
     txs_by_output: HashMap<HashOutput, Vec<TransactionKey>>,
+    txs_by_input: HashMap<HashOutput, Vec<TransactionKey>>,
     txs_by_unique_id: HashMap<[u8; 32], Vec<TransactionKey>>,
 }
 
@@ -98,6 +113,18 @@ pub struct RetrieveResults {
     pub transactions_to_remove_and_insert: Vec<(TransactionKey, Arc<Transaction>)>,
 }
 
+/// The outcome of checking a new transaction against the pool transactions that spend the same inputs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplacementCheck {
+    /// The transaction does not spend any input that is spent by a pool transaction
+    NoConflicts,
+    /// The transaction may replace the conflicting pool transactions. These keys are the conflicting transactions and
+    /// all of their unconfirmed descendants, which must be removed before the transaction is inserted.
+    Replace(Vec<TransactionKey>),
+    /// The transaction conflicts with pool transactions but does not meet the replace-by-fee rules
+    Reject(String),
+}
+
 pub type CompleteTransactionBranch = HashMap<TransactionKey, (HashMap<TransactionKey, Arc<Transaction>>, u64, u64)>;
 
 impl UnconfirmedPool {
@@ -110,6 +137,7 @@ impl UnconfirmedPool {
             txs_by_signature: HashMap::new(),
             tx_by_priority: BTreeMap::new(),
             txs_by_output: HashMap::new(),
+            txs_by_input: HashMap::new(),
             txs_by_unique_id: HashMap::new(),
         }
     }
@@ -145,6 +173,9 @@ impl UnconfirmedPool {
         for output in prioritized_tx.transaction.body.outputs() {
             self.txs_by_output.entry(output.hash()).or_default().push(new_key);
         }
+        for input in prioritized_tx.transaction.body.inputs() {
+            self.txs_by_input.entry(input.output_hash()).or_default().push(new_key);
+        }
         for kernel in prioritized_tx.transaction.body.kernels() {
             let sig = kernel.excess_sig.get_signature();
             self.txs_by_signature.entry(sig.clone()).or_default().push(new_key);
@@ -154,11 +185,210 @@ impl UnconfirmedPool {
             target: LOG_TARGET,
             "Inserted transaction {} into unconfirmed pool:", prioritized_tx
         );
+        let ancestors = self.get_ancestors(&prioritized_tx.dependent_output_hashes);
         self.tx_by_key.insert(new_key, prioritized_tx);
+        // A child paying a higher fee than its unconfirmed parents raises the priority of the whole package
+        for key in ancestors {
+            self.update_package_priority(key)?;
+        }
 
         Ok(())
     }
 
+    /// Checks whether a valid transaction may be inserted given the pool transactions that spend any of the same
+    /// inputs. A conflicting transaction replaces the pool transactions if:
+    /// - it pays a strictly higher fee per byte than each of the transactions it directly conflicts with,
+    /// - its fee exceeds the combined fees of all the transactions it evicts (the conflicting transactions and their
+    ///   descendants) by at least `min_replacement_fee_per_gram_increase` per gram of its own weight,
+    /// - it evicts no more than `max_replaced_transactions` transactions, and
+    /// - it does not spend the outputs of any of the transactions it evicts.
+    pub fn check_replacement(
+        &self,
+        tx: &Transaction,
+        dependent_outputs: Option<&[HashOutput]>,
+        transaction_weighting: &TransactionWeight,
+    ) -> Result<ReplacementCheck, UnconfirmedPoolError> {
+        // A transaction that is already in the pool is not a replacement of itself
+        if tx
+            .body
+            .kernels()
+            .iter()
+            .all(|k| self.txs_by_signature.contains_key(k.excess_sig.get_signature()))
+        {
+            return Ok(ReplacementCheck::NoConflicts);
+        }
+
+        let conflicts = tx
+            .body
+            .inputs()
+            .iter()
+            .filter_map(|input| self.txs_by_input.get(&input.output_hash()))
+            .flatten()
+            .copied()
+            .collect::<HashSet<_>>();
+        if conflicts.is_empty() {
+            return Ok(ReplacementCheck::NoConflicts);
+        }
+
+        let mut evicted = conflicts.clone();
+        for key in &conflicts {
+            let conflict = self.tx_by_key.get(key).ok_or(UnconfirmedPoolError::StorageOutofSync)?;
+            evicted.extend(self.get_descendants(&conflict.transaction));
+        }
+        if evicted.len() > self.config.max_replaced_transactions {
+            return Ok(ReplacementCheck::Reject(format!(
+                "Replacement would evict {} transactions, at most {} may be replaced",
+                evicted.len(),
+                self.config.max_replaced_transactions
+            )));
+        }
+        if dependent_outputs.unwrap_or_default().iter().any(|hash| {
+            self.txs_by_output
+                .get(hash)
+                .is_some_and(|keys| keys.iter().any(|key| evicted.contains(key)))
+        }) {
+            return Ok(ReplacementCheck::Reject(
+                "Replacement spends outputs of the transactions it replaces".to_string(),
+            ));
+        }
+
+        let weight = tx.calculate_weight(transaction_weighting)?;
+        let fee = tx.body.get_total_fee()?.as_u64();
+        let fee_per_byte = fee
+            .saturating_mul(1000)
+            .checked_div(weight)
+            .ok_or(TransactionError::ZeroWeight)?;
+        for key in &conflicts {
+            let conflict = self.tx_by_key.get(key).ok_or(UnconfirmedPoolError::StorageOutofSync)?;
+            if fee_per_byte <= conflict.fee_per_byte {
+                return Ok(ReplacementCheck::Reject(format!(
+                    "Replacement fee per byte {} does not exceed {} of the conflicting transaction {}",
+                    fee_per_byte, conflict.fee_per_byte, conflict
+                )));
+            }
+        }
+
+        let mut replaced_fees = 0u64;
+        for key in &evicted {
+            let evicted_tx = self.tx_by_key.get(key).ok_or(UnconfirmedPoolError::StorageOutofSync)?;
+            replaced_fees = replaced_fees
+                .checked_add(evicted_tx.transaction.body.get_total_fee()?.as_u64())
+                .ok_or(UnconfirmedPoolError::InternalError(
+                    "Overflow when calculating replaced fees".to_string(),
+                ))?;
+        }
+        let min_fee =
+            replaced_fees.saturating_add(self.config.min_replacement_fee_per_gram_increase.saturating_mul(weight));
+        if fee < min_fee {
+            return Ok(ReplacementCheck::Reject(format!(
+                "Replacement fee {} is less than the required {} (replaced fees {})",
+                fee, min_fee, replaced_fees
+            )));
+        }
+
+        Ok(ReplacementCheck::Replace(evicted.into_iter().collect()))
+    }
+
+    /// Returns true if a transaction spending the given outputs would be part of a package of more than
+    /// `max_package_size` transactions, either with its unconfirmed ancestors or as a descendant of one of them. The
+    /// `replaced` transactions are about to be evicted and are not counted. Bounding the package size bounds the work
+    /// needed to update package priorities when transactions are inserted and removed.
+    pub fn exceeds_package_limit(&self, dependent_outputs: Option<&[HashOutput]>, replaced: &[TransactionKey]) -> bool {
+        let ancestors = self.get_ancestors(dependent_outputs.unwrap_or_default());
+        if ancestors.len() >= self.config.max_package_size {
+            return true;
+        }
+        ancestors
+            .iter()
+            .filter_map(|key| self.tx_by_key.get(key))
+            .any(|ancestor| {
+                let descendants = self
+                    .get_descendants(&ancestor.transaction)
+                    .into_iter()
+                    .filter(|key| !replaced.contains(key))
+                    .count();
+                // The ancestor, its current descendants and the new transaction
+                descendants.saturating_add(2) > self.config.max_package_size
+            })
+    }
+
+    /// Returns the keys of the pool transactions that create the given outputs, and all of their unconfirmed ancestors
+    fn get_ancestors(&self, dependent_outputs: &[HashOutput]) -> HashSet<TransactionKey> {
+        let mut ancestors = HashSet::new();
+        let mut pending = dependent_outputs.to_vec();
+        while let Some(hash) = pending.pop() {
+            for key in self.txs_by_output.get(&hash).into_iter().flatten() {
+                if ancestors.insert(*key) {
+                    if let Some(ancestor) = self.tx_by_key.get(key) {
+                        pending.extend(ancestor.dependent_output_hashes.iter().copied());
+                    }
+                }
+            }
+        }
+        ancestors
+    }
+
+    /// Returns the keys of the pool transactions that spend the outputs of the given transaction, and all of their
+    /// descendants
+    fn get_descendants(&self, transaction: &Transaction) -> HashSet<TransactionKey> {
+        let mut descendants = HashSet::new();
+        let mut pending = transaction.body.outputs().iter().map(|o| o.hash()).collect::<Vec<_>>();
+        while let Some(hash) = pending.pop() {
+            for key in self.txs_by_input.get(&hash).into_iter().flatten() {
+                if descendants.insert(*key) {
+                    if let Some(descendant) = self.tx_by_key.get(key) {
+                        pending.extend(descendant.transaction.body.outputs().iter().map(|o| o.hash()));
+                    }
+                }
+            }
+        }
+        descendants
+    }
+
+    /// The fee per byte of the transaction together with all of its unconfirmed ancestors, i.e. the fee per byte a
+    /// miner earns by including the transaction.
+    fn get_package_fee_per_byte(&self, tx_key: TransactionKey) -> Result<u64, UnconfirmedPoolError> {
+        let transaction = self
+            .tx_by_key
+            .get(&tx_key)
+            .ok_or(UnconfirmedPoolError::StorageOutofSync)?;
+        let mut total_fees = transaction.transaction.body.get_total_fee()?.as_u64();
+        let mut total_weight = transaction.weight;
+        for key in self.get_ancestors(&transaction.dependent_output_hashes) {
+            let ancestor = self.tx_by_key.get(&key).ok_or(UnconfirmedPoolError::StorageOutofSync)?;
+            total_fees = total_fees.saturating_add(ancestor.transaction.body.get_total_fee()?.as_u64());
+            total_weight = total_weight.saturating_add(ancestor.weight);
+        }
+        Ok(total_fees
+            .saturating_mul(1000)
+            .checked_div(total_weight)
+            .ok_or(TransactionError::ZeroWeight)?)
+    }
+
+    /// Sets the priority of a transaction to the higher of its own fee per byte and the best package fee per byte of
+    /// its unconfirmed descendants, so that a child paying for its parent keeps the parent from being evicted first.
+    fn update_package_priority(&mut self, tx_key: TransactionKey) -> Result<(), UnconfirmedPoolError> {
+        let Some(transaction) = self.tx_by_key.get(&tx_key) else {
+            return Ok(());
+        };
+        let mut fee_per_byte = transaction.fee_per_byte;
+        for key in self.get_descendants(&transaction.transaction) {
+            fee_per_byte = fee_per_byte.max(self.get_package_fee_per_byte(key)?);
+        }
+        let priority = FeePriority::with_fee_per_byte(&transaction.transaction, transaction.inserted_at, fee_per_byte);
+        if priority == transaction.priority {
+            return Ok(());
+        }
+        let transaction = self
+            .tx_by_key
+            .get_mut(&tx_key)
+            .ok_or(UnconfirmedPoolError::StorageOutofSync)?;
+        self.tx_by_priority.remove(&transaction.priority);
+        self.tx_by_priority.insert(priority.clone(), tx_key);
+        transaction.priority = priority;
+        Ok(())
+    }
+
     /// This will search the unconfirmed pool for the set of outputs and return true if all of them are found
     pub fn contains_all_outputs(&mut self, outputs: &[HashOutput]) -> bool {
         outputs.iter().all(|hash| self.txs_by_output.contains_key(hash))
@@ -560,6 +790,7 @@ impl UnconfirmedPool {
         self.txs_by_signature.clear();
         self.tx_by_priority.clear();
         self.txs_by_output.clear();
+        self.txs_by_input.clear();
         self.tx_by_key.drain().map(|(_, val)| val.transaction).collect()
     }
 
@@ -727,6 +958,23 @@ impl UnconfirmedPool {
             }
         }
 
+        for input in prioritized_transaction.transaction.body.inputs() {
+            let output_hash = input.output_hash();
+            if let Some(keys) = self.txs_by_input.get_mut(&output_hash) {
+                if let Some(pos) = keys.iter().position(|k| *k == tx_key) {
+                    keys.remove(pos);
+                }
+                if keys.is_empty() {
+                    self.txs_by_input.remove(&output_hash);
+                }
+            }
+        }
+
+        // The ancestors no longer benefit from the fee paid by this transaction
+        for key in self.get_ancestors(&prioritized_transaction.dependent_output_hashes) {
+            self.update_package_priority(key)?;
+        }
+
         trace!(
             target: LOG_TARGET,
             "Deleted transaction: {}",
@@ -755,6 +1003,34 @@ impl UnconfirmedPool {
         Ok(weights.iter().sum())
     }
 
+    /// Returns the fee per gram and insertion time of the pooled transactions that were included in the given block.
+    /// This must be called before the block's transactions are removed from the pool.
+    pub fn get_published_transaction_fees(
+        &self,
+        published_block: &Block,
+    ) -> Result<Vec<MinedTransactionFee>, UnconfirmedPoolError> {
+        let keys = published_block
+            .body
+            .kernels()
+            .iter()
+            .filter_map(|kernel| self.txs_by_signature.get(kernel.excess_sig.get_signature()))
+            .flatten()
+            .copied()
+            .collect::<HashSet<_>>();
+        let mut fees = Vec::with_capacity(keys.len());
+        for key in keys {
+            let tx = self.tx_by_key.get(&key).ok_or(UnconfirmedPoolError::StorageOutofSync)?;
+            if tx.weight == 0 {
+                continue;
+            }
+            fees.push(MinedTransactionFee {
+                fee_per_gram: tx.transaction.body.get_total_fee()? / tx.weight,
+                inserted_at: tx.inserted_at,
+            });
+        }
+        Ok(fees)
+    }
+
     pub fn get_fee_per_gram_stats(
         &self,
         count: usize,
@@ -827,6 +1103,9 @@ impl UnconfirmedPool {
             self.txs_by_output
                 .values()
                 .all(|tx_keys| tx_keys.iter().all(|tx_key| self.tx_by_key.contains_key(tx_key))) &&
+            self.txs_by_input
+                .values()
+                .all(|tx_keys| tx_keys.iter().all(|tx_key| self.tx_by_key.contains_key(tx_key))) &&
             self.txs_by_unique_id
                 .values()
                 .all(|tx_keys| tx_keys.iter().all(|tx_key| self.tx_by_key.contains_key(tx_key)))
@@ -844,6 +1123,7 @@ impl UnconfirmedPool {
         let (old, new) = shrink_hashmap(&mut self.tx_by_key);
         shrink_hashmap(&mut self.txs_by_signature);
         shrink_hashmap(&mut self.txs_by_output);
+        shrink_hashmap(&mut self.txs_by_input);
         shrink_hashmap(&mut self.txs_by_unique_id);
 
         if old > new {
@@ -943,6 +1223,7 @@ mod test {
             storage_capacity: 4,
             weight_tx_skip_count: 3,
             min_fee: 0,
+            ..Default::default()
         });
 
         let tx_weight = TransactionWeight::latest();
@@ -1042,6 +1323,7 @@ mod test {
             storage_capacity: 4,
             weight_tx_skip_count: 3,
             min_fee: 0,
+            ..Default::default()
         });
 
         let tx_weight = TransactionWeight::latest();
@@ -1102,6 +1384,7 @@ mod test {
             storage_capacity: 10,
             weight_tx_skip_count: 3,
             min_fee: 0,
+            ..Default::default()
         });
         unconfirmed_pool
             .insert_many(
@@ -1178,6 +1461,7 @@ mod test {
             storage_capacity: 10,
             weight_tx_skip_count: 3,
             min_fee: 0,
+            ..Default::default()
         });
         unconfirmed_pool
             .insert_many(
@@ -1208,6 +1492,176 @@ mod test {
         assert!(unconfirmed_pool.check_data_consistency());
     }
 
+    #[tokio::test]
+    async fn test_replace_by_fee() {
+        let key_manager = create_memory_db_key_manager().unwrap();
+        let tx1 = Arc::new(
+            tx!(MicroMinotari(5_000), fee: MicroMinotari(5), inputs: 2, outputs: 1, &key_manager)
+                .expect("Failed to get tx")
+                .0,
+        );
+        let mut same_fee = tx!(MicroMinotari(5_000), fee: MicroMinotari(5), inputs: 2, outputs: 1, &key_manager)
+            .expect("Failed to get tx")
+            .0;
+        let mut higher_fee = tx!(MicroMinotari(5_000), fee: MicroMinotari(50), inputs: 2, outputs: 1, &key_manager)
+            .expect("Failed to get tx")
+            .0;
+        // Both transactions spend an input of tx1
+        for tx in [&mut same_fee, &mut higher_fee] {
+            let mut inputs = tx.body.inputs().clone();
+            inputs[0] = tx1.body.inputs()[1].clone();
+            tx.body = AggregateBody::new(inputs, tx.body().outputs().clone(), tx.body().kernels().clone());
+        }
+
+        let tx_weight = TransactionWeight::latest();
+        let mut unconfirmed_pool = UnconfirmedPool::new(UnconfirmedPoolConfig {
+            min_fee: 0,
+            ..Default::default()
+        });
+        unconfirmed_pool
+            .insert_many(vec![tx1.clone()], &tx_weight)
+            .expect("Failed to insert many");
+        let tx1_key = unconfirmed_pool.txs_by_signature[tx1.body.kernels()[0].excess_sig.get_signature()][0];
+
+        assert_eq!(
+            unconfirmed_pool.check_replacement(&tx1, None, &tx_weight).unwrap(),
+            ReplacementCheck::NoConflicts
+        );
+        assert!(matches!(
+            unconfirmed_pool.check_replacement(&same_fee, None, &tx_weight).unwrap(),
+            ReplacementCheck::Reject(_)
+        ));
+        assert_eq!(
+            unconfirmed_pool
+                .check_replacement(&higher_fee, None, &tx_weight)
+                .unwrap(),
+            ReplacementCheck::Replace(vec![tx1_key])
+        );
+
+        // The replacement must also pay for the bandwidth it uses
+        unconfirmed_pool.config.min_replacement_fee_per_gram_increase = 50;
+        assert!(matches!(
+            unconfirmed_pool
+                .check_replacement(&higher_fee, None, &tx_weight)
+                .unwrap(),
+            ReplacementCheck::Reject(_)
+        ));
+        unconfirmed_pool.config.min_replacement_fee_per_gram_increase = 1;
+        unconfirmed_pool.config.max_replaced_transactions = 0;
+        assert!(matches!(
+            unconfirmed_pool
+                .check_replacement(&higher_fee, None, &tx_weight)
+                .unwrap(),
+            ReplacementCheck::Reject(_)
+        ));
+
+        assert!(unconfirmed_pool.check_data_consistency());
+    }
+
+    #[tokio::test]
+    async fn test_child_pays_for_parent() {
+        let key_manager = create_memory_db_key_manager().unwrap();
+        let (child, child_inputs, _) =
+            tx!(MicroMinotari(5_000), fee: MicroMinotari(50), inputs: 1, outputs: 1, &key_manager)
+                .expect("Failed to get tx");
+        let mut parent = tx!(MicroMinotari(5_000), fee: MicroMinotari(1), inputs: 1, outputs: 1, &key_manager)
+            .expect("Failed to get tx")
+            .0;
+        let other = Arc::new(
+            tx!(MicroMinotari(5_000), fee: MicroMinotari(10), inputs: 1, outputs: 1, &key_manager)
+                .expect("Failed to get tx")
+                .0,
+        );
+        // The parent creates the output that the child spends
+        let parent_output = child_inputs[0].to_transaction_output(&key_manager).await.unwrap();
+        let parent_output_hash = parent_output.hash();
+        parent.body = AggregateBody::new(
+            parent.body().inputs().clone(),
+            vec![parent_output],
+            parent.body().kernels().clone(),
+        );
+        let parent = Arc::new(parent);
+        let child = Arc::new(child);
+
+        let tx_weight = TransactionWeight::latest();
+        let mut unconfirmed_pool = UnconfirmedPool::new(UnconfirmedPoolConfig {
+            min_fee: 0,
+            ..Default::default()
+        });
+        unconfirmed_pool
+            .insert_many(vec![parent.clone(), other.clone()], &tx_weight)
+            .expect("Failed to insert many");
+        let key_of = |pool: &UnconfirmedPool, tx: &Transaction| {
+            pool.txs_by_signature[tx.body.kernels()[0].excess_sig.get_signature()][0]
+        };
+        let parent_key = key_of(&unconfirmed_pool, &parent);
+        let other_key = key_of(&unconfirmed_pool, &other);
+        assert_eq!(
+            unconfirmed_pool.lowest_priority().unwrap(),
+            &unconfirmed_pool.tx_by_key[&parent_key].priority
+        );
+
+        unconfirmed_pool
+            .insert(child.clone(), Some(vec![parent_output_hash]), &tx_weight)
+            .unwrap();
+        // The child raises the priority of the parent above the unrelated transaction
+        assert_eq!(
+            unconfirmed_pool.lowest_priority().unwrap(),
+            &unconfirmed_pool.tx_by_key[&other_key].priority
+        );
+        assert!(unconfirmed_pool.check_data_consistency());
+
+        let child_key = key_of(&unconfirmed_pool, &child);
+        unconfirmed_pool.remove_transaction(child_key).unwrap();
+        assert_eq!(
+            unconfirmed_pool.lowest_priority().unwrap(),
+            &unconfirmed_pool.tx_by_key[&parent_key].priority
+        );
+        assert!(unconfirmed_pool.check_data_consistency());
+    }
+
+    #[tokio::test]
+    async fn test_package_size_limit() {
+        let key_manager = create_memory_db_key_manager().unwrap();
+        let (child, child_inputs, _) =
+            tx!(MicroMinotari(5_000), fee: MicroMinotari(50), inputs: 1, outputs: 1, &key_manager)
+                .expect("Failed to get tx");
+        let mut parent = tx!(MicroMinotari(5_000), fee: MicroMinotari(1), inputs: 1, outputs: 1, &key_manager)
+            .expect("Failed to get tx")
+            .0;
+        let parent_output = child_inputs[0].to_transaction_output(&key_manager).await.unwrap();
+        let parent_output_hash = parent_output.hash();
+        parent.body = AggregateBody::new(
+            parent.body().inputs().clone(),
+            vec![parent_output],
+            parent.body().kernels().clone(),
+        );
+
+        let tx_weight = TransactionWeight::latest();
+        let mut unconfirmed_pool = UnconfirmedPool::new(UnconfirmedPoolConfig {
+            min_fee: 0,
+            max_package_size: 2,
+            ..Default::default()
+        });
+        unconfirmed_pool.insert(Arc::new(parent), None, &tx_weight).unwrap();
+        let dependent_outputs = [parent_output_hash];
+        assert!(!unconfirmed_pool.exceeds_package_limit(Some(&dependent_outputs), &[]));
+
+        let child = Arc::new(child);
+        unconfirmed_pool
+            .insert(child.clone(), Some(dependent_outputs.to_vec()), &tx_weight)
+            .unwrap();
+        // A second child would give the parent two descendants
+        assert!(unconfirmed_pool.exceeds_package_limit(Some(&dependent_outputs), &[]));
+        // unless it replaces the first child
+        let child_key = unconfirmed_pool.txs_by_signature[child.body.kernels()[0].excess_sig.get_signature()][0];
+        assert!(!unconfirmed_pool.exceeds_package_limit(Some(&dependent_outputs), &[child_key]));
+
+        unconfirmed_pool.config.max_package_size = 1;
+        assert!(unconfirmed_pool.exceeds_package_limit(Some(&dependent_outputs), &[child_key]));
+        assert!(!unconfirmed_pool.exceeds_package_limit(None, &[]));
+    }
+
     #[tokio::test]
     async fn test_multiple_transactions_with_same_outputs_in_mempool() {
         let key_manager = create_memory_db_key_manager().unwrap();
@@ -1233,6 +1687,7 @@ mod test {
             storage_capacity: 10,
             weight_tx_skip_count: 3,
             min_fee: 0,
+            ..Default::default()
         });
         let txns = vec![
             Arc::new(tx1.clone()),
@@ -1380,5 +1835,31 @@ mod test {
             let stats = unconfirmed_pool.get_fee_per_gram_stats(2, 2000).unwrap();
             assert_eq!(stats, expected_stats);
         }
+
+        #[tokio::test]
+        async fn it_returns_the_fees_of_published_transactions() {
+            let key_manager = create_memory_db_key_manager().unwrap();
+            let consensus = create_consensus_rules();
+            let (tx1, _, _) = tx!(MicroMinotari(150_000), fee: MicroMinotari(10), inputs: 1, outputs: 1, &key_manager)
+                .expect("Failed to get tx");
+            let (tx2, _, _) = tx!(MicroMinotari(150_000), fee: MicroMinotari(5), inputs: 1, outputs: 1, &key_manager)
+                .expect("Failed to get tx");
+            let tx1 = Arc::new(tx1);
+            let tx2 = Arc::new(tx2);
+
+            let tx_weight = TransactionWeight::latest();
+            let mut unconfirmed_pool = UnconfirmedPool::new(UnconfirmedPoolConfig::default());
+            unconfirmed_pool
+                .insert_many(vec![tx1.clone(), tx2], &tx_weight)
+                .expect("Failed to insert many");
+
+            let published_block = create_orphan_block(0, vec![(*tx1).clone()], &consensus);
+            let fees = unconfirmed_pool
+                .get_published_transaction_fees(&published_block)
+                .unwrap();
+            assert_eq!(fees.len(), 1);
+            assert_eq!(fees[0].fee_per_gram, 10.into());
+            assert!(fees[0].inserted_at > 0);
+        }
     }
 }
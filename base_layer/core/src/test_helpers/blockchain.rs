@@ -44,6 +44,7 @@ use crate::{
     blocks::{Block, BlockAccumulatedData, BlockHeader, BlockHeaderAccumulatedData, ChainBlock, ChainHeader},
     chain_storage::{
         create_lmdb_database,
+        create_memory_database,
         BlockAddResult,
         BlockchainBackend,
         BlockchainDatabase,
@@ -57,8 +58,11 @@ use crate::{
         HorizonData,
         InputMinedInfo,
         LMDBDatabase,
+        MemoryDatabase,
         MmrTree,
+        OutputIndex,
         OutputMinedInfo,
+        OutputSmtDelta,
         Reorg,
         TemplateRegistrationEntry,
         Validators,
@@ -100,6 +104,60 @@ pub fn create_new_blockchain_with_network(network: Network) -> BlockchainDatabas
     create_custom_blockchain(consensus_manager)
 }
 
+/// Create a new blockchain database with the given config, containing the genesis block.
+pub fn create_new_blockchain_with_config(config: BlockchainDatabaseConfig) -> BlockchainDatabase<TempDatabase> {
+    let network = Network::LocalNet;
+    let consensus_constants = ConsensusConstantsBuilder::new(network).build();
+    let consensus_manager = ConsensusManager::builder(network)
+        .add_consensus_constants(consensus_constants)
+        .on_ties(ChainStrengthComparerBuilder::new().by_height().build())
+        .build()
+        .unwrap();
+    let validators = Validators::new(
+        MockValidator::new(true),
+        MockValidator::new(true),
+        MockValidator::new(true),
+    );
+    create_store_with_consensus_and_validators_and_config(
+        consensus_manager,
+        validators,
+        config,
+        Arc::new(RwLock::new(OutputSmt::new())),
+    )
+}
+
+/// Create a new blockchain database backed by an in-memory backend, containing the genesis block.
+pub fn create_new_memory_blockchain() -> BlockchainDatabase<MemoryDatabase> {
+    create_new_memory_blockchain_with_config(BlockchainDatabaseConfig::default())
+}
+
+/// Create a new blockchain database backed by an in-memory backend with the given config, containing the genesis block.
+pub fn create_new_memory_blockchain_with_config(
+    config: BlockchainDatabaseConfig,
+) -> BlockchainDatabase<MemoryDatabase> {
+    let network = Network::LocalNet;
+    let consensus_constants = ConsensusConstantsBuilder::new(network).build();
+    let consensus_manager = ConsensusManager::builder(network)
+        .add_consensus_constants(consensus_constants)
+        .on_ties(ChainStrengthComparerBuilder::new().by_height().build())
+        .build()
+        .unwrap();
+    let validators = Validators::new(
+        MockValidator::new(true),
+        MockValidator::new(true),
+        MockValidator::new(true),
+    );
+    BlockchainDatabase::new(
+        create_memory_database(consensus_manager.clone()),
+        consensus_manager.clone(),
+        validators,
+        config,
+        DifficultyCalculator::new(consensus_manager, Default::default()),
+        Arc::new(RwLock::new(OutputSmt::new())),
+    )
+    .unwrap()
+}
+
 /// Create a new custom blockchain database containing no blocks.
 pub fn create_custom_blockchain(rules: ConsensusManager) -> BlockchainDatabase<TempDatabase> {
     let validators = Validators::new(
@@ -424,6 +482,22 @@ impl BlockchainBackend for TempDatabase {
             .fetch_template_registrations(start_height, end_height)
     }
 
+    fn fetch_outputs_by_index(
+        &self,
+        index: &OutputIndex,
+        start_height: u64,
+        end_height: u64,
+    ) -> Result<Vec<OutputMinedInfo>, ChainStorageError> {
+        self.db
+            .as_ref()
+            .unwrap()
+            .fetch_outputs_by_index(index, start_height, end_height)
+    }
+
+    fn fetch_output_smt_delta(&self, height: u64) -> Result<Option<OutputSmtDelta>, ChainStorageError> {
+        self.db.as_ref().unwrap().fetch_output_smt_delta(height)
+    }
+
     fn calculate_tip_smt(&self) -> Result<OutputSmt, ChainStorageError> {
         self.db.as_ref().unwrap().calculate_tip_smt()
     }
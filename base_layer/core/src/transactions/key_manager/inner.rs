@@ -49,7 +49,7 @@ use tari_common_types::{
     },
     tari_address::TariAddress,
     types::{ComAndPubSignature, Commitment, PrivateKey, PublicKey, RangeProof, Signature},
-    wallet_types::WalletType,
+    wallet_types::{AccountId, WalletType, DEFAULT_ACCOUNT_ID},
 };
 use tari_comms::types::CommsDHKE;
 use tari_crypto::{
@@ -406,9 +406,10 @@ where TBackend: KeyManagerBackend<PublicKey> + 'static
 
                         // If we're trying to access any of the private keys, just say no bueno
                         if &TransactionKeyManagerBranch::Spend.get_branch_key() == branch {
-                            return wallet.private_spend_key.clone().ok_or(
-                                KeyManagerServiceError::ImportedPrivateKeyInaccessible(key_id.to_string()),
-                            );
+                            return wallet
+                                .private_spend_key
+                                .clone()
+                                .ok_or(KeyManagerServiceError::WatchOnlyWallet(key_id.to_string()));
                         }
                     },
                 }
@@ -457,9 +458,10 @@ where TBackend: KeyManagerBackend<PublicKey> + 'static
                         Ok(private_key)
                     },
                     WalletType::ProvidedKeys(wallet) => {
-                        let private_alpha = wallet.private_spend_key.clone().ok_or(
-                            KeyManagerServiceError::ImportedPrivateKeyInaccessible(key_id.to_string()),
-                        )?;
+                        let private_alpha = wallet
+                            .private_spend_key
+                            .clone()
+                            .ok_or(KeyManagerServiceError::WatchOnlyWallet(key_id.to_string()))?;
 
                         let hasher =
                             DomainSeparatedHasher::<Blake2b<U64>, KeyManagerTransactionsHashDomain>::new_with_label(
@@ -519,6 +521,71 @@ where TBackend: KeyManagerBackend<PublicKey> + 'static
         Ok(KeyAndId { key_id, pub_key: key })
     }
 
+    pub async fn get_account_view_key(
+        &self,
+        account_id: AccountId,
+    ) -> Result<KeyAndId<PublicKey>, KeyManagerServiceError> {
+        if account_id == DEFAULT_ACCOUNT_ID {
+            return self.get_view_key().await;
+        }
+        self.get_account_key(TransactionKeyManagerBranch::DataEncryption, account_id)
+            .await
+    }
+
+    pub async fn get_account_spend_key(
+        &self,
+        account_id: AccountId,
+    ) -> Result<KeyAndId<PublicKey>, KeyManagerServiceError> {
+        if account_id == DEFAULT_ACCOUNT_ID {
+            return self.get_spend_key().await;
+        }
+        self.get_account_key(TransactionKeyManagerBranch::Spend, account_id)
+            .await
+    }
+
+    /// Accounts other than the default account only exist in wallets that derive their keys from the seed, so that
+    /// every account key is derived from its own branch of the seed
+    async fn get_account_key(
+        &self,
+        branch: TransactionKeyManagerBranch,
+        account_id: AccountId,
+    ) -> Result<KeyAndId<PublicKey>, KeyManagerServiceError> {
+        if !matches!(*self.wallet_type, WalletType::DerivedKeys) {
+            return Err(KeyManagerServiceError::UnknownError(format!(
+                "Account {} keys can only be derived by a wallet with derived keys",
+                account_id
+            )));
+        }
+        let key_id = KeyId::Managed {
+            branch: branch.get_account_branch_key(account_id),
+            index: 0,
+        };
+        let pub_key = self.get_public_key_at_key_id(&key_id).await?;
+        Ok(KeyAndId { key_id, pub_key })
+    }
+
+    pub async fn get_account_script_key_id(
+        &self,
+        commitment_mask_key_id: &TariKeyId,
+        account_id: AccountId,
+    ) -> Result<TariKeyId, KeyManagerServiceError> {
+        if account_id == DEFAULT_ACCOUNT_ID {
+            return Ok(KeyId::Derived {
+                key: commitment_mask_key_id.into(),
+            });
+        }
+        let spend_key = self.get_account_spend_key(account_id).await?;
+        let private_alpha = self.get_private_key(&spend_key.key_id).await?;
+        let commitment_mask = self.get_private_key(commitment_mask_key_id).await?;
+        let hasher = DomainSeparatedHasher::<Blake2b<U64>, KeyManagerTransactionsHashDomain>::new_with_label(
+            HASHER_LABEL_STEALTH_KEY,
+        );
+        let hasher = hasher.chain(commitment_mask.as_bytes()).finalize();
+        let private_key = PrivateKey::from_uniform_bytes(hasher.as_ref())
+            .map_err(|_| KeyManagerServiceError::UnknownError("Invalid private key for Spend".to_string()))?;
+        self.import_key(private_key + private_alpha).await
+    }
+
     pub async fn get_next_commitment_mask_and_script_key(
         &self,
     ) -> Result<(KeyAndId<PublicKey>, KeyAndId<PublicKey>), KeyManagerServiceError> {
@@ -20,7 +20,7 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::str::FromStr;
+use std::{str::FromStr, sync::Arc};
 
 use blake2::Blake2b;
 use digest::consts::U64;
@@ -28,6 +28,7 @@ use strum_macros::EnumIter;
 use tari_common_types::{
     tari_address::TariAddress,
     types::{ComAndPubSignature, Commitment, PrivateKey, PublicKey, RangeProof, Signature},
+    wallet_types::{AccountId, WalletType},
 };
 use tari_comms::types::CommsDHKE;
 use tari_crypto::{hashing::DomainSeparatedHash, ristretto::RistrettoComSig};
@@ -107,6 +108,24 @@ pub trait TransactionKeyManagerInterface: KeyManagerInterface<PublicKey> {
 
     async fn get_comms_key(&self) -> Result<KeyAndId<PublicKey>, KeyManagerServiceError>;
 
+    /// Get the wallet type
+    async fn get_wallet_type(&self) -> Arc<WalletType>;
+
+    /// Gets the view key of a wallet account, the default account uses the wallet view key
+    async fn get_account_view_key(&self, account_id: AccountId) -> Result<KeyAndId<PublicKey>, KeyManagerServiceError>;
+
+    /// Gets the spend key of a wallet account, the default account uses the wallet spend key
+    async fn get_account_spend_key(&self, account_id: AccountId)
+        -> Result<KeyAndId<PublicKey>, KeyManagerServiceError>;
+
+    /// Gets the id of the stealth script key of an output with the given commitment mask that is spendable by the
+    /// spend key of a wallet account. Script keys of accounts other than the default account are imported.
+    async fn get_account_script_key_id(
+        &self,
+        commitment_mask_key_id: &TariKeyId,
+        account_id: AccountId,
+    ) -> Result<TariKeyId, KeyManagerServiceError>;
+
     async fn get_next_commitment_mask_and_script_key(
         &self,
     ) -> Result<(KeyAndId<PublicKey>, KeyAndId<PublicKey>), KeyManagerServiceError>;
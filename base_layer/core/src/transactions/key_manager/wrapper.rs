@@ -27,7 +27,7 @@ use digest::consts::U64;
 use tari_common_types::{
     tari_address::TariAddress,
     types::{ComAndPubSignature, Commitment, PrivateKey, PublicKey, RangeProof, Signature},
-    wallet_types::WalletType,
+    wallet_types::{AccountId, WalletType},
 };
 use tari_comms::types::CommsDHKE;
 use tari_crypto::{hashing::DomainSeparatedHash, ristretto::RistrettoComSig};
@@ -97,11 +97,6 @@ where TBackend: KeyManagerBackend<PublicKey> + 'static
             )?)),
         })
     }
-
-    /// Get the wallet type
-    pub async fn get_wallet_type(&self) -> Arc<WalletType> {
-        self.transaction_key_manager_inner.read().await.get_wallet_type()
-    }
 }
 
 #[async_trait::async_trait]
@@ -228,6 +223,41 @@ where TBackend: KeyManagerBackend<PublicKey> + 'static
         self.transaction_key_manager_inner.read().await.get_comms_key().await
     }
 
+    async fn get_wallet_type(&self) -> Arc<WalletType> {
+        self.transaction_key_manager_inner.read().await.get_wallet_type()
+    }
+
+    async fn get_account_view_key(&self, account_id: AccountId) -> Result<KeyAndId<PublicKey>, KeyManagerServiceError> {
+        self.transaction_key_manager_inner
+            .read()
+            .await
+            .get_account_view_key(account_id)
+            .await
+    }
+
+    async fn get_account_spend_key(
+        &self,
+        account_id: AccountId,
+    ) -> Result<KeyAndId<PublicKey>, KeyManagerServiceError> {
+        self.transaction_key_manager_inner
+            .read()
+            .await
+            .get_account_spend_key(account_id)
+            .await
+    }
+
+    async fn get_account_script_key_id(
+        &self,
+        commitment_mask_key_id: &TariKeyId,
+        account_id: AccountId,
+    ) -> Result<TariKeyId, KeyManagerServiceError> {
+        self.transaction_key_manager_inner
+            .read()
+            .await
+            .get_account_script_key_id(commitment_mask_key_id, account_id)
+            .await
+    }
+
     async fn get_next_commitment_mask_and_script_key(
         &self,
     ) -> Result<(KeyAndId<PublicKey>, KeyAndId<PublicKey>), KeyManagerServiceError> {
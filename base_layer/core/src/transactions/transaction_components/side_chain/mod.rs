@@ -21,7 +21,7 @@
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 mod sidechain_feature;
-pub use sidechain_feature::SideChainFeature;
+pub use sidechain_feature::{SideChainFeature, SideChainFeatureType};
 
 mod confidential_output;
 mod template_registration;
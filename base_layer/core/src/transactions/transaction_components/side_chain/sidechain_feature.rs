@@ -21,6 +21,8 @@
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 use borsh::{BorshDeserialize, BorshSerialize};
+use num_derive::FromPrimitive;
+use num_traits::FromPrimitive;
 use serde::{Deserialize, Serialize};
 
 use crate::transactions::transaction_components::{
@@ -37,6 +39,14 @@ pub enum SideChainFeature {
 }
 
 impl SideChainFeature {
+    pub fn feature_type(&self) -> SideChainFeatureType {
+        match self {
+            Self::ValidatorNodeRegistration(_) => SideChainFeatureType::ValidatorNodeRegistration,
+            Self::CodeTemplateRegistration(_) => SideChainFeatureType::CodeTemplateRegistration,
+            Self::ConfidentialOutput(_) => SideChainFeatureType::ConfidentialOutput,
+        }
+    }
+
     pub fn code_template_registration(&self) -> Option<&CodeTemplateRegistration> {
         match self {
             Self::CodeTemplateRegistration(v) => Some(v),
@@ -58,3 +68,25 @@ impl SideChainFeature {
         }
     }
 }
+
+/// The kind of a [SideChainFeature], without any of its data
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Deserialize, Serialize, FromPrimitive)]
+#[repr(u8)]
+pub enum SideChainFeatureType {
+    ValidatorNodeRegistration = 0,
+    CodeTemplateRegistration = 1,
+    ConfidentialOutput = 2,
+}
+
+impl SideChainFeatureType {
+    /// Returns a single byte that represents this SideChainFeatureType
+    pub fn as_byte(self) -> u8 {
+        self as u8
+    }
+
+    /// Returns the SideChainFeatureType that corresponds to the byte. If the byte does not correspond to any
+    /// SideChainFeatureType, None is returned.
+    pub fn from_byte(value: u8) -> Option<Self> {
+        FromPrimitive::from_u8(value)
+    }
+}
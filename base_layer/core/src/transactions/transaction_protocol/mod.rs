@@ -93,6 +93,7 @@ use thiserror::Error;
 
 use crate::transactions::{tari_amount::*, transaction_components::TransactionError};
 
+pub mod partially_signed;
 pub mod proto;
 pub mod recipient;
 pub mod sender;
@@ -0,0 +1,517 @@
+// Copyright 2024 The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+//! A versioned, self-describing container for a transaction that has been built but not yet signed. It allows the
+//! wallet that selects the inputs and negotiates with the recipient to be different from the (possibly air-gapped)
+//! wallet that holds the spend key:
+//!
+//! 1. The online wallet builds the transaction up to the point where only the sender signatures are missing and exports
+//!    it as a [PartiallySignedTransaction].
+//! 2. The offline wallet, restored from the same seed, checks the details and [signs](PartiallySignedTransaction::sign)
+//!    it.
+//! 3. The online wallet imports the signed transaction, finalises it and broadcasts it.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use tari_common::configuration::Network;
+use tari_common_types::{tari_address::TariAddress, transaction::TxId, types::CommitmentFactory};
+use tari_script::push_pubkey_script;
+use thiserror::Error;
+
+use crate::{
+    common::one_sided::{shared_secret_to_output_encryption_key, shared_secret_to_output_spending_key},
+    transactions::{
+        key_manager::TransactionKeyManagerInterface,
+        tari_amount::MicroMinotari,
+        transaction_components::{encrypted_data::PaymentId, EncryptedData, Transaction, TransactionError},
+        transaction_protocol::{sender::SenderTransactionProtocol, TransactionProtocolError},
+    },
+};
+
+/// The value of the `format` field identifying a partially signed Tari transaction
+pub const PARTIALLY_SIGNED_TRANSACTION_FORMAT: &str = "partially_signed_tari_transaction";
+/// The current version of the partially signed transaction format
+pub const PARTIALLY_SIGNED_TRANSACTION_VERSION: u32 = 1;
+
+#[derive(Debug, Error)]
+pub enum PartiallySignedTransactionError {
+    #[error("Not a partially signed Tari transaction (format `{0}`)")]
+    UnknownFormat(String),
+    #[error("Unsupported partially signed transaction version {0}")]
+    UnsupportedVersion(u32),
+    #[error("The transaction is for network {found}, expected {expected}")]
+    NetworkMismatch { expected: Network, found: Network },
+    #[error("The transaction is not ready to be signed")]
+    NotReadyForSigning,
+    #[error("The transaction details do not match the transaction being signed: {0}")]
+    DetailsMismatch(String),
+    #[error("The transaction has already been signed")]
+    AlreadySigned,
+    #[error("The transaction has not been signed")]
+    NotSigned,
+    #[error("The signed transaction is not valid: {0}")]
+    InvalidTransaction(String),
+    #[error("Transaction protocol error: {0}")]
+    TransactionProtocolError(#[from] TransactionProtocolError),
+    #[error("Serialization error: {0}")]
+    SerializationError(String),
+}
+
+/// How the payment to the recipient is made
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PaymentKind {
+    /// The recipient output was created by the sender; the recipient does not take part
+    OneSided,
+    /// The recipient output and partial signatures were negotiated with the recipient
+    Interactive,
+}
+
+impl fmt::Display for PaymentKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PaymentKind::OneSided => write!(f, "one-sided"),
+            PaymentKind::Interactive => write!(f, "interactive"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct PartiallySignedTransaction {
+    /// Always [PARTIALLY_SIGNED_TRANSACTION_FORMAT]
+    pub format: String,
+    pub version: u32,
+    pub network: Network,
+    pub tx_id: TxId,
+    pub kind: PaymentKind,
+    pub recipient: TariAddress,
+    /// The amount paid to the recipient, excluding change and fee
+    pub amount: MicroMinotari,
+    pub fee: MicroMinotari,
+    pub message: String,
+    /// The sender protocol, in the `Finalizing` state before signing and in the `FinalizedTransaction` state after
+    pub sender_protocol: SenderTransactionProtocol,
+}
+
+impl PartiallySignedTransaction {
+    /// Wraps a sender protocol that is waiting only for the sender signatures
+    pub fn new(
+        network: Network,
+        kind: PaymentKind,
+        recipient: TariAddress,
+        message: String,
+        sender_protocol: SenderTransactionProtocol,
+    ) -> Result<Self, PartiallySignedTransactionError> {
+        if !sender_protocol.is_finalizing() {
+            return Err(PartiallySignedTransactionError::NotReadyForSigning);
+        }
+        Ok(Self {
+            format: PARTIALLY_SIGNED_TRANSACTION_FORMAT.to_string(),
+            version: PARTIALLY_SIGNED_TRANSACTION_VERSION,
+            network,
+            tx_id: sender_protocol.get_tx_id()?,
+            kind,
+            recipient,
+            amount: sender_protocol.get_amount_to_recipient()?,
+            fee: sender_protocol.get_fee_amount()?,
+            message,
+            sender_protocol,
+        })
+    }
+
+    /// Checks that this is a partially signed transaction of a supported version for the given network
+    pub fn verify_format(&self, network: Network) -> Result<(), PartiallySignedTransactionError> {
+        if self.format != PARTIALLY_SIGNED_TRANSACTION_FORMAT {
+            return Err(PartiallySignedTransactionError::UnknownFormat(self.format.clone()));
+        }
+        if self.version != PARTIALLY_SIGNED_TRANSACTION_VERSION {
+            return Err(PartiallySignedTransactionError::UnsupportedVersion(self.version));
+        }
+        if self.network != network {
+            return Err(PartiallySignedTransactionError::NetworkMismatch {
+                expected: network,
+                found: self.network,
+            });
+        }
+        Ok(())
+    }
+
+    pub fn is_signed(&self) -> bool {
+        self.sender_protocol.is_finalized()
+    }
+
+    /// Adds the sender signatures using keys held by `key_manager`, which must be derived from the same seed as the
+    /// wallet that created the transaction
+    pub async fn sign<KM: TransactionKeyManagerInterface>(
+        &mut self,
+        key_manager: &KM,
+    ) -> Result<(), PartiallySignedTransactionError> {
+        if self.is_signed() {
+            return Err(PartiallySignedTransactionError::AlreadySigned);
+        }
+        if !self.sender_protocol.is_finalizing() {
+            return Err(PartiallySignedTransactionError::NotReadyForSigning);
+        }
+        // The details are what the signer is shown, so they must describe the transaction that is actually signed
+        if !self.sender_protocol.check_tx_id(self.tx_id) {
+            return Err(PartiallySignedTransactionError::DetailsMismatch("tx_id".to_string()));
+        }
+        if self.sender_protocol.get_amount_to_recipient()? != self.amount {
+            return Err(PartiallySignedTransactionError::DetailsMismatch("amount".to_string()));
+        }
+        if self.sender_protocol.get_fee_amount()? != self.fee {
+            return Err(PartiallySignedTransactionError::DetailsMismatch("fee".to_string()));
+        }
+        if self.sender_protocol.get_message()? != self.message {
+            return Err(PartiallySignedTransactionError::DetailsMismatch("message".to_string()));
+        }
+        if self.kind == PaymentKind::OneSided {
+            let _payment_id = self.verify_one_sided_recipient_output(key_manager).await?;
+        }
+        self.sender_protocol.finalize(key_manager).await?;
+        Ok(())
+    }
+
+    /// Derives the one-sided output for `recipient` from the sender offset key of the unsigned transaction and checks
+    /// that it is the recipient output of the transaction, paying `amount` to a one-sided or stealth script of
+    /// `recipient`. Returns the payment id carried by the output.
+    pub async fn verify_one_sided_recipient_output<KM: TransactionKeyManagerInterface>(
+        &self,
+        key_manager: &KM,
+    ) -> Result<PaymentId, PartiallySignedTransactionError> {
+        let mismatch = |field: &str| PartiallySignedTransactionError::DetailsMismatch(field.to_string());
+        let output = self
+            .sender_protocol
+            .get_recipient_output()?
+            .ok_or_else(|| mismatch("recipient"))?;
+        let sender_offset_key_id = self
+            .sender_protocol
+            .get_recipient_sender_offset_private_key()?
+            .ok_or_else(|| mismatch("recipient"))?;
+        let sender_offset_public_key = key_manager
+            .get_public_key_at_key_id(&sender_offset_key_id)
+            .await
+            .map_err(TransactionProtocolError::from)?;
+        if sender_offset_public_key != output.sender_offset_public_key {
+            return Err(mismatch("recipient"));
+        }
+
+        let view_key = self.recipient.public_view_key().ok_or_else(|| mismatch("recipient"))?;
+        let shared_secret = key_manager
+            .get_diffie_hellman_shared_secret(&sender_offset_key_id, view_key)
+            .await
+            .map_err(TransactionProtocolError::from)?;
+        let encryption_key = shared_secret_to_output_encryption_key(&shared_secret)
+            .map_err(|e| TransactionProtocolError::ConversionError(e.to_string()))?;
+        let commitment_mask = shared_secret_to_output_spending_key(&shared_secret)
+            .map_err(|e| TransactionProtocolError::ConversionError(e.to_string()))?;
+        let (value, encrypted_mask, payment_id) =
+            EncryptedData::decrypt_data(&encryption_key, &output.commitment, &output.encrypted_data)
+                .map_err(|_| mismatch("recipient"))?;
+        if encrypted_mask != commitment_mask {
+            return Err(mismatch("recipient"));
+        }
+        if value != self.amount {
+            return Err(mismatch("amount"));
+        }
+        let commitment_mask_key_id = key_manager
+            .import_key(commitment_mask)
+            .await
+            .map_err(TransactionProtocolError::from)?;
+        let commitment = key_manager
+            .get_commitment(&commitment_mask_key_id, &value.into())
+            .await
+            .map_err(TransactionProtocolError::from)?;
+        if commitment != output.commitment {
+            return Err(mismatch("amount"));
+        }
+
+        let spend_key = self.recipient.public_spend_key();
+        let stealth_script_key = key_manager
+            .stealth_address_script_spending_key(&commitment_mask_key_id, spend_key)
+            .await
+            .map_err(TransactionProtocolError::from)?;
+        if output.script != push_pubkey_script(spend_key) && output.script != push_pubkey_script(&stealth_script_key) {
+            return Err(mismatch("recipient"));
+        }
+        Ok(payment_id)
+    }
+
+    /// Verifies the kernel, metadata and script signatures of the signed transaction
+    pub fn verify_signatures(&self, factory: &CommitmentFactory) -> Result<(), PartiallySignedTransactionError> {
+        let invalid = |e: TransactionError| PartiallySignedTransactionError::InvalidTransaction(e.to_string());
+        let tx = self.transaction()?;
+        tx.body.verify_kernel_signatures().map_err(invalid)?;
+        for output in tx.body.outputs() {
+            output.verify_metadata_signature().map_err(invalid)?;
+        }
+        for input in tx.body.inputs() {
+            input.run_and_verify_script(factory, None).map_err(invalid)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the signed transaction
+    pub fn transaction(&self) -> Result<&Transaction, PartiallySignedTransactionError> {
+        self.sender_protocol
+            .get_transaction()
+            .map_err(|_| PartiallySignedTransactionError::NotSigned)
+    }
+
+    pub fn to_json(&self) -> Result<String, PartiallySignedTransactionError> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| PartiallySignedTransactionError::SerializationError(e.to_string()))
+    }
+
+    /// Parses a partially signed transaction and verifies its format for the given network
+    pub fn from_json(json: &str, network: Network) -> Result<Self, PartiallySignedTransactionError> {
+        let tx = serde_json::from_str::<Self>(json)
+            .map_err(|e| PartiallySignedTransactionError::SerializationError(e.to_string()))?;
+        tx.verify_format(network)?;
+        Ok(tx)
+    }
+}
+
+impl fmt::Display for PartiallySignedTransaction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} payment {} of {} to {} (fee {}, {})",
+            self.kind,
+            self.tx_id,
+            self.amount,
+            self.recipient,
+            self.fee,
+            if self.is_signed() { "signed" } else { "unsigned" }
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::rngs::OsRng;
+    use tari_common_types::{
+        key_branches::TransactionKeyManagerBranch,
+        tari_address::TariAddress,
+        types::{PrivateKey, PublicKey},
+    };
+    use tari_crypto::keys::{PublicKey as PK, SecretKey};
+    use tari_key_manager::key_manager_service::{KeyId, KeyManagerInterface};
+    use tari_script::{inputs, TariScript};
+
+    use super::*;
+    use crate::{
+        covenants::Covenant,
+        test_helpers::create_consensus_constants,
+        transactions::{
+            key_manager::{create_memory_db_key_manager, MemoryDbKeyManager},
+            test_helpers::{create_test_input, TestParams},
+            transaction_components::{OutputFeatures, WalletOutputBuilder},
+            transaction_protocol::{recipient::ReceiverTransactionProtocol, sender::TransactionSenderMessage},
+        },
+    };
+
+    const AMOUNT: MicroMinotari = MicroMinotari(500);
+
+    fn random_address() -> TariAddress {
+        TariAddress::new_dual_address_with_default_features(
+            PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng)),
+            PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng)),
+            Network::LocalNet,
+        )
+    }
+
+    /// Builds a one-sided payment to `recipient` the same way the wallet does, up to the sender signatures
+    async fn create_unsigned_sender_protocol(
+        recipient: &TariAddress,
+    ) -> (SenderTransactionProtocol, MemoryDbKeyManager) {
+        let key_manager = create_memory_db_key_manager().unwrap();
+        let consensus_constants = create_consensus_constants(0);
+        let change = TestParams::new(&key_manager).await;
+        let input = create_test_input(MicroMinotari(1200), 0, &key_manager, vec![]).await;
+        let script = push_pubkey_script(recipient.public_spend_key());
+        let mut builder = SenderTransactionProtocol::builder(consensus_constants.clone(), key_manager.clone());
+        builder
+            .with_lock_height(0)
+            .with_fee_per_gram(MicroMinotari(2))
+            .with_change_data(
+                TariScript::default(),
+                inputs!(change.script_key_pk),
+                change.script_key_id.clone(),
+                change.commitment_mask_key_id.clone(),
+                Covenant::default(),
+                TariAddress::default(),
+            )
+            .with_input(input)
+            .await
+            .unwrap()
+            .with_recipient_data(
+                script.clone(),
+                OutputFeatures::default(),
+                Covenant::default(),
+                MicroMinotari::zero(),
+                AMOUNT,
+            )
+            .await
+            .unwrap();
+        let mut stp = builder.build().await.unwrap();
+
+        let sender_offset = key_manager
+            .get_next_key(TransactionKeyManagerBranch::OneSidedSenderOffset.get_branch_key())
+            .await
+            .unwrap();
+        stp.change_recipient_sender_offset_private_key(sender_offset.key_id.clone())
+            .unwrap();
+        let _single_round_sender_data = stp.build_single_round_message(&key_manager).await.unwrap();
+        let shared_secret = key_manager
+            .get_diffie_hellman_shared_secret(&sender_offset.key_id, recipient.public_view_key().unwrap())
+            .await
+            .unwrap();
+        let commitment_mask_key_id = key_manager
+            .import_key(shared_secret_to_output_spending_key(&shared_secret).unwrap())
+            .await
+            .unwrap();
+        let encryption_key_id = key_manager
+            .import_key(shared_secret_to_output_encryption_key(&shared_secret).unwrap())
+            .await
+            .unwrap();
+        let sender_message = TransactionSenderMessage::new_single_round_message(
+            stp.get_single_round_message(&key_manager).await.unwrap(),
+        );
+        let output = WalletOutputBuilder::new(AMOUNT, commitment_mask_key_id)
+            .with_features(OutputFeatures::default())
+            .with_script(script)
+            .encrypt_data_for_recovery(&key_manager, Some(&encryption_key_id), PaymentId::Empty)
+            .await
+            .unwrap()
+            .with_input_data(Default::default())
+            .with_sender_offset_public_key(sender_offset.pub_key)
+            .with_script_key(KeyId::Zero)
+            .sign_as_sender_and_receiver_verified(&key_manager, &sender_offset.key_id, recipient)
+            .await
+            .unwrap()
+            .try_build(&key_manager)
+            .await
+            .unwrap();
+        let rtp = ReceiverTransactionProtocol::new(sender_message, output, &key_manager, &consensus_constants).await;
+        stp.add_presigned_recipient_info(rtp.get_signed_data().unwrap().clone())
+            .unwrap();
+        (stp, key_manager)
+    }
+
+    #[tokio::test]
+    async fn it_round_trips_and_checks_the_format() {
+        let recipient = random_address();
+        let (stp, _) = create_unsigned_sender_protocol(&recipient).await;
+        let tx = PartiallySignedTransaction::new(
+            Network::LocalNet,
+            PaymentKind::OneSided,
+            recipient,
+            "offline".to_string(),
+            stp,
+        )
+        .unwrap();
+        assert!(!tx.is_signed());
+        assert!(tx.transaction().is_err());
+
+        let json = tx.to_json().unwrap();
+        assert_eq!(
+            PartiallySignedTransaction::from_json(&json, Network::LocalNet).unwrap(),
+            tx
+        );
+        assert!(matches!(
+            PartiallySignedTransaction::from_json(&json, Network::MainNet),
+            Err(PartiallySignedTransactionError::NetworkMismatch { .. })
+        ));
+
+        let mut newer = tx.clone();
+        newer.version = PARTIALLY_SIGNED_TRANSACTION_VERSION + 1;
+        assert!(matches!(
+            newer.verify_format(Network::LocalNet),
+            Err(PartiallySignedTransactionError::UnsupportedVersion(_))
+        ));
+        let mut other = tx;
+        other.format = "psbt".to_string();
+        assert!(matches!(
+            other.verify_format(Network::LocalNet),
+            Err(PartiallySignedTransactionError::UnknownFormat(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn it_signs_with_the_sender_keys() {
+        let recipient = random_address();
+        let (stp, key_manager) = create_unsigned_sender_protocol(&recipient).await;
+        let mut tx =
+            PartiallySignedTransaction::new(Network::LocalNet, PaymentKind::OneSided, recipient, String::new(), stp)
+                .unwrap();
+        let unsigned = tx.clone();
+        tx.sign(&key_manager).await.unwrap();
+        assert!(tx.is_signed());
+        assert_eq!(tx.transaction().unwrap().body.kernels()[0].fee, tx.fee);
+        tx.verify_signatures(&CommitmentFactory::default()).unwrap();
+        assert!(matches!(
+            tx.sign(&key_manager).await,
+            Err(PartiallySignedTransactionError::AlreadySigned)
+        ));
+
+        // The signed transaction is the one the unsigned protocol describes, and no other
+        unsigned
+            .sender_protocol
+            .verify_finalized_transaction(tx.transaction().unwrap(), &key_manager)
+            .await
+            .unwrap();
+        let (mut other, other_key_manager) = create_unsigned_sender_protocol(&random_address()).await;
+        other.finalize(&other_key_manager).await.unwrap();
+        assert!(unsigned
+            .sender_protocol
+            .verify_finalized_transaction(other.get_transaction().unwrap(), &key_manager)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn it_does_not_sign_tampered_details() {
+        let recipient = random_address();
+        let (stp, key_manager) = create_unsigned_sender_protocol(&recipient).await;
+        let mut tx =
+            PartiallySignedTransaction::new(Network::LocalNet, PaymentKind::OneSided, recipient, String::new(), stp)
+                .unwrap();
+        let untampered = tx.clone();
+        tx.fee = MicroMinotari(1);
+        assert!(matches!(
+            tx.sign(&key_manager).await,
+            Err(PartiallySignedTransactionError::DetailsMismatch(_))
+        ));
+        assert!(!tx.is_signed());
+
+        let mut tx = untampered.clone();
+        tx.message = "pay the attacker".to_string();
+        assert!(matches!(
+            tx.sign(&key_manager).await,
+            Err(PartiallySignedTransactionError::DetailsMismatch(field)) if field == "message"
+        ));
+
+        // The output pays the original recipient, not the one shown to the signer
+        let mut tx = untampered;
+        tx.recipient = random_address();
+        assert!(matches!(
+            tx.sign(&key_manager).await,
+            Err(PartiallySignedTransactionError::DetailsMismatch(field)) if field == "recipient"
+        ));
+        assert!(!tx.is_signed());
+    }
+
+    #[tokio::test]
+    async fn it_rejects_protocols_that_are_not_ready_for_signing() {
+        let err = PartiallySignedTransaction::new(
+            Network::LocalNet,
+            PaymentKind::Interactive,
+            TariAddress::default(),
+            String::new(),
+            SenderTransactionProtocol::new_placeholder(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, PartiallySignedTransactionError::NotReadyForSigning));
+    }
+}
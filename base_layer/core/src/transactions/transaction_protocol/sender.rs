@@ -377,6 +377,86 @@ impl SenderTransactionProtocol {
         }
     }
 
+    /// Returns the output created for the recipient, once it has been received or presigned
+    pub fn get_recipient_output(&self) -> Result<Option<&TransactionOutput>, TPE> {
+        match &self.state {
+            SenderState::Initializing(info) |
+            SenderState::Finalizing(info) |
+            SenderState::SingleRoundMessageReady(info) |
+            SenderState::CollectingSingleSignature(info) => Ok(info.recipient_output.as_ref()),
+            SenderState::FinalizedTransaction(_) => Err(TPE::InvalidStateError),
+            SenderState::Failed(_) => Err(TPE::InvalidStateError),
+        }
+    }
+
+    /// Returns the message sent to the recipient
+    pub fn get_message(&self) -> Result<&str, TPE> {
+        match &self.state {
+            SenderState::Initializing(info) |
+            SenderState::Finalizing(info) |
+            SenderState::SingleRoundMessageReady(info) |
+            SenderState::CollectingSingleSignature(info) => Ok(&info.text_message),
+            SenderState::FinalizedTransaction(_) => Err(TPE::InvalidStateError),
+            SenderState::Failed(_) => Err(TPE::InvalidStateError),
+        }
+    }
+
+    /// Checks that a transaction that was finalized elsewhere, e.g. by an offline signer, spends exactly the inputs
+    /// and creates exactly the outputs of this protocol, with the same fee and lock height. The protocol must be in the
+    /// `Finalizing` state.
+    pub async fn verify_finalized_transaction<KM: TransactionKeyManagerInterface>(
+        &self,
+        transaction: &Transaction,
+        key_manager: &KM,
+    ) -> Result<(), TPE> {
+        let SenderState::Finalizing(info) = &self.state else {
+            return Err(TPE::InvalidStateError);
+        };
+        let mut expected_inputs = Vec::with_capacity(info.inputs.len());
+        for input in &info.inputs {
+            expected_inputs.push(input.output.commitment(key_manager).await?);
+        }
+        let mut inputs = Vec::with_capacity(transaction.body.inputs().len());
+        for input in transaction.body.inputs() {
+            inputs.push(input.commitment()?.clone());
+        }
+        expected_inputs.sort();
+        inputs.sort();
+        if inputs != expected_inputs {
+            return Err(TPE::ValidationError(
+                "The transaction does not spend the inputs of the protocol".into(),
+            ));
+        }
+
+        let mut expected_outputs = Vec::with_capacity(info.outputs.len() + 2);
+        for output in info.outputs.iter().chain(info.change_output.iter()) {
+            expected_outputs.push(output.output.commitment(key_manager).await?);
+        }
+        if let Some(received_output) = &info.recipient_output {
+            expected_outputs.push(received_output.commitment.clone());
+        }
+        let mut outputs = transaction
+            .body
+            .outputs()
+            .iter()
+            .map(|o| o.commitment.clone())
+            .collect::<Vec<_>>();
+        expected_outputs.sort();
+        outputs.sort();
+        if outputs != expected_outputs {
+            return Err(TPE::ValidationError(
+                "The transaction does not create the outputs of the protocol".into(),
+            ));
+        }
+
+        match transaction.body.kernels() {
+            [kernel] if kernel.fee == info.metadata.fee && kernel.lock_height == info.metadata.lock_height => Ok(()),
+            _ => Err(TPE::ValidationError(
+                "The transaction kernel does not match the protocol".into(),
+            )),
+        }
+    }
+
     /// Build the sender's message for the single-round protocol (one recipient) and move to next State
     pub async fn build_single_round_message<KM: TransactionKeyManagerInterface>(
         &mut self,
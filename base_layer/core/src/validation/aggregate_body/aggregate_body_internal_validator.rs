@@ -101,11 +101,58 @@ impl AggregateBodyInternalConsistencyValidator {
         total_reward: Option<MicroMinotari>,
         prev_header: Option<HashOutput>,
         height: u64,
+    ) -> Result<(), ValidationError> {
+        self.validate_inner(body, tx_offset, script_offset, total_reward, prev_header, height, true)
+    }
+
+    /// Performs the same checks as [validate](Self::validate), except for the kernel signatures, metadata signatures
+    /// and range proofs. This must only be used for bodies that have already passed
+    /// [verify_stateless](Self::verify_stateless).
+    pub fn validate_pre_verified(
+        &self,
+        body: &AggregateBody,
+        tx_offset: &PrivateKey,
+        script_offset: &PrivateKey,
+        total_reward: Option<MicroMinotari>,
+        prev_header: Option<HashOutput>,
+        height: u64,
+    ) -> Result<(), ValidationError> {
+        self.validate_inner(body, tx_offset, script_offset, total_reward, prev_header, height, false)
+    }
+
+    /// Verifies the kernel signatures, metadata signatures and range proofs of the given bodies. These checks do not
+    /// depend on the chain state, so they can be done ahead of the rest of the validation. The range proofs of all the
+    /// bodies are verified in a single batch.
+    pub fn verify_stateless(&self, bodies: &[&AggregateBody]) -> Result<(), ValidationError> {
+        for body in bodies {
+            verify_kernel_signatures(body)?;
+            verify_metadata_signatures(body)?;
+        }
+        if !self.bypass_range_proof_verification {
+            trace!(target: LOG_TARGET, "Checking range proofs of {} bodies", bodies.len());
+            let outputs = bodies.iter().flat_map(|b| b.outputs()).collect::<Vec<_>>();
+            batch_verify_range_proofs(&self.factories.range_proof, &outputs)?;
+        }
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn validate_inner(
+        &self,
+        body: &AggregateBody,
+        tx_offset: &PrivateKey,
+        script_offset: &PrivateKey,
+        total_reward: Option<MicroMinotari>,
+        prev_header: Option<HashOutput>,
+        height: u64,
+        verify_signatures_and_proofs: bool,
     ) -> Result<(), ValidationError> {
         let total_reward = total_reward.unwrap_or(MicroMinotari::zero());
 
         // old internal validator
-        verify_kernel_signatures(body)?;
+        if verify_signatures_and_proofs {
+            verify_kernel_signatures(body)?;
+        }
 
         let constants = self.consensus_manager.consensus_constants(height);
 
@@ -130,10 +177,12 @@ impl AggregateBodyInternalConsistencyValidator {
         let total_offset = self.factories.commitment.commit_value(tx_offset, total_reward.0);
         validate_kernel_sum(body, total_offset, &self.factories.commitment)?;
 
-        if !self.bypass_range_proof_verification {
-            validate_range_proofs(body, &self.factories.range_proof)?;
+        if verify_signatures_and_proofs {
+            if !self.bypass_range_proof_verification {
+                validate_range_proofs(body, &self.factories.range_proof)?;
+            }
+            verify_metadata_signatures(body)?;
         }
-        verify_metadata_signatures(body)?;
 
         let script_offset_g = PublicKey::from_secret_key(script_offset);
         validate_script_and_script_offset(body, script_offset_g, &self.factories.commitment, prev_header, height)?;
@@ -69,6 +69,23 @@ impl BlockBodyFullValidator {
         block: &Block,
         metadata_option: Option<&ChainMetadata>,
         smt: Arc<RwLock<OutputSmt>>,
+    ) -> Result<Block, ValidationError> {
+        self.validate_inner(backend, block, metadata_option, smt, true)
+    }
+
+    /// Verifies the parts of the given blocks that do not depend on the chain state, i.e. the kernel signatures,
+    /// metadata signatures and range proofs. The range proofs of all the blocks are verified in one batch.
+    pub fn verify_stateless(&self, blocks: &[Block]) -> Result<(), ValidationError> {
+        self.block_internal_validator.verify_stateless(blocks)
+    }
+
+    fn validate_inner<B: BlockchainBackend>(
+        &self,
+        backend: &B,
+        block: &Block,
+        metadata_option: Option<&ChainMetadata>,
+        smt: Arc<RwLock<OutputSmt>>,
+        verify_signatures_and_proofs: bool,
     ) -> Result<Block, ValidationError> {
         if let Some(metadata) = metadata_option {
             validate_block_metadata(block, metadata)?;
@@ -83,7 +100,11 @@ impl BlockBodyFullValidator {
         let block = Block::new(block.header.clone(), body);
 
         // validate the internal consistency of the block body
-        self.block_internal_validator.validate(&block)?;
+        if verify_signatures_and_proofs {
+            self.block_internal_validator.validate(&block)?;
+        } else {
+            self.block_internal_validator.validate_pre_verified(&block)?;
+        }
 
         // validate the merkle mountain range roots+
         let mut output_smt = smt.write().map_err(|e| {
@@ -117,6 +138,19 @@ impl<B: BlockchainBackend> BlockBodyValidator<B> for BlockBodyFullValidator {
     fn validate_body(&self, backend: &B, block: &Block, smt: Arc<RwLock<OutputSmt>>) -> Result<Block, ValidationError> {
         self.validate(backend, block, None, smt)
     }
+
+    fn verify_stateless(&self, blocks: &[Block]) -> Result<(), ValidationError> {
+        BlockBodyFullValidator::verify_stateless(self, blocks)
+    }
+
+    fn validate_pre_verified_body(
+        &self,
+        backend: &B,
+        block: &Block,
+        smt: Arc<RwLock<OutputSmt>>,
+    ) -> Result<Block, ValidationError> {
+        self.validate_inner(backend, block, None, smt, false)
+    }
 }
 
 fn validate_block_metadata(block: &Block, metadata: &ChainMetadata) -> Result<(), ValidationError> {
@@ -63,10 +63,26 @@ impl BlockBodyInternalConsistencyValidator {
 
     pub fn validate(&self, block: &Block) -> Result<(), ValidationError> {
         validate_block_specific_checks(block, &self.consensus_manager, &self.factories)?;
-        validate_block_aggregate_body(block, &self.aggregate_body_validator, &self.consensus_manager)?;
+        validate_block_aggregate_body(block, &self.aggregate_body_validator, &self.consensus_manager, true)?;
 
         Ok(())
     }
+
+    /// Validates the block without verifying the kernel signatures, metadata signatures and range proofs, which must
+    /// already have been checked using [verify_stateless](Self::verify_stateless).
+    pub fn validate_pre_verified(&self, block: &Block) -> Result<(), ValidationError> {
+        validate_block_specific_checks(block, &self.consensus_manager, &self.factories)?;
+        validate_block_aggregate_body(block, &self.aggregate_body_validator, &self.consensus_manager, false)?;
+
+        Ok(())
+    }
+
+    /// Verifies the kernel signatures, metadata signatures and range proofs of all the given blocks, batching the
+    /// range proofs of all the blocks together.
+    pub fn verify_stateless(&self, blocks: &[Block]) -> Result<(), ValidationError> {
+        let bodies = blocks.iter().map(|b| &b.body).collect::<Vec<_>>();
+        self.aggregate_body_validator.verify_stateless(&bodies)
+    }
 }
 
 impl InternalConsistencyValidator for BlockBodyInternalConsistencyValidator {
@@ -104,6 +120,7 @@ fn validate_block_aggregate_body(
     block: &Block,
     validator: &AggregateBodyInternalConsistencyValidator,
     consensus_manager: &ConsensusManager,
+    verify_signatures_and_proofs: bool,
 ) -> Result<(), ValidationError> {
     let offset = &block.header.total_kernel_offset;
     let script_offset = &block.header.total_script_offset;
@@ -118,8 +135,8 @@ fn validate_block_aggregate_body(
             );
             ValidationError::CoinbaseExceedsMaxLimit
         })?;
-    validator
-        .validate(
+    let result = if verify_signatures_and_proofs {
+        validator.validate(
             &block.body,
             offset,
             script_offset,
@@ -127,15 +144,25 @@ fn validate_block_aggregate_body(
             Some(block.header.prev_hash),
             block.header.height,
         )
-        .map_err(|err| {
-            warn!(
-                target: LOG_TARGET,
-                "Validation failed on block:{}:{:?}",
-                block.hash().to_hex(),
-                err
-            );
+    } else {
+        validator.validate_pre_verified(
+            &block.body,
+            offset,
+            script_offset,
+            Some(total_coinbase),
+            Some(block.header.prev_hash),
+            block.header.height,
+        )
+    };
+    result.map_err(|err| {
+        warn!(
+            target: LOG_TARGET,
+            "Validation failed on block:{}:{:?}",
+            block.hash().to_hex(),
             err
-        })?;
+        );
+        err
+    })?;
 
     Ok(())
 }
@@ -0,0 +1,90 @@
+// Copyright 2024 The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+use std::{panic, slice, thread};
+
+use log::*;
+
+use crate::{
+    blocks::Block,
+    validation::{BlockBodyValidator, ValidationError},
+};
+
+const LOG_TARGET: &str = "c::val::block_body_concurrent_verification";
+
+/// The reason that [verify_stateless_concurrently] failed
+#[derive(Debug)]
+pub enum ConcurrentVerificationError {
+    /// The block at `index` failed verification on its own
+    InvalidBlock { index: usize, error: ValidationError },
+    /// The blocks at indexes `start` to `end` (inclusive) failed verification together, but each of them passed on its
+    /// own, so the failure cannot be attributed to a block
+    Inconclusive {
+        start: usize,
+        end: usize,
+        error: ValidationError,
+    },
+}
+
+/// Verifies the state independent parts of the given blocks (see [BlockBodyValidator::verify_stateless]), splitting
+/// the blocks into `concurrency` chunks that are each verified on their own thread. If a chunk fails, its blocks are
+/// verified one at a time to find the first invalid block.
+pub fn verify_stateless_concurrently<B, V>(
+    validator: &V,
+    blocks: &[Block],
+    concurrency: usize,
+) -> Result<(), ConcurrentVerificationError>
+where
+    V: BlockBodyValidator<B> + ?Sized,
+{
+    if blocks.is_empty() {
+        return Ok(());
+    }
+    let chunk_size = blocks.len().div_ceil(concurrency.max(1));
+    let results = thread::scope(|scope| {
+        let handles = blocks
+            .chunks(chunk_size)
+            .map(|chunk| scope.spawn(move || validator.verify_stateless(chunk)))
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap_or_else(|e| panic::resume_unwind(e)))
+            .collect::<Vec<_>>()
+    });
+
+    for ((chunk_index, result), chunk) in results.into_iter().enumerate().zip(blocks.chunks(chunk_size)) {
+        if let Err(err) = result {
+            let chunk_start = chunk_index * chunk_size;
+            debug!(
+                target: LOG_TARGET,
+                "Verification of blocks #{} to #{} failed ({}). Verifying blocks individually.",
+                chunk[0].header.height,
+                chunk[chunk.len() - 1].header.height,
+                err
+            );
+            // The range proofs of a chunk are verified as a batch, so we have to check each block to find the invalid
+            // one
+            for (i, block) in chunk.iter().enumerate() {
+                if let Err(error) = validator.verify_stateless(slice::from_ref(block)) {
+                    return Err(ConcurrentVerificationError::InvalidBlock {
+                        index: chunk_start + i,
+                        error,
+                    });
+                }
+            }
+            warn!(
+                target: LOG_TARGET,
+                "Blocks #{} to #{} failed verification together ({}) but each passed on its own",
+                chunk[0].header.height,
+                chunk[chunk.len() - 1].header.height,
+                err
+            );
+            return Err(ConcurrentVerificationError::Inconclusive {
+                start: chunk_start,
+                end: chunk_start + chunk.len() - 1,
+                error: err,
+            });
+        }
+    }
+    Ok(())
+}
@@ -28,3 +28,6 @@ pub use block_body_internal_validator::BlockBodyInternalConsistencyValidator;
 
 mod block_body_full_validator;
 pub use block_body_full_validator::BlockBodyFullValidator;
+
+mod concurrent_verification;
+pub use concurrent_verification::{verify_stateless_concurrently, ConcurrentVerificationError};
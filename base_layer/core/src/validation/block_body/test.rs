@@ -715,3 +715,92 @@ mod orphan_validator {
         assert!(output_type == OutputType::Standard || output_type == OutputType::Coinbase);
     }
 }
+
+mod concurrent_verification {
+    use std::sync::RwLock;
+
+    use super::*;
+    use crate::{
+        blocks::{Block, BlockHeader},
+        test_helpers::blockchain::TempDatabase,
+        tx,
+        validation::block_body::{verify_stateless_concurrently, ConcurrentVerificationError},
+        OutputSmt,
+    };
+
+    async fn create_unconnected_blocks(blockchain: &TestBlockchain, num_blocks: u64) -> Vec<Block> {
+        let mut blocks = Vec::new();
+        for height in 1..=num_blocks {
+            let (tx, _, _) = tx!(10 * T, fee: 25 * uT, inputs: 1, outputs: 3, &blockchain.km).unwrap();
+            let mut header = BlockHeader::new(0);
+            header.height = height;
+            blocks.push(Block::new(header, tx.body));
+        }
+        blocks
+    }
+
+    #[tokio::test]
+    async fn it_accepts_valid_blocks() {
+        let (blockchain, validator) = setup(true).await;
+        let blocks = create_unconnected_blocks(&blockchain, 5).await;
+        for concurrency in [1, 2, 5, 10] {
+            verify_stateless_concurrently::<TempDatabase, _>(&validator, &blocks, concurrency).unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn it_returns_the_index_of_the_first_invalid_block() {
+        let (blockchain, validator) = setup(true).await;
+        let mut blocks = create_unconnected_blocks(&blockchain, 5).await;
+        // Use the kernel signature of another block, which is a valid signature for the wrong message
+        let mut kernels = blocks[3].body.kernels().clone();
+        kernels[0].excess_sig = blocks[0].body.kernels()[0].excess_sig.clone();
+        let body = AggregateBody::new(
+            blocks[3].body.inputs().clone(),
+            blocks[3].body.outputs().clone(),
+            kernels,
+        );
+        blocks[3] = Block::new(blocks[3].header.clone(), body);
+
+        for concurrency in [1, 2, 5] {
+            let err = verify_stateless_concurrently::<TempDatabase, _>(&validator, &blocks, concurrency).unwrap_err();
+            assert!(matches!(err, ConcurrentVerificationError::InvalidBlock {
+                index: 3,
+                error: ValidationError::TransactionError(TransactionError::InvalidSignatureError(_))
+            }));
+        }
+    }
+
+    /// Fails any batch of more than one block, like a batch verifier with a bug would
+    struct BatchOnlyFailure;
+
+    impl BlockBodyValidator<TempDatabase> for BatchOnlyFailure {
+        fn validate_body(
+            &self,
+            _backend: &TempDatabase,
+            block: &Block,
+            _smt: Arc<RwLock<OutputSmt>>,
+        ) -> Result<Block, ValidationError> {
+            Ok(block.clone())
+        }
+
+        fn verify_stateless(&self, blocks: &[Block]) -> Result<(), ValidationError> {
+            if blocks.len() > 1 {
+                return Err(ValidationError::ConsensusError("Batch failure".to_string()));
+            }
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn it_does_not_blame_a_block_for_a_batch_failure() {
+        let (blockchain, _) = setup(true).await;
+        let blocks = create_unconnected_blocks(&blockchain, 4).await;
+        let err = verify_stateless_concurrently::<TempDatabase, _>(&BatchOnlyFailure, &blocks, 2).unwrap_err();
+        assert!(matches!(err, ConcurrentVerificationError::Inconclusive {
+            start: 0,
+            end: 1,
+            ..
+        }));
+    }
+}
@@ -60,9 +60,31 @@ impl<B: BlockchainBackend> FinalHorizonStateValidation<B> for ChainBalanceValida
         total_utxo_sum: &Commitment,
         total_kernel_sum: &Commitment,
         total_burned_sum: &Commitment,
+    ) -> Result<(), ValidationError> {
+        let chain_header = backend.fetch_chain_header_by_height(height)?;
+        self.validate_with_offset(
+            height,
+            &chain_header.accumulated_data().total_kernel_offset,
+            total_utxo_sum,
+            total_kernel_sum,
+            total_burned_sum,
+        )
+    }
+}
+
+impl<B: BlockchainBackend> ChainBalanceValidator<B> {
+    /// Validates the chain balance at `height` given the total kernel offset accumulated up to that height. This
+    /// allows the balance to be checked before the header at that height has been stored.
+    pub fn validate_with_offset(
+        &self,
+        height: u64,
+        total_kernel_offset: &PrivateKey,
+        total_utxo_sum: &Commitment,
+        total_kernel_sum: &Commitment,
+        total_burned_sum: &Commitment,
     ) -> Result<(), ValidationError> {
         let emission_h = self.get_emission_commitment_at(height);
-        let total_offset = self.fetch_total_offset_commitment(height, backend)?;
+        let total_offset = self.factories.commitment.commit(total_kernel_offset, &0u64.into());
 
         debug!(
             target: LOG_TARGET,
@@ -82,14 +104,6 @@ impl<B: BlockchainBackend> FinalHorizonStateValidation<B> for ChainBalanceValida
 
         Ok(())
     }
-}
-
-impl<B: BlockchainBackend> ChainBalanceValidator<B> {
-    fn fetch_total_offset_commitment(&self, height: u64, backend: &B) -> Result<Commitment, ValidationError> {
-        let chain_header = backend.fetch_chain_header_by_height(height)?;
-        let offset = &chain_header.accumulated_data().total_kernel_offset;
-        Ok(self.factories.commitment.commit(offset, &0u64.into()))
-    }
 
     fn get_emission_commitment_at(&self, height: u64) -> Commitment {
         // With inflating tail emission, we **must** know the value of the premine as part of the supply calc in order
@@ -39,6 +39,23 @@ use crate::{
 /// validated
 pub trait BlockBodyValidator<B>: Send + Sync {
     fn validate_body(&self, backend: &B, block: &Block, smt: Arc<RwLock<OutputSmt>>) -> Result<Block, ValidationError>;
+
+    /// Verifies the parts of the given blocks that do not depend on the chain state, such as signatures and range
+    /// proofs. This may be called for blocks that are not yet connected to the chain.
+    fn verify_stateless(&self, _blocks: &[Block]) -> Result<(), ValidationError> {
+        Ok(())
+    }
+
+    /// Validates a block body that has already passed [verify_stateless](Self::verify_stateless), skipping the checks
+    /// done there.
+    fn validate_pre_verified_body(
+        &self,
+        backend: &B,
+        block: &Block,
+        smt: Arc<RwLock<OutputSmt>>,
+    ) -> Result<Block, ValidationError> {
+        self.validate_body(backend, block, smt)
+    }
 }
 
 /// A validator that validates a body after it has been determined to be a valid orphan
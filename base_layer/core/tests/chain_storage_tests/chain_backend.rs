@@ -20,6 +20,8 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+use std::io::{self, Cursor, Read, Seek, SeekFrom};
+
 use rand::rngs::OsRng;
 use tari_common::configuration::Network;
 use tari_common_types::types::{
@@ -32,39 +34,154 @@ use tari_common_types::types::{
     Signature,
 };
 use tari_core::{
-    blocks::{BlockAccumulatedData, BlockHeader, BlockHeaderAccumulatedData, ChainHeader, UpdateBlockAccumulatedData},
-    chain_storage::{create_lmdb_database, BlockchainBackend, ChainStorageError, DbKey, DbTransaction, DbValue},
+    blocks::{BlockHeader, ChainBlock},
+    chain_storage::{
+        check_chain_consistency,
+        create_lmdb_database,
+        export_snapshot,
+        import_snapshot,
+        BlockAddResult,
+        BlockchainBackend,
+        BlockchainDatabase,
+        BlockchainDatabaseConfig,
+        ChainStorageError,
+        DbKey,
+        DbTransaction,
+        DbValue,
+        InconsistencyKind,
+        OutputIndex,
+        SnapshotError,
+    },
     consensus::{ConsensusManager, ConsensusManagerBuilder},
     covenants::Covenant,
-    test_helpers::blockchain::create_test_db,
-    transactions::transaction_components::{
-        EncryptedOpenings,
-        KernelFeatures,
-        OutputFeatures,
-        TransactionKernel,
-        TransactionKernelVersion,
-        TransactionOutput,
-        TransactionOutputVersion,
+    test_helpers::create_consensus_rules,
+    transactions::{
+        key_manager::{create_memory_db_key_manager, MemoryDbKeyManager},
+        transaction_components::{
+            EncryptedData,
+            KernelFeatures,
+            OutputFeatures,
+            OutputType,
+            TransactionKernel,
+            TransactionKernelVersion,
+            TransactionOutput,
+            TransactionOutputVersion,
+        },
+        CryptoFactories,
     },
     tx,
 };
 use tari_crypto::keys::{PublicKey as PKtrait, SecretKey as SKtrait};
-use tari_script::TariScript;
+use tari_script::script;
 use tari_storage::lmdb_store::LMDBConfig;
 use tari_test_utils::paths::create_temporary_data_path;
 
-use crate::helpers::database::create_orphan_block;
+use crate::helpers::{
+    block_builders::construct_chained_blocks,
+    database::{
+        create_mock_validators,
+        create_orphan_block,
+        create_store_with_consensus_and_validators_and_config,
+        TestBackend,
+    },
+};
+
+backend_tests!(
+    test_insert_contains_delete_and_fetch_orphan,
+    test_kernel_order,
+    test_utxo_order,
+    test_starts_with_genesis,
+    test_adds_blocks_and_indexes_their_contents,
+    test_rejects_a_duplicate_block,
+    test_removes_blocks_and_restores_the_output_smt,
+    test_allows_the_chain_to_be_rebuilt,
+    test_stores_and_cleans_up_orphans,
+    test_measures_the_number_of_entries,
+    test_fetches_outputs_by_output_type_and_script_hash,
+    test_removes_index_entries_on_rewind,
+    test_backfills_the_index_when_enabled,
+    test_imports_an_exported_snapshot,
+    test_rejects_a_corrupted_snapshot,
+    test_only_imports_into_an_empty_pruned_node,
+    test_resumes_an_interrupted_import,
+    test_reports_a_consistent_chain,
+    test_detects_missing_kernels,
+    test_detects_missing_outputs,
+    test_fetches_an_output_at_a_past_height,
+    test_calculates_the_output_smt_at_a_past_height,
+    test_limits_the_output_smt_rewind_depth,
+    test_stores_output_smt_deltas_on_an_archival_node,
+);
 
-#[test]
-fn test_lmdb_insert_contains_delete_and_fetch_orphan() {
+/// Create a blockchain database containing only the genesis block that accepts every block
+fn create_db<B: TestBackend>(config: BlockchainDatabaseConfig) -> BlockchainDatabase<B> {
+    create_store_with_consensus_and_validators_and_config(create_consensus_rules(), create_mock_validators(), config)
+}
+
+fn create_indexed_db<B: TestBackend>() -> BlockchainDatabase<B> {
+    create_db(BlockchainDatabaseConfig {
+        index_outputs: true,
+        ..Default::default()
+    })
+}
+
+fn create_pruned_db<B: TestBackend>() -> BlockchainDatabase<B> {
+    create_db(BlockchainDatabaseConfig {
+        pruning_horizon: 1000,
+        ..Default::default()
+    })
+}
+
+/// Add `size` empty blocks on top of the current tip
+async fn add_many_chained_blocks<B: BlockchainBackend>(
+    size: usize,
+    db: &BlockchainDatabase<B>,
+    key_manager: &MemoryDbKeyManager,
+) -> Vec<ChainBlock> {
+    let last_header = db.fetch_last_header().unwrap();
+    let prev_block = db
+        .fetch_block(last_header.height, true)
+        .unwrap()
+        .try_into_chain_block()
+        .unwrap();
+    construct_chained_blocks(db, prev_block, db.rules(), size, key_manager).await
+}
+
+fn assert_blocks_stored<B: BlockchainBackend>(db: &BlockchainDatabase<B>, blocks: &[ChainBlock]) {
+    for block in blocks {
+        let header = db.fetch_header_by_block_hash(*block.hash()).unwrap().unwrap();
+        assert_eq!(header.height, block.height());
+        let output = &block.block().body.outputs()[0];
+        let mined_info = db.fetch_output(output.hash()).unwrap().unwrap();
+        assert_eq!(mined_info.mined_height, block.height());
+        assert_eq!(
+            db.fetch_unspent_output_hash_by_commitment(output.commitment.clone())
+                .unwrap(),
+            Some(output.hash())
+        );
+        let kernel = &block.block().body.kernels()[0];
+        let (_, header_hash) = db
+            .fetch_kernel_by_excess_sig(kernel.excess_sig.clone())
+            .unwrap()
+            .unwrap();
+        assert_eq!(header_hash, *block.hash());
+    }
+}
+
+async fn test_insert_contains_delete_and_fetch_orphan<B: TestBackend>() {
     let network = Network::LocalNet;
-    let consensus = ConsensusManagerBuilder::new(network).build();
-    let mut db = create_test_db();
+    let consensus = ConsensusManagerBuilder::new(network).build().unwrap();
+    let mut db = B::create_test_db(&consensus);
+    let key_manager = create_memory_db_key_manager().unwrap();
     let txs = vec![
-        (tx!(1000.into(), fee: 4.into(), inputs: 2, outputs: 1)).0,
-        (tx!(2000.into(), fee: 6.into(), inputs: 1, outputs: 1)).0,
+        (tx!(1000.into(), fee: 4.into(), inputs: 2, outputs: 1, &key_manager))
+            .expect("Failed to get tx")
+            .0,
+        (tx!(2000.into(), fee: 6.into(), inputs: 1, outputs: 1, &key_manager))
+            .expect("Failed to get tx")
+            .0,
     ];
-    let orphan = create_orphan_block(10, txs, &consensus);
+    let orphan = create_orphan_block(10, txs, &consensus, &key_manager).await;
     let hash = orphan.hash();
     assert!(!db.contains(&DbKey::OrphanBlock(hash)).unwrap());
 
@@ -85,9 +202,8 @@ fn test_lmdb_insert_contains_delete_and_fetch_orphan() {
     assert!(!db.contains(&DbKey::OrphanBlock(hash)).unwrap());
 }
 
-#[test]
-fn test_kernel_order() {
-    let mut db = create_test_db();
+async fn test_kernel_order<B: TestBackend>() {
+    let mut db = B::create_test_db(&create_consensus_rules());
 
     let block_hash = FixedHash::zero();
     let mut kernels = Vec::with_capacity(2000);
@@ -103,26 +219,20 @@ fn test_kernel_order() {
     }
     kernels.sort();
 
-    for (i, kernel) in kernels.iter().enumerate().take(2000) {
+    for (i, kernel) in kernels.iter().enumerate() {
         let mut tx = DbTransaction::new();
-        tx.insert_kernel(kernel.clone(), block_hash, i as u32);
+        tx.insert_kernel(kernel.clone(), block_hash, u64::try_from(i).unwrap());
         db.write(tx).unwrap();
     }
 
     let read_kernels = db.fetch_kernels_in_block(&block_hash).unwrap();
-    assert_eq!(kernels.len(), read_kernels.len());
-    for i in 0..2000 {
-        assert_eq!(kernels[i], read_kernels[i]);
-    }
+    assert_eq!(kernels, read_kernels);
 }
 
-#[test]
-fn test_utxo_order() {
-    let mut db = create_test_db();
+async fn test_utxo_order<B: TestBackend>() {
+    let mut db = B::create_test_db(&create_consensus_rules());
 
-    let block_data = BlockAccumulatedData::default();
-    let header = BlockHeader::new(0);
-    let block_hash = header.hash();
+    let block_hash = BlockHeader::new(0).hash();
     let mut utxos = Vec::with_capacity(2000);
     let version = TransactionOutputVersion::V0;
     let features = OutputFeatures::default();
@@ -130,7 +240,6 @@ fn test_utxo_order() {
     let proof = RangeProof::default();
     let sig = ComAndPubSignature::default();
     let covenant = Covenant::default();
-    let encrypt = EncryptedOpenings::default();
     for _i in 0..2000 {
         let pvt_key = PrivateKey::random(&mut OsRng);
         let pub_key = PublicKey::from_secret_key(&pvt_key);
@@ -144,43 +253,27 @@ fn test_utxo_order() {
             pub_key,
             sig.clone(),
             covenant.clone(),
-            encrypt,
+            EncryptedData::default(),
             0.into(),
         );
         utxos.push(utxo);
     }
-    utxos.sort();
 
-    for (i, utxo) in utxos.iter().enumerate().take(2000) {
+    for utxo in &utxos {
         let mut tx = DbTransaction::new();
-        tx.insert_utxo(utxo.clone(), block_hash, 0, i as u32, 0);
+        tx.insert_utxo(utxo.clone(), block_hash, 0, 0);
         db.write(tx).unwrap();
     }
 
-    let mut tx = DbTransaction::new();
-    let data = BlockHeaderAccumulatedData {
-        hash: header.hash(),
-        ..Default::default()
-    };
-    let chainheader = ChainHeader::try_construct(header, data).unwrap();
-    let sum = block_data.kernel_sum().clone();
-    let (kernels, utxo_set, deleted) = block_data.dissolve();
-    let update_data = UpdateBlockAccumulatedData {
-        kernel_hash_set: Some(kernels),
-        utxo_hash_set: Some(utxo_set),
-        deleted_diff: Some(deleted.into()),
-        kernel_sum: Some(sum),
-    };
-    tx.insert_chain_header(chainheader);
-    tx.update_block_accumulated_data(block_hash, update_data);
-
-    db.write(tx).unwrap();
-
-    let read_utxos = db.fetch_outputs_in_block_with_spend_state(&block_hash).unwrap();
-    assert_eq!(utxos.len(), read_utxos.len());
-    for i in 0..2000 {
-        assert_eq!(&utxos[i], read_utxos[i].to_transaction_output().unwrap());
-    }
+    // The outputs of a block are returned in the order of their hashes
+    utxos.sort_by_key(|utxo| utxo.hash());
+    let read_utxos = db
+        .fetch_outputs_in_block_with_spend_state(&block_hash, None)
+        .unwrap()
+        .into_iter()
+        .map(|(utxo, _)| utxo)
+        .collect::<Vec<_>>();
+    assert_eq!(utxos, read_utxos);
 }
 
 #[test]
@@ -190,7 +283,7 @@ fn test_lmdb_file_lock() {
 
     // Perform test
     {
-        let consensus_manager = ConsensusManager::builder(Network::LocalNet).build();
+        let consensus_manager = ConsensusManager::builder(Network::LocalNet).build().unwrap();
         let db = create_lmdb_database(&temp_path, LMDBConfig::default(), consensus_manager.clone()).unwrap();
 
         match create_lmdb_database(&temp_path, LMDBConfig::default(), consensus_manager.clone()) {
@@ -209,3 +302,446 @@ fn test_lmdb_file_lock() {
         std::fs::remove_dir_all(&temp_path).expect("Could not clear temp storage for db");
     }
 }
+
+// Adding and removing blocks
+
+async fn test_starts_with_genesis<B: TestBackend>() {
+    let db = create_db::<B>(Default::default());
+    let metadata = db.get_chain_metadata().unwrap();
+    assert_eq!(metadata.best_block_height(), 0);
+    assert_eq!(db.fetch_blocks(.., true).unwrap().len(), 1);
+}
+
+async fn test_adds_blocks_and_indexes_their_contents<B: TestBackend>() {
+    let db = create_db::<B>(Default::default());
+    let key_manager = create_memory_db_key_manager().unwrap();
+    let blocks = add_many_chained_blocks(3, &db, &key_manager).await;
+    let tip = db.fetch_tip_header().unwrap();
+    assert_eq!(tip.height(), 3);
+    assert_eq!(tip.hash(), blocks[2].hash());
+    assert_blocks_stored(&db, &blocks);
+    assert_eq!(db.fetch_header_containing_kernel_mmr(1).unwrap().height(), 1);
+}
+
+async fn test_rejects_a_duplicate_block<B: TestBackend>() {
+    let db = create_db::<B>(Default::default());
+    let key_manager = create_memory_db_key_manager().unwrap();
+    let blocks = add_many_chained_blocks(1, &db, &key_manager).await;
+    assert_eq!(
+        db.add_block(blocks[0].to_arc_block()).unwrap(),
+        BlockAddResult::BlockExists
+    );
+}
+
+async fn test_removes_blocks_and_restores_the_output_smt<B: TestBackend>() {
+    let db = create_db::<B>(Default::default());
+    let key_manager = create_memory_db_key_manager().unwrap();
+    let genesis_output_mr = db.fetch_chain_header(0).unwrap().header().output_mr;
+    let blocks = add_many_chained_blocks(3, &db, &key_manager).await;
+
+    let removed = db.rewind_to_height(1).unwrap();
+    assert_eq!(removed.len(), 2);
+    assert_eq!(db.get_chain_metadata().unwrap().best_block_height(), 1);
+    assert!(db
+        .fetch_output(blocks[2].block().body.outputs()[0].hash())
+        .unwrap()
+        .is_none());
+    assert!(db
+        .fetch_output(blocks[0].block().body.outputs()[0].hash())
+        .unwrap()
+        .is_some());
+
+    db.rewind_to_height(0).unwrap();
+    let mut smt = db.db_read_access().unwrap().calculate_tip_smt().unwrap();
+    assert_eq!(smt.hash().as_slice(), genesis_output_mr.as_slice());
+}
+
+async fn test_allows_the_chain_to_be_rebuilt<B: TestBackend>() {
+    let db = create_db::<B>(Default::default());
+    let key_manager = create_memory_db_key_manager().unwrap();
+    add_many_chained_blocks(2, &db, &key_manager).await;
+    db.rewind_to_height(0).unwrap();
+    let blocks = add_many_chained_blocks(2, &db, &key_manager).await;
+    assert_eq!(db.fetch_tip_header().unwrap().hash(), blocks[1].hash());
+}
+
+async fn test_stores_and_cleans_up_orphans<B: TestBackend>() {
+    let db = create_db::<B>(Default::default());
+    let key_manager = create_memory_db_key_manager().unwrap();
+    let blocks = add_many_chained_blocks(2, &db, &key_manager).await;
+    db.rewind_to_height(0).unwrap();
+
+    let mut txn = DbTransaction::new();
+    txn.insert_orphan(blocks[1].to_arc_block());
+    db.commit(txn).unwrap();
+    assert_eq!(db.orphan_count().unwrap(), 1);
+    assert_eq!(&db.fetch_orphan(*blocks[1].hash()).unwrap().hash(), blocks[1].hash());
+    let children = db
+        .db_read_access()
+        .unwrap()
+        .fetch_orphan_children_of(*blocks[0].hash())
+        .unwrap();
+    assert_eq!(children.len(), 1);
+
+    db.cleanup_all_orphans().unwrap();
+    assert_eq!(db.orphan_count().unwrap(), 0);
+    let children = db
+        .db_read_access()
+        .unwrap()
+        .fetch_orphan_children_of(*blocks[0].hash())
+        .unwrap();
+    assert!(children.is_empty());
+}
+
+async fn test_measures_the_number_of_entries<B: TestBackend>() {
+    let db = create_db::<B>(Default::default());
+    let genesis_output_count = db.fetch_header(0).unwrap().unwrap().output_smt_size;
+    let key_manager = create_memory_db_key_manager().unwrap();
+    add_many_chained_blocks(2, &db, &key_manager).await;
+    let stats = db.fetch_total_size_stats().unwrap();
+    assert_eq!(
+        stats.sizes().iter().find(|s| s.name == "utxos").unwrap().num_entries,
+        genesis_output_count + 2
+    );
+    let stats = db.get_stats().unwrap();
+    assert_eq!(
+        stats.db_stats().iter().find(|s| s.name == "headers").unwrap().entries,
+        3
+    );
+}
+
+// Output indexes
+
+async fn test_fetches_outputs_by_output_type_and_script_hash<B: TestBackend>() {
+    let db = create_indexed_db::<B>();
+    let key_manager = create_memory_db_key_manager().unwrap();
+    let blocks = add_many_chained_blocks(3, &db, &key_manager).await;
+
+    let coinbases = db
+        .fetch_outputs_by_index(OutputIndex::OutputType(OutputType::Coinbase), 1..)
+        .unwrap();
+    assert_eq!(coinbases.len(), 3);
+    for (info, block) in coinbases.iter().zip(&blocks) {
+        assert_eq!(info.mined_height, block.height());
+        assert_eq!(info.output.hash(), block.block().body.outputs()[0].hash());
+    }
+
+    let coinbases = db
+        .fetch_outputs_by_index(OutputIndex::OutputType(OutputType::Coinbase), 2..=2)
+        .unwrap();
+    assert_eq!(coinbases.len(), 1);
+    assert_eq!(&coinbases[0].header_hash, blocks[1].hash());
+
+    let output = &blocks[0].block().body.outputs()[0];
+    let by_script = db
+        .fetch_outputs_by_index(OutputIndex::ScriptHash(OutputIndex::script_hash(&output.script)), ..)
+        .unwrap();
+    assert!(by_script.iter().any(|info| info.output.hash() == output.hash()));
+
+    let by_sender_offset = db
+        .fetch_outputs_by_index(
+            OutputIndex::SenderOffsetPublicKey(output.sender_offset_public_key.clone()),
+            ..,
+        )
+        .unwrap();
+    assert!(by_sender_offset.iter().any(|info| info.output.hash() == output.hash()));
+}
+
+async fn test_removes_index_entries_on_rewind<B: TestBackend>() {
+    let db = create_indexed_db::<B>();
+    let key_manager = create_memory_db_key_manager().unwrap();
+    add_many_chained_blocks(3, &db, &key_manager).await;
+
+    db.rewind_to_height(1).unwrap();
+    let coinbases = db
+        .fetch_outputs_by_index(OutputIndex::OutputType(OutputType::Coinbase), 1..)
+        .unwrap();
+    assert_eq!(coinbases.len(), 1);
+    assert_eq!(coinbases[0].mined_height, 1);
+}
+
+async fn test_backfills_the_index_when_enabled<B: TestBackend>() {
+    let db = create_db::<B>(Default::default());
+    let key_manager = create_memory_db_key_manager().unwrap();
+    let blocks = add_many_chained_blocks(2, &db, &key_manager).await;
+    let index = OutputIndex::OutputType(OutputType::Coinbase);
+    assert!(matches!(
+        db.fetch_outputs_by_index(index.clone(), ..),
+        Err(ChainStorageError::InvalidOperation(_))
+    ));
+
+    let mut txn = DbTransaction::new();
+    txn.set_output_indexing(true);
+    db.write(txn).unwrap();
+    assert_eq!(db.fetch_outputs_by_index(index, 1..).unwrap().len(), 2);
+    let output = &blocks[1].block().body.outputs()[0];
+    let by_sender_offset = db
+        .fetch_outputs_by_index(
+            OutputIndex::SenderOffsetPublicKey(output.sender_offset_public_key.clone()),
+            1..,
+        )
+        .unwrap();
+    assert_eq!(by_sender_offset[0].output.hash(), output.hash());
+}
+
+// Snapshots
+
+async fn export<B: TestBackend>(height: u64) -> (Vec<u8>, Vec<ChainBlock>) {
+    let db = create_db::<B>(Default::default());
+    let key_manager = create_memory_db_key_manager().unwrap();
+    let blocks = add_many_chained_blocks(4, &db, &key_manager).await;
+    let mut snapshot = Vec::new();
+    let summary = export_snapshot(&db, height, &mut snapshot).unwrap();
+    assert_eq!(summary.height, height);
+    assert_eq!(
+        &summary.best_block_hash,
+        blocks[usize::try_from(height).unwrap() - 1].hash()
+    );
+    (snapshot, blocks)
+}
+
+async fn test_imports_an_exported_snapshot<B: TestBackend>() {
+    let (snapshot, blocks) = export::<B>(3).await;
+    let db = create_pruned_db::<B>();
+    let summary = import_snapshot(&db, Cursor::new(snapshot), CryptoFactories::default()).unwrap();
+    assert_eq!(summary.height, 3);
+    assert_eq!(summary.num_outputs, 3);
+
+    let metadata = db.get_chain_metadata().unwrap();
+    assert_eq!(metadata.best_block_height(), 3);
+    assert_eq!(metadata.best_block_hash(), blocks[2].hash());
+    assert_eq!(metadata.pruned_height(), 3);
+    assert_eq!(&db.fetch_last_header().unwrap().hash(), blocks[2].hash());
+    assert!(db
+        .fetch_output(blocks[0].block().body.outputs()[0].hash())
+        .unwrap()
+        .is_some());
+    assert!(db
+        .fetch_output(blocks[3].block().body.outputs()[0].hash())
+        .unwrap()
+        .is_none());
+    assert_eq!(
+        db.smt_read_access().unwrap().clone().hash().as_slice(),
+        blocks[2].header().output_mr.as_slice()
+    );
+}
+
+async fn test_rejects_a_corrupted_snapshot<B: TestBackend>() {
+    let (mut snapshot, _) = export::<B>(2).await;
+    let len = snapshot.len();
+    snapshot[len / 2] ^= 0xff;
+    let db = create_pruned_db::<B>();
+    let err = import_snapshot(&db, Cursor::new(snapshot), CryptoFactories::default()).unwrap_err();
+    assert!(matches!(err, SnapshotError::ChecksumMismatch));
+    assert_eq!(db.get_chain_metadata().unwrap().best_block_height(), 0);
+}
+
+async fn test_only_imports_into_an_empty_pruned_node<B: TestBackend>() {
+    let (snapshot, _) = export::<B>(2).await;
+    let db = create_db::<B>(Default::default());
+    let err = import_snapshot(&db, Cursor::new(snapshot.clone()), CryptoFactories::default()).unwrap_err();
+    assert!(matches!(err, SnapshotError::NotPrunedNode));
+
+    let db = create_pruned_db::<B>();
+    let key_manager = create_memory_db_key_manager().unwrap();
+    add_many_chained_blocks(1, &db, &key_manager).await;
+    let err = import_snapshot(&db, Cursor::new(snapshot), CryptoFactories::default()).unwrap_err();
+    assert!(matches!(err, SnapshotError::DatabaseNotEmpty(2)));
+}
+
+/// Fails the third read that crosses `offset`. The checksum and validation passes of an import each read the whole
+/// snapshot before anything is written, so this interrupts the import of the blocks part way through.
+struct InterruptingReader {
+    inner: Cursor<Vec<u8>>,
+    offset: u64,
+    num_crossings: usize,
+}
+
+impl Read for InterruptingReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let position = self.inner.position();
+        if position <= self.offset && self.offset < position + buf.len() as u64 {
+            self.num_crossings += 1;
+            if self.num_crossings == 3 {
+                return Err(io::Error::new(io::ErrorKind::Other, "Interrupted"));
+            }
+        }
+        self.inner.read(buf)
+    }
+}
+
+impl Seek for InterruptingReader {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+async fn test_resumes_an_interrupted_import<B: TestBackend>() {
+    let db = create_db::<B>(Default::default());
+    let key_manager = create_memory_db_key_manager().unwrap();
+    let blocks = add_many_chained_blocks(20, &db, &key_manager).await;
+    let mut snapshot = Vec::new();
+    export_snapshot(&db, 20, &mut snapshot).unwrap();
+
+    let db = create_pruned_db::<B>();
+    let reader = InterruptingReader {
+        offset: snapshot.len() as u64 * 3 / 4,
+        inner: Cursor::new(snapshot.clone()),
+        num_crossings: 0,
+    };
+    let err = import_snapshot(&db, reader, CryptoFactories::default()).unwrap_err();
+    assert!(matches!(err, SnapshotError::IoError(_)));
+    let imported_height = db.fetch_last_header().unwrap().height;
+    assert!(imported_height > 0 && imported_height < 20);
+    assert_eq!(db.get_chain_metadata().unwrap().best_block_height(), 0);
+
+    let summary = import_snapshot(&db, Cursor::new(snapshot), CryptoFactories::default()).unwrap();
+    assert_eq!(summary.height, 20);
+    assert_eq!(summary.num_outputs, 20);
+    let metadata = db.get_chain_metadata().unwrap();
+    assert_eq!(metadata.best_block_height(), 20);
+    assert_eq!(metadata.best_block_hash(), blocks[19].hash());
+    for block in &blocks {
+        assert!(db
+            .fetch_output(block.block().body.outputs()[0].hash())
+            .unwrap()
+            .is_some());
+    }
+    assert_eq!(
+        db.smt_read_access().unwrap().clone().hash().as_slice(),
+        blocks[19].header().output_mr.as_slice()
+    );
+}
+
+// Consistency checks
+
+async fn test_reports_a_consistent_chain<B: TestBackend>() {
+    let db = create_db::<B>(Default::default());
+    let key_manager = create_memory_db_key_manager().unwrap();
+    add_many_chained_blocks(4, &db, &key_manager).await;
+    let report = check_chain_consistency(&db, 0, 4).unwrap();
+    assert!(report.is_consistent());
+    assert_eq!(report.end_height, 4);
+    assert_eq!(report.last_good_height(), None);
+
+    let report = check_chain_consistency(&db, 2, 3).unwrap();
+    assert!(report.is_consistent());
+    assert!(check_chain_consistency(&db, 3, 5).is_err());
+}
+
+async fn test_detects_missing_kernels<B: TestBackend>() {
+    let db = create_db::<B>(Default::default());
+    let key_manager = create_memory_db_key_manager().unwrap();
+    let blocks = add_many_chained_blocks(4, &db, &key_manager).await;
+    let mut txn = DbTransaction::new();
+    txn.delete_all_kernerls_in_block(*blocks[1].hash());
+    db.commit(txn).unwrap();
+
+    let report = check_chain_consistency(&db, 0, 4).unwrap();
+    assert_eq!(report.end_height, 2);
+    assert_eq!(report.first_inconsistent_height(), Some(2));
+    assert_eq!(report.last_good_height(), Some(1));
+    assert!(report
+        .inconsistencies
+        .iter()
+        .any(|i| matches!(i.kind, InconsistencyKind::KernelMmrRootMismatch { .. })));
+}
+
+async fn test_detects_missing_outputs<B: TestBackend>() {
+    let db = create_db::<B>(Default::default());
+    let key_manager = create_memory_db_key_manager().unwrap();
+    let blocks = add_many_chained_blocks(4, &db, &key_manager).await;
+    let output = &blocks[2].block().body.outputs()[0];
+    let mut txn = DbTransaction::new();
+    txn.prune_output_from_all_dbs(output.hash(), output.commitment.clone(), output.features.output_type);
+    db.commit(txn).unwrap();
+
+    let report = check_chain_consistency(&db, 1, 4).unwrap();
+    assert_eq!(report.last_good_height(), Some(2));
+    assert!(report
+        .inconsistencies
+        .iter()
+        .any(|i| matches!(i.kind, InconsistencyKind::OutputSmtRootMismatch { .. })));
+}
+
+// Historical queries
+
+async fn test_fetches_an_output_at_a_past_height<B: TestBackend>() {
+    let db = create_indexed_db::<B>();
+    let key_manager = create_memory_db_key_manager().unwrap();
+    let blocks = add_many_chained_blocks(3, &db, &key_manager).await;
+    let output = &blocks[1].block().body.outputs()[0];
+
+    assert!(db
+        .fetch_output_at_height(output.commitment.clone(), 1)
+        .unwrap()
+        .is_none());
+    let state = db
+        .fetch_output_at_height(output.commitment.clone(), 2)
+        .unwrap()
+        .unwrap();
+    assert_eq!(state.output.output.hash(), output.hash());
+    assert_eq!(state.output.mined_height, 2);
+    assert!(state.is_unspent());
+    assert!(db.fetch_output_at_height(output.commitment.clone(), 4).is_err());
+}
+
+async fn test_calculates_the_output_smt_at_a_past_height<B: TestBackend>() {
+    let db = create_db::<B>(Default::default());
+    let key_manager = create_memory_db_key_manager().unwrap();
+    let blocks = add_many_chained_blocks(3, &db, &key_manager).await;
+    for block in &blocks {
+        let mut smt = db.calculate_output_smt_at_height(block.height()).unwrap();
+        assert_eq!(smt.hash().as_slice(), block.header().output_mr.as_slice());
+        assert_eq!(smt.size(), block.header().output_smt_size);
+    }
+    assert_eq!(
+        db.smt_read_access().unwrap().clone().hash().as_slice(),
+        db.calculate_output_smt_at_height(3).unwrap().hash().as_slice()
+    );
+}
+
+async fn test_limits_the_output_smt_rewind_depth<B: TestBackend>() {
+    let db = create_db::<B>(BlockchainDatabaseConfig {
+        max_output_smt_rewind_depth: 2,
+        ..Default::default()
+    });
+    let key_manager = create_memory_db_key_manager().unwrap();
+    let blocks = add_many_chained_blocks(4, &db, &key_manager).await;
+    let mut smt = db.calculate_output_smt_at_height(2).unwrap();
+    assert_eq!(smt.hash().as_slice(), blocks[1].header().output_mr.as_slice());
+    assert!(matches!(
+        db.calculate_output_smt_at_height(1),
+        Err(ChainStorageError::InvalidArguments { .. })
+    ));
+}
+
+async fn test_stores_output_smt_deltas_on_an_archival_node<B: TestBackend>() {
+    let db = create_db::<B>(BlockchainDatabaseConfig {
+        max_output_smt_rewind_depth: 2,
+        archival: true,
+        ..Default::default()
+    });
+    let key_manager = create_memory_db_key_manager().unwrap();
+    let blocks = add_many_chained_blocks(4, &db, &key_manager).await;
+    for block in &blocks {
+        let delta = db
+            .db_read_access()
+            .unwrap()
+            .fetch_output_smt_delta(block.height())
+            .unwrap()
+            .unwrap();
+        assert_eq!(delta.inserted.len(), 1);
+        let mut smt = db.calculate_output_smt_at_height(block.height()).unwrap();
+        assert_eq!(smt.hash().as_slice(), block.header().output_mr.as_slice());
+    }
+    // The rewind depth does not apply to archival nodes
+    let genesis_output_mr = db.fetch_chain_header(0).unwrap().header().output_mr;
+    let mut smt = db.calculate_output_smt_at_height(0).unwrap();
+    assert_eq!(smt.hash().as_slice(), genesis_output_mr.as_slice());
+
+    db.rewind_to_height(2).unwrap();
+    let deltas = db.db_read_access().unwrap();
+    assert!(deltas.fetch_output_smt_delta(2).unwrap().is_some());
+    assert!(deltas.fetch_output_smt_delta(3).unwrap().is_none());
+}
@@ -20,11 +20,13 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+use std::sync::{Arc, RwLock};
+
 use rand::{rngs::OsRng, RngCore};
 use tari_common::configuration::Network;
 use tari_common_types::types::BlockHash;
 use tari_core::{
-    blocks::{genesis_block, Block, BlockHeader},
+    blocks::{Block, BlockHeader},
     chain_storage::{
         create_lmdb_database,
         BlockAddResult,
@@ -36,29 +38,22 @@ use tari_core::{
         MmrTree,
         Validators,
     },
-    consensus::{emission::Emission, ConsensusConstantsBuilder, ConsensusManager, ConsensusManagerBuilder},
+    consensus::{ConsensusConstantsBuilder, ConsensusManager, ConsensusManagerBuilder},
     proof_of_work::Difficulty,
-    test_helpers::blockchain::{
-        create_store_with_consensus,
-        create_store_with_consensus_and_validators,
-        create_test_blockchain_db,
-        create_test_db,
-        TempDatabase,
-    },
+    test_helpers::blockchain::TempDatabase,
     transactions::{
+        key_manager::create_memory_db_key_manager,
         tari_amount::{uT, MicroMinotari, T},
         test_helpers::spend_utxos,
-        CryptoFactories,
     },
     tx,
     txn_schema,
     validation::{mocks::MockValidator, DifficultyCalculator, ValidationError},
+    OutputSmt,
 };
 use tari_storage::lmdb_store::LMDBConfig;
 use tari_test_utils::{paths::create_temporary_data_path, unpack_enum};
 
-// use crate::helpers::database::create_test_db;
-// use crate::helpers::database::create_store;
 use crate::helpers::{
     block_builders::{
         append_block,
@@ -71,36 +66,66 @@ use crate::helpers::{
         generate_new_block_with_achieved_difficulty,
         generate_new_block_with_coinbase,
     },
-    database::create_orphan_block,
-    sample_blockchains::{create_new_blockchain, create_new_blockchain_lmdb},
+    database::{
+        create_mock_validators,
+        create_orphan_block,
+        create_store_with_consensus,
+        create_store_with_consensus_and_validators,
+        create_store_with_consensus_and_validators_and_config,
+        create_test_blockchain_db,
+        TestBackend,
+    },
+    sample_blockchains::{create_new_blockchain_with_backend, create_new_blockchain_with_validators},
 };
 
-#[test]
-fn test_fetch_nonexistent_header() {
-    let network = Network::LocalNet;
-    let _consensus_manager = ConsensusManagerBuilder::new(network).build();
-    let store = create_test_blockchain_db();
+backend_tests!(
+    test_fetch_nonexistent_header,
+    test_insert_and_fetch_header,
+    test_insert_and_fetch_orphan,
+    test_store_and_retrieve_block,
+    test_add_multiple_blocks,
+    test_checkpoints,
+    test_rewind_to_height,
+    test_coverage_chain_storage,
+    test_rewind_past_horizon_height,
+    test_handle_tip_reorg_with_zero_conf,
+    test_handle_tip_reorg,
+    test_handle_tip_reset,
+    test_handle_reorg,
+    test_reorgs_should_update_orphan_tips,
+    test_handle_reorg_with_no_removed_blocks,
+    test_handle_reorg_failure_recovery,
+    test_store_and_retrieve_blocks,
+    test_store_and_retrieve_blocks_from_contents,
+    test_invalid_block,
+    test_orphan_cleanup_on_block_add,
+    test_horizon_height_orphan_cleanup,
+    test_orphan_cleanup_on_reorg,
+    test_fails_validation,
+    pruned_mode_cleanup_and_fetch_block,
+    test_fetch_spent_output_block_hash,
+);
+
+async fn test_fetch_nonexistent_header<B: TestBackend>() {
+    let store = create_test_blockchain_db::<B>();
 
     assert_eq!(store.fetch_header(1).unwrap(), None);
 }
 
-#[test]
-fn test_insert_and_fetch_header() {
-    let network = Network::LocalNet;
-    let _consensus_manager = ConsensusManagerBuilder::new(network).build();
-    let store = create_test_blockchain_db();
+async fn test_insert_and_fetch_header<B: TestBackend>() {
+    let store = create_test_blockchain_db::<B>();
     let genesis_block = store.fetch_tip_header().unwrap();
     let mut header1 = BlockHeader::from_previous(genesis_block.header());
 
     header1.kernel_mmr_size += 1;
-    header1.output_mmr_size += 1;
+    header1.output_smt_size += 1;
 
     let chain1 = create_chain_header(header1.clone(), genesis_block.accumulated_data());
 
     store.insert_valid_headers(vec![chain1.clone()]).unwrap();
     let mut header2 = BlockHeader::from_previous(&header1);
     header2.kernel_mmr_size += 2;
-    header2.output_mmr_size += 2;
+    header2.output_smt_size += 2;
     let chain2 = create_chain_header(header2.clone(), chain1.accumulated_data());
 
     store.insert_valid_headers(vec![chain2]).unwrap();
@@ -110,16 +135,20 @@ fn test_insert_and_fetch_header() {
     assert_eq!(store.fetch_header(2).unwrap().unwrap(), header2);
 }
 
-#[test]
-fn test_insert_and_fetch_orphan() {
+async fn test_insert_and_fetch_orphan<B: TestBackend>() {
     let network = Network::LocalNet;
-    let consensus_manager = ConsensusManagerBuilder::new(network).build();
-    let store = create_test_blockchain_db();
+    let consensus_manager = ConsensusManagerBuilder::new(network).build().unwrap();
+    let store = create_test_blockchain_db::<B>();
+    let key_manager = create_memory_db_key_manager().unwrap();
     let txs = vec![
-        (tx!(1000.into(), fee: 4.into(), inputs: 2, outputs: 1)).0,
-        (tx!(2000.into(), fee: 6.into(), inputs: 1, outputs: 1)).0,
+        (tx!(1000.into(), fee: 4.into(), inputs: 2, outputs: 1, &key_manager))
+            .expect("Failed to get tx")
+            .0,
+        (tx!(2000.into(), fee: 6.into(), inputs: 1, outputs: 1, &key_manager))
+            .expect("Failed to get tx")
+            .0,
     ];
-    let orphan = create_orphan_block(10, txs, &consensus_manager);
+    let orphan = create_orphan_block(10, txs, &consensus_manager, &key_manager).await;
     let orphan_hash = orphan.hash();
     let mut txn = DbTransaction::new();
     txn.insert_orphan(orphan.clone().into());
@@ -127,15 +156,14 @@ fn test_insert_and_fetch_orphan() {
     assert_eq!(store.fetch_orphan(orphan_hash).unwrap(), orphan);
 }
 
-#[test]
-fn test_store_and_retrieve_block() {
-    let (db, blocks, _, _) = create_new_blockchain(Network::LocalNet);
+async fn test_store_and_retrieve_block<B: TestBackend>() {
+    let (db, blocks, _, _, _) = create_new_blockchain_with_backend::<B>(Network::LocalNet).await;
     let hash = blocks[0].hash();
     // Check the metadata
     let metadata = db.get_chain_metadata().unwrap();
     assert_eq!(metadata.best_block_height(), 0);
-    assert_eq!(metadata.best_block(), hash);
-    assert_eq!(metadata.horizon_block(metadata.best_block_height()), 0);
+    assert_eq!(metadata.best_block_hash(), hash);
+    assert_eq!(metadata.pruned_height(), 0);
     // Fetch the block back
     let block0 = db.fetch_block(0, true).unwrap();
     assert_eq!(block0.confirmations(), 1);
@@ -144,29 +172,31 @@ fn test_store_and_retrieve_block() {
     assert_eq!(blocks[0].block(), &block0);
 }
 
-#[test]
-fn test_add_multiple_blocks() {
+async fn test_add_multiple_blocks<B: TestBackend>() {
     // Create new database with genesis block
     let network = Network::LocalNet;
-    let consensus_manager = ConsensusManagerBuilder::new(network).build();
-    let store = create_store_with_consensus(consensus_manager.clone());
+    let consensus_manager = ConsensusManagerBuilder::new(network).build().unwrap();
+    let store = create_store_with_consensus::<B>(consensus_manager.clone());
+    let key_manager = create_memory_db_key_manager().unwrap();
     let metadata = store.get_chain_metadata().unwrap();
     assert_eq!(metadata.best_block_height(), 0);
     let block0 = store.fetch_block(0, true).unwrap();
-    assert_eq!(metadata.best_block(), block0.hash());
+    assert_eq!(metadata.best_block_hash(), block0.hash());
     // Add another block
-    let block1 = append_block(
+    let (block1, _) = append_block(
         &store,
         &block0.try_into_chain_block().unwrap(),
         vec![],
         &consensus_manager,
         Difficulty::min(),
+        &key_manager,
     )
+    .await
     .unwrap();
     let metadata = store.get_chain_metadata().unwrap();
     let hash = block1.hash();
     assert_eq!(metadata.best_block_height(), 1);
-    assert_eq!(metadata.best_block(), hash);
+    assert_eq!(metadata.best_block_hash(), hash);
     // Adding blocks is idempotent
     assert_eq!(
         store.add_block(block1.to_arc_block()).unwrap(),
@@ -175,20 +205,28 @@ fn test_add_multiple_blocks() {
     // Check the metadata
     let metadata = store.get_chain_metadata().unwrap();
     assert_eq!(metadata.best_block_height(), 1);
-    assert_eq!(metadata.best_block(), hash);
+    assert_eq!(metadata.best_block_hash(), hash);
 }
 
-#[test]
-fn test_checkpoints() {
+async fn test_checkpoints<B: TestBackend>() {
     let network = Network::LocalNet;
-    let (db, blocks, outputs, consensus_manager) = create_new_blockchain(network);
+    let (db, blocks, outputs, consensus_manager, key_manager) = create_new_blockchain_with_backend::<B>(network).await;
 
     let txn = txn_schema!(
         from: vec![outputs[0][0].clone()],
         to: vec![MicroMinotari(5_000), MicroMinotari(6_000)]
     );
-    let (txn, _) = spend_utxos(txn);
-    let block1 = append_block(&db, &blocks[0], vec![txn], &consensus_manager, Difficulty::min()).unwrap();
+    let (txn, _) = spend_utxos(txn, &key_manager).await;
+    let (block1, _) = append_block(
+        &db,
+        &blocks[0],
+        vec![txn],
+        &consensus_manager,
+        Difficulty::min(),
+        &key_manager,
+    )
+    .await
+    .unwrap();
     // Get the checkpoint
     let block_a = db.fetch_block(0, false).unwrap();
     assert_eq!(block_a.confirmations(), 2);
@@ -200,24 +238,40 @@ fn test_checkpoints() {
     assert_eq!(block1, block_b);
 }
 
-#[test]
 #[allow(clippy::identity_op)]
-fn test_rewind_to_height() {
+async fn test_rewind_to_height<B: TestBackend>() {
     let _ = env_logger::builder().is_test(true).try_init();
     let network = Network::LocalNet;
-    let (mut db, mut blocks, mut outputs, consensus_manager) = create_new_blockchain(network);
+    let (mut db, mut blocks, mut outputs, consensus_manager, key_manager) =
+        create_new_blockchain_with_backend::<B>(network).await;
 
     // Block 1
     let schema = vec![txn_schema!(from: vec![outputs[0][0].clone()], to: vec![6 * T, 3 * T])];
     unpack_enum!(
-        BlockAddResult::Ok(_b1) =
-            generate_new_block(&mut db, &mut blocks, &mut outputs, schema, &consensus_manager).unwrap()
+        BlockAddResult::Ok(_b1) = generate_new_block(
+            &mut db,
+            &mut blocks,
+            &mut outputs,
+            schema,
+            &consensus_manager,
+            &key_manager
+        )
+        .await
+        .unwrap()
     );
     // Block 2
     let schema = vec![txn_schema!(from: vec![outputs[1][0].clone()], to: vec![3 * T, 1 * T])];
     unpack_enum!(
-        BlockAddResult::Ok(_b2) =
-            generate_new_block(&mut db, &mut blocks, &mut outputs, schema, &consensus_manager).unwrap()
+        BlockAddResult::Ok(_b2) = generate_new_block(
+            &mut db,
+            &mut blocks,
+            &mut outputs,
+            schema,
+            &consensus_manager,
+            &key_manager
+        )
+        .await
+        .unwrap()
     );
     // Block 3
     let schema = vec![
@@ -225,8 +279,16 @@ fn test_rewind_to_height() {
         txn_schema!(from: vec![outputs[1][1].clone()], to: vec![500_000 * uT]),
     ];
     unpack_enum!(
-        BlockAddResult::Ok(_b3) =
-            generate_new_block(&mut db, &mut blocks, &mut outputs, schema, &consensus_manager).unwrap()
+        BlockAddResult::Ok(_b3) = generate_new_block(
+            &mut db,
+            &mut blocks,
+            &mut outputs,
+            schema,
+            &consensus_manager,
+            &key_manager
+        )
+        .await
+        .unwrap()
     );
 
     db.rewind_to_height(3).unwrap();
@@ -239,74 +301,102 @@ fn test_rewind_to_height() {
     assert_eq!(db.get_height().unwrap(), 1);
 }
 
-#[test]
-fn test_coverage_chain_storage() {
-    let validators = Validators::new(
-        MockValidator::new(true),
-        MockValidator::new(true),
-        MockValidator::new(true),
-    );
+async fn test_coverage_chain_storage<B: TestBackend>() {
     let network = Network::LocalNet;
-    let rules = ConsensusManagerBuilder::new(network).build();
-    let db = create_test_db();
+    let rules = ConsensusManagerBuilder::new(network).build().unwrap();
+    let db = B::create_test_db(&rules);
     assert_eq!(db.kernel_count().unwrap(), 0);
     let store = BlockchainDatabase::new(
         db,
         rules.clone(),
-        validators,
+        create_mock_validators(),
         BlockchainDatabaseConfig::default(),
         DifficultyCalculator::new(rules.clone(), Default::default()),
+        Arc::new(RwLock::new(OutputSmt::new())),
     )
     .unwrap();
+    let key_manager = create_memory_db_key_manager().unwrap();
 
     let block0 = store.fetch_block(0, true).unwrap();
+    let kernel_mmr_size = store.fetch_mmr_size(MmrTree::Kernel).unwrap();
+    let utxo_count = store.utxo_count().unwrap();
     append_block(
         &store,
         &block0.clone().try_into_chain_block().unwrap(),
         vec![],
         &rules,
         Difficulty::min(),
+        &key_manager,
     )
+    .await
     .unwrap();
-    assert_eq!(store.fetch_all_reorgs().unwrap(), vec![]);
-    assert_eq!(store.fetch_mmr_size(MmrTree::Kernel).unwrap(), 2);
-    assert_eq!(store.fetch_mmr_size(MmrTree::Utxo).unwrap(), 2);
+    assert!(store.fetch_all_reorgs().unwrap().is_empty());
+    assert_eq!(store.fetch_mmr_size(MmrTree::Kernel).unwrap(), kernel_mmr_size + 1);
+    assert_eq!(store.utxo_count().unwrap(), utxo_count + 1);
 
     let mut txn = DbTransaction::new();
-    txn.insert_bad_block(*block0.hash(), 0);
+    txn.insert_bad_block(*block0.hash(), 0, "Bad block".to_string());
     store.commit(txn).unwrap();
+    assert!(store.bad_block_exists(*block0.hash()).unwrap().0);
 }
 
-#[test]
-fn test_rewind_past_horizon_height() {
+async fn test_rewind_past_horizon_height<B: TestBackend>() {
     let network = Network::LocalNet;
-    let block0 = genesis_block::get_esmeralda_genesis_block();
-    let consensus_manager = ConsensusManagerBuilder::new(network).with_block(block0.clone()).build();
-    let validators = Validators::new(
-        MockValidator::new(true),
-        MockValidator::new(true),
-        MockValidator::new(true),
-    );
-    let db = create_test_db();
+    let consensus_manager = ConsensusManagerBuilder::new(network).build().unwrap();
+    let block0 = consensus_manager.get_genesis_block();
     let config = BlockchainDatabaseConfig {
         orphan_storage_capacity: 3,
         pruning_horizon: 2,
         pruning_interval: 1,
         ..Default::default()
     };
-    let store = BlockchainDatabase::new(
-        db,
+    let store = create_store_with_consensus_and_validators_and_config::<B>(
         consensus_manager.clone(),
-        validators,
+        create_mock_validators(),
         config,
-        DifficultyCalculator::new(consensus_manager.clone(), Default::default()),
+    );
+    let key_manager = create_memory_db_key_manager().unwrap();
+
+    let (block1, _) = append_block(
+        &store,
+        &block0,
+        vec![],
+        &consensus_manager,
+        Difficulty::min(),
+        &key_manager,
     )
+    .await
+    .unwrap();
+    let (block2, _) = append_block(
+        &store,
+        &block1,
+        vec![],
+        &consensus_manager,
+        Difficulty::min(),
+        &key_manager,
+    )
+    .await
+    .unwrap();
+    let (block3, _) = append_block(
+        &store,
+        &block2,
+        vec![],
+        &consensus_manager,
+        Difficulty::min(),
+        &key_manager,
+    )
+    .await
+    .unwrap();
+    let _block4 = append_block(
+        &store,
+        &block3,
+        vec![],
+        &consensus_manager,
+        Difficulty::min(),
+        &key_manager,
+    )
+    .await
     .unwrap();
-
-    let block1 = append_block(&store, &block0, vec![], &consensus_manager, Difficulty::min()).unwrap();
-    let block2 = append_block(&store, &block1, vec![], &consensus_manager, Difficulty::min()).unwrap();
-    let block3 = append_block(&store, &block2, vec![], &consensus_manager, Difficulty::min()).unwrap();
-    let _block4 = append_block(&store, &block3, vec![], &consensus_manager, Difficulty::min()).unwrap();
 
     let metadata = store.get_chain_metadata().unwrap();
     assert_eq!(metadata.best_block_height(), 4);
@@ -320,15 +410,15 @@ fn test_rewind_past_horizon_height() {
     assert_eq!(metadata.best_block_height(), 0);
 }
 
-#[test]
 #[allow(clippy::too_many_lines)]
-fn test_handle_tip_reorg_with_zero_conf() {
+async fn test_handle_tip_reorg_with_zero_conf<B: TestBackend>() {
     // GB --> A1 --> A2 --> A3(Low PoW)      [Main Chain]
     //          \--> B2 --> B3 -- B4 --> B5(Highest PoW)  [Forked Chain]
 
     // Create Main Chain
     let network = Network::LocalNet;
-    let (mut store, mut blocks, mut outputs, consensus_manager) = create_new_blockchain(network);
+    let (mut store, mut blocks, mut outputs, consensus_manager, key_manager) =
+        create_new_blockchain_with_backend::<B>(network).await;
     // Block A1
     let txs = vec![txn_schema!(
         from: vec![outputs[0][0].clone()],
@@ -339,21 +429,31 @@ fn test_handle_tip_reorg_with_zero_conf() {
         &mut blocks,
         &mut outputs,
         txs,
-        Difficulty::from(1),
+        Difficulty::from_u64(1).unwrap(),
         &consensus_manager,
+        &key_manager,
     )
+    .await
     .unwrap();
     // Block A2
     let txs_1 = txn_schema!(from: vec![outputs[1][3].clone()], to: vec![6 * T]);
-    let (tx_1, utxos_1) = spend_utxos(txs_1);
+    let (tx_1, utxos_1) = spend_utxos(txs_1, &key_manager).await;
     // create zero conf
     let txs_2 = txn_schema!(from: vec![utxos_1[0].clone()], to: vec![4 * T]);
-    let (tx_2, utxos_2) = spend_utxos(txs_2);
+    let (tx_2, utxos_2) = spend_utxos(txs_2, &key_manager).await;
     let txns = vec![tx_1, tx_2];
 
     outputs.push(utxos_2);
-    generate_block_with_achieved_difficulty(&mut store, &mut blocks, txns, Difficulty::from(3), &consensus_manager)
-        .unwrap();
+    generate_block_with_achieved_difficulty(
+        &mut store,
+        &mut blocks,
+        txns,
+        Difficulty::from_u64(3).unwrap(),
+        &consensus_manager,
+        &key_manager,
+    )
+    .await
+    .unwrap();
 
     // Block A3
     let txs = vec![txn_schema!(from: vec![outputs[2][0].clone()], to: vec![2 * T])];
@@ -362,15 +462,17 @@ fn test_handle_tip_reorg_with_zero_conf() {
         &mut blocks,
         &mut outputs,
         txs,
-        Difficulty::from(1),
-        &consensus_manager
+        Difficulty::from_u64(1).unwrap(),
+        &consensus_manager,
+        &key_manager
     )
+    .await
     .is_ok());
     assert_eq!(store.get_chain_metadata().unwrap().best_block_height(), 3);
 
     // Create Forked Chain
 
-    let mut orphan_store = create_store_with_consensus(consensus_manager.clone());
+    let mut orphan_store = create_store_with_consensus::<B>(consensus_manager.clone());
     orphan_store.add_block(blocks[1].to_arc_block()).unwrap();
     let mut orphan_blocks = vec![blocks[0].clone(), blocks[1].clone()];
     let mut orphan_outputs = vec![outputs[0].clone(), outputs[1].clone()];
@@ -390,9 +492,11 @@ fn test_handle_tip_reorg_with_zero_conf() {
         &mut orphan_blocks,
         &mut orphan_outputs,
         txs,
-        Difficulty::from(7),
+        Difficulty::from_u64(7).unwrap(),
         &consensus_manager,
+        &key_manager,
     )
+    .await
     .unwrap();
 
     // Adding B2 to the main chain will produce a reorg to GB->A1->B2.
@@ -422,9 +526,11 @@ fn test_handle_tip_reorg_with_zero_conf() {
         &mut orphan_blocks,
         &mut orphan_outputs,
         txs,
-        Difficulty::from(1),
-        &consensus_manager
+        Difficulty::from_u64(1).unwrap(),
+        &consensus_manager,
+        &key_manager
     )
+    .await
     .is_ok());
 
     if let Ok(BlockAddResult::Ok { .. }) = store.add_block(orphan_blocks[3].to_arc_block()) {
@@ -441,9 +547,11 @@ fn test_handle_tip_reorg_with_zero_conf() {
         &mut orphan_blocks,
         &mut orphan_outputs,
         txs,
-        Difficulty::from(1),
-        &consensus_manager
+        Difficulty::from_u64(1).unwrap(),
+        &consensus_manager,
+        &key_manager
     )
+    .await
     .is_ok());
 
     if let Ok(BlockAddResult::Ok { .. }) = store.add_block(orphan_blocks[4].to_arc_block()) {
@@ -461,9 +569,11 @@ fn test_handle_tip_reorg_with_zero_conf() {
         &mut orphan_blocks,
         &mut orphan_outputs,
         txs,
-        Difficulty::from(1),
-        &consensus_manager
+        Difficulty::from_u64(1).unwrap(),
+        &consensus_manager,
+        &key_manager
     )
+    .await
     .is_ok());
 
     if let Ok(BlockAddResult::Ok { .. }) = store.add_block(orphan_blocks[5].to_arc_block()) {
@@ -472,9 +582,8 @@ fn test_handle_tip_reorg_with_zero_conf() {
     }
     assert_eq!(store.get_chain_metadata().unwrap().best_block_height(), 5);
 }
-#[test]
-#[allow(clippy::too_many_lines)]
-fn test_handle_tip_reorg() {
+
+async fn test_handle_tip_reorg<B: TestBackend>() {
     // GB --> A1 --> A2(Low PoW)      [Main Chain]
     //          \--> B2(Highest PoW)  [Forked Chain]
     // Initially, the main chain is GB->A1->A2. B2 has a higher accumulated PoW and when B2 is added the main chain is
@@ -482,7 +591,8 @@ fn test_handle_tip_reorg() {
 
     // Create Main Chain
     let network = Network::LocalNet;
-    let (mut store, mut blocks, mut outputs, consensus_manager) = create_new_blockchain(network);
+    let (mut store, mut blocks, mut outputs, consensus_manager, key_manager) =
+        create_new_blockchain_with_backend::<B>(network).await;
     // Block A1
     let txs = vec![txn_schema!(
         from: vec![outputs[0][0].clone()],
@@ -493,9 +603,11 @@ fn test_handle_tip_reorg() {
         &mut blocks,
         &mut outputs,
         txs,
-        Difficulty::from(1),
+        Difficulty::from_u64(1).unwrap(),
         &consensus_manager,
+        &key_manager,
     )
+    .await
     .unwrap();
     // Block A2
     let txs = vec![txn_schema!(from: vec![outputs[1][3].clone()], to: vec![6 * T])];
@@ -504,14 +616,16 @@ fn test_handle_tip_reorg() {
         &mut blocks,
         &mut outputs,
         txs,
-        Difficulty::from(3),
+        Difficulty::from_u64(3).unwrap(),
         &consensus_manager,
+        &key_manager,
     )
+    .await
     .unwrap();
 
     // Create Forked Chain
 
-    let mut orphan_store = create_store_with_consensus(consensus_manager.clone());
+    let mut orphan_store = create_store_with_consensus::<B>(consensus_manager.clone());
     orphan_store.add_block(blocks[1].to_arc_block()).unwrap();
     let mut orphan_blocks = vec![blocks[0].clone(), blocks[1].clone()];
     let mut orphan_outputs = vec![outputs[0].clone(), outputs[1].clone()];
@@ -522,9 +636,11 @@ fn test_handle_tip_reorg() {
         &mut orphan_blocks,
         &mut orphan_outputs,
         txs,
-        Difficulty::from(7),
+        Difficulty::from_u64(7).unwrap(),
         &consensus_manager,
+        &key_manager,
     )
+    .await
     .unwrap();
 
     // Adding B2 to the main chain will produce a reorg to GB->A1->B2.
@@ -539,8 +655,7 @@ fn test_handle_tip_reorg() {
     assert!(store.fetch_orphan(*blocks[2].hash()).is_ok());
 }
 
-#[test]
-fn test_handle_tip_reset() {
+async fn test_handle_tip_reset<B: TestBackend>() {
     // GB --> A1 --> A2(Low PoW)      [Main Chain]
     //          \--> B2(Highest PoW)  [Forked Chain]
     // Initially, the main chain is GB->A1->A2. B2 has a higher accumulated PoW and when B2 is added the main chain is
@@ -548,7 +663,8 @@ fn test_handle_tip_reset() {
 
     // Create Main Chain
     let network = Network::LocalNet;
-    let (mut store, mut blocks, mut outputs, consensus_manager) = create_new_blockchain(network);
+    let (mut store, mut blocks, mut outputs, consensus_manager, key_manager) =
+        create_new_blockchain_with_backend::<B>(network).await;
     // Block A1
     let txs = vec![txn_schema!(
         from: vec![outputs[0][0].clone()],
@@ -559,9 +675,11 @@ fn test_handle_tip_reset() {
         &mut blocks,
         &mut outputs,
         txs,
-        Difficulty::from(1),
+        Difficulty::from_u64(1).unwrap(),
         &consensus_manager,
+        &key_manager,
     )
+    .await
     .unwrap();
     // Block A2
     let txs = vec![txn_schema!(from: vec![outputs[1][3].clone()], to: vec![6 * T])];
@@ -570,14 +688,16 @@ fn test_handle_tip_reset() {
         &mut blocks,
         &mut outputs,
         txs,
-        Difficulty::from(3),
+        Difficulty::from_u64(3).unwrap(),
         &consensus_manager,
+        &key_manager,
     )
+    .await
     .unwrap();
 
     // Create Forked Chain
 
-    let mut orphan_store = create_store_with_consensus(consensus_manager.clone());
+    let mut orphan_store = create_store_with_consensus::<B>(consensus_manager.clone());
     orphan_store.add_block(blocks[1].to_arc_block()).unwrap();
     let mut orphan_blocks = vec![blocks[0].clone(), blocks[1].clone()];
     let mut orphan_outputs = vec![outputs[0].clone(), outputs[1].clone()];
@@ -588,9 +708,11 @@ fn test_handle_tip_reset() {
         &mut orphan_blocks,
         &mut orphan_outputs,
         txs,
-        Difficulty::from(7),
+        Difficulty::from_u64(7).unwrap(),
         &consensus_manager,
+        &key_manager,
     )
+    .await
     .unwrap();
 
     // Adding B2 to the main chain will produce a reorg to GB->A1->B2.
@@ -627,10 +749,9 @@ fn test_handle_tip_reset() {
     assert_eq!(store.fetch_tip_header().unwrap().hash(), blocks[1].hash());
 }
 
-#[test]
 #[allow(clippy::identity_op)]
 #[allow(clippy::too_many_lines)]
-fn test_handle_reorg() {
+async fn test_handle_reorg<B: TestBackend>() {
     // GB --> A1 --> A2 --> A3 -----> A4(Low PoW)     [Main Chain]
     //          \--> B2 --> B3(?) --> B4(Medium PoW)  [Forked Chain 1]
     //                        \-----> C4(Highest PoW) [Forked Chain 2]
@@ -639,7 +760,8 @@ fn test_handle_reorg() {
 
     // Create Main Chain
     let network = Network::LocalNet;
-    let (mut store, mut blocks, mut outputs, consensus_manager) = create_new_blockchain(network);
+    let (mut store, mut blocks, mut outputs, consensus_manager, key_manager) =
+        create_new_blockchain_with_backend::<B>(network).await;
     // Block A1
     let txs = vec![txn_schema!(
         from: vec![outputs[0][0].clone()],
@@ -650,9 +772,11 @@ fn test_handle_reorg() {
         &mut blocks,
         &mut outputs,
         txs,
-        Difficulty::from(1),
-        &consensus_manager
+        Difficulty::from_u64(1).unwrap(),
+        &consensus_manager,
+        &key_manager
     )
+    .await
     .is_ok());
     // Block A2
     let txs = vec![txn_schema!(from: vec![outputs[1][3].clone()], to: vec![6 * T])];
@@ -661,9 +785,11 @@ fn test_handle_reorg() {
         &mut blocks,
         &mut outputs,
         txs,
-        Difficulty::from(3),
-        &consensus_manager
+        Difficulty::from_u64(3).unwrap(),
+        &consensus_manager,
+        &key_manager
     )
+    .await
     .is_ok());
     // Block A3
     let txs = vec![txn_schema!(from: vec![outputs[2][0].clone()], to: vec![2 * T])];
@@ -672,9 +798,11 @@ fn test_handle_reorg() {
         &mut blocks,
         &mut outputs,
         txs,
-        Difficulty::from(1),
-        &consensus_manager
+        Difficulty::from_u64(1).unwrap(),
+        &consensus_manager,
+        &key_manager
     )
+    .await
     .is_ok());
     // Block A4
     let txs = vec![txn_schema!(from: vec![outputs[1][0].clone()], to: vec![2 * T])];
@@ -683,13 +811,15 @@ fn test_handle_reorg() {
         &mut blocks,
         &mut outputs,
         txs,
-        Difficulty::from(1),
-        &consensus_manager
+        Difficulty::from_u64(1).unwrap(),
+        &consensus_manager,
+        &key_manager
     )
+    .await
     .is_ok());
 
     // Create Forked Chain 1
-    let mut orphan1_store = create_store_with_consensus(consensus_manager.clone());
+    let mut orphan1_store = create_store_with_consensus::<B>(consensus_manager.clone());
     orphan1_store
         .add_block(blocks[1].to_arc_block())
         .unwrap()
@@ -703,9 +833,11 @@ fn test_handle_reorg() {
         &mut orphan1_blocks,
         &mut orphan1_outputs,
         txs,
-        Difficulty::from(1),
-        &consensus_manager
+        Difficulty::from_u64(1).unwrap(),
+        &consensus_manager,
+        &key_manager
     )
+    .await
     .is_ok());
     // Block B3
     let txs = vec![
@@ -717,9 +849,11 @@ fn test_handle_reorg() {
         &mut orphan1_blocks,
         &mut orphan1_outputs,
         txs,
-        Difficulty::from(1),
-        &consensus_manager
+        Difficulty::from_u64(1).unwrap(),
+        &consensus_manager,
+        &key_manager
     )
+    .await
     .is_ok());
     // Block B4
     let txs = vec![txn_schema!(from: vec![orphan1_outputs[3][0].clone()], to: vec![1 * T])];
@@ -728,13 +862,15 @@ fn test_handle_reorg() {
         &mut orphan1_blocks,
         &mut orphan1_outputs,
         txs,
-        Difficulty::from(5),
-        &consensus_manager
+        Difficulty::from_u64(5).unwrap(),
+        &consensus_manager,
+        &key_manager
     )
+    .await
     .is_ok());
 
     // Create Forked Chain 2
-    let mut orphan2_store = create_store_with_consensus(consensus_manager.clone());
+    let mut orphan2_store = create_store_with_consensus::<B>(consensus_manager.clone());
     orphan2_store
         .add_block(blocks[1].to_arc_block())
         .unwrap()
@@ -766,9 +902,11 @@ fn test_handle_reorg() {
         &mut orphan2_blocks,
         &mut orphan2_outputs,
         txs,
-        Difficulty::from(20),
-        &consensus_manager
+        Difficulty::from_u64(20).unwrap(),
+        &consensus_manager,
+        &key_manager
     )
+    .await
     .is_ok());
 
     // Now add the fork blocks C4, B2, B4 and B3 (out of order) to the first DB and observe a reorg. Blocks are added
@@ -798,12 +936,11 @@ fn test_handle_reorg() {
     assert!(store.fetch_orphan(*blocks[2].hash()).is_ok()); // A2
     assert!(store.fetch_orphan(*blocks[3].hash()).is_ok()); // A3
     assert!(store.fetch_orphan(*blocks[4].hash()).is_ok()); // A4
-    assert!(store.fetch_orphan(*blocks[4].hash()).is_ok()); // B4
+    assert!(store.fetch_orphan(*orphan1_blocks[4].hash()).is_ok()); // B4
 }
 
-#[test]
 #[allow(clippy::too_many_lines)]
-fn test_reorgs_should_update_orphan_tips() {
+async fn test_reorgs_should_update_orphan_tips<B: TestBackend>() {
     // Create a main chain GB -> A1 -> A2
     // Create an orphan chain GB -> B1
     // Add a block B2 that forces a reorg to B2
@@ -816,10 +953,11 @@ fn test_reorgs_should_update_orphan_tips() {
     // Check that B4 is in the orphan chain tips db
 
     let network = Network::LocalNet;
-    let (store, blocks, outputs, consensus_manager) = create_new_blockchain(network);
+    let (store, blocks, outputs, consensus_manager, key_manager) =
+        create_new_blockchain_with_backend::<B>(network).await;
 
     // Create "A" Chain
-    let mut a_store = create_store_with_consensus(consensus_manager.clone());
+    let mut a_store = create_store_with_consensus::<B>(consensus_manager.clone());
     let mut a_blocks = vec![blocks[0].clone()];
     let mut a_outputs = vec![outputs[0].clone()];
 
@@ -830,9 +968,11 @@ fn test_reorgs_should_update_orphan_tips() {
         &mut a_blocks,
         &mut a_outputs,
         txs,
-        Difficulty::from(1),
+        Difficulty::from_u64(1).unwrap(),
         &consensus_manager,
+        &key_manager,
     )
+    .await
     .unwrap();
 
     store.add_block(a_blocks[1].to_arc_block()).unwrap().assert_added();
@@ -844,16 +984,18 @@ fn test_reorgs_should_update_orphan_tips() {
         &mut a_blocks,
         &mut a_outputs,
         txs,
-        Difficulty::from(3),
+        Difficulty::from_u64(3).unwrap(),
         &consensus_manager,
+        &key_manager,
     )
+    .await
     .unwrap();
 
     store.add_block(a_blocks[2].to_arc_block()).unwrap().assert_added();
     let a2_hash = *a_blocks[2].hash();
 
     // Create "B" Chain
-    let mut b_store = create_store_with_consensus(consensus_manager.clone());
+    let mut b_store = create_store_with_consensus::<B>(consensus_manager.clone());
     let mut b_blocks = vec![blocks[0].clone()];
     let mut b_outputs = vec![outputs[0].clone()];
 
@@ -864,9 +1006,11 @@ fn test_reorgs_should_update_orphan_tips() {
         &mut b_blocks,
         &mut b_outputs,
         txs,
-        Difficulty::from(2),
+        Difficulty::from_u64(2).unwrap(),
         &consensus_manager,
+        &key_manager,
     )
+    .await
     .unwrap();
 
     store.add_block(b_blocks[1].to_arc_block()).unwrap().assert_orphaned();
@@ -888,9 +1032,11 @@ fn test_reorgs_should_update_orphan_tips() {
         &mut b_blocks,
         &mut b_outputs,
         txs,
-        Difficulty::from(4),
+        Difficulty::from_u64(4).unwrap(),
         &consensus_manager,
+        &key_manager,
     )
+    .await
     .unwrap();
 
     store.add_block(b_blocks[2].to_arc_block()).unwrap().assert_reorg(2, 2);
@@ -920,9 +1066,11 @@ fn test_reorgs_should_update_orphan_tips() {
         &mut a_blocks,
         &mut a_outputs,
         txs,
-        Difficulty::from(5), // A chain accumulated difficulty 9
+        Difficulty::from_u64(5).unwrap(), // A chain accumulated difficulty 9
         &consensus_manager,
+        &key_manager,
     )
+    .await
     .unwrap();
 
     store.add_block(a_blocks[3].to_arc_block()).unwrap().assert_reorg(3, 2);
@@ -952,9 +1100,11 @@ fn test_reorgs_should_update_orphan_tips() {
         &mut b_blocks,
         &mut b_outputs,
         txs,
-        Difficulty::from(1), // B chain accumulated difficulty 7
+        Difficulty::from_u64(1).unwrap(), // B chain accumulated difficulty 7
         &consensus_manager,
+        &key_manager,
     )
+    .await
     .unwrap();
 
     store.add_block(b_blocks[3].to_arc_block()).unwrap().assert_orphaned();
@@ -967,9 +1117,11 @@ fn test_reorgs_should_update_orphan_tips() {
         &mut b_blocks,
         &mut b_outputs,
         txs,
-        Difficulty::from(5), // B chain accumulated difficulty 12
+        Difficulty::from_u64(5).unwrap(), // B chain accumulated difficulty 12
         &consensus_manager,
+        &key_manager,
     )
+    .await
     .unwrap();
 
     store.add_block(b_blocks[4].to_arc_block()).unwrap().assert_reorg(4, 3);
@@ -999,9 +1151,11 @@ fn test_reorgs_should_update_orphan_tips() {
         &mut a_blocks,
         &mut a_outputs,
         txs,
-        Difficulty::from(2), // A chain accumulated difficulty 11
+        Difficulty::from_u64(2).unwrap(), // A chain accumulated difficulty 11
         &consensus_manager,
+        &key_manager,
     )
+    .await
     .unwrap();
 
     store.add_block(a_blocks[4].to_arc_block()).unwrap().assert_orphaned();
@@ -1013,9 +1167,11 @@ fn test_reorgs_should_update_orphan_tips() {
         &mut a_blocks,
         &mut a_outputs,
         txs,
-        Difficulty::from(4), // A chain accumulated difficulty 15
+        Difficulty::from_u64(4).unwrap(), // A chain accumulated difficulty 15
         &consensus_manager,
+        &key_manager,
     )
+    .await
     .unwrap();
 
     store.add_block(a_blocks[5].to_arc_block()).unwrap().assert_reorg(5, 4);
@@ -1051,8 +1207,7 @@ fn test_reorgs_should_update_orphan_tips() {
     assert!(store.fetch_orphan(*a_blocks[5].hash()).is_err()); // A5
 }
 
-#[test]
-fn test_handle_reorg_with_no_removed_blocks() {
+async fn test_handle_reorg_with_no_removed_blocks<B: TestBackend>() {
     // GB --> A1
     //          \--> B2 (?) --> B3)
     // Initially, the main chain is GB->A1 with orphaned blocks B3. When B2 arrives late and is
@@ -1060,7 +1215,8 @@ fn test_handle_reorg_with_no_removed_blocks() {
 
     // Create Main Chain
     let network = Network::LocalNet;
-    let (mut store, mut blocks, mut outputs, consensus_manager) = create_new_blockchain(network);
+    let (mut store, mut blocks, mut outputs, consensus_manager, key_manager) =
+        create_new_blockchain_with_backend::<B>(network).await;
 
     // Block A1
     let txs = vec![txn_schema!(
@@ -1072,13 +1228,15 @@ fn test_handle_reorg_with_no_removed_blocks() {
         &mut blocks,
         &mut outputs,
         txs,
-        Difficulty::from(1),
+        Difficulty::from_u64(1).unwrap(),
         &consensus_manager,
+        &key_manager,
     )
+    .await
     .unwrap();
 
     // Create Forked Chain 1
-    let mut orphan1_store = create_store_with_consensus(consensus_manager.clone());
+    let mut orphan1_store = create_store_with_consensus::<B>(consensus_manager.clone());
     orphan1_store.add_block(blocks[1].to_arc_block()).unwrap(); // A1
     let mut orphan1_blocks = vec![blocks[0].clone(), blocks[1].clone()];
     let mut orphan1_outputs = vec![outputs[0].clone(), outputs[1].clone()];
@@ -1089,9 +1247,11 @@ fn test_handle_reorg_with_no_removed_blocks() {
         &mut orphan1_blocks,
         &mut orphan1_outputs,
         txs,
-        Difficulty::from(1),
+        Difficulty::from_u64(1).unwrap(),
         &consensus_manager,
+        &key_manager,
     )
+    .await
     .unwrap();
     // Block B3
     let txs = vec![
@@ -1103,9 +1263,11 @@ fn test_handle_reorg_with_no_removed_blocks() {
         &mut orphan1_blocks,
         &mut orphan1_outputs,
         txs,
-        Difficulty::from(1),
+        Difficulty::from_u64(1).unwrap(),
         &consensus_manager,
+        &key_manager,
     )
+    .await
     .unwrap();
 
     // Now add the fork blocks B3 and B2 (out of order) to the first DB and ensure a reorg.
@@ -1124,8 +1286,8 @@ fn test_handle_reorg_with_no_removed_blocks() {
     assert_eq!(store.fetch_tip_header().unwrap().header(), orphan1_blocks[3].header());
 }
 
-#[test]
-fn test_handle_reorg_failure_recovery() {
+#[allow(clippy::too_many_lines)]
+async fn test_handle_reorg_failure_recovery<B: TestBackend>() {
     // GB --> A1 --> A2 --> A3 -----> A4(Low PoW)     [Main Chain]
     //          \--> B2 --> B3(double spend - rejected by db)  [Forked Chain 1]
     //          \--> B2 --> B3'(validation failed)      [Forked Chain 1]
@@ -1133,12 +1295,10 @@ fn test_handle_reorg_failure_recovery() {
     // 1. recovery from failure to commit reorged blocks, and
     // 2. recovery from failed block validation.
 
-    let block_validator = MockValidator::new(true);
-    let validators = Validators::new(block_validator, MockValidator::new(true), MockValidator::new(true));
     // Create Main Chain
     let network = Network::LocalNet;
-    let (mut store, mut blocks, mut outputs, consensus_manager) =
-        create_new_blockchain_lmdb(network, validators, Default::default());
+    let (mut store, mut blocks, mut outputs, consensus_manager, key_manager) =
+        create_new_blockchain_with_validators::<B>(network, create_mock_validators(), Default::default()).await;
     // Block A1
     let txs = vec![txn_schema!(
         from: vec![outputs[0][0].clone()],
@@ -1149,9 +1309,11 @@ fn test_handle_reorg_failure_recovery() {
         &mut blocks,
         &mut outputs,
         txs,
-        Difficulty::from(1),
+        Difficulty::from_u64(1).unwrap(),
         &consensus_manager,
+        &key_manager,
     )
+    .await
     .unwrap();
     // Block A2
     let txs = vec![txn_schema!(from: vec![outputs[1][3].clone()], to: vec![6 * T])];
@@ -1160,9 +1322,11 @@ fn test_handle_reorg_failure_recovery() {
         &mut blocks,
         &mut outputs,
         txs,
-        Difficulty::from(1),
+        Difficulty::from_u64(1).unwrap(),
         &consensus_manager,
+        &key_manager,
     )
+    .await
     .unwrap();
     // Block A3
     let txs = vec![txn_schema!(from: vec![outputs[2][0].clone()], to: vec![2 * T])];
@@ -1171,9 +1335,11 @@ fn test_handle_reorg_failure_recovery() {
         &mut blocks,
         &mut outputs,
         txs,
-        Difficulty::from(2),
+        Difficulty::from_u64(2).unwrap(),
         &consensus_manager,
+        &key_manager,
     )
+    .await
     .unwrap();
     // Block A4
     let txs = vec![txn_schema!(from: vec![outputs[1][0].clone()], to: vec![2 * T])];
@@ -1182,13 +1348,15 @@ fn test_handle_reorg_failure_recovery() {
         &mut blocks,
         &mut outputs,
         txs,
-        Difficulty::from(2),
+        Difficulty::from_u64(2).unwrap(),
         &consensus_manager,
+        &key_manager,
     )
+    .await
     .unwrap();
 
     // Create Forked Chain 1
-    let mut orphan1_store = create_store_with_consensus(consensus_manager.clone());
+    let mut orphan1_store = create_store_with_consensus::<B>(consensus_manager.clone());
     orphan1_store.add_block(blocks[1].to_arc_block()).unwrap(); // A1
     let mut orphan1_blocks = vec![blocks[0].clone(), blocks[1].clone()];
     let mut orphan1_outputs = vec![outputs[0].clone(), outputs[1].clone()];
@@ -1199,9 +1367,11 @@ fn test_handle_reorg_failure_recovery() {
         &mut orphan1_blocks,
         &mut orphan1_outputs,
         txs,
-        Difficulty::from(1),
+        Difficulty::from_u64(1).unwrap(),
         &consensus_manager,
+        &key_manager,
     )
+    .await
     .unwrap();
     // Block B3 (Incorrect height)
     let double_spend_block = {
@@ -1213,13 +1383,19 @@ fn test_handle_reorg_failure_recovery() {
         let mut txns = Vec::new();
         let mut block_utxos = Vec::new();
         for schema in schemas {
-            let (tx, mut utxos) = spend_utxos(schema);
+            let (tx, mut utxos) = spend_utxos(schema, &key_manager).await;
             txns.push(tx);
             block_utxos.append(&mut utxos);
         }
         orphan1_outputs.push(block_utxos);
 
-        let template = chain_block(orphan1_blocks.last().unwrap().block(), txns, &consensus_manager);
+        let template = chain_block(
+            orphan1_blocks.last().unwrap().block(),
+            txns,
+            &consensus_manager,
+            &key_manager,
+        )
+        .await;
         let mut block = orphan1_store.prepare_new_block(template).unwrap();
         block.header.nonce = OsRng.next_u64();
         block.header.height += 1;
@@ -1243,35 +1419,26 @@ fn test_handle_reorg_failure_recovery() {
     assert!(store.fetch_orphan(*blocks[4].hash()).is_err()); // A4
 }
 
-#[test]
-fn test_store_and_retrieve_blocks() {
-    let validators = Validators::new(
-        MockValidator::new(true),
-        MockValidator::new(true),
-        MockValidator::new(true),
-    );
+async fn test_store_and_retrieve_blocks<B: TestBackend>() {
     let network = Network::LocalNet;
-    let rules = ConsensusManagerBuilder::new(network).build();
-    let db = create_test_db();
-    let store = BlockchainDatabase::new(
-        db,
-        rules.clone(),
-        validators,
-        BlockchainDatabaseConfig::default(),
-        DifficultyCalculator::new(rules.clone(), Default::default()),
-    )
-    .unwrap();
+    let rules = ConsensusManagerBuilder::new(network).build().unwrap();
+    let store = create_store_with_consensus_and_validators::<B>(rules.clone(), create_mock_validators());
+    let key_manager = create_memory_db_key_manager().unwrap();
 
     let block0 = store.fetch_block(0, true).unwrap();
-    let block1 = append_block(
+    let (block1, _) = append_block(
         &store,
         &block0.clone().try_into_chain_block().unwrap(),
         vec![],
         &rules,
         Difficulty::min(),
+        &key_manager,
     )
+    .await
     .unwrap();
-    let block2 = append_block(&store, &block1, vec![], &rules, Difficulty::min()).unwrap();
+    let (block2, _) = append_block(&store, &block1, vec![], &rules, Difficulty::min(), &key_manager)
+        .await
+        .unwrap();
     assert_eq!(
         store.fetch_block(0, true).unwrap().try_into_chain_block().unwrap(),
         block0.clone().try_into_chain_block().unwrap()
@@ -1285,7 +1452,9 @@ fn test_store_and_retrieve_blocks() {
         block2
     );
 
-    let block3 = append_block(&store, &block2, vec![], &rules, Difficulty::min()).unwrap();
+    let (block3, _) = append_block(&store, &block2, vec![], &rules, Difficulty::min(), &key_manager)
+        .await
+        .unwrap();
     assert_eq!(
         store.fetch_block(0, true).unwrap().try_into_chain_block().unwrap(),
         block0.try_into_chain_block().unwrap()
@@ -1304,23 +1473,39 @@ fn test_store_and_retrieve_blocks() {
     );
 }
 
-#[test]
 #[allow(clippy::identity_op)]
-fn test_store_and_retrieve_blocks_from_contents() {
+async fn test_store_and_retrieve_blocks_from_contents<B: TestBackend>() {
     let network = Network::LocalNet;
-    let (mut db, mut blocks, mut outputs, consensus_manager) = create_new_blockchain(network);
+    let (mut db, mut blocks, mut outputs, consensus_manager, key_manager) =
+        create_new_blockchain_with_backend::<B>(network).await;
 
     // Block 1
     let schema = vec![txn_schema!(from: vec![outputs[0][0].clone()], to: vec![6 * T, 3 * T])];
     unpack_enum!(
-        BlockAddResult::Ok(_b1) =
-            generate_new_block(&mut db, &mut blocks, &mut outputs, schema, &consensus_manager).unwrap()
+        BlockAddResult::Ok(_b1) = generate_new_block(
+            &mut db,
+            &mut blocks,
+            &mut outputs,
+            schema,
+            &consensus_manager,
+            &key_manager
+        )
+        .await
+        .unwrap()
     );
     // Block 2
     let schema = vec![txn_schema!(from: vec![outputs[1][0].clone()], to: vec![3 * T, 1 * T])];
     unpack_enum!(
-        BlockAddResult::Ok(_b2) =
-            generate_new_block(&mut db, &mut blocks, &mut outputs, schema, &consensus_manager).unwrap()
+        BlockAddResult::Ok(_b2) = generate_new_block(
+            &mut db,
+            &mut blocks,
+            &mut outputs,
+            schema,
+            &consensus_manager,
+            &key_manager
+        )
+        .await
+        .unwrap()
     );
     let kernel_sig = blocks[1].block().body.kernels()[0].clone().excess_sig;
     let utxo_commit = blocks.last().unwrap().block().body.outputs()[0].clone().commitment;
@@ -1343,17 +1528,14 @@ fn test_store_and_retrieve_blocks_from_contents() {
     );
 }
 
-#[test]
-fn test_restore_metadata_and_pruning_horizon_update() {
+#[tokio::test]
+async fn test_restore_metadata_and_pruning_horizon_update() {
     // Perform test
-    let validators = Validators::new(
-        MockValidator::new(true),
-        MockValidator::new(true),
-        MockValidator::new(true),
-    );
+    let validators = create_mock_validators();
     let network = Network::LocalNet;
-    let block0 = genesis_block::get_esmeralda_genesis_block();
-    let rules = ConsensusManagerBuilder::new(network).with_block(block0.clone()).build();
+    let rules = ConsensusManagerBuilder::new(network).build().unwrap();
+    let block0 = rules.get_genesis_block();
+    let key_manager = create_memory_db_key_manager().unwrap();
     let mut config = BlockchainDatabaseConfig::default();
     let block_hash: BlockHash;
     let temp_path = create_temporary_data_path();
@@ -1367,15 +1549,18 @@ fn test_restore_metadata_and_pruning_horizon_update() {
             validators.clone(),
             config,
             DifficultyCalculator::new(rules.clone(), Default::default()),
+            Arc::new(RwLock::new(OutputSmt::new())),
         )
         .unwrap();
 
-        let block1 = append_block(&db, &block0, vec![], &rules, Difficulty::min()).unwrap();
+        let (block1, _) = append_block(&db, &block0, vec![], &rules, Difficulty::min(), &key_manager)
+            .await
+            .unwrap();
         db.add_block(block1.to_arc_block()).unwrap();
         block_hash = *block1.hash();
         let metadata = db.get_chain_metadata().unwrap();
         assert_eq!(metadata.best_block_height(), 1);
-        assert_eq!(metadata.best_block(), &block_hash);
+        assert_eq!(metadata.best_block_hash(), &block_hash);
         assert_eq!(metadata.pruning_horizon(), 1000);
     }
     // Restore blockchain db with larger pruning horizon
@@ -1390,12 +1575,13 @@ fn test_restore_metadata_and_pruning_horizon_update() {
             validators.clone(),
             config,
             DifficultyCalculator::new(rules.clone(), Default::default()),
+            Arc::new(RwLock::new(OutputSmt::new())),
         )
         .unwrap();
 
         let metadata = db.get_chain_metadata().unwrap();
         assert_eq!(metadata.best_block_height(), 1);
-        assert_eq!(metadata.best_block(), &block_hash);
+        assert_eq!(metadata.best_block_hash(), &block_hash);
         assert_eq!(metadata.pruning_horizon(), 2000);
     }
     // Restore blockchain db with smaller pruning horizon update
@@ -1408,39 +1594,43 @@ fn test_restore_metadata_and_pruning_horizon_update() {
             validators,
             config,
             DifficultyCalculator::new(rules, Default::default()),
+            Arc::new(RwLock::new(OutputSmt::new())),
         )
         .unwrap();
 
         let metadata = db.get_chain_metadata().unwrap();
         assert_eq!(metadata.best_block_height(), 1);
-        assert_eq!(metadata.best_block(), &block_hash);
+        assert_eq!(metadata.best_block_hash(), &block_hash);
         assert_eq!(metadata.pruning_horizon(), 900);
     }
 }
+
 static EMISSION: [u64; 2] = [10, 10];
-#[test]
-fn test_invalid_block() {
-    let factories = CryptoFactories::default();
+
+#[allow(clippy::too_many_lines)]
+async fn test_invalid_block<B: TestBackend>() {
     let network = Network::LocalNet;
+    let key_manager = create_memory_db_key_manager().unwrap();
     let consensus_constants = ConsensusConstantsBuilder::new(network)
-        .with_emission_amounts(100_000_000.into(), &EMISSION, 100.into())
+        .with_emission_amounts(100_000_000.into(), &EMISSION, 10, 1000)
         .build();
-    let (block0, output) = create_genesis_block( &consensus_constants);
+    let (block0, output) = create_genesis_block(&consensus_constants, &key_manager).await;
     let consensus_manager = ConsensusManagerBuilder::new(network)
         .add_consensus_constants(consensus_constants)
         .with_block(block0.clone())
-        .build();
+        .build()
+        .unwrap();
     let validator = MockValidator::new(true);
     let is_valid = validator.shared_flag();
-    let validators = Validators::new(MockValidator::new(true), MockValidator::new(true), validator);
-    let mut store = create_store_with_consensus_and_validators(consensus_manager.clone(), validators);
+    let validators = Validators::new(validator, MockValidator::new(true), MockValidator::new(true));
+    let mut store = create_store_with_consensus_and_validators::<B>(consensus_manager.clone(), validators);
 
     let mut blocks = vec![block0];
     let mut outputs = vec![vec![output]];
     let block0_hash = *blocks[0].hash();
     let metadata = store.get_chain_metadata().unwrap();
     assert_eq!(metadata.best_block_height(), 0);
-    assert_eq!(metadata.best_block(), &block0_hash);
+    assert_eq!(metadata.best_block_hash(), &block0_hash);
     assert_eq!(store.fetch_block(0, true).unwrap().block().hash(), block0_hash);
     assert!(store.fetch_block(1, true).is_err());
 
@@ -1453,19 +1643,20 @@ fn test_invalid_block() {
     unpack_enum!(
         BlockAddResult::Ok(_b1) = generate_new_block_with_coinbase(
             &mut store,
-            &factories,
             &mut blocks,
             &mut outputs,
             txs,
             coinbase_value,
-            &consensus_manager
+            &consensus_manager,
+            &key_manager
         )
+        .await
         .unwrap()
     );
     let block1_hash = *blocks[1].hash();
     let metadata = store.get_chain_metadata().unwrap();
     assert_eq!(metadata.best_block_height(), 1);
-    assert_eq!(metadata.best_block(), &block1_hash);
+    assert_eq!(metadata.best_block_hash(), &block1_hash);
     assert_eq!(store.fetch_block(0, true).unwrap().hash(), &block0_hash);
     assert_eq!(store.fetch_block(1, true).unwrap().hash(), &block1_hash);
     assert!(store.fetch_block(2, true).is_err());
@@ -1474,21 +1665,24 @@ fn test_invalid_block() {
     is_valid.set(false);
     let txs = vec![txn_schema!(from: vec![outputs[0][0].clone()], to: vec![20 * T, 20 * T])];
     let coinbase_value = consensus_manager.emission_schedule().block_reward(2);
-    unpack_enum!(
-        ChainStorageError::InvalidOperation(_msg) = generate_new_block_with_coinbase(
-            &mut store,
-            &factories,
-            &mut blocks,
-            &mut outputs,
-            txs,
-            coinbase_value,
-            &consensus_manager
-        )
-        .unwrap_err()
-    );
+    let err = generate_new_block_with_coinbase(
+        &mut store,
+        &mut blocks,
+        &mut outputs,
+        txs,
+        coinbase_value,
+        &consensus_manager,
+        &key_manager,
+    )
+    .await
+    .unwrap_err();
+    unpack_enum!(ChainStorageError::ValidationError { source } = err);
+    unpack_enum!(ValidationError::ConsensusError(_s) = source);
+    // The outputs of the rejected block can't be spent
+    outputs.pop();
     let metadata = store.get_chain_metadata().unwrap();
     assert_eq!(metadata.best_block_height(), 1);
-    assert_eq!(metadata.best_block(), &block1_hash);
+    assert_eq!(metadata.best_block_hash(), &block1_hash);
     assert_eq!(store.fetch_block(0, true).unwrap().hash(), &block0_hash);
     assert_eq!(store.fetch_block(1, true).unwrap().hash(), &block1_hash);
     assert!(store.fetch_block(2, true).is_err());
@@ -1498,59 +1692,51 @@ fn test_invalid_block() {
     let txs = vec![txn_schema!(from: vec![outputs[1][0].clone()], to: vec![4 * T, 4 * T])];
     let coinbase_value = consensus_manager.emission_schedule().block_reward(2);
     unpack_enum!(
-        BlockAddResult::Ok(_b1) = generate_new_block_with_coinbase(
+        BlockAddResult::Ok(_b2) = generate_new_block_with_coinbase(
             &mut store,
-            &factories,
             &mut blocks,
             &mut outputs,
             txs,
             coinbase_value,
-            &consensus_manager
+            &consensus_manager,
+            &key_manager
         )
+        .await
         .unwrap()
     );
     let block2_hash = blocks[2].hash();
     let metadata = store.get_chain_metadata().unwrap();
     assert_eq!(metadata.best_block_height(), 2);
-    assert_eq!(metadata.best_block(), block2_hash);
+    assert_eq!(metadata.best_block_hash(), block2_hash);
     assert_eq!(store.fetch_block(0, true).unwrap().hash(), &block0_hash);
     assert_eq!(store.fetch_block(1, true).unwrap().hash(), &block1_hash);
     assert_eq!(store.fetch_block(2, true).unwrap().hash(), block2_hash);
     assert!(store.fetch_block(3, true).is_err());
 }
 
-#[test]
-fn test_orphan_cleanup_on_block_add() {
+async fn test_orphan_cleanup_on_block_add<B: TestBackend>() {
     let network = Network::LocalNet;
-    let consensus_manager = ConsensusManagerBuilder::new(network).build();
-    let validators = Validators::new(
-        MockValidator::new(true),
-        MockValidator::new(true),
-        MockValidator::new(true),
-    );
-    let db = create_test_db();
+    let consensus_manager = ConsensusManagerBuilder::new(network).build().unwrap();
     let config = BlockchainDatabaseConfig {
         orphan_storage_capacity: 3,
         pruning_horizon: 0,
         pruning_interval: 50,
         ..Default::default()
     };
-    let store = BlockchainDatabase::new(
-        db,
+    let store = create_store_with_consensus_and_validators_and_config::<B>(
         consensus_manager.clone(),
-        validators,
+        create_mock_validators(),
         config,
-        DifficultyCalculator::new(consensus_manager.clone(), Default::default()),
-    )
-    .unwrap();
-
-    let orphan1 = create_orphan_block(500, vec![], &consensus_manager);
-    let orphan2 = create_orphan_block(5, vec![], &consensus_manager);
-    let orphan3 = create_orphan_block(30, vec![], &consensus_manager);
-    let orphan4 = create_orphan_block(700, vec![], &consensus_manager);
-    let orphan5 = create_orphan_block(43, vec![], &consensus_manager);
-    let orphan6 = create_orphan_block(75, vec![], &consensus_manager);
-    let orphan7 = create_orphan_block(150, vec![], &consensus_manager);
+    );
+    let key_manager = create_memory_db_key_manager().unwrap();
+
+    let orphan1 = create_orphan_block(500, vec![], &consensus_manager, &key_manager).await;
+    let orphan2 = create_orphan_block(5, vec![], &consensus_manager, &key_manager).await;
+    let orphan3 = create_orphan_block(30, vec![], &consensus_manager, &key_manager).await;
+    let orphan4 = create_orphan_block(700, vec![], &consensus_manager, &key_manager).await;
+    let orphan5 = create_orphan_block(43, vec![], &consensus_manager, &key_manager).await;
+    let orphan6 = create_orphan_block(75, vec![], &consensus_manager, &key_manager).await;
+    let orphan7 = create_orphan_block(150, vec![], &consensus_manager, &key_manager).await;
     let orphan1_hash = orphan1.hash();
     let orphan2_hash = orphan2.hash();
     let orphan3_hash = orphan3.hash();
@@ -1586,35 +1772,26 @@ fn test_orphan_cleanup_on_block_add() {
     assert_eq!(store.fetch_orphan(orphan7_hash).unwrap(), orphan7);
 }
 
-#[test]
-fn test_horizon_height_orphan_cleanup() {
+async fn test_horizon_height_orphan_cleanup<B: TestBackend>() {
     let network = Network::LocalNet;
-    let block0 = genesis_block::get_esmeralda_genesis_block();
-    let consensus_manager = ConsensusManagerBuilder::new(network).with_block(block0.clone()).build();
-    let validators = Validators::new(
-        MockValidator::new(true),
-        MockValidator::new(true),
-        MockValidator::new(true),
-    );
-    let db = create_test_db();
+    let consensus_manager = ConsensusManagerBuilder::new(network).build().unwrap();
+    let block0 = consensus_manager.get_genesis_block();
     let config = BlockchainDatabaseConfig {
         orphan_storage_capacity: 3,
         pruning_horizon: 2,
         pruning_interval: 50,
         ..Default::default()
     };
-    let store = BlockchainDatabase::new(
-        db,
+    let store = create_store_with_consensus_and_validators_and_config::<B>(
         consensus_manager.clone(),
-        validators,
+        create_mock_validators(),
         config,
-        DifficultyCalculator::new(consensus_manager.clone(), Default::default()),
-    )
-    .unwrap();
-    let orphan1 = create_orphan_block(2, vec![], &consensus_manager);
-    let orphan2 = create_orphan_block(3, vec![], &consensus_manager);
-    let orphan3 = create_orphan_block(1, vec![], &consensus_manager);
-    let orphan4 = create_orphan_block(4, vec![], &consensus_manager);
+    );
+    let key_manager = create_memory_db_key_manager().unwrap();
+    let orphan1 = create_orphan_block(2, vec![], &consensus_manager, &key_manager).await;
+    let orphan2 = create_orphan_block(3, vec![], &consensus_manager, &key_manager).await;
+    let orphan3 = create_orphan_block(1, vec![], &consensus_manager, &key_manager).await;
+    let orphan4 = create_orphan_block(4, vec![], &consensus_manager, &key_manager).await;
     let orphan1_hash = orphan1.hash();
     let orphan2_hash = orphan2.hash();
     let orphan3_hash = orphan3.hash();
@@ -1627,10 +1804,46 @@ fn test_horizon_height_orphan_cleanup() {
     assert_eq!(store.add_block(orphan3.into()).unwrap(), BlockAddResult::OrphanBlock);
     assert_eq!(store.db_read_access().unwrap().orphan_count().unwrap(), 3);
 
-    let block1 = append_block(&store, &block0, vec![], &consensus_manager, Difficulty::min()).unwrap();
-    let block2 = append_block(&store, &block1, vec![], &consensus_manager, Difficulty::min()).unwrap();
-    let block3 = append_block(&store, &block2, vec![], &consensus_manager, Difficulty::min()).unwrap();
-    let _block4 = append_block(&store, &block3, vec![], &consensus_manager, Difficulty::min()).unwrap();
+    let (block1, _) = append_block(
+        &store,
+        &block0,
+        vec![],
+        &consensus_manager,
+        Difficulty::min(),
+        &key_manager,
+    )
+    .await
+    .unwrap();
+    let (block2, _) = append_block(
+        &store,
+        &block1,
+        vec![],
+        &consensus_manager,
+        Difficulty::min(),
+        &key_manager,
+    )
+    .await
+    .unwrap();
+    let (block3, _) = append_block(
+        &store,
+        &block2,
+        vec![],
+        &consensus_manager,
+        Difficulty::min(),
+        &key_manager,
+    )
+    .await
+    .unwrap();
+    let _block4 = append_block(
+        &store,
+        &block3,
+        vec![],
+        &consensus_manager,
+        Difficulty::min(),
+        &key_manager,
+    )
+    .await
+    .unwrap();
 
     // Adding another orphan block will trigger the orphan cleanup as the storage limit was reached
     assert_eq!(
@@ -1645,38 +1858,29 @@ fn test_horizon_height_orphan_cleanup() {
     assert_eq!(store.fetch_orphan(orphan4_hash).unwrap(), orphan4);
 }
 
-#[test]
 #[allow(clippy::too_many_lines)]
-fn test_orphan_cleanup_on_reorg() {
+async fn test_orphan_cleanup_on_reorg<B: TestBackend>() {
     // Create Main Chain
     let network = Network::LocalNet;
-    let factories = CryptoFactories::default();
+    let key_manager = create_memory_db_key_manager().unwrap();
     let consensus_constants = ConsensusConstantsBuilder::new(network).build();
-    let (block0, output) = create_genesis_block( &consensus_constants);
+    let (block0, output) = create_genesis_block(&consensus_constants, &key_manager).await;
     let consensus_manager = ConsensusManagerBuilder::new(network)
         .add_consensus_constants(consensus_constants)
         .with_block(block0.clone())
-        .build();
-    let validators = Validators::new(
-        MockValidator::new(true),
-        MockValidator::new(true),
-        MockValidator::new(true),
-    );
-    let db = create_test_db();
+        .build()
+        .unwrap();
     let config = BlockchainDatabaseConfig {
         orphan_storage_capacity: 3,
         pruning_horizon: 0,
         pruning_interval: 50,
         ..Default::default()
     };
-    let mut store = BlockchainDatabase::new(
-        db,
+    let mut store = create_store_with_consensus_and_validators_and_config::<B>(
         consensus_manager.clone(),
-        validators,
+        create_mock_validators(),
         config,
-        DifficultyCalculator::new(consensus_manager.clone(), Default::default()),
-    )
-    .unwrap();
+    );
     let mut blocks = vec![block0];
     let mut outputs = vec![vec![output]];
 
@@ -1686,9 +1890,11 @@ fn test_orphan_cleanup_on_reorg() {
         &mut blocks,
         &mut outputs,
         vec![],
-        Difficulty::from(2),
+        Difficulty::from_u64(2).unwrap(),
         &consensus_manager,
+        &key_manager,
     )
+    .await
     .unwrap();
     // Block A2
     generate_new_block_with_achieved_difficulty(
@@ -1696,9 +1902,11 @@ fn test_orphan_cleanup_on_reorg() {
         &mut blocks,
         &mut outputs,
         vec![],
-        Difficulty::from(3),
+        Difficulty::from_u64(3).unwrap(),
         &consensus_manager,
+        &key_manager,
     )
+    .await
     .unwrap();
     // Block A3
     generate_new_block_with_achieved_difficulty(
@@ -1706,9 +1914,11 @@ fn test_orphan_cleanup_on_reorg() {
         &mut blocks,
         &mut outputs,
         vec![],
-        Difficulty::from(3),
+        Difficulty::from_u64(3).unwrap(),
         &consensus_manager,
+        &key_manager,
     )
+    .await
     .unwrap();
     // Block A4
     generate_new_block_with_achieved_difficulty(
@@ -1716,13 +1926,15 @@ fn test_orphan_cleanup_on_reorg() {
         &mut blocks,
         &mut outputs,
         vec![],
-        Difficulty::from(3),
+        Difficulty::from_u64(3).unwrap(),
         &consensus_manager,
+        &key_manager,
     )
+    .await
     .unwrap();
 
     // Create Forked Chain
-    let mut orphan_store = create_store_with_consensus(consensus_manager.clone());
+    let mut orphan_store = create_store_with_consensus::<B>(consensus_manager.clone());
     let mut orphan_blocks = vec![blocks[0].clone()];
     let mut orphan_outputs = vec![outputs[0].clone()];
     // Block B1
@@ -1731,9 +1943,11 @@ fn test_orphan_cleanup_on_reorg() {
         &mut orphan_blocks,
         &mut orphan_outputs,
         vec![],
-        Difficulty::from(2),
+        Difficulty::from_u64(2).unwrap(),
         &consensus_manager,
+        &key_manager,
     )
+    .await
     .unwrap();
     // Block B2
     generate_new_block_with_achieved_difficulty(
@@ -1741,9 +1955,11 @@ fn test_orphan_cleanup_on_reorg() {
         &mut orphan_blocks,
         &mut orphan_outputs,
         vec![],
-        Difficulty::from(10),
+        Difficulty::from_u64(10).unwrap(),
         &consensus_manager,
+        &key_manager,
     )
+    .await
     .unwrap();
     // Block B3
     generate_new_block_with_achieved_difficulty(
@@ -1751,14 +1967,16 @@ fn test_orphan_cleanup_on_reorg() {
         &mut orphan_blocks,
         &mut orphan_outputs,
         vec![],
-        Difficulty::from(15),
+        Difficulty::from_u64(15).unwrap(),
         &consensus_manager,
+        &key_manager,
     )
+    .await
     .unwrap();
 
     // Fill orphan block pool
-    let orphan1 = create_orphan_block(1, vec![], &consensus_manager);
-    let orphan2 = create_orphan_block(1, vec![], &consensus_manager);
+    let orphan1 = create_orphan_block(1, vec![], &consensus_manager, &key_manager).await;
+    let orphan2 = create_orphan_block(1, vec![], &consensus_manager, &key_manager).await;
     assert_eq!(store.add_block(orphan1.into()).unwrap(), BlockAddResult::OrphanBlock);
     assert_eq!(store.add_block(orphan2.into()).unwrap(), BlockAddResult::OrphanBlock);
 
@@ -1782,15 +2000,12 @@ fn test_orphan_cleanup_on_reorg() {
     assert_eq!(store.fetch_orphan(*blocks[4].hash()).unwrap(), *blocks[4].block());
 }
 
-#[test]
-fn test_orphan_cleanup_delete_all_orphans() {
+#[tokio::test]
+async fn test_orphan_cleanup_delete_all_orphans() {
     let path = create_temporary_data_path();
     let network = Network::LocalNet;
-    let validators = Validators::new(
-        MockValidator::new(true),
-        MockValidator::new(true),
-        MockValidator::new(true),
-    );
+    let validators = create_mock_validators();
+    let key_manager = create_memory_db_key_manager().unwrap();
     let mut config = BlockchainDatabaseConfig {
         orphan_storage_capacity: 5,
         pruning_horizon: 0,
@@ -1799,7 +2014,7 @@ fn test_orphan_cleanup_delete_all_orphans() {
     };
     // Test cleanup during runtime
     {
-        let consensus_manager = ConsensusManager::builder(network).build();
+        let consensus_manager = ConsensusManager::builder(network).build().unwrap();
         let db = create_lmdb_database(&path, LMDBConfig::default(), consensus_manager.clone()).unwrap();
         let store = BlockchainDatabase::new(
             db,
@@ -1807,14 +2022,15 @@ fn test_orphan_cleanup_delete_all_orphans() {
             validators.clone(),
             config,
             DifficultyCalculator::new(consensus_manager.clone(), Default::default()),
+            Arc::new(RwLock::new(OutputSmt::new())),
         )
         .unwrap();
 
-        let orphan1 = create_orphan_block(500, vec![], &consensus_manager);
-        let orphan2 = create_orphan_block(5, vec![], &consensus_manager);
-        let orphan3 = create_orphan_block(30, vec![], &consensus_manager);
-        let orphan4 = create_orphan_block(700, vec![], &consensus_manager);
-        let orphan5 = create_orphan_block(43, vec![], &consensus_manager);
+        let orphan1 = create_orphan_block(500, vec![], &consensus_manager, &key_manager).await;
+        let orphan2 = create_orphan_block(5, vec![], &consensus_manager, &key_manager).await;
+        let orphan3 = create_orphan_block(30, vec![], &consensus_manager, &key_manager).await;
+        let orphan4 = create_orphan_block(700, vec![], &consensus_manager, &key_manager).await;
+        let orphan5 = create_orphan_block(43, vec![], &consensus_manager, &key_manager).await;
 
         // Add orphans and verify
         assert_eq!(
@@ -1853,7 +2069,7 @@ fn test_orphan_cleanup_delete_all_orphans() {
 
     // Test orphans are present on open
     {
-        let consensus_manager = ConsensusManager::builder(Network::LocalNet).build();
+        let consensus_manager = ConsensusManager::builder(network).build().unwrap();
         let db = create_lmdb_database(&path, LMDBConfig::default(), consensus_manager.clone()).unwrap();
         let store = BlockchainDatabase::new(
             db,
@@ -1861,6 +2077,7 @@ fn test_orphan_cleanup_delete_all_orphans() {
             validators.clone(),
             config,
             DifficultyCalculator::new(consensus_manager, Default::default()),
+            Arc::new(RwLock::new(OutputSmt::new())),
         )
         .unwrap();
         assert_eq!(store.db_read_access().unwrap().orphan_count().unwrap(), 5);
@@ -1868,7 +2085,7 @@ fn test_orphan_cleanup_delete_all_orphans() {
 
     // Test orphans cleanup on open
     {
-        let consensus_manager = ConsensusManager::builder(Network::LocalNet).build();
+        let consensus_manager = ConsensusManager::builder(network).build().unwrap();
         let db = create_lmdb_database(&path, LMDBConfig::default(), consensus_manager.clone()).unwrap();
         config.cleanup_orphans_at_startup = true;
         let store = BlockchainDatabase::new(
@@ -1877,6 +2094,7 @@ fn test_orphan_cleanup_delete_all_orphans() {
             validators,
             config,
             DifficultyCalculator::new(consensus_manager, Default::default()),
+            Arc::new(RwLock::new(OutputSmt::new())),
         )
         .unwrap();
         assert_eq!(store.db_read_access().unwrap().orphan_count().unwrap(), 0);
@@ -1887,36 +2105,29 @@ fn test_orphan_cleanup_delete_all_orphans() {
     }
 }
 
-#[test]
-fn test_fails_validation() {
+async fn test_fails_validation<B: TestBackend>() {
     let network = Network::LocalNet;
-    let factories = CryptoFactories::default();
+    let key_manager = create_memory_db_key_manager().unwrap();
     let consensus_constants = ConsensusConstantsBuilder::new(network).build();
-    let (block0, output) = create_genesis_block(&factories, &consensus_constants);
+    let (block0, output) = create_genesis_block(&consensus_constants, &key_manager).await;
     let consensus_manager = ConsensusManagerBuilder::new(network)
         .add_consensus_constants(consensus_constants)
         .with_block(block0.clone())
-        .build();
+        .build()
+        .unwrap();
     let validators = Validators::new(
         MockValidator::new(false),
         MockValidator::new(true),
         MockValidator::new(true),
     );
-    let db = create_test_db();
     let config = BlockchainDatabaseConfig {
         orphan_storage_capacity: 3,
         pruning_horizon: 0,
         pruning_interval: 50,
         ..Default::default()
     };
-    let mut store = BlockchainDatabase::new(
-        db,
-        consensus_manager.clone(),
-        validators,
-        config,
-        DifficultyCalculator::new(consensus_manager.clone(), Default::default()),
-    )
-    .unwrap();
+    let mut store =
+        create_store_with_consensus_and_validators_and_config::<B>(consensus_manager.clone(), validators, config);
     let mut blocks = vec![block0];
     let mut outputs = vec![vec![]];
 
@@ -1926,51 +2137,66 @@ fn test_fails_validation() {
         &mut blocks,
         &mut outputs,
         schemas,
-        Difficulty::from(3),
+        Difficulty::from_u64(3).unwrap(),
         &consensus_manager,
+        &key_manager,
     )
+    .await
     .unwrap_err();
     unpack_enum!(ChainStorageError::ValidationError { source } = err);
-    unpack_enum!(ValidationError::CustomError(_s) = source);
+    unpack_enum!(ValidationError::ConsensusError(_s) = source);
 
     let metadata = store.get_chain_metadata().unwrap();
     assert_eq!(metadata.best_block_height(), 0);
 }
 
-#[test]
-fn pruned_mode_cleanup_and_fetch_block() {
+async fn pruned_mode_cleanup_and_fetch_block<B: TestBackend>() {
     let network = Network::LocalNet;
-    let block0 = genesis_block::get_esmeralda_genesis_block();
-    let consensus_manager = ConsensusManagerBuilder::new(network).with_block(block0.clone()).build();
-    let validators = Validators::new(
-        MockValidator::new(true),
-        MockValidator::new(true),
-        MockValidator::new(true),
-    );
-    let db = create_test_db();
+    let consensus_manager = ConsensusManagerBuilder::new(network).build().unwrap();
+    let block0 = consensus_manager.get_genesis_block();
     let config = BlockchainDatabaseConfig {
         orphan_storage_capacity: 3,
         pruning_horizon: 3,
         pruning_interval: 1,
         ..Default::default()
     };
-    let store = BlockchainDatabase::new(
-        db,
+    let store = create_store_with_consensus_and_validators_and_config::<B>(
         consensus_manager.clone(),
-        validators,
+        create_mock_validators(),
         config,
-        DifficultyCalculator::new(consensus_manager.clone(), Default::default()),
-    )
-    .unwrap();
-    let block1 = append_block(&store, &block0, vec![], &consensus_manager, Difficulty::min()).unwrap();
-    let block2 = append_block(&store, &block1, vec![], &consensus_manager, Difficulty::min()).unwrap();
-    let block3 = append_block(&store, &block2, vec![], &consensus_manager, Difficulty::min()).unwrap();
+    );
+    let key_manager = create_memory_db_key_manager().unwrap();
+    let mut prev_block = block0;
+    for _ in 0..3 {
+        let (block, _) = append_block(
+            &store,
+            &prev_block,
+            vec![],
+            &consensus_manager,
+            Difficulty::min(),
+            &key_manager,
+        )
+        .await
+        .unwrap();
+        prev_block = block;
+    }
 
     let metadata = store.get_chain_metadata().unwrap();
     assert_eq!(metadata.pruned_height(), 0);
 
-    let block4 = append_block(&store, &block3, vec![], &consensus_manager, Difficulty::min()).unwrap();
-    let _block5 = append_block(&store, &block4, vec![], &consensus_manager, Difficulty::min()).unwrap();
+    for _ in 0..2 {
+        let (block, _) = append_block(
+            &store,
+            &prev_block,
+            vec![],
+            &consensus_manager,
+            Difficulty::min(),
+            &key_manager,
+        )
+        .await
+        .unwrap();
+        prev_block = block;
+    }
 
     let metadata = store.get_chain_metadata().unwrap();
     assert_eq!(metadata.pruned_height(), 2);
@@ -1979,7 +2205,7 @@ fn pruned_mode_cleanup_and_fetch_block() {
 }
 
 mod malleability {
-    use tari_common_types::types::{ComAndPubSignature, RangeProof};
+    use tari_common_types::types::{ComAndPubSignature, FixedHash, RangeProof};
     use tari_core::{
         blocks::Block,
         covenant,
@@ -1996,140 +2222,157 @@ mod malleability {
 
         use super::*;
 
-        // This test hightlights that the "version" field is not being included in the input hash
-        // so a consensus change is needed for the input to include it
-        #[test]
-        fn test_version() {
+        #[tokio::test]
+        async fn test_version() {
             check_input_malleability(|block: &mut Block| {
-                let input = &mut block.body.inputs_mut()[0];
-                let mod_version = match input.version {
-                    TransactionInputVersion::V0 => TransactionInputVersion::V1,
-                    _ => TransactionInputVersion::V0,
-                };
-                input.version = mod_version;
-            });
+                modify_input(block, |input| {
+                    input.version = match input.version {
+                        TransactionInputVersion::V0 => TransactionInputVersion::V1,
+                        _ => TransactionInputVersion::V0,
+                    };
+                });
+            })
+            .await;
         }
 
-        #[test]
-        fn test_spent_output() {
+        #[tokio::test]
+        async fn test_spent_output() {
             check_input_malleability(|block: &mut Block| {
                 // to modify the spent output, we will substitue it for a copy of a different output
                 // we will use one of the outputs of the current transaction
                 // because of how the test blockchain is created, they will never be equal
-                let output = &block.body.outputs()[0].clone();
-                let input = &mut block.body.inputs_mut()[0];
-                input.add_output_data(
-                    output.version,
-                    output.features.clone(),
-                    output.commitment.clone(),
-                    output.script.clone(),
-                    output.sender_offset_public_key.clone(),
-                    output.covenant.clone(),
-                    output.encrypted_data,
-                    output.minimum_value_promise,
-                );
-            });
+                let output = block.body.outputs()[0].clone();
+                let rangeproof_hash = output.proof.as_ref().map_or(FixedHash::zero(), |proof| proof.hash());
+                modify_input(block, |input| {
+                    input.add_output_data(
+                        output.version,
+                        output.features,
+                        output.commitment,
+                        output.script,
+                        output.sender_offset_public_key,
+                        output.covenant,
+                        output.encrypted_data,
+                        output.metadata_signature,
+                        rangeproof_hash,
+                        output.minimum_value_promise,
+                    );
+                });
+            })
+            .await;
         }
 
-        #[test]
-        fn test_input_data() {
+        #[tokio::test]
+        async fn test_input_data() {
             check_input_malleability(|block: &mut Block| {
-                block.body.inputs_mut()[0]
-                    .input_data
-                    .push(StackItem::Hash(*b"I can't do whatever I want......"))
-                    .unwrap();
-            });
+                modify_input(block, |input| {
+                    input
+                        .input_data
+                        .push(StackItem::Hash(*b"I can't do whatever I want......"))
+                        .unwrap();
+                });
+            })
+            .await;
         }
 
-        #[test]
-        fn test_script_signature() {
+        #[tokio::test]
+        async fn test_script_signature() {
             check_input_malleability(|block: &mut Block| {
-                let input = &mut block.body.inputs_mut()[0];
-                input.script_signature = ComAndPubSignature::default();
-            });
+                modify_input(block, |input| {
+                    input.script_signature = ComAndPubSignature::default();
+                });
+            })
+            .await;
         }
     }
 
     mod output {
         use super::*;
 
-        #[test]
-        fn test_version() {
+        #[tokio::test]
+        async fn test_version() {
             check_output_malleability(|block: &mut Block| {
-                let output = &mut block.body.outputs_mut()[0];
-                let mod_version = match output.version {
-                    TransactionOutputVersion::V0 => TransactionOutputVersion::V1,
-                    _ => TransactionOutputVersion::V0,
-                };
-                output.version = mod_version;
-            });
+                modify_output(block, |output| {
+                    output.version = match output.version {
+                        TransactionOutputVersion::V0 => TransactionOutputVersion::V1,
+                        _ => TransactionOutputVersion::V0,
+                    };
+                });
+            })
+            .await;
         }
 
-        #[test]
-        fn test_features() {
+        #[tokio::test]
+        async fn test_features() {
             check_output_malleability(|block: &mut Block| {
-                let output = &mut block.body.outputs_mut()[0];
-                output.features.maturity += 1;
-            });
+                modify_output(block, |output| {
+                    output.features.maturity += 1;
+                });
+            })
+            .await;
         }
 
-        #[test]
-        fn test_commitment() {
+        #[tokio::test]
+        async fn test_commitment() {
             check_output_malleability(|block: &mut Block| {
-                let output = &mut block.body.outputs_mut()[0];
-                let mod_commitment = &output.commitment + &output.commitment;
-                output.commitment = mod_commitment;
-            });
+                modify_output(block, |output| {
+                    output.commitment = &output.commitment + &output.commitment;
+                });
+            })
+            .await;
         }
 
-        #[test]
-        fn test_proof() {
+        #[tokio::test]
+        async fn test_proof() {
             check_output_malleability(|block: &mut Block| {
-                let output = &mut block.body.outputs_mut()[0];
-                let mod_proof = RangeProof::from_hex(&(output.proof.as_ref().unwrap().to_hex() + "00")).unwrap();
-                output.proof = Some(mod_proof);
-            });
+                modify_output(block, |output| {
+                    let mod_proof = RangeProof::from_hex(&(output.proof.as_ref().unwrap().to_hex() + "00")).unwrap();
+                    output.proof = Some(mod_proof);
+                });
+            })
+            .await;
         }
 
-        #[test]
-        fn test_script() {
+        #[tokio::test]
+        async fn test_script() {
             check_output_malleability(|block: &mut Block| {
-                let output = &mut block.body.outputs_mut()[0];
-                let mut script_bytes = output.script.to_bytes();
-                Opcode::PushZero.to_bytes(&mut script_bytes);
-                let mod_script = TariScript::from_bytes(&script_bytes).unwrap();
-                output.script = mod_script;
-            });
+                modify_output(block, |output| {
+                    let mut script_bytes = output.script.to_bytes();
+                    Opcode::PushZero.to_bytes(&mut script_bytes);
+                    output.script = TariScript::from_bytes(&script_bytes).unwrap();
+                });
+            })
+            .await;
         }
 
-        // This test hightlights that the "sender_offset_public_key" field is not being included in the output hash
-        // so a consensus change is needed for the output to include it
-        #[test]
-        fn test_sender_offset_public_key() {
+        #[tokio::test]
+        async fn test_sender_offset_public_key() {
             check_output_malleability(|block: &mut Block| {
-                let output = &mut block.body.outputs_mut()[0];
-
-                // "gerate_keys" should return a random, different key than the present one
-                let mod_pk = generate_keys().pk;
-                output.sender_offset_public_key = mod_pk;
-            });
+                modify_output(block, |output| {
+                    // "gerate_keys" should return a random, different key than the present one
+                    output.sender_offset_public_key = generate_keys().pk;
+                });
+            })
+            .await;
         }
 
-        #[test]
-        fn test_metadata_signature() {
+        #[tokio::test]
+        async fn test_metadata_signature() {
             check_output_malleability(|block: &mut Block| {
-                let output = &mut block.body.outputs_mut()[0];
-                output.metadata_signature = ComAndPubSignature::default();
-            });
+                modify_output(block, |output| {
+                    output.metadata_signature = ComAndPubSignature::default();
+                });
+            })
+            .await;
         }
 
-        #[test]
-        fn test_covenant() {
+        #[tokio::test]
+        async fn test_covenant() {
             check_output_malleability(|block: &mut Block| {
-                let output = &mut block.body.outputs_mut()[0];
-                let mod_covenant = covenant!(absolute_height(@uint(42))).unwrap();
-                output.covenant = mod_covenant;
-            });
+                modify_output(block, |output| {
+                    output.covenant = covenant!(absolute_height(@uint(42))).unwrap();
+                });
+            })
+            .await;
         }
     }
 
@@ -2142,49 +2385,57 @@ mod malleability {
         // the "version" field only has one value (V0) so malleability test is not possible for it
         // the "features" field has only a constant value at the moment, so no malleability test possible
 
-        #[test]
-        fn test_fee() {
+        #[tokio::test]
+        async fn test_fee() {
             check_kernel_malleability(|block: &mut Block| {
-                let kernel = &mut block.body.kernels_mut()[0];
-                kernel.fee += MicroMinotari::from(1);
-            });
+                modify_kernel(block, |kernel| {
+                    kernel.fee += MicroMinotari::from(1);
+                });
+            })
+            .await;
         }
 
-        #[test]
-        fn test_lock_height() {
+        #[tokio::test]
+        async fn test_lock_height() {
             check_kernel_malleability(|block: &mut Block| {
-                let kernel = &mut block.body.kernels_mut()[0];
-                kernel.lock_height += 1;
-            });
+                modify_kernel(block, |kernel| {
+                    kernel.lock_height += 1;
+                });
+            })
+            .await;
         }
 
-        #[test]
-        fn test_excess() {
+        #[tokio::test]
+        async fn test_excess() {
             check_kernel_malleability(|block: &mut Block| {
-                let kernel = &mut block.body.kernels_mut()[0];
-                let mod_excess = &kernel.excess + &kernel.excess;
-                kernel.excess = mod_excess;
-            });
+                modify_kernel(block, |kernel| {
+                    kernel.excess = &kernel.excess + &kernel.excess;
+                });
+            })
+            .await;
         }
 
-        #[test]
-        fn test_excess_sig() {
+        #[tokio::test]
+        async fn test_excess_sig() {
             check_kernel_malleability(|block: &mut Block| {
-                let kernel = &mut block.body.kernels_mut()[0];
-                // "gerate_keys" should return a group of random keys, different from the ones in the field
-                let keys = generate_keys();
-                kernel.excess_sig = Signature::new(keys.pk, keys.k);
-            });
+                modify_kernel(block, |kernel| {
+                    // "gerate_keys" should return a group of random keys, different from the ones in the field
+                    let keys = generate_keys();
+                    kernel.excess_sig = Signature::new(keys.pk, keys.k);
+                });
+            })
+            .await;
         }
     }
 }
 
 #[allow(clippy::identity_op)]
-#[test]
-fn test_fetch_deleted_position_block_hash() {
+#[allow(clippy::too_many_lines)]
+async fn test_fetch_spent_output_block_hash<B: TestBackend>() {
     // Create Main Chain
     let network = Network::LocalNet;
-    let (mut store, mut blocks, mut outputs, consensus_manager) = create_new_blockchain(network);
+    let (mut store, mut blocks, mut outputs, consensus_manager, key_manager) =
+        create_new_blockchain_with_backend::<B>(network).await;
     // Block 1
     let txs = vec![txn_schema!(
         from: vec![outputs[0][0].clone()],
@@ -2195,9 +2446,11 @@ fn test_fetch_deleted_position_block_hash() {
         &mut blocks,
         &mut outputs,
         txs,
-        Difficulty::from(1),
+        Difficulty::from_u64(1).unwrap(),
         &consensus_manager,
+        &key_manager,
     )
+    .await
     .unwrap()
     .assert_added();
     // Block 2
@@ -2207,21 +2460,25 @@ fn test_fetch_deleted_position_block_hash() {
         &mut blocks,
         &mut outputs,
         txs,
-        Difficulty::from(3),
+        Difficulty::from_u64(3).unwrap(),
         &consensus_manager,
+        &key_manager,
     )
+    .await
     .unwrap()
     .assert_added();
-    // Blocks 3 - 12 so we can test the search in the bottom and top half
+    // Blocks 3 - 12 so the spent outputs are spread out over the chain
     for i in 0..10 {
         generate_new_block_with_achieved_difficulty(
             &mut store,
             &mut blocks,
             &mut outputs,
             vec![],
-            Difficulty::from(4 + i),
+            Difficulty::from_u64(4 + i).unwrap(),
             &consensus_manager,
+            &key_manager,
         )
+        .await
         .unwrap()
         .assert_added();
     }
@@ -2232,9 +2489,11 @@ fn test_fetch_deleted_position_block_hash() {
         &mut blocks,
         &mut outputs,
         txs,
-        Difficulty::from(30),
+        Difficulty::from_u64(30).unwrap(),
         &consensus_manager,
+        &key_manager,
     )
+    .await
     .unwrap()
     .assert_added();
     // Block 14
@@ -2244,31 +2503,31 @@ fn test_fetch_deleted_position_block_hash() {
         &mut blocks,
         &mut outputs,
         txs,
-        Difficulty::from(50),
+        Difficulty::from_u64(50).unwrap(),
         &consensus_manager,
+        &key_manager,
     )
+    .await
     .unwrap()
     .assert_added();
 
-    let block1_hash = store.fetch_header(1).unwrap().unwrap().hash();
-    let block2_hash = store.fetch_header(2).unwrap().unwrap().hash();
-    let block13_hash = store.fetch_header(13).unwrap().unwrap().hash();
-    let block14_hash = store.fetch_header(14).unwrap().unwrap().hash();
-
-    let deleted_positions = store
-        .fetch_complete_deleted_bitmap_at(block14_hash)
+    let mut spent_output_hashes = Vec::new();
+    for output in [&outputs[0][0], &outputs[1][3], &outputs[2][0], &outputs[13][0]] {
+        spent_output_hashes.push(output.hash(&key_manager).await.unwrap());
+    }
+    let mut spent_in = store
+        .fetch_inputs_mined_info(spent_output_hashes)
         .unwrap()
-        .bitmap()
-        .to_vec();
-
-    let headers = store
-        .fetch_header_hash_by_deleted_mmr_positions(deleted_positions)
-        .unwrap();
-    let mut headers = headers.into_iter().map(Option::unwrap).collect::<Vec<_>>();
-    headers.sort_by(|(a, _), (b, _)| a.cmp(b));
-
-    assert_eq!(headers[3], (14, block14_hash));
-    assert_eq!(headers[2], (13, block13_hash));
-    assert_eq!(headers[1], (2, block2_hash));
-    assert_eq!(headers[0], (1, block1_hash));
+        .into_iter()
+        .map(|info| {
+            let info = info.unwrap();
+            (info.spent_height, info.header_hash)
+        })
+        .collect::<Vec<_>>();
+    spent_in.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    assert_eq!(spent_in[3], (14, store.fetch_header(14).unwrap().unwrap().hash()));
+    assert_eq!(spent_in[2], (13, store.fetch_header(13).unwrap().unwrap().hash()));
+    assert_eq!(spent_in[1], (2, store.fetch_header(2).unwrap().unwrap().hash()));
+    assert_eq!(spent_in[0], (1, store.fetch_header(1).unwrap().unwrap().hash()));
 }
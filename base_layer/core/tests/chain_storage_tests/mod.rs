@@ -21,5 +21,47 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 //
 
+//! The chain storage tests are generic over the [BlockchainBackend](tari_core::chain_storage::BlockchainBackend) and
+//! are run against every backend that implements [TestBackend](crate::helpers::database::TestBackend).
+
+/// Instantiates a `#[tokio::test]` for each of the given generic tests, once for every backend
+macro_rules! backend_tests {
+    ($($test:ident),+ $(,)?) => {
+        mod lmdb {
+            use tari_core::test_helpers::blockchain::TempDatabase;
+
+            $(
+                #[tokio::test]
+                async fn $test() {
+                    super::$test::<TempDatabase>().await;
+                }
+            )+
+        }
+
+        mod memory {
+            use tari_core::chain_storage::MemoryDatabase;
+
+            $(
+                #[tokio::test]
+                async fn $test() {
+                    super::$test::<MemoryDatabase>().await;
+                }
+            )+
+        }
+
+        #[cfg(feature = "redb")]
+        mod redb {
+            use tari_core::chain_storage::RedbDatabase;
+
+            $(
+                #[tokio::test]
+                async fn $test() {
+                    super::$test::<RedbDatabase>().await;
+                }
+            )+
+        }
+    };
+}
+
 mod chain_backend;
 mod chain_storage;
@@ -23,9 +23,16 @@
 use tari_core::{
     blocks::Block,
     transactions::{
+        aggregated_body::AggregateBody,
         tari_amount::T,
         test_helpers::schema_to_transaction,
-        transaction_components::{TransactionInputVersion, TransactionOutputVersion},
+        transaction_components::{
+            TransactionInput,
+            TransactionInputVersion,
+            TransactionKernel,
+            TransactionOutput,
+            TransactionOutputVersion,
+        },
     },
     txn_schema,
 };
@@ -54,6 +61,30 @@ pub async fn check_kernel_malleability(block_mod_fn: impl Fn(&mut Block)) {
     check_block_changes_are_detected(MerkleMountainRangeField::Kernel, block_mod_fn).await;
 }
 
+/// Applies `modify` to the first input of the block body
+#[allow(dead_code)]
+pub fn modify_input(block: &mut Block, modify: impl FnOnce(&mut TransactionInput)) {
+    let (mut inputs, outputs, kernels) = block.body.clone().dissolve();
+    modify(&mut inputs[0]);
+    block.body = AggregateBody::new(inputs, outputs, kernels);
+}
+
+/// Applies `modify` to the first output of the block body
+#[allow(dead_code)]
+pub fn modify_output(block: &mut Block, modify: impl FnOnce(&mut TransactionOutput)) {
+    let (inputs, mut outputs, kernels) = block.body.clone().dissolve();
+    modify(&mut outputs[0]);
+    block.body = AggregateBody::new(inputs, outputs, kernels);
+}
+
+/// Applies `modify` to the first kernel of the block body
+#[allow(dead_code)]
+pub fn modify_kernel(block: &mut Block, modify: impl FnOnce(&mut TransactionKernel)) {
+    let (inputs, outputs, mut kernels) = block.body.clone().dissolve();
+    modify(&mut kernels[0]);
+    block.body = AggregateBody::new(inputs, outputs, kernels);
+}
+
 #[allow(dead_code)]
 async fn check_block_changes_are_detected(field: MerkleMountainRangeField, block_mod_fn: impl Fn(&mut Block)) {
     // create a blockchain with a couple of valid blocks
@@ -90,6 +121,8 @@ async fn check_block_changes_are_detected(field: MerkleMountainRangeField, block
     // create an altered version of the last block, using the provided modification function
     let mut mod_block = block.block().clone();
     block_mod_fn(&mut mod_block);
+    // the modification may have changed the order of the body components
+    mod_block.body.sort();
 
     // calculate the metadata for the modified block
     blockchain
@@ -20,17 +20,122 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::convert::TryInto;
+use std::{
+    convert::TryInto,
+    sync::{Arc, RwLock},
+};
 
 use tari_core::{
     blocks::{Block, BlockHeader, NewBlockTemplate},
+    chain_storage::{
+        create_memory_database,
+        BlockchainBackend,
+        BlockchainDatabase,
+        BlockchainDatabaseConfig,
+        MemoryDatabase,
+        Validators,
+    },
     consensus::{emission::Emission, ConsensusManager},
     proof_of_work::Difficulty,
-    transactions::{key_manager::MemoryDbKeyManager, tari_amount::MicroMinotari, transaction_components::Transaction},
+    test_helpers::{blockchain::TempDatabase, create_consensus_rules},
+    transactions::{
+        key_manager::MemoryDbKeyManager,
+        tari_amount::MicroMinotari,
+        transaction_components::Transaction,
+        CryptoFactories,
+    },
+    validation::{
+        block_body::{BlockBodyFullValidator, BlockBodyInternalConsistencyValidator},
+        mocks::MockValidator,
+        DifficultyCalculator,
+    },
+    OutputSmt,
 };
 
 use crate::helpers::block_builders::create_coinbase;
 
+/// A blockchain backend that the chain storage tests are run against
+pub trait TestBackend: BlockchainBackend + Sized + 'static {
+    /// Creates a new, empty database
+    fn create_test_db(consensus_manager: &ConsensusManager) -> Self;
+}
+
+impl TestBackend for TempDatabase {
+    fn create_test_db(_: &ConsensusManager) -> Self {
+        TempDatabase::new()
+    }
+}
+
+impl TestBackend for MemoryDatabase {
+    fn create_test_db(consensus_manager: &ConsensusManager) -> Self {
+        create_memory_database(consensus_manager.clone())
+    }
+}
+
+#[cfg(feature = "redb")]
+impl TestBackend for tari_core::chain_storage::RedbDatabase {
+    fn create_test_db(consensus_manager: &ConsensusManager) -> Self {
+        // redb can run on an in-memory backend, so there are no database files to clean up
+        let db = redb::Database::builder()
+            .create_with_backend(redb::backends::InMemoryBackend::new())
+            .unwrap();
+        Self::new(db, consensus_manager.clone()).unwrap()
+    }
+}
+
+/// Returns validators that accept every block
+#[allow(dead_code)]
+pub fn create_mock_validators<B: BlockchainBackend>() -> Validators<B> {
+    Validators::new(
+        MockValidator::new(true),
+        MockValidator::new(true),
+        MockValidator::new(true),
+    )
+}
+
+/// Create a new blockchain database on the given backend, containing only the genesis block
+#[allow(dead_code)]
+pub fn create_store_with_consensus_and_validators_and_config<B: TestBackend>(
+    rules: ConsensusManager,
+    validators: Validators<B>,
+    config: BlockchainDatabaseConfig,
+) -> BlockchainDatabase<B> {
+    BlockchainDatabase::new(
+        B::create_test_db(&rules),
+        rules.clone(),
+        validators,
+        config,
+        DifficultyCalculator::new(rules, Default::default()),
+        Arc::new(RwLock::new(OutputSmt::new())),
+    )
+    .unwrap()
+}
+
+#[allow(dead_code)]
+pub fn create_store_with_consensus_and_validators<B: TestBackend>(
+    rules: ConsensusManager,
+    validators: Validators<B>,
+) -> BlockchainDatabase<B> {
+    create_store_with_consensus_and_validators_and_config(rules, validators, BlockchainDatabaseConfig::default())
+}
+
+/// Create a new blockchain database on the given backend that fully validates block bodies
+#[allow(dead_code)]
+pub fn create_store_with_consensus<B: TestBackend>(rules: ConsensusManager) -> BlockchainDatabase<B> {
+    let factories = CryptoFactories::default();
+    let validators = Validators::new(
+        BlockBodyFullValidator::new(rules.clone(), true),
+        MockValidator::new(true),
+        BlockBodyInternalConsistencyValidator::new(rules.clone(), false, factories),
+    );
+    create_store_with_consensus_and_validators(rules, validators)
+}
+
+#[allow(dead_code)]
+pub fn create_test_blockchain_db<B: TestBackend>() -> BlockchainDatabase<B> {
+    create_store_with_consensus(create_consensus_rules())
+}
+
 /// Create a partially constructed block using the provided set of transactions
 /// is chain_block, or rename it to `create_orphan_block` and drop the prev_block argument
 #[allow(dead_code)]
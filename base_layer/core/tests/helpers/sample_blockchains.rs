@@ -21,25 +21,24 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 //
 
-use std::sync::{Arc, RwLock};
-
 use tari_common::configuration::Network;
 use tari_core::{
     blocks::ChainBlock,
     chain_storage::{BlockchainDatabase, BlockchainDatabaseConfig, Validators},
     consensus::{ConsensusConstants, ConsensusConstantsBuilder, ConsensusManager, ConsensusManagerBuilder},
-    test_helpers::blockchain::{create_store_with_consensus, TempDatabase},
+    test_helpers::blockchain::TempDatabase,
     transactions::{
         key_manager::{create_memory_db_key_manager, MemoryDbKeyManager},
         tari_amount::{uT, T},
         transaction_components::WalletOutput,
     },
     txn_schema,
-    validation::DifficultyCalculator,
-    OutputSmt,
 };
 
-use crate::helpers::block_builders::{create_genesis_block, generate_new_block};
+use crate::helpers::{
+    block_builders::{create_genesis_block, generate_new_block},
+    database::{create_store_with_consensus, create_store_with_consensus_and_validators_and_config, TestBackend},
+};
 
 static EMISSION: [u64; 2] = [10, 10];
 
@@ -189,6 +188,20 @@ pub async fn create_new_blockchain(
     Vec<Vec<WalletOutput>>,
     ConsensusManager,
     MemoryDbKeyManager,
+) {
+    create_new_blockchain_with_backend(network).await
+}
+
+/// Create a new blockchain database on the given backend containing only the Genesis block
+#[allow(dead_code)]
+pub async fn create_new_blockchain_with_backend<B: TestBackend>(
+    network: Network,
+) -> (
+    BlockchainDatabase<B>,
+    Vec<ChainBlock>,
+    Vec<Vec<WalletOutput>>,
+    ConsensusManager,
+    MemoryDbKeyManager,
 ) {
     let key_manager = create_memory_db_key_manager().unwrap();
     let consensus_constants = consensus_constants(network).build();
@@ -235,14 +248,15 @@ pub async fn create_new_blockchain_with_constants(
     )
 }
 
-/// Create a new blockchain database containing only the Genesis block
+/// Create a new blockchain database on the given backend with the given validators and config, containing only the
+/// Genesis block
 #[allow(dead_code)]
-pub async fn create_new_blockchain_lmdb(
+pub async fn create_new_blockchain_with_validators<B: TestBackend>(
     network: Network,
-    validators: Validators<TempDatabase>,
+    validators: Validators<B>,
     config: BlockchainDatabaseConfig,
 ) -> (
-    BlockchainDatabase<TempDatabase>,
+    BlockchainDatabase<B>,
     Vec<ChainBlock>,
     Vec<Vec<WalletOutput>>,
     ConsensusManager,
@@ -256,16 +270,6 @@ pub async fn create_new_blockchain_lmdb(
         .with_block(block0.clone())
         .build()
         .unwrap();
-    let db = TempDatabase::new();
-    let smt = Arc::new(RwLock::new(OutputSmt::new()));
-    let db = BlockchainDatabase::new(
-        db,
-        consensus_manager.clone(),
-        validators,
-        config,
-        DifficultyCalculator::new(consensus_manager.clone(), Default::default()),
-        smt,
-    )
-    .unwrap();
+    let db = create_store_with_consensus_and_validators_and_config(consensus_manager.clone(), validators, config);
     (db, vec![block0], vec![vec![output]], consensus_manager, key_manager)
 }
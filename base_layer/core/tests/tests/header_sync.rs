@@ -20,9 +20,15 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+use futures::StreamExt;
 use tari_core::{
-    base_node::{state_machine_service::states::StateEvent, sync::HeaderSyncStatus},
+    base_node::{
+        state_machine_service::states::StateEvent,
+        sync::{rpc::BaseNodeSyncRpcClient, HeaderSyncStatus},
+    },
+    blocks::BlockHeader,
     chain_storage::BlockchainDatabaseConfig,
+    proto::base_node::SyncHeadersRequest,
 };
 
 use crate::helpers::{sync, sync::WhatToDelete};
@@ -500,3 +506,75 @@ async fn test_header_sync_even_headers_and_blocks_peer_metadata_improve_with_reo
     // Bob will not be banned
     assert!(!sync::wait_for_is_peer_banned(&alice_node, bob_node.node_identity.node_id(), 1).await);
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_header_stream_resumes_after_interruption() {
+    // Create the network with Alice, Bob and Carol nodes
+    let (_state_machines, mut peer_nodes, initial_block, consensus_manager, key_manager, initial_coinbase) =
+        sync::create_network_with_multiple_nodes(vec![
+            BlockchainDatabaseConfig::default(),
+            BlockchainDatabaseConfig::default(),
+            BlockchainDatabaseConfig::default(),
+        ])
+        .await;
+    let alice_node = peer_nodes.remove(0);
+    let bob_node = peer_nodes.remove(0);
+    let carol_node = peer_nodes.remove(0);
+
+    // Bob and Carol have the same 10 blocks
+    let (blocks, _coinbases) = sync::create_and_add_some_blocks(
+        &bob_node,
+        &initial_block,
+        &initial_coinbase,
+        10,
+        &consensus_manager,
+        &key_manager,
+        &[3; 10],
+        &None,
+    )
+    .await;
+    sync::add_some_existing_blocks(&blocks[1..], &carol_node);
+    assert_eq!(carol_node.blockchain_db.get_height().unwrap(), 10);
+
+    // Alice streams headers from Bob, but the stream is interrupted after 4 headers
+    let mut conn = alice_node
+        .comms
+        .connectivity()
+        .dial_peer(bob_node.node_identity.node_id().clone())
+        .await
+        .unwrap();
+    let mut client = conn.connect_rpc::<BaseNodeSyncRpcClient>().await.unwrap();
+    let mut header_stream = client
+        .sync_headers(SyncHeadersRequest {
+            start_hash: initial_block.hash().to_vec(),
+            count: 10,
+        })
+        .await
+        .unwrap();
+    for _ in 0..4 {
+        header_stream.next().await.unwrap().unwrap();
+    }
+    let token = header_stream.resumption_token().unwrap();
+    assert_eq!(token.position(), 4);
+    drop(header_stream);
+    client.close().await;
+
+    // Alice resumes the stream from Carol and only receives the remaining headers
+    let mut conn = alice_node
+        .comms
+        .connectivity()
+        .dial_peer(carol_node.node_identity.node_id().clone())
+        .await
+        .unwrap();
+    let mut client = conn.connect_rpc::<BaseNodeSyncRpcClient>().await.unwrap();
+    let header_stream = client.resume_stream(token).await.unwrap();
+    let headers = header_stream
+        .map(|header| BlockHeader::try_from(header.unwrap()).unwrap())
+        .collect::<Vec<_>>()
+        .await;
+    assert_eq!(headers.len(), 6);
+    for (header, block) in headers.iter().zip(&blocks[5..]) {
+        assert_eq!(header.height, block.height());
+        assert_eq!(header.hash(), *block.hash());
+    }
+}
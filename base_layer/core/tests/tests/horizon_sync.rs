@@ -49,6 +49,8 @@ async fn test_initial_horizon_sync_from_archival_node_happy_path() {
                 pruning_interval: 5,
                 track_reorgs: false,
                 cleanup_orphans_at_startup: false,
+                index_outputs: false,
+                ..Default::default()
             },
             BlockchainDatabaseConfig::default(),
         ])
@@ -302,6 +304,8 @@ async fn test_consecutive_horizon_sync_from_prune_node_happy_path() {
                 pruning_interval: 5,
                 track_reorgs: false,
                 cleanup_orphans_at_startup: false,
+                index_outputs: false,
+                ..Default::default()
             },
             // Carol is a pruned node
             BlockchainDatabaseConfig {
@@ -310,6 +314,8 @@ async fn test_consecutive_horizon_sync_from_prune_node_happy_path() {
                 pruning_interval: 5,
                 track_reorgs: false,
                 cleanup_orphans_at_startup: false,
+                index_outputs: false,
+                ..Default::default()
             },
             // Bob is an archival node
             BlockchainDatabaseConfig::default(),
@@ -680,6 +686,8 @@ async fn test_initial_horizon_sync_from_prune_node_happy_path() {
                 pruning_interval: 5,
                 track_reorgs: false,
                 cleanup_orphans_at_startup: false,
+                index_outputs: false,
+                ..Default::default()
             },
             // Carol is a pruned node
             BlockchainDatabaseConfig {
@@ -688,6 +696,8 @@ async fn test_initial_horizon_sync_from_prune_node_happy_path() {
                 pruning_interval: 5,
                 track_reorgs: false,
                 cleanup_orphans_at_startup: false,
+                index_outputs: false,
+                ..Default::default()
             },
             // Bob is an archival node
             BlockchainDatabaseConfig::default(),
@@ -1705,7 +1705,7 @@ async fn block_event_and_reorg_event_handling() {
     // There are 5 transactions created
     // TX1 the base transaction and then TX2A and TX3A that spend it
     // Double spends TX2B and TX3B are also created spending TX1
-    // Both nodes have all transactions in their mempools
+    // Both nodes reject TX2B and TX3B as they do not pay a higher fee than the transactions they would replace
     // When block B2A is submitted, then both nodes have TX2A and TX3A in their reorg pools
     // When block B2B is submitted with TX2B, TX3B, then TX2A, TX3A are discarded (Not Stored)
     let network = Network::LocalNet;
@@ -1807,12 +1807,18 @@ async fn block_event_and_reorg_event_handling() {
     );
     alice.mempool.insert(Arc::new(tx2a.clone())).await.unwrap();
     alice.mempool.insert(Arc::new(tx3a.clone())).await.unwrap();
-    alice.mempool.insert(Arc::new(tx2b.clone())).await.unwrap();
-    alice.mempool.insert(Arc::new(tx3b.clone())).await.unwrap();
     bob.mempool.insert(Arc::new(tx2a.clone())).await.unwrap();
     bob.mempool.insert(Arc::new(tx3a.clone())).await.unwrap();
-    bob.mempool.insert(Arc::new(tx2b.clone())).await.unwrap();
-    bob.mempool.insert(Arc::new(tx3b.clone())).await.unwrap();
+    for tx in [&tx2b, &tx3b] {
+        assert_eq!(
+            alice.mempool.insert(Arc::new(tx.clone())).await.unwrap(),
+            TxStorageResponse::NotStoredReplacementRejected
+        );
+        assert_eq!(
+            bob.mempool.insert(Arc::new(tx.clone())).await.unwrap(),
+            TxStorageResponse::NotStoredReplacementRejected
+        );
+    }
 
     let mut block2a = bob
         .blockchain_db
@@ -1826,7 +1832,7 @@ async fn block_event_and_reorg_event_handling() {
         .unwrap();
     find_header_with_achieved_difficulty(&mut block2b.header, Difficulty::from_u64(10).unwrap());
 
-    // Add Block2a - tx2b and tx3b will be discarded as double spends.
+    // Add Block2a - tx2a and tx3a will be moved to the ReorgPool.
     assert!(bob.local_nci.submit_block(block2a.clone(),).await.is_ok());
 
     async_assert_eventually!(
@@ -1862,7 +1868,7 @@ async fn block_event_and_reorg_event_handling() {
             .has_tx_with_excess_sig(tx2b_excess_sig.clone())
             .await
             .unwrap(),
-        TxStorageResponse::ReorgPool
+        TxStorageResponse::NotStored
     );
     assert_eq!(
         alice
@@ -1870,6 +1876,6 @@ async fn block_event_and_reorg_event_handling() {
             .has_tx_with_excess_sig(tx3b_excess_sig.clone())
             .await
             .unwrap(),
-        TxStorageResponse::ReorgPool
+        TxStorageResponse::NotStored
     );
 }
@@ -65,6 +65,8 @@ pub enum KeyManagerServiceError {
     StorageError(#[from] StorageError),
     #[error("The imported private key cannot be accessed or read: `{0}")]
     ImportedPrivateKeyInaccessible(String),
+    #[error("Watch-only wallets do not hold the private spend key and cannot sign: `{0}`")]
+    WatchOnlyWallet(String),
 }
 
 impl From<RangeProofError> for KeyManagerServiceError {
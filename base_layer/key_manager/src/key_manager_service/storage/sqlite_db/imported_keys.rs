@@ -22,6 +22,7 @@
 use chacha20poly1305::XChaCha20Poly1305;
 use chrono::{NaiveDateTime, Utc};
 use diesel::prelude::*;
+use tari_common_sqlite::util::diesel_ext::ExpectedRowsExtension;
 use tari_common_types::encryption::{decrypt_bytes_integral_nonce, encrypt_bytes_integral_nonce};
 use tari_crypto::keys::PublicKey;
 use tari_utilities::{hex::Hex, ByteArray, Hidden};
@@ -35,7 +36,7 @@ use crate::{
             sqlite_db::{imported_keys, Encryptable},
         },
     },
-    schema::imported_keys::{private_key, public_key, table, timestamp},
+    schema::imported_keys::{id, private_key, public_key, table, timestamp},
 };
 
 /// Represents a row in the imported keys table.
@@ -108,6 +109,15 @@ impl ImportedKeySql {
         Ok(imported_key)
     }
 
+    /// Writes the private key of this instance back to the database, used when the key is re-encrypted
+    pub fn update_private_key(&self, conn: &mut SqliteConnection) -> Result<(), KeyManagerStorageError> {
+        diesel::update(table.filter(id.eq(self.id)))
+            .set(private_key.eq(&self.private_key))
+            .execute(conn)
+            .num_rows_affected_or_not_found(1)?;
+        Ok(())
+    }
+
     /// Retrieve the key manager for the provided branch
     /// Will return Err if the branch does not exist in the database
     pub fn get_key<PK: PublicKey>(
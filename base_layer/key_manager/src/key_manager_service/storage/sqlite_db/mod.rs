@@ -23,6 +23,7 @@
 use std::sync::{Arc, RwLock};
 
 use chacha20poly1305::XChaCha20Poly1305;
+use diesel::SqliteConnection;
 use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
 pub use key_manager_state::{KeyManagerStateSql, NewKeyManagerStateSql};
 use log::*;
@@ -31,6 +32,7 @@ use tari_common_types::encryption::Encryptable;
 use tari_crypto::keys::PublicKey;
 use tari_utilities::acquire_read_lock;
 use tokio::time::Instant;
+use zeroize::Zeroize;
 
 use crate::key_manager_service::{
     error::KeyManagerStorageError,
@@ -46,6 +48,51 @@ mod key_manager_state;
 const MIGRATIONS: EmbeddedMigrations = embed_migrations!("./migrations");
 const LOG_TARGET: &str = "wallet::key_manager_service::database::wallet";
 
+/// Re-encrypts the key manager states and imported keys from `old_cipher` to `new_cipher` and returns the number of
+/// rows updated. This is meant to run inside the transaction that replaces the database key, so that the rows are
+/// never left encrypted under a key that is not stored.
+pub fn reencrypt_key_manager_data(
+    conn: &mut SqliteConnection,
+    old_cipher: &XChaCha20Poly1305,
+    new_cipher: &XChaCha20Poly1305,
+) -> Result<usize, KeyManagerStorageError> {
+    let mut num_updated = 0;
+    for state in KeyManagerStateSql::index(conn)? {
+        let state = state
+            .decrypt(old_cipher)
+            .and_then(|s| s.encrypt(new_cipher))
+            .map_err(KeyManagerStorageError::AeadError)?;
+        KeyManagerStateSql::set_index(state.id, state.primary_key_index, conn)?;
+        num_updated += 1;
+    }
+    for key in ImportedKeySql::index(conn)? {
+        key.decrypt(old_cipher)
+            .and_then(|k| k.encrypt(new_cipher))
+            .map_err(KeyManagerStorageError::AeadError)?
+            .update_private_key(conn)?;
+        num_updated += 1;
+    }
+    Ok(num_updated)
+}
+
+/// Checks that every key manager state and imported key decrypts with `cipher` and returns the number of rows checked
+pub fn verify_key_manager_data(
+    conn: &mut SqliteConnection,
+    cipher: &XChaCha20Poly1305,
+) -> Result<usize, KeyManagerStorageError> {
+    let states = KeyManagerStateSql::index(conn)?;
+    let keys = ImportedKeySql::index(conn)?;
+    let num_verified = states.len() + keys.len();
+    for state in states {
+        state.decrypt(cipher).map_err(KeyManagerStorageError::AeadError)?;
+    }
+    for key in keys {
+        let mut key = key.decrypt(cipher).map_err(KeyManagerStorageError::AeadError)?;
+        key.private_key.zeroize();
+    }
+    Ok(num_verified)
+}
+
 /// A Sqlite backend for the Output Manager Service. The Backend is accessed via a connection pool to the Sqlite file.
 #[derive(Clone)]
 pub struct KeyManagerSqliteDatabase<TKeyManagerDbConnection> {
@@ -38,7 +38,11 @@ use tari_common::{
     DnsNameServer,
     SubConfigPath,
 };
-use tari_comms::multiaddr::Multiaddr;
+use tari_comms::{
+    connection_manager::{BandwidthConfig, BandwidthLimit},
+    multiaddr::Multiaddr,
+    protocol::ProtocolId,
+};
 use tari_comms_dht::{DbConnectionUrl, DhtConfig};
 
 use crate::transport::TransportConfig;
@@ -135,6 +139,9 @@ pub struct P2pConfig {
     /// The maximum allowed RPC sessions per peer.
     /// Default: 10
     pub rpc_max_sessions_per_peer: usize,
+    /// Bandwidth limits for peer connections.
+    /// Default: unlimited
+    pub bandwidth: BandwidthLimitsConfig,
 }
 
 impl Default for P2pConfig {
@@ -159,6 +166,7 @@ impl Default for P2pConfig {
             auxiliary_tcp_listener_address: None,
             rpc_max_simultaneous_sessions: 100,
             rpc_max_sessions_per_peer: 10,
+            bandwidth: BandwidthLimitsConfig::default(),
         }
     }
 }
@@ -179,10 +187,76 @@ impl P2pConfig {
     }
 }
 
+/// Global, per-peer and per-protocol bandwidth limits
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BandwidthLimitsConfig {
+    /// Limit on the combined bandwidth of all peer connections
+    pub global: BandwidthLimit,
+    /// Limit applied to each peer individually
+    pub per_peer: BandwidthLimit,
+    /// Limits applied to all substreams of a protocol across all peers
+    pub per_protocol: Vec<ProtocolBandwidthLimit>,
+}
+
+/// A bandwidth limit for a protocol
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProtocolBandwidthLimit {
+    /// The protocol id e.g. "t/blksync/1" (base node sync) or "t/msg/0.1" (messaging)
+    pub protocol: String,
+    /// The maximum number of bytes per second that may be sent on this protocol
+    pub upload_bytes_per_sec: Option<u64>,
+    /// The maximum number of bytes per second that may be received on this protocol
+    pub download_bytes_per_sec: Option<u64>,
+}
+
+impl From<&BandwidthLimitsConfig> for BandwidthConfig {
+    fn from(config: &BandwidthLimitsConfig) -> Self {
+        Self {
+            global: config.global,
+            per_peer: config.per_peer,
+            per_protocol: config
+                .per_protocol
+                .iter()
+                .map(|limit| {
+                    (
+                        ProtocolId::from(limit.protocol.clone()),
+                        BandwidthLimit::new(limit.upload_bytes_per_sec, limit.download_bytes_per_sec),
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
+    use tari_comms::{connection_manager::BandwidthConfig, protocol::ProtocolId};
+
+    use super::BandwidthLimitsConfig;
     use crate::PeerSeedsConfig;
 
+    #[test]
+    fn it_deserializes_bandwidth_limits() {
+        let config_str = r#"
+            global = { upload_bytes_per_sec = 10000000 }
+            per_protocol = [
+                { protocol = "t/blksync/1", upload_bytes_per_sec = 2000000, download_bytes_per_sec = 1000000 },
+            ]
+         "#;
+        let config = toml::from_str::<BandwidthLimitsConfig>(config_str).unwrap();
+        let config = BandwidthConfig::from(&config);
+        assert_eq!(config.global.upload_bytes_per_sec, Some(10_000_000));
+        assert!(config.global.download_bytes_per_sec.is_none());
+        assert!(config.per_peer.is_unlimited());
+        let sync_limit = config
+            .per_protocol
+            .get(&ProtocolId::from_static(b"t/blksync/1"))
+            .unwrap();
+        assert_eq!(sync_limit.download_bytes_per_sec, Some(1_000_000));
+    }
+
     #[test]
     fn it_deserializes_from_toml() {
         // No empty fields, no omitted fields
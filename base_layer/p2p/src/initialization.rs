@@ -39,6 +39,8 @@ use tari_common::{
     configuration::Network,
     exit_codes::{ExitCode, ExitError},
 };
+#[cfg(feature = "quic")]
+use tari_comms::transports::{QuicTransport, QuicTransportError};
 use tari_comms::{
     backoff::ConstantBackoff,
     multiaddr::multiaddr,
@@ -112,6 +114,11 @@ pub enum CommsInitializationError {
     InvalidTorForwardAddress(std::io::Error),
     #[error("IO Error: `{0}`")]
     IoError(#[from] std::io::Error),
+    #[cfg(feature = "quic")]
+    #[error("QUIC transport error: `{0}`")]
+    QuicTransportError(#[from] QuicTransportError),
+    #[error("The QUIC transport is not available, this application was built without the `quic` feature")]
+    QuicNotSupported,
 }
 
 impl CommsInitializationError {
@@ -278,6 +285,19 @@ pub async fn spawn_comms_using_transport<F: Fn(TorIdentity) + Send + Sync + Unpi
                 .spawn_with_transport(transport)
                 .await?
         },
+        #[cfg(feature = "quic")]
+        TransportType::Quic => {
+            debug!(target: LOG_TARGET, "Building QUIC comms stack");
+            let config = transport_config.quic;
+            let listener_address = config.listener_address.clone();
+            let transport = QuicTransport::new(config.into())?;
+            comms
+                .with_listener_address(listener_address)
+                .spawn_with_transport(transport)
+                .await?
+        },
+        #[cfg(not(feature = "quic"))]
+        TransportType::Quic => return Err(CommsInitializationError::QuicNotSupported),
     };
 
     Ok(comms)
@@ -332,7 +352,8 @@ async fn configure_comms_and_dht(
         .with_listener_liveness_allowlist_cidrs(listener_liveness_allowlist_cidrs)
         .with_dial_backoff(ConstantBackoff::new(Duration::from_millis(500)))
         .with_peer_storage(peer_database, Some(file_lock))
-        .with_excluded_dial_addresses(config.dht.excluded_dial_addresses.clone().into_vec().clone());
+        .with_excluded_dial_addresses(config.dht.excluded_dial_addresses.clone().into_vec().clone())
+        .with_bandwidth_config((&config.bandwidth).into());
 
     let mut comms = match config.auxiliary_tcp_listener_address {
         Some(ref addr) => builder.with_auxiliary_tcp_listener_address(addr.clone()).build()?,
@@ -47,7 +47,7 @@ pub use tari_common::configuration::Network;
 pub use tor_authentication::TorControlAuthentication;
 pub use transport::{Socks5TransportConfig, TcpTransportConfig, TorTransportConfig, TransportConfig, TransportType};
 
-pub use self::config::{P2pConfig, PeerSeedsConfig};
+pub use self::config::{BandwidthLimitsConfig, P2pConfig, PeerSeedsConfig, ProtocolBandwidthLimit};
 
 /// Major network version. Peers will refuse connections if this value differs
 pub const MAJOR_NETWORK_VERSION: u8 = 0;
@@ -19,9 +19,12 @@
 //  SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
-use std::{num::NonZeroU16, sync::Arc};
+use std::{num::NonZeroU16, sync::Arc, time::Duration};
 
 use serde::{Deserialize, Serialize};
+use tari_common::configuration::serializers;
+#[cfg(feature = "quic")]
+use tari_comms::transports::QuicConfig;
 use tari_comms::{
     multiaddr::Multiaddr,
     socks,
@@ -42,6 +45,7 @@ pub struct TransportConfig {
     pub tor: TorTransportConfig,
     pub socks: Socks5TransportConfig,
     pub memory: MemoryTransportConfig,
+    pub quic: QuicTransportConfig,
 }
 
 impl TransportConfig {
@@ -61,6 +65,14 @@ impl TransportConfig {
         }
     }
 
+    pub fn new_quic(config: QuicTransportConfig) -> Self {
+        Self {
+            transport_type: TransportType::Quic,
+            quic: config,
+            ..Default::default()
+        }
+    }
+
     pub fn new_tor(config: TorTransportConfig) -> Self {
         Self {
             transport_type: TransportType::Tor,
@@ -99,6 +111,10 @@ pub enum TransportType {
     Tor,
     /// Use a SOCKS5 proxy transport. This transport allows any addresses supported by the proxy.
     Socks5,
+    /// Use QUIC over UDP to join the Tari network. Substreams map onto independent QUIC streams, so a slow RPC session
+    /// does not hold up messaging on the same connection. This transport can only contact QUIC nodes and requires the
+    /// `quic` feature.
+    Quic,
 }
 
 impl Default for TransportType {
@@ -243,3 +259,40 @@ impl Default for MemoryTransportConfig {
         }
     }
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct QuicTransportConfig {
+    /// UDP socket to bind the QUIC listener, in the form '/ip4/x.x.x.x/udp/port/quic'
+    pub listener_address: Multiaddr,
+    /// Interval at which keep-alive packets are sent on idle connections
+    #[serde(with = "serializers::seconds")]
+    pub keep_alive_interval: Duration,
+    /// A connection is closed when nothing has been received from the peer for this long
+    #[serde(with = "serializers::seconds")]
+    pub max_idle_timeout: Duration,
+    /// The maximum number of substreams a peer may have open on a connection at once
+    pub max_concurrent_substreams: u32,
+}
+
+#[cfg(feature = "quic")]
+impl From<QuicTransportConfig> for QuicConfig {
+    fn from(config: QuicTransportConfig) -> Self {
+        Self {
+            keep_alive_interval: config.keep_alive_interval,
+            max_idle_timeout: config.max_idle_timeout,
+            max_concurrent_substreams: config.max_concurrent_substreams,
+        }
+    }
+}
+
+impl Default for QuicTransportConfig {
+    fn default() -> Self {
+        Self {
+            listener_address: "/ip4/0.0.0.0/udp/18189/quic".parse().unwrap(),
+            keep_alive_interval: Duration::from_secs(10),
+            max_idle_timeout: Duration::from_secs(60),
+            max_concurrent_substreams: 512,
+        }
+    }
+}
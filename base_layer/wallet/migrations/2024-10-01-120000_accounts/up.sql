@@ -0,0 +1,16 @@
+-- Named accounts partition the outputs of the wallet. Account 0 is the default account that all existing outputs
+-- belong to.
+CREATE TABLE accounts
+(
+    id         INTEGER PRIMARY KEY NOT NULL,
+    name       TEXT UNIQUE         NOT NULL,
+    created_at TIMESTAMP           NOT NULL
+);
+
+INSERT INTO accounts (id, name, created_at)
+VALUES (0, 'default', CURRENT_TIMESTAMP);
+
+ALTER TABLE outputs
+    ADD account_id INTEGER NOT NULL DEFAULT 0;
+
+CREATE INDEX idx_outputs_account_id ON outputs (account_id);
@@ -0,0 +1,3 @@
+-- Pending outbound transactions that are signed by another (offline) wallet before they are finalized
+ALTER TABLE outbound_transactions
+    ADD offline_signing INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE scheduled_payment_runs;
+DROP TABLE scheduled_payments;
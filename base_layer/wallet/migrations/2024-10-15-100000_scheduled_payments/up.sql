@@ -0,0 +1,33 @@
+-- Payments that the transaction service sends at a future time. A one-off payment has `run_once_at` set, a recurring
+-- payment has `cron_expression` set. `next_run_at` is NULL once a payment will not run again. `account_id` is NULL if
+-- the payment is spent from all accounts.
+CREATE TABLE scheduled_payments
+(
+    id                  BIGINT PRIMARY KEY NOT NULL,
+    destination_address BLOB               NOT NULL,
+    amount              BIGINT             NOT NULL,
+    fee_per_gram        BIGINT             NOT NULL,
+    message             TEXT               NOT NULL,
+    payment_kind        INTEGER            NOT NULL,
+    run_once_at         TIMESTAMP          NULL,
+    cron_expression     TEXT               NULL,
+    next_run_at         TIMESTAMP          NULL,
+    created_at          TIMESTAMP          NOT NULL,
+    cancelled           INTEGER            NOT NULL DEFAULT 0,
+    account_id          BIGINT             NULL
+);
+
+CREATE INDEX idx_scheduled_payments_next_run_at ON scheduled_payments (next_run_at);
+
+-- The outcome of every attempt to send a scheduled payment
+CREATE TABLE scheduled_payment_runs
+(
+    id                   INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    scheduled_payment_id BIGINT                            NOT NULL,
+    run_at               TIMESTAMP                         NOT NULL,
+    tx_id                BIGINT                            NULL,
+    error                TEXT                              NULL,
+    FOREIGN KEY (scheduled_payment_id) REFERENCES scheduled_payments (id)
+);
+
+CREATE INDEX idx_scheduled_payment_runs_scheduled_payment_id ON scheduled_payment_runs (scheduled_payment_id);
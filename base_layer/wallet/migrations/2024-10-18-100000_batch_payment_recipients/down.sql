@@ -0,0 +1,2 @@
+DROP INDEX idx_batch_payment_recipients_tx_id;
+DROP TABLE batch_payment_recipients;
@@ -0,0 +1,12 @@
+-- Every recipient of a batch payment, which pays several recipients in one transaction. The completed transaction only
+-- records the first recipient as its destination.
+CREATE TABLE batch_payment_recipients
+(
+    id                  INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    tx_id               BIGINT                            NOT NULL,
+    destination_address BLOB                              NOT NULL,
+    amount              BIGINT                            NOT NULL,
+    payment_id          BLOB                              NOT NULL
+);
+
+CREATE INDEX idx_batch_payment_recipients_tx_id ON batch_payment_recipients (tx_id);
@@ -0,0 +1,7 @@
+DROP INDEX idx_outputs_label;
+
+ALTER TABLE outputs
+    DROP COLUMN frozen;
+
+ALTER TABLE outputs
+    DROP COLUMN label;
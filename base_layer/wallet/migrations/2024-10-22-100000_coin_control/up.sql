@@ -0,0 +1,9 @@
+-- Coin control: a user assigned label that groups outputs, and a flag that keeps an output from being selected for
+-- spending
+ALTER TABLE outputs
+    ADD label TEXT NULL;
+
+ALTER TABLE outputs
+    ADD frozen INTEGER NOT NULL DEFAULT 0;
+
+CREATE INDEX idx_outputs_label ON outputs (label);
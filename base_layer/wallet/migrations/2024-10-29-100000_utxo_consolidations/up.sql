@@ -0,0 +1,12 @@
+-- Every transaction that consolidated outputs of the wallet, with the reason it was made
+CREATE TABLE utxo_consolidations
+(
+    id           INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    tx_id        BIGINT                            NOT NULL,
+    reason       INTEGER                           NOT NULL,
+    num_inputs   BIGINT                            NOT NULL,
+    input_value  BIGINT                            NOT NULL,
+    fee          BIGINT                            NOT NULL,
+    fee_per_gram BIGINT                            NOT NULL,
+    created_at   TIMESTAMP                         NOT NULL
+);
@@ -0,0 +1,4 @@
+-- The transaction history is read a page at a time, ordered by creation time and tx id
+CREATE INDEX idx_inbound_transactions_timestamp_tx_id ON inbound_transactions (timestamp, tx_id);
+CREATE INDEX idx_outbound_transactions_timestamp_tx_id ON outbound_transactions (timestamp, tx_id);
+CREATE INDEX idx_completed_transactions_timestamp_tx_id ON completed_transactions (timestamp, tx_id);
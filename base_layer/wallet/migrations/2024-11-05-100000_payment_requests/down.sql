@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX idx_completed_transactions_payment_id;
+DROP TABLE payment_request_payments;
+DROP TABLE payment_requests;
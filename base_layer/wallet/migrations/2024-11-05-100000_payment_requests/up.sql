@@ -0,0 +1,26 @@
+-- Payment requests issued by this wallet. `request` holds the signed request as a URI and `payment_id` the payment id
+-- incoming transactions are matched against.
+CREATE TABLE payment_requests
+(
+    id              BIGINT PRIMARY KEY NOT NULL,
+    payment_id      BLOB               NOT NULL,
+    request         TEXT               NOT NULL,
+    status          INTEGER            NOT NULL,
+    amount_received BIGINT             NOT NULL DEFAULT 0,
+    created_at      TIMESTAMP          NOT NULL
+);
+
+CREATE UNIQUE INDEX idx_payment_requests_payment_id ON payment_requests (payment_id);
+
+-- The inbound transactions that have been matched to a payment request, so that every update only has to look at the
+-- transactions completed since the last one
+CREATE TABLE payment_request_payments
+(
+    tx_id              BIGINT PRIMARY KEY NOT NULL,
+    payment_request_id BIGINT             NOT NULL,
+    amount             BIGINT             NOT NULL,
+    FOREIGN KEY (payment_request_id) REFERENCES payment_requests (id)
+);
+
+CREATE INDEX idx_payment_request_payments_payment_request_id ON payment_request_payments (payment_request_id);
+CREATE INDEX idx_completed_transactions_payment_id ON completed_transactions (payment_id);
@@ -65,6 +65,10 @@ impl WalletConnectivityMock {
         self.base_node_sync_rpc_client.send(Some(RpcClientLease::new(client)));
     }
 
+    pub fn set_connectivity_status(&self, status: OnlineStatus) {
+        self.online_status_watch.send(status);
+    }
+
     pub fn notify_base_node_set(&self, base_node_peer: BaseNodePeerManager) {
         self.base_node_watch.send(Some(base_node_peer));
     }
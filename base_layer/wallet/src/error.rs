@@ -186,6 +186,8 @@ pub enum WalletStorageError {
     RecoverySeedError(String),
     #[error("Bad encryption version: `{0}`")]
     BadEncryptionVersion(String),
+    #[error("Could not rotate the database encryption key: {0}")]
+    KeyRotationError(String),
 }
 
 impl From<HexError> for WalletStorageError {
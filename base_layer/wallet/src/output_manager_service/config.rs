@@ -20,7 +20,10 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+use std::time::Duration;
+
 use serde::{Deserialize, Serialize};
+use tari_common::configuration::serializers;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
@@ -45,6 +48,25 @@ pub struct OutputManagerServiceConfig {
     pub autoignore_onesided_utxos: bool,
     /// The number of seconds that have to pass for the wallet to run revalidation of invalid UTXOs on startup.
     pub num_of_seconds_to_revalidate_invalid_utxos: u64,
+    /// If set to `true`, outputs below `consolidation_threshold` are joined into a single output on the
+    /// `consolidation_schedule`, provided that the estimated fee is no more than `consolidation_max_fee_per_gram`
+    pub auto_consolidation_enabled: bool,
+    /// Outputs below this value, in micro MinoTari, are consolidated
+    pub consolidation_threshold: u64,
+    /// Automatic consolidation is postponed while the estimated fee per gram, in micro MinoTari, is above this value
+    pub consolidation_max_fee_per_gram: u64,
+    /// The maximum number of outputs joined by a single consolidation transaction
+    pub consolidation_max_inputs: usize,
+    /// Automatic consolidation is skipped when fewer than this many outputs can be joined
+    pub consolidation_min_inputs: usize,
+    /// A cron expression (`minute hour day-of-month month day-of-week`, in UTC) for when automatic consolidation runs
+    pub consolidation_schedule: String,
+    /// This is the interval at which the wallet checks whether an automatic consolidation is due
+    #[serde(with = "serializers::seconds")]
+    pub consolidation_check_interval: Duration,
+    /// If set to `true`, a consolidation only joins outputs sharing the same label, so that outputs from different
+    /// sources are not linked by being spent together
+    pub consolidation_avoid_mixing_labels: bool,
 }
 
 impl Default for OutputManagerServiceConfig {
@@ -57,6 +79,14 @@ impl Default for OutputManagerServiceConfig {
             tx_validator_batch_size: 100,
             autoignore_onesided_utxos: false,
             num_of_seconds_to_revalidate_invalid_utxos: 60 * 60 * 24 * 3,
+            auto_consolidation_enabled: false,
+            consolidation_threshold: 1_000_000,
+            consolidation_max_fee_per_gram: 5,
+            consolidation_max_inputs: 500,
+            consolidation_min_inputs: 20,
+            consolidation_schedule: "0 3 * * *".to_string(),
+            consolidation_check_interval: Duration::from_secs(60),
+            consolidation_avoid_mixing_labels: true,
         }
     }
 }
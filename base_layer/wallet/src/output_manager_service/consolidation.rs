@@ -0,0 +1,104 @@
+// Copyright 2024 The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+use std::fmt::{self, Display, Formatter};
+
+use tari_common_types::wallet_types::AccountId;
+use tari_core::transactions::tari_amount::MicroMinotari;
+
+use crate::output_manager_service::storage::models::DbWalletOutput;
+
+/// What to consolidate and what to pay for it. Values that are not set are taken from the consolidation policy of the
+/// output manager configuration.
+#[derive(Debug, Clone, Default)]
+pub struct UtxoConsolidationParams {
+    /// Only outputs below this value are consolidated
+    pub threshold: Option<MicroMinotari>,
+    /// The maximum number of outputs joined by the transaction
+    pub max_inputs: Option<usize>,
+    pub fee_per_gram: MicroMinotari,
+    /// Only consolidate outputs of this account, otherwise the account with the most outputs below the threshold
+    pub account: Option<AccountId>,
+    /// Only join outputs sharing the same label (or all unlabelled), so that outputs from different sources are not
+    /// linked by being spent together
+    pub avoid_mixing_labels: Option<bool>,
+}
+
+impl Display for UtxoConsolidationParams {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "fee_per_gram: {}", self.fee_per_gram)?;
+        if let Some(threshold) = self.threshold {
+            write!(f, ", threshold: {}", threshold)?;
+        }
+        if let Some(max_inputs) = self.max_inputs {
+            write!(f, ", max_inputs: {}", max_inputs)?;
+        }
+        if let Some(account) = self.account {
+            write!(f, ", account: {}", account)?;
+        }
+        if let Some(avoid_mixing_labels) = self.avoid_mixing_labels {
+            write!(f, ", avoid_mixing_labels: {}", avoid_mixing_labels)?;
+        }
+        Ok(())
+    }
+}
+
+/// The outcome of a consolidation, worked out without building the transaction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UtxoConsolidationPreview {
+    pub account: AccountId,
+    pub num_inputs: usize,
+    pub input_value: MicroMinotari,
+    pub fee: MicroMinotari,
+    /// The value of the single output that replaces the inputs
+    pub output_value: MicroMinotari,
+    /// The number of unspent outputs the wallet has once the consolidation is done
+    pub resulting_output_count: usize,
+}
+
+/// Picks the outputs to consolidate out of `outputs`, which are expected in the order they should be spent. Outputs
+/// are never joined across accounts, so unless `account` is given the account with the most outputs below the
+/// threshold is consolidated. When `avoid_mixing_labels` is set, only the label group of that account with the most
+/// outputs below the threshold is consolidated.
+pub(crate) fn select_consolidation_inputs(
+    outputs: Vec<DbWalletOutput>,
+    threshold: MicroMinotari,
+    max_inputs: usize,
+    account: Option<AccountId>,
+    avoid_mixing_labels: bool,
+) -> Option<(AccountId, Vec<DbWalletOutput>)> {
+    let mut candidates = outputs
+        .into_iter()
+        .filter(|o| o.wallet_output.value < threshold && account.map_or(true, |a| o.account_id == a))
+        .collect::<Vec<_>>();
+    let account = match account {
+        Some(account) => account,
+        None => most_common(candidates.iter().map(|o| o.account_id))?,
+    };
+    candidates.retain(|o| o.account_id == account);
+    if avoid_mixing_labels {
+        let label = most_common(candidates.iter().map(|o| o.label.clone()))?;
+        candidates.retain(|o| o.label == label);
+    }
+    candidates.truncate(max_inputs);
+    if candidates.is_empty() {
+        return None;
+    }
+    Some((account, candidates))
+}
+
+/// The value occurring most often, where the first value to reach the highest count wins
+fn most_common<T: PartialEq>(values: impl Iterator<Item = T>) -> Option<T> {
+    let mut counts = Vec::<(T, usize)>::new();
+    for value in values {
+        match counts.iter_mut().find(|(v, _)| *v == value) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((value, 1)),
+        }
+    }
+    counts
+        .into_iter()
+        .rev()
+        .max_by_key(|(_, count)| *count)
+        .map(|(value, _)| value)
+}
@@ -190,6 +190,10 @@ pub enum OutputManagerStorageError {
     OutputAlreadySpent,
     #[error("Output is already encumbered")]
     OutputAlreadyEncumbered,
+    #[error("An account named `{0}` already exists")]
+    AccountAlreadyExists(String),
+    #[error("Account not found: `{0}`")]
+    AccountNotFound(String),
     #[error("Key Manager not initialized")]
     KeyManagerNotInitialized,
     #[error("Diesel R2d2 error: `{0}`")]
@@ -26,10 +26,12 @@ use tari_common_types::{
     tari_address::TariAddress,
     transaction::TxId,
     types::{Commitment, FixedHash, HashOutput, PublicKey},
+    wallet_types::AccountId,
 };
 use tari_core::{
     covenants::Covenant,
     transactions::{
+        key_manager::TariKeyId,
         tari_amount::MicroMinotari,
         transaction_components::{OutputFeatures, Transaction, TransactionOutput, WalletOutput, WalletOutputBuilder},
         transaction_protocol::{sender::TransactionSenderMessage, TransactionMetadata},
@@ -45,9 +47,11 @@ use tokio::sync::broadcast;
 use tower::Service;
 
 use crate::output_manager_service::{
-    error::OutputManagerError,
+    error::{OutputManagerError, OutputManagerStorageError},
     service::{Balance, OutputInfoByTxId, UseOutput},
-    storage::models::{DbWalletOutput, KnownOneSidedPaymentScript, SpendingPriority},
+    storage::models::{DbWalletOutput, KnownOneSidedPaymentScript, SpendingPriority, UtxoConsolidation, WalletAccount},
+    UtxoConsolidationParams,
+    UtxoConsolidationPreview,
     UtxoSelectionCriteria,
 };
 
@@ -55,6 +59,26 @@ use crate::output_manager_service::{
 #[allow(clippy::large_enum_variant)]
 pub enum OutputManagerRequest {
     GetBalance,
+    GetAccountBalance(AccountId),
+    CreateAccount(String),
+    GetAccounts,
+    MoveOutputsToAccount {
+        commitments: Vec<Commitment>,
+        account: AccountId,
+    },
+    GetAccountAddress(AccountId),
+    GetAccountTxIds(AccountId),
+    SetOutputLabel {
+        commitments: Vec<Commitment>,
+        label: Option<String>,
+    },
+    SetOutputsFrozen {
+        commitments: Vec<Commitment>,
+        frozen: bool,
+    },
+    PreviewUtxoConsolidation(UtxoConsolidationParams),
+    ConsolidateUtxos(UtxoConsolidationParams),
+    GetUtxoConsolidations,
     AddOutput((Box<WalletOutput>, Option<SpendingPriority>)),
     AddOutputWithTxId((TxId, Box<WalletOutput>, Option<SpendingPriority>)),
     AddUnvalidatedOutput((TxId, Box<WalletOutput>, Option<SpendingPriority>)),
@@ -93,6 +117,13 @@ pub enum OutputManagerRequest {
         covenant: Covenant,
         minimum_value_promise: MicroMinotari,
     },
+    PrepareToSendBatchTransaction {
+        tx_id: TxId,
+        outputs: Vec<(WalletOutput, TariKeyId)>,
+        selection_criteria: UtxoSelectionCriteria,
+        fee_per_gram: MicroMinotari,
+        message: String,
+    },
     CreatePayToSelfTransaction {
         tx_id: TxId,
         amount: MicroMinotari,
@@ -141,6 +172,10 @@ pub enum OutputManagerRequest {
     },
 
     ReinstateCancelledInboundTx(TxId),
+    ReinstateCancelledOutboundTx {
+        tx_id: TxId,
+        spent_commitments: Vec<Commitment>,
+    },
     CreateClaimShaAtomicSwapTransaction(HashOutput, PublicKey, MicroMinotari),
     CreateHtlcRefundTransaction(HashOutput, MicroMinotari),
     GetOutputInfoByTxId(TxId),
@@ -153,6 +188,29 @@ impl fmt::Display for OutputManagerRequest {
         use OutputManagerRequest::*;
         match self {
             GetBalance => write!(f, "GetBalance"),
+            GetAccountBalance(account) => write!(f, "GetAccountBalance ({})", account),
+            CreateAccount(name) => write!(f, "CreateAccount ({})", name),
+            GetAccounts => write!(f, "GetAccounts"),
+            MoveOutputsToAccount { commitments, account } => write!(
+                f,
+                "MoveOutputsToAccount ({} output(s) to account {})",
+                commitments.len(),
+                account
+            ),
+            GetAccountAddress(account) => write!(f, "GetAccountAddress ({})", account),
+            GetAccountTxIds(account) => write!(f, "GetAccountTxIds ({})", account),
+            SetOutputLabel { commitments, label } => write!(
+                f,
+                "SetOutputLabel ({} output(s), {})",
+                commitments.len(),
+                label.as_deref().unwrap_or("<none>")
+            ),
+            SetOutputsFrozen { commitments, frozen } => {
+                write!(f, "SetOutputsFrozen ({} output(s), {})", commitments.len(), frozen)
+            },
+            PreviewUtxoConsolidation(params) => write!(f, "PreviewUtxoConsolidation ({})", params),
+            ConsolidateUtxos(params) => write!(f, "ConsolidateUtxos ({})", params),
+            GetUtxoConsolidations => write!(f, "GetUtxoConsolidations"),
             AddOutput((v, _)) => write!(f, "AddOutput ({})", v.value),
             AddOutputWithTxId((t, v, _)) => write!(f, "AddOutputWithTxId ({}: {})", t, v.value),
             AddUnvalidatedOutput((t, v, _)) => {
@@ -205,6 +263,12 @@ impl fmt::Display for OutputManagerRequest {
             GetRecipientTransaction(_) => write!(f, "GetRecipientTransaction"),
             ConfirmPendingTransaction(v) => write!(f, "ConfirmPendingTransaction ({})", v),
             PrepareToSendTransaction { message, .. } => write!(f, "PrepareToSendTransaction ({})", message),
+            PrepareToSendBatchTransaction { outputs, message, .. } => write!(
+                f,
+                "PrepareToSendBatchTransaction ({} outputs, {})",
+                outputs.len(),
+                message
+            ),
             CreatePayToSelfTransaction { .. } => write!(f, "CreatePayToSelfTransaction",),
             CancelTransaction(v) => write!(f, "CancelTransaction ({})", v),
             GetSpentOutputs => write!(f, "GetSpentOutputs"),
@@ -251,6 +315,7 @@ impl fmt::Display for OutputManagerRequest {
             },
             CreatePayToSelfWithOutputs { .. } => write!(f, "CreatePayToSelfWithOutputs"),
             ReinstateCancelledInboundTx(_) => write!(f, "ReinstateCancelledInboundTx"),
+            ReinstateCancelledOutboundTx { tx_id, .. } => write!(f, "ReinstateCancelledOutboundTx ({})", tx_id),
             CreateClaimShaAtomicSwapTransaction(output, pre_image, fee_per_gram) => write!(
                 f,
                 "ClaimShaAtomicSwap(output hash: {}, pre_image: {}, fee_per_gram: {} )",
@@ -271,6 +336,16 @@ impl fmt::Display for OutputManagerRequest {
 #[derive(Debug, Clone)]
 pub enum OutputManagerResponse {
     Balance(Balance),
+    AccountCreated(WalletAccount),
+    Accounts(Vec<WalletAccount>),
+    OutputsMovedToAccount(usize),
+    AccountAddress(TariAddress),
+    AccountTxIds(Vec<TxId>),
+    OutputLabelSet(usize),
+    OutputsFrozenSet(usize),
+    UtxoConsolidationPreview(UtxoConsolidationPreview),
+    UtxosConsolidated(Box<(UtxoConsolidation, Transaction)>),
+    UtxoConsolidations(Vec<UtxoConsolidation>),
     OutputAdded,
     ConvertedToTransactionOutput(Box<TransactionOutput>),
     OutputMetadataSignatureUpdated,
@@ -313,6 +388,7 @@ pub enum OutputManagerResponse {
         tx_id: TxId,
     },
     ReinstatedCancelledInboundTx,
+    ReinstatedCancelledOutboundTx,
     ClaimHtlcTransaction((TxId, MicroMinotari, MicroMinotari, Transaction)),
     OutputInfoByTxId(OutputInfoByTxId),
     CoinPreview((Vec<MicroMinotari>, MicroMinotari)),
@@ -328,6 +404,11 @@ pub enum OutputManagerEvent {
     TxoValidationInternalFailure(u64),
     TxoValidationCommunicationFailure(u64),
     TxoValidationAlreadyBusy(u64),
+    /// The automatic consolidation policy created a consolidation transaction, which still has to be submitted
+    UtxoConsolidationCreated {
+        consolidation: UtxoConsolidation,
+        transaction: Box<Transaction>,
+    },
 }
 
 impl fmt::Display for OutputManagerEvent {
@@ -345,6 +426,9 @@ impl fmt::Display for OutputManagerEvent {
             OutputManagerEvent::TxoValidationAlreadyBusy(tx) => {
                 write!(f, "Txo is already running, stopping {}", tx)
             },
+            OutputManagerEvent::UtxoConsolidationCreated { consolidation, .. } => {
+                write!(f, "UtxoConsolidationCreated for {}", consolidation.tx_id)
+            },
         }
     }
 }
@@ -476,6 +560,151 @@ impl OutputManagerHandle {
         }
     }
 
+    pub async fn get_account_balance(&mut self, account: AccountId) -> Result<Balance, OutputManagerError> {
+        match self
+            .handle
+            .call(OutputManagerRequest::GetAccountBalance(account))
+            .await??
+        {
+            OutputManagerResponse::Balance(b) => Ok(b),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    pub async fn create_account(&mut self, name: String) -> Result<WalletAccount, OutputManagerError> {
+        match self.handle.call(OutputManagerRequest::CreateAccount(name)).await?? {
+            OutputManagerResponse::AccountCreated(account) => Ok(account),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    pub async fn get_accounts(&mut self) -> Result<Vec<WalletAccount>, OutputManagerError> {
+        match self.handle.call(OutputManagerRequest::GetAccounts).await?? {
+            OutputManagerResponse::Accounts(accounts) => Ok(accounts),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Get the one-sided address that payments to the account are sent to
+    pub async fn get_account_address(&mut self, account: AccountId) -> Result<TariAddress, OutputManagerError> {
+        match self
+            .handle
+            .call(OutputManagerRequest::GetAccountAddress(account))
+            .await??
+        {
+            OutputManagerResponse::AccountAddress(address) => Ok(address),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Find an account by name
+    pub async fn get_account_by_name(&mut self, name: &str) -> Result<WalletAccount, OutputManagerError> {
+        self.get_accounts()
+            .await?
+            .into_iter()
+            .find(|a| a.name == name)
+            .ok_or_else(|| OutputManagerStorageError::AccountNotFound(name.to_string()).into())
+    }
+
+    /// Assign unspent outputs to an account, returning the number of outputs that were moved
+    pub async fn move_outputs_to_account(
+        &mut self,
+        commitments: Vec<Commitment>,
+        account: AccountId,
+    ) -> Result<usize, OutputManagerError> {
+        match self
+            .handle
+            .call(OutputManagerRequest::MoveOutputsToAccount { commitments, account })
+            .await??
+        {
+            OutputManagerResponse::OutputsMovedToAccount(num_moved) => Ok(num_moved),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Set the label of the given outputs, or clear it if `None`, returning the number of outputs updated
+    pub async fn set_output_label(
+        &mut self,
+        commitments: Vec<Commitment>,
+        label: Option<String>,
+    ) -> Result<usize, OutputManagerError> {
+        match self
+            .handle
+            .call(OutputManagerRequest::SetOutputLabel { commitments, label })
+            .await??
+        {
+            OutputManagerResponse::OutputLabelSet(num_updated) => Ok(num_updated),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Freeze the given outputs so that they are never selected for spending, or unfreeze them, returning the number
+    /// of outputs updated
+    pub async fn set_outputs_frozen(
+        &mut self,
+        commitments: Vec<Commitment>,
+        frozen: bool,
+    ) -> Result<usize, OutputManagerError> {
+        match self
+            .handle
+            .call(OutputManagerRequest::SetOutputsFrozen { commitments, frozen })
+            .await??
+        {
+            OutputManagerResponse::OutputsFrozenSet(num_updated) => Ok(num_updated),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Work out the fee and outcome of consolidating outputs without creating the transaction
+    pub async fn preview_utxo_consolidation(
+        &mut self,
+        params: UtxoConsolidationParams,
+    ) -> Result<UtxoConsolidationPreview, OutputManagerError> {
+        match self
+            .handle
+            .call(OutputManagerRequest::PreviewUtxoConsolidation(params))
+            .await??
+        {
+            OutputManagerResponse::UtxoConsolidationPreview(preview) => Ok(preview),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Create a transaction that joins small outputs into a single output. The transaction still has to be submitted
+    /// to the transaction service.
+    pub async fn consolidate_utxos(
+        &mut self,
+        params: UtxoConsolidationParams,
+    ) -> Result<(UtxoConsolidation, Transaction), OutputManagerError> {
+        match self
+            .handle
+            .call(OutputManagerRequest::ConsolidateUtxos(params))
+            .await??
+        {
+            OutputManagerResponse::UtxosConsolidated(consolidation) => Ok(*consolidation),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    pub async fn get_utxo_consolidations(&mut self) -> Result<Vec<UtxoConsolidation>, OutputManagerError> {
+        match self.handle.call(OutputManagerRequest::GetUtxoConsolidations).await?? {
+            OutputManagerResponse::UtxoConsolidations(consolidations) => Ok(consolidations),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Get the ids of all transactions that created or spent outputs of the account
+    pub async fn get_account_tx_ids(&mut self, account: AccountId) -> Result<Vec<TxId>, OutputManagerError> {
+        match self
+            .handle
+            .call(OutputManagerRequest::GetAccountTxIds(account))
+            .await??
+        {
+            OutputManagerResponse::AccountTxIds(tx_ids) => Ok(tx_ids),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
     pub async fn revalidate_all_outputs(&mut self) -> Result<u64, OutputManagerError> {
         match self.handle.call(OutputManagerRequest::RevalidateTxos).await?? {
             OutputManagerResponse::TxoValidationStarted(request_key) => Ok(request_key),
@@ -531,6 +760,33 @@ impl OutputManagerHandle {
         }
     }
 
+    /// Prepares a transaction paying the given fully built recipient outputs, each paired with the key id of its
+    /// sender offset key. Inputs are selected to cover the outputs and the fee, with a single change output when
+    /// required. The returned protocol only needs to be finalized.
+    pub async fn prepare_batch_transaction_to_send(
+        &mut self,
+        tx_id: TxId,
+        outputs: Vec<(WalletOutput, TariKeyId)>,
+        selection_criteria: UtxoSelectionCriteria,
+        fee_per_gram: MicroMinotari,
+        message: String,
+    ) -> Result<SenderTransactionProtocol, OutputManagerError> {
+        match self
+            .handle
+            .call(OutputManagerRequest::PrepareToSendBatchTransaction {
+                tx_id,
+                outputs,
+                selection_criteria,
+                fee_per_gram,
+                message,
+            })
+            .await??
+        {
+            OutputManagerResponse::TransactionToSend(stp) => Ok(stp),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
     pub async fn scrape_wallet(
         &mut self,
         tx_id: TxId,
@@ -938,6 +1194,25 @@ impl OutputManagerHandle {
         }
     }
 
+    /// Restore the encumberance of an outbound transaction that was cancelled, spending the given outputs in it again
+    pub async fn reinstate_cancelled_outbound_transaction_outputs(
+        &mut self,
+        tx_id: TxId,
+        spent_commitments: Vec<Commitment>,
+    ) -> Result<(), OutputManagerError> {
+        match self
+            .handle
+            .call(OutputManagerRequest::ReinstateCancelledOutboundTx {
+                tx_id,
+                spent_commitments,
+            })
+            .await??
+        {
+            OutputManagerResponse::ReinstatedCancelledOutboundTx => Ok(()),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
     pub async fn get_output_info_for_tx_id(&mut self, tx_id: TxId) -> Result<OutputInfoByTxId, OutputManagerError> {
         match self
             .handle
@@ -25,7 +25,7 @@ use std::{
     fmt::{Display, Formatter},
 };
 
-use tari_common_types::types::Commitment;
+use tari_common_types::{types::Commitment, wallet_types::AccountId};
 
 #[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
 pub enum UtxoSelectionMode {
@@ -42,6 +42,13 @@ pub struct UtxoSelectionCriteria {
     pub excluding: Vec<Commitment>,
     pub min_dust: u64,
     pub excluding_onesided: bool,
+    /// Only select outputs belonging to this account. All accounts are selected from if not set.
+    pub account: Option<AccountId>,
+    /// Only select outputs carrying one of these labels. Labelled and unlabelled outputs are selected from if empty.
+    pub labels: Vec<String>,
+    /// Only select outputs sharing the same label (or all unlabelled), so that outputs from different sources are not
+    /// linked by being spent together
+    pub avoid_mixing_labels: bool,
 }
 
 impl UtxoSelectionCriteria {
@@ -63,6 +70,17 @@ impl UtxoSelectionCriteria {
         }
     }
 
+    /// Never spends outputs with different labels in the same transaction
+    pub fn privacy_preserving(min_dust: u64) -> Self {
+        Self {
+            filter: UtxoSelectionFilter::Standard,
+            ordering: UtxoSelectionOrdering::Default,
+            min_dust,
+            avoid_mixing_labels: true,
+            ..Default::default()
+        }
+    }
+
     pub fn specific(commitments: Vec<Commitment>) -> Self {
         Self {
             filter: UtxoSelectionFilter::SpecificOutputs { commitments },
@@ -71,11 +89,33 @@ impl UtxoSelectionCriteria {
             ..Default::default()
         }
     }
+
+    /// Restrict the selection to outputs belonging to the given account
+    pub fn for_account(mut self, account: Option<AccountId>) -> Self {
+        self.account = account;
+        self
+    }
+
+    /// Restrict the selection to outputs carrying one of the given labels
+    pub fn for_labels(mut self, labels: Vec<String>) -> Self {
+        self.labels = labels;
+        self
+    }
 }
 
 impl Display for UtxoSelectionCriteria {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "filter: {}, ordering: {}", self.filter, self.ordering)
+        write!(f, "filter: {}, ordering: {}", self.filter, self.ordering)?;
+        if let Some(account) = self.account {
+            write!(f, ", account: {}", account)?;
+        }
+        if !self.labels.is_empty() {
+            write!(f, ", labels: {}", self.labels.join(","))?;
+        }
+        if self.avoid_mixing_labels {
+            write!(f, ", avoiding label mixing")?;
+        }
+        Ok(())
     }
 }
 
@@ -21,6 +21,8 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 pub mod config;
+mod consolidation;
+pub use consolidation::{UtxoConsolidationParams, UtxoConsolidationPreview};
 pub mod error;
 pub mod handle;
 
@@ -25,7 +25,8 @@ use std::{str::FromStr, time::Instant};
 use log::*;
 use tari_common_types::{
     transaction::TxId,
-    types::{FixedHash, PrivateKey},
+    types::{FixedHash, PrivateKey, PublicKey},
+    wallet_types::{AccountId, DEFAULT_ACCOUNT_ID},
 };
 use tari_core::transactions::{
     key_manager::{TariKeyId, TransactionKeyManagerInterface},
@@ -79,8 +80,16 @@ where
         let outputs_length = outputs.len();
 
         let known_scripts = self.db.get_all_known_one_sided_payment_scripts()?;
+        // Outputs spendable by the spend key of another account belong to that account
+        let mut account_spend_keys = Vec::new();
+        for account in self.db.fetch_accounts()? {
+            if account.id != DEFAULT_ACCOUNT_ID {
+                let spend_key = self.master_key_manager.get_account_spend_key(account.id).await?;
+                account_spend_keys.push((account.id, spend_key.pub_key));
+            }
+        }
 
-        let mut rewound_outputs: Vec<(WalletOutput, bool, FixedHash, Option<TxId>)> = Vec::new();
+        let mut rewound_outputs: Vec<(WalletOutput, bool, FixedHash, Option<TxId>, AccountId)> = Vec::new();
         let push_pub_key_script = script!(PushPubKey(Box::default()))?;
         for (output, tx_id) in outputs {
             let known_script_index = known_scripts.iter().position(|s| s.script == output.script);
@@ -95,11 +104,17 @@ where
                 Some(recovered) => recovered,
                 None => continue,
             };
-            let (input_data, script_key) = match self
-                .find_script_key(&output.script, &spending_key, known_script_index, &known_scripts)
+            let (input_data, script_key, account) = match self
+                .find_script_key(
+                    &output.script,
+                    &spending_key,
+                    known_script_index,
+                    &known_scripts,
+                    &account_spend_keys,
+                )
                 .await?
             {
-                Some((input_data, script_key)) => (input_data, script_key),
+                Some(found) => found,
                 None => continue,
             };
 
@@ -122,7 +137,7 @@ where
                 payment_id,
             );
 
-            rewound_outputs.push((uo, known_script_index.is_some(), hash, tx_id));
+            rewound_outputs.push((uo, known_script_index.is_some(), hash, tx_id, account));
         }
 
         let rewind_time = start.elapsed();
@@ -134,7 +149,7 @@ where
         );
 
         let mut rewound_outputs_with_tx_id: Vec<RecoveredOutput> = Vec::new();
-        for (output, has_known_script, hash, tx_id) in &mut rewound_outputs {
+        for (output, has_known_script, hash, tx_id, account) in &mut rewound_outputs {
             let db_output = DbWalletOutput::from_wallet_output(
                 output.clone(),
                 &self.master_key_manager,
@@ -143,7 +158,8 @@ where
                 None,
                 None,
             )
-            .await?;
+            .await?
+            .with_account(Some(*account));
             let tx_id = match tx_id {
                 Some(id) => *id,
                 None => TxId::new_random(),
@@ -203,7 +219,8 @@ where
         spending_key: &TariKeyId,
         known_script_index: Option<usize>,
         known_scripts: &[KnownOneSidedPaymentScript],
-    ) -> Result<Option<(ExecutionStack, TariKeyId)>, OutputManagerError> {
+        account_spend_keys: &[(AccountId, PublicKey)],
+    ) -> Result<Option<(ExecutionStack, TariKeyId, AccountId)>, OutputManagerError> {
         let (input_data, script_key) = if script == &script!(Nop)? {
             // This is a nop, so we can just create a new key for the input stack.
             let key = if let KeyId::Derived { key } = spending_key {
@@ -231,6 +248,20 @@ where
                     if let Some(script_key_id) = result {
                         (ExecutionStack::default(), script_key_id)
                     } else {
+                        // The output may be change that is spendable by the spend key of another account
+                        for (account, account_spend_key) in account_spend_keys {
+                            let account_script_key = self
+                                .master_key_manager
+                                .stealth_address_script_spending_key(spending_key, account_spend_key)
+                                .await?;
+                            if account_script_key == **public_key {
+                                let script_key_id = self
+                                    .master_key_manager
+                                    .get_account_script_key_id(spending_key, *account)
+                                    .await?;
+                                return Ok(Some((ExecutionStack::default(), script_key_id, *account)));
+                            }
+                        }
                         // The spending key is recoverable but we dont know how to calculate the script key
                         return Ok(None);
                     }
@@ -241,7 +272,7 @@ where
                 }
             }
         };
-        Ok(Some((input_data, script_key)))
+        Ok(Some((input_data, script_key, DEFAULT_ACCOUNT_ID)))
     }
 
     async fn attempt_output_recovery(
@@ -21,6 +21,7 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 use std::{collections::HashMap, convert::TryInto, fmt, sync::Arc};
 
+use chrono::{NaiveDateTime, Utc};
 use diesel::result::{DatabaseErrorKind, Error as DieselError};
 use futures::{pin_mut, StreamExt};
 use log::*;
@@ -31,6 +32,7 @@ use tari_common_types::{
     tari_address::{TariAddress, TariAddressFeatures},
     transaction::TxId,
     types::{BlockHash, Commitment, HashOutput, PrivateKey, PublicKey},
+    wallet_types::{AccountId, WalletType, DEFAULT_ACCOUNT_ID},
 };
 use tari_comms::types::CommsDHKE;
 use tari_core::{
@@ -42,7 +44,7 @@ use tari_core::{
         shared_secret_to_output_encryption_key,
         shared_secret_to_output_spending_key,
     },
-    proto::base_node::FetchMatchingUtxos,
+    proto::base_node::{EstimateFeeRequest, FetchMatchingUtxos},
     transactions::{
         fee::Fee,
         key_manager::{TariKeyId, TransactionKeyManagerInterface},
@@ -60,14 +62,18 @@ use tari_core::{
             WalletOutput,
             WalletOutputBuilder,
         },
-        transaction_protocol::{sender::TransactionSenderMessage, TransactionMetadata},
+        transaction_protocol::{
+            sender::TransactionSenderMessage,
+            transaction_initializer::SenderTransactionInitializer,
+            TransactionMetadata,
+        },
         CryptoFactories,
         ReceiverTransactionProtocol,
         SenderTransactionProtocol,
     },
 };
 use tari_crypto::ristretto::pedersen::PedersenCommitment;
-use tari_key_manager::key_manager_service::{KeyAndId, KeyId, SerializedKeyString};
+use tari_key_manager::key_manager_service::{KeyAndId, KeyId};
 use tari_script::{
     inputs,
     push_pubkey_script,
@@ -81,13 +87,17 @@ use tari_script::{
 use tari_service_framework::reply_channel;
 use tari_shutdown::ShutdownSignal;
 use tari_utilities::{hex::Hex, ByteArray};
-use tokio::{sync::Mutex, time::Instant};
+use tokio::{
+    sync::Mutex,
+    time::{interval, Instant, MissedTickBehavior},
+};
 
 use crate::{
     base_node_service::handle::{BaseNodeEvent, BaseNodeServiceHandle},
-    connectivity_service::WalletConnectivityInterface,
+    connectivity_service::{OnlineStatus, WalletConnectivityInterface},
     output_manager_service::{
         config::OutputManagerServiceConfig,
+        consolidation::select_consolidation_inputs,
         error::{OutputManagerError, OutputManagerProtocolError, OutputManagerStorageError},
         handle::{
             OutputManagerEvent,
@@ -101,16 +111,28 @@ use crate::{
         resources::OutputManagerResources,
         storage::{
             database::{OutputBackendQuery, OutputManagerBackend, OutputManagerDatabase},
-            models::{DbWalletOutput, KnownOneSidedPaymentScript, SpendingPriority},
+            models::{
+                DbWalletOutput,
+                KnownOneSidedPaymentScript,
+                SpendingPriority,
+                UtxoConsolidation,
+                UtxoConsolidationReason,
+                WalletAccount,
+            },
             OutputSource,
             OutputStatus,
         },
         tasks::TxoValidationTask,
+        UtxoConsolidationParams,
+        UtxoConsolidationPreview,
         TRANSACTION_INPUTS_LIMIT,
     },
+    util::cron::CronSchedule,
 };
 
 const LOG_TARGET: &str = "wallet::output_manager_service";
+/// The number of blocks within which an automatic consolidation should be mined
+const CONSOLIDATION_FEE_TARGET_BLOCKS: u64 = 10;
 
 /// This service will manage a wallet's available outputs and the key manager that produces the keys for these outputs.
 /// The service will assemble transactions to be sent from the wallets available outputs and provide keys to receive
@@ -123,6 +145,8 @@ pub struct OutputManagerService<TBackend, TWalletConnectivity, TKeyManagerInterf
     base_node_service: BaseNodeServiceHandle,
     last_seen_tip_height: Option<u64>,
     validation_in_progress: Arc<Mutex<()>>,
+    consolidation_schedule: Option<CronSchedule>,
+    next_consolidation_at: Option<NaiveDateTime>,
 }
 
 impl<TBackend, TWalletConnectivity, TKeyManagerInterface>
@@ -164,6 +188,29 @@ where
         );
         let interactive_tari_address =
             TariAddress::new_dual_address(view_key.pub_key, spend_key.pub_key, network, interactive_features);
+        // Every account derives its keys from its own branches, so these need to be loaded into the key manager
+        for account in db.fetch_accounts()? {
+            if account.id != DEFAULT_ACCOUNT_ID {
+                Self::add_account_key_branches(&key_manager, account.id).await?;
+            }
+        }
+        let consolidation_schedule = if config.auto_consolidation_enabled {
+            match config.consolidation_schedule.parse::<CronSchedule>() {
+                Ok(schedule) => Some(schedule),
+                Err(e) => {
+                    warn!(
+                        target: LOG_TARGET,
+                        "Automatic UTXO consolidation is disabled, invalid consolidation schedule: {}", e
+                    );
+                    None
+                },
+            }
+        } else {
+            None
+        };
+        let next_consolidation_at = consolidation_schedule
+            .as_ref()
+            .and_then(|schedule| schedule.next_after(Utc::now().naive_utc()));
         let resources = OutputManagerResources {
             config,
             db,
@@ -183,6 +230,8 @@ where
             base_node_service,
             last_seen_tip_height: None,
             validation_in_progress: Arc::new(Mutex::new(())),
+            consolidation_schedule,
+            next_consolidation_at,
         })
     }
 
@@ -198,6 +247,9 @@ where
 
         let mut base_node_service_event_stream = self.base_node_service.get_event_stream();
 
+        let mut consolidation_check = interval(self.resources.config.consolidation_check_interval);
+        consolidation_check.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
         debug!(target: LOG_TARGET, "Output Manager Service started");
         // Outputs marked as shorttermencumbered are not yet stored as transactions in the TMS, so lets clear them
         self.resources.db.clear_short_term_encumberances()?;
@@ -220,6 +272,11 @@ where
                         warn!(target: LOG_TARGET, "Failed to send reply");
                     });
                 },
+                _ = consolidation_check.tick() => {
+                    if let Err(e) = self.consolidate_utxos_if_due().await {
+                        warn!(target: LOG_TARGET, "Error consolidating UTXOs: {}", e);
+                    }
+                },
                 _ = shutdown.wait() => {
                     info!(target: LOG_TARGET, "Output manager service shutting down because it received the shutdown signal");
                     break;
@@ -312,6 +369,59 @@ where
                 self.get_balance(current_tip_for_time_lock_calculation)
                     .map(OutputManagerResponse::Balance)
             },
+            OutputManagerRequest::GetAccountBalance(account) => {
+                let current_tip_for_time_lock_calculation = match self.base_node_service.get_chain_metadata().await {
+                    Ok(metadata) => metadata.map(|m| m.best_block_height()),
+                    Err(_) => None,
+                };
+                Ok(OutputManagerResponse::Balance(
+                    self.resources
+                        .db
+                        .get_account_balance(current_tip_for_time_lock_calculation, account)?,
+                ))
+            },
+            OutputManagerRequest::CreateAccount(name) => self
+                .create_account(name)
+                .await
+                .map(OutputManagerResponse::AccountCreated),
+            OutputManagerRequest::GetAccounts => {
+                Ok(OutputManagerResponse::Accounts(self.resources.db.fetch_accounts()?))
+            },
+            OutputManagerRequest::MoveOutputsToAccount { commitments, account } => {
+                Ok(OutputManagerResponse::OutputsMovedToAccount(
+                    self.resources.db.move_outputs_to_account(&commitments, account)?,
+                ))
+            },
+            OutputManagerRequest::GetAccountAddress(account) => self
+                .get_account_address(account)
+                .await
+                .map(OutputManagerResponse::AccountAddress),
+            OutputManagerRequest::GetAccountTxIds(account) => Ok(OutputManagerResponse::AccountTxIds(
+                self.resources.db.fetch_tx_ids_for_account(account)?,
+            )),
+            OutputManagerRequest::SetOutputLabel { commitments, label } => {
+                // Empty labels are treated as clearing the label
+                let label = label.filter(|l| !l.trim().is_empty());
+                Ok(OutputManagerResponse::OutputLabelSet(
+                    self.resources.db.set_output_label(&commitments, label)?,
+                ))
+            },
+            OutputManagerRequest::SetOutputsFrozen { commitments, frozen } => Ok(
+                OutputManagerResponse::OutputsFrozenSet(self.resources.db.set_outputs_frozen(&commitments, frozen)?),
+            ),
+            OutputManagerRequest::PreviewUtxoConsolidation(params) => self
+                .plan_utxo_consolidation(&params)
+                .await
+                .map(|(_, preview)| OutputManagerResponse::UtxoConsolidationPreview(preview)),
+            OutputManagerRequest::ConsolidateUtxos(params) => {
+                let (inputs, preview) = self.plan_utxo_consolidation(&params).await?;
+                self.consolidate_utxos(inputs, preview, params.fee_per_gram, UtxoConsolidationReason::Manual)
+                    .await
+                    .map(|consolidation| OutputManagerResponse::UtxosConsolidated(Box::new(consolidation)))
+            },
+            OutputManagerRequest::GetUtxoConsolidations => Ok(OutputManagerResponse::UtxoConsolidations(
+                self.resources.db.fetch_utxo_consolidations()?,
+            )),
             OutputManagerRequest::GetRecipientTransaction(tsm) => self
                 .get_default_recipient_transaction(tsm)
                 .await
@@ -342,6 +452,16 @@ where
                 )
                 .await
                 .map(OutputManagerResponse::TransactionToSend),
+            OutputManagerRequest::PrepareToSendBatchTransaction {
+                tx_id,
+                outputs,
+                selection_criteria,
+                fee_per_gram,
+                message,
+            } => self
+                .prepare_batch_transaction_to_send(tx_id, outputs, selection_criteria, fee_per_gram, message)
+                .await
+                .map(OutputManagerResponse::TransactionToSend),
             OutputManagerRequest::CreatePayToSelfTransaction {
                 tx_id,
                 amount,
@@ -462,6 +582,12 @@ where
             OutputManagerRequest::ReinstateCancelledInboundTx(tx_id) => self
                 .reinstate_cancelled_inbound_transaction_outputs(tx_id)
                 .map(|_| OutputManagerResponse::ReinstatedCancelledInboundTx),
+            OutputManagerRequest::ReinstateCancelledOutboundTx {
+                tx_id,
+                spent_commitments,
+            } => self
+                .reinstate_cancelled_outbound_transaction_outputs(tx_id, &spent_commitments)
+                .map(|_| OutputManagerResponse::ReinstatedCancelledOutboundTx),
             OutputManagerRequest::CreateOutputWithFeatures { value, features } => {
                 let wallet_output = self.create_output_with_features(value, *features).await?;
                 Ok(OutputManagerResponse::CreateOutputWithFeatures {
@@ -499,6 +625,18 @@ where
     fn get_output_info_by_tx_id(&self, tx_id: TxId) -> Result<OutputInfoByTxId, OutputManagerError> {
         let outputs = self.resources.db.fetch_outputs_by_tx_id(tx_id)?;
         let statuses = outputs.clone().into_iter().map(|uo| uo.status).collect();
+        let spent_value = outputs
+            .iter()
+            .filter(|uo| uo.spent_in_tx_id == Some(tx_id))
+            .map(|uo| uo.wallet_output.value)
+            .sum();
+        let mut spent_accounts = outputs
+            .iter()
+            .filter(|uo| uo.spent_in_tx_id == Some(tx_id))
+            .map(|uo| uo.account_id);
+        let account = spent_accounts
+            .next()
+            .filter(|first| spent_accounts.all(|account| account == *first));
         // We need the maximum mined height and corresponding block hash (faux transactions outputs can have different
         // mined heights)
         let (mut last_height, mut max_mined_height, mut block_hash) = (0u64, None, None);
@@ -513,6 +651,8 @@ where
         }
         Ok(OutputInfoByTxId {
             statuses,
+            spent_value,
+            account,
             mined_height: max_mined_height,
             block_hash,
         })
@@ -745,6 +885,100 @@ where
             .with_script_key(script_key.key_id))
     }
 
+    async fn create_account(&mut self, name: String) -> Result<WalletAccount, OutputManagerError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(OutputManagerError::InvalidArgument(
+                "Account name must not be empty".to_string(),
+            ));
+        }
+        // The keys of an account are derived from the wallet seed
+        if !matches!(
+            *self.resources.key_manager.get_wallet_type().await,
+            WalletType::DerivedKeys
+        ) {
+            return Err(OutputManagerError::InvalidArgument(
+                "Accounts can only be created in wallets that derive their keys from a seed".to_string(),
+            ));
+        }
+        let account = self.resources.db.create_account(name)?;
+        Self::add_account_key_branches(&self.resources.key_manager, account.id).await?;
+        info!(target: LOG_TARGET, "Created account `{}` with id {}", account.name, account.id);
+        Ok(account)
+    }
+
+    /// Load the commitment mask, view and spend key branches of an account into the key manager
+    async fn add_account_key_branches(
+        key_manager: &TKeyManagerInterface,
+        account: AccountId,
+    ) -> Result<(), OutputManagerError> {
+        for branch in [
+            TransactionKeyManagerBranch::CommitmentMask,
+            TransactionKeyManagerBranch::DataEncryption,
+            TransactionKeyManagerBranch::Spend,
+        ] {
+            key_manager
+                .add_new_branch(branch.get_account_branch_key(account))
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// The one-sided address of an account, made up of the account's own view and spend keys. Payments to this address
+    /// are credited to the account when they are scanned.
+    async fn get_account_address(&self, account: AccountId) -> Result<TariAddress, OutputManagerError> {
+        if account == DEFAULT_ACCOUNT_ID {
+            return Ok(self.resources.one_sided_tari_address.clone());
+        }
+        if !self.resources.db.fetch_accounts()?.iter().any(|a| a.id == account) {
+            return Err(OutputManagerStorageError::AccountNotFound(account.to_string()).into());
+        }
+        let view_key = self.resources.key_manager.get_account_view_key(account).await?;
+        let spend_key = self.resources.key_manager.get_account_spend_key(account).await?;
+        Ok(TariAddress::new_dual_address(
+            view_key.pub_key,
+            spend_key.pub_key,
+            self.resources.one_sided_tari_address.network(),
+            TariAddressFeatures::create_one_sided_only(),
+        ))
+    }
+
+    /// Derive the commitment mask and script key for a change output, or any other output the wallet pays to itself.
+    /// Outputs paid to an account are derived from that account's key branch and are spent with its spend key.
+    async fn get_next_change_keys(
+        &self,
+        account: Option<AccountId>,
+    ) -> Result<(KeyAndId<PublicKey>, KeyAndId<PublicKey>), OutputManagerError> {
+        match account {
+            None | Some(DEFAULT_ACCOUNT_ID) => Ok(self
+                .resources
+                .key_manager
+                .get_next_commitment_mask_and_script_key()
+                .await?),
+            Some(account) => {
+                let commitment_mask = self
+                    .resources
+                    .key_manager
+                    .get_next_key(TransactionKeyManagerBranch::CommitmentMask.get_account_branch_key(account))
+                    .await?;
+                let script_key_id = self
+                    .resources
+                    .key_manager
+                    .get_account_script_key_id(&commitment_mask.key_id, account)
+                    .await?;
+                let script_public_key = self
+                    .resources
+                    .key_manager
+                    .get_public_key_at_key_id(&script_key_id)
+                    .await?;
+                Ok((commitment_mask, KeyAndId {
+                    key_id: script_key_id,
+                    pub_key: script_public_key,
+                }))
+            },
+        }
+    }
+
     fn get_balance(&self, current_tip_for_time_lock_calculation: Option<u64>) -> Result<Balance, OutputManagerError> {
         let balance = self.resources.db.get_balance(current_tip_for_time_lock_calculation)?;
         trace!(target: LOG_TARGET, "Balance: {:?}", balance);
@@ -974,22 +1208,10 @@ where
             selection_criteria,
             fee_per_gram,
         );
-        let features_and_scripts_byte_size = self
-            .resources
-            .consensus_constants
-            .transaction_weight_params()
-            .round_up_features_and_scripts_size(
-                recipient_output_features
-                    .get_serialized_size()
-                    .map_err(|e| OutputManagerError::ConversionError(e.to_string()))? +
-                    recipient_script
-                        .get_serialized_size()
-                        .map_err(|e| OutputManagerError::ConversionError(e.to_string()))? +
-                    recipient_covenant
-                        .get_serialized_size()
-                        .map_err(|e| OutputManagerError::ConversionError(e.to_string()))?,
-            );
+        let features_and_scripts_byte_size =
+            self.features_and_scripts_size(&recipient_output_features, &recipient_script, &recipient_covenant)?;
 
+        let account = selection_criteria.account;
         let input_selection = self
             .select_utxos(
                 amount,
@@ -1031,33 +1253,152 @@ where
             input_selection.num_selected()
         );
 
-        let (change_commitment_mask_key, change_script_key) = self
-            .resources
-            .key_manager
-            .get_next_commitment_mask_and_script_key()
+        self.add_change_data(&mut builder, account).await?;
+
+        let stp = builder
+            .build()
+            .await
+            .map_err(|e| OutputManagerError::BuildError(e.message))?;
+        if input_selection.requires_change_output() && stp.get_change_output()?.is_none() {
+            return Err(OutputManagerError::BuildError(
+                "There should be a change output metadata signature available".to_string(),
+            ));
+        }
+
+        // The Transaction Protocol built successfully so we will pull the unspent outputs out of the unspent list and
+        // store them until the transaction times out OR is confirmed
+        self.encumber_inputs_and_change(tx_id, &stp, input_selection, account)
+            .await?;
+
+        debug!(target: LOG_TARGET, "Prepared transaction (TxId: {}) to send", tx_id);
+
+        Ok(stp)
+    }
+
+    /// Prepare a Sender Transaction Protocol paying the provided, already signed, outputs. Only the change output
+    /// belongs to this wallet; the other outputs are tracked by their owners.
+    async fn prepare_batch_transaction_to_send(
+        &mut self,
+        tx_id: TxId,
+        outputs: Vec<(WalletOutput, TariKeyId)>,
+        selection_criteria: UtxoSelectionCriteria,
+        fee_per_gram: MicroMinotari,
+        message: String,
+    ) -> Result<SenderTransactionProtocol, OutputManagerError> {
+        let total_value = outputs.iter().map(|(o, _)| o.value).sum();
+        debug!(
+            target: LOG_TARGET,
+            "Preparing to send batch transaction. Outputs: {}. Total amount: {}. UTXO Selection: {}. Fee per gram: {}.",
+            outputs.len(),
+            total_value,
+            selection_criteria,
+            fee_per_gram,
+        );
+        let mut features_and_scripts_byte_size = 0;
+        for (output, _) in &outputs {
+            features_and_scripts_byte_size +=
+                self.features_and_scripts_size(&output.features, &output.script, &output.covenant)?;
+        }
+
+        let account = selection_criteria.account;
+        let input_selection = self
+            .select_utxos(
+                total_value,
+                selection_criteria,
+                fee_per_gram,
+                outputs.len(),
+                features_and_scripts_byte_size,
+            )
+            .await?;
+
+        let mut builder = SenderTransactionProtocol::builder(
+            self.resources.consensus_constants.clone(),
+            self.resources.key_manager.clone(),
+        );
+        builder
+            .with_lock_height(0)
+            .with_fee_per_gram(fee_per_gram)
+            .with_sender_address(self.resources.interactive_tari_address.clone())
+            .with_message(message)
+            .with_prevent_fee_gt_amount(self.resources.config.prevent_fee_gt_amount)
+            .with_kernel_features(KernelFeatures::empty())
+            .with_tx_id(tx_id);
+        for uo in input_selection.iter() {
+            builder.with_input(uo.wallet_output.clone()).await?;
+        }
+        self.add_change_data(&mut builder, account).await?;
+        for (output, sender_offset_key_id) in outputs {
+            builder
+                .with_output(output, sender_offset_key_id)
+                .await
+                .map_err(|e| OutputManagerError::BuildError(e.to_string()))?;
+        }
+
+        let stp = builder
+            .build()
+            .await
+            .map_err(|e| OutputManagerError::BuildError(e.message))?;
+        self.encumber_inputs_and_change(tx_id, &stp, input_selection, account)
             .await?;
+
+        debug!(target: LOG_TARGET, "Prepared batch transaction (TxId: {}) to send", tx_id);
+
+        Ok(stp)
+    }
+
+    /// The serialized size of the features, script and covenant of an output, rounded up as it is weighed for the fee
+    fn features_and_scripts_size(
+        &self,
+        features: &OutputFeatures,
+        script: &TariScript,
+        covenant: &Covenant,
+    ) -> Result<usize, OutputManagerError> {
+        Ok(self
+            .resources
+            .consensus_constants
+            .transaction_weight_params()
+            .round_up_features_and_scripts_size(
+                features
+                    .get_serialized_size()
+                    .map_err(|e| OutputManagerError::ConversionError(e.to_string()))? +
+                    script
+                        .get_serialized_size()
+                        .map_err(|e| OutputManagerError::ConversionError(e.to_string()))? +
+                    covenant
+                        .get_serialized_size()
+                        .map_err(|e| OutputManagerError::ConversionError(e.to_string()))?,
+            ))
+    }
+
+    /// Returns any change of the transaction being built to `account`
+    async fn add_change_data(
+        &self,
+        builder: &mut SenderTransactionInitializer<TKeyManagerInterface>,
+        account: Option<AccountId>,
+    ) -> Result<(), OutputManagerError> {
+        let (change_commitment_mask_key, change_script_key) = self.get_next_change_keys(account).await?;
         builder.with_change_data(
-            script!(PushPubKey(Box::new(change_script_key.pub_key.clone())))?,
+            script!(PushPubKey(Box::new(change_script_key.pub_key)))?,
             ExecutionStack::default(),
             change_script_key.key_id,
             change_commitment_mask_key.key_id,
             Covenant::default(),
             self.resources.interactive_tari_address.clone(),
         );
+        Ok(())
+    }
 
-        let stp = builder
-            .build()
-            .await
-            .map_err(|e| OutputManagerError::BuildError(e.message))?;
-
-        // If a change output was created add it to the pending_outputs list.
+    /// Encumbers the inputs selected for a transaction and adds its change output, if it has one, to `account` as a
+    /// pending incoming output
+    async fn encumber_inputs_and_change(
+        &mut self,
+        tx_id: TxId,
+        stp: &SenderTransactionProtocol,
+        input_selection: UtxoSelection,
+        account: Option<AccountId>,
+    ) -> Result<(), OutputManagerError> {
         let mut change_output = Vec::<DbWalletOutput>::new();
-        if input_selection.requires_change_output() {
-            let wallet_output = stp.get_change_output()?.ok_or_else(|| {
-                OutputManagerError::BuildError(
-                    "There should be a change output metadata signature available".to_string(),
-                )
-            })?;
+        if let Some(wallet_output) = stp.get_change_output()? {
             change_output.push(
                 DbWalletOutput::from_wallet_output(
                     wallet_output,
@@ -1067,19 +1408,15 @@ where
                     Some(tx_id),
                     None,
                 )
-                .await?,
+                .await?
+                .with_account(account)
+                .with_label(input_selection.change_label()),
             );
         }
-
-        // The Transaction Protocol built successfully so we will pull the unspent outputs out of the unspent list and
-        // store them until the transaction times out OR is confirmed
         self.resources
             .db
             .encumber_outputs(tx_id, input_selection.into_selected(), change_output)?;
-
-        debug!(target: LOG_TARGET, "Prepared transaction (TxId: {}) to send", tx_id);
-
-        Ok(stp)
+        Ok(())
     }
 
     #[allow(clippy::too_many_lines)]
@@ -1113,6 +1450,7 @@ where
             features_and_scripts_byte_size += weighting.round_up_features_and_scripts_size(features + covenant + script)
         }
 
+        let account = selection_criteria.account;
         let input_selection = self
             .select_utxos(
                 total_value,
@@ -1139,11 +1477,7 @@ where
         }
 
         if input_selection.requires_change_output() {
-            let (change_commitment_mask_key, change_script_key) = self
-                .resources
-                .key_manager
-                .get_next_commitment_mask_and_script_key()
-                .await?;
+            let (change_commitment_mask_key, change_script_key) = self.get_next_change_keys(account).await?;
             builder.with_change_data(
                 script!(PushPubKey(Box::new(change_script_key.pub_key)))?,
                 ExecutionStack::default(),
@@ -1198,7 +1532,9 @@ where
                     Some(tx_id),
                     None,
                 )
-                .await?,
+                .await?
+                .with_account(account)
+                .with_label(input_selection.change_label()),
             );
         }
 
@@ -1886,6 +2222,7 @@ where
                         .map_err(|e| OutputManagerError::ConversionError(e.to_string()))?,
             );
 
+        let account = selection_criteria.account;
         let input_selection = self
             .select_utxos(
                 amount,
@@ -1912,7 +2249,7 @@ where
             builder.with_input(kmo.wallet_output.clone()).await?;
         }
 
-        let (output, sender_offset_key_id) = self.output_to_self(output_features, amount, covenant).await?;
+        let (output, sender_offset_key_id) = self.output_to_self(output_features, amount, covenant, account).await?;
 
         builder
             .with_output(output.wallet_output.clone(), sender_offset_key_id.clone())
@@ -1921,11 +2258,7 @@ where
 
         let mut outputs = vec![output];
 
-        let (change_commitment_mask_key_id, change_script_public_key) = self
-            .resources
-            .key_manager
-            .get_next_commitment_mask_and_script_key()
-            .await?;
+        let (change_commitment_mask_key_id, change_script_public_key) = self.get_next_change_keys(account).await?;
         builder.with_change_data(
             script!(PushPubKey(Box::new(change_script_public_key.pub_key.clone())))?,
             ExecutionStack::default(),
@@ -1954,7 +2287,9 @@ where
                 Some(tx_id),
                 None,
             )
-            .await?;
+            .await?
+            .with_account(account)
+            .with_label(input_selection.change_label());
             outputs.push(change_output);
         }
 
@@ -2000,6 +2335,20 @@ where
         Ok(())
     }
 
+    /// Restore the pending transaction encumberance of an outbound transaction that was previously cancelled, spending
+    /// the given outputs in it again.
+    fn reinstate_cancelled_outbound_transaction_outputs(
+        &mut self,
+        tx_id: TxId,
+        spent_commitments: &[Commitment],
+    ) -> Result<(), OutputManagerError> {
+        self.resources
+            .db
+            .reinstate_cancelled_outbound_outputs(tx_id, spent_commitments)?;
+
+        Ok(())
+    }
+
     /// Select which unspent transaction outputs to use to send a transaction of the specified amount. Use the specified
     /// selection strategy to choose the outputs. It also determines if a change output is required.
     #[allow(clippy::too_many_lines)]
@@ -2022,8 +2371,6 @@ where
             total_output_features_and_scripts_byte_size,
             selection_criteria
         );
-        let mut utxos = Vec::new();
-
         let fee_calc = self.get_fee_calc();
 
         // Attempt to get the chain tip height
@@ -2077,40 +2424,87 @@ where
 
         trace!(target: LOG_TARGET, "We found {} UTXOs to select from", uo_len);
 
-        let mut requires_change_output = false;
-        let mut utxos_total_value = MicroMinotari::from(0);
-        let mut fee_without_change = MicroMinotari::from(0);
-        let mut fee_with_change = MicroMinotari::from(0);
-        for o in uo {
-            utxos_total_value += o.wallet_output.value;
-
-            trace!(target: LOG_TARGET, "-- utxos_total_value = {:?}", utxos_total_value);
-            utxos.push(o);
-            // The assumption here is that the only output will be the payment output and change if required
-            fee_without_change = fee_calc.calculate(
-                fee_per_gram,
-                1,
-                utxos.len(),
-                num_outputs,
-                total_output_features_and_scripts_byte_size,
-            );
-            if utxos_total_value == amount + fee_without_change {
-                break;
+        let accumulate = |candidates: Vec<DbWalletOutput>| {
+            let mut utxos = Vec::new();
+            let mut requires_change_output = false;
+            let mut utxos_total_value = MicroMinotari::from(0);
+            let mut fee_without_change = MicroMinotari::from(0);
+            let mut fee_with_change = MicroMinotari::from(0);
+            for o in candidates {
+                utxos_total_value += o.wallet_output.value;
+
+                trace!(target: LOG_TARGET, "-- utxos_total_value = {:?}", utxos_total_value);
+                utxos.push(o);
+                // The assumption here is that the only output will be the payment output and change if required
+                fee_without_change = fee_calc.calculate(
+                    fee_per_gram,
+                    1,
+                    utxos.len(),
+                    num_outputs,
+                    total_output_features_and_scripts_byte_size,
+                );
+                if utxos_total_value == amount + fee_without_change {
+                    break;
+                }
+                fee_with_change = fee_calc.calculate(
+                    fee_per_gram,
+                    1,
+                    utxos.len(),
+                    num_outputs + 1,
+                    total_output_features_and_scripts_byte_size + default_features_and_scripts_size,
+                );
+
+                trace!(target: LOG_TARGET, "-- amt+fee = {:?} {}", amount, fee_with_change);
+                if utxos_total_value > amount + fee_with_change {
+                    requires_change_output = true;
+                    break;
+                }
             }
-            fee_with_change = fee_calc.calculate(
-                fee_per_gram,
-                1,
-                utxos.len(),
-                num_outputs + 1,
-                total_output_features_and_scripts_byte_size + default_features_and_scripts_size,
-            );
+            UtxoSelection {
+                utxos,
+                requires_change_output,
+                total_value: utxos_total_value,
+                fee_without_change,
+                fee_with_change,
+                change_label: None,
+            }
+        };
 
-            trace!(target: LOG_TARGET, "-- amt+fee = {:?} {}", amount, fee_with_change);
-            if utxos_total_value > amount + fee_with_change {
-                requires_change_output = true;
-                break;
+        let selection = if selection_criteria.avoid_mixing_labels {
+            // Select from one label group only, trying the groups in the order of their first candidate
+            let mut groups: Vec<(Option<String>, Vec<DbWalletOutput>)> = Vec::new();
+            for o in uo {
+                match groups.iter_mut().find(|(label, _)| *label == o.label) {
+                    Some((_, group)) => group.push(o),
+                    None => groups.push((o.label.clone(), vec![o])),
+                }
             }
-        }
+            let mut best: Option<UtxoSelection> = None;
+            for (_, group) in groups {
+                let group_selection = accumulate(group);
+                if group_selection.covers(amount) {
+                    best = Some(group_selection);
+                    break;
+                }
+                if best
+                    .as_ref()
+                    .map_or(true, |b| group_selection.total_value > b.total_value)
+                {
+                    best = Some(group_selection);
+                }
+            }
+            best.unwrap_or_else(|| accumulate(Vec::new()))
+        } else {
+            accumulate(uo)
+        };
+        let UtxoSelection {
+            utxos,
+            requires_change_output,
+            total_value: utxos_total_value,
+            fee_without_change,
+            fee_with_change,
+            ..
+        } = selection;
 
         let perfect_utxo_selection = utxos_total_value == amount + fee_without_change;
         let enough_spendable = utxos_total_value > amount + fee_with_change;
@@ -2140,12 +2534,21 @@ where
             }
         }
 
+        // Change keeps the label shared by the selected outputs. The change of a spend restricted to labels otherwise
+        // takes the first of them, so that it stays spendable under the same restriction.
+        let change_label = utxos
+            .first()
+            .and_then(|o| o.label.clone())
+            .filter(|label| utxos.iter().all(|o| o.label.as_ref() == Some(label)))
+            .or_else(|| selection_criteria.labels.first().cloned());
+
         Ok(UtxoSelection {
             utxos,
             requires_change_output,
             total_value: utxos_total_value,
             fee_without_change,
             fee_with_change,
+            change_label,
         })
     }
 
@@ -2332,6 +2735,7 @@ where
 
         let default_features_and_scripts_size = self.default_features_and_scripts_size();
         let mut dest_outputs = Vec::with_capacity(number_of_splits + 1);
+        let account = src_outputs.first().map(|o| o.account_id);
 
         // accumulated value amount from given source outputs
         let accumulated_amount_with_fee = src_outputs
@@ -2386,7 +2790,12 @@ where
             };
 
             let (output, sender_offset_key_id) = self
-                .output_to_self(OutputFeatures::default(), amount_per_split, Covenant::default())
+                .output_to_self(
+                    OutputFeatures::default(),
+                    amount_per_split,
+                    Covenant::default(),
+                    account,
+                )
                 .await?;
 
             tx_builder
@@ -2454,6 +2863,7 @@ where
             .default_features_and_scripts_size()
             .map_err(|e| OutputManagerError::ConversionError(e.to_string()))?;
         let mut dest_outputs = Vec::with_capacity(number_of_splits + 1);
+        let account = src_outputs.first().map(|o| o.account_id);
         let total_split_amount = MicroMinotari::from(amount_per_split.as_u64() * number_of_splits as u64);
 
         // accumulated value amount from given source outputs
@@ -2541,7 +2951,12 @@ where
 
         for _ in 0..number_of_splits {
             let (output, sender_offset_key_id) = self
-                .output_to_self(OutputFeatures::default(), amount_per_split, Covenant::default())
+                .output_to_self(
+                    OutputFeatures::default(),
+                    amount_per_split,
+                    Covenant::default(),
+                    account,
+                )
                 .await?;
 
             tx_builder
@@ -2556,11 +2971,7 @@ where
 
         // extending transaction if there is some `change` left over
         if has_leftover_change {
-            let (change_mask, change_script) = self
-                .resources
-                .key_manager
-                .get_next_commitment_mask_and_script_key()
-                .await?;
+            let (change_mask, change_script) = self.get_next_change_keys(account).await?;
             tx_builder.with_change_data(
                 script!(PushPubKey(Box::new(change_script.pub_key)))?,
                 ExecutionStack::default(),
@@ -2633,17 +3044,15 @@ where
         Ok((tx_id, stp.into_transaction()?, value))
     }
 
+    /// Builds an output paying `amount` to `account`, or to the default account if no account is given
     async fn output_to_self(
         &mut self,
         output_features: OutputFeatures,
         amount: MicroMinotari,
         covenant: Covenant,
+        account: Option<AccountId>,
     ) -> Result<(DbWalletOutput, TariKeyId), OutputManagerError> {
-        let (commitment_mask_key, script_key) = self
-            .resources
-            .key_manager
-            .get_next_commitment_mask_and_script_key()
-            .await?;
+        let (commitment_mask_key, script_key) = self.get_next_change_keys(account).await?;
         let script = script!(PushPubKey(Box::new(script_key.pub_key.clone())))?;
         let payment_id = PaymentId::Address(self.resources.interactive_tari_address.clone());
         let encrypted_data = self
@@ -2702,7 +3111,8 @@ where
             None,
             None,
         )
-        .await?;
+        .await?
+        .with_account(account);
 
         Ok((output, sender_offset.key_id))
     }
@@ -2722,6 +3132,13 @@ where
             MicroMinotari::zero(),
             None,
         )?;
+        // The joined output stays in the account of the outputs it joins
+        let account = src_outputs.first().map(|o| o.account_id);
+        if src_outputs.iter().any(|o| Some(o.account_id) != account) {
+            return Err(OutputManagerError::InvalidArgument(
+                "Outputs of different accounts cannot be joined".to_string(),
+            ));
+        }
 
         let accumulated_amount_with_fee = src_outputs
             .iter()
@@ -2769,7 +3186,12 @@ where
         }
 
         let (output, sender_offset_key_id) = self
-            .output_to_self(OutputFeatures::default(), accumulated_amount, Covenant::default())
+            .output_to_self(
+                OutputFeatures::default(),
+                accumulated_amount,
+                Covenant::default(),
+                account,
+            )
             .await?;
 
         tx_builder
@@ -2809,6 +3231,193 @@ where
         Ok((tx_id, stp.into_transaction()?, accumulated_amount + fee))
     }
 
+    /// Works out which outputs a consolidation with the given parameters joins and what it costs. Outputs that are
+    /// worth less than the fee to spend them are left alone.
+    async fn plan_utxo_consolidation(
+        &mut self,
+        params: &UtxoConsolidationParams,
+    ) -> Result<(Vec<DbWalletOutput>, UtxoConsolidationPreview), OutputManagerError> {
+        let threshold = params
+            .threshold
+            .unwrap_or_else(|| self.resources.config.consolidation_threshold.into());
+        let max_inputs = params
+            .max_inputs
+            .unwrap_or(self.resources.config.consolidation_max_inputs)
+            .min(TRANSACTION_INPUTS_LIMIT as usize);
+        let tip_height = self
+            .base_node_service
+            .get_chain_metadata()
+            .await?
+            .map(|m| m.best_block_height());
+
+        let mut selection_criteria = UtxoSelectionCriteria::smallest_first(0);
+        selection_criteria.excluding_onesided = self.resources.config.autoignore_onesided_utxos;
+        let fee_calc = self.get_fee_calc();
+        let input_fee = fee_calc.calculate(params.fee_per_gram, 0, 1, 0, 0);
+        let outputs = self
+            .resources
+            .db
+            .fetch_unspent_outputs_for_spending(&selection_criteria, MicroMinotari::zero(), tip_height)?
+            .into_iter()
+            .filter(|o| o.wallet_output.value > input_fee)
+            .collect();
+        let avoid_mixing_labels = params
+            .avoid_mixing_labels
+            .unwrap_or(self.resources.config.consolidation_avoid_mixing_labels);
+        let (account, inputs) =
+            select_consolidation_inputs(outputs, threshold, max_inputs, params.account, avoid_mixing_labels)
+                .filter(|(_, inputs)| inputs.len() > 1)
+                .ok_or_else(|| {
+                    OutputManagerError::InvalidArgument(format!(
+                        "There are not enough spendable outputs below {} to consolidate",
+                        threshold
+                    ))
+                })?;
+
+        let input_value = inputs
+            .iter()
+            .fold(MicroMinotari::zero(), |acc, x| acc + x.wallet_output.value);
+        let fee = fee_calc.calculate(
+            params.fee_per_gram,
+            1,
+            inputs.len(),
+            1,
+            self.default_features_and_scripts_size()?,
+        );
+        if fee >= input_value {
+            return Err(OutputManagerError::NotEnoughFunds);
+        }
+        let num_unspent = self.resources.db.fetch_all_unspent_outputs()?.len();
+        let preview = UtxoConsolidationPreview {
+            account,
+            num_inputs: inputs.len(),
+            input_value,
+            fee,
+            output_value: input_value - fee,
+            resulting_output_count: num_unspent.saturating_sub(inputs.len()) + 1,
+        };
+        Ok((inputs, preview))
+    }
+
+    /// Joins the planned inputs into a single output and records the consolidation
+    async fn consolidate_utxos(
+        &mut self,
+        inputs: Vec<DbWalletOutput>,
+        preview: UtxoConsolidationPreview,
+        fee_per_gram: MicroMinotari,
+        reason: UtxoConsolidationReason,
+    ) -> Result<(UtxoConsolidation, Transaction), OutputManagerError> {
+        let commitments = inputs.into_iter().map(|o| o.commitment).collect();
+        let (tx_id, transaction, input_value) = self.create_coin_join(commitments, fee_per_gram).await?;
+        let consolidation = UtxoConsolidation {
+            tx_id,
+            reason,
+            num_inputs: preview.num_inputs,
+            input_value,
+            fee: transaction.body.get_total_fee()?,
+            fee_per_gram,
+            created_at: Utc::now().naive_utc(),
+        };
+        self.resources.db.insert_utxo_consolidation(consolidation.clone())?;
+        info!(
+            target: LOG_TARGET,
+            "{} consolidation (tx_id={}) joins {} outputs worth {} of account {} for a fee of {}",
+            reason,
+            tx_id,
+            preview.num_inputs,
+            input_value,
+            preview.account,
+            consolidation.fee
+        );
+        Ok((consolidation, transaction))
+    }
+
+    /// Runs the automatic consolidation policy. A consolidation that is due waits for the estimated fee to drop to the
+    /// configured maximum, and is skipped if too few outputs can be joined. The consolidation transaction is published
+    /// as an event for the transaction service to submit.
+    async fn consolidate_utxos_if_due(&mut self) -> Result<(), OutputManagerError> {
+        let now = Utc::now().naive_utc();
+        let schedule = match (&self.consolidation_schedule, self.next_consolidation_at) {
+            (Some(schedule), Some(next)) if next <= now => schedule.clone(),
+            _ => return Ok(()),
+        };
+        if self.resources.key_manager.get_wallet_type().await.is_watch_only() {
+            return Ok(());
+        }
+        if !self.resources.connectivity.is_base_node_set() ||
+            self.resources.connectivity.get_connectivity_status() != OnlineStatus::Online
+        {
+            debug!(target: LOG_TARGET, "UTXO consolidation is due but the wallet is offline");
+            return Ok(());
+        }
+        let fee_per_gram = self.estimate_consolidation_fee_per_gram().await?;
+        let max_fee_per_gram = MicroMinotari::from(self.resources.config.consolidation_max_fee_per_gram);
+        if fee_per_gram > max_fee_per_gram {
+            debug!(
+                target: LOG_TARGET,
+                "UTXO consolidation is due but the estimated fee of {} per gram is above the maximum of {}",
+                fee_per_gram,
+                max_fee_per_gram
+            );
+            return Ok(());
+        }
+        self.next_consolidation_at = schedule.next_after(now);
+
+        let params = UtxoConsolidationParams {
+            fee_per_gram,
+            ..Default::default()
+        };
+        let (inputs, preview) = match self.plan_utxo_consolidation(&params).await {
+            Ok((inputs, preview)) if preview.num_inputs >= self.resources.config.consolidation_min_inputs => {
+                (inputs, preview)
+            },
+            Ok((_, preview)) => {
+                debug!(
+                    target: LOG_TARGET,
+                    "Skipping UTXO consolidation, only {} outputs can be joined", preview.num_inputs
+                );
+                return Ok(());
+            },
+            Err(OutputManagerError::InvalidArgument(_)) | Err(OutputManagerError::NotEnoughFunds) => {
+                debug!(target: LOG_TARGET, "Skipping UTXO consolidation, there is nothing to join");
+                return Ok(());
+            },
+            Err(e) => return Err(e),
+        };
+        let (consolidation, transaction) = self
+            .consolidate_utxos(inputs, preview, fee_per_gram, UtxoConsolidationReason::Scheduled)
+            .await?;
+        let event = OutputManagerEvent::UtxoConsolidationCreated {
+            consolidation,
+            transaction: Box::new(transaction),
+        };
+        if let Err(e) = self.resources.event_publisher.send(Arc::new(event)) {
+            warn!(target: LOG_TARGET, "Error publishing UTXO consolidation event: {}", e);
+        }
+        Ok(())
+    }
+
+    /// The fee per gram the base node recommends for a consolidation, which is not urgent and only needs to be mined
+    /// within `CONSOLIDATION_FEE_TARGET_BLOCKS` blocks, or the minimum of 1
+    async fn estimate_consolidation_fee_per_gram(&mut self) -> Result<MicroMinotari, OutputManagerError> {
+        let estimates = self
+            .resources
+            .connectivity
+            .obtain_base_node_wallet_rpc_client()
+            .await
+            .ok_or_else(|| {
+                OutputManagerError::InvalidResponseError("Could not connect to base node rpc client".to_string())
+            })?
+            .estimate_fee(EstimateFeeRequest {
+                target_blocks: vec![CONSOLIDATION_FEE_TARGET_BLOCKS],
+            })
+            .await?
+            .estimates;
+        Ok(MicroMinotari::from(
+            estimates.first().map_or(1, |estimate| estimate.fee_per_gram).max(1),
+        ))
+    }
+
     pub async fn scrape_wallet(
         &mut self,
         tx_id: TxId,
@@ -3143,6 +3752,13 @@ where
         }
 
         let view_key = self.resources.key_manager.get_view_key().await?;
+        // Stealth payments are credited to the account whose view and spend keys they were sent to
+        let mut account_keys = Vec::new();
+        for account in self.resources.db.fetch_accounts()? {
+            let account_view_key = self.resources.key_manager.get_account_view_key(account.id).await?;
+            let account_spend_key = self.resources.key_manager.get_account_spend_key(account.id).await?;
+            account_keys.push((account.id, account_view_key.key_id, account_spend_key.pub_key));
+        }
 
         let mut scanned_outputs = vec![];
 
@@ -3185,75 +3801,76 @@ where
                                 payment_id,
                             );
 
-                            scanned_outputs.push((rewound_output, OutputSource::OneSided, tx_id));
+                            scanned_outputs.push((rewound_output, OutputSource::OneSided, tx_id, DEFAULT_ACCOUNT_ID));
                         }
                     }
                 }
-                // it is not some known key, so lets try and see if this is a stealth tx for us
+                // it is not some known key, so lets try and see if this is a stealth tx for one of our accounts
                 else {
-                    let shared_secret = self
-                        .resources
-                        .key_manager
-                        .get_diffie_hellman_shared_secret(&view_key.key_id, &output.sender_offset_public_key)
-                        .await?;
-
-                    let encryption_key = shared_secret_to_output_encryption_key(&shared_secret)?;
-                    if let Ok((committed_value, commitment_mask_private_key, payment_id)) =
-                        EncryptedData::decrypt_data(&encryption_key, &output.commitment, &output.encrypted_data)
-                    {
-                        let commitment_mask_key_id = &self
+                    let mut matched = None;
+                    for (account, account_view_key_id, account_spend_key) in &account_keys {
+                        let shared_secret = self
                             .resources
                             .key_manager
-                            .import_key(commitment_mask_private_key.clone())
+                            .get_diffie_hellman_shared_secret(account_view_key_id, &output.sender_offset_public_key)
                             .await?;
 
-                        if output.verify_mask(
+                        let encryption_key = shared_secret_to_output_encryption_key(&shared_secret)?;
+                        let Ok((committed_value, commitment_mask_private_key, payment_id)) =
+                            EncryptedData::decrypt_data(&encryption_key, &output.commitment, &output.encrypted_data)
+                        else {
+                            continue;
+                        };
+                        if !output.verify_mask(
                             &self.resources.factories.range_proof,
                             &commitment_mask_private_key,
                             committed_value.into(),
                         )? {
-                            let script_spending_key = self
-                                .resources
-                                .key_manager
-                                .stealth_address_script_spending_key(
-                                    commitment_mask_key_id,
-                                    &self.resources.key_manager.get_spend_key().await?.pub_key,
-                                )
-                                .await?;
-
-                            if script_spending_key != **scanned_pk {
-                                continue;
-                            }
-                            let commitment_mask = self
-                                .resources
-                                .key_manager
-                                .import_key(commitment_mask_private_key)
-                                .await?;
-                            let script_key = TariKeyId::Derived {
-                                key: SerializedKeyString::from(commitment_mask.to_string()),
-                            };
-
-                            let rewound_output = WalletOutput::new_with_rangeproof(
-                                output.version,
-                                committed_value,
-                                commitment_mask,
-                                output.features,
-                                output.script,
-                                ExecutionStack::new(vec![]),
-                                script_key,
-                                output.sender_offset_public_key,
-                                output.metadata_signature,
-                                0,
-                                output.covenant,
-                                output.encrypted_data,
-                                output.minimum_value_promise,
-                                output.proof,
-                                payment_id,
-                            );
-
-                            scanned_outputs.push((rewound_output, OutputSource::StealthOneSided, tx_id));
+                            continue;
+                        }
+                        let commitment_mask = self
+                            .resources
+                            .key_manager
+                            .import_key(commitment_mask_private_key)
+                            .await?;
+                        let script_spending_key = self
+                            .resources
+                            .key_manager
+                            .stealth_address_script_spending_key(&commitment_mask, account_spend_key)
+                            .await?;
+                        if script_spending_key == **scanned_pk {
+                            matched = Some((*account, committed_value, commitment_mask, payment_id));
+                            break;
                         }
                     }
+                    let Some((account, committed_value, commitment_mask, payment_id)) = matched else {
+                        continue;
+                    };
+                    let script_key = self
+                        .resources
+                        .key_manager
+                        .get_account_script_key_id(&commitment_mask, account)
+                        .await?;
+
+                    let rewound_output = WalletOutput::new_with_rangeproof(
+                        output.version,
+                        committed_value,
+                        commitment_mask,
+                        output.features,
+                        output.script,
+                        ExecutionStack::new(vec![]),
+                        script_key,
+                        output.sender_offset_public_key,
+                        output.metadata_signature,
+                        0,
+                        output.covenant,
+                        output.encrypted_data,
+                        output.minimum_value_promise,
+                        output.proof,
+                        payment_id,
+                    );
+
+                    scanned_outputs.push((rewound_output, OutputSource::StealthOneSided, tx_id, account));
                 }
             }
         }
@@ -3264,11 +3881,11 @@ where
     // Import scanned outputs into the wallet
     async fn import_onesided_outputs(
         &self,
-        scanned_outputs: Vec<(WalletOutput, OutputSource, Option<TxId>)>,
+        scanned_outputs: Vec<(WalletOutput, OutputSource, Option<TxId>, AccountId)>,
     ) -> Result<Vec<RecoveredOutput>, OutputManagerError> {
         let mut rewound_outputs = Vec::with_capacity(scanned_outputs.len());
 
-        for (output, output_source, tx_id) in scanned_outputs {
+        for (output, output_source, tx_id, account) in scanned_outputs {
             let tx_id = tx_id.unwrap_or(TxId::new_random());
             let db_output = DbWalletOutput::from_wallet_output(
                 output.clone(),
@@ -3278,7 +3895,8 @@ where
                 Some(tx_id),
                 None,
             )
-            .await?;
+            .await?
+            .with_account(Some(account));
             let hash = db_output.hash;
 
             match self
@@ -3392,6 +4010,7 @@ struct UtxoSelection {
     total_value: MicroMinotari,
     fee_without_change: MicroMinotari,
     fee_with_change: MicroMinotari,
+    change_label: Option<String>,
 }
 
 #[allow(dead_code)]
@@ -3407,6 +4026,11 @@ impl UtxoSelection {
         self.requires_change_output
     }
 
+    /// Whether the selected outputs pay for the amount and the fee, exactly or with change
+    fn covers(&self, amount: MicroMinotari) -> bool {
+        self.total_value == amount + self.fee_without_change || self.total_value > amount + self.fee_with_change
+    }
+
     /// Total value of the selected inputs
     pub fn total_value(&self) -> MicroMinotari {
         self.total_value
@@ -3416,6 +4040,11 @@ impl UtxoSelection {
         self.utxos.len()
     }
 
+    /// The label to give the change output
+    pub fn change_label(&self) -> Option<String> {
+        self.change_label.clone()
+    }
+
     pub fn into_selected(self) -> Vec<DbWalletOutput> {
         self.utxos
     }
@@ -3428,6 +4057,10 @@ impl UtxoSelection {
 #[derive(Debug, Clone)]
 pub struct OutputInfoByTxId {
     pub statuses: Vec<OutputStatus>,
+    /// The total value of the wallet outputs spent by the transaction
+    pub spent_value: MicroMinotari,
+    /// The account of the wallet outputs spent by the transaction, if they all belong to the same account
+    pub account: Option<AccountId>,
     pub(crate) mined_height: Option<u64>,
     pub(crate) block_hash: Option<BlockHash>,
 }
@@ -4,6 +4,7 @@
 use tari_common_types::{
     transaction::TxId,
     types::{Commitment, FixedHash},
+    wallet_types::AccountId,
 };
 use tari_core::transactions::transaction_components::{OutputType, TransactionOutput};
 
@@ -13,7 +14,7 @@ use crate::output_manager_service::{
     service::Balance,
     storage::{
         database::{DbKey, DbValue, OutputBackendQuery, WriteOperation},
-        models::DbWalletOutput,
+        models::{DbWalletOutput, UtxoConsolidation, WalletAccount},
         sqlite_db::{ReceivedOutputInfoForBatch, SpentOutputInfoForBatch},
     },
 };
@@ -82,8 +83,16 @@ pub trait OutputManagerBackend: Send + Sync + Clone {
     fn get_last_spent_output(&self) -> Result<Option<DbWalletOutput>, OutputManagerStorageError>;
     /// Reinstate a cancelled inbound output
     fn reinstate_cancelled_inbound_output(&self, tx_id: TxId) -> Result<(), OutputManagerStorageError>;
-    /// Return the available, time locked, pending incoming and pending outgoing balance
-    fn get_balance(&self, tip: Option<u64>) -> Result<Balance, OutputManagerStorageError>;
+    /// Reinstate the encumberance of a cancelled outbound transaction: its inputs are spent in it again and its change
+    /// outputs are expected again
+    fn reinstate_cancelled_outbound_outputs(
+        &self,
+        tx_id: TxId,
+        spent_commitments: &[Commitment],
+    ) -> Result<(), OutputManagerStorageError>;
+    /// Return the available, time locked, pending incoming and pending outgoing balance of the account, or of the
+    /// whole wallet if no account is specified
+    fn get_balance(&self, tip: Option<u64>, account: Option<AccountId>) -> Result<Balance, OutputManagerStorageError>;
     /// Import unvalidated output
     fn add_unvalidated_output(&self, output: DbWalletOutput, tx_id: TxId) -> Result<(), OutputManagerStorageError>;
     fn fetch_unspent_outputs_for_spending(
@@ -94,4 +103,29 @@ pub trait OutputManagerBackend: Send + Sync + Clone {
     ) -> Result<Vec<DbWalletOutput>, OutputManagerStorageError>;
     fn fetch_outputs_by_tx_id(&self, tx_id: TxId) -> Result<Vec<DbWalletOutput>, OutputManagerStorageError>;
     fn fetch_outputs_by_query(&self, q: OutputBackendQuery) -> Result<Vec<DbWalletOutput>, OutputManagerStorageError>;
+    /// Create a new named account
+    fn create_account(&self, name: &str) -> Result<WalletAccount, OutputManagerStorageError>;
+    /// Retrieve all accounts, ordered by id
+    fn fetch_accounts(&self) -> Result<Vec<WalletAccount>, OutputManagerStorageError>;
+    /// Assign the unspent outputs with the given commitments to an account, returning the number of outputs moved
+    fn move_outputs_to_account(
+        &self,
+        commitments: &[Commitment],
+        account: AccountId,
+    ) -> Result<usize, OutputManagerStorageError>;
+    /// Set the label of the outputs with the given commitments, or clear it if `None`, returning the number of outputs
+    /// updated
+    fn set_output_label(
+        &self,
+        commitments: &[Commitment],
+        label: Option<String>,
+    ) -> Result<usize, OutputManagerStorageError>;
+    /// Freeze or unfreeze the outputs with the given commitments, returning the number of outputs updated
+    fn set_outputs_frozen(&self, commitments: &[Commitment], frozen: bool) -> Result<usize, OutputManagerStorageError>;
+    /// Retrieve the ids of all transactions that created or spent outputs of the account
+    fn fetch_tx_ids_for_account(&self, account: AccountId) -> Result<Vec<TxId>, OutputManagerStorageError>;
+    /// Record a transaction that consolidated outputs of the wallet
+    fn insert_utxo_consolidation(&self, consolidation: UtxoConsolidation) -> Result<(), OutputManagerStorageError>;
+    /// Retrieve all recorded consolidation transactions, oldest first
+    fn fetch_utxo_consolidations(&self) -> Result<Vec<UtxoConsolidation>, OutputManagerStorageError>;
 }
@@ -31,6 +31,7 @@ use log::*;
 use tari_common_types::{
     transaction::TxId,
     types::{Commitment, FixedHash, HashOutput},
+    wallet_types::{AccountId, DEFAULT_ACCOUNT_ID},
 };
 use tari_core::transactions::{
     tari_amount::MicroMinotari,
@@ -43,7 +44,7 @@ use crate::output_manager_service::{
     input_selection::UtxoSelectionCriteria,
     service::Balance,
     storage::{
-        models::{DbWalletOutput, KnownOneSidedPaymentScript},
+        models::{DbWalletOutput, KnownOneSidedPaymentScript, UtxoConsolidation, WalletAccount},
         sqlite_db::{ReceivedOutputInfoForBatch, SpentOutputInfoForBatch},
         OutputStatus,
     },
@@ -181,7 +182,16 @@ where T: OutputManagerBackend + 'static
         &self,
         current_tip_for_time_lock_calculation: Option<u64>,
     ) -> Result<Balance, OutputManagerStorageError> {
-        self.db.get_balance(current_tip_for_time_lock_calculation)
+        self.db.get_balance(current_tip_for_time_lock_calculation, None)
+    }
+
+    pub fn get_account_balance(
+        &self,
+        current_tip_for_time_lock_calculation: Option<u64>,
+        account: AccountId,
+    ) -> Result<Balance, OutputManagerStorageError> {
+        self.db
+            .get_balance(current_tip_for_time_lock_calculation, Some(account))
     }
 
     /// This method is called when a transaction is built to be sent. It will encumber unspent outputs against a pending
@@ -190,8 +200,24 @@ where T: OutputManagerBackend + 'static
         &self,
         tx_id: TxId,
         outputs_to_send: Vec<DbWalletOutput>,
-        outputs_to_receive: Vec<DbWalletOutput>,
+        mut outputs_to_receive: Vec<DbWalletOutput>,
     ) -> Result<(), OutputManagerStorageError> {
+        // Change and any other outputs created by the transaction belong to the account that funded it, and keep the
+        // label of the spent outputs if they all share one
+        let account = outputs_to_send
+            .first()
+            .map(|o| o.account_id)
+            .unwrap_or(DEFAULT_ACCOUNT_ID);
+        let label = outputs_to_send
+            .first()
+            .and_then(|o| o.label.clone())
+            .filter(|label| outputs_to_send.iter().all(|o| o.label.as_ref() == Some(label)));
+        for output in &mut outputs_to_receive {
+            output.account_id = account;
+            if output.label.is_none() {
+                output.label = label.clone();
+            }
+        }
         self.db
             .short_term_encumber_outputs(tx_id, &outputs_to_send, &outputs_to_receive)
     }
@@ -321,6 +347,14 @@ where T: OutputManagerBackend + 'static
         self.db.reinstate_cancelled_inbound_output(tx_id)
     }
 
+    pub fn reinstate_cancelled_outbound_outputs(
+        &self,
+        tx_id: TxId,
+        spent_commitments: &[Commitment],
+    ) -> Result<(), OutputManagerStorageError> {
+        self.db.reinstate_cancelled_outbound_outputs(tx_id, spent_commitments)
+    }
+
     pub fn get_all_known_one_sided_payment_scripts(
         &self,
     ) -> Result<Vec<KnownOneSidedPaymentScript>, OutputManagerStorageError> {
@@ -437,6 +471,50 @@ where T: OutputManagerBackend + 'static
     ) -> Result<Vec<DbWalletOutput>, OutputManagerStorageError> {
         self.db.fetch_outputs_by_query(q)
     }
+
+    pub fn create_account(&self, name: &str) -> Result<WalletAccount, OutputManagerStorageError> {
+        self.db.create_account(name)
+    }
+
+    pub fn fetch_accounts(&self) -> Result<Vec<WalletAccount>, OutputManagerStorageError> {
+        self.db.fetch_accounts()
+    }
+
+    pub fn move_outputs_to_account(
+        &self,
+        commitments: &[Commitment],
+        account: AccountId,
+    ) -> Result<usize, OutputManagerStorageError> {
+        self.db.move_outputs_to_account(commitments, account)
+    }
+
+    pub fn fetch_tx_ids_for_account(&self, account: AccountId) -> Result<Vec<TxId>, OutputManagerStorageError> {
+        self.db.fetch_tx_ids_for_account(account)
+    }
+
+    pub fn set_output_label(
+        &self,
+        commitments: &[Commitment],
+        label: Option<String>,
+    ) -> Result<usize, OutputManagerStorageError> {
+        self.db.set_output_label(commitments, label)
+    }
+
+    pub fn set_outputs_frozen(
+        &self,
+        commitments: &[Commitment],
+        frozen: bool,
+    ) -> Result<usize, OutputManagerStorageError> {
+        self.db.set_outputs_frozen(commitments, frozen)
+    }
+
+    pub fn insert_utxo_consolidation(&self, consolidation: UtxoConsolidation) -> Result<(), OutputManagerStorageError> {
+        self.db.insert_utxo_consolidation(consolidation)
+    }
+
+    pub fn fetch_utxo_consolidations(&self) -> Result<Vec<UtxoConsolidation>, OutputManagerStorageError> {
+        self.db.fetch_utxo_consolidations()
+    }
 }
 
 fn unexpected_result<T>(req: DbKey, res: DbValue) -> Result<T, OutputManagerStorageError> {
@@ -24,12 +24,15 @@ use std::{cmp::Ordering, convert::TryFrom};
 
 use chrono::NaiveDateTime;
 use derivative::Derivative;
+use strum_macros::Display;
 use tari_common_types::{
     transaction::TxId,
     types::{BlockHash, Commitment, HashOutput},
+    wallet_types::{AccountId, DEFAULT_ACCOUNT_ID},
 };
 use tari_core::transactions::{
     key_manager::{TariKeyId, TransactionKeyManagerInterface},
+    tari_amount::MicroMinotari,
     transaction_components::{encrypted_data::PaymentId, WalletOutput},
 };
 use tari_script::{ExecutionStack, TariScript};
@@ -57,9 +60,28 @@ pub struct DbWalletOutput {
     pub received_in_tx_id: Option<TxId>,
     pub spent_in_tx_id: Option<TxId>,
     pub payment_id: PaymentId,
+    pub account_id: AccountId,
+    /// User assigned label used to group outputs for coin control
+    pub label: Option<String>,
+    /// Frozen outputs are never selected for spending
+    pub frozen: bool,
 }
 
 impl DbWalletOutput {
+    /// Credit the output to an account, or to the default account if no account is given
+    pub fn with_account(mut self, account: Option<AccountId>) -> Self {
+        self.account_id = account.unwrap_or(DEFAULT_ACCOUNT_ID);
+        self
+    }
+
+    /// Label the output, keeping any label it already has if none is given
+    pub fn with_label(mut self, label: Option<String>) -> Self {
+        if label.is_some() {
+            self.label = label;
+        }
+        self
+    }
+
     pub async fn from_wallet_output<KM: TransactionKeyManagerInterface>(
         output: WalletOutput,
         key_manager: &KM,
@@ -85,6 +107,9 @@ impl DbWalletOutput {
             received_in_tx_id,
             spent_in_tx_id,
             payment_id,
+            account_id: DEFAULT_ACCOUNT_ID,
+            label: None,
+            frozen: false,
         })
     }
 }
@@ -159,3 +184,47 @@ impl PartialEq for KnownOneSidedPaymentScript {
         self.script_hash == other.script_hash
     }
 }
+
+/// A named account of the wallet. Every output belongs to exactly one account, which determines the balance and the
+/// transaction history of the account.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WalletAccount {
+    pub id: AccountId,
+    pub name: String,
+    pub created_at: NaiveDateTime,
+}
+
+/// Why a consolidation transaction was made
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+pub enum UtxoConsolidationReason {
+    /// Requested by the user
+    Manual,
+    /// Made by the automatic consolidation policy of the output manager
+    Scheduled,
+}
+
+impl TryFrom<i32> for UtxoConsolidationReason {
+    type Error = OutputManagerStorageError;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(UtxoConsolidationReason::Manual),
+            1 => Ok(UtxoConsolidationReason::Scheduled),
+            _ => Err(OutputManagerStorageError::ConversionError {
+                reason: "Was expecting value between 0 and 1 for UtxoConsolidationReason".to_string(),
+            }),
+        }
+    }
+}
+
+/// A transaction that joined many small outputs of the wallet into a single output
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UtxoConsolidation {
+    pub tx_id: TxId,
+    pub reason: UtxoConsolidationReason,
+    pub num_inputs: usize,
+    pub input_value: MicroMinotari,
+    pub fee: MicroMinotari,
+    pub fee_per_gram: MicroMinotari,
+    pub created_at: NaiveDateTime,
+}
@@ -0,0 +1,80 @@
+// Copyright 2024 The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+use chrono::{NaiveDateTime, Utc};
+use diesel::{prelude::*, result::Error as DieselError};
+use tari_common_types::wallet_types::AccountId;
+
+use crate::{
+    output_manager_service::{error::OutputManagerStorageError, storage::models::WalletAccount},
+    schema::accounts,
+};
+
+#[derive(Clone, Debug, Queryable, Insertable, PartialEq)]
+#[diesel(table_name = accounts)]
+pub struct AccountSql {
+    id: i32,
+    name: String,
+    created_at: NaiveDateTime,
+}
+
+impl AccountSql {
+    /// Return all accounts, ordered by id
+    pub fn index(conn: &mut SqliteConnection) -> Result<Vec<AccountSql>, OutputManagerStorageError> {
+        Ok(accounts::table.order(accounts::id.asc()).load::<AccountSql>(conn)?)
+    }
+
+    pub fn find(id: i32, conn: &mut SqliteConnection) -> Result<Option<AccountSql>, OutputManagerStorageError> {
+        accounts::table
+            .filter(accounts::id.eq(id))
+            .first::<AccountSql>(conn)
+            .map(Some)
+            .or_else(|err| match err {
+                DieselError::NotFound => Ok(None),
+                err => Err(err.into()),
+            })
+    }
+
+    pub fn find_by_name(
+        name: &str,
+        conn: &mut SqliteConnection,
+    ) -> Result<Option<AccountSql>, OutputManagerStorageError> {
+        accounts::table
+            .filter(accounts::name.eq(name))
+            .first::<AccountSql>(conn)
+            .map(Some)
+            .or_else(|err| match err {
+                DieselError::NotFound => Ok(None),
+                err => Err(err.into()),
+            })
+    }
+
+    /// Create a new account with the next unused id
+    pub fn create(name: &str, conn: &mut SqliteConnection) -> Result<AccountSql, OutputManagerStorageError> {
+        conn.transaction::<_, OutputManagerStorageError, _>(|conn| {
+            if Self::find_by_name(name, conn)?.is_some() {
+                return Err(OutputManagerStorageError::AccountAlreadyExists(name.to_string()));
+            }
+            let max_id = accounts::table
+                .select(diesel::dsl::max(accounts::id))
+                .first::<Option<i32>>(conn)?;
+            let account = AccountSql {
+                id: max_id.map_or(0, |id| id + 1),
+                name: name.to_string(),
+                created_at: Utc::now().naive_utc(),
+            };
+            diesel::insert_into(accounts::table).values(&account).execute(conn)?;
+            Ok(account)
+        })
+    }
+}
+
+impl From<AccountSql> for WalletAccount {
+    fn from(account: AccountSql) -> Self {
+        Self {
+            id: account.id as AccountId,
+            name: account.name,
+            created_at: account.created_at,
+        }
+    }
+}
@@ -22,6 +22,7 @@
 
 use std::{convert::TryFrom, str::FromStr};
 
+pub use account_sql::AccountSql;
 use chrono::{NaiveDateTime, Utc};
 use derivative::Derivative;
 use diesel::{
@@ -37,6 +38,7 @@ use tari_common_sqlite::{sqlite_connection_pool::PooledDbConnection, util::diese
 use tari_common_types::{
     transaction::TxId,
     types::{Commitment, FixedHash},
+    wallet_types::AccountId,
 };
 use tari_core::transactions::{
     key_manager::TariKeyId,
@@ -45,6 +47,7 @@ use tari_core::transactions::{
 use tari_crypto::tari_utilities::{hex::Hex, ByteArray};
 use tari_script::{ExecutionStack, TariScript};
 use tokio::time::Instant;
+pub use utxo_consolidation_sql::{NewUtxoConsolidationSql, UtxoConsolidationSql};
 
 use crate::{
     output_manager_service::{
@@ -52,7 +55,7 @@ use crate::{
         service::Balance,
         storage::{
             database::{DbKey, DbKeyValuePair, DbValue, OutputBackendQuery, OutputManagerBackend, WriteOperation},
-            models::{DbWalletOutput, KnownOneSidedPaymentScript},
+            models::{DbWalletOutput, KnownOneSidedPaymentScript, UtxoConsolidation, WalletAccount},
             OutputStatus,
         },
         UtxoSelectionCriteria,
@@ -61,8 +64,10 @@ use crate::{
     storage::sqlite_utilities::wallet_db_connection::WalletDbConnection,
 };
 
+mod account_sql;
 mod new_output_sql;
 mod output_sql;
+mod utxo_consolidation_sql;
 const LOG_TARGET: &str = "wallet::output_manager_service::database::wallet";
 
 /// A Sqlite backend for the Output Manager Service. The Backend is accessed via a connection pool to the Sqlite file.
@@ -943,12 +948,13 @@ impl OutputManagerBackend for OutputManagerSqliteDatabase {
     fn get_balance(
         &self,
         current_tip_for_time_lock_calculation: Option<u64>,
+        account: Option<AccountId>,
     ) -> Result<Balance, OutputManagerStorageError> {
         let start = Instant::now();
         let mut conn = self.database_connection.get_pooled_connection()?;
         let acquire_lock = start.elapsed();
 
-        let result = OutputSql::get_balance(current_tip_for_time_lock_calculation, &mut conn);
+        let result = OutputSql::get_balance(current_tip_for_time_lock_calculation, account, &mut conn);
         if start.elapsed().as_millis() > 0 {
             trace!(
                 target: LOG_TARGET,
@@ -1123,6 +1129,63 @@ impl OutputManagerBackend for OutputManagerSqliteDatabase {
         Ok(())
     }
 
+    fn reinstate_cancelled_outbound_outputs(
+        &self,
+        tx_id: TxId,
+        spent_commitments: &[Commitment],
+    ) -> Result<(), OutputManagerStorageError> {
+        let start = Instant::now();
+        let mut conn = self.database_connection.get_pooled_connection()?;
+        let acquire_lock = start.elapsed();
+
+        let commitments = spent_commitments.iter().map(|c| c.as_bytes()).collect::<Vec<_>>();
+        conn.transaction::<_, _, _>(|conn| {
+            // The inputs must not have been spent elsewhere since the transaction was cancelled
+            if !OutputSql::find_by_commitments_excluding_status(commitments.clone(), OutputStatus::Unspent, conn)?
+                .is_empty()
+            {
+                return Err(OutputManagerStorageError::OutputAlreadySpent);
+            };
+
+            let count = OutputSql::update_by_commitments(
+                commitments,
+                UpdateOutput {
+                    status: Some(OutputStatus::EncumberedToBeSpent),
+                    spent_in_tx_id: Some(Some(tx_id)),
+                    ..Default::default()
+                },
+                conn,
+            )?;
+            if count != spent_commitments.len() {
+                let msg = format!(
+                    "Inconsistent reinstatement of spent outputs! Lengths do not match - {} vs {}",
+                    count,
+                    spent_commitments.len()
+                );
+                error!(target: LOG_TARGET, "{}", msg,);
+                return Err(OutputManagerStorageError::UnexpectedResult(msg));
+            }
+
+            update_outputs_with_tx_id_and_status_to_new_status(
+                conn,
+                tx_id,
+                OutputStatus::CancelledInbound,
+                OutputStatus::EncumberedToBeReceived,
+            )
+        })?;
+
+        if start.elapsed().as_millis() > 0 {
+            trace!(
+                target: LOG_TARGET,
+                "sqlite profile - reinstate_cancelled_outbound_outputs: lock {} + db_op {} = {} ms",
+                acquire_lock.as_millis(),
+                (start.elapsed() - acquire_lock).as_millis(),
+                start.elapsed().as_millis()
+            );
+        }
+        Ok(())
+    }
+
     fn add_unvalidated_output(&self, output: DbWalletOutput, tx_id: TxId) -> Result<(), OutputManagerStorageError> {
         let start = Instant::now();
         let mut conn = self.database_connection.get_pooled_connection()?;
@@ -1199,6 +1262,74 @@ impl OutputManagerBackend for OutputManagerSqliteDatabase {
             })
             .collect())
     }
+
+    fn create_account(&self, name: &str) -> Result<WalletAccount, OutputManagerStorageError> {
+        let mut conn = self.database_connection.get_pooled_connection()?;
+        Ok(AccountSql::create(name, &mut conn)?.into())
+    }
+
+    fn fetch_accounts(&self) -> Result<Vec<WalletAccount>, OutputManagerStorageError> {
+        let mut conn = self.database_connection.get_pooled_connection()?;
+        Ok(AccountSql::index(&mut conn)?.into_iter().map(Into::into).collect())
+    }
+
+    fn move_outputs_to_account(
+        &self,
+        commitments: &[Commitment],
+        account: AccountId,
+    ) -> Result<usize, OutputManagerStorageError> {
+        let account_id = account_id_to_i32(account)?;
+        let mut conn = self.database_connection.get_pooled_connection()?;
+        conn.transaction::<_, OutputManagerStorageError, _>(|conn| {
+            if AccountSql::find(account_id, conn)?.is_none() {
+                return Err(OutputManagerStorageError::AccountNotFound(account.to_string()));
+            }
+            OutputSql::update_account_by_commitments(commitments, account_id, conn)
+        })
+    }
+
+    fn set_output_label(
+        &self,
+        commitments: &[Commitment],
+        label: Option<String>,
+    ) -> Result<usize, OutputManagerStorageError> {
+        let mut conn = self.database_connection.get_pooled_connection()?;
+        OutputSql::update_label_by_commitments(commitments, label, &mut conn)
+    }
+
+    fn set_outputs_frozen(&self, commitments: &[Commitment], frozen: bool) -> Result<usize, OutputManagerStorageError> {
+        let mut conn = self.database_connection.get_pooled_connection()?;
+        OutputSql::update_frozen_by_commitments(commitments, frozen, &mut conn)
+    }
+
+    fn fetch_tx_ids_for_account(&self, account: AccountId) -> Result<Vec<TxId>, OutputManagerStorageError> {
+        let account_id = account_id_to_i32(account)?;
+        let mut conn = self.database_connection.get_pooled_connection()?;
+        let mut tx_ids = OutputSql::find_tx_ids_by_account(account_id, &mut conn)?
+            .into_iter()
+            .flat_map(|(received, spent)| received.into_iter().chain(spent))
+            .collect::<Vec<_>>();
+        tx_ids.sort_unstable();
+        tx_ids.dedup();
+        Ok(tx_ids.into_iter().map(|tx_id| TxId::from(tx_id as u64)).collect())
+    }
+
+    fn insert_utxo_consolidation(&self, consolidation: UtxoConsolidation) -> Result<(), OutputManagerStorageError> {
+        let mut conn = self.database_connection.get_pooled_connection()?;
+        NewUtxoConsolidationSql::from(consolidation).commit(&mut conn)
+    }
+
+    fn fetch_utxo_consolidations(&self) -> Result<Vec<UtxoConsolidation>, OutputManagerStorageError> {
+        let mut conn = self.database_connection.get_pooled_connection()?;
+        UtxoConsolidationSql::index(&mut conn)?
+            .into_iter()
+            .map(UtxoConsolidation::try_from)
+            .collect()
+    }
+}
+
+fn account_id_to_i32(account: AccountId) -> Result<i32, OutputManagerStorageError> {
+    i32::try_from(account).map_err(|e| OutputManagerStorageError::ConversionError { reason: e.to_string() })
 }
 
 /// These are the fields to be set for the received outputs batch mode update
@@ -34,6 +34,7 @@ use tari_common_sqlite::util::diesel_ext::ExpectedRowsExtension;
 use tari_common_types::{
     transaction::TxId,
     types::{ComAndPubSignature, Commitment, FixedHash, PrivateKey, PublicKey, RangeProof},
+    wallet_types::AccountId,
 };
 use tari_core::transactions::{
     tari_amount::MicroMinotari,
@@ -110,6 +111,9 @@ pub struct OutputSql {
     pub source: i32,
     pub last_validation_timestamp: Option<NaiveDateTime>,
     pub payment_id: Option<Vec<u8>>,
+    pub account_id: i32,
+    pub label: Option<String>,
+    pub frozen: i32,
 }
 
 impl OutputSql {
@@ -207,12 +211,28 @@ impl OutputSql {
         let i64_tip_height = tip_height.and_then(|h| i64::try_from(h).ok()).unwrap_or(i64::MAX);
         let i64_value = i64::try_from(selection_criteria.min_dust).unwrap_or(i64::MAX);
 
+        let account_id = selection_criteria
+            .account
+            .map(|id| {
+                i32::try_from(id).map_err(|e| OutputManagerStorageError::ConversionError { reason: e.to_string() })
+            })
+            .transpose()?;
+
         let mut query = outputs::table
             .into_boxed()
             .filter(outputs::status.eq(OutputStatus::Unspent as i32))
             .filter(outputs::value.gt(i64_value))
+            .filter(outputs::frozen.eq(0))
             .order_by(outputs::spending_priority.desc());
 
+        if let Some(account_id) = account_id {
+            query = query.filter(outputs::account_id.eq(account_id));
+        }
+
+        if !selection_criteria.labels.is_empty() {
+            query = query.filter(outputs::label.eq_any(selection_criteria.labels.clone()));
+        }
+
         // NOTE: Safe mode presets `script_lock_height` and `maturity` filters for all queries
         if selection_criteria.mode == UtxoSelectionMode::Safe {
             query = query
@@ -254,10 +274,19 @@ impl OutputSql {
             UtxoSelectionOrdering::Default => {
                 // NOTE: keeping filtering by `script_lock_height` and `maturity` for all modes
                 // lets get the max value for all utxos
-                let max: Option<i64> = outputs::table
+                let mut max_query = outputs::table
+                    .into_boxed()
                     .filter(outputs::status.eq(OutputStatus::Unspent as i32))
                     .filter(outputs::script_lock_height.le(i64_tip_height))
                     .filter(outputs::maturity.le(i64_tip_height))
+                    .filter(outputs::frozen.eq(0));
+                if let Some(account_id) = account_id {
+                    max_query = max_query.filter(outputs::account_id.eq(account_id));
+                }
+                if !selection_criteria.labels.is_empty() {
+                    max_query = max_query.filter(outputs::label.eq_any(selection_criteria.labels.clone()));
+                }
+                let max: Option<i64> = max_query
                     .order(outputs::value.desc())
                     .select(outputs::value)
                     .first(conn)
@@ -388,6 +417,61 @@ impl OutputSql {
             .load(conn)?)
     }
 
+    /// Return the ids of all transactions that created or spent outputs belonging to the account
+    pub fn find_tx_ids_by_account(
+        account_id: i32,
+        conn: &mut SqliteConnection,
+    ) -> Result<Vec<(Option<i64>, Option<i64>)>, OutputManagerStorageError> {
+        Ok(outputs::table
+            .filter(outputs::account_id.eq(account_id))
+            .select((outputs::received_in_tx_id, outputs::spent_in_tx_id))
+            .load(conn)?)
+    }
+
+    /// Assign the unspent outputs with the specified commitments to an account, returning the number of outputs moved
+    pub fn update_account_by_commitments(
+        commitments: &[Commitment],
+        account_id: i32,
+        conn: &mut SqliteConnection,
+    ) -> Result<usize, OutputManagerStorageError> {
+        let commitments: Vec<_> = commitments.iter().map(|c| c.to_vec()).collect();
+        Ok(diesel::update(
+            outputs::table
+                .filter(outputs::commitment.eq_any(commitments))
+                .filter(outputs::status.eq(OutputStatus::Unspent as i32)),
+        )
+        .set(outputs::account_id.eq(account_id))
+        .execute(conn)?)
+    }
+
+    /// Set or clear the label of the outputs with the specified commitments, returning the number of outputs updated
+    pub fn update_label_by_commitments(
+        commitments: &[Commitment],
+        label: Option<String>,
+        conn: &mut SqliteConnection,
+    ) -> Result<usize, OutputManagerStorageError> {
+        let commitments: Vec<_> = commitments.iter().map(|c| c.to_vec()).collect();
+        Ok(
+            diesel::update(outputs::table.filter(outputs::commitment.eq_any(commitments)))
+                .set(outputs::label.eq(label))
+                .execute(conn)?,
+        )
+    }
+
+    /// Freeze or unfreeze the outputs with the specified commitments, returning the number of outputs updated
+    pub fn update_frozen_by_commitments(
+        commitments: &[Commitment],
+        frozen: bool,
+        conn: &mut SqliteConnection,
+    ) -> Result<usize, OutputManagerStorageError> {
+        let commitments: Vec<_> = commitments.iter().map(|c| c.to_vec()).collect();
+        Ok(
+            diesel::update(outputs::table.filter(outputs::commitment.eq_any(commitments)))
+                .set(outputs::frozen.eq(i32::from(frozen)))
+                .execute(conn)?,
+        )
+    }
+
     /// Verify that outputs with specified commitments exist in the database
     pub fn verify_outputs_exist(
         commitments: &[Commitment],
@@ -413,12 +497,19 @@ impl OutputSql {
         Ok(query_result[0].count == commitments_len)
     }
 
-    /// Return the available, time locked, pending incoming and pending outgoing balance
+    /// Return the available, time locked, pending incoming and pending outgoing balance. Frozen outputs are not
+    /// counted as available or time locked.
     #[allow(clippy::cast_possible_wrap)]
     pub fn get_balance(
         current_tip_for_time_lock_calculation: Option<u64>,
+        account_id: Option<AccountId>,
         conn: &mut SqliteConnection,
     ) -> Result<Balance, OutputManagerStorageError> {
+        let account_id = account_id
+            .map(|id| {
+                i32::try_from(id).map_err(|e| OutputManagerStorageError::ConversionError { reason: e.to_string() })
+            })
+            .transpose()?;
         #[derive(QueryableByName, Clone)]
         struct BalanceQueryResult {
             #[diesel(sql_type = diesel::sql_types::BigInt)]
@@ -428,18 +519,21 @@ impl OutputSql {
         }
         let balance_query_result = if let Some(current_tip) = current_tip_for_time_lock_calculation {
             let balance_query = sql_query(
-                "SELECT coalesce(sum(value), 0) as amount, 'available_balance' as category \
-                 FROM outputs WHERE status = ? AND maturity <= ? AND script_lock_height <= ? \
+                "WITH account_outputs AS (SELECT * FROM outputs WHERE account_id = coalesce(?, account_id)) \
+                 SELECT coalesce(sum(value), 0) as amount, 'available_balance' as category \
+                 FROM account_outputs WHERE frozen = 0 AND status = ? AND maturity <= ? AND script_lock_height <= ? \
                  UNION ALL \
                  SELECT coalesce(sum(value), 0) as amount, 'time_locked_balance' as category \
-                 FROM outputs WHERE status = ? AND maturity > ? OR script_lock_height > ? \
+                 FROM account_outputs WHERE frozen = 0 AND (status = ? AND maturity > ? OR script_lock_height > ?) \
                  UNION ALL \
                  SELECT coalesce(sum(value), 0) as amount, 'pending_incoming_balance' as category \
-                 FROM outputs WHERE source != ? AND status = ? OR status = ? OR status = ? \
+                 FROM account_outputs WHERE source != ? AND status = ? OR status = ? OR status = ? \
                  UNION ALL \
                  SELECT coalesce(sum(value), 0) as amount, 'pending_outgoing_balance' as category \
-                 FROM outputs WHERE status = ? OR status = ? OR status = ?",
+                 FROM account_outputs WHERE status = ? OR status = ? OR status = ?",
             )
+                // outputs of the account, or all outputs
+                .bind::<diesel::sql_types::Nullable<diesel::sql_types::Integer>, _>(account_id)
                 // available_balance
                 .bind::<diesel::sql_types::Integer, _>(OutputStatus::Unspent as i32)
                 .bind::<diesel::sql_types::BigInt, _>(current_tip as i64)
@@ -460,15 +554,18 @@ impl OutputSql {
             balance_query.load::<BalanceQueryResult>(conn)?
         } else {
             let balance_query = sql_query(
-                "SELECT coalesce(sum(value), 0) as amount, 'available_balance' as category \
-                 FROM outputs WHERE status = ? \
+                "WITH account_outputs AS (SELECT * FROM outputs WHERE account_id = coalesce(?, account_id)) \
+                 SELECT coalesce(sum(value), 0) as amount, 'available_balance' as category \
+                 FROM account_outputs WHERE frozen = 0 AND status = ? \
                  UNION ALL \
                  SELECT coalesce(sum(value), 0) as amount, 'pending_incoming_balance' as category \
-                 FROM outputs WHERE source != ? AND status = ? OR status = ? OR status = ? \
+                 FROM account_outputs WHERE source != ? AND status = ? OR status = ? OR status = ? \
                  UNION ALL \
                  SELECT coalesce(sum(value), 0) as amount, 'pending_outgoing_balance' as category \
-                 FROM outputs WHERE status = ? OR status = ? OR status = ?",
+                 FROM account_outputs WHERE status = ? OR status = ? OR status = ?",
             )
+                // outputs of the account, or all outputs
+                .bind::<diesel::sql_types::Nullable<diesel::sql_types::Integer>, _>(account_id)
                 // available_balance
                 .bind::<diesel::sql_types::Integer, _>(OutputStatus::Unspent as i32)
                 // pending_incoming_balance
@@ -823,6 +920,9 @@ impl OutputSql {
             received_in_tx_id: self.received_in_tx_id.map(|d| (d as u64).into()),
             spent_in_tx_id: self.spent_in_tx_id.map(|d| (d as u64).into()),
             payment_id,
+            account_id: self.account_id as AccountId,
+            label: self.label,
+            frozen: self.frozen != 0,
         })
     }
 }
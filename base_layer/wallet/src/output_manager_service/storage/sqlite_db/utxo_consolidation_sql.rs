@@ -0,0 +1,93 @@
+// Copyright 2024 The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+use std::convert::TryFrom;
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use tari_common_types::transaction::TxId;
+use tari_core::transactions::tari_amount::MicroMinotari;
+
+use crate::{
+    output_manager_service::{
+        error::OutputManagerStorageError,
+        storage::models::{UtxoConsolidation, UtxoConsolidationReason},
+    },
+    schema::utxo_consolidations,
+};
+
+#[derive(Clone, Debug, Queryable, PartialEq)]
+pub struct UtxoConsolidationSql {
+    id: i32,
+    tx_id: i64,
+    reason: i32,
+    num_inputs: i64,
+    input_value: i64,
+    fee: i64,
+    fee_per_gram: i64,
+    created_at: NaiveDateTime,
+}
+
+impl UtxoConsolidationSql {
+    /// Return all consolidations, oldest first
+    pub fn index(conn: &mut SqliteConnection) -> Result<Vec<UtxoConsolidationSql>, OutputManagerStorageError> {
+        Ok(utxo_consolidations::table
+            .order(utxo_consolidations::id.asc())
+            .load::<UtxoConsolidationSql>(conn)?)
+    }
+}
+
+impl TryFrom<UtxoConsolidationSql> for UtxoConsolidation {
+    type Error = OutputManagerStorageError;
+
+    fn try_from(c: UtxoConsolidationSql) -> Result<Self, Self::Error> {
+        Ok(Self {
+            tx_id: TxId::from(c.tx_id as u64),
+            reason: UtxoConsolidationReason::try_from(c.reason)?,
+            num_inputs: usize::try_from(c.num_inputs)
+                .map_err(|e| OutputManagerStorageError::ConversionError { reason: e.to_string() })?,
+            input_value: MicroMinotari::from(c.input_value as u64),
+            fee: MicroMinotari::from(c.fee as u64),
+            fee_per_gram: MicroMinotari::from(c.fee_per_gram as u64),
+            created_at: c.created_at,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Insertable)]
+#[diesel(table_name = utxo_consolidations)]
+pub struct NewUtxoConsolidationSql {
+    tx_id: i64,
+    reason: i32,
+    num_inputs: i64,
+    input_value: i64,
+    fee: i64,
+    fee_per_gram: i64,
+    created_at: NaiveDateTime,
+}
+
+impl NewUtxoConsolidationSql {
+    pub fn commit(&self, conn: &mut SqliteConnection) -> Result<(), OutputManagerStorageError> {
+        diesel::insert_into(utxo_consolidations::table)
+            .values(self.clone())
+            .execute(conn)?;
+        Ok(())
+    }
+}
+
+impl From<UtxoConsolidation> for NewUtxoConsolidationSql {
+    fn from(c: UtxoConsolidation) -> Self {
+        Self {
+            tx_id: c.tx_id.as_i64_wrapped(),
+            reason: match c.reason {
+                UtxoConsolidationReason::Manual => 0,
+                UtxoConsolidationReason::Scheduled => 1,
+            },
+            num_inputs: c.num_inputs as i64,
+            input_value: c.input_value.as_u64() as i64,
+            fee: c.fee.as_u64() as i64,
+            fee_per_gram: c.fee_per_gram.as_u64() as i64,
+            created_at: c.created_at,
+        }
+    }
+}
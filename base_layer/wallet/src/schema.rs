@@ -1,5 +1,23 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    accounts (id) {
+        id -> Integer,
+        name -> Text,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    batch_payment_recipients (id) {
+        id -> Integer,
+        tx_id -> BigInt,
+        destination_address -> Binary,
+        amount -> BigInt,
+        payment_id -> Binary,
+    }
+}
+
 diesel::table! {
     burnt_proofs (id) {
         id -> Integer,
@@ -79,6 +97,7 @@ diesel::table! {
         direct_send_success -> Integer,
         send_count -> Integer,
         last_send_timestamp -> Nullable<Timestamp>,
+        offline_signing -> Integer,
     }
 }
 
@@ -119,6 +138,28 @@ diesel::table! {
         source -> Integer,
         last_validation_timestamp -> Nullable<Timestamp>,
         payment_id -> Nullable<Binary>,
+        account_id -> Integer,
+        label -> Nullable<Text>,
+        frozen -> Integer,
+    }
+}
+
+diesel::table! {
+    payment_request_payments (tx_id) {
+        tx_id -> BigInt,
+        payment_request_id -> BigInt,
+        amount -> BigInt,
+    }
+}
+
+diesel::table! {
+    payment_requests (id) {
+        id -> BigInt,
+        payment_id -> Binary,
+        request -> Text,
+        status -> Integer,
+        amount_received -> BigInt,
+        created_at -> Timestamp,
     }
 }
 
@@ -132,6 +173,46 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    scheduled_payment_runs (id) {
+        id -> Integer,
+        scheduled_payment_id -> BigInt,
+        run_at -> Timestamp,
+        tx_id -> Nullable<BigInt>,
+        error -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    scheduled_payments (id) {
+        id -> BigInt,
+        destination_address -> Binary,
+        amount -> BigInt,
+        fee_per_gram -> BigInt,
+        message -> Text,
+        payment_kind -> Integer,
+        run_once_at -> Nullable<Timestamp>,
+        cron_expression -> Nullable<Text>,
+        next_run_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        cancelled -> Integer,
+        account_id -> Nullable<BigInt>,
+    }
+}
+
+diesel::table! {
+    utxo_consolidations (id) {
+        id -> Integer,
+        tx_id -> BigInt,
+        reason -> Integer,
+        num_inputs -> BigInt,
+        input_value -> BigInt,
+        fee -> BigInt,
+        fee_per_gram -> BigInt,
+        created_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     wallet_settings (key) {
         key -> Text,
@@ -140,6 +221,8 @@ diesel::table! {
 }
 
 diesel::allow_tables_to_appear_in_same_query!(
+    accounts,
+    batch_payment_recipients,
     burnt_proofs,
     client_key_values,
     completed_transactions,
@@ -147,6 +230,11 @@ diesel::allow_tables_to_appear_in_same_query!(
     known_one_sided_payment_scripts,
     outbound_transactions,
     outputs,
+    payment_request_payments,
+    payment_requests,
     scanned_blocks,
+    scheduled_payment_runs,
+    scheduled_payments,
+    utxo_consolidations,
     wallet_settings,
 );
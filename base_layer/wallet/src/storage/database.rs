@@ -63,6 +63,11 @@ pub trait WalletBackend: Send + Sync + Clone {
     /// Change the passphrase used to encrypt the database
     fn change_passphrase(&self, existing: &SafePassword, new: &SafePassword) -> Result<(), WalletStorageError>;
 
+    /// Change the passphrase and re-encrypt all encrypted data in the database under a new main key, returning the
+    /// number of values re-encrypted. Other backends sharing the database hold their own copy of the old key, so this
+    /// must only be used while no wallet services are running on the database.
+    fn rotate_encryption_key(&self, existing: &SafePassword, new: &SafePassword) -> Result<usize, WalletStorageError>;
+
     fn create_burnt_proof(
         &self,
         id: u32,
@@ -169,6 +174,14 @@ where T: WalletBackend + 'static
         Ok(())
     }
 
+    pub fn rotate_encryption_key(
+        &self,
+        existing: &SafePassword,
+        new: &SafePassword,
+    ) -> Result<usize, WalletStorageError> {
+        self.db.rotate_encryption_key(existing, new)
+    }
+
     pub fn get_master_seed(&self) -> Result<Option<CipherSeed>, WalletStorageError> {
         let c = match self.db.fetch(&DbKey::MasterSeed) {
             Ok(None) => Ok(None),
@@ -49,7 +49,10 @@ use tari_comms::{
     tor::TorIdentity,
 };
 use tari_crypto::{hash_domain, hashing::DomainSeparatedHasher};
-use tari_key_manager::cipher_seed::CipherSeed;
+use tari_key_manager::{
+    cipher_seed::CipherSeed,
+    key_manager_service::storage::sqlite_db::{reencrypt_key_manager_data, verify_key_manager_data},
+};
 use tari_utilities::{
     hex::{from_hex, Hex},
     hidden_type,
@@ -69,11 +72,16 @@ use crate::{
         sqlite_db::scanned_blocks::ScannedBlockSql,
         sqlite_utilities::wallet_db_connection::WalletDbConnection,
     },
+    transaction_service::storage::sqlite_db::{reencrypt_transactions, verify_transactions_encryption},
     utxo_scanner_service::service::ScannedBlock,
 };
 
 const LOG_TARGET: &str = "wallet::storage::wallet";
 
+// The domains the encrypted wallet settings are encrypted under
+const MASTER_SEED_DOMAIN: &[u8] = b"wallet_setting_master_seed";
+const TOR_ID_DOMAIN: &[u8] = b"wallet_setting_tor_id";
+
 // The main `XChaCha20-Poly1305` key used for database encryption
 // This isn't a `SafeArray` because of how we populate it from an authenticated decryption
 // However, it is `Hidden` and therefore should be safe to use
@@ -244,9 +252,8 @@ impl WalletSqliteDatabase {
         }
 
         let seed_bytes = Hidden::hide(seed.encipher(None)?);
-        let ciphertext_integral_nonce =
-            encrypt_bytes_integral_nonce(&cipher, b"wallet_setting_master_seed".to_vec(), seed_bytes)
-                .map_err(|e| WalletStorageError::AeadError(format!("Encryption Error:{}", e)))?;
+        let ciphertext_integral_nonce = encrypt_bytes_integral_nonce(&cipher, MASTER_SEED_DOMAIN.to_vec(), seed_bytes)
+            .map_err(|e| WalletStorageError::AeadError(format!("Encryption Error:{}", e)))?;
         WalletSettingSql::new(DbKey::MasterSeed, ciphertext_integral_nonce.to_hex()).set(conn)?;
 
         Ok(())
@@ -259,12 +266,8 @@ impl WalletSqliteDatabase {
                 // Decrypted_key_bytes contains sensitive data regarding decrypted
                 // seed words. For this reason, we should zeroize the underlying data buffer
                 let decrypted_key_bytes = Hidden::hide(
-                    decrypt_bytes_integral_nonce(
-                        &cipher,
-                        b"wallet_setting_master_seed".to_vec(),
-                        &from_hex(seed_str.as_str())?,
-                    )
-                    .map_err(|e| WalletStorageError::AeadError(format!("Decryption Error:{}", e)))?,
+                    decrypt_bytes_integral_nonce(&cipher, MASTER_SEED_DOMAIN.to_vec(), &from_hex(seed_str.as_str())?)
+                        .map_err(|e| WalletStorageError::AeadError(format!("Decryption Error:{}", e)))?,
                 );
                 CipherSeed::from_enciphered_bytes(decrypted_key_bytes.reveal(), None)?
             };
@@ -316,7 +319,7 @@ impl WalletSqliteDatabase {
 
         let bytes =
             Hidden::hide(bincode::serialize(&tor).map_err(|e| WalletStorageError::ConversionError(e.to_string()))?);
-        let ciphertext_integral_nonce = encrypt_bytes_integral_nonce(&cipher, b"wallet_setting_tor_id".to_vec(), bytes)
+        let ciphertext_integral_nonce = encrypt_bytes_integral_nonce(&cipher, TOR_ID_DOMAIN.to_vec(), bytes)
             .map_err(|e| WalletStorageError::AeadError(format!("Encryption Error:{}", e)))?;
 
         WalletSettingSql::new(DbKey::TorId, ciphertext_integral_nonce.to_hex()).set(conn)?;
@@ -331,7 +334,7 @@ impl WalletSqliteDatabase {
                 // we must zeroize decrypted_key_bytes, as this contains sensitive data,
                 // including private key informations
                 let decrypted_key_bytes = Hidden::hide(
-                    decrypt_bytes_integral_nonce(&cipher, b"wallet_setting_tor_id".to_vec(), &from_hex(&key_str)?)
+                    decrypt_bytes_integral_nonce(&cipher, TOR_ID_DOMAIN.to_vec(), &from_hex(&key_str)?)
                         .map_err(|e| WalletStorageError::AeadError(format!("Decryption Error:{}", e)))?,
                 );
 
@@ -589,38 +592,10 @@ impl WalletBackend for WalletSqliteDatabase {
         match DatabaseEncryptionFields::read(&mut conn) {
             // Key-related data was present and valid
             Ok(Some(data)) => {
-                // Use the given version if it is valid
-                let argon2_params = Argon2Parameters::from_version(Some(data.secondary_key_version))?;
-
-                // Derive a secondary key from the existing passphrase and salt
-                let (secondary_key, secondary_key_hash) =
-                    derive_secondary_key(existing, argon2_params.clone(), &data.secondary_key_salt)?;
-
-                // Attempt to decrypt the encrypted main key
-                if data.secondary_key_hash != secondary_key_hash {
-                    return Err(WalletStorageError::InvalidPassphrase);
-                }
-                let main_key = decrypt_main_key(&secondary_key, &data.encrypted_main_key, argon2_params.id)?;
-
-                // Now use the most recent version
-                let new_argon2_params = Argon2Parameters::from_version(None)?;
-
-                // Derive a new secondary key from the new passphrase and a fresh salt
-                let new_secondary_key_salt = SaltString::generate(&mut OsRng).to_string();
-                let (new_secondary_key, new_secondary_key_hash) =
-                    derive_secondary_key(new, new_argon2_params.clone(), &new_secondary_key_salt)?;
-
-                // Encrypt the main key with the new secondary key
-                let new_encrypted_main_key = encrypt_main_key(&new_secondary_key, &main_key, new_argon2_params.id)?;
+                let main_key = unlock_main_key(&data, existing)?;
 
-                // Store the new key-related fields
-                DatabaseEncryptionFields {
-                    secondary_key_version: new_argon2_params.id,
-                    secondary_key_salt: new_secondary_key_salt,
-                    secondary_key_hash: new_secondary_key_hash,
-                    encrypted_main_key: new_encrypted_main_key,
-                }
-                .write(&mut conn)?;
+                // Encrypt the main key under the new passphrase and store the new key-related fields
+                lock_main_key(&main_key, new)?.write(&mut conn)?;
             },
 
             // If any key-related is not present, this is an invalid state
@@ -634,6 +609,62 @@ impl WalletBackend for WalletSqliteDatabase {
         Ok(())
     }
 
+    fn rotate_encryption_key(&self, existing: &SafePassword, new: &SafePassword) -> Result<usize, WalletStorageError> {
+        let mut conn = self.database_connection.get_pooled_connection()?;
+        // Hold the write lock throughout, so that nothing is encrypted under the old key while the data is re-encrypted
+        let mut cipher = acquire_write_lock!(self.cipher);
+
+        let data = DatabaseEncryptionFields::read(&mut conn)?.ok_or_else(|| {
+            WalletStorageError::UnexpectedResult("Unable to get valid key-related data from database".into())
+        })?;
+        let old_cipher = XChaCha20Poly1305::new(Key::from_slice(unlock_main_key(&data, existing)?.reveal()));
+
+        // Generate a new high-entropy main key and encrypt it under the new passphrase
+        let mut new_main_key = WalletMainEncryptionKey::from(vec![0u8; size_of::<Key>()]);
+        OsRng.fill_bytes(new_main_key.reveal_mut());
+        let new_fields = lock_main_key(&new_main_key, new)?;
+        let new_cipher = XChaCha20Poly1305::new(Key::from_slice(new_main_key.reveal()));
+
+        // Everything is re-encrypted, the new key stored and the result verified in a single transaction, so that a
+        // crash or a failed verification leaves the database as it was
+        let num_updated = conn.immediate_transaction::<_, WalletStorageError, _>(|conn| {
+            let num_updated = reencrypt_wallet_data(conn, &old_cipher, &new_cipher)? +
+                reencrypt_transactions(conn, &old_cipher, &new_cipher)
+                    .map_err(|e| WalletStorageError::KeyRotationError(e.to_string()))? +
+                reencrypt_key_manager_data(conn, &old_cipher, &new_cipher)
+                    .map_err(|e| WalletStorageError::KeyRotationError(e.to_string()))?;
+            new_fields.write(conn)?;
+
+            let num_verified = verify_wallet_data(conn, &new_cipher)? +
+                verify_transactions_encryption(conn, &new_cipher)
+                    .map_err(|e| WalletStorageError::KeyRotationError(e.to_string()))? +
+                verify_key_manager_data(conn, &new_cipher)
+                    .map_err(|e| WalletStorageError::KeyRotationError(e.to_string()))?;
+            if num_verified != num_updated {
+                return Err(WalletStorageError::KeyRotationError(format!(
+                    "{} values were re-encrypted but {} could be verified",
+                    num_updated, num_verified
+                )));
+            }
+            let stored = DatabaseEncryptionFields::read(conn)?.ok_or_else(|| {
+                WalletStorageError::KeyRotationError("The new key-related data was not stored".into())
+            })?;
+            if unlock_main_key(&stored, new)?.reveal() != new_main_key.reveal() {
+                return Err(WalletStorageError::KeyRotationError(
+                    "The new passphrase does not unlock the new key".into(),
+                ));
+            }
+            Ok(num_updated)
+        })?;
+
+        *cipher = new_cipher;
+        info!(
+            target: LOG_TARGET,
+            "Rotated the database encryption key, {} values were re-encrypted", num_updated
+        );
+        Ok(num_updated)
+    }
+
     fn create_burnt_proof(
         &self,
         id: u32,
@@ -728,6 +759,114 @@ impl WalletBackend for WalletSqliteDatabase {
     }
 }
 
+/// Check the passphrase against the stored key-related data and use it to decrypt the main key
+fn unlock_main_key(
+    data: &DatabaseEncryptionFields,
+    passphrase: &SafePassword,
+) -> Result<WalletMainEncryptionKey, WalletStorageError> {
+    // Use the given version if it is valid
+    let argon2_params = Argon2Parameters::from_version(Some(data.secondary_key_version))?;
+
+    // Derive a secondary key from the passphrase and salt
+    let (secondary_key, secondary_key_hash) =
+        derive_secondary_key(passphrase, argon2_params.clone(), &data.secondary_key_salt)?;
+
+    // Attempt to decrypt the encrypted main key
+    if data.secondary_key_hash != secondary_key_hash {
+        return Err(WalletStorageError::InvalidPassphrase);
+    }
+    decrypt_main_key(&secondary_key, &data.encrypted_main_key, argon2_params.id)
+}
+
+/// Produce the key-related data that protects the main key with the passphrase, using the most recent `Argon2`
+/// parameters and a fresh salt
+fn lock_main_key(
+    main_key: &WalletMainEncryptionKey,
+    passphrase: &SafePassword,
+) -> Result<DatabaseEncryptionFields, WalletStorageError> {
+    let argon2_params = Argon2Parameters::from_version(None)?;
+
+    // Derive a secondary key from the passphrase and a fresh salt
+    let secondary_key_salt = SaltString::generate(&mut OsRng).to_string();
+    let (secondary_key, secondary_key_hash) =
+        derive_secondary_key(passphrase, argon2_params.clone(), &secondary_key_salt)?;
+
+    // Encrypt the main key with the secondary key
+    let encrypted_main_key = encrypt_main_key(&secondary_key, main_key, argon2_params.id)?;
+
+    Ok(DatabaseEncryptionFields {
+        secondary_key_version: argon2_params.id,
+        secondary_key_salt,
+        secondary_key_hash,
+        encrypted_main_key,
+    })
+}
+
+/// Re-encrypt the encrypted wallet settings, client key-values and burnt proofs from `old_cipher` to `new_cipher`,
+/// returning the number of values re-encrypted
+fn reencrypt_wallet_data(
+    conn: &mut SqliteConnection,
+    old_cipher: &XChaCha20Poly1305,
+    new_cipher: &XChaCha20Poly1305,
+) -> Result<usize, WalletStorageError> {
+    let mut num_updated = 0;
+    for (key, domain) in [(DbKey::MasterSeed, MASTER_SEED_DOMAIN), (DbKey::TorId, TOR_ID_DOMAIN)] {
+        if let Some(value) = WalletSettingSql::get(&key, conn)? {
+            let plaintext = Hidden::hide(
+                decrypt_bytes_integral_nonce(old_cipher, domain.to_vec(), &from_hex(&value)?)
+                    .map_err(|e| WalletStorageError::AeadError(format!("Decryption Error:{}", e)))?,
+            );
+            let ciphertext = encrypt_bytes_integral_nonce(new_cipher, domain.to_vec(), plaintext)
+                .map_err(|e| WalletStorageError::AeadError(format!("Encryption Error:{}", e)))?;
+            WalletSettingSql::new(key, ciphertext.to_hex()).set(conn)?;
+            num_updated += 1;
+        }
+    }
+    for client_key_value in ClientKeyValueSql::index(conn)? {
+        client_key_value
+            .decrypt(old_cipher)
+            .and_then(|kv| kv.encrypt(new_cipher))
+            .map_err(WalletStorageError::AeadError)?
+            .set(conn)?;
+        num_updated += 1;
+    }
+    for burnt_proof in BurntProofSql::index(conn)? {
+        burnt_proof
+            .decrypt(old_cipher)
+            .and_then(|p| p.encrypt(new_cipher))
+            .map_err(WalletStorageError::AeadError)?
+            .update_payload(conn)?;
+        num_updated += 1;
+    }
+    Ok(num_updated)
+}
+
+/// Check that the encrypted wallet settings, client key-values and burnt proofs decrypt with `cipher`, returning the
+/// number of values checked
+fn verify_wallet_data(conn: &mut SqliteConnection, cipher: &XChaCha20Poly1305) -> Result<usize, WalletStorageError> {
+    let mut num_verified = 0;
+    for (key, domain) in [(DbKey::MasterSeed, MASTER_SEED_DOMAIN), (DbKey::TorId, TOR_ID_DOMAIN)] {
+        if let Some(value) = WalletSettingSql::get(&key, conn)? {
+            let _plaintext = Hidden::hide(
+                decrypt_bytes_integral_nonce(cipher, domain.to_vec(), &from_hex(&value)?)
+                    .map_err(|e| WalletStorageError::AeadError(format!("Decryption Error:{}", e)))?,
+            );
+            num_verified += 1;
+        }
+    }
+    for client_key_value in ClientKeyValueSql::index(conn)? {
+        client_key_value
+            .decrypt(cipher)
+            .map_err(WalletStorageError::AeadError)?;
+        num_verified += 1;
+    }
+    for burnt_proof in BurntProofSql::index(conn)? {
+        burnt_proof.decrypt(cipher).map_err(WalletStorageError::AeadError)?;
+        num_verified += 1;
+    }
+    Ok(num_verified)
+}
+
 /// Derive a secondary database key and associated commitment
 fn derive_secondary_key(
     passphrase: &SafePassword,
@@ -920,7 +1059,6 @@ impl ClientKeyValueSql {
         client_kv.encrypt(cipher).map_err(WalletStorageError::AeadError)
     }
 
-    #[allow(dead_code)]
     pub fn index(conn: &mut SqliteConnection) -> Result<Vec<Self>, WalletStorageError> {
         Ok(client_key_values::table.load::<ClientKeyValueSql>(conn)?)
     }
@@ -1046,6 +1184,14 @@ impl BurntProofSql {
         let num_deleted = diesel::delete(burnt_proofs::table.filter(burnt_proofs::id.eq(id as i32))).execute(conn)?;
         Ok(num_deleted > 0)
     }
+
+    /// Write the payload of this instance back to the database, used when the payload is re-encrypted
+    pub fn update_payload(&self, conn: &mut SqliteConnection) -> Result<(), WalletStorageError> {
+        diesel::update(burnt_proofs::table.filter(burnt_proofs::id.eq(self.id)))
+            .set(burnt_proofs::payload.eq(&self.payload))
+            .execute(conn)?;
+        Ok(())
+    }
 }
 
 impl Encryptable<XChaCha20Poly1305> for BurntProofSql {
@@ -1092,9 +1238,30 @@ impl Encryptable<XChaCha20Poly1305> for BurntProofSql {
 
 #[cfg(test)]
 mod test {
+    use chrono::Utc;
+    use rand::rngs::OsRng;
     use tari_common_sqlite::sqlite_connection_pool::PooledDbConnection;
-    use tari_common_types::encryption::{decrypt_bytes_integral_nonce, Encryptable};
-    use tari_key_manager::cipher_seed::CipherSeed;
+    use tari_common_types::{
+        encryption::{decrypt_bytes_integral_nonce, Encryptable},
+        tari_address::TariAddress,
+        transaction::{TransactionDirection, TransactionStatus, TxId},
+        types::{PrivateKey, PublicKey},
+    };
+    use tari_core::transactions::{
+        key_manager::create_memory_db_key_manager,
+        tari_amount::MicroMinotari,
+        test_helpers::{create_wallet_output_with_data, TestParams},
+        transaction_components::{OutputFeatures, Transaction},
+    };
+    use tari_crypto::keys::{PublicKey as PublicKeyTrait, SecretKey as SecretKeyTrait};
+    use tari_key_manager::{
+        cipher_seed::CipherSeed,
+        key_manager_service::storage::{
+            database::{KeyManagerBackend, KeyManagerState},
+            sqlite_db::{verify_key_manager_data, KeyManagerSqliteDatabase},
+        },
+    };
+    use tari_script::script;
     use tari_test_utils::random::string;
     use tari_utilities::{
         hex::{from_hex, Hex},
@@ -1103,10 +1270,23 @@ mod test {
     };
     use tempfile::tempdir;
 
-    use crate::storage::{
-        database::{DbKey, DbValue, WalletBackend},
-        sqlite_db::wallet::{ClientKeyValueSql, WalletSettingSql, WalletSqliteDatabase},
-        sqlite_utilities::run_migration_and_create_sqlite_connection,
+    use crate::{
+        output_manager_service::storage::{
+            database::OutputManagerDatabase,
+            models::DbWalletOutput,
+            sqlite_db::OutputManagerSqliteDatabase,
+            OutputSource,
+        },
+        storage::{
+            database::{DbKey, DbValue, WalletBackend},
+            sqlite_db::wallet::{ClientKeyValueSql, WalletSettingSql, WalletSqliteDatabase},
+            sqlite_utilities::run_migration_and_create_sqlite_connection,
+        },
+        transaction_service::storage::{
+            database::TransactionDatabase,
+            models::CompletedTransaction,
+            sqlite_db::{verify_transactions_encryption, TransactionServiceSqliteDatabase},
+        },
     };
     #[test]
     fn test_passphrase() {
@@ -1342,4 +1522,148 @@ mod test {
 
         assert_eq!(decrypted_db_seed, seed_bytes);
     }
+
+    #[tokio::test]
+    #[allow(clippy::too_many_lines)]
+    async fn test_rotate_encryption_key() {
+        let db_name = format!("{}.sqlite3", string(8).as_str());
+        let db_tempdir = tempdir().unwrap();
+        let db_folder = db_tempdir.path().to_str().unwrap().to_string();
+        let connection = run_migration_and_create_sqlite_connection(format!("{}{}", db_folder, db_name), 16).unwrap();
+        let mut conn = connection.get_pooled_connection().unwrap();
+
+        let db = WalletSqliteDatabase::new(connection.clone(), "passphrase".to_string().into()).unwrap();
+        let old_cipher = db.cipher();
+        // The key manager tables share the database and must be migrated as well
+        let key_manager_db = KeyManagerSqliteDatabase::init(connection.clone(), old_cipher.clone());
+
+        let seed = CipherSeed::new();
+        db.set_master_seed(&seed, &mut conn).unwrap();
+        ClientKeyValueSql::new("key1".to_string(), "value1".to_string(), &old_cipher)
+            .unwrap()
+            .set(&mut conn)
+            .unwrap();
+
+        // A completed transaction, a key manager state and an imported key
+        let transaction_db = TransactionDatabase::new(TransactionServiceSqliteDatabase::new(
+            connection.clone(),
+            old_cipher.clone(),
+        ));
+        let transaction = CompletedTransaction::new(
+            TxId::from(1u64),
+            TariAddress::default(),
+            TariAddress::default(),
+            MicroMinotari::from(1000),
+            MicroMinotari::from(10),
+            Transaction::new(
+                Vec::new(),
+                Vec::new(),
+                Vec::new(),
+                PrivateKey::random(&mut OsRng),
+                PrivateKey::random(&mut OsRng),
+            ),
+            TransactionStatus::Completed,
+            "message".to_string(),
+            Utc::now().naive_utc(),
+            TransactionDirection::Inbound,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        transaction_db
+            .insert_completed_transaction(TxId::from(1u64), transaction.clone())
+            .unwrap();
+        KeyManagerBackend::<PublicKey>::add_key_manager(&key_manager_db, KeyManagerState {
+            branch_seed: "branch".to_string(),
+            primary_key_index: 3,
+        })
+        .unwrap();
+        let (imported_private_key, imported_public_key) = PublicKey::random_keypair(&mut OsRng);
+        key_manager_db
+            .insert_imported_key(imported_public_key.clone(), imported_private_key.clone())
+            .unwrap();
+
+        // Outputs only refer to their keys by id, so they are stored unencrypted and must survive the rotation as they
+        // are
+        let key_manager = create_memory_db_key_manager().unwrap();
+        let test_params = TestParams::new(&key_manager).await;
+        let wallet_output = create_wallet_output_with_data(
+            script!(Nop).unwrap(),
+            OutputFeatures::default(),
+            &test_params,
+            MicroMinotari::from(5000),
+            &key_manager,
+        )
+        .await
+        .unwrap();
+        let output =
+            DbWalletOutput::from_wallet_output(wallet_output, &key_manager, None, OutputSource::Standard, None, None)
+                .await
+                .unwrap();
+        let output_db = OutputManagerDatabase::new(OutputManagerSqliteDatabase::new(connection.clone()));
+        output_db.add_unspent_output(output.clone()).unwrap();
+
+        // A wrong passphrase is rejected and nothing changes
+        assert!(db
+            .rotate_encryption_key(
+                &"evil passphrase".to_string().into(),
+                &"new passphrase".to_string().into()
+            )
+            .is_err());
+        assert!(WalletSqliteDatabase::new(connection.clone(), "passphrase".to_string().into()).is_ok());
+        assert_eq!(verify_transactions_encryption(&mut conn, &old_cipher).unwrap(), 1);
+        assert_eq!(verify_key_manager_data(&mut conn, &old_cipher).unwrap(), 2);
+
+        // The master seed, the client key-value, the transaction, the key manager state and the imported key are
+        // re-encrypted
+        let num_updated = db
+            .rotate_encryption_key(&"passphrase".to_string().into(), &"new passphrase".to_string().into())
+            .unwrap();
+        assert_eq!(num_updated, 5);
+
+        // Only the new passphrase opens the database
+        assert!(WalletSqliteDatabase::new(connection.clone(), "passphrase".to_string().into()).is_err());
+        let reopened = WalletSqliteDatabase::new(connection.clone(), "new passphrase".to_string().into()).unwrap();
+        let new_cipher = reopened.cipher();
+
+        // The data can no longer be decrypted with the old key, but can with the new one
+        let ckv = ClientKeyValueSql::get("key1", &mut conn).unwrap().unwrap();
+        assert!(ckv.clone().decrypt(&old_cipher).is_err());
+        assert_eq!(ckv.decrypt(&new_cipher).unwrap().value, "value1");
+        match reopened.fetch(&DbKey::MasterSeed).unwrap().unwrap() {
+            DbValue::MasterSeed(read_seed) => assert_eq!(read_seed, seed),
+            _ => panic!("Should be the master seed"),
+        }
+
+        assert!(verify_transactions_encryption(&mut conn, &old_cipher).is_err());
+        assert_eq!(verify_transactions_encryption(&mut conn, &new_cipher).unwrap(), 1);
+        let transaction_db = TransactionDatabase::new(TransactionServiceSqliteDatabase::new(
+            connection.clone(),
+            new_cipher.clone(),
+        ));
+        let read_transaction = transaction_db.get_completed_transaction(TxId::from(1u64)).unwrap();
+        assert_eq!(read_transaction.transaction, transaction.transaction);
+
+        assert!(verify_key_manager_data(&mut conn, &old_cipher).is_err());
+        assert_eq!(verify_key_manager_data(&mut conn, &new_cipher).unwrap(), 2);
+        let key_manager_db = KeyManagerSqliteDatabase::init(connection.clone(), new_cipher);
+        assert_eq!(
+            KeyManagerBackend::<PublicKey>::get_key_manager(&key_manager_db, "branch")
+                .unwrap()
+                .unwrap()
+                .primary_key_index,
+            3
+        );
+        assert_eq!(
+            key_manager_db.get_imported_key(&imported_public_key).unwrap(),
+            imported_private_key
+        );
+
+        let read_outputs = OutputManagerDatabase::new(OutputManagerSqliteDatabase::new(connection))
+            .fetch_all_unspent_outputs()
+            .unwrap();
+        assert_eq!(read_outputs.len(), 1);
+        assert_eq!(read_outputs[0].commitment, output.commitment);
+    }
 }
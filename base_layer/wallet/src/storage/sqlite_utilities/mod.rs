@@ -35,7 +35,7 @@ use crate::{
     error::WalletStorageError,
     output_manager_service::storage::sqlite_db::OutputManagerSqliteDatabase,
     storage::{
-        database::DbKey,
+        database::{DbKey, WalletBackend},
         sqlite_db::wallet::{WalletSettingSql, WalletSqliteDatabase},
     },
     transaction_service::storage::sqlite_db::TransactionServiceSqliteDatabase,
@@ -164,6 +164,19 @@ pub fn initialize_sqlite_database_backends<P: AsRef<Path>>(
     ))
 }
 
+/// Change the passphrase of the wallet database at `db_path`, re-encrypting all encrypted data under a new key. The
+/// exclusive file lock is held throughout, so this fails if a wallet is running on the database. Returns the number of
+/// values re-encrypted.
+pub fn change_wallet_passphrase<P: AsRef<Path>>(
+    db_path: P,
+    existing: SafePassword,
+    new: &SafePassword,
+) -> Result<usize, WalletStorageError> {
+    // This also migrates the key manager tables, so that every encrypted table exists before it is re-encrypted
+    let (wallet_backend, ..) = initialize_sqlite_database_backends(db_path, existing.clone(), 1)?;
+    wallet_backend.rotate_encryption_key(&existing, new)
+}
+
 pub fn get_last_version<P: AsRef<Path>>(db_path: P) -> Result<Option<String>, WalletStorageError> {
     let path_str = db_path
         .as_ref()
@@ -69,6 +69,9 @@ pub struct TransactionServiceConfig {
     /// This is the timeout period that will be used to re-submit transactions not found in the mempool
     #[serde(with = "serializers::seconds")]
     pub transaction_mempool_resubmission_window: Duration,
+    /// This is the interval at which the wallet checks for scheduled payments that are due to be sent
+    #[serde(with = "serializers::seconds")]
+    pub scheduled_payment_check_interval: Duration,
 }
 
 impl Default for TransactionServiceConfig {
@@ -87,6 +90,7 @@ impl Default for TransactionServiceConfig {
             transaction_routing_mechanism: TransactionRoutingMechanism::default(),
             transaction_event_channel_size: 1000,
             transaction_mempool_resubmission_window: Duration::from_secs(600),
+            scheduled_payment_check_interval: Duration::from_secs(60),
         }
     }
 }
@@ -33,7 +33,7 @@ use tari_comms::{connectivity::ConnectivityError, peer_manager::node_id::NodeIdE
 use tari_comms_dht::outbound::DhtOutboundError;
 use tari_core::transactions::{
     transaction_components::{EncryptedDataError, TransactionError},
-    transaction_protocol::TransactionProtocolError,
+    transaction_protocol::{partially_signed::PartiallySignedTransactionError, TransactionProtocolError},
 };
 use tari_crypto::{errors::RangeProofError, signatures::CommitmentSignatureError};
 use tari_key_manager::key_manager_service::KeyManagerServiceError;
@@ -48,9 +48,11 @@ use crate::{
     error::WalletStorageError,
     output_manager_service::error::OutputManagerError,
     transaction_service::{
+        payment_request::PaymentRequestError,
         storage::{database::DbKey, sqlite_db::CompletedTransactionConversionError},
         utc::NegativeDurationError,
     },
+    util::cron::CronScheduleError,
 };
 
 #[derive(Debug, Error)]
@@ -196,8 +198,26 @@ pub enum TransactionServiceError {
     InvalidAddress(String),
     #[error("Transaction is not supported: `{0}`")]
     NotSupported(String),
+    #[error("Watch-only wallets cannot sign transactions: `{0}`")]
+    WatchOnlyWallet(String),
+    #[error("Transaction fee cannot be bumped: `{0}`")]
+    FeeBumpNotPossible(String),
     #[error("Tari script error: {0}")]
     ScriptError(#[from] ScriptError),
+    #[error("Partially signed transaction error: {0}")]
+    PartiallySignedTransactionError(#[from] PartiallySignedTransactionError),
+    #[error("Transaction {0} is not awaiting an offline signature")]
+    NotAwaitingSignature(TxId),
+    #[error("Invalid payment schedule: {0}")]
+    InvalidPaymentSchedule(String),
+    #[error("Cron schedule error: {0}")]
+    CronScheduleError(#[from] CronScheduleError),
+    #[error("Invalid batch payment: {0}")]
+    InvalidBatchPayment(String),
+    #[error("Invalid payment request: {0}")]
+    InvalidPaymentRequest(String),
+    #[error("Payment request error: {0}")]
+    PaymentRequestError(#[from] PaymentRequestError),
 }
 
 impl From<RangeProofError> for TransactionServiceError {
@@ -284,6 +304,10 @@ pub enum TransactionStorageError {
     SqliteStorageError(#[from] SqliteStorageError),
     #[error("Coinbase transactions are not supported in the wallet")]
     CoinbaseNotSupported,
+    #[error("Scheduled payment {0} not found")]
+    ScheduledPaymentNotFound(u64),
+    #[error("Payment request {0} not found")]
+    PaymentRequestNotFound(u64),
 }
 
 impl From<ByteArrayError> for TransactionStorageError {
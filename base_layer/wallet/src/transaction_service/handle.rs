@@ -33,10 +33,11 @@ use tari_common_types::{
     tari_address::TariAddress,
     transaction::{ImportStatus, TxId},
     types::{FixedHash, HashOutput, PrivateKey, PublicKey, Signature},
+    wallet_types::AccountId,
 };
 use tari_comms::types::CommsPublicKey;
 use tari_core::{
-    mempool::FeePerGramStat,
+    mempool::{FeeEstimate, FeePerGramStat},
     proto,
     transactions::{
         tari_amount::MicroMinotari,
@@ -49,6 +50,7 @@ use tari_core::{
             Transaction,
             TransactionOutput,
         },
+        transaction_protocol::partially_signed::{PartiallySignedTransaction, PaymentKind},
     },
 };
 use tari_crypto::ristretto::pedersen::PedersenCommitment;
@@ -64,9 +66,16 @@ use crate::{
     transaction_service::{
         error::TransactionServiceError,
         storage::models::{
+            BatchPaymentRecipient,
             CompletedTransaction,
             InboundTransaction,
+            IssuedPaymentRequest,
             OutboundTransaction,
+            PaymentSchedule,
+            ScheduledPayment,
+            ScheduledPaymentRun,
+            TransactionHistoryFilter,
+            TransactionHistoryRecord,
             TxCancellationReason,
             WalletTransaction,
         },
@@ -84,6 +93,7 @@ pub enum TransactionServiceRequest {
     GetCancelledPendingInboundTransactions,
     GetCancelledPendingOutboundTransactions,
     GetCancelledCompletedTransactions,
+    GetTransactionHistory(TransactionHistoryFilter),
     GetCompletedTransaction(TxId),
     GetAnyTransaction(TxId),
     ImportTransaction(WalletTransaction),
@@ -157,6 +167,13 @@ pub enum TransactionServiceRequest {
         message: String,
         payment_id: PaymentId,
     },
+    SendBatchTransaction {
+        recipients: Vec<BatchPaymentRecipient>,
+        selection_criteria: UtxoSelectionCriteria,
+        fee_per_gram: MicroMinotari,
+        message: String,
+    },
+    GetBatchPaymentRecipients(TxId),
     SendOneSidedToStealthAddressTransaction {
         destination: TariAddress,
         amount: MicroMinotari,
@@ -170,8 +187,47 @@ pub enum TransactionServiceRequest {
         destination: TariAddress,
         fee_per_gram: MicroMinotari,
     },
+    /// Builds a transaction that is signed by another wallet holding the spend key. Interactive transactions are
+    /// negotiated with the recipient before they can be exported for signing.
+    CreatePartiallySignedTransaction {
+        destination: TariAddress,
+        amount: MicroMinotari,
+        selection_criteria: UtxoSelectionCriteria,
+        fee_per_gram: MicroMinotari,
+        message: String,
+        payment_id: PaymentId,
+        kind: PaymentKind,
+    },
+    GetPartiallySignedTransaction(TxId),
+    SignPartiallySignedTransaction(Box<PartiallySignedTransaction>),
+    SubmitSignedTransaction(Box<PartiallySignedTransaction>),
     SendShaAtomicSwapTransaction(TariAddress, MicroMinotari, UtxoSelectionCriteria, MicroMinotari, String),
     CancelTransaction(TxId),
+    /// Replaces an unconfirmed outbound transaction with one spending the same inputs at a higher fee per gram
+    BumpFee {
+        tx_id: TxId,
+        fee_per_gram: MicroMinotari,
+    },
+    /// Stores a payment that is sent once it is due, provided that the wallet is online and has the funds
+    CreateScheduledPayment {
+        destination: TariAddress,
+        amount: MicroMinotari,
+        fee_per_gram: MicroMinotari,
+        message: String,
+        kind: PaymentKind,
+        schedule: PaymentSchedule,
+        account: Option<AccountId>,
+    },
+    GetScheduledPayments,
+    GetScheduledPaymentRuns(u64),
+    CancelScheduledPayment(u64),
+    CreatePaymentRequest {
+        amount: MicroMinotari,
+        memo: String,
+        payment_id: Option<PaymentId>,
+        expires_at: Option<NaiveDateTime>,
+    },
+    GetPaymentRequests,
     ImportUtxoWithStatus {
         amount: MicroMinotari,
         source_address: TariAddress,
@@ -196,6 +252,35 @@ pub enum TransactionServiceRequest {
     GetFeePerGramStatsPerBlock {
         count: usize,
     },
+    /// Returns the base node's recommended fee per gram for confirmation within each of the target number of blocks.
+    EstimateFee {
+        target_blocks: Vec<u64>,
+    },
+}
+
+impl TransactionServiceRequest {
+    /// Returns true if handling the request signs a transaction with the wallet's spend key, which watch-only wallets
+    /// do not hold
+    pub fn requires_spend_key(&self) -> bool {
+        matches!(
+            self,
+            Self::SendTransaction { .. } |
+                Self::BurnTari { .. } |
+                Self::EncumberAggregateUtxo { .. } |
+                Self::SpendBackupPreMineUtxo { .. } |
+                Self::FinalizeSentAggregateTransaction { .. } |
+                Self::RegisterValidatorNode { .. } |
+                Self::RegisterCodeTemplate { .. } |
+                Self::SendOneSidedTransaction { .. } |
+                Self::SendOneSidedToStealthAddressTransaction { .. } |
+                Self::SendBatchTransaction { .. } |
+                Self::ScrapeWallet { .. } |
+                Self::SignPartiallySignedTransaction(_) |
+                Self::SendShaAtomicSwapTransaction(..) |
+                Self::BumpFee { .. } |
+                Self::CreateScheduledPayment { .. }
+        )
+    }
 }
 
 impl fmt::Display for TransactionServiceRequest {
@@ -209,6 +294,7 @@ impl fmt::Display for TransactionServiceRequest {
             Self::GetCancelledPendingInboundTransactions => write!(f, "GetCancelledPendingInboundTransactions"),
             Self::GetCancelledPendingOutboundTransactions => write!(f, "GetCancelledPendingOutboundTransactions"),
             Self::GetCancelledCompletedTransactions => write!(f, "GetCancelledCompletedTransactions"),
+            Self::GetTransactionHistory(filter) => write!(f, "GetTransactionHistory({:?})", filter),
             Self::GetCompletedTransaction(t) => write!(f, "GetCompletedTransaction({})", t),
             Self::ScrapeWallet {
                 destination,
@@ -335,6 +421,16 @@ impl fmt::Display for TransactionServiceRequest {
                 "SendOneSidedTransaction (to {}, {}, {})",
                 destination, amount, message
             ),
+            Self::SendBatchTransaction {
+                recipients, message, ..
+            } => write!(
+                f,
+                "SendBatchTransaction (to {} recipients, {}, {})",
+                recipients.len(),
+                recipients.iter().map(|r| r.amount).sum::<MicroMinotari>(),
+                message
+            ),
+            Self::GetBatchPaymentRecipients(tx_id) => write!(f, "GetBatchPaymentRecipients ({})", tx_id),
             Self::SendOneSidedToStealthAddressTransaction {
                 destination,
                 amount,
@@ -345,10 +441,48 @@ impl fmt::Display for TransactionServiceRequest {
                 "SendOneSidedToStealthAddressTransaction (to {}, {}, {})",
                 destination, amount, message
             ),
+            Self::CreatePartiallySignedTransaction {
+                destination,
+                amount,
+                kind,
+                ..
+            } => write!(
+                f,
+                "CreatePartiallySignedTransaction ({} to {}, {})",
+                kind, destination, amount
+            ),
+            Self::GetPartiallySignedTransaction(t) => write!(f, "GetPartiallySignedTransaction ({})", t),
+            Self::SignPartiallySignedTransaction(tx) => write!(f, "SignPartiallySignedTransaction ({})", tx.tx_id),
+            Self::SubmitSignedTransaction(tx) => write!(f, "SubmitSignedTransaction ({})", tx.tx_id),
             Self::SendShaAtomicSwapTransaction(k, _, v, _, msg) => {
                 write!(f, "SendShaAtomicSwapTransaction (to {}, {}, {})", k, v, msg)
             },
             Self::CancelTransaction(t) => write!(f, "CancelTransaction ({})", t),
+            Self::BumpFee { tx_id, fee_per_gram } => {
+                write!(f, "BumpFee (tx_id: {}, fee_per_gram: {})", tx_id, fee_per_gram)
+            },
+            Self::CreateScheduledPayment {
+                destination,
+                amount,
+                kind,
+                schedule,
+                ..
+            } => write!(
+                f,
+                "CreateScheduledPayment ({} to {}, {}, {})",
+                kind, destination, amount, schedule
+            ),
+            Self::GetScheduledPayments => write!(f, "GetScheduledPayments"),
+            Self::GetScheduledPaymentRuns(id) => write!(f, "GetScheduledPaymentRuns ({})", id),
+            Self::CancelScheduledPayment(id) => write!(f, "CancelScheduledPayment ({})", id),
+            Self::CreatePaymentRequest { amount, expires_at, .. } => {
+                write!(
+                    f,
+                    "CreatePaymentRequest (amount: {}, expires_at: {:?})",
+                    amount, expires_at
+                )
+            },
+            Self::GetPaymentRequests => write!(f, "GetPaymentRequests"),
             Self::ImportUtxoWithStatus {
                 amount,
                 source_address,
@@ -377,6 +511,7 @@ impl fmt::Display for TransactionServiceRequest {
             Self::GetFeePerGramStatsPerBlock { count } => {
                 write!(f, "GetFeePerGramEstimatesPerBlock(count: {})", count,)
             },
+            Self::EstimateFee { target_blocks } => write!(f, "EstimateFee(target_blocks: {:?})", target_blocks),
             TransactionServiceRequest::RegisterCodeTemplate { template_name, .. } => {
                 write!(f, "RegisterCodeTemplate: {}", template_name)
             },
@@ -411,6 +546,7 @@ pub enum TransactionServiceResponse {
     PendingInboundTransactions(HashMap<TxId, InboundTransaction>),
     PendingOutboundTransactions(HashMap<TxId, OutboundTransaction>),
     CompletedTransactions(HashMap<TxId, CompletedTransaction>),
+    TransactionHistory(Vec<TransactionHistoryRecord>),
     CompletedTransaction(Box<CompletedTransaction>),
     BaseNodePublicKeySet,
     UtxoImported(TxId),
@@ -425,6 +561,15 @@ pub enum TransactionServiceResponse {
     CompletedTransactionValidityChanged,
     ShaAtomicSwapTransactionSent(Box<(TxId, PublicKey, TransactionOutput)>),
     FeePerGramStatsPerBlock(FeePerGramStatsResponse),
+    FeeEstimates(Vec<FeeEstimate>),
+    PartiallySignedTransaction(Box<PartiallySignedTransaction>),
+    ScheduledPayment(Box<ScheduledPayment>),
+    ScheduledPayments(Vec<ScheduledPayment>),
+    ScheduledPaymentRuns(Vec<ScheduledPaymentRun>),
+    ScheduledPaymentCancelled,
+    BatchPaymentRecipients(Vec<BatchPaymentRecipient>),
+    PaymentRequest(Box<IssuedPaymentRequest>),
+    PaymentRequests(Vec<IssuedPaymentRequest>),
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq, Default)]
@@ -731,6 +876,45 @@ impl TransactionServiceHandle {
         }
     }
 
+    /// Pays all of the recipients with one-sided outputs in a single transaction that has one change output. Every
+    /// recipient output carries its own payment id and encrypted data.
+    pub async fn send_batch_transaction(
+        &mut self,
+        recipients: Vec<BatchPaymentRecipient>,
+        selection_criteria: UtxoSelectionCriteria,
+        fee_per_gram: MicroMinotari,
+        message: String,
+    ) -> Result<TxId, TransactionServiceError> {
+        match self
+            .handle
+            .call(TransactionServiceRequest::SendBatchTransaction {
+                recipients,
+                selection_criteria,
+                fee_per_gram,
+                message,
+            })
+            .await??
+        {
+            TransactionServiceResponse::TransactionSent(tx_id) => Ok(tx_id),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Returns every recipient paid by a batch payment transaction, in the order they were paid
+    pub async fn get_batch_payment_recipients(
+        &mut self,
+        tx_id: TxId,
+    ) -> Result<Vec<BatchPaymentRecipient>, TransactionServiceError> {
+        match self
+            .handle
+            .call(TransactionServiceRequest::GetBatchPaymentRecipients(tx_id))
+            .await??
+        {
+            TransactionServiceResponse::BatchPaymentRecipients(recipients) => Ok(recipients),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
     /// Burns the given amount of Tari from the wallet
     pub async fn burn_tari(
         &mut self,
@@ -891,6 +1075,82 @@ impl TransactionServiceHandle {
         }
     }
 
+    /// Builds a transaction to be signed by another wallet holding the spend key. The inputs remain encumbered until
+    /// the signed transaction is submitted or the transaction is cancelled.
+    pub async fn create_partially_signed_transaction(
+        &mut self,
+        destination: TariAddress,
+        amount: MicroMinotari,
+        selection_criteria: UtxoSelectionCriteria,
+        fee_per_gram: MicroMinotari,
+        message: String,
+        payment_id: PaymentId,
+        kind: PaymentKind,
+    ) -> Result<TxId, TransactionServiceError> {
+        match self
+            .handle
+            .call(TransactionServiceRequest::CreatePartiallySignedTransaction {
+                destination,
+                amount,
+                selection_criteria,
+                fee_per_gram,
+                message,
+                payment_id,
+                kind,
+            })
+            .await??
+        {
+            TransactionServiceResponse::TransactionSent(tx_id) => Ok(tx_id),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Exports a pending transaction that is ready to be signed. Interactive transactions are only ready once the
+    /// recipient has replied.
+    pub async fn get_partially_signed_transaction(
+        &mut self,
+        tx_id: TxId,
+    ) -> Result<PartiallySignedTransaction, TransactionServiceError> {
+        match self
+            .handle
+            .call(TransactionServiceRequest::GetPartiallySignedTransaction(tx_id))
+            .await??
+        {
+            TransactionServiceResponse::PartiallySignedTransaction(tx) => Ok(*tx),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Signs a transaction created by another wallet derived from the same seed
+    pub async fn sign_partially_signed_transaction(
+        &mut self,
+        tx: PartiallySignedTransaction,
+    ) -> Result<PartiallySignedTransaction, TransactionServiceError> {
+        match self
+            .handle
+            .call(TransactionServiceRequest::SignPartiallySignedTransaction(Box::new(tx)))
+            .await??
+        {
+            TransactionServiceResponse::PartiallySignedTransaction(tx) => Ok(*tx),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Completes a pending transaction with its signed counterpart and broadcasts it
+    pub async fn submit_signed_transaction(
+        &mut self,
+        tx: PartiallySignedTransaction,
+    ) -> Result<(), TransactionServiceError> {
+        match self
+            .handle
+            .call(TransactionServiceRequest::SubmitSignedTransaction(Box::new(tx)))
+            .await??
+        {
+            TransactionServiceResponse::TransactionSubmitted => Ok(()),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
     pub async fn cancel_transaction(&mut self, tx_id: TxId) -> Result<(), TransactionServiceError> {
         match self
             .handle
@@ -902,6 +1162,129 @@ impl TransactionServiceHandle {
         }
     }
 
+    /// Replaces an unconfirmed outbound transaction with a one-sided transaction to the same destination that spends
+    /// the same inputs at the higher `fee_per_gram`, so that base nodes replace the original in their mempools. The
+    /// original transaction is cancelled and the id of the replacement is returned.
+    pub async fn bump_fee(
+        &mut self,
+        tx_id: TxId,
+        fee_per_gram: MicroMinotari,
+    ) -> Result<TxId, TransactionServiceError> {
+        match self
+            .handle
+            .call(TransactionServiceRequest::BumpFee { tx_id, fee_per_gram })
+            .await??
+        {
+            TransactionServiceResponse::TransactionSent(tx_id) => Ok(tx_id),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Schedules a payment that is sent when it falls due, provided that the wallet is online and the available balance
+    /// of the account covers the amount and fee. Payments that fall due while the wallet is offline are sent once, when
+    /// it next comes online. The payment is spent from all accounts if `account` is `None`.
+    pub async fn create_scheduled_payment(
+        &mut self,
+        destination: TariAddress,
+        amount: MicroMinotari,
+        fee_per_gram: MicroMinotari,
+        message: String,
+        kind: PaymentKind,
+        schedule: PaymentSchedule,
+        account: Option<AccountId>,
+    ) -> Result<ScheduledPayment, TransactionServiceError> {
+        match self
+            .handle
+            .call(TransactionServiceRequest::CreateScheduledPayment {
+                destination,
+                amount,
+                fee_per_gram,
+                message,
+                kind,
+                schedule,
+                account,
+            })
+            .await??
+        {
+            TransactionServiceResponse::ScheduledPayment(payment) => Ok(*payment),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    pub async fn get_scheduled_payments(&mut self) -> Result<Vec<ScheduledPayment>, TransactionServiceError> {
+        match self
+            .handle
+            .call(TransactionServiceRequest::GetScheduledPayments)
+            .await??
+        {
+            TransactionServiceResponse::ScheduledPayments(payments) => Ok(payments),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Returns the outcome of every attempt to send the scheduled payment, oldest first
+    pub async fn get_scheduled_payment_runs(
+        &mut self,
+        id: u64,
+    ) -> Result<Vec<ScheduledPaymentRun>, TransactionServiceError> {
+        match self
+            .handle
+            .call(TransactionServiceRequest::GetScheduledPaymentRuns(id))
+            .await??
+        {
+            TransactionServiceResponse::ScheduledPaymentRuns(runs) => Ok(runs),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Stops a scheduled payment from being sent again. Transactions already sent for it are not affected.
+    pub async fn cancel_scheduled_payment(&mut self, id: u64) -> Result<(), TransactionServiceError> {
+        match self
+            .handle
+            .call(TransactionServiceRequest::CancelScheduledPayment(id))
+            .await??
+        {
+            TransactionServiceResponse::ScheduledPaymentCancelled => Ok(()),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Issues a payment request for `amount` to the one-sided address of this wallet, signed with its spend key. A
+    /// random payment id is used unless one is given.
+    pub async fn create_payment_request(
+        &mut self,
+        amount: MicroMinotari,
+        memo: String,
+        payment_id: Option<PaymentId>,
+        expires_at: Option<NaiveDateTime>,
+    ) -> Result<IssuedPaymentRequest, TransactionServiceError> {
+        match self
+            .handle
+            .call(TransactionServiceRequest::CreatePaymentRequest {
+                amount,
+                memo,
+                payment_id,
+                expires_at,
+            })
+            .await??
+        {
+            TransactionServiceResponse::PaymentRequest(request) => Ok(*request),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Returns the payment requests issued by this wallet, oldest first, with their status brought up to date
+    pub async fn get_payment_requests(&mut self) -> Result<Vec<IssuedPaymentRequest>, TransactionServiceError> {
+        match self
+            .handle
+            .call(TransactionServiceRequest::GetPaymentRequests)
+            .await??
+        {
+            TransactionServiceResponse::PaymentRequests(requests) => Ok(requests),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
     pub async fn get_pending_inbound_transactions(
         &mut self,
     ) -> Result<HashMap<TxId, InboundTransaction>, TransactionServiceError> {
@@ -967,6 +1350,21 @@ impl TransactionServiceHandle {
         }
     }
 
+    /// Returns pending, completed and cancelled transactions selected by `filter`, oldest first
+    pub async fn get_transaction_history(
+        &mut self,
+        filter: TransactionHistoryFilter,
+    ) -> Result<Vec<TransactionHistoryRecord>, TransactionServiceError> {
+        match self
+            .handle
+            .call(TransactionServiceRequest::GetTransactionHistory(filter))
+            .await??
+        {
+            TransactionServiceResponse::TransactionHistory(records) => Ok(records),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
     pub async fn get_cancelled_completed_transactions(
         &mut self,
     ) -> Result<HashMap<TxId, CompletedTransaction>, TransactionServiceError> {
@@ -1196,4 +1594,17 @@ impl TransactionServiceHandle {
             _ => Err(TransactionServiceError::UnexpectedApiResponse),
         }
     }
+
+    /// Query the base node for the recommended fee per gram to be mined within each of the target number of blocks.
+    /// If no targets are given, the base node's default targets are used.
+    pub async fn estimate_fee(&mut self, target_blocks: Vec<u64>) -> Result<Vec<FeeEstimate>, TransactionServiceError> {
+        match self
+            .handle
+            .call(TransactionServiceRequest::EstimateFee { target_blocks })
+            .await??
+        {
+            TransactionServiceResponse::FeeEstimates(estimates) => Ok(estimates),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
 }
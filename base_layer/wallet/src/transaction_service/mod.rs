@@ -68,6 +68,7 @@ use crate::{
 pub mod config;
 pub mod error;
 pub mod handle;
+pub mod payment_request;
 pub mod protocols;
 pub mod service;
 pub mod storage;
@@ -0,0 +1,411 @@
+// Copyright 2024 The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+//! Signed payment requests (invoices) that a wallet hands to a payer, usually as a URI or a QR code of it.
+//!
+//! A request is signed with the private key of the spend key of the address it asks to be paid to, so anyone holding
+//! the request can check that it was issued by the owner of that address. The view key is not used, since it is shared
+//! with view-only wallets and services that scan on behalf of the wallet. The URI has the form
+//!
+//! `tari://<network>/payment_request?tariAddress=<base58>&amount=<uT>&paymentId=<hex>&memo=<text>&expiresAt=<unix
+//! seconds>&nonce=<hex>&signature=<hex>`
+//!
+//! where `memo` is percent-encoded and `memo` and `expiresAt` are omitted when not set. The payer is expected to attach
+//! the payment id to the payment so that the issuing wallet can match it to the request.
+
+use std::{fmt, str::FromStr};
+
+use blake2::Blake2b;
+use chrono::NaiveDateTime;
+use digest::consts::U64;
+use rand::rngs::OsRng;
+use tari_common_types::{
+    tari_address::TariAddress,
+    types::{PrivateKey, PublicKey, Signature},
+};
+use tari_core::transactions::{tari_amount::MicroMinotari, transaction_components::encrypted_data::PaymentId};
+use tari_crypto::{hash_domain, hashing::DomainSeparatedHasher, keys::PublicKey as PublicKeyTrait};
+use tari_utilities::{hex::Hex, ByteArray};
+use thiserror::Error;
+
+hash_domain!(
+    PaymentRequestSigningDomain,
+    "com.tari.base_layer.wallet.payment_request",
+    1
+);
+
+const URI_SCHEME: &str = "tari://";
+const URI_PATH: &str = "/payment_request";
+
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+pub enum PaymentRequestError {
+    #[error("Not a payment request URI")]
+    NotAPaymentRequest,
+    #[error("Missing `{0}` parameter")]
+    MissingField(&'static str),
+    #[error("Invalid `{field}` parameter `{value}`")]
+    InvalidField { field: &'static str, value: String },
+    #[error("The spend key does not belong to the address")]
+    SpendKeyMismatch,
+    #[error("The signature of the payment request is invalid")]
+    InvalidSignature,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaymentRequest {
+    /// The address to pay to
+    pub address: TariAddress,
+    pub amount: MicroMinotari,
+    /// The payment id the payer must attach to the payment for it to be matched to the request
+    pub payment_id: PaymentId,
+    pub memo: String,
+    /// The request should not be paid after this (UTC) time
+    pub expires_at: Option<NaiveDateTime>,
+    pub signature: Signature,
+}
+
+impl PaymentRequest {
+    /// Creates a request from a signature of its [challenge](Self::signature_challenge), which must have been made
+    /// with the private spend key of `address`
+    pub fn new(
+        address: TariAddress,
+        amount: MicroMinotari,
+        payment_id: PaymentId,
+        memo: String,
+        expires_at: Option<NaiveDateTime>,
+        signature: Signature,
+    ) -> Result<Self, PaymentRequestError> {
+        let request = Self {
+            address,
+            amount,
+            payment_id,
+            memo,
+            expires_at,
+            signature,
+        };
+        request.verify()?;
+        Ok(request)
+    }
+
+    /// Creates a request signed with `spend_key`, which must be the private spend key of `address`
+    pub fn new_signed(
+        address: TariAddress,
+        amount: MicroMinotari,
+        payment_id: PaymentId,
+        memo: String,
+        expires_at: Option<NaiveDateTime>,
+        spend_key: &PrivateKey,
+    ) -> Result<Self, PaymentRequestError> {
+        if *address.public_spend_key() != PublicKey::from_secret_key(spend_key) {
+            return Err(PaymentRequestError::SpendKeyMismatch);
+        }
+        let (nonce, public_nonce) = PublicKey::random_keypair(&mut OsRng);
+        let challenge = Self::signature_challenge(&address, amount, &payment_id, &memo, expires_at, &public_nonce);
+        let signature = Signature::sign_raw_uniform(spend_key, nonce, &challenge)
+            .expect("Sign cannot fail with 64-byte challenge and a RistrettoPublicKey");
+        Self::new(address, amount, payment_id, memo, expires_at, signature)
+    }
+
+    /// The challenge that the owner of `address` signs with its private spend key and the private key of
+    /// `public_nonce` to issue a request
+    pub fn signature_challenge(
+        address: &TariAddress,
+        amount: MicroMinotari,
+        payment_id: &PaymentId,
+        memo: &str,
+        expires_at: Option<NaiveDateTime>,
+        public_nonce: &PublicKey,
+    ) -> [u8; 64] {
+        let hasher = DomainSeparatedHasher::<Blake2b<U64>, PaymentRequestSigningDomain>::new_with_label("signature")
+            .chain(address.public_spend_key().as_bytes())
+            .chain(public_nonce.as_bytes())
+            .chain(signature_message(address, amount, payment_id, memo, expires_at));
+        digest::Digest::finalize(hasher).into()
+    }
+
+    /// Checks that the request was signed by the owner of the address it asks to be paid to
+    pub fn verify(&self) -> Result<(), PaymentRequestError> {
+        let challenge = Self::signature_challenge(
+            &self.address,
+            self.amount,
+            &self.payment_id,
+            &self.memo,
+            self.expires_at,
+            self.signature.get_public_nonce(),
+        );
+        if self
+            .signature
+            .verify_raw_uniform(self.address.public_spend_key(), &challenge)
+        {
+            Ok(())
+        } else {
+            Err(PaymentRequestError::InvalidSignature)
+        }
+    }
+
+    pub fn is_expired(&self, now: NaiveDateTime) -> bool {
+        self.expires_at.map_or(false, |expires_at| now > expires_at)
+    }
+
+    pub fn to_uri(&self) -> String {
+        let mut uri = format!(
+            "{}{}{}?tariAddress={}&amount={}&paymentId={}",
+            URI_SCHEME,
+            self.address.network(),
+            URI_PATH,
+            self.address.to_base58(),
+            self.amount.as_u64(),
+            self.payment_id.to_bytes().to_hex()
+        );
+        if !self.memo.is_empty() {
+            uri.push_str(&format!("&memo={}", percent_encode(&self.memo)));
+        }
+        if let Some(expires_at) = self.expires_at {
+            uri.push_str(&format!("&expiresAt={}", expires_at.timestamp()));
+        }
+        uri.push_str(&format!(
+            "&nonce={}&signature={}",
+            self.signature.get_public_nonce().to_hex(),
+            self.signature.get_signature().to_hex()
+        ));
+        uri
+    }
+}
+
+impl fmt::Display for PaymentRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_uri())
+    }
+}
+
+impl FromStr for PaymentRequest {
+    type Err = PaymentRequestError;
+
+    /// Parses a payment request URI and verifies its signature
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (location, query) = s
+            .trim()
+            .strip_prefix(URI_SCHEME)
+            .and_then(|rest| rest.split_once('?'))
+            .ok_or(PaymentRequestError::NotAPaymentRequest)?;
+        let network = location
+            .strip_suffix(URI_PATH)
+            .ok_or(PaymentRequestError::NotAPaymentRequest)?;
+
+        let params = query
+            .split('&')
+            .filter_map(|param| param.split_once('='))
+            .collect::<Vec<_>>();
+        let get = |field: &'static str| params.iter().find(|(k, _)| *k == field).map(|(_, v)| *v);
+        let require = |field: &'static str| get(field).ok_or(PaymentRequestError::MissingField(field));
+        let invalid = |field: &'static str, value: &str| PaymentRequestError::InvalidField {
+            field,
+            value: value.to_string(),
+        };
+
+        let address =
+            require("tariAddress").and_then(|v| TariAddress::from_base58(v).map_err(|_| invalid("tariAddress", v)))?;
+        if address.network().to_string() != network {
+            return Err(invalid("network", network));
+        }
+        let amount = require("amount").and_then(|v| {
+            v.parse::<u64>()
+                .map(MicroMinotari::from)
+                .map_err(|_| invalid("amount", v))
+        })?;
+        let payment_id = require("paymentId").and_then(|v| {
+            Vec::<u8>::from_hex(v)
+                .ok()
+                .and_then(|bytes| PaymentId::from_bytes(&bytes).ok())
+                .ok_or_else(|| invalid("paymentId", v))
+        })?;
+        let memo = match get("memo") {
+            Some(v) => percent_decode(v).ok_or_else(|| invalid("memo", v))?,
+            None => String::new(),
+        };
+        let expires_at = match get("expiresAt") {
+            Some(v) => Some(
+                v.parse::<i64>()
+                    .ok()
+                    .and_then(|t| NaiveDateTime::from_timestamp_opt(t, 0))
+                    .ok_or_else(|| invalid("expiresAt", v))?,
+            ),
+            None => None,
+        };
+        let nonce = require("nonce").and_then(|v| PublicKey::from_hex(v).map_err(|_| invalid("nonce", v)))?;
+        let signature =
+            require("signature").and_then(|v| PrivateKey::from_hex(v).map_err(|_| invalid("signature", v)))?;
+
+        Self::new(
+            address,
+            amount,
+            payment_id,
+            memo,
+            expires_at,
+            Signature::new(nonce, signature),
+        )
+    }
+}
+
+fn signature_message(
+    address: &TariAddress,
+    amount: MicroMinotari,
+    payment_id: &PaymentId,
+    memo: &str,
+    expires_at: Option<NaiveDateTime>,
+) -> Vec<u8> {
+    let payment_id = payment_id.to_bytes();
+    let mut challenge = address.to_vec();
+    challenge.extend_from_slice(&amount.as_u64().to_le_bytes());
+    challenge.extend_from_slice(&(payment_id.len() as u64).to_le_bytes());
+    challenge.extend_from_slice(&payment_id);
+    challenge.extend_from_slice(&(memo.len() as u64).to_le_bytes());
+    challenge.extend_from_slice(memo.as_bytes());
+    if let Some(expires_at) = expires_at {
+        challenge.push(1);
+        challenge.extend_from_slice(&expires_at.timestamp().to_le_bytes());
+    } else {
+        challenge.push(0);
+    }
+    challenge
+}
+
+fn percent_encode(s: &str) -> String {
+    s.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => char::from(b).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+fn percent_decode(s: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(s.len());
+    let mut iter = s.bytes();
+    while let Some(b) = iter.next() {
+        match b {
+            b'%' => {
+                let hex = [iter.next()?, iter.next()?];
+                bytes.push(u8::from_str_radix(std::str::from_utf8(&hex).ok()?, 16).ok()?);
+            },
+            b'+' => bytes.push(b' '),
+            b => bytes.push(b),
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+#[cfg(test)]
+mod test {
+    use tari_common::configuration::Network;
+    use tari_crypto::keys::SecretKey;
+
+    use super::*;
+
+    fn address_with_spend_key() -> (TariAddress, PrivateKey) {
+        let spend_key = PrivateKey::random(&mut OsRng);
+        let address = TariAddress::new_dual_address_with_default_features(
+            PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng)),
+            PublicKey::from_secret_key(&spend_key),
+            Network::LocalNet,
+        );
+        (address, spend_key)
+    }
+
+    #[test]
+    fn it_roundtrips_through_a_uri() {
+        let (address, spend_key) = address_with_spend_key();
+        let expires_at = NaiveDateTime::from_timestamp_opt(1_730_000_000, 0);
+        let request = PaymentRequest::new_signed(
+            address,
+            MicroMinotari::from(1_500_000),
+            PaymentId::U64(42),
+            "Invoice #7: 2 × coffee & cake".to_string(),
+            expires_at,
+            &spend_key,
+        )
+        .unwrap();
+        request.verify().unwrap();
+
+        let uri = request.to_uri();
+        assert!(uri.starts_with("tari://localnet/payment_request?tariAddress="));
+        assert_eq!(uri.parse::<PaymentRequest>().unwrap(), request);
+    }
+
+    #[test]
+    fn it_rejects_tampered_and_foreign_requests() {
+        let (address, spend_key) = address_with_spend_key();
+        let request = PaymentRequest::new_signed(
+            address.clone(),
+            MicroMinotari::from(1000),
+            PaymentId::U64(1),
+            String::new(),
+            None,
+            &spend_key,
+        )
+        .unwrap();
+        let tampered = request.to_uri().replace("&amount=1000&", "&amount=1&");
+        assert_eq!(
+            tampered.parse::<PaymentRequest>().unwrap_err(),
+            PaymentRequestError::InvalidSignature
+        );
+
+        let (other_address, other_spend_key) = address_with_spend_key();
+        assert_eq!(
+            PaymentRequest::new_signed(
+                address.clone(),
+                MicroMinotari::from(1000),
+                PaymentId::U64(1),
+                String::new(),
+                None,
+                &other_spend_key
+            )
+            .unwrap_err(),
+            PaymentRequestError::SpendKeyMismatch
+        );
+        // A request signed for another address does not verify against this one
+        let foreign = PaymentRequest::new_signed(
+            other_address,
+            MicroMinotari::from(1000),
+            PaymentId::U64(1),
+            String::new(),
+            None,
+            &other_spend_key,
+        )
+        .unwrap();
+        assert_eq!(
+            PaymentRequest::new(
+                address,
+                foreign.amount,
+                foreign.payment_id.clone(),
+                foreign.memo.clone(),
+                foreign.expires_at,
+                foreign.signature
+            )
+            .unwrap_err(),
+            PaymentRequestError::InvalidSignature
+        );
+        // The view key of the address cannot sign for it
+        let view_only = TariAddress::new_dual_address_with_default_features(
+            PublicKey::from_secret_key(&spend_key),
+            PublicKey::from_secret_key(&other_spend_key),
+            Network::LocalNet,
+        );
+        assert_eq!(
+            PaymentRequest::new_signed(
+                view_only,
+                MicroMinotari::from(1000),
+                PaymentId::U64(1),
+                String::new(),
+                None,
+                &spend_key
+            )
+            .unwrap_err(),
+            PaymentRequestError::SpendKeyMismatch
+        );
+        assert_eq!(
+            "tari://localnet/transactions/send?tariAddress=x".parse::<PaymentRequest>(),
+            Err(PaymentRequestError::NotAPaymentRequest)
+        );
+    }
+}
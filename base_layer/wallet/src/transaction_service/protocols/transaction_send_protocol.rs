@@ -41,6 +41,7 @@ use tari_core::{
         tari_amount::MicroMinotari,
         transaction_components::OutputFeatures,
         transaction_protocol::{
+            partially_signed::PaymentKind,
             proto::protocol as proto,
             recipient::RecipientSignedMessage,
             sender::SingleRoundSenderData,
@@ -91,6 +92,7 @@ pub struct TransactionSendProtocol<TBackend, TWalletConnectivity, TKeyManagerInt
     id: TxId,
     dest_address: TariAddress,
     amount: MicroMinotari,
+    selection_criteria: UtxoSelectionCriteria,
     fee_per_gram: MicroMinotari,
     message: String,
     service_request_reply_channel: Option<oneshot::Sender<Result<TransactionServiceResponse, TransactionServiceError>>>,
@@ -99,6 +101,8 @@ pub struct TransactionSendProtocol<TBackend, TWalletConnectivity, TKeyManagerInt
     transaction_reply_receiver: Option<Receiver<(CommsPublicKey, RecipientSignedMessage)>>,
     cancellation_receiver: Option<oneshot::Receiver<()>>,
     tx_meta: TransactionMetadata,
+    /// The transaction is signed by another wallet holding the spend key once the recipient has replied
+    offline_signing: bool,
     sender_protocol: Option<SenderTransactionProtocol>,
 }
 
@@ -116,9 +120,11 @@ where
         cancellation_receiver: oneshot::Receiver<()>,
         dest_address: TariAddress,
         amount: MicroMinotari,
+        selection_criteria: UtxoSelectionCriteria,
         fee_per_gram: MicroMinotari,
         message: String,
         tx_meta: TransactionMetadata,
+        offline_signing: bool,
         service_request_reply_channel: Option<
             oneshot::Sender<Result<TransactionServiceResponse, TransactionServiceError>>,
         >,
@@ -132,11 +138,13 @@ where
             cancellation_receiver: Some(cancellation_receiver),
             dest_address,
             amount,
+            selection_criteria,
             fee_per_gram,
             message,
             service_request_reply_channel,
             stage,
             tx_meta,
+            offline_signing,
             sender_protocol,
         }
     }
@@ -220,7 +228,7 @@ where
             .prepare_transaction_to_send(
                 self.id,
                 self.amount,
-                UtxoSelectionCriteria::default(),
+                self.selection_criteria.clone(),
                 OutputFeatures::default(),
                 self.fee_per_gram,
                 self.tx_meta.clone(),
@@ -336,7 +344,7 @@ where
             let fee = sender_protocol
                 .get_fee_amount()
                 .map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))?;
-            let outbound_tx = OutboundTransaction::new(
+            let mut outbound_tx = OutboundTransaction::new(
                 tx_id,
                 self.dest_address.clone(),
                 self.amount,
@@ -347,6 +355,7 @@ where
                 Utc::now().naive_utc(),
                 initial_send.direct_send_result,
             );
+            outbound_tx.offline_signing = self.offline_signing.then_some(PaymentKind::Interactive);
             self.resources
                 .db
                 .add_pending_outbound_transaction(outbound_tx.tx_id, outbound_tx.clone())
@@ -574,6 +583,24 @@ where
             .await
             .map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))?;
 
+        if outbound_tx.offline_signing.is_some() {
+            // The wallet holding the spend key signs the transaction, it is completed when the signed transaction is
+            // submitted
+            self.resources
+                .db
+                .update_pending_outbound_sender_protocol(tx_id, outbound_tx.sender_protocol)
+                .map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))?;
+            info!(
+                target: LOG_TARGET,
+                "Transaction Recipient Reply for TX_ID = {} received, awaiting an offline signature", tx_id,
+            );
+            let _size = self
+                .resources
+                .event_publisher
+                .send(Arc::new(TransactionEvent::ReceivedTransactionReply(tx_id)));
+            return Ok(());
+        }
+
         outbound_tx
             .sender_protocol
             .finalize(&self.resources.transaction_key_manager_service)
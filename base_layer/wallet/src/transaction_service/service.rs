@@ -28,10 +28,11 @@ use std::{
 };
 
 use chrono::{NaiveDateTime, Utc};
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
 use digest::Digest;
 use futures::{pin_mut, stream::FuturesUnordered, Stream, StreamExt};
 use log::*;
-use rand::rngs::OsRng;
+use rand::{rngs::OsRng, RngCore};
 use sha2::Sha256;
 use tari_common::configuration::Network;
 use tari_common_types::{
@@ -40,7 +41,7 @@ use tari_common_types::{
     tari_address::{TariAddress, TariAddressFeatures},
     transaction::{ImportStatus, TransactionDirection, TransactionStatus, TxId},
     types::{CommitmentFactory, HashOutput, PrivateKey, PublicKey, Signature},
-    wallet_types::WalletType,
+    wallet_types::{AccountId, WalletType},
 };
 use tari_comms::{types::CommsPublicKey, NodeIdentity};
 use tari_comms_dht::outbound::OutboundMessageRequester;
@@ -51,7 +52,7 @@ use tari_core::{
     one_sided::{shared_secret_to_output_encryption_key, shared_secret_to_output_spending_key},
     proto::{base_node as base_node_proto, base_node::FetchMatchingUtxos},
     transactions::{
-        key_manager::TransactionKeyManagerInterface,
+        key_manager::{TariKeyId, TransactionKeyManagerInterface},
         tari_amount::MicroMinotari,
         transaction_components::{
             encrypted_data::PaymentId,
@@ -60,9 +61,11 @@ use tari_core::{
             OutputFeatures,
             Transaction,
             TransactionOutput,
+            WalletOutput,
             WalletOutputBuilder,
         },
         transaction_protocol::{
+            partially_signed::{PartiallySignedTransaction, PartiallySignedTransactionError, PaymentKind},
             proto::protocol as proto,
             recipient::RecipientSignedMessage,
             sender::TransactionSenderMessage,
@@ -70,6 +73,7 @@ use tari_core::{
         },
         CryptoFactories,
         ReceiverTransactionProtocol,
+        SenderTransactionProtocol,
     },
 };
 use tari_crypto::{
@@ -85,11 +89,12 @@ use tari_shutdown::ShutdownSignal;
 use tokio::{
     sync::{mpsc, mpsc::Sender, oneshot, Mutex},
     task::JoinHandle,
+    time::{self as tokio_time, MissedTickBehavior},
 };
 
 use crate::{
     base_node_service::handle::{BaseNodeEvent, BaseNodeServiceHandle},
-    connectivity_service::WalletConnectivityInterface,
+    connectivity_service::{OnlineStatus, WalletConnectivityInterface},
     output_manager_service::{
         handle::{OutputManagerEvent, OutputManagerHandle},
         service::UseOutput,
@@ -99,7 +104,7 @@ use crate::{
     storage::database::{WalletBackend, WalletDatabase},
     transaction_service::{
         config::TransactionServiceConfig,
-        error::{TransactionServiceError, TransactionServiceProtocolError},
+        error::{TransactionServiceError, TransactionServiceProtocolError, TransactionStorageError},
         handle::{
             FeePerGramStatsResponse,
             TransactionEvent,
@@ -107,6 +112,7 @@ use crate::{
             TransactionServiceRequest,
             TransactionServiceResponse,
         },
+        payment_request::PaymentRequest,
         protocols::{
             check_transaction_size,
             transaction_broadcast_protocol::TransactionBroadcastProtocol,
@@ -117,7 +123,14 @@ use crate::{
         storage::{
             database::{TransactionBackend, TransactionDatabase},
             models::{
+                BatchPaymentRecipient,
                 CompletedTransaction,
+                IssuedPaymentRequest,
+                OutboundTransaction,
+                PaymentRequestStatus,
+                PaymentSchedule,
+                ScheduledPayment,
+                ScheduledPaymentRun,
                 TxCancellationReason,
                 WalletTransaction::{Completed, PendingInbound, PendingOutbound},
             },
@@ -375,12 +388,18 @@ where
         let mut base_node_service_event_stream = self.base_node_service.get_event_stream();
         let mut output_manager_event_stream = self.resources.output_manager_service.get_event_stream();
 
+        let mut scheduled_payment_check = tokio_time::interval(self.config.scheduled_payment_check_interval);
+        scheduled_payment_check.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
         debug!(target: LOG_TARGET, "Transaction Service started");
         loop {
             tokio::select! {
                 event = output_manager_event_stream.recv() => {
                     match event {
-                        Ok(msg) => self.handle_output_manager_service_event(msg).await,
+                        Ok(msg) => self.handle_output_manager_service_event(
+                            msg,
+                            &mut transaction_broadcast_protocol_handles,
+                        ).await,
                         Err(e) => debug!(target: LOG_TARGET, "Lagging read on base node event broadcast channel: {}", e),
                     };
                 },
@@ -573,6 +592,17 @@ where
                         ),
                         Err(e) => error!(target: LOG_TARGET, "Error resolving Transaction Validation protocol: {:?}", e),
                     };
+                }
+                _ = scheduled_payment_check.tick() => {
+                    if let Err(e) = self.send_due_scheduled_payments(
+                        &mut send_transaction_protocol_handles,
+                        &mut transaction_broadcast_protocol_handles,
+                    ).await {
+                        warn!(target: LOG_TARGET, "Error sending scheduled payments: {}", e);
+                    }
+                    if let Err(e) = self.update_payment_requests() {
+                        warn!(target: LOG_TARGET, "Error updating payment requests: {}", e);
+                    }
                 }
                  _ = shutdown.wait() => {
                     info!(target: LOG_TARGET, "Transaction service shutting down because it received the shutdown signal");
@@ -603,6 +633,14 @@ where
         >,
         reply_channel: oneshot::Sender<Result<TransactionServiceResponse, TransactionServiceError>>,
     ) -> Result<(), TransactionServiceError> {
+        if self.resources.wallet_type.is_watch_only() && request.requires_spend_key() {
+            let _result = reply_channel
+                .send(Err(TransactionServiceError::WatchOnlyWallet(request.to_string())))
+                .inspect_err(|_| {
+                    warn!(target: LOG_TARGET, "Failed to send reply");
+                });
+            return Ok(());
+        }
         let mut reply_channel = Some(reply_channel);
 
         trace!(target: LOG_TARGET, "Handling Service Request: {}", request);
@@ -624,6 +662,7 @@ where
                     fee_per_gram,
                     message,
                     TransactionMetadata::default(),
+                    false,
                     send_transaction_join_handles,
                     transaction_broadcast_join_handles,
                     rp,
@@ -631,6 +670,56 @@ where
                 .await?;
                 return Ok(());
             },
+            TransactionServiceRequest::CreatePartiallySignedTransaction {
+                destination,
+                amount,
+                selection_criteria,
+                fee_per_gram,
+                message,
+                payment_id,
+                kind,
+            } => match kind {
+                PaymentKind::OneSided => self
+                    .create_one_sided_transaction_for_offline_signing(
+                        destination,
+                        amount,
+                        selection_criteria,
+                        fee_per_gram,
+                        message,
+                        payment_id,
+                    )
+                    .await
+                    .map(TransactionServiceResponse::TransactionSent),
+                PaymentKind::Interactive => {
+                    let rp = reply_channel.take().expect("Cannot be missing");
+                    self.send_transaction(
+                        destination,
+                        amount,
+                        selection_criteria,
+                        OutputFeatures::default(),
+                        fee_per_gram,
+                        message,
+                        TransactionMetadata::default(),
+                        true,
+                        send_transaction_join_handles,
+                        transaction_broadcast_join_handles,
+                        rp,
+                    )
+                    .await?;
+                    return Ok(());
+                },
+            },
+            TransactionServiceRequest::GetPartiallySignedTransaction(tx_id) => self
+                .get_partially_signed_transaction(tx_id)
+                .map(|tx| TransactionServiceResponse::PartiallySignedTransaction(Box::new(tx))),
+            TransactionServiceRequest::SignPartiallySignedTransaction(tx) => self
+                .sign_partially_signed_transaction(*tx)
+                .await
+                .map(|tx| TransactionServiceResponse::PartiallySignedTransaction(Box::new(tx))),
+            TransactionServiceRequest::SubmitSignedTransaction(tx) => self
+                .submit_signed_transaction(*tx, transaction_broadcast_join_handles)
+                .await
+                .map(|_| TransactionServiceResponse::TransactionSubmitted),
             TransactionServiceRequest::SendOneSidedTransaction {
                 destination,
                 amount,
@@ -652,6 +741,24 @@ where
                 )
                 .await
                 .map(TransactionServiceResponse::TransactionSent),
+            TransactionServiceRequest::SendBatchTransaction {
+                recipients,
+                selection_criteria,
+                fee_per_gram,
+                message,
+            } => self
+                .send_batch_transaction(
+                    recipients,
+                    selection_criteria,
+                    fee_per_gram,
+                    message,
+                    transaction_broadcast_join_handles,
+                )
+                .await
+                .map(TransactionServiceResponse::TransactionSent),
+            TransactionServiceRequest::GetBatchPaymentRecipients(tx_id) => Ok(
+                TransactionServiceResponse::BatchPaymentRecipients(self.db.get_batch_payment_recipients(tx_id)?),
+            ),
 
             TransactionServiceRequest::ScrapeWallet {
                 destination,
@@ -850,6 +957,44 @@ where
                 .cancel_pending_transaction(tx_id)
                 .await
                 .map(|_| TransactionServiceResponse::TransactionCancelled),
+            TransactionServiceRequest::BumpFee { tx_id, fee_per_gram } => self
+                .bump_fee(tx_id, fee_per_gram, transaction_broadcast_join_handles)
+                .await
+                .map(TransactionServiceResponse::TransactionSent),
+            TransactionServiceRequest::CreateScheduledPayment {
+                destination,
+                amount,
+                fee_per_gram,
+                message,
+                kind,
+                schedule,
+                account,
+            } => self
+                .create_scheduled_payment(destination, amount, fee_per_gram, message, kind, schedule, account)
+                .await
+                .map(|payment| TransactionServiceResponse::ScheduledPayment(Box::new(payment))),
+            TransactionServiceRequest::GetScheduledPayments => Ok(TransactionServiceResponse::ScheduledPayments(
+                self.db.get_scheduled_payments()?,
+            )),
+            TransactionServiceRequest::GetScheduledPaymentRuns(id) => Ok(
+                TransactionServiceResponse::ScheduledPaymentRuns(self.db.get_scheduled_payment_runs(id)?),
+            ),
+            TransactionServiceRequest::CancelScheduledPayment(id) => {
+                self.db.cancel_scheduled_payment(id)?;
+                Ok(TransactionServiceResponse::ScheduledPaymentCancelled)
+            },
+            TransactionServiceRequest::CreatePaymentRequest {
+                amount,
+                memo,
+                payment_id,
+                expires_at,
+            } => self
+                .create_payment_request(amount, memo, payment_id, expires_at)
+                .await
+                .map(|request| TransactionServiceResponse::PaymentRequest(Box::new(request))),
+            TransactionServiceRequest::GetPaymentRequests => self
+                .update_payment_requests()
+                .map(TransactionServiceResponse::PaymentRequests),
             TransactionServiceRequest::GetPendingInboundTransactions => Ok(
                 TransactionServiceResponse::PendingInboundTransactions(self.db.get_pending_inbound_transactions()?),
             ),
@@ -860,6 +1005,9 @@ where
             TransactionServiceRequest::GetCompletedTransactions => Ok(
                 TransactionServiceResponse::CompletedTransactions(self.db.get_completed_transactions()?),
             ),
+            TransactionServiceRequest::GetTransactionHistory(filter) => Ok(
+                TransactionServiceResponse::TransactionHistory(self.db.get_transaction_history(&filter)?),
+            ),
             TransactionServiceRequest::GetCancelledPendingInboundTransactions => {
                 Ok(TransactionServiceResponse::PendingInboundTransactions(
                     self.db.get_cancelled_pending_inbound_transactions()?,
@@ -943,6 +1091,7 @@ where
                     send_transaction_join_handles,
                     receive_transaction_join_handles,
                 )
+                .await
                 .map(|_| TransactionServiceResponse::ProtocolsRestarted),
             TransactionServiceRequest::RestartBroadcastProtocols => self
                 .restart_broadcast_protocols(transaction_broadcast_join_handles)
@@ -967,6 +1116,11 @@ where
                 self.handle_get_fee_per_gram_stats_per_block_request(count, reply_channel);
                 return Ok(());
             },
+            TransactionServiceRequest::EstimateFee { target_blocks } => {
+                let reply_channel = reply_channel.take().expect("reply_channel is Some");
+                self.handle_estimate_fee_request(target_blocks, reply_channel);
+                return Ok(());
+            },
         };
 
         // If the individual handlers did not already send the API response then do it here.
@@ -1020,6 +1174,35 @@ where
         });
     }
 
+    fn handle_estimate_fee_request(
+        &self,
+        target_blocks: Vec<u64>,
+        reply_channel: oneshot::Sender<Result<TransactionServiceResponse, TransactionServiceError>>,
+    ) {
+        let mut connectivity = self.resources.connectivity.clone();
+
+        let query_base_node_fut = async move {
+            let mut client = connectivity
+                .obtain_base_node_wallet_rpc_client()
+                .await
+                .ok_or(TransactionServiceError::Shutdown)?;
+
+            let resp = client
+                .estimate_fee(base_node_proto::EstimateFeeRequest { target_blocks })
+                .await?;
+            Ok(TransactionServiceResponse::FeeEstimates(
+                resp.estimates.into_iter().map(Into::into).collect(),
+            ))
+        };
+
+        tokio::spawn(async move {
+            let resp = query_base_node_fut.await;
+            if reply_channel.send(resp).is_err() {
+                warn!(target: LOG_TARGET, "handle_estimate_fee_request: service reply cancelled");
+            }
+        });
+    }
+
     async fn handle_base_node_service_event(
         &mut self,
         event: Arc<BaseNodeEvent>,
@@ -1045,22 +1228,49 @@ where
         }
     }
 
-    async fn handle_output_manager_service_event(&mut self, event: Arc<OutputManagerEvent>) {
-        if let OutputManagerEvent::TxoValidationSuccess(_) = (*event).clone() {
-            let db = self.db.clone();
-            let output_manager_handle = self.resources.output_manager_service.clone();
-            let metadata = self.wallet_db.get_chain_metadata().unwrap_or_default();
-            let tip_height = match metadata {
-                Some(val) => val.best_block_height(),
-                None => 0u64,
-            };
-            let event_publisher = self.event_publisher.clone();
-            tokio::spawn(check_detected_transactions(
-                output_manager_handle,
-                db,
-                event_publisher,
-                tip_height,
-            ));
+    async fn handle_output_manager_service_event(
+        &mut self,
+        event: Arc<OutputManagerEvent>,
+        transaction_broadcast_join_handles: &mut FuturesUnordered<
+            JoinHandle<Result<TxId, TransactionServiceProtocolError<TxId>>>,
+        >,
+    ) {
+        match (*event).clone() {
+            OutputManagerEvent::TxoValidationSuccess(_) => {
+                let db = self.db.clone();
+                let output_manager_handle = self.resources.output_manager_service.clone();
+                let metadata = self.wallet_db.get_chain_metadata().unwrap_or_default();
+                let tip_height = match metadata {
+                    Some(val) => val.best_block_height(),
+                    None => 0u64,
+                };
+                let event_publisher = self.event_publisher.clone();
+                tokio::spawn(check_detected_transactions(
+                    output_manager_handle,
+                    db,
+                    event_publisher,
+                    tip_height,
+                ));
+            },
+            OutputManagerEvent::UtxoConsolidationCreated {
+                consolidation,
+                transaction,
+            } => {
+                if let Err(e) = self
+                    .submit_transaction_to_self(
+                        transaction_broadcast_join_handles,
+                        consolidation.tx_id,
+                        *transaction,
+                        consolidation.fee,
+                        consolidation.input_value,
+                        "Automatic UTXO consolidation".to_string(),
+                    )
+                    .await
+                {
+                    warn!(target: LOG_TARGET, "Error submitting UTXO consolidation: {}", e);
+                }
+            },
+            _ => {},
         }
     }
 
@@ -1078,6 +1288,7 @@ where
         fee_per_gram: MicroMinotari,
         message: String,
         tx_meta: TransactionMetadata,
+        offline_signing: bool,
         join_handles: &mut FuturesUnordered<
             JoinHandle<Result<TransactionSendResult, TransactionServiceProtocolError<TxId>>>,
         >,
@@ -1108,6 +1319,16 @@ where
                 target: LOG_TARGET,
                 "Received transaction with spend-to-self transaction"
             );
+            if offline_signing {
+                let _result = reply_channel
+                    .send(Err(TransactionServiceError::NotSupported(
+                        "Offline signing of a payment to self".to_string(),
+                    )))
+                    .inspect_err(|_| {
+                        warn!(target: LOG_TARGET, "Failed to send service reply");
+                    });
+                return Ok(());
+            }
 
             let (fee, transaction) = self
                 .resources
@@ -1162,9 +1383,11 @@ where
             cancellation_receiver,
             destination,
             amount,
+            selection_criteria,
             fee_per_gram,
             message,
             tx_meta,
+            offline_signing,
             Some(reply_channel),
             TransactionSendProtocolStage::Initial,
             None,
@@ -1671,167 +1894,20 @@ where
         payment_id: PaymentId,
     ) -> Result<TxId, TransactionServiceError> {
         let tx_id = TxId::new_random();
-        let payment_id = match payment_id {
-            PaymentId::Open(v) => PaymentId::AddressAndData(self.resources.interactive_tari_address.clone(), v),
-            PaymentId::Empty => PaymentId::Address(self.resources.interactive_tari_address.clone()),
-            _ => payment_id,
-        };
-        self.verify_send(&dest_address, TariAddressFeatures::create_one_sided_only())?;
-
-        // For a stealth transaction, the script is not provided because the public key that should be included
-        // is not known at this stage. This will only be known later. For now,
-        // we include a default public key to ensure that the script size is correct.
-        let (mut script, use_stealth_address) = match recipient_script {
-            Some(s) => (s, false),
-            None => (push_pubkey_script(&Default::default()), true),
-        };
-        // Prepare sender part of the transaction
-        let mut stp = self
-            .resources
-            .output_manager_service
-            .prepare_transaction_to_send(
+        let (mut stp, payment_id) = self
+            .prepare_one_sided_or_stealth(
                 tx_id,
+                dest_address.clone(),
                 amount,
                 selection_criteria,
                 output_features,
                 fee_per_gram,
-                TransactionMetadata::default(),
                 message.clone(),
-                script.clone(),
-                Covenant::default(),
-                MicroMinotari::zero(),
-            )
-            .await?;
-
-        // This call is needed to advance the state from `SingleRoundMessageReady` to `SingleRoundMessageReady`,
-        // but the returned value is not used. We have to wait until the sender transaction protocol creates a
-        // sender_offset_private_key for us, so we can use it to create the shared secret
-        let key = self
-            .resources
-            .transaction_key_manager_service
-            .get_next_key(TransactionKeyManagerBranch::OneSidedSenderOffset.get_branch_key())
-            .await?;
-
-        stp.change_recipient_sender_offset_private_key(key.key_id)?;
-        let _single_round_sender_data = stp
-            .build_single_round_message(&self.resources.transaction_key_manager_service)
-            .await
-            .map_err(|e| TransactionServiceProtocolError::new(tx_id, e.into()))?;
-
-        // Prepare receiver part of the transaction
-
-        // Diffie-Hellman shared secret `k_Ob * K_Sb = K_Ob * k_Sb` results in a public key, which is fed into
-        // KDFs to produce the spending, rewind, and encryption keys
-        let sender_offset_private_key = stp
-            .get_recipient_sender_offset_private_key()
-            .map_err(|e| TransactionServiceProtocolError::new(tx_id, e.into()))?
-            .ok_or(TransactionServiceProtocolError::new(
-                tx_id,
-                TransactionServiceError::InvalidKeyId("Missing sender offset keyid".to_string()),
-            ))?;
-
-        let shared_secret = self
-            .resources
-            .transaction_key_manager_service
-            .get_diffie_hellman_shared_secret(
-                &sender_offset_private_key,
-                dest_address
-                    .public_view_key()
-                    .ok_or(TransactionServiceProtocolError::new(
-                        tx_id,
-                        TransactionServiceError::OneSidedTransactionError("Missing public view key".to_string()),
-                    ))?,
-            )
-            .await?;
-        let commitment_mask_private_key = shared_secret_to_output_spending_key(&shared_secret)
-            .map_err(|e| TransactionServiceProtocolError::new(tx_id, e.into()))?;
-        let commitment_mask_key_id = &self
-            .resources
-            .transaction_key_manager_service
-            .import_key(commitment_mask_private_key.clone())
-            .await?;
-
-        if use_stealth_address {
-            let script_spending_key = self
-                .resources
-                .transaction_key_manager_service
-                .stealth_address_script_spending_key(commitment_mask_key_id, dest_address.public_spend_key())
-                .await?;
-            script = push_pubkey_script(&script_spending_key);
-        }
-
-        let sender_message = TransactionSenderMessage::new_single_round_message(
-            stp.get_single_round_message(&self.resources.transaction_key_manager_service)
-                .await?,
-        );
-
-        let encryption_private_key = shared_secret_to_output_encryption_key(&shared_secret)?;
-        let encryption_key = self
-            .resources
-            .transaction_key_manager_service
-            .import_key(encryption_private_key)
-            .await?;
-
-        let spending_key_id = self
-            .resources
-            .transaction_key_manager_service
-            .import_key(commitment_mask_private_key)
-            .await?;
-
-        let sender_offset_public_key = self
-            .resources
-            .transaction_key_manager_service
-            .get_public_key_at_key_id(&sender_offset_private_key)
-            .await?;
-
-        let minimum_value_promise = MicroMinotari::zero();
-        let output = WalletOutputBuilder::new(amount, spending_key_id)
-            .with_features(
-                sender_message
-                    .single()
-                    .ok_or(TransactionServiceProtocolError::new(
-                        tx_id,
-                        TransactionServiceError::InvalidMessageError("Sent invalid message type".to_string()),
-                    ))?
-                    .features
-                    .clone(),
-            )
-            .with_script(script)
-            .encrypt_data_for_recovery(
-                &self.resources.transaction_key_manager_service,
-                Some(&encryption_key),
-                payment_id.clone(),
-            )
-            .await?
-            .with_input_data(Default::default())
-            .with_sender_offset_public_key(sender_offset_public_key)
-            .with_script_key(KeyId::Zero)
-            .with_minimum_value_promise(minimum_value_promise)
-            .sign_as_sender_and_receiver_verified(
-                &self.resources.transaction_key_manager_service,
-                &sender_offset_private_key,
-                &dest_address,
+                recipient_script,
+                payment_id,
             )
-            .await?
-            .try_build(&self.resources.transaction_key_manager_service)
             .await?;
 
-        let tip_height = self.last_seen_tip_height.unwrap_or(0);
-        let consensus_constants = self.consensus_manager.consensus_constants(tip_height);
-        let rtp = ReceiverTransactionProtocol::new(
-            sender_message,
-            output,
-            &self.resources.transaction_key_manager_service,
-            consensus_constants,
-        )
-        .await;
-
-        let recipient_reply = rtp.get_signed_data()?.clone();
-
-        // Start finalizing
-        stp.add_presigned_recipient_info(recipient_reply)
-            .map_err(|e| TransactionServiceProtocolError::new(tx_id, e.into()))?;
-
         // Finalize
 
         stp.finalize(&self.resources.transaction_key_manager_service)
@@ -1897,16 +1973,349 @@ where
         Ok(tx_id)
     }
 
-    #[allow(clippy::too_many_lines)]
-    async fn scrape_wallet(
+    /// Builds a one-sided transaction up to the point where only the sender signatures are missing. Returns the
+    /// sender protocol in the `Finalizing` state and the payment id embedded in the recipient output.
+    async fn prepare_one_sided_or_stealth(
         &mut self,
+        tx_id: TxId,
         dest_address: TariAddress,
+        amount: MicroMinotari,
+        selection_criteria: UtxoSelectionCriteria,
+        output_features: OutputFeatures,
         fee_per_gram: MicroMinotari,
-        transaction_broadcast_join_handles: &mut FuturesUnordered<
-            JoinHandle<Result<TxId, TransactionServiceProtocolError<TxId>>>,
-        >,
-    ) -> Result<TxId, TransactionServiceError> {
-        let tx_id = TxId::new_random();
+        message: String,
+        recipient_script: Option<TariScript>,
+        payment_id: PaymentId,
+    ) -> Result<(SenderTransactionProtocol, PaymentId), TransactionServiceError> {
+        let payment_id = self.with_sender_address(payment_id);
+        self.verify_send(&dest_address, TariAddressFeatures::create_one_sided_only())?;
+
+        // For a stealth transaction, the script is not provided because the public key that should be included
+        // is not known at this stage. This will only be known later. For now,
+        // we include a default public key to ensure that the script size is correct.
+        let script = recipient_script
+            .clone()
+            .unwrap_or_else(|| push_pubkey_script(&Default::default()));
+        // Prepare sender part of the transaction
+        let mut stp = self
+            .resources
+            .output_manager_service
+            .prepare_transaction_to_send(
+                tx_id,
+                amount,
+                selection_criteria,
+                output_features,
+                fee_per_gram,
+                TransactionMetadata::default(),
+                message.clone(),
+                script,
+                Covenant::default(),
+                MicroMinotari::zero(),
+            )
+            .await?;
+
+        // This call is needed to advance the state from `SingleRoundMessageReady` to `SingleRoundMessageReady`,
+        // but the returned value is not used. We have to wait until the sender transaction protocol creates a
+        // sender_offset_private_key for us, so we can use it to create the shared secret
+        let key = self
+            .resources
+            .transaction_key_manager_service
+            .get_next_key(TransactionKeyManagerBranch::OneSidedSenderOffset.get_branch_key())
+            .await?;
+
+        stp.change_recipient_sender_offset_private_key(key.key_id)?;
+        let _single_round_sender_data = stp
+            .build_single_round_message(&self.resources.transaction_key_manager_service)
+            .await
+            .map_err(|e| TransactionServiceProtocolError::new(tx_id, e.into()))?;
+
+        // Prepare receiver part of the transaction
+        let sender_offset_private_key = stp
+            .get_recipient_sender_offset_private_key()
+            .map_err(|e| TransactionServiceProtocolError::new(tx_id, e.into()))?
+            .ok_or(TransactionServiceProtocolError::new(
+                tx_id,
+                TransactionServiceError::InvalidKeyId("Missing sender offset keyid".to_string()),
+            ))?;
+        let sender_message = TransactionSenderMessage::new_single_round_message(
+            stp.get_single_round_message(&self.resources.transaction_key_manager_service)
+                .await?,
+        );
+        let recipient_features = sender_message
+            .single()
+            .ok_or(TransactionServiceProtocolError::new(
+                tx_id,
+                TransactionServiceError::InvalidMessageError("Sent invalid message type".to_string()),
+            ))?
+            .features
+            .clone();
+        let output = self
+            .create_one_sided_output(
+                tx_id,
+                &dest_address,
+                amount,
+                recipient_features,
+                recipient_script,
+                &sender_offset_private_key,
+                payment_id.clone(),
+            )
+            .await?;
+
+        let tip_height = self.last_seen_tip_height.unwrap_or(0);
+        let consensus_constants = self.consensus_manager.consensus_constants(tip_height);
+        let rtp = ReceiverTransactionProtocol::new(
+            sender_message,
+            output,
+            &self.resources.transaction_key_manager_service,
+            consensus_constants,
+        )
+        .await;
+
+        let recipient_reply = rtp.get_signed_data()?.clone();
+
+        // Start finalizing
+        stp.add_presigned_recipient_info(recipient_reply)
+            .map_err(|e| TransactionServiceProtocolError::new(tx_id, e.into()))?;
+
+        Ok((stp, payment_id))
+    }
+
+    /// Adds the interactive address of this wallet to a payment id, so that the recipient of a one-sided payment knows
+    /// who sent it
+    fn with_sender_address(&self, payment_id: PaymentId) -> PaymentId {
+        match payment_id {
+            PaymentId::Open(v) => PaymentId::AddressAndData(self.resources.interactive_tari_address.clone(), v),
+            PaymentId::Empty => PaymentId::Address(self.resources.interactive_tari_address.clone()),
+            _ => payment_id,
+        }
+    }
+
+    /// Builds and signs the one-sided output paying `amount` to `dest_address`, whose keys are derived from the
+    /// sender offset key. The output is locked to a stealth address script unless a `recipient_script` is provided.
+    async fn create_one_sided_output(
+        &mut self,
+        tx_id: TxId,
+        dest_address: &TariAddress,
+        amount: MicroMinotari,
+        output_features: OutputFeatures,
+        recipient_script: Option<TariScript>,
+        sender_offset_private_key: &TariKeyId,
+        payment_id: PaymentId,
+    ) -> Result<WalletOutput, TransactionServiceError> {
+        // Diffie-Hellman shared secret `k_Ob * K_Sb = K_Ob * k_Sb` results in a public key, which is fed into
+        // KDFs to produce the spending, rewind, and encryption keys
+        let shared_secret = self
+            .resources
+            .transaction_key_manager_service
+            .get_diffie_hellman_shared_secret(
+                sender_offset_private_key,
+                dest_address
+                    .public_view_key()
+                    .ok_or(TransactionServiceProtocolError::new(
+                        tx_id,
+                        TransactionServiceError::OneSidedTransactionError("Missing public view key".to_string()),
+                    ))?,
+            )
+            .await?;
+        let commitment_mask_private_key = shared_secret_to_output_spending_key(&shared_secret)
+            .map_err(|e| TransactionServiceProtocolError::new(tx_id, e.into()))?;
+        let spending_key_id = self
+            .resources
+            .transaction_key_manager_service
+            .import_key(commitment_mask_private_key)
+            .await?;
+
+        let script = match recipient_script {
+            Some(script) => script,
+            None => {
+                let script_spending_key = self
+                    .resources
+                    .transaction_key_manager_service
+                    .stealth_address_script_spending_key(&spending_key_id, dest_address.public_spend_key())
+                    .await?;
+                push_pubkey_script(&script_spending_key)
+            },
+        };
+
+        let encryption_private_key = shared_secret_to_output_encryption_key(&shared_secret)?;
+        let encryption_key = self
+            .resources
+            .transaction_key_manager_service
+            .import_key(encryption_private_key)
+            .await?;
+
+        let sender_offset_public_key = self
+            .resources
+            .transaction_key_manager_service
+            .get_public_key_at_key_id(sender_offset_private_key)
+            .await?;
+
+        let output = WalletOutputBuilder::new(amount, spending_key_id)
+            .with_features(output_features)
+            .with_script(script)
+            .encrypt_data_for_recovery(
+                &self.resources.transaction_key_manager_service,
+                Some(&encryption_key),
+                payment_id,
+            )
+            .await?
+            .with_input_data(Default::default())
+            .with_sender_offset_public_key(sender_offset_public_key)
+            .with_script_key(KeyId::Zero)
+            .with_minimum_value_promise(MicroMinotari::zero())
+            .sign_as_sender_and_receiver_verified(
+                &self.resources.transaction_key_manager_service,
+                sender_offset_private_key,
+                dest_address,
+            )
+            .await?
+            .try_build(&self.resources.transaction_key_manager_service)
+            .await?;
+        Ok(output)
+    }
+
+    /// Sends a single transaction containing a one-sided output for every recipient and one change output. The
+    /// transaction is recorded with the first recipient as its destination and the total paid as its amount, and every
+    /// recipient is stored with it.
+    async fn send_batch_transaction(
+        &mut self,
+        recipients: Vec<BatchPaymentRecipient>,
+        selection_criteria: UtxoSelectionCriteria,
+        fee_per_gram: MicroMinotari,
+        message: String,
+        transaction_broadcast_join_handles: &mut FuturesUnordered<
+            JoinHandle<Result<TxId, TransactionServiceProtocolError<TxId>>>,
+        >,
+    ) -> Result<TxId, TransactionServiceError> {
+        let first_recipient = recipients
+            .first()
+            .map(|r| r.address.clone())
+            .ok_or_else(|| TransactionServiceError::InvalidBatchPayment("No recipients provided".to_string()))?;
+        let tx_id = TxId::new_random();
+        let mut outputs = Vec::with_capacity(recipients.len());
+        let mut sent_recipients = Vec::with_capacity(recipients.len());
+        let mut total_amount = MicroMinotari::zero();
+        for recipient in recipients {
+            if recipient.amount == MicroMinotari::zero() {
+                return Err(TransactionServiceError::InvalidBatchPayment(format!(
+                    "The amount paid to {} must be greater than zero",
+                    recipient.address
+                )));
+            }
+            self.verify_send(&recipient.address, TariAddressFeatures::create_one_sided_only())?;
+            let payment_id = self.with_sender_address(recipient.payment_id);
+            let sender_offset_key_id = self
+                .resources
+                .transaction_key_manager_service
+                .get_next_key(TransactionKeyManagerBranch::OneSidedSenderOffset.get_branch_key())
+                .await?
+                .key_id;
+            let output = self
+                .create_one_sided_output(
+                    tx_id,
+                    &recipient.address,
+                    recipient.amount,
+                    OutputFeatures::default(),
+                    Some(push_pubkey_script(recipient.address.public_spend_key())),
+                    &sender_offset_key_id,
+                    payment_id.clone(),
+                )
+                .await?;
+            outputs.push((output, sender_offset_key_id));
+            total_amount += recipient.amount;
+            sent_recipients.push(BatchPaymentRecipient {
+                address: recipient.address,
+                amount: recipient.amount,
+                payment_id,
+            });
+        }
+
+        let mut stp = self
+            .resources
+            .output_manager_service
+            .prepare_batch_transaction_to_send(tx_id, outputs, selection_criteria, fee_per_gram, message.clone())
+            .await?;
+        stp.finalize(&self.resources.transaction_key_manager_service)
+            .await
+            .map_err(|e| {
+                error!(
+                    target: LOG_TARGET,
+                    "Batch transaction (TxId: {}) could not be finalized. Failure error: {:?}", tx_id, e,
+                );
+                TransactionServiceProtocolError::new(tx_id, e.into())
+            })?;
+        info!(
+            target: LOG_TARGET,
+            "Finalized batch transaction TxId: {} paying {} to {} recipients",
+            tx_id,
+            total_amount,
+            sent_recipients.len()
+        );
+
+        // This event being sent is important, but not critical to the protocol being successful. Send only fails if
+        // there are no subscribers.
+        let _result = self
+            .event_publisher
+            .send(Arc::new(TransactionEvent::TransactionCompletedImmediately(tx_id)));
+
+        let tx = stp
+            .get_transaction()
+            .map_err(|e| TransactionServiceProtocolError::new(tx_id, e.into()))?;
+        let fee = stp
+            .get_fee_amount()
+            .map_err(|e| TransactionServiceProtocolError::new(tx_id, e.into()))?;
+
+        self.resources
+            .output_manager_service
+            .confirm_pending_transaction(tx_id)
+            .await
+            .map_err(|e| TransactionServiceProtocolError::new(tx_id, e.into()))?;
+        self.submit_transaction(
+            transaction_broadcast_join_handles,
+            CompletedTransaction::new(
+                tx_id,
+                self.resources.one_sided_tari_address.clone(),
+                first_recipient,
+                total_amount,
+                fee,
+                tx.clone(),
+                TransactionStatus::Completed,
+                message,
+                Utc::now().naive_utc(),
+                TransactionDirection::Outbound,
+                None,
+                None,
+                None,
+            )?,
+        )
+        .await?;
+        self.db
+            .insert_batch_payment_recipients(tx_id, sent_recipients.clone())?;
+
+        for recipient in sent_recipients {
+            tokio::spawn(send_finalized_transaction_message(
+                tx_id,
+                tx.clone(),
+                recipient.address.comms_public_key().clone(),
+                self.resources.outbound_message_service.clone(),
+                self.resources.config.direct_send_timeout,
+                self.resources.config.transaction_routing_mechanism,
+            ));
+        }
+
+        Ok(tx_id)
+    }
+
+    #[allow(clippy::too_many_lines)]
+    async fn scrape_wallet(
+        &mut self,
+        dest_address: TariAddress,
+        fee_per_gram: MicroMinotari,
+        transaction_broadcast_join_handles: &mut FuturesUnordered<
+            JoinHandle<Result<TxId, TransactionServiceProtocolError<TxId>>>,
+        >,
+    ) -> Result<TxId, TransactionServiceError> {
+        let tx_id = TxId::new_random();
         let payment_id = PaymentId::Address(self.resources.interactive_tari_address.clone());
         self.verify_send(&dest_address, TariAddressFeatures::create_one_sided_only())?;
 
@@ -2462,6 +2871,173 @@ where
         .await
     }
 
+    /// Builds a one-sided transaction that is signed by another wallet holding the spend key. The transaction is held
+    /// as a pending outbound transaction, with its inputs encumbered, until the signed transaction is submitted.
+    pub async fn create_one_sided_transaction_for_offline_signing(
+        &mut self,
+        destination: TariAddress,
+        amount: MicroMinotari,
+        selection_criteria: UtxoSelectionCriteria,
+        fee_per_gram: MicroMinotari,
+        message: String,
+        payment_id: PaymentId,
+    ) -> Result<TxId, TransactionServiceError> {
+        let tx_id = TxId::new_random();
+        let dest_pubkey = destination.public_spend_key().clone();
+        let (stp, _payment_id) = self
+            .prepare_one_sided_or_stealth(
+                tx_id,
+                destination.clone(),
+                amount,
+                selection_criteria,
+                OutputFeatures::default(),
+                fee_per_gram,
+                message.clone(),
+                Some(push_pubkey_script(&dest_pubkey)),
+                payment_id,
+            )
+            .await?;
+        self.resources
+            .output_manager_service
+            .confirm_pending_transaction(tx_id)
+            .await?;
+
+        let fee = stp.get_fee_amount()?;
+        let mut outbound_tx = OutboundTransaction::new(
+            tx_id,
+            destination,
+            amount,
+            fee,
+            stp,
+            TransactionStatus::Pending,
+            message,
+            Utc::now().naive_utc(),
+            false,
+        );
+        outbound_tx.offline_signing = Some(PaymentKind::OneSided);
+        self.db.add_pending_outbound_transaction(tx_id, outbound_tx)?;
+        info!(
+            target: LOG_TARGET,
+            "One-sided transaction TxId: {} is awaiting an offline signature", tx_id
+        );
+
+        Ok(tx_id)
+    }
+
+    /// Returns a pending outbound transaction that is ready to be signed by the wallet holding the spend key
+    fn get_partially_signed_transaction(
+        &self,
+        tx_id: TxId,
+    ) -> Result<PartiallySignedTransaction, TransactionServiceError> {
+        let outbound_tx = self.db.get_pending_outbound_transaction(tx_id)?;
+        let kind = outbound_tx
+            .offline_signing
+            .ok_or(TransactionServiceError::NotAwaitingSignature(tx_id))?;
+        Ok(PartiallySignedTransaction::new(
+            self.resources.interactive_tari_address.network(),
+            kind,
+            outbound_tx.destination_address,
+            outbound_tx.message,
+            outbound_tx.sender_protocol,
+        )?)
+    }
+
+    /// Signs a transaction created by a wallet derived from the same seed as this one
+    async fn sign_partially_signed_transaction(
+        &self,
+        mut tx: PartiallySignedTransaction,
+    ) -> Result<PartiallySignedTransaction, TransactionServiceError> {
+        tx.verify_format(self.resources.interactive_tari_address.network())?;
+        tx.sign(&self.resources.transaction_key_manager_service).await?;
+        info!(
+            target: LOG_TARGET,
+            "Signed {} payment TxId: {} of {} to {}", tx.kind, tx.tx_id, tx.amount, tx.recipient
+        );
+        Ok(tx)
+    }
+
+    /// Completes a pending outbound transaction with the transaction signed by the offline wallet and broadcasts it.
+    /// The signed transaction must be the stored unsigned transaction with valid signatures added.
+    async fn submit_signed_transaction(
+        &mut self,
+        signed: PartiallySignedTransaction,
+        transaction_broadcast_join_handles: &mut FuturesUnordered<
+            JoinHandle<Result<TxId, TransactionServiceProtocolError<TxId>>>,
+        >,
+    ) -> Result<(), TransactionServiceError> {
+        signed.verify_format(self.resources.interactive_tari_address.network())?;
+        let tx_id = signed.tx_id;
+        let outbound_tx = self.db.get_pending_outbound_transaction(tx_id)?;
+        let kind = outbound_tx
+            .offline_signing
+            .ok_or(TransactionServiceError::NotAwaitingSignature(tx_id))?;
+        let tx = signed.transaction()?;
+        if kind != signed.kind || tx.body.get_total_fee()? != outbound_tx.fee {
+            return Err(PartiallySignedTransactionError::DetailsMismatch(format!(
+                "TxId {} was created as a {} payment with fee {}",
+                tx_id, kind, outbound_tx.fee
+            ))
+            .into());
+        }
+        outbound_tx
+            .sender_protocol
+            .verify_finalized_transaction(tx, &self.resources.transaction_key_manager_service)
+            .await
+            .map_err(|e| PartiallySignedTransactionError::DetailsMismatch(e.to_string()))?;
+        signed.verify_signatures(&self.resources.factories.commitment)?;
+        let payment_id = match kind {
+            PaymentKind::OneSided => Some(
+                self.get_partially_signed_transaction(tx_id)?
+                    .verify_one_sided_recipient_output(&self.resources.transaction_key_manager_service)
+                    .await?,
+            ),
+            PaymentKind::Interactive => None,
+        };
+
+        let source_address = match kind {
+            PaymentKind::OneSided => self.resources.one_sided_tari_address.clone(),
+            PaymentKind::Interactive => self.resources.interactive_tari_address.clone(),
+        };
+        let completed_tx = CompletedTransaction::new(
+            tx_id,
+            source_address,
+            outbound_tx.destination_address.clone(),
+            outbound_tx.amount,
+            outbound_tx.fee,
+            tx.clone(),
+            TransactionStatus::Completed,
+            outbound_tx.message,
+            Utc::now().naive_utc(),
+            TransactionDirection::Outbound,
+            None,
+            None,
+            payment_id,
+        )?;
+        check_transaction_size(&completed_tx.transaction, tx_id)?;
+        self.db.complete_outbound_transaction(tx_id, completed_tx)?;
+        info!(target: LOG_TARGET, "Signed transaction TxId: {} submitted", tx_id);
+        let _size = self
+            .event_publisher
+            .send(Arc::new(TransactionEvent::TransactionCompletedImmediately(tx_id)));
+
+        tokio::spawn(send_finalized_transaction_message(
+            tx_id,
+            tx,
+            outbound_tx.destination_address.comms_public_key().clone(),
+            self.resources.outbound_message_service.clone(),
+            self.resources.config.direct_send_timeout,
+            self.resources.config.transaction_routing_mechanism,
+        ));
+        self.complete_send_transaction_protocol(
+            Ok(TransactionSendResult {
+                tx_id,
+                transaction_status: TransactionStatus::Completed,
+            }),
+            transaction_broadcast_join_handles,
+        );
+        Ok(())
+    }
+
     /// Accept the public reply from a recipient and apply the reply to the relevant transaction protocol
     /// # Arguments
     /// 'recipient_reply' - The public response from a recipient with data required to complete the transaction
@@ -2615,6 +3191,17 @@ where
                 if val.transaction_status != TransactionStatus::Queued {
                     let _sender = self.pending_transaction_reply_senders.remove(&val.tx_id);
                     let _sender = self.send_transaction_cancellation_senders.remove(&val.tx_id);
+                    if self
+                        .db
+                        .get_pending_outbound_transaction(val.tx_id)
+                        .is_ok_and(|tx| tx.offline_signing.is_some())
+                    {
+                        debug!(
+                            target: LOG_TARGET,
+                            "Send Transaction Protocol for TxId: {} is awaiting an offline signature", val.tx_id
+                        );
+                        return;
+                    }
                     let completed_tx = match self.db.get_completed_transaction(val.tx_id) {
                         Ok(v) => v,
                         Err(e) => {
@@ -2704,6 +3291,140 @@ where
         Ok(())
     }
 
+    /// Replace an unconfirmed one-sided outbound transaction with a one-sided transaction to the same destination that
+    /// spends the same inputs at a higher fee per gram. Base nodes replace the original in their mempools as the
+    /// replacement conflicts with it and pays a higher fee. Interactive transactions cannot be replaced as the
+    /// recipient took part in building them. The original is only cancelled once the replacement has been sent; if
+    /// sending fails the original stays in place.
+    async fn bump_fee(
+        &mut self,
+        tx_id: TxId,
+        fee_per_gram: MicroMinotari,
+        transaction_broadcast_join_handles: &mut FuturesUnordered<
+            JoinHandle<Result<TxId, TransactionServiceProtocolError<TxId>>>,
+        >,
+    ) -> Result<TxId, TransactionServiceError> {
+        let original = self.db.get_completed_transaction_cancelled_or_not(tx_id)?;
+        if original.cancelled.is_some() ||
+            original.direction != TransactionDirection::Outbound ||
+            !matches!(
+                original.status,
+                TransactionStatus::Completed | TransactionStatus::Broadcast
+            ) ||
+            original.mined_height.is_some()
+        {
+            return Err(TransactionServiceError::FeeBumpNotPossible(format!(
+                "Transaction {} is not an unconfirmed outbound transaction (status: {})",
+                tx_id, original.status
+            )));
+        }
+        if original.source_address != self.resources.one_sided_tari_address {
+            return Err(TransactionServiceError::FeeBumpNotPossible(format!(
+                "Transaction {} is an interactive transaction, only one-sided transactions can be replaced",
+                tx_id
+            )));
+        }
+        self.verify_send(
+            &original.destination_address,
+            TariAddressFeatures::create_one_sided_only(),
+        )?;
+
+        let tip_height = self.last_seen_tip_height.unwrap_or(0);
+        let weighting = self
+            .consensus_manager
+            .consensus_constants(tip_height)
+            .transaction_weight_params();
+        let weight = original.transaction.calculate_weight(weighting)?;
+        let current_fee_per_gram = MicroMinotari(original.fee.as_u64() / weight.max(1));
+        if fee_per_gram <= current_fee_per_gram {
+            return Err(TransactionServiceError::FeeBumpNotPossible(format!(
+                "Fee per gram {} must be higher than the current fee per gram {}",
+                fee_per_gram, current_fee_per_gram
+            )));
+        }
+        let spent_value = self
+            .resources
+            .output_manager_service
+            .get_output_info_for_tx_id(tx_id)
+            .await?
+            .spent_value;
+        // Everything left over after the amount and fee went to a change output, all other outputs pay recipients
+        let num_change_outputs = usize::from(spent_value > original.amount + original.fee);
+        if original.transaction.body.outputs().len() > num_change_outputs + 1 {
+            return Err(TransactionServiceError::FeeBumpNotPossible(format!(
+                "Transaction {} pays more than one recipient",
+                tx_id
+            )));
+        }
+        let required = original.amount + weight * fee_per_gram;
+        if spent_value < required {
+            return Err(TransactionServiceError::FeeBumpNotPossible(format!(
+                "The inputs of transaction {} ({}) cannot cover the amount and the higher fee ({})",
+                tx_id, spent_value, required
+            )));
+        }
+
+        // The original inputs are released so that the replacement can spend them, and are encumbered by the original
+        // again if the replacement cannot be sent
+        let commitments = original
+            .transaction
+            .body
+            .inputs()
+            .iter()
+            .map(|input| input.commitment().cloned())
+            .collect::<Result<Vec<_>, _>>()?;
+        self.resources.output_manager_service.cancel_transaction(tx_id).await?;
+        let replacement_tx_id = match self
+            .send_one_sided_to_stealth_address_transaction(
+                original.destination_address,
+                original.amount,
+                UtxoSelectionCriteria::specific(commitments.clone()),
+                OutputFeatures::default(),
+                fee_per_gram,
+                original.message,
+                original.payment_id.unwrap_or(PaymentId::Empty),
+                transaction_broadcast_join_handles,
+            )
+            .await
+        {
+            Ok(replacement_tx_id) => replacement_tx_id,
+            Err(e) => {
+                if let Err(reinstate_error) = self
+                    .resources
+                    .output_manager_service
+                    .reinstate_cancelled_outbound_transaction_outputs(tx_id, commitments)
+                    .await
+                {
+                    error!(
+                        target: LOG_TARGET,
+                        "Failed to reinstate the outputs of transaction (TxId: {}) after its replacement could not be \
+                         sent: {}",
+                        tx_id,
+                        reinstate_error
+                    );
+                }
+                return Err(e);
+            },
+        };
+
+        self.db
+            .reject_completed_transaction(tx_id, TxCancellationReason::Replaced)?;
+        let _size = self
+            .event_publisher
+            .send(Arc::new(TransactionEvent::TransactionCancelled(
+                tx_id,
+                TxCancellationReason::Replaced,
+            )));
+        info!(
+            target: LOG_TARGET,
+            "Transaction (TxId: {}) replaced by transaction (TxId: {}) at {} per gram",
+            tx_id,
+            replacement_tx_id,
+            fee_per_gram
+        );
+        Ok(replacement_tx_id)
+    }
+
     /// Handle a Transaction Cancelled message received from the Comms layer
     pub async fn handle_transaction_cancelled_message(
         &mut self,
@@ -2740,7 +3461,7 @@ where
     }
 
     #[allow(clippy::map_entry)]
-    fn restart_all_send_transaction_protocols(
+    async fn restart_all_send_transaction_protocols(
         &mut self,
         join_handles: &mut FuturesUnordered<
             JoinHandle<Result<TransactionSendResult, TransactionServiceProtocolError<TxId>>>,
@@ -2748,6 +3469,13 @@ where
     ) -> Result<(), TransactionServiceError> {
         let outbound_txs = self.db.get_pending_outbound_transactions()?;
         for (tx_id, tx) in outbound_txs {
+            if tx.offline_signing.is_some() && tx.sender_protocol.is_finalizing() {
+                trace!(
+                    target: LOG_TARGET,
+                    "Pending Outbound Transaction TxId: {} is awaiting an offline signature", tx_id
+                );
+                continue;
+            }
             let (sender_protocol, stage) = if tx.send_count > 0 {
                 (None, TransactionSendProtocolStage::WaitForReply)
             } else {
@@ -2780,6 +3508,13 @@ where
                 self.pending_transaction_reply_senders.insert(tx_id, tx_reply_sender);
                 self.send_transaction_cancellation_senders
                     .insert(tx_id, cancellation_sender);
+                // Keep the restarted transaction in the account its inputs were selected from
+                let account = self
+                    .resources
+                    .output_manager_service
+                    .get_output_info_for_tx_id(tx_id)
+                    .await?
+                    .account;
 
                 let protocol = TransactionSendProtocol::new(
                     tx_id,
@@ -2788,9 +3523,11 @@ where
                     cancellation_receiver,
                     tx.destination_address,
                     tx.amount,
+                    UtxoSelectionCriteria::default().for_account(account),
                     tx.fee,
                     tx.message,
                     TransactionMetadata::default(),
+                    tx.offline_signing.is_some(),
                     None,
                     stage,
                     sender_protocol,
@@ -3206,7 +3943,7 @@ where
         }
     }
 
-    fn restart_transaction_negotiation_protocols(
+    async fn restart_transaction_negotiation_protocols(
         &mut self,
         send_transaction_join_handles: &mut FuturesUnordered<
             JoinHandle<Result<TransactionSendResult, TransactionServiceProtocolError<TxId>>>,
@@ -3217,6 +3954,7 @@ where
     ) -> Result<(), TransactionServiceError> {
         trace!(target: LOG_TARGET, "Restarting transaction negotiation protocols");
         self.restart_all_send_transaction_protocols(send_transaction_join_handles)
+            .await
             .map_err(|resp| {
                 error!(
                     target: LOG_TARGET,
@@ -3237,6 +3975,328 @@ where
         Ok(())
     }
 
+    async fn create_scheduled_payment(
+        &mut self,
+        destination: TariAddress,
+        amount: MicroMinotari,
+        fee_per_gram: MicroMinotari,
+        message: String,
+        kind: PaymentKind,
+        schedule: PaymentSchedule,
+        account: Option<AccountId>,
+    ) -> Result<ScheduledPayment, TransactionServiceError> {
+        let sending_method = match kind {
+            PaymentKind::OneSided => TariAddressFeatures::create_one_sided_only(),
+            PaymentKind::Interactive => TariAddressFeatures::create_interactive_only(),
+        };
+        self.verify_send(&destination, sending_method)?;
+        if amount == MicroMinotari::zero() {
+            return Err(TransactionServiceError::InvalidPaymentSchedule(
+                "The amount must be greater than zero".to_string(),
+            ));
+        }
+        if let Some(account) = account {
+            let accounts = self.resources.output_manager_service.get_accounts().await?;
+            if !accounts.iter().any(|a| a.id == account) {
+                return Err(TransactionServiceError::InvalidPaymentSchedule(format!(
+                    "Account {} does not exist",
+                    account
+                )));
+            }
+        }
+        let now = Utc::now().naive_utc();
+        let next_run_at = schedule.next_run_after(now).ok_or_else(|| {
+            TransactionServiceError::InvalidPaymentSchedule(format!("Payment scheduled {} is never due", schedule))
+        })?;
+        let payment = self.db.insert_scheduled_payment(ScheduledPayment {
+            id: 0,
+            destination,
+            amount,
+            fee_per_gram,
+            message,
+            kind,
+            schedule,
+            next_run_at: Some(next_run_at),
+            created_at: now,
+            cancelled: false,
+            account,
+        })?;
+        info!(
+            target: LOG_TARGET,
+            "Scheduled payment {} of {} to {}, {}, next due at {}",
+            payment.id,
+            payment.amount,
+            payment.destination,
+            payment.schedule,
+            next_run_at
+        );
+        Ok(payment)
+    }
+
+    /// Issues a payment request to the one-sided address of this wallet, signed with the spend key behind that address
+    async fn create_payment_request(
+        &self,
+        amount: MicroMinotari,
+        memo: String,
+        payment_id: Option<PaymentId>,
+        expires_at: Option<NaiveDateTime>,
+    ) -> Result<IssuedPaymentRequest, TransactionServiceError> {
+        if self.resources.wallet_type.is_watch_only() {
+            return Err(TransactionServiceError::InvalidPaymentRequest(
+                "A watch-only wallet cannot sign payment requests".to_string(),
+            ));
+        }
+        if amount == MicroMinotari::zero() {
+            return Err(TransactionServiceError::InvalidPaymentRequest(
+                "The amount must be greater than zero".to_string(),
+            ));
+        }
+        let now = Utc::now().naive_utc();
+        if expires_at.map_or(false, |expires_at| expires_at <= now) {
+            return Err(TransactionServiceError::InvalidPaymentRequest(
+                "The expiry time must be in the future".to_string(),
+            ));
+        }
+        let payment_id = payment_id.unwrap_or_else(|| PaymentId::U64(OsRng.next_u64()));
+        if payment_id == PaymentId::Empty {
+            return Err(TransactionServiceError::InvalidPaymentRequest(
+                "A payment id is needed to match payments to the request".to_string(),
+            ));
+        }
+
+        let key_manager = &self.resources.transaction_key_manager_service;
+        let address = self.resources.one_sided_tari_address.clone();
+        let spend_key = key_manager.get_spend_key().await?;
+        if spend_key.pub_key != *address.public_spend_key() {
+            return Err(TransactionServiceError::InvalidPaymentRequest(
+                "The spend key of this wallet is not the spend key of its one-sided address".to_string(),
+            ));
+        }
+        let nonce = key_manager.get_random_key().await?;
+        let challenge =
+            PaymentRequest::signature_challenge(&address, amount, &payment_id, &memo, expires_at, &nonce.pub_key);
+        let signature = key_manager
+            .sign_with_nonce_and_challenge(&spend_key.key_id, &nonce.key_id, &challenge)
+            .await?;
+        let request = PaymentRequest::new(address, amount, payment_id.clone(), memo, expires_at, signature)?;
+        // The payment id is unique among the payment requests, so that payments can be matched to a single request
+        let issued = match self.db.insert_payment_request(IssuedPaymentRequest {
+            id: 0,
+            request,
+            status: PaymentRequestStatus::Open,
+            amount_received: MicroMinotari::zero(),
+            created_at: now,
+        }) {
+            Ok(issued) => issued,
+            Err(TransactionStorageError::DieselError(DieselError::DatabaseError(
+                DatabaseErrorKind::UniqueViolation,
+                _,
+            ))) => {
+                return Err(TransactionServiceError::InvalidPaymentRequest(format!(
+                    "Payment id {} is already used by another payment request",
+                    payment_id
+                )));
+            },
+            Err(e) => return Err(e.into()),
+        };
+        info!(
+            target: LOG_TARGET,
+            "Issued payment request {} for {} with payment id {}",
+            issued.id,
+            issued.request.amount,
+            issued.request.payment_id
+        );
+        Ok(issued)
+    }
+
+    /// Matches the inbound transactions completed since the last update to the payment requests of this wallet by
+    /// their payment id and brings the status of the requests up to date. A request that was paid can fall back if one
+    /// of its payments is cancelled.
+    fn update_payment_requests(&self) -> Result<Vec<IssuedPaymentRequest>, TransactionServiceError> {
+        let updated = self.db.update_payment_request_payments()?;
+        if !updated.is_empty() {
+            debug!(
+                target: LOG_TARGET,
+                "Payments received for payment requests {:?} have changed", updated
+            );
+        }
+
+        let mut requests = self.db.get_payment_requests()?;
+        let now = Utc::now().naive_utc();
+        for request in &mut requests {
+            let status = request.status_for(request.amount_received, now);
+            if status == request.status {
+                continue;
+            }
+            self.db
+                .update_payment_request_status(request.id, status, request.amount_received)?;
+            if status == PaymentRequestStatus::Paid {
+                info!(
+                    target: LOG_TARGET,
+                    "Payment request {} has been paid, received {}", request.id, request.amount_received
+                );
+            }
+            request.status = status;
+        }
+        Ok(requests)
+    }
+
+    /// Sends the scheduled payments that are due. Nothing is sent while the wallet is offline, and a payment that is
+    /// not covered by the available balance of its account, including the estimated fee, stays due until it is. The
+    /// schedule only moves on once a payment has been sent, so a payment that could not be sent is retried. Runs missed
+    /// while the wallet was offline are not made up; a recurring payment is sent once and moves on to its next run
+    /// after now.
+    async fn send_due_scheduled_payments(
+        &mut self,
+        send_transaction_join_handles: &mut FuturesUnordered<
+            JoinHandle<Result<TransactionSendResult, TransactionServiceProtocolError<TxId>>>,
+        >,
+        transaction_broadcast_join_handles: &mut FuturesUnordered<
+            JoinHandle<Result<TxId, TransactionServiceProtocolError<TxId>>>,
+        >,
+    ) -> Result<(), TransactionServiceError> {
+        let now = Utc::now().naive_utc();
+        let due = self.db.get_due_scheduled_payments(now)?;
+        if due.is_empty() {
+            return Ok(());
+        }
+        if !self.connectivity().is_base_node_set() ||
+            self.resources.connectivity.clone().get_connectivity_status() != OnlineStatus::Online
+        {
+            debug!(
+                target: LOG_TARGET,
+                "{} scheduled payment(s) are due but the wallet is offline",
+                due.len()
+            );
+            return Ok(());
+        }
+
+        for payment in due {
+            let selection_criteria = UtxoSelectionCriteria::default().for_account(payment.account);
+            if !self.is_scheduled_payment_funded(&payment, &selection_criteria).await? {
+                continue;
+            }
+            info!(
+                target: LOG_TARGET,
+                "Sending scheduled payment {} of {} to {}", payment.id, payment.amount, payment.destination
+            );
+            let next_run_at = payment.schedule.next_run_after(now);
+            let mut run = ScheduledPaymentRun {
+                scheduled_payment_id: payment.id,
+                run_at: now,
+                tx_id: None,
+                error: None,
+            };
+            let sent = match payment.kind {
+                PaymentKind::OneSided => self
+                    .send_one_sided_transaction(
+                        payment.destination,
+                        payment.amount,
+                        selection_criteria,
+                        OutputFeatures::default(),
+                        payment.fee_per_gram,
+                        payment.message,
+                        PaymentId::Empty,
+                        transaction_broadcast_join_handles,
+                    )
+                    .await
+                    .map(Some),
+                PaymentKind::Interactive => {
+                    let (reply_tx, reply_rx) = oneshot::channel();
+                    let result = self
+                        .send_transaction(
+                            payment.destination,
+                            payment.amount,
+                            selection_criteria,
+                            OutputFeatures::default(),
+                            payment.fee_per_gram,
+                            payment.message,
+                            TransactionMetadata::default(),
+                            false,
+                            send_transaction_join_handles,
+                            transaction_broadcast_join_handles,
+                            reply_tx,
+                        )
+                        .await;
+                    if result.is_ok() {
+                        // The transaction has been created and its inputs encumbered. The reply is only sent once
+                        // the initial send attempt to the recipient has completed.
+                        let mut run = run.clone();
+                        let db = self.db.clone();
+                        tokio::spawn(async move {
+                            match reply_rx.await {
+                                Ok(Ok(TransactionServiceResponse::TransactionSent(tx_id))) => run.tx_id = Some(tx_id),
+                                Ok(Ok(_)) => {
+                                    run.error = Some(TransactionServiceError::UnexpectedApiResponse.to_string())
+                                },
+                                Ok(Err(e)) => run.error = Some(e.to_string()),
+                                Err(e) => run.error = Some(e.to_string()),
+                            }
+                            if let Err(e) = db.insert_scheduled_payment_run(run) {
+                                warn!(target: LOG_TARGET, "Could not record scheduled payment run: {}", e);
+                            }
+                        });
+                    }
+                    result.map(|_| None)
+                },
+            };
+            match sent {
+                Ok(tx_id) => {
+                    self.db.set_scheduled_payment_next_run(payment.id, next_run_at)?;
+                    if let Some(tx_id) = tx_id {
+                        run.tx_id = Some(tx_id);
+                        self.db.insert_scheduled_payment_run(run)?;
+                    }
+                },
+                Err(e) => {
+                    warn!(
+                        target: LOG_TARGET,
+                        "Scheduled payment {} could not be sent and stays due: {}", payment.id, e
+                    );
+                    run.error = Some(e.to_string());
+                    self.db.insert_scheduled_payment_run(run)?;
+                },
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns true if the available balance of the account a scheduled payment is spent from covers its amount and the
+    /// estimated fee
+    async fn is_scheduled_payment_funded(
+        &mut self,
+        payment: &ScheduledPayment,
+        selection_criteria: &UtxoSelectionCriteria,
+    ) -> Result<bool, TransactionServiceError> {
+        let balance = match payment.account {
+            Some(account) => {
+                self.resources
+                    .output_manager_service
+                    .get_account_balance(account)
+                    .await?
+            },
+            None => self.resources.output_manager_service.get_balance().await?,
+        };
+        let fee = self
+            .resources
+            .output_manager_service
+            .fee_estimate(payment.amount, selection_criteria.clone(), payment.fee_per_gram, 1, 1)
+            .await?;
+        let required = payment.amount.saturating_add(fee);
+        if required > balance.available_balance {
+            debug!(
+                target: LOG_TARGET,
+                "Scheduled payment {} of {} (fee {}) is due but the available balance is {}",
+                payment.id,
+                payment.amount,
+                fee,
+                balance.available_balance
+            );
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
     async fn start_transaction_revalidation(
         &mut self,
         join_handles: &mut FuturesUnordered<
@@ -38,15 +38,23 @@ use tari_common_types::{
 use tari_core::transactions::{
     tari_amount::MicroMinotari,
     transaction_components::{encrypted_data::PaymentId, Transaction, TransactionOutput},
+    transaction_protocol::sender::SenderTransactionProtocol,
 };
 
 use crate::transaction_service::{
     error::TransactionStorageError,
     storage::{
         models::{
+            BatchPaymentRecipient,
             CompletedTransaction,
             InboundTransaction,
+            IssuedPaymentRequest,
             OutboundTransaction,
+            PaymentRequestStatus,
+            ScheduledPayment,
+            ScheduledPaymentRun,
+            TransactionHistoryFilter,
+            TransactionHistoryRecord,
             TxCancellationReason,
             WalletTransaction,
         },
@@ -124,6 +132,12 @@ pub trait TransactionBackend: Send + Sync + Clone {
     ) -> Result<TariAddress, TransactionStorageError>;
     /// Mark a pending transaction direct send attempt as a success
     fn mark_direct_send_success(&self, tx_id: TxId) -> Result<(), TransactionStorageError>;
+    /// Replace the sender protocol of a pending outbound transaction
+    fn update_pending_outbound_sender_protocol(
+        &self,
+        tx_id: TxId,
+        sender_protocol: SenderTransactionProtocol,
+    ) -> Result<(), TransactionStorageError>;
     /// Increment the send counter and timestamp of a transaction
     fn increment_send_count(&self, tx_id: TxId) -> Result<(), TransactionStorageError>;
     /// Update a transactions mined height. A transaction can either be mined as valid or mined as invalid
@@ -157,6 +171,56 @@ pub trait TransactionBackend: Send + Sync + Clone {
         &self,
         height: u64,
     ) -> Result<Vec<CompletedTransaction>, TransactionStorageError>;
+    /// Store a new scheduled payment, replacing its id with the next unused id
+    fn insert_scheduled_payment(&self, payment: ScheduledPayment) -> Result<ScheduledPayment, TransactionStorageError>;
+    /// Fetch all scheduled payments, including cancelled and finished ones
+    fn fetch_scheduled_payments(&self) -> Result<Vec<ScheduledPayment>, TransactionStorageError>;
+    /// Fetch the scheduled payments that are not cancelled and due to run at or before `now`
+    fn fetch_due_scheduled_payments(
+        &self,
+        now: NaiveDateTime,
+    ) -> Result<Vec<ScheduledPayment>, TransactionStorageError>;
+    fn cancel_scheduled_payment(&self, id: u64) -> Result<(), TransactionStorageError>;
+    fn set_scheduled_payment_next_run(
+        &self,
+        id: u64,
+        next_run_at: Option<NaiveDateTime>,
+    ) -> Result<(), TransactionStorageError>;
+    fn insert_scheduled_payment_run(&self, run: ScheduledPaymentRun) -> Result<(), TransactionStorageError>;
+    fn fetch_scheduled_payment_runs(&self, id: u64) -> Result<Vec<ScheduledPaymentRun>, TransactionStorageError>;
+    /// Fetch the pending, completed and cancelled transactions selected by `filter`, in the order of the history
+    fn fetch_transaction_history(
+        &self,
+        filter: &TransactionHistoryFilter,
+    ) -> Result<Vec<TransactionHistoryRecord>, TransactionStorageError>;
+    /// Store the recipients of a batch payment transaction
+    fn insert_batch_payment_recipients(
+        &self,
+        tx_id: TxId,
+        recipients: Vec<BatchPaymentRecipient>,
+    ) -> Result<(), TransactionStorageError>;
+    /// Fetch the recipients of a batch payment transaction, in the order they were paid
+    fn fetch_batch_payment_recipients(
+        &self,
+        tx_id: TxId,
+    ) -> Result<Vec<BatchPaymentRecipient>, TransactionStorageError>;
+    /// Store a new payment request, replacing its id with the next unused id
+    fn insert_payment_request(
+        &self,
+        request: IssuedPaymentRequest,
+    ) -> Result<IssuedPaymentRequest, TransactionStorageError>;
+    /// Fetch all payment requests, oldest first
+    fn fetch_payment_requests(&self) -> Result<Vec<IssuedPaymentRequest>, TransactionStorageError>;
+    fn update_payment_request_status(
+        &self,
+        id: u64,
+        status: PaymentRequestStatus,
+        amount_received: MicroMinotari,
+    ) -> Result<(), TransactionStorageError>;
+    /// Match the inbound transactions completed since the last call to the payment requests by their payment id, drop
+    /// the payments of transactions cancelled since and update the amount received of the affected requests. Returns
+    /// the ids of the affected requests.
+    fn update_payment_request_payments(&self) -> Result<Vec<u64>, TransactionStorageError>;
 }
 
 #[derive(Clone, PartialEq)]
@@ -599,6 +663,14 @@ where T: TransactionBackend + 'static
         self.get_completed_transactions_by_cancelled(true)
     }
 
+    /// Returns the transactions selected by `filter`, oldest first
+    pub fn get_transaction_history(
+        &self,
+        filter: &TransactionHistoryFilter,
+    ) -> Result<Vec<TransactionHistoryRecord>, TransactionStorageError> {
+        self.db.fetch_transaction_history(filter)
+    }
+
     pub fn get_any_transaction(&self, tx_id: TxId) -> Result<Option<WalletTransaction>, TransactionStorageError> {
         let key = DbKey::AnyTransaction(tx_id);
         let t = match self.db.fetch(&key) {
@@ -678,6 +750,14 @@ where T: TransactionBackend + 'static
         self.db.mark_direct_send_success(tx_id)
     }
 
+    pub fn update_pending_outbound_sender_protocol(
+        &self,
+        tx_id: TxId,
+        sender_protocol: SenderTransactionProtocol,
+    ) -> Result<(), TransactionStorageError> {
+        self.db.update_pending_outbound_sender_protocol(tx_id, sender_protocol)
+    }
+
     /// Indicated that the specified completed transaction has been broadcast into the mempool
     pub fn broadcast_completed_transaction(&self, tx_id: TxId) -> Result<(), TransactionStorageError> {
         self.db.broadcast_completed_transaction(tx_id)
@@ -773,6 +853,83 @@ where T: TransactionBackend + 'static
         }?;
         Ok(t)
     }
+
+    pub fn insert_scheduled_payment(
+        &self,
+        payment: ScheduledPayment,
+    ) -> Result<ScheduledPayment, TransactionStorageError> {
+        self.db.insert_scheduled_payment(payment)
+    }
+
+    pub fn get_scheduled_payments(&self) -> Result<Vec<ScheduledPayment>, TransactionStorageError> {
+        self.db.fetch_scheduled_payments()
+    }
+
+    pub fn get_due_scheduled_payments(
+        &self,
+        now: NaiveDateTime,
+    ) -> Result<Vec<ScheduledPayment>, TransactionStorageError> {
+        self.db.fetch_due_scheduled_payments(now)
+    }
+
+    pub fn cancel_scheduled_payment(&self, id: u64) -> Result<(), TransactionStorageError> {
+        self.db.cancel_scheduled_payment(id)
+    }
+
+    pub fn set_scheduled_payment_next_run(
+        &self,
+        id: u64,
+        next_run_at: Option<NaiveDateTime>,
+    ) -> Result<(), TransactionStorageError> {
+        self.db.set_scheduled_payment_next_run(id, next_run_at)
+    }
+
+    pub fn insert_scheduled_payment_run(&self, run: ScheduledPaymentRun) -> Result<(), TransactionStorageError> {
+        self.db.insert_scheduled_payment_run(run)
+    }
+
+    pub fn get_scheduled_payment_runs(&self, id: u64) -> Result<Vec<ScheduledPaymentRun>, TransactionStorageError> {
+        self.db.fetch_scheduled_payment_runs(id)
+    }
+
+    pub fn insert_batch_payment_recipients(
+        &self,
+        tx_id: TxId,
+        recipients: Vec<BatchPaymentRecipient>,
+    ) -> Result<(), TransactionStorageError> {
+        self.db.insert_batch_payment_recipients(tx_id, recipients)
+    }
+
+    pub fn get_batch_payment_recipients(
+        &self,
+        tx_id: TxId,
+    ) -> Result<Vec<BatchPaymentRecipient>, TransactionStorageError> {
+        self.db.fetch_batch_payment_recipients(tx_id)
+    }
+
+    pub fn insert_payment_request(
+        &self,
+        request: IssuedPaymentRequest,
+    ) -> Result<IssuedPaymentRequest, TransactionStorageError> {
+        self.db.insert_payment_request(request)
+    }
+
+    pub fn get_payment_requests(&self) -> Result<Vec<IssuedPaymentRequest>, TransactionStorageError> {
+        self.db.fetch_payment_requests()
+    }
+
+    pub fn update_payment_request_status(
+        &self,
+        id: u64,
+        status: PaymentRequestStatus,
+        amount_received: MicroMinotari,
+    ) -> Result<(), TransactionStorageError> {
+        self.db.update_payment_request_status(id, status, amount_received)
+    }
+
+    pub fn update_payment_request_payments(&self) -> Result<Vec<u64>, TransactionStorageError> {
+        self.db.update_payment_request_payments()
+    }
 }
 
 impl Display for DbKey {
@@ -27,19 +27,25 @@ use std::{
 
 use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
+use strum_macros::{Display as StrumDisplay, EnumString};
 use tari_common_types::{
     tari_address::TariAddress,
     transaction::{TransactionConversionError, TransactionDirection, TransactionStatus, TxId},
-    types::{BlockHash, PrivateKey, Signature},
+    types::{BlockHash, Commitment, PrivateKey, Signature},
+    wallet_types::AccountId,
 };
 use tari_core::transactions::{
     tari_amount::MicroMinotari,
     transaction_components::{encrypted_data::PaymentId, Transaction},
+    transaction_protocol::partially_signed::PaymentKind,
     ReceiverTransactionProtocol,
     SenderTransactionProtocol,
 };
 
-use crate::transaction_service::error::TransactionStorageError;
+use crate::{
+    transaction_service::{error::TransactionStorageError, payment_request::PaymentRequest},
+    util::cron::CronSchedule,
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct InboundTransaction {
@@ -96,6 +102,8 @@ pub struct OutboundTransaction {
     pub direct_send_success: bool,
     pub send_count: u32,
     pub last_send_timestamp: Option<NaiveDateTime>,
+    /// Set if the transaction is signed by another wallet holding the spend key rather than finalized by this wallet
+    pub offline_signing: Option<PaymentKind>,
 }
 
 impl OutboundTransaction {
@@ -123,6 +131,7 @@ impl OutboundTransaction {
             direct_send_success,
             send_count: 0,
             last_send_timestamp: None,
+            offline_signing: None,
         }
     }
 }
@@ -231,6 +240,7 @@ impl From<CompletedTransaction> for OutboundTransaction {
             direct_send_success: false,
             send_count: 0,
             last_send_timestamp: None,
+            offline_signing: None,
         }
     }
 }
@@ -336,6 +346,7 @@ pub enum TxCancellationReason {
     TimeLocked,         // 5
     InvalidTransaction, // 6
     Oversized,          // 7
+    Replaced,           // 8
 }
 
 impl TryFrom<u32> for TxCancellationReason {
@@ -351,6 +362,7 @@ impl TryFrom<u32> for TxCancellationReason {
             5 => Ok(TxCancellationReason::TimeLocked),
             6 => Ok(TxCancellationReason::InvalidTransaction),
             7 => Ok(TxCancellationReason::Oversized),
+            8 => Ok(TxCancellationReason::Replaced),
             code => Err(TransactionConversionError { code: code as i32 }),
         }
     }
@@ -369,7 +381,307 @@ impl Display for TxCancellationReason {
             TimeLocked => "TimeLocked",
             InvalidTransaction => "Invalid Transaction",
             Oversized => "Oversized",
+            Replaced => "Replaced",
         };
         fmt.write_str(response)
     }
 }
+
+/// When a scheduled payment is sent
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PaymentSchedule {
+    /// Sent once at the given (UTC) time
+    Once(NaiveDateTime),
+    /// Sent every time the cron expression matches
+    Recurring(CronSchedule),
+}
+
+impl PaymentSchedule {
+    /// Returns the first time the payment is due strictly after `after`
+    pub fn next_run_after(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        match self {
+            PaymentSchedule::Once(at) => Some(*at).filter(|at| *at > after),
+            PaymentSchedule::Recurring(schedule) => schedule.next_after(after),
+        }
+    }
+}
+
+impl Display for PaymentSchedule {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> Result<(), Error> {
+        match self {
+            PaymentSchedule::Once(at) => write!(fmt, "once at {}", at),
+            PaymentSchedule::Recurring(schedule) => write!(fmt, "cron `{}`", schedule),
+        }
+    }
+}
+
+/// A payment the transaction service sends without user interaction once it is due
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScheduledPayment {
+    pub id: u64,
+    pub destination: TariAddress,
+    pub amount: MicroMinotari,
+    pub fee_per_gram: MicroMinotari,
+    pub message: String,
+    pub kind: PaymentKind,
+    pub schedule: PaymentSchedule,
+    /// `None` once the payment will not be sent again
+    pub next_run_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub cancelled: bool,
+    /// The account the payment is spent from, all accounts if `None`
+    pub account: Option<AccountId>,
+}
+
+/// A single recipient of a batch payment, which pays several recipients in one transaction
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BatchPaymentRecipient {
+    pub address: TariAddress,
+    pub amount: MicroMinotari,
+    pub payment_id: PaymentId,
+}
+
+/// The outcome of an attempt to send a scheduled payment
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScheduledPaymentRun {
+    pub scheduled_payment_id: u64,
+    pub run_at: NaiveDateTime,
+    pub tx_id: Option<TxId>,
+    pub error: Option<String>,
+}
+
+/// The state of a payment request this wallet issued
+#[derive(Debug, Clone, Copy, PartialEq, Eq, StrumDisplay)]
+#[strum(serialize_all = "snake_case")]
+pub enum PaymentRequestStatus {
+    /// Nothing has been received yet and the request has not expired
+    Open,
+    /// At least the requested amount has been received
+    Paid,
+    /// Less than the requested amount has been received
+    Underpaid,
+    /// Nothing was received before the request expired
+    Expired,
+}
+
+impl TryFrom<i32> for PaymentRequestStatus {
+    type Error = TransactionStorageError;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(PaymentRequestStatus::Open),
+            1 => Ok(PaymentRequestStatus::Paid),
+            2 => Ok(PaymentRequestStatus::Underpaid),
+            3 => Ok(PaymentRequestStatus::Expired),
+            v => Err(TransactionStorageError::UnexpectedResult(format!(
+                "Invalid payment request status {}",
+                v
+            ))),
+        }
+    }
+}
+
+impl From<PaymentRequestStatus> for i32 {
+    fn from(status: PaymentRequestStatus) -> Self {
+        match status {
+            PaymentRequestStatus::Open => 0,
+            PaymentRequestStatus::Paid => 1,
+            PaymentRequestStatus::Underpaid => 2,
+            PaymentRequestStatus::Expired => 3,
+        }
+    }
+}
+
+/// A payment request issued by this wallet, with what has been received for it so far
+#[derive(Debug, Clone, PartialEq)]
+pub struct IssuedPaymentRequest {
+    pub id: u64,
+    pub request: PaymentRequest,
+    pub status: PaymentRequestStatus,
+    pub amount_received: MicroMinotari,
+    pub created_at: NaiveDateTime,
+}
+
+impl IssuedPaymentRequest {
+    /// The status of the request once `amount_received` has been received for it. A partial payment is reported as
+    /// underpaid even after the request has expired.
+    pub fn status_for(&self, amount_received: MicroMinotari, now: NaiveDateTime) -> PaymentRequestStatus {
+        if amount_received >= self.request.amount {
+            PaymentRequestStatus::Paid
+        } else if amount_received > MicroMinotari::zero() {
+            PaymentRequestStatus::Underpaid
+        } else if self.request.is_expired(now) {
+            PaymentRequestStatus::Expired
+        } else {
+            PaymentRequestStatus::Open
+        }
+    }
+}
+
+/// The coarse state of a transaction in the transaction history
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, StrumDisplay, EnumString)]
+#[strum(serialize_all = "snake_case", ascii_case_insensitive)]
+pub enum TransactionHistoryStatus {
+    /// Still being negotiated with the counterparty
+    Pending,
+    /// Completed, whether or not it has been mined yet
+    Completed,
+    Cancelled,
+}
+
+/// Selects the transactions that make up the transaction history, or one page of it
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TransactionHistoryFilter {
+    /// Only transactions created at or after this time
+    pub from: Option<NaiveDateTime>,
+    /// Only transactions created before this time
+    pub to: Option<NaiveDateTime>,
+    /// Only transactions in one of these states, all transactions if empty
+    pub statuses: Vec<TransactionHistoryStatus>,
+    /// Only transactions that come after this position in the history. The next page starts after the position of
+    /// the last record of the previous page.
+    pub after: Option<TransactionHistoryPosition>,
+    /// Return at most this many transactions, all of them if not set
+    pub limit: Option<usize>,
+}
+
+impl TransactionHistoryFilter {
+    pub fn includes_status(&self, status: TransactionHistoryStatus) -> bool {
+        self.statuses.is_empty() || self.statuses.contains(&status)
+    }
+
+    /// The page of the history that follows the given position, with the same filter
+    pub fn page_after(&self, position: TransactionHistoryPosition, limit: usize) -> Self {
+        Self {
+            after: Some(position),
+            limit: Some(limit),
+            ..self.clone()
+        }
+    }
+}
+
+/// The position of a transaction in the transaction history, which is ordered by creation time and then by tx id
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransactionHistoryPosition {
+    pub timestamp: NaiveDateTime,
+    pub tx_id: TxId,
+}
+
+/// A single entry of the transaction history, flattened from any kind of wallet transaction. Amounts are in
+/// MicroMinotari only so that the records can be valued in any currency afterwards.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransactionHistoryRecord {
+    pub tx_id: TxId,
+    pub timestamp: NaiveDateTime,
+    pub history_status: TransactionHistoryStatus,
+    pub status: TransactionStatus,
+    pub cancellation_reason: Option<TxCancellationReason>,
+    pub direction: TransactionDirection,
+    pub amount: MicroMinotari,
+    /// The fee paid by this wallet, zero for inbound transactions
+    pub fee: MicroMinotari,
+    /// The destination of outbound transactions and the source of all others
+    pub counterparty_address: TariAddress,
+    pub payment_id: Option<PaymentId>,
+    pub mined_height: Option<u64>,
+    pub mined_timestamp: Option<NaiveDateTime>,
+    pub confirmations: Option<u64>,
+    /// Only known once the transaction is completed
+    pub kernel_excess: Option<Commitment>,
+    pub message: String,
+}
+
+impl TransactionHistoryRecord {
+    pub fn position(&self) -> TransactionHistoryPosition {
+        TransactionHistoryPosition {
+            timestamp: self.timestamp,
+            tx_id: self.tx_id,
+        }
+    }
+}
+
+impl From<CompletedTransaction> for TransactionHistoryRecord {
+    fn from(tx: CompletedTransaction) -> Self {
+        let counterparty_address = match tx.direction {
+            TransactionDirection::Outbound => tx.destination_address,
+            _ => tx.source_address,
+        };
+        let fee = match tx.direction {
+            TransactionDirection::Inbound => MicroMinotari::from(0),
+            _ => tx.fee,
+        };
+        Self {
+            tx_id: tx.tx_id,
+            timestamp: tx.timestamp,
+            history_status: if tx.cancelled.is_some() {
+                TransactionHistoryStatus::Cancelled
+            } else {
+                TransactionHistoryStatus::Completed
+            },
+            status: tx.status,
+            cancellation_reason: tx.cancelled,
+            direction: tx.direction,
+            amount: tx.amount,
+            fee,
+            counterparty_address,
+            payment_id: tx.payment_id,
+            mined_height: tx.mined_height,
+            mined_timestamp: tx.mined_timestamp,
+            confirmations: tx.confirmations,
+            kernel_excess: tx.transaction.body.kernels().first().map(|k| k.excess.clone()),
+            message: tx.message,
+        }
+    }
+}
+
+impl From<InboundTransaction> for TransactionHistoryRecord {
+    fn from(tx: InboundTransaction) -> Self {
+        Self {
+            tx_id: tx.tx_id,
+            timestamp: tx.timestamp,
+            history_status: if tx.cancelled {
+                TransactionHistoryStatus::Cancelled
+            } else {
+                TransactionHistoryStatus::Pending
+            },
+            status: tx.status,
+            cancellation_reason: None,
+            direction: TransactionDirection::Inbound,
+            amount: tx.amount,
+            fee: MicroMinotari::from(0),
+            counterparty_address: tx.source_address,
+            payment_id: None,
+            mined_height: None,
+            mined_timestamp: None,
+            confirmations: None,
+            kernel_excess: None,
+            message: tx.message,
+        }
+    }
+}
+
+impl From<OutboundTransaction> for TransactionHistoryRecord {
+    fn from(tx: OutboundTransaction) -> Self {
+        Self {
+            tx_id: tx.tx_id,
+            timestamp: tx.timestamp,
+            history_status: if tx.cancelled {
+                TransactionHistoryStatus::Cancelled
+            } else {
+                TransactionHistoryStatus::Pending
+            },
+            status: tx.status,
+            cancellation_reason: None,
+            direction: TransactionDirection::Outbound,
+            amount: tx.amount,
+            fee: tx.fee,
+            counterparty_address: tx.destination_address,
+            payment_id: None,
+            mined_height: None,
+            mined_timestamp: None,
+            confirmations: None,
+            kernel_excess: None,
+            message: tx.message,
+        }
+    }
+}
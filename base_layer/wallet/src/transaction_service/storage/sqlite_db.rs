@@ -42,29 +42,55 @@ use tari_common_types::{
         TxId,
     },
     types::{BlockHash, PrivateKey, PublicKey, Signature},
+    wallet_types::AccountId,
+};
+use tari_core::transactions::{
+    tari_amount::MicroMinotari,
+    transaction_components::encrypted_data::PaymentId,
+    transaction_protocol::partially_signed::PaymentKind,
+    SenderTransactionProtocol,
 };
-use tari_core::transactions::{tari_amount::MicroMinotari, transaction_components::encrypted_data::PaymentId};
 use tari_utilities::{hex::Hex, ByteArray, Hidden};
 use thiserror::Error;
 use tokio::time::Instant;
 use zeroize::Zeroize;
 
 use crate::{
-    schema::{completed_transactions, inbound_transactions, outbound_transactions},
+    schema::{
+        batch_payment_recipients,
+        completed_transactions,
+        inbound_transactions,
+        outbound_transactions,
+        payment_request_payments,
+        payment_requests,
+        scheduled_payment_runs,
+        scheduled_payments,
+    },
     storage::sqlite_utilities::wallet_db_connection::WalletDbConnection,
     transaction_service::{
         error::{TransactionKeyError, TransactionStorageError},
+        payment_request::PaymentRequest,
         storage::{
             database::{DbKey, DbKeyValuePair, DbValue, TransactionBackend, WriteOperation},
             models::{
+                BatchPaymentRecipient,
                 CompletedTransaction,
                 InboundTransaction,
+                IssuedPaymentRequest,
                 OutboundTransaction,
+                PaymentRequestStatus,
+                PaymentSchedule,
+                ScheduledPayment,
+                ScheduledPaymentRun,
+                TransactionHistoryFilter,
+                TransactionHistoryRecord,
+                TransactionHistoryStatus,
                 TxCancellationReason,
                 WalletTransaction,
             },
         },
     },
+    util::cron::CronSchedule,
 };
 
 const LOG_TARGET: &str = "wallet::transaction_service::database::wallet";
@@ -781,6 +807,52 @@ impl TransactionBackend for TransactionServiceSqliteDatabase {
         Ok(())
     }
 
+    fn update_pending_outbound_sender_protocol(
+        &self,
+        tx_id: TxId,
+        sender_protocol: SenderTransactionProtocol,
+    ) -> Result<(), TransactionStorageError> {
+        let start = Instant::now();
+        let mut conn = self.database_connection.get_pooled_connection()?;
+        let acquire_lock = start.elapsed();
+        let cipher = acquire_read_lock!(self.cipher);
+
+        let outbound_tx_sql = match OutboundTransactionSql::find_by_cancelled(tx_id, false, &mut conn) {
+            Ok(v) => v,
+            Err(TransactionStorageError::DieselError(DieselError::NotFound)) => {
+                return Err(TransactionStorageError::ValueNotFound(
+                    DbKey::PendingOutboundTransaction(tx_id),
+                ))
+            },
+            Err(e) => return Err(e),
+        };
+        let mut outbound_tx = OutboundTransaction::try_from(outbound_tx_sql, &cipher)?;
+        outbound_tx.sender_protocol = sender_protocol;
+        let outbound_tx_sql = OutboundTransactionSql::try_from(outbound_tx, &cipher)?;
+        outbound_tx_sql.update(
+            UpdateOutboundTransactionSql {
+                cancelled: None,
+                direct_send_success: None,
+                sender_protocol: Some(outbound_tx_sql.sender_protocol.clone()),
+                send_count: None,
+                last_send_timestamp: None,
+            },
+            &mut conn,
+        )?;
+
+        if start.elapsed().as_millis() > 0 {
+            trace!(
+                target: LOG_TARGET,
+                "sqlite profile - update_pending_outbound_sender_protocol: lock {} + db_op {} = {} ms",
+                acquire_lock.as_millis(),
+                (start.elapsed() - acquire_lock).as_millis(),
+                start.elapsed().as_millis()
+            );
+        }
+
+        Ok(())
+    }
+
     fn increment_send_count(&self, tx_id: TxId) -> Result<(), TransactionStorageError> {
         let start = Instant::now();
         let mut conn = self.database_connection.get_pooled_connection()?;
@@ -1120,6 +1192,267 @@ impl TransactionBackend for TransactionServiceSqliteDatabase {
         coinbases.append(&mut one_sided);
         Ok(coinbases)
     }
+
+    fn insert_scheduled_payment(&self, payment: ScheduledPayment) -> Result<ScheduledPayment, TransactionStorageError> {
+        let mut conn = self.database_connection.get_pooled_connection()?;
+        let payment = ScheduledPaymentSql::create(ScheduledPaymentSql::from(payment), &mut conn)?;
+        ScheduledPayment::try_from(payment)
+    }
+
+    fn fetch_scheduled_payments(&self) -> Result<Vec<ScheduledPayment>, TransactionStorageError> {
+        let mut conn = self.database_connection.get_pooled_connection()?;
+        ScheduledPaymentSql::index(&mut conn)?
+            .into_iter()
+            .map(ScheduledPayment::try_from)
+            .collect()
+    }
+
+    fn fetch_due_scheduled_payments(
+        &self,
+        now: NaiveDateTime,
+    ) -> Result<Vec<ScheduledPayment>, TransactionStorageError> {
+        let mut conn = self.database_connection.get_pooled_connection()?;
+        ScheduledPaymentSql::index_due(now, &mut conn)?
+            .into_iter()
+            .map(ScheduledPayment::try_from)
+            .collect()
+    }
+
+    fn cancel_scheduled_payment(&self, id: u64) -> Result<(), TransactionStorageError> {
+        let mut conn = self.database_connection.get_pooled_connection()?;
+        let num_updated = diesel::update(scheduled_payments::table.filter(scheduled_payments::id.eq(id as i64)))
+            .set((
+                scheduled_payments::cancelled.eq(1),
+                scheduled_payments::next_run_at.eq(None::<NaiveDateTime>),
+            ))
+            .execute(&mut conn)?;
+        if num_updated == 0 {
+            return Err(TransactionStorageError::ScheduledPaymentNotFound(id));
+        }
+        Ok(())
+    }
+
+    fn set_scheduled_payment_next_run(
+        &self,
+        id: u64,
+        next_run_at: Option<NaiveDateTime>,
+    ) -> Result<(), TransactionStorageError> {
+        let mut conn = self.database_connection.get_pooled_connection()?;
+        let num_updated = diesel::update(scheduled_payments::table.filter(scheduled_payments::id.eq(id as i64)))
+            .set(scheduled_payments::next_run_at.eq(next_run_at))
+            .execute(&mut conn)?;
+        if num_updated == 0 {
+            return Err(TransactionStorageError::ScheduledPaymentNotFound(id));
+        }
+        Ok(())
+    }
+
+    fn insert_scheduled_payment_run(&self, run: ScheduledPaymentRun) -> Result<(), TransactionStorageError> {
+        let mut conn = self.database_connection.get_pooled_connection()?;
+        diesel::insert_into(scheduled_payment_runs::table)
+            .values(NewScheduledPaymentRunSql::from(run))
+            .execute(&mut conn)?;
+        Ok(())
+    }
+
+    fn fetch_scheduled_payment_runs(&self, id: u64) -> Result<Vec<ScheduledPaymentRun>, TransactionStorageError> {
+        let mut conn = self.database_connection.get_pooled_connection()?;
+        let runs = scheduled_payment_runs::table
+            .filter(scheduled_payment_runs::scheduled_payment_id.eq(id as i64))
+            .order(scheduled_payment_runs::id.asc())
+            .select((
+                scheduled_payment_runs::scheduled_payment_id,
+                scheduled_payment_runs::run_at,
+                scheduled_payment_runs::tx_id,
+                scheduled_payment_runs::error,
+            ))
+            .load::<ScheduledPaymentRunSql>(&mut conn)?;
+        Ok(runs.into_iter().map(ScheduledPaymentRun::from).collect())
+    }
+
+    fn fetch_transaction_history(
+        &self,
+        filter: &TransactionHistoryFilter,
+    ) -> Result<Vec<TransactionHistoryRecord>, TransactionStorageError> {
+        let start = Instant::now();
+        let mut conn = self.database_connection.get_pooled_connection()?;
+        let acquire_lock = start.elapsed();
+        let cipher = acquire_read_lock!(self.cipher);
+
+        let pending = filter.includes_status(TransactionHistoryStatus::Pending);
+        let completed = filter.includes_status(TransactionHistoryStatus::Completed);
+        let cancelled = filter.includes_status(TransactionHistoryStatus::Cancelled);
+        // Every table returns at most one page, sorted the same way, so the page of the whole history is the start of
+        // their merged records
+        let mut records = Vec::<TransactionHistoryRecord>::new();
+        if pending || cancelled {
+            for tx in InboundTransactionSql::index_for_history(filter, pending, cancelled, &mut conn)? {
+                records.push(InboundTransaction::try_from(tx, &cipher)?.into());
+            }
+            for tx in OutboundTransactionSql::index_for_history(filter, pending, cancelled, &mut conn)? {
+                records.push(OutboundTransaction::try_from(tx, &cipher)?.into());
+            }
+        }
+        if completed || cancelled {
+            for tx in CompletedTransactionSql::index_for_history(filter, completed, cancelled, &mut conn)? {
+                records.push(CompletedTransaction::try_from(tx, &cipher)?.into());
+            }
+        }
+        records.sort_by_key(history_order);
+        if let Some(limit) = filter.limit {
+            records.truncate(limit);
+        }
+        if start.elapsed().as_millis() > 0 {
+            trace!(
+                target: LOG_TARGET,
+                "sqlite profile - fetch_transaction_history: lock {} + db_op {} = {} ms",
+                acquire_lock.as_millis(),
+                (start.elapsed() - acquire_lock).as_millis(),
+                start.elapsed().as_millis()
+            );
+        }
+        Ok(records)
+    }
+
+    fn insert_batch_payment_recipients(
+        &self,
+        tx_id: TxId,
+        recipients: Vec<BatchPaymentRecipient>,
+    ) -> Result<(), TransactionStorageError> {
+        let mut conn = self.database_connection.get_pooled_connection()?;
+        let recipients = recipients
+            .into_iter()
+            .map(|r| NewBatchPaymentRecipientSql {
+                tx_id: tx_id.as_u64() as i64,
+                destination_address: r.address.to_vec(),
+                amount: u64::from(r.amount) as i64,
+                payment_id: r.payment_id.to_bytes(),
+            })
+            .collect::<Vec<_>>();
+        diesel::insert_into(batch_payment_recipients::table)
+            .values(&recipients)
+            .execute(&mut conn)?;
+        Ok(())
+    }
+
+    fn fetch_batch_payment_recipients(
+        &self,
+        tx_id: TxId,
+    ) -> Result<Vec<BatchPaymentRecipient>, TransactionStorageError> {
+        let mut conn = self.database_connection.get_pooled_connection()?;
+        let recipients = batch_payment_recipients::table
+            .filter(batch_payment_recipients::tx_id.eq(tx_id.as_u64() as i64))
+            .order(batch_payment_recipients::id.asc())
+            .select((
+                batch_payment_recipients::destination_address,
+                batch_payment_recipients::amount,
+                batch_payment_recipients::payment_id,
+            ))
+            .load::<BatchPaymentRecipientSql>(&mut conn)?;
+        recipients.into_iter().map(BatchPaymentRecipient::try_from).collect()
+    }
+
+    fn insert_payment_request(
+        &self,
+        request: IssuedPaymentRequest,
+    ) -> Result<IssuedPaymentRequest, TransactionStorageError> {
+        let mut conn = self.database_connection.get_pooled_connection()?;
+        let request = PaymentRequestSql::create(PaymentRequestSql::from(request), &mut conn)?;
+        IssuedPaymentRequest::try_from(request)
+    }
+
+    fn fetch_payment_requests(&self) -> Result<Vec<IssuedPaymentRequest>, TransactionStorageError> {
+        let mut conn = self.database_connection.get_pooled_connection()?;
+        PaymentRequestSql::index(&mut conn)?
+            .into_iter()
+            .map(IssuedPaymentRequest::try_from)
+            .collect()
+    }
+
+    fn update_payment_request_status(
+        &self,
+        id: u64,
+        status: PaymentRequestStatus,
+        amount_received: MicroMinotari,
+    ) -> Result<(), TransactionStorageError> {
+        let mut conn = self.database_connection.get_pooled_connection()?;
+        let num_updated = diesel::update(payment_requests::table.filter(payment_requests::id.eq(id as i64)))
+            .set((
+                payment_requests::status.eq(i32::from(status)),
+                payment_requests::amount_received.eq(amount_received.as_u64() as i64),
+            ))
+            .execute(&mut conn)?;
+        if num_updated == 0 {
+            return Err(TransactionStorageError::PaymentRequestNotFound(id));
+        }
+        Ok(())
+    }
+
+    fn update_payment_request_payments(&self) -> Result<Vec<u64>, TransactionStorageError> {
+        let mut conn = self.database_connection.get_pooled_connection()?;
+        let updated = PaymentRequestPaymentSql::update(&mut conn)?;
+        Ok(updated.into_iter().map(|id| id as u64).collect())
+    }
+}
+
+/// Re-encrypts the protocol data of every transaction from `old_cipher` to `new_cipher` and returns the number of
+/// transactions updated. This is meant to run inside the transaction that replaces the wallet database key.
+pub(crate) fn reencrypt_transactions(
+    conn: &mut SqliteConnection,
+    old_cipher: &XChaCha20Poly1305,
+    new_cipher: &XChaCha20Poly1305,
+) -> Result<usize, TransactionStorageError> {
+    let mut num_updated = 0;
+    for tx in InboundTransactionSql::index(conn)? {
+        tx.decrypt(old_cipher)
+            .and_then(|tx| tx.encrypt(new_cipher))
+            .map_err(TransactionStorageError::AeadError)?
+            .update_encryption(conn)?;
+        num_updated += 1;
+    }
+    for tx in OutboundTransactionSql::index(conn)? {
+        tx.decrypt(old_cipher)
+            .and_then(|tx| tx.encrypt(new_cipher))
+            .map_err(TransactionStorageError::AeadError)?
+            .update_encryption(conn)?;
+        num_updated += 1;
+    }
+    for tx in CompletedTransactionSql::index(conn)? {
+        tx.decrypt(old_cipher)
+            .and_then(|tx| tx.encrypt(new_cipher))
+            .map_err(TransactionStorageError::AeadError)?
+            .update_encryption(conn)?;
+        num_updated += 1;
+    }
+    Ok(num_updated)
+}
+
+/// The order of the transaction history, which matches the order of its records in the database
+#[allow(clippy::cast_possible_wrap)]
+fn history_order(record: &TransactionHistoryRecord) -> (NaiveDateTime, i64) {
+    (record.timestamp, record.tx_id.as_u64() as i64)
+}
+
+/// Checks that the protocol data of every transaction decrypts with `cipher` and returns the number of transactions
+/// checked
+pub(crate) fn verify_transactions_encryption(
+    conn: &mut SqliteConnection,
+    cipher: &XChaCha20Poly1305,
+) -> Result<usize, TransactionStorageError> {
+    let inbound = InboundTransactionSql::index(conn)?;
+    let outbound = OutboundTransactionSql::index(conn)?;
+    let completed = CompletedTransactionSql::index(conn)?;
+    let num_verified = inbound.len() + outbound.len() + completed.len();
+    for tx in inbound {
+        tx.decrypt(cipher).map_err(TransactionStorageError::AeadError)?;
+    }
+    for tx in outbound {
+        tx.decrypt(cipher).map_err(TransactionStorageError::AeadError)?;
+    }
+    for tx in completed {
+        tx.decrypt(cipher).map_err(TransactionStorageError::AeadError)?;
+    }
+    Ok(num_verified)
 }
 
 #[derive(Debug, PartialEq)]
@@ -1195,6 +1528,41 @@ impl InboundTransactionSql {
             .load::<InboundTransactionSql>(conn)?)
     }
 
+    /// The transactions of the history page selected by `filter`, which are ordered by their timestamp and tx id.
+    /// `pending` and `cancelled` select whether uncancelled and cancelled transactions are included.
+    #[allow(clippy::cast_possible_wrap)]
+    pub fn index_for_history(
+        filter: &TransactionHistoryFilter,
+        pending: bool,
+        cancelled: bool,
+        conn: &mut SqliteConnection,
+    ) -> Result<Vec<InboundTransactionSql>, TransactionStorageError> {
+        let mut query = inbound_transactions::table.into_boxed();
+        if pending != cancelled {
+            query = query.filter(inbound_transactions::cancelled.eq(i32::from(cancelled)));
+        }
+        if let Some(from) = filter.from {
+            query = query.filter(inbound_transactions::timestamp.ge(from));
+        }
+        if let Some(to) = filter.to {
+            query = query.filter(inbound_transactions::timestamp.lt(to));
+        }
+        if let Some(after) = filter.after {
+            query = query.filter(
+                inbound_transactions::timestamp
+                    .gt(after.timestamp)
+                    .or(inbound_transactions::timestamp
+                        .eq(after.timestamp)
+                        .and(inbound_transactions::tx_id.gt(after.tx_id.as_u64() as i64))),
+            );
+        }
+        query = query.order((inbound_transactions::timestamp.asc(), inbound_transactions::tx_id.asc()));
+        if let Some(limit) = filter.limit {
+            query = query.limit(i64::try_from(limit).unwrap_or(i64::MAX));
+        }
+        Ok(query.load::<InboundTransactionSql>(conn)?)
+    }
+
     pub fn find(tx_id: TxId, conn: &mut SqliteConnection) -> Result<InboundTransactionSql, TransactionStorageError> {
         Ok(inbound_transactions::table
             .filter(inbound_transactions::tx_id.eq(tx_id.as_u64() as i64))
@@ -1328,7 +1696,6 @@ impl InboundTransactionSql {
         Ok(())
     }
 
-    #[allow(dead_code)]
     pub fn update_encryption(&self, conn: &mut SqliteConnection) -> Result<(), TransactionStorageError> {
         self.update(
             UpdateInboundTransactionSql {
@@ -1435,6 +1802,7 @@ struct OutboundTransactionSql {
     direct_send_success: i32,
     send_count: i32,
     last_send_timestamp: Option<NaiveDateTime>,
+    offline_signing: i32,
 }
 
 impl OutboundTransactionSql {
@@ -1459,6 +1827,44 @@ impl OutboundTransactionSql {
             .load::<OutboundTransactionSql>(conn)?)
     }
 
+    /// The transactions of the history page selected by `filter`, which are ordered by their timestamp and tx id.
+    /// `pending` and `cancelled` select whether uncancelled and cancelled transactions are included.
+    #[allow(clippy::cast_possible_wrap)]
+    pub fn index_for_history(
+        filter: &TransactionHistoryFilter,
+        pending: bool,
+        cancelled: bool,
+        conn: &mut SqliteConnection,
+    ) -> Result<Vec<OutboundTransactionSql>, TransactionStorageError> {
+        let mut query = outbound_transactions::table.into_boxed();
+        if pending != cancelled {
+            query = query.filter(outbound_transactions::cancelled.eq(i32::from(cancelled)));
+        }
+        if let Some(from) = filter.from {
+            query = query.filter(outbound_transactions::timestamp.ge(from));
+        }
+        if let Some(to) = filter.to {
+            query = query.filter(outbound_transactions::timestamp.lt(to));
+        }
+        if let Some(after) = filter.after {
+            query = query.filter(
+                outbound_transactions::timestamp
+                    .gt(after.timestamp)
+                    .or(outbound_transactions::timestamp
+                        .eq(after.timestamp)
+                        .and(outbound_transactions::tx_id.gt(after.tx_id.as_u64() as i64))),
+            );
+        }
+        query = query.order((
+            outbound_transactions::timestamp.asc(),
+            outbound_transactions::tx_id.asc(),
+        ));
+        if let Some(limit) = filter.limit {
+            query = query.limit(i64::try_from(limit).unwrap_or(i64::MAX));
+        }
+        Ok(query.load::<OutboundTransactionSql>(conn)?)
+    }
+
     pub fn find(tx_id: TxId, conn: &mut SqliteConnection) -> Result<OutboundTransactionSql, TransactionStorageError> {
         Ok(outbound_transactions::table
             .filter(outbound_transactions::tx_id.eq(tx_id.as_u64() as i64))
@@ -1576,7 +1982,6 @@ impl OutboundTransactionSql {
         Ok(())
     }
 
-    #[allow(dead_code)]
     pub fn update_encryption(&self, conn: &mut SqliteConnection) -> Result<(), TransactionStorageError> {
         self.update(
             UpdateOutboundTransactionSql {
@@ -1605,6 +2010,11 @@ impl OutboundTransactionSql {
             direct_send_success: i32::from(o.direct_send_success),
             send_count: o.send_count as i32,
             last_send_timestamp: o.last_send_timestamp,
+            offline_signing: match o.offline_signing {
+                None => 0,
+                Some(PaymentKind::OneSided) => 1,
+                Some(PaymentKind::Interactive) => 2,
+            },
         };
 
         outbound_tx.encrypt(cipher).map_err(TransactionStorageError::AeadError)
@@ -1657,6 +2067,17 @@ impl OutboundTransaction {
             direct_send_success: o.direct_send_success != 0,
             send_count: o.send_count as u32,
             last_send_timestamp: o.last_send_timestamp,
+            offline_signing: match o.offline_signing {
+                0 => None,
+                1 => Some(PaymentKind::OneSided),
+                2 => Some(PaymentKind::Interactive),
+                v => {
+                    return Err(TransactionStorageError::UnexpectedResult(format!(
+                        "Invalid offline signing kind {}",
+                        v
+                    )))
+                },
+            },
         };
 
         // zeroize decrypted data
@@ -1730,6 +2151,48 @@ impl CompletedTransactionSql {
         Ok(query.load::<CompletedTransactionSql>(conn)?)
     }
 
+    /// The transactions of the history page selected by `filter`, which are ordered by their timestamp and tx id.
+    /// `completed` and `cancelled` select whether uncancelled and cancelled transactions are included.
+    #[allow(clippy::cast_possible_wrap)]
+    pub fn index_for_history(
+        filter: &TransactionHistoryFilter,
+        completed: bool,
+        cancelled: bool,
+        conn: &mut SqliteConnection,
+    ) -> Result<Vec<CompletedTransactionSql>, TransactionStorageError> {
+        let mut query = completed_transactions::table.into_boxed();
+        if completed != cancelled {
+            query = if cancelled {
+                query.filter(completed_transactions::cancelled.is_not_null())
+            } else {
+                query.filter(completed_transactions::cancelled.is_null())
+            };
+        }
+        if let Some(from) = filter.from {
+            query = query.filter(completed_transactions::timestamp.ge(from));
+        }
+        if let Some(to) = filter.to {
+            query = query.filter(completed_transactions::timestamp.lt(to));
+        }
+        if let Some(after) = filter.after {
+            query = query.filter(
+                completed_transactions::timestamp
+                    .gt(after.timestamp)
+                    .or(completed_transactions::timestamp
+                        .eq(after.timestamp)
+                        .and(completed_transactions::tx_id.gt(after.tx_id.as_u64() as i64))),
+            );
+        }
+        query = query.order((
+            completed_transactions::timestamp.asc(),
+            completed_transactions::tx_id.asc(),
+        ));
+        if let Some(limit) = filter.limit {
+            query = query.limit(i64::try_from(limit).unwrap_or(i64::MAX));
+        }
+        Ok(query.load::<CompletedTransactionSql>(conn)?)
+    }
+
     pub fn index_by_status_and_cancelled(
         status: TransactionStatus,
         cancelled: bool,
@@ -1948,7 +2411,6 @@ impl CompletedTransactionSql {
         Ok(())
     }
 
-    #[allow(dead_code)]
     pub fn update_encryption(&self, conn: &mut SqliteConnection) -> Result<(), TransactionStorageError> {
         self.update(
             UpdateCompletedTransactionSql {
@@ -2198,6 +2660,353 @@ impl UnconfirmedTransactionInfoSql {
     }
 }
 
+#[derive(Clone, Debug, Queryable, Insertable, PartialEq)]
+#[diesel(table_name = scheduled_payments)]
+struct ScheduledPaymentSql {
+    id: i64,
+    destination_address: Vec<u8>,
+    amount: i64,
+    fee_per_gram: i64,
+    message: String,
+    payment_kind: i32,
+    run_once_at: Option<NaiveDateTime>,
+    cron_expression: Option<String>,
+    next_run_at: Option<NaiveDateTime>,
+    created_at: NaiveDateTime,
+    cancelled: i32,
+    account_id: Option<i64>,
+}
+
+impl ScheduledPaymentSql {
+    /// Return all scheduled payments, ordered by id
+    pub fn index(conn: &mut SqliteConnection) -> Result<Vec<ScheduledPaymentSql>, TransactionStorageError> {
+        Ok(scheduled_payments::table
+            .order(scheduled_payments::id.asc())
+            .load::<ScheduledPaymentSql>(conn)?)
+    }
+
+    pub fn index_due(
+        now: NaiveDateTime,
+        conn: &mut SqliteConnection,
+    ) -> Result<Vec<ScheduledPaymentSql>, TransactionStorageError> {
+        Ok(scheduled_payments::table
+            .filter(scheduled_payments::cancelled.eq(0))
+            .filter(scheduled_payments::next_run_at.le(now))
+            .order(scheduled_payments::next_run_at.asc())
+            .load::<ScheduledPaymentSql>(conn)?)
+    }
+
+    /// Insert the scheduled payment with the next unused id
+    pub fn create(
+        mut payment: ScheduledPaymentSql,
+        conn: &mut SqliteConnection,
+    ) -> Result<ScheduledPaymentSql, TransactionStorageError> {
+        conn.transaction::<_, TransactionStorageError, _>(|conn| {
+            let max_id = scheduled_payments::table
+                .select(diesel::dsl::max(scheduled_payments::id))
+                .first::<Option<i64>>(conn)?;
+            payment.id = max_id.map_or(1, |id| id + 1);
+            diesel::insert_into(scheduled_payments::table)
+                .values(&payment)
+                .execute(conn)?;
+            Ok(payment)
+        })
+    }
+}
+
+impl From<ScheduledPayment> for ScheduledPaymentSql {
+    fn from(p: ScheduledPayment) -> Self {
+        let (run_once_at, cron_expression) = match p.schedule {
+            PaymentSchedule::Once(at) => (Some(at), None),
+            PaymentSchedule::Recurring(schedule) => (None, Some(schedule.to_string())),
+        };
+        Self {
+            id: p.id as i64,
+            destination_address: p.destination.to_vec(),
+            amount: u64::from(p.amount) as i64,
+            fee_per_gram: u64::from(p.fee_per_gram) as i64,
+            message: p.message,
+            payment_kind: match p.kind {
+                PaymentKind::OneSided => 1,
+                PaymentKind::Interactive => 2,
+            },
+            run_once_at,
+            cron_expression,
+            next_run_at: p.next_run_at,
+            created_at: p.created_at,
+            cancelled: i32::from(p.cancelled),
+            account_id: p.account.map(i64::from),
+        }
+    }
+}
+
+impl TryFrom<ScheduledPaymentSql> for ScheduledPayment {
+    type Error = TransactionStorageError;
+
+    fn try_from(p: ScheduledPaymentSql) -> Result<Self, Self::Error> {
+        let schedule = match (p.run_once_at, p.cron_expression) {
+            (Some(at), None) => PaymentSchedule::Once(at),
+            (None, Some(expression)) => PaymentSchedule::Recurring(
+                expression
+                    .parse::<CronSchedule>()
+                    .map_err(|e| TransactionStorageError::UnexpectedResult(e.to_string()))?,
+            ),
+            _ => {
+                return Err(TransactionStorageError::UnexpectedResult(format!(
+                    "Scheduled payment {} must have exactly one of a run time or a cron expression",
+                    p.id
+                )))
+            },
+        };
+        Ok(Self {
+            id: p.id as u64,
+            destination: TariAddress::from_bytes(&p.destination_address).map_err(TransactionKeyError::Destination)?,
+            amount: MicroMinotari::from(p.amount as u64),
+            fee_per_gram: MicroMinotari::from(p.fee_per_gram as u64),
+            message: p.message,
+            kind: match p.payment_kind {
+                1 => PaymentKind::OneSided,
+                2 => PaymentKind::Interactive,
+                v => {
+                    return Err(TransactionStorageError::UnexpectedResult(format!(
+                        "Invalid scheduled payment kind {}",
+                        v
+                    )))
+                },
+            },
+            schedule,
+            next_run_at: p.next_run_at,
+            created_at: p.created_at,
+            cancelled: p.cancelled != 0,
+            account: p
+                .account_id
+                .map(AccountId::try_from)
+                .transpose()
+                .map_err(|e| TransactionStorageError::UnexpectedResult(e.to_string()))?,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Queryable, PartialEq)]
+struct ScheduledPaymentRunSql {
+    scheduled_payment_id: i64,
+    run_at: NaiveDateTime,
+    tx_id: Option<i64>,
+    error: Option<String>,
+}
+
+impl From<ScheduledPaymentRunSql> for ScheduledPaymentRun {
+    fn from(r: ScheduledPaymentRunSql) -> Self {
+        Self {
+            scheduled_payment_id: r.scheduled_payment_id as u64,
+            run_at: r.run_at,
+            tx_id: r.tx_id.map(|tx_id| TxId::from(tx_id as u64)),
+            error: r.error,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Insertable)]
+#[diesel(table_name = scheduled_payment_runs)]
+struct NewScheduledPaymentRunSql {
+    scheduled_payment_id: i64,
+    run_at: NaiveDateTime,
+    tx_id: Option<i64>,
+    error: Option<String>,
+}
+
+impl From<ScheduledPaymentRun> for NewScheduledPaymentRunSql {
+    fn from(r: ScheduledPaymentRun) -> Self {
+        Self {
+            scheduled_payment_id: r.scheduled_payment_id as i64,
+            run_at: r.run_at,
+            tx_id: r.tx_id.map(|tx_id| tx_id.as_u64() as i64),
+            error: r.error,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Insertable)]
+#[diesel(table_name = batch_payment_recipients)]
+struct NewBatchPaymentRecipientSql {
+    tx_id: i64,
+    destination_address: Vec<u8>,
+    amount: i64,
+    payment_id: Vec<u8>,
+}
+
+#[derive(Clone, Debug, Queryable, PartialEq)]
+struct BatchPaymentRecipientSql {
+    destination_address: Vec<u8>,
+    amount: i64,
+    payment_id: Vec<u8>,
+}
+
+impl TryFrom<BatchPaymentRecipientSql> for BatchPaymentRecipient {
+    type Error = TransactionStorageError;
+
+    fn try_from(r: BatchPaymentRecipientSql) -> Result<Self, Self::Error> {
+        Ok(Self {
+            address: TariAddress::from_bytes(&r.destination_address).map_err(TransactionKeyError::Destination)?,
+            amount: MicroMinotari::from(r.amount as u64),
+            payment_id: PaymentId::from_bytes(&r.payment_id)
+                .map_err(|e| TransactionStorageError::UnexpectedResult(format!("Invalid payment id: {}", e)))?,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Queryable, Insertable, PartialEq)]
+#[diesel(table_name = payment_requests)]
+struct PaymentRequestSql {
+    id: i64,
+    payment_id: Vec<u8>,
+    request: String,
+    status: i32,
+    amount_received: i64,
+    created_at: NaiveDateTime,
+}
+
+impl PaymentRequestSql {
+    /// Return all payment requests, ordered by id
+    pub fn index(conn: &mut SqliteConnection) -> Result<Vec<PaymentRequestSql>, TransactionStorageError> {
+        Ok(payment_requests::table
+            .order(payment_requests::id.asc())
+            .load::<PaymentRequestSql>(conn)?)
+    }
+
+    /// Insert the payment request with the next unused id
+    pub fn create(
+        mut request: PaymentRequestSql,
+        conn: &mut SqliteConnection,
+    ) -> Result<PaymentRequestSql, TransactionStorageError> {
+        conn.transaction::<_, TransactionStorageError, _>(|conn| {
+            let max_id = payment_requests::table
+                .select(diesel::dsl::max(payment_requests::id))
+                .first::<Option<i64>>(conn)?;
+            request.id = max_id.map_or(1, |id| id + 1);
+            diesel::insert_into(payment_requests::table)
+                .values(&request)
+                .execute(conn)?;
+            Ok(request)
+        })
+    }
+}
+
+#[derive(Clone, Debug, Queryable, Insertable, PartialEq)]
+#[diesel(table_name = payment_request_payments)]
+struct PaymentRequestPaymentSql {
+    tx_id: i64,
+    payment_request_id: i64,
+    amount: i64,
+}
+
+impl PaymentRequestPaymentSql {
+    /// Records the inbound transactions that pay a payment request and have not been matched yet, drops the payments
+    /// of transactions that have been cancelled since and brings the amount received of the affected requests up to
+    /// date. Returns the ids of the affected requests.
+    pub fn update(conn: &mut SqliteConnection) -> Result<Vec<i64>, TransactionStorageError> {
+        conn.transaction::<_, TransactionStorageError, _>(|conn| {
+            let requests = payment_requests::table
+                .select((payment_requests::payment_id, payment_requests::id))
+                .load::<(Vec<u8>, i64)>(conn)?
+                .into_iter()
+                .collect::<HashMap<_, _>>();
+            if requests.is_empty() {
+                return Ok(Vec::new());
+            }
+
+            let new_payments = completed_transactions::table
+                .select((
+                    completed_transactions::tx_id,
+                    completed_transactions::payment_id,
+                    completed_transactions::amount,
+                ))
+                .filter(completed_transactions::payment_id.eq_any(requests.keys().cloned().collect::<Vec<_>>()))
+                .filter(completed_transactions::direction.eq(TransactionDirection::Inbound as i32))
+                .filter(completed_transactions::cancelled.is_null())
+                .filter(
+                    completed_transactions::tx_id
+                        .ne_all(payment_request_payments::table.select(payment_request_payments::tx_id)),
+                )
+                .load::<(i64, Option<Vec<u8>>, i64)>(conn)?
+                .into_iter()
+                .filter_map(|(tx_id, payment_id, amount)| {
+                    let payment_request_id = *requests.get(&payment_id?)?;
+                    Some(Self {
+                        tx_id,
+                        payment_request_id,
+                        amount,
+                    })
+                })
+                .collect::<Vec<_>>();
+            diesel::insert_into(payment_request_payments::table)
+                .values(&new_payments)
+                .execute(conn)?;
+
+            let cancelled_tx_ids = completed_transactions::table
+                .select(completed_transactions::tx_id)
+                .filter(completed_transactions::cancelled.is_not_null());
+            let cancelled_payments = payment_request_payments::table
+                .filter(payment_request_payments::tx_id.eq_any(cancelled_tx_ids))
+                .load::<Self>(conn)?;
+            diesel::delete(payment_request_payments::table.filter(
+                payment_request_payments::tx_id.eq_any(cancelled_payments.iter().map(|p| p.tx_id).collect::<Vec<_>>()),
+            ))
+            .execute(conn)?;
+
+            let mut updated = new_payments
+                .iter()
+                .chain(&cancelled_payments)
+                .map(|p| p.payment_request_id)
+                .collect::<Vec<_>>();
+            updated.sort_unstable();
+            updated.dedup();
+            for &id in &updated {
+                let amount_received = payment_request_payments::table
+                    .select(payment_request_payments::amount)
+                    .filter(payment_request_payments::payment_request_id.eq(id))
+                    .load::<i64>(conn)?
+                    .into_iter()
+                    .fold(0i64, i64::saturating_add);
+                diesel::update(payment_requests::table.filter(payment_requests::id.eq(id)))
+                    .set(payment_requests::amount_received.eq(amount_received))
+                    .execute(conn)?;
+            }
+            Ok(updated)
+        })
+    }
+}
+
+impl From<IssuedPaymentRequest> for PaymentRequestSql {
+    fn from(r: IssuedPaymentRequest) -> Self {
+        Self {
+            id: r.id as i64,
+            payment_id: r.request.payment_id.to_bytes(),
+            request: r.request.to_uri(),
+            status: i32::from(r.status),
+            amount_received: r.amount_received.as_u64() as i64,
+            created_at: r.created_at,
+        }
+    }
+}
+
+impl TryFrom<PaymentRequestSql> for IssuedPaymentRequest {
+    type Error = TransactionStorageError;
+
+    fn try_from(r: PaymentRequestSql) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: r.id as u64,
+            request: r
+                .request
+                .parse::<PaymentRequest>()
+                .map_err(|e| TransactionStorageError::UnexpectedResult(e.to_string()))?,
+            status: PaymentRequestStatus::try_from(r.status)?,
+            amount_received: MicroMinotari::from(r.amount_received as u64),
+            created_at: r.created_at,
+        })
+    }
+}
+
 #[cfg(test)]
 mod test {
     use std::{mem::size_of, time::Duration};
@@ -2339,6 +3148,7 @@ mod test {
             direct_send_success: false,
             send_count: 0,
             last_send_timestamp: None,
+            offline_signing: None,
         };
         let address = TariAddress::new_single_address_with_interactive_only(
             PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng)),
@@ -2358,6 +3168,7 @@ mod test {
                 direct_send_success: false,
                 send_count: 0,
                 last_send_timestamp: None,
+                offline_signing: None,
             },
             &cipher,
         )
@@ -2733,6 +3544,7 @@ mod test {
             direct_send_success: false,
             send_count: 0,
             last_send_timestamp: None,
+            offline_signing: None,
         };
 
         let outbound_tx_sql = OutboundTransactionSql::try_from(outbound_tx.clone(), &cipher).unwrap();
@@ -2872,6 +3684,7 @@ mod test {
                 direct_send_success: false,
                 send_count: 0,
                 last_send_timestamp: None,
+                offline_signing: None,
             };
             let outbound_tx_sql = OutboundTransactionSql::try_from(outbound_tx, &cipher).unwrap();
 
@@ -0,0 +1,220 @@
+// Copyright 2024 The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+//! A minimal cron expression parser used to schedule recurring payments.
+//!
+//! Expressions have the five standard fields `minute hour day-of-month month day-of-week` and are evaluated in UTC.
+//! Each field is `*`, a value, a range `a-b` or a comma separated list of these, any of which may take a `/step`
+//! suffix. Day-of-week runs from 0 (Sunday) to 6, with 7 accepted as an alias for Sunday. As with standard cron, when
+//! both day-of-month and day-of-week are restricted a day matches if either of them does.
+
+use std::{fmt, str::FromStr};
+
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+use thiserror::Error;
+
+/// How far ahead [CronSchedule::next_after] searches before concluding that a schedule never runs, e.g. `0 0 30 2 *`
+const MAX_SEARCH_DAYS: usize = 5 * 366;
+
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+pub enum CronScheduleError {
+    #[error("Expected 5 fields (minute hour day-of-month month day-of-week) but got {0}")]
+    InvalidFieldCount(usize),
+    #[error("Invalid {field} field `{value}`")]
+    InvalidField { field: &'static str, value: String },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CronSchedule {
+    expression: String,
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    any_day_of_month: bool,
+    any_day_of_week: bool,
+}
+
+impl CronSchedule {
+    /// Returns the first time matching this schedule strictly after `after`, or `None` if there is none in the
+    /// foreseeable future
+    pub fn next_after(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        let start = after.checked_add_signed(Duration::minutes(1))?;
+        let mut date = start.date();
+        let mut from = (start.hour(), start.minute());
+        for _ in 0..MAX_SEARCH_DAYS {
+            if self.matches_date(date) {
+                if let Some(time) = self.first_time_from(from) {
+                    return Some(date.and_time(time));
+                }
+            }
+            date = date.succ_opt()?;
+            from = (0, 0);
+        }
+        None
+    }
+
+    fn matches_date(&self, date: NaiveDate) -> bool {
+        if !is_set(self.months, date.month()) {
+            return false;
+        }
+        let day_of_month = is_set(self.days_of_month, date.day());
+        let day_of_week = is_set(self.days_of_week, date.weekday().num_days_from_sunday());
+        match (self.any_day_of_month, self.any_day_of_week) {
+            (true, true) => true,
+            (true, false) => day_of_week,
+            (false, true) => day_of_month,
+            (false, false) => day_of_month || day_of_week,
+        }
+    }
+
+    fn first_time_from(&self, (hour, minute): (u32, u32)) -> Option<NaiveTime> {
+        (hour..24).filter(|h| is_set(self.hours, *h)).find_map(|h| {
+            let first_minute = if h == hour { minute } else { 0 };
+            (first_minute..60)
+                .find(|m| is_set(self.minutes, *m))
+                .and_then(|m| NaiveTime::from_hms_opt(h, m, 0))
+        })
+    }
+}
+
+impl FromStr for CronSchedule {
+    type Err = CronScheduleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields = s.split_whitespace().collect::<Vec<_>>();
+        if fields.len() != 5 {
+            return Err(CronScheduleError::InvalidFieldCount(fields.len()));
+        }
+        let mut days_of_week = parse_field("day-of-week", fields[4], 0, 7)?;
+        // 7 is an alias for Sunday
+        if is_set(days_of_week, 7) {
+            days_of_week = (days_of_week | 1) & !(1 << 7);
+        }
+        Ok(Self {
+            expression: fields.join(" "),
+            minutes: parse_field("minute", fields[0], 0, 59)?,
+            hours: parse_field("hour", fields[1], 0, 23)?,
+            days_of_month: parse_field("day-of-month", fields[2], 1, 31)?,
+            months: parse_field("month", fields[3], 1, 12)?,
+            days_of_week,
+            any_day_of_month: fields[2] == "*",
+            any_day_of_week: fields[4] == "*",
+        })
+    }
+}
+
+impl fmt::Display for CronSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.expression)
+    }
+}
+
+fn is_set(mask: u64, value: u32) -> bool {
+    mask & (1 << value) != 0
+}
+
+/// Parses a single field into a bit mask with a bit set for every matching value in `min..=max`
+fn parse_field(field: &'static str, value: &str, min: u32, max: u32) -> Result<u64, CronScheduleError> {
+    let invalid = || CronScheduleError::InvalidField {
+        field,
+        value: value.to_string(),
+    };
+    let parse = |v: &str| {
+        v.parse::<u32>()
+            .ok()
+            .filter(|v| (min..=max).contains(v))
+            .ok_or_else(invalid)
+    };
+
+    let mut mask = 0u64;
+    for item in value.split(',') {
+        let (range, step) = match item.split_once('/') {
+            Some((range, step)) => (range, step.parse::<u32>().ok().filter(|s| *s > 0).ok_or_else(invalid)?),
+            None => (item, 1),
+        };
+        let (start, end) = match range {
+            "*" => (min, max),
+            range => match range.split_once('-') {
+                Some((start, end)) => (parse(start)?, parse(end)?),
+                // As in standard cron, `a/step` runs from `a` to the end of the range
+                None if item.contains('/') => (parse(range)?, max),
+                None => {
+                    let v = parse(range)?;
+                    (v, v)
+                },
+            },
+        };
+        if start > end {
+            return Err(invalid());
+        }
+        for v in (start..=end).step_by(step as usize) {
+            mask |= 1 << v;
+        }
+    }
+    Ok(mask)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn at(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    fn next(expression: &str, after: &str) -> Option<NaiveDateTime> {
+        expression.parse::<CronSchedule>().unwrap().next_after(at(after))
+    }
+
+    #[test]
+    fn it_parses_and_displays_expressions() {
+        let schedule = "0  12 * *   1-5".parse::<CronSchedule>().unwrap();
+        assert_eq!(schedule.to_string(), "0 12 * * 1-5");
+
+        assert_eq!(
+            "* * * *".parse::<CronSchedule>().unwrap_err(),
+            CronScheduleError::InvalidFieldCount(4)
+        );
+        for invalid in [
+            "60 * * * *",
+            "* 24 * * *",
+            "* * 0 * *",
+            "* * * 13 *",
+            "* * * * 8",
+            "5-1 * * * *",
+            "*/0 * * * *",
+            "a * * * *",
+            "1, * * * *",
+        ] {
+            assert!(
+                matches!(
+                    invalid.parse::<CronSchedule>(),
+                    Err(CronScheduleError::InvalidField { .. })
+                ),
+                "{}",
+                invalid
+            );
+        }
+    }
+
+    #[test]
+    fn it_finds_the_next_run() {
+        // Strictly after
+        assert_eq!(next("30 9 * * *", "2024-10-15 09:30"), Some(at("2024-10-16 09:30")));
+        assert_eq!(next("30 9 * * *", "2024-10-15 09:29"), Some(at("2024-10-15 09:30")));
+        // Steps, lists and ranges
+        assert_eq!(next("*/15 * * * *", "2024-10-15 09:31"), Some(at("2024-10-15 09:45")));
+        assert_eq!(next("5/20 * * * *", "2024-10-15 09:46"), Some(at("2024-10-15 10:05")));
+        assert_eq!(next("0 8,17 * * *", "2024-10-15 09:00"), Some(at("2024-10-15 17:00")));
+        assert_eq!(next("0 0 1 * *", "2024-12-15 00:00"), Some(at("2025-01-01 00:00")));
+        // 2024-10-19 is a Saturday
+        assert_eq!(next("0 9 * * 1-5", "2024-10-18 10:00"), Some(at("2024-10-21 09:00")));
+        assert_eq!(next("0 9 * * 7", "2024-10-18 10:00"), Some(at("2024-10-20 09:00")));
+        // Day-of-month or day-of-week when both are restricted
+        assert_eq!(next("0 0 25 * 0", "2024-10-18 10:00"), Some(at("2024-10-20 00:00")));
+        assert_eq!(next("0 0 29 2 *", "2024-03-01 00:00"), Some(at("2028-02-29 00:00")));
+        assert_eq!(next("0 0 30 2 *", "2024-03-01 00:00"), None);
+    }
+}
@@ -783,6 +783,11 @@ where
         Ok(self.db.get_client_key_value(RECOVERY_KEY.to_string())?.is_some())
     }
 
+    /// Returns true if the wallet tracks funds using only the view key and public spend key, and cannot spend them
+    pub fn is_watch_only(&self) -> bool {
+        self.wallet_type.is_watch_only()
+    }
+
     pub fn get_seed_words(&self, language: &MnemonicLanguage) -> Result<SeedWords, WalletError> {
         let master_seed = self.db.get_master_seed()?.ok_or_else(|| {
             WalletError::WalletStorageError(WalletStorageError::RecoverySeedError(
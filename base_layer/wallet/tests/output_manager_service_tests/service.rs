@@ -32,10 +32,11 @@ use minotari_wallet::{
         service::OutputManagerService,
         storage::{
             database::{OutputManagerBackend, OutputManagerDatabase},
-            models::SpendingPriority,
+            models::{SpendingPriority, UtxoConsolidationReason},
             sqlite_db::OutputManagerSqliteDatabase,
             OutputStatus,
         },
+        UtxoConsolidationParams,
         UtxoSelectionCriteria,
     },
     test_utils::create_consensus_constants,
@@ -48,6 +49,7 @@ use tari_common_types::{
     tari_address::TariAddress,
     transaction::TxId,
     types::{ComAndPubSignature, FixedHash, PublicKey},
+    wallet_types::DEFAULT_ACCOUNT_ID,
 };
 use tari_comms::{
     peer_manager::{NodeIdentity, PeerFeatures},
@@ -1086,6 +1088,57 @@ async fn test_get_balance() {
     assert_eq!(output_val, balance.pending_outgoing_balance);
 }
 
+#[tokio::test]
+async fn change_of_label_restricted_spend_keeps_a_label() {
+    let (connection, _tempdir) = get_temp_sqlite_database_connection();
+    let backend = OutputManagerSqliteDatabase::new(connection.clone());
+    let mut oms = setup_output_manager_service(backend.clone(), true).await;
+
+    for label in ["exchange", "mining"] {
+        let uo = make_input(
+            &mut OsRng.clone(),
+            MicroMinotari::from(2000),
+            &OutputFeatures::default(),
+            &oms.key_manager_handle,
+        )
+        .await;
+        oms.output_manager_handle.add_output(uo.clone(), None).await.unwrap();
+        backend
+            .mark_outputs_as_unspent(vec![(uo.hash(&oms.key_manager_handle).await.unwrap(), true)])
+            .unwrap();
+        oms.output_manager_handle
+            .set_output_label(
+                vec![uo.commitment(&oms.key_manager_handle).await.unwrap()],
+                Some(label.to_string()),
+            )
+            .await
+            .unwrap();
+    }
+
+    // The spend needs both outputs, so the change cannot keep a label shared by its inputs
+    let stp = oms
+        .output_manager_handle
+        .prepare_transaction_to_send(
+            TxId::new_random(),
+            MicroMinotari::from(3000),
+            UtxoSelectionCriteria::default().for_labels(vec!["exchange".to_string(), "mining".to_string()]),
+            OutputFeatures::default(),
+            MicroMinotari::from(4),
+            TransactionMetadata::default(),
+            "".to_string(),
+            script!(Nop).unwrap(),
+            Covenant::default(),
+            MicroMinotari::zero(),
+        )
+        .await
+        .unwrap();
+    let change = stp.get_change_output().unwrap().unwrap();
+    let change = OutputManagerDatabase::new(backend)
+        .fetch_by_commitment(change.commitment(&oms.key_manager_handle).await.unwrap())
+        .unwrap();
+    assert_eq!(change.label.as_deref(), Some("exchange"));
+}
+
 #[tokio::test]
 async fn sending_transaction_persisted_while_offline() {
     let (connection, _tempdir) = get_temp_sqlite_database_connection();
@@ -1312,6 +1365,140 @@ async fn it_handles_large_coin_splits() {
     assert_eq!(coin_split_tx.body.outputs().len(), split_count + 1);
 }
 
+#[tokio::test]
+async fn consolidate_utxos_below_threshold() {
+    let (connection, _tempdir) = get_temp_sqlite_database_connection();
+    let backend = OutputManagerSqliteDatabase::new(connection.clone());
+    let mut oms = setup_output_manager_service(backend.clone(), true).await;
+
+    // Five small outputs and one large output
+    let mut small_value = MicroMinotari::zero();
+    for value in [20 * T, 5_000 * uT, 6_000 * uT, 7_000 * uT, 8_000 * uT, 9_000 * uT] {
+        let uo = make_input(&mut OsRng, value, &OutputFeatures::default(), &oms.key_manager_handle).await;
+        oms.output_manager_handle.add_output(uo.clone(), None).await.unwrap();
+        backend
+            .mark_outputs_as_unspent(vec![(uo.hash(&oms.key_manager_handle).await.unwrap(), true)])
+            .unwrap();
+        if value < T {
+            small_value += value;
+        }
+    }
+
+    let params = UtxoConsolidationParams {
+        threshold: Some(T),
+        max_inputs: Some(4),
+        fee_per_gram: MicroMinotari::from(1),
+        account: None,
+        avoid_mixing_labels: None,
+    };
+    let preview = oms
+        .output_manager_handle
+        .preview_utxo_consolidation(params.clone())
+        .await
+        .unwrap();
+    // The smallest outputs are joined first
+    assert_eq!(preview.num_inputs, 4);
+    assert_eq!(preview.input_value, 26_000 * uT);
+    assert_eq!(preview.output_value, preview.input_value - preview.fee);
+    assert_eq!(preview.resulting_output_count, 3);
+    // A preview does not touch any outputs
+    assert_eq!(oms.output_manager_handle.get_unspent_outputs().await.unwrap().len(), 6);
+
+    let params = UtxoConsolidationParams {
+        max_inputs: None,
+        ..params
+    };
+    let (consolidation, tx) = oms.output_manager_handle.consolidate_utxos(params).await.unwrap();
+    assert_eq!(tx.body.inputs().len(), 5);
+    assert_eq!(tx.body.outputs().len(), 1);
+    assert_eq!(consolidation.reason, UtxoConsolidationReason::Manual);
+    assert_eq!(consolidation.num_inputs, 5);
+    assert_eq!(consolidation.input_value, small_value);
+    assert_eq!(tx.body.get_total_fee().unwrap(), consolidation.fee);
+    assert_eq!(oms.output_manager_handle.get_unspent_outputs().await.unwrap().len(), 1);
+    let consolidations = oms.output_manager_handle.get_utxo_consolidations().await.unwrap();
+    assert_eq!(consolidations.len(), 1);
+    assert_eq!(consolidations[0].tx_id, consolidation.tx_id);
+    assert_eq!(consolidations[0].reason, UtxoConsolidationReason::Manual);
+    assert_eq!(consolidations[0].fee, consolidation.fee);
+
+    // Nothing is left to consolidate
+    let err = oms
+        .output_manager_handle
+        .preview_utxo_consolidation(UtxoConsolidationParams {
+            threshold: Some(T),
+            fee_per_gram: MicroMinotari::from(1),
+            ..Default::default()
+        })
+        .await
+        .unwrap_err();
+    assert!(matches!(err, OutputManagerError::InvalidArgument(_)));
+}
+
+#[tokio::test]
+async fn consolidation_does_not_mix_labels() {
+    let (connection, _tempdir) = get_temp_sqlite_database_connection();
+    let backend = OutputManagerSqliteDatabase::new(connection.clone());
+    let mut oms = setup_output_manager_service(backend.clone(), true).await;
+
+    // Three outputs labelled `mining` and two without a label
+    let mut mining_value = MicroMinotari::zero();
+    for (value, label) in [
+        (5_000 * uT, Some("mining")),
+        (6_000 * uT, None),
+        (7_000 * uT, Some("mining")),
+        (8_000 * uT, None),
+        (9_000 * uT, Some("mining")),
+    ] {
+        let uo = make_input(&mut OsRng, value, &OutputFeatures::default(), &oms.key_manager_handle).await;
+        oms.output_manager_handle.add_output(uo.clone(), None).await.unwrap();
+        backend
+            .mark_outputs_as_unspent(vec![(uo.hash(&oms.key_manager_handle).await.unwrap(), true)])
+            .unwrap();
+        if let Some(label) = label {
+            oms.output_manager_handle
+                .set_output_label(
+                    vec![uo.commitment(&oms.key_manager_handle).await.unwrap()],
+                    Some(label.to_string()),
+                )
+                .await
+                .unwrap();
+            mining_value += value;
+        }
+    }
+
+    let params = UtxoConsolidationParams {
+        threshold: Some(T),
+        fee_per_gram: MicroMinotari::from(1),
+        ..Default::default()
+    };
+    // Only the largest label group is joined, and the joined output keeps its label and account
+    let (consolidation, tx) = oms
+        .output_manager_handle
+        .consolidate_utxos(params.clone())
+        .await
+        .unwrap();
+    assert_eq!(consolidation.num_inputs, 3);
+    assert_eq!(consolidation.input_value, mining_value);
+    assert_eq!(tx.body.get_total_fee().unwrap(), consolidation.fee);
+    let joined = OutputManagerDatabase::new(backend.clone())
+        .fetch_by_commitment(tx.body.outputs()[0].commitment.clone())
+        .unwrap();
+    assert_eq!(joined.label.as_deref(), Some("mining"));
+    assert_eq!(joined.account_id, DEFAULT_ACCOUNT_ID);
+
+    // Mixing labels joins everything that is left
+    let preview = oms
+        .output_manager_handle
+        .preview_utxo_consolidation(UtxoConsolidationParams {
+            avoid_mixing_labels: Some(false),
+            ..params
+        })
+        .await
+        .unwrap();
+    assert_eq!(preview.num_inputs, 2);
+}
+
 #[tokio::test]
 #[allow(clippy::too_many_lines)]
 async fn test_txo_validation() {
@@ -32,11 +32,13 @@ use minotari_wallet::output_manager_service::{
         OutputSource,
         OutputStatus,
     },
+    UtxoSelectionCriteria,
 };
 use rand::{rngs::OsRng, RngCore};
 use tari_common_types::{
     transaction::TxId,
     types::{FixedHash, HashOutput, PrivateKey},
+    wallet_types::DEFAULT_ACCOUNT_ID,
 };
 use tari_core::transactions::{
     key_manager::create_memory_db_key_manager,
@@ -626,6 +628,192 @@ pub async fn test_short_term_encumberance() {
     );
 }
 
+#[tokio::test]
+pub async fn test_accounts() {
+    let (connection, _tempdir) = get_temp_sqlite_database_connection();
+    let backend = OutputManagerSqliteDatabase::new(connection);
+    let db = OutputManagerDatabase::new(backend);
+
+    // The default account always exists
+    let accounts = db.fetch_accounts().unwrap();
+    assert_eq!(accounts.len(), 1);
+    assert_eq!(accounts[0].id, DEFAULT_ACCOUNT_ID);
+
+    let savings = db.create_account("savings").unwrap();
+    assert_eq!(savings.id, 1);
+    let err = db.create_account("savings").unwrap_err();
+    assert!(matches!(err, OutputManagerStorageError::AccountAlreadyExists(_)));
+    assert_eq!(db.fetch_accounts().unwrap().len(), 2);
+
+    let mut unspent_outputs = Vec::new();
+    let key_manager = create_memory_db_key_manager().unwrap();
+    for _ in 0..4 {
+        let kmo = make_input(
+            &mut OsRng,
+            MicroMinotari::from(100 + OsRng.next_u64() % 1000),
+            &OutputFeatures::default(),
+            &key_manager,
+        )
+        .await;
+        let kmo = DbWalletOutput::from_wallet_output(kmo, &key_manager, None, OutputSource::Standard, None, None)
+            .await
+            .unwrap();
+        db.add_unspent_output(kmo.clone()).unwrap();
+        db.mark_outputs_as_unspent(vec![(kmo.hash, true)]).unwrap();
+        unspent_outputs.push(kmo);
+    }
+    let total = |outputs: &[DbWalletOutput]| {
+        outputs
+            .iter()
+            .fold(MicroMinotari::from(0), |acc, x| acc + x.wallet_output.value)
+    };
+
+    // All outputs are received into the default account
+    assert_eq!(
+        db.get_account_balance(None, savings.id).unwrap().available_balance,
+        0.into()
+    );
+    let commitments = unspent_outputs[0..2]
+        .iter()
+        .map(|o| o.commitment.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(db.move_outputs_to_account(&commitments, savings.id).unwrap(), 2);
+    let err = db.move_outputs_to_account(&commitments, 99).unwrap_err();
+    assert!(matches!(err, OutputManagerStorageError::AccountNotFound(_)));
+
+    assert_eq!(
+        db.get_account_balance(None, savings.id).unwrap().available_balance,
+        total(&unspent_outputs[0..2])
+    );
+    assert_eq!(
+        db.get_account_balance(None, DEFAULT_ACCOUNT_ID)
+            .unwrap()
+            .available_balance,
+        total(&unspent_outputs[2..4])
+    );
+    assert_eq!(db.get_balance(None).unwrap().available_balance, total(&unspent_outputs));
+
+    // Only outputs of the account are selected for spending
+    let selection_criteria = UtxoSelectionCriteria::default().for_account(Some(savings.id));
+    let selected = db
+        .fetch_unspent_outputs_for_spending(&selection_criteria, MicroMinotari::from(1), None)
+        .unwrap();
+    assert_eq!(selected.len(), 2);
+    assert!(selected.iter().all(|o| o.account_id == savings.id));
+
+    // Outputs received by a transaction belong to the account that funded it
+    let change = make_input(
+        &mut OsRng,
+        MicroMinotari::from(50),
+        &OutputFeatures::default(),
+        &key_manager,
+    )
+    .await;
+    let change = DbWalletOutput::from_wallet_output(change, &key_manager, None, OutputSource::Standard, None, None)
+        .await
+        .unwrap();
+    db.encumber_outputs(1u64.into(), vec![selected[0].clone()], vec![change.clone()])
+        .unwrap();
+    db.confirm_encumbered_outputs(1u64.into()).unwrap();
+    assert_eq!(
+        db.fetch_by_commitment(change.commitment).unwrap().account_id,
+        savings.id
+    );
+    assert_eq!(db.fetch_tx_ids_for_account(savings.id).unwrap(), vec![TxId::from(1u64)]);
+    assert!(db.fetch_tx_ids_for_account(DEFAULT_ACCOUNT_ID).unwrap().is_empty());
+}
+
+#[tokio::test]
+pub async fn test_output_labels_and_freezing() {
+    let (connection, _tempdir) = get_temp_sqlite_database_connection();
+    let backend = OutputManagerSqliteDatabase::new(connection);
+    let db = OutputManagerDatabase::new(backend);
+
+    let mut unspent_outputs = Vec::new();
+    let key_manager = create_memory_db_key_manager().unwrap();
+    for _ in 0..4 {
+        let kmo = make_input(
+            &mut OsRng,
+            MicroMinotari::from(100 + OsRng.next_u64() % 1000),
+            &OutputFeatures::default(),
+            &key_manager,
+        )
+        .await;
+        let kmo = DbWalletOutput::from_wallet_output(kmo, &key_manager, None, OutputSource::Standard, None, None)
+            .await
+            .unwrap();
+        db.add_unspent_output(kmo.clone()).unwrap();
+        db.mark_outputs_as_unspent(vec![(kmo.hash, true)]).unwrap();
+        unspent_outputs.push(kmo);
+    }
+    let commitments = unspent_outputs.iter().map(|o| o.commitment.clone()).collect::<Vec<_>>();
+
+    assert_eq!(
+        db.set_output_label(&commitments[0..2], Some("exchange".to_string()))
+            .unwrap(),
+        2
+    );
+    assert_eq!(db.set_outputs_frozen(&commitments[3..4], true).unwrap(), 1);
+    let frozen = db.fetch_by_commitment(commitments[3].clone()).unwrap();
+    assert!(frozen.frozen);
+    assert_eq!(frozen.label, None);
+
+    // Frozen outputs are not available to spend
+    let unfrozen_value = unspent_outputs[0..3]
+        .iter()
+        .fold(MicroMinotari::from(0), |acc, o| acc + o.wallet_output.value);
+    assert_eq!(db.get_balance(None).unwrap().available_balance, unfrozen_value);
+    assert_eq!(db.get_balance(Some(0)).unwrap().available_balance, unfrozen_value);
+
+    // Frozen outputs are never selected
+    let selected = db
+        .fetch_unspent_outputs_for_spending(&UtxoSelectionCriteria::default(), MicroMinotari::from(1), None)
+        .unwrap();
+    assert_eq!(selected.len(), 3);
+    assert!(selected.iter().all(|o| o.commitment != commitments[3]));
+
+    // Selection can be restricted to labelled outputs
+    let selection_criteria = UtxoSelectionCriteria::default().for_labels(vec!["exchange".to_string()]);
+    let selected = db
+        .fetch_unspent_outputs_for_spending(&selection_criteria, MicroMinotari::from(1), None)
+        .unwrap();
+    assert_eq!(selected.len(), 2);
+    assert!(selected.iter().all(|o| o.label.as_deref() == Some("exchange")));
+
+    assert_eq!(db.set_outputs_frozen(&commitments[3..4], false).unwrap(), 1);
+    let selected = db
+        .fetch_unspent_outputs_for_spending(&UtxoSelectionCriteria::default(), MicroMinotari::from(1), None)
+        .unwrap();
+    assert_eq!(selected.len(), 4);
+    assert_eq!(
+        db.get_balance(None).unwrap().available_balance,
+        unfrozen_value + unspent_outputs[3].wallet_output.value
+    );
+
+    // Change keeps the label shared by all of the spent outputs
+    let change = make_input(
+        &mut OsRng,
+        MicroMinotari::from(50),
+        &OutputFeatures::default(),
+        &key_manager,
+    )
+    .await;
+    let change = DbWalletOutput::from_wallet_output(change, &key_manager, None, OutputSource::Standard, None, None)
+        .await
+        .unwrap();
+    let labelled = unspent_outputs[0..2]
+        .iter()
+        .map(|o| db.fetch_by_commitment(o.commitment.clone()).unwrap())
+        .collect::<Vec<_>>();
+    db.encumber_outputs(1u64.into(), labelled, vec![change.clone()])
+        .unwrap();
+    db.confirm_encumbered_outputs(1u64.into()).unwrap();
+    assert_eq!(
+        db.fetch_by_commitment(change.commitment).unwrap().label.as_deref(),
+        Some("exchange")
+    );
+}
+
 #[tokio::test]
 pub async fn test_no_duplicate_outputs() {
     let (connection, _tempdir) = get_temp_sqlite_database_connection();
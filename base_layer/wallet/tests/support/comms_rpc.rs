@@ -46,6 +46,8 @@ use tari_core::{
     proto::{
         base_node::{
             ChainMetadata as ChainMetadataProto,
+            EstimateFeeRequest,
+            EstimateFeeResponse,
             FetchMatchingUtxos,
             FetchUtxosResponse,
             GetMempoolFeePerGramStatsRequest,
@@ -111,6 +113,7 @@ pub struct BaseNodeWalletRpcMockState {
     utxos: Arc<Mutex<Vec<TransactionOutput>>>,
     blocks: Arc<Mutex<HashMap<u64, BlockHeader>>>,
     get_mempool_fee_per_gram_stats: Arc<Mutex<GetMempoolFeePerGramStatsResponse>>,
+    estimate_fee: Arc<Mutex<EstimateFeeResponse>>,
     utxos_by_block: Arc<Mutex<Vec<UtxosByBlock>>>,
     sync_utxos_by_block_trigger_channel: Arc<Mutex<Option<mpsc::Receiver<usize>>>>,
 }
@@ -175,6 +178,7 @@ impl BaseNodeWalletRpcMockState {
             utxos: Arc::new(Mutex::new(Vec::new())),
             blocks: Arc::new(Mutex::new(Default::default())),
             get_mempool_fee_per_gram_stats: Default::default(),
+            estimate_fee: Default::default(),
 
             utxos_by_block: Arc::new(Mutex::new(vec![])),
             sync_utxos_by_block_trigger_channel: Arc::new(Mutex::new(None)),
@@ -243,6 +247,11 @@ impl BaseNodeWalletRpcMockState {
         *lock = resp;
     }
 
+    pub fn set_estimate_fee_response(&self, resp: EstimateFeeResponse) {
+        let mut lock = acquire_lock!(self.estimate_fee);
+        *lock = resp;
+    }
+
     pub fn set_utxos_by_block(&self, utxos_by_block: Vec<UtxosByBlock>) {
         let mut lock = acquire_lock!(self.utxos_by_block);
         *lock = utxos_by_block;
@@ -850,6 +859,13 @@ impl BaseNodeWalletService for BaseNodeWalletRpcMockService {
             acquire_lock!(self.state.get_mempool_fee_per_gram_stats).clone(),
         ))
     }
+
+    async fn estimate_fee(
+        &self,
+        _request: Request<EstimateFeeRequest>,
+    ) -> Result<Response<EstimateFeeResponse>, RpcStatus> {
+        Ok(Response::new(acquire_lock!(self.state.estimate_fee).clone()))
+    }
 }
 
 #[derive(Clone, Debug)]
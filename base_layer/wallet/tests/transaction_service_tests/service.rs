@@ -42,6 +42,7 @@ use minotari_wallet::{
     connectivity_service::{
         create_wallet_connectivity_mock,
         BaseNodePeerManager,
+        OnlineStatus,
         WalletConnectivityHandle,
         WalletConnectivityInitializer,
         WalletConnectivityInterface,
@@ -72,11 +73,20 @@ use minotari_wallet::{
     },
     transaction_service::{
         config::TransactionServiceConfig,
+        error::TransactionServiceError,
         handle::{TransactionEvent, TransactionSendStatus, TransactionServiceHandle},
         service::TransactionService,
         storage::{
             database::{DbKeyValuePair, TransactionBackend, TransactionDatabase, WriteOperation},
-            models::{CompletedTransaction, InboundTransaction, OutboundTransaction, WalletTransaction},
+            models::{
+                BatchPaymentRecipient,
+                CompletedTransaction,
+                InboundTransaction,
+                OutboundTransaction,
+                PaymentSchedule,
+                TxCancellationReason,
+                WalletTransaction,
+            },
             sqlite_db::TransactionServiceSqliteDatabase,
         },
         TransactionServiceInitializer,
@@ -90,7 +100,7 @@ use tari_common_types::{
     tari_address::TariAddress,
     transaction::{ImportStatus, TransactionDirection, TransactionStatus, TxId},
     types::{FixedHash, PrivateKey, PublicKey, Signature},
-    wallet_types::{ProvidedKeysWallet, WalletType},
+    wallet_types::{ProvidedKeysWallet, WalletType, DEFAULT_ACCOUNT_ID},
 };
 use tari_comms::{
     message::EnvelopeBody,
@@ -137,6 +147,7 @@ use tari_core::{
             Transaction,
         },
         transaction_protocol::{
+            partially_signed::{PartiallySignedTransactionError, PaymentKind},
             proto::protocol as proto,
             recipient::RecipientSignedMessage,
             sender::TransactionSenderMessage,
@@ -194,6 +205,44 @@ async fn setup_transaction_service<P: AsRef<Path>>(
     WalletConnectivityHandle,
     MemoryDbKeyManager,
     OutputManagerSqliteDatabase,
+) {
+    let wallet_type = WalletType::ProvidedKeys(ProvidedKeysWallet {
+        public_spend_key: PublicKey::from_secret_key(node_identity.secret_key()),
+        private_spend_key: Some(node_identity.secret_key().clone()),
+        view_key: SK::random(&mut OsRng),
+        private_comms_key: Some(node_identity.secret_key().clone()),
+    });
+    setup_transaction_service_with_wallet_type(
+        node_identity,
+        peers,
+        consensus_manager,
+        factories,
+        db_connection,
+        database_path,
+        discovery_request_timeout,
+        shutdown_signal,
+        wallet_type,
+    )
+    .await
+}
+
+async fn setup_transaction_service_with_wallet_type<P: AsRef<Path>>(
+    node_identity: Arc<NodeIdentity>,
+    peers: Vec<Arc<NodeIdentity>>,
+    consensus_manager: ConsensusManager,
+    factories: CryptoFactories,
+    db_connection: WalletDbConnection,
+    database_path: P,
+    discovery_request_timeout: Duration,
+    shutdown_signal: ShutdownSignal,
+    wallet_type: WalletType,
+) -> (
+    TransactionServiceHandle,
+    OutputManagerHandle,
+    CommsNode,
+    WalletConnectivityHandle,
+    MemoryDbKeyManager,
+    OutputManagerSqliteDatabase,
 ) {
     let (publisher, subscription_factory) = pubsub_connector(100);
     let subscription_factory = Arc::new(subscription_factory);
@@ -228,12 +277,7 @@ async fn setup_transaction_service<P: AsRef<Path>>(
     let key_ga = Key::from_slice(&key);
     let db_cipher = XChaCha20Poly1305::new(key_ga);
     let kms_backend = KeyManagerSqliteDatabase::init(connection, db_cipher);
-    let wallet_type = Arc::new(WalletType::ProvidedKeys(ProvidedKeysWallet {
-        public_spend_key: PublicKey::from_secret_key(node_identity.secret_key()),
-        private_spend_key: Some(node_identity.secret_key().clone()),
-        view_key: SK::random(&mut OsRng),
-        private_comms_key: Some(node_identity.secret_key().clone()),
-    }));
+    let wallet_type = Arc::new(wallet_type);
     let handles = StackBuilder::new(shutdown_signal)
         .add_initializer(RegisterHandle::new(dht))
         .add_initializer(RegisterHandle::new(comms.connectivity()))
@@ -1673,6 +1717,215 @@ async fn send_one_sided_transaction_to_other() {
     assert!(found, "'TransactionCompletedImmediately(_)' event not found");
 }
 
+#[tokio::test]
+async fn bump_fee_replaces_one_sided_transaction() {
+    let network = Network::LocalNet;
+    let consensus_manager = ConsensusManager::builder(network).build().unwrap();
+    let factories = CryptoFactories::default();
+    let alice_node_identity = Arc::new(NodeIdentity::random(
+        &mut OsRng,
+        get_next_memory_address(),
+        PeerFeatures::COMMUNICATION_NODE,
+    ));
+    let bob_node_identity = Arc::new(NodeIdentity::random(
+        &mut OsRng,
+        get_next_memory_address(),
+        PeerFeatures::COMMUNICATION_NODE,
+    ));
+
+    let temp_dir = tempdir().unwrap();
+    let database_path = temp_dir.path().to_str().unwrap().to_string();
+    let db_connection = make_wallet_database_memory_connection();
+
+    let shutdown = Shutdown::new();
+    let (mut alice_ts, mut alice_oms, _alice_comms, _alice_connectivity, key_manager_handle, alice_db) =
+        setup_transaction_service(
+            alice_node_identity,
+            vec![],
+            consensus_manager,
+            factories.clone(),
+            db_connection,
+            database_path,
+            Duration::from_secs(0),
+            shutdown.to_signal(),
+        )
+        .await;
+
+    let initial_wallet_value = 25000.into();
+    let uo1 = make_input(
+        &mut OsRng,
+        initial_wallet_value,
+        &OutputFeatures::default(),
+        &key_manager_handle,
+    )
+    .await;
+    alice_oms.add_output(uo1.clone(), None).await.unwrap();
+    alice_db
+        .mark_outputs_as_unspent(vec![(uo1.hash(&key_manager_handle).await.unwrap(), true)])
+        .unwrap();
+
+    let value = 10000.into();
+    let bob_view_key = PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng));
+    let bob_address = TariAddress::new_dual_address_with_default_features(
+        bob_view_key,
+        bob_node_identity.public_key().clone(),
+        network,
+    );
+    let tx_id = alice_ts
+        .send_one_sided_to_stealth_address_transaction(
+            bob_address.clone(),
+            value,
+            UtxoSelectionCriteria::default(),
+            OutputFeatures::default(),
+            5.into(),
+            "Bump me".to_string(),
+            PaymentId::Empty,
+        )
+        .await
+        .unwrap();
+    let original = alice_ts.get_completed_transaction(tx_id).await.unwrap();
+
+    // The replacement must pay a higher fee per gram
+    let err = alice_ts.bump_fee(tx_id, 1.into()).await.unwrap_err();
+    assert!(matches!(err, TransactionServiceError::FeeBumpNotPossible(_)));
+    // and a failed replacement leaves the original in place
+    assert_eq!(alice_ts.get_completed_transaction(tx_id).await.unwrap().cancelled, None);
+    assert_eq!(
+        alice_oms.get_balance().await.unwrap().pending_incoming_balance,
+        initial_wallet_value - value - original.fee
+    );
+
+    let replacement_tx_id = alice_ts.bump_fee(tx_id, 20.into()).await.unwrap();
+    assert_ne!(replacement_tx_id, tx_id);
+
+    let cancelled = alice_ts.get_any_transaction(tx_id).await.unwrap().unwrap();
+    match cancelled {
+        WalletTransaction::Completed(tx) => assert_eq!(tx.cancelled, Some(TxCancellationReason::Replaced)),
+        WalletTransaction::PendingInbound(_) | WalletTransaction::PendingOutbound(_) => {
+            panic!("Replaced transaction should be a completed transaction")
+        },
+    }
+    let replacement = alice_ts.get_completed_transaction(replacement_tx_id).await.unwrap();
+    assert_eq!(replacement.destination_address, bob_address);
+    assert_eq!(replacement.amount, value);
+    assert!(replacement.fee > original.fee);
+    // The replacement spends the same input
+    assert_eq!(
+        replacement.transaction.body.inputs()[0].commitment().unwrap(),
+        original.transaction.body.inputs()[0].commitment().unwrap()
+    );
+    assert_eq!(
+        alice_oms.get_balance().await.unwrap().pending_incoming_balance,
+        initial_wallet_value - value - replacement.fee
+    );
+
+    // A cancelled transaction cannot be bumped again
+    let err = alice_ts.bump_fee(tx_id, 40.into()).await.unwrap_err();
+    assert!(matches!(err, TransactionServiceError::FeeBumpNotPossible(_)));
+}
+
+#[tokio::test]
+async fn send_batch_transaction_to_many_recipients() {
+    let network = Network::LocalNet;
+    let consensus_manager = ConsensusManager::builder(network).build().unwrap();
+    let factories = CryptoFactories::default();
+    let alice_node_identity = Arc::new(NodeIdentity::random(
+        &mut OsRng,
+        get_next_memory_address(),
+        PeerFeatures::COMMUNICATION_NODE,
+    ));
+
+    let temp_dir = tempdir().unwrap();
+    let database_path = temp_dir.path().to_str().unwrap().to_string();
+    let db_connection = make_wallet_database_memory_connection();
+
+    let shutdown = Shutdown::new();
+    let (mut alice_ts, mut alice_oms, _alice_comms, _alice_connectivity, key_manager_handle, alice_db) =
+        setup_transaction_service(
+            alice_node_identity,
+            vec![],
+            consensus_manager,
+            factories.clone(),
+            db_connection,
+            database_path,
+            Duration::from_secs(0),
+            shutdown.to_signal(),
+        )
+        .await;
+
+    let initial_wallet_value = 25000.into();
+    let uo1 = make_input(
+        &mut OsRng,
+        initial_wallet_value,
+        &OutputFeatures::default(),
+        &key_manager_handle,
+    )
+    .await;
+    alice_oms.add_output(uo1.clone(), None).await.unwrap();
+    alice_db
+        .mark_outputs_as_unspent(vec![(uo1.hash(&key_manager_handle).await.unwrap(), true)])
+        .unwrap();
+
+    let err = alice_ts
+        .send_batch_transaction(vec![], UtxoSelectionCriteria::default(), 5.into(), "Empty".to_string())
+        .await
+        .unwrap_err();
+    assert!(matches!(err, TransactionServiceError::InvalidBatchPayment(_)));
+
+    let recipients = (0..3u64)
+        .map(|i| {
+            let node_identity =
+                NodeIdentity::random(&mut OsRng, get_next_memory_address(), PeerFeatures::COMMUNICATION_NODE);
+            BatchPaymentRecipient {
+                address: TariAddress::new_dual_address_with_default_features(
+                    PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng)),
+                    node_identity.public_key().clone(),
+                    network,
+                ),
+                amount: (2000 * (i + 1)).into(),
+                payment_id: PaymentId::Open(format!("invoice-{}", i).into_bytes()),
+            }
+        })
+        .collect::<Vec<_>>();
+    let total = recipients.iter().map(|r| r.amount).sum::<MicroMinotari>();
+
+    let tx_id = alice_ts
+        .send_batch_transaction(
+            recipients.clone(),
+            UtxoSelectionCriteria::default(),
+            5.into(),
+            "Payroll".to_string(),
+        )
+        .await
+        .unwrap();
+
+    let completed_tx = alice_ts.get_completed_transaction(tx_id).await.unwrap();
+    assert_eq!(completed_tx.destination_address, recipients[0].address);
+    assert_eq!(completed_tx.amount, total);
+    // One output per recipient plus the change
+    assert_eq!(completed_tx.transaction.body.outputs().len(), recipients.len() + 1);
+    assert_eq!(completed_tx.transaction.body.kernels().len(), 1);
+    assert_eq!(
+        alice_oms.get_balance().await.unwrap().pending_incoming_balance,
+        initial_wallet_value - total - completed_tx.fee
+    );
+
+    // Every recipient is stored with the transaction, with the payment id that was sent to them
+    let stored = alice_ts.get_batch_payment_recipients(tx_id).await.unwrap();
+    assert_eq!(stored.len(), recipients.len());
+    for (i, (stored, recipient)) in stored.iter().zip(&recipients).enumerate() {
+        assert_eq!(stored.address, recipient.address);
+        assert_eq!(stored.amount, recipient.amount);
+        assert!(
+            matches!(&stored.payment_id, PaymentId::AddressAndData(_, data) if *data == format!("invoice-{}", i).into_bytes())
+        );
+    }
+
+    // Replacing the transaction with a single payment would drop the other recipients
+    let err = alice_ts.bump_fee(tx_id, 20.into()).await.unwrap_err();
+    assert!(matches!(err, TransactionServiceError::FeeBumpNotPossible(_)));
+}
+
 #[tokio::test]
 async fn recover_one_sided_transaction() {
     let network = Network::LocalNet;
@@ -1938,6 +2191,459 @@ async fn recover_stealth_one_sided_transaction() {
     assert!(recovered_outputs_2.is_empty());
 }
 
+#[tokio::test]
+async fn recover_stealth_one_sided_transaction_to_account() {
+    let network = Network::LocalNet;
+    let consensus_manager = ConsensusManager::builder(network).build().unwrap();
+    let factories = CryptoFactories::default();
+    // Alice's parameters
+    let alice_node_identity = Arc::new(NodeIdentity::random(
+        &mut OsRng,
+        get_next_memory_address(),
+        PeerFeatures::COMMUNICATION_NODE,
+    ));
+
+    // Bob's parameters
+    let bob_node_identity = Arc::new(NodeIdentity::random(
+        &mut OsRng,
+        get_next_memory_address(),
+        PeerFeatures::COMMUNICATION_NODE,
+    ));
+
+    let base_node_identity = Arc::new(NodeIdentity::random(
+        &mut OsRng,
+        get_next_memory_address(),
+        PeerFeatures::COMMUNICATION_NODE,
+    ));
+
+    log::info!(
+        "manage_single_transaction: Alice: '{}', Bob: '{}', Base: '{}'",
+        alice_node_identity.node_id().short_str(),
+        bob_node_identity.node_id().short_str(),
+        base_node_identity.node_id().short_str()
+    );
+
+    let temp_dir = tempdir().unwrap();
+    let temp_dir2 = tempdir().unwrap();
+    let database_path = temp_dir.path().to_str().unwrap().to_string();
+    let database_path2 = temp_dir2.path().to_str().unwrap().to_string();
+
+    let alice_connection = make_wallet_database_memory_connection();
+    let bob_connection = make_wallet_database_memory_connection();
+
+    let shutdown = Shutdown::new();
+    let (mut alice_ts, alice_oms, _alice_comms, _alice_connectivity, alice_key_manager_handle, alice_db) =
+        setup_transaction_service(
+            alice_node_identity,
+            vec![],
+            consensus_manager.clone(),
+            factories.clone(),
+            alice_connection,
+            database_path,
+            Duration::from_secs(0),
+            shutdown.to_signal(),
+        )
+        .await;
+
+    let (_bob_ts, mut bob_oms, _bob_comms, _bob_connectivity, bob_key_manager_handle, _bob_db) =
+        setup_transaction_service(
+            bob_node_identity.clone(),
+            vec![],
+            consensus_manager,
+            factories.clone(),
+            bob_connection,
+            database_path2,
+            Duration::from_secs(0),
+            shutdown.to_signal(),
+        )
+        .await;
+
+    let bob_view_key = bob_key_manager_handle.get_view_key().await.unwrap();
+
+    let initial_wallet_value = 25000.into();
+    let uo1 = make_input(
+        &mut OsRng,
+        initial_wallet_value,
+        &OutputFeatures::default(),
+        &alice_key_manager_handle,
+    )
+    .await;
+    let mut alice_oms_clone = alice_oms;
+    alice_oms_clone.add_output(uo1.clone(), None).await.unwrap();
+    alice_db
+        .mark_outputs_as_unspent(vec![(uo1.hash(&alice_key_manager_handle).await.unwrap(), true)])
+        .unwrap();
+
+    let message = "".to_string();
+    let value = 10000.into();
+    let mut alice_ts_clone = alice_ts.clone();
+
+    // Funds sent to the address of an account are received into that account
+    let savings = bob_oms.create_account("savings".to_string()).await.unwrap();
+    let bob_address = bob_oms.get_account_address(savings.id).await.unwrap();
+    assert_ne!(bob_address.public_view_key(), Some(&bob_view_key.pub_key));
+    let tx_id = alice_ts_clone
+        .send_one_sided_to_stealth_address_transaction(
+            bob_address,
+            value,
+            UtxoSelectionCriteria::default(),
+            OutputFeatures::default(),
+            20.into(),
+            message.clone(),
+            PaymentId::Empty,
+        )
+        .await
+        .expect("Alice sending one-sided tx to Bob");
+
+    let completed_tx = alice_ts
+        .get_completed_transaction(tx_id)
+        .await
+        .expect("Could not find completed one-sided tx");
+    let outputs = completed_tx.transaction.body.outputs().clone();
+
+    let recovered_outputs_1 = bob_oms
+        .scan_outputs_for_one_sided_payments(outputs.iter().map(|o| (o.clone(), None)).collect())
+        .await
+        .unwrap();
+    // Bob should be able to claim 1 output into the account it was sent to.
+    assert_eq!(1, recovered_outputs_1.len());
+    assert_eq!(value, recovered_outputs_1[0].output.value);
+    let account_balance = bob_oms.get_account_balance(savings.id).await.unwrap();
+    assert_eq!(
+        account_balance.pending_incoming_balance + account_balance.available_balance,
+        value
+    );
+    let default_balance = bob_oms.get_account_balance(DEFAULT_ACCOUNT_ID).await.unwrap();
+    assert_eq!(
+        default_balance.pending_incoming_balance + default_balance.available_balance,
+        MicroMinotari::zero()
+    );
+
+    // Should ignore already existing outputs
+    let recovered_outputs_2 = bob_oms
+        .scan_outputs_for_one_sided_payments(outputs.into_iter().map(|o| (o, None)).collect())
+        .await
+        .unwrap();
+    assert!(recovered_outputs_2.is_empty());
+}
+
+#[tokio::test]
+async fn watch_only_wallet_scans_one_sided_payments_but_cannot_spend() {
+    let network = Network::LocalNet;
+    let consensus_manager = ConsensusManager::builder(network).build().unwrap();
+    let factories = CryptoFactories::default();
+    let alice_node_identity = Arc::new(NodeIdentity::random(
+        &mut OsRng,
+        get_next_memory_address(),
+        PeerFeatures::COMMUNICATION_NODE,
+    ));
+    let bob_node_identity = Arc::new(NodeIdentity::random(
+        &mut OsRng,
+        get_next_memory_address(),
+        PeerFeatures::COMMUNICATION_NODE,
+    ));
+
+    let temp_dir = tempdir().unwrap();
+    let temp_dir2 = tempdir().unwrap();
+    let database_path = temp_dir.path().to_str().unwrap().to_string();
+    let database_path2 = temp_dir2.path().to_str().unwrap().to_string();
+
+    let shutdown = Shutdown::new();
+    let (mut alice_ts, mut alice_oms, _alice_comms, _alice_connectivity, alice_key_manager_handle, alice_db) =
+        setup_transaction_service(
+            alice_node_identity,
+            vec![],
+            consensus_manager.clone(),
+            factories.clone(),
+            make_wallet_database_memory_connection(),
+            database_path,
+            Duration::from_secs(0),
+            shutdown.to_signal(),
+        )
+        .await;
+
+    // Bob only holds the private view key and the public spend key
+    let bob_view_key = SK::random(&mut OsRng);
+    let bob_spend_key = PublicKey::from_secret_key(&SK::random(&mut OsRng));
+    let (mut bob_ts, mut bob_oms, _bob_comms, _bob_connectivity, _bob_key_manager_handle, _bob_db) =
+        setup_transaction_service_with_wallet_type(
+            bob_node_identity,
+            vec![],
+            consensus_manager,
+            factories.clone(),
+            make_wallet_database_memory_connection(),
+            database_path2,
+            Duration::from_secs(0),
+            shutdown.to_signal(),
+            WalletType::ProvidedKeys(ProvidedKeysWallet::watch_only(
+                bob_spend_key.clone(),
+                bob_view_key.clone(),
+            )),
+        )
+        .await;
+    let bob_address = bob_oms.get_account_address(DEFAULT_ACCOUNT_ID).await.unwrap();
+    assert_eq!(
+        bob_address.public_view_key(),
+        Some(&PublicKey::from_secret_key(&bob_view_key))
+    );
+    assert_eq!(bob_address.public_spend_key(), &bob_spend_key);
+
+    let uo1 = make_input(
+        &mut OsRng,
+        25000.into(),
+        &OutputFeatures::default(),
+        &alice_key_manager_handle,
+    )
+    .await;
+    alice_oms.add_output(uo1.clone(), None).await.unwrap();
+    alice_db
+        .mark_outputs_as_unspent(vec![(uo1.hash(&alice_key_manager_handle).await.unwrap(), true)])
+        .unwrap();
+
+    let value = 10000.into();
+    let tx_id = alice_ts
+        .send_one_sided_to_stealth_address_transaction(
+            bob_address.clone(),
+            value,
+            UtxoSelectionCriteria::default(),
+            OutputFeatures::default(),
+            20.into(),
+            "".to_string(),
+            PaymentId::Empty,
+        )
+        .await
+        .expect("Alice sending one-sided tx to Bob");
+    let completed_tx = alice_ts.get_completed_transaction(tx_id).await.unwrap();
+    let outputs = completed_tx.transaction.body.outputs().clone();
+
+    // The watch-only wallet finds the payment with its view key
+    let recovered_outputs = bob_oms
+        .scan_outputs_for_one_sided_payments(outputs.into_iter().map(|o| (o, None)).collect())
+        .await
+        .unwrap();
+    assert_eq!(1, recovered_outputs.len());
+    assert_eq!(value, recovered_outputs[0].output.value);
+    let balance = bob_oms.get_balance().await.unwrap();
+    assert_eq!(balance.pending_incoming_balance + balance.available_balance, value);
+
+    // but refuses anything that needs the spend key
+    let err = bob_ts
+        .send_one_sided_to_stealth_address_transaction(
+            bob_address,
+            1000.into(),
+            UtxoSelectionCriteria::default(),
+            OutputFeatures::default(),
+            20.into(),
+            "".to_string(),
+            PaymentId::Empty,
+        )
+        .await
+        .unwrap_err();
+    assert!(matches!(err, TransactionServiceError::WatchOnlyWallet(_)));
+}
+
+#[tokio::test]
+async fn scheduled_payments_are_sent_when_funded_including_the_fee() {
+    let factories = CryptoFactories::default();
+    let temp_dir = tempdir().unwrap();
+    let db_path = format!("{}/{}.sqlite3", temp_dir.path().to_str().unwrap(), random::string(8));
+    let connection = run_migration_and_create_sqlite_connection(&db_path, 16).unwrap();
+    let mut ts_interface = setup_transaction_service_no_comms(
+        factories,
+        connection,
+        Some(TransactionServiceConfig {
+            scheduled_payment_check_interval: Duration::from_millis(100),
+            ..Default::default()
+        }),
+    )
+    .await;
+    ts_interface
+        .wallet_connectivity_service_mock
+        .set_connectivity_status(OnlineStatus::Online);
+
+    let balance = MicroMinotari::from(250000);
+    let uo = make_input(
+        &mut OsRng,
+        balance,
+        &OutputFeatures::default(),
+        &ts_interface.key_manager_handle,
+    )
+    .await;
+    ts_interface
+        .output_manager_service_handle
+        .add_output(uo.clone(), None)
+        .await
+        .unwrap();
+    ts_interface
+        .oms_db
+        .mark_outputs_as_unspent(vec![(uo.hash(&ts_interface.key_manager_handle).await.unwrap(), true)])
+        .unwrap();
+
+    let destination = TariAddress::new_dual_address_with_default_features(
+        PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng)),
+        PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng)),
+        Network::LocalNet,
+    );
+    let run_at = Utc::now().naive_utc() + ChronoDuration::seconds(1);
+    // The balance covers the amount but not the fee, so this payment stays due
+    let unfunded = ts_interface
+        .transaction_service_handle
+        .create_scheduled_payment(
+            destination.clone(),
+            balance,
+            MicroMinotari::from(5),
+            "unfunded".to_string(),
+            PaymentKind::OneSided,
+            PaymentSchedule::Once(run_at),
+            None,
+        )
+        .await
+        .unwrap();
+    let funded = ts_interface
+        .transaction_service_handle
+        .create_scheduled_payment(
+            destination,
+            MicroMinotari::from(10000),
+            MicroMinotari::from(5),
+            "funded".to_string(),
+            PaymentKind::OneSided,
+            PaymentSchedule::Once(run_at),
+            None,
+        )
+        .await
+        .unwrap();
+
+    let mut runs = Vec::new();
+    for _ in 0..50 {
+        runs = ts_interface
+            .transaction_service_handle
+            .get_scheduled_payment_runs(funded.id)
+            .await
+            .unwrap();
+        if !runs.is_empty() {
+            break;
+        }
+        sleep(Duration::from_millis(100)).await;
+    }
+    assert_eq!(runs.len(), 1);
+    assert!(runs[0].tx_id.is_some());
+    assert!(runs[0].error.is_none());
+
+    let payments = ts_interface
+        .transaction_service_handle
+        .get_scheduled_payments()
+        .await
+        .unwrap();
+    let funded = payments.iter().find(|p| p.id == funded.id).unwrap();
+    assert!(funded.next_run_at.is_none());
+    let unfunded = payments.iter().find(|p| p.id == unfunded.id).unwrap();
+    assert!(unfunded.next_run_at.is_some());
+    assert!(ts_interface
+        .transaction_service_handle
+        .get_scheduled_payment_runs(unfunded.id)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_offline_signing_create_sign_and_submit() {
+    let network = Network::LocalNet;
+    let consensus_manager = ConsensusManager::builder(network).build().unwrap();
+    let factories = CryptoFactories::default();
+    let alice_node_identity = Arc::new(NodeIdentity::random(
+        &mut OsRng,
+        get_next_memory_address(),
+        PeerFeatures::COMMUNICATION_NODE,
+    ));
+    let temp_dir = tempdir().unwrap();
+    let database_path = temp_dir.path().to_str().unwrap().to_string();
+    let shutdown = Shutdown::new();
+    let (mut alice_ts, mut alice_oms, _alice_comms, _alice_connectivity, alice_key_manager_handle, alice_db) =
+        setup_transaction_service(
+            alice_node_identity,
+            vec![],
+            consensus_manager,
+            factories,
+            make_wallet_database_memory_connection(),
+            database_path,
+            Duration::from_secs(0),
+            shutdown.to_signal(),
+        )
+        .await;
+
+    for _ in 0..2 {
+        let uo = make_input(
+            &mut OsRng,
+            MicroMinotari::from(25000),
+            &OutputFeatures::default(),
+            &alice_key_manager_handle,
+        )
+        .await;
+        alice_oms.add_output(uo.clone(), None).await.unwrap();
+        alice_db
+            .mark_outputs_as_unspent(vec![(uo.hash(&alice_key_manager_handle).await.unwrap(), true)])
+            .unwrap();
+    }
+
+    let bob_address = TariAddress::new_dual_address_with_default_features(
+        PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng)),
+        PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng)),
+        network,
+    );
+    let mut tx_ids = Vec::new();
+    for amount in [10000u64, 12000] {
+        let tx_id = alice_ts
+            .create_partially_signed_transaction(
+                bob_address.clone(),
+                MicroMinotari::from(amount),
+                UtxoSelectionCriteria::default(),
+                20.into(),
+                "offline".to_string(),
+                PaymentId::Empty,
+                PaymentKind::OneSided,
+            )
+            .await
+            .unwrap();
+        tx_ids.push(tx_id);
+    }
+    let (tx_id, other_tx_id) = (tx_ids[0], tx_ids[1]);
+
+    let unsigned = alice_ts.get_partially_signed_transaction(tx_id).await.unwrap();
+    assert!(!unsigned.is_signed());
+
+    // The signer refuses details that do not describe the transaction being signed
+    let mut tampered = unsigned.clone();
+    tampered.recipient = TariAddress::new_dual_address_with_default_features(
+        PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng)),
+        PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng)),
+        network,
+    );
+    assert!(matches!(
+        alice_ts.sign_partially_signed_transaction(tampered).await,
+        Err(TransactionServiceError::PartiallySignedTransactionError(
+            PartiallySignedTransactionError::DetailsMismatch(_)
+        ))
+    ));
+
+    let signed = alice_ts.sign_partially_signed_transaction(unsigned).await.unwrap();
+    assert!(signed.is_signed());
+
+    // A validly signed transaction is only accepted for the unsigned transaction it was created from
+    let other = alice_ts.get_partially_signed_transaction(other_tx_id).await.unwrap();
+    let mut swapped = alice_ts.sign_partially_signed_transaction(other).await.unwrap();
+    swapped.tx_id = tx_id;
+    swapped.fee = signed.fee;
+    assert!(alice_ts.submit_signed_transaction(swapped).await.is_err());
+    assert!(alice_ts.get_completed_transaction(tx_id).await.is_err());
+
+    alice_ts.submit_signed_transaction(signed.clone()).await.unwrap();
+    let completed = alice_ts.get_completed_transaction(tx_id).await.unwrap();
+    assert_eq!(completed.transaction, *signed.transaction().unwrap());
+    // The payment id embedded in the recipient output is kept
+    assert!(matches!(completed.payment_id, Some(PaymentId::Address(_))));
+}
+
 #[tokio::test]
 async fn test_htlc_send_and_claim() {
     let network = Network::LocalNet;
@@ -4283,6 +4989,7 @@ async fn test_restarting_transaction_protocols() {
         direct_send_success: false,
         send_count: 0,
         last_send_timestamp: None,
+        offline_signing: None,
     };
     bob_backend
         .write(WriteOperation::Insert(DbKeyValuePair::PendingOutboundTransaction(
@@ -4669,6 +5376,7 @@ async fn test_resend_on_startup() {
         direct_send_success: false,
         send_count: 1,
         last_send_timestamp: Some(Utc::now().naive_utc()),
+        offline_signing: None,
     };
     let connection = make_wallet_database_memory_connection();
 
@@ -5201,6 +5909,7 @@ async fn test_transaction_timeout_cancellation() {
         direct_send_success: false,
         send_count: 1,
         last_send_timestamp: Some(Utc::now().naive_utc()),
+        offline_signing: None,
     };
     let bob_connection = make_wallet_database_memory_connection();
 
@@ -6217,3 +6926,40 @@ async fn test_get_fee_per_gram_per_block_basic() {
     assert_eq!(estimates.stats, stats.into_iter().map(Into::into).collect::<Vec<_>>());
     assert_eq!(estimates.stats.len(), 1)
 }
+
+#[tokio::test]
+async fn test_estimate_fee_basic() {
+    let factories = CryptoFactories::default();
+    let connection = make_wallet_database_memory_connection();
+    let mut alice_ts_interface = setup_transaction_service_no_comms(factories, connection, None).await;
+    let estimates = vec![
+        base_node_proto::FeeEstimate {
+            target_blocks: 1,
+            fee_per_gram: 25,
+            mempool_fee_per_gram: 25,
+            history_fee_per_gram: 20,
+            num_samples: 40,
+        },
+        base_node_proto::FeeEstimate {
+            target_blocks: 3,
+            fee_per_gram: 5,
+            mempool_fee_per_gram: 5,
+            history_fee_per_gram: 0,
+            num_samples: 40,
+        },
+    ];
+    alice_ts_interface
+        .base_node_rpc_mock_state
+        .set_estimate_fee_response(base_node_proto::EstimateFeeResponse {
+            estimates: estimates.clone(),
+        });
+
+    let result = alice_ts_interface
+        .transaction_service_handle
+        .estimate_fee(vec![1, 3])
+        .await
+        .unwrap();
+    assert_eq!(result, estimates.into_iter().map(Into::into).collect::<Vec<_>>());
+    assert_eq!(result[0].history_fee_per_gram, Some(20.into()));
+    assert_eq!(result[1].history_fee_per_gram, None);
+}
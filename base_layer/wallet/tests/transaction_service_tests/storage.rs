@@ -24,20 +24,33 @@ use std::mem::size_of;
 
 use chacha20poly1305::{Key, KeyInit, XChaCha20Poly1305};
 use chrono::{NaiveDateTime, Utc};
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
 use minotari_wallet::{
     storage::sqlite_utilities::run_migration_and_create_sqlite_connection,
     test_utils::create_consensus_constants,
-    transaction_service::storage::{
-        database::{DbKeyValuePair, TransactionBackend, TransactionDatabase, WriteOperation},
-        models::{
-            CompletedTransaction,
-            InboundTransaction,
-            OutboundTransaction,
-            TxCancellationReason,
-            WalletTransaction,
+    transaction_service::{
+        error::TransactionStorageError,
+        payment_request::PaymentRequest,
+        storage::{
+            database::{DbKeyValuePair, TransactionBackend, TransactionDatabase, WriteOperation},
+            models::{
+                CompletedTransaction,
+                InboundTransaction,
+                IssuedPaymentRequest,
+                OutboundTransaction,
+                PaymentRequestStatus,
+                PaymentSchedule,
+                ScheduledPayment,
+                ScheduledPaymentRun,
+                TransactionHistoryFilter,
+                TransactionHistoryStatus,
+                TxCancellationReason,
+                WalletTransaction,
+            },
+            sqlite_db::TransactionServiceSqliteDatabase,
         },
-        sqlite_db::TransactionServiceSqliteDatabase,
     },
+    util::cron::CronSchedule,
 };
 use rand::{rngs::OsRng, RngCore};
 use tari_common::configuration::Network;
@@ -62,7 +75,7 @@ use tari_core::{
             TransactionOutputVersion,
             WalletOutput,
         },
-        transaction_protocol::sender::TransactionSenderMessage,
+        transaction_protocol::{partially_signed::PaymentKind, sender::TransactionSenderMessage},
         ReceiverTransactionProtocol,
         SenderTransactionProtocol,
     },
@@ -146,6 +159,7 @@ pub async fn test_db_backend<T: TransactionBackend + 'static>(backend: T) {
             direct_send_success: false,
             send_count: 0,
             last_send_timestamp: None,
+            offline_signing: None,
         });
         assert!(!db.transaction_exists(tx_id).unwrap(), "TxId should not exist");
 
@@ -684,3 +698,326 @@ async fn import_tx_and_read_it_from_db() {
     assert_eq!(db_tx.first().unwrap().tx_id, TxId::from(3u64));
     assert_eq!(db_tx.first().unwrap().mined_height, Some(7));
 }
+
+#[tokio::test]
+async fn scheduled_payments_are_stored_and_become_due() {
+    let db_name = format!("{}.sqlite3", random::string(8));
+    let db_tempdir = tempdir().unwrap();
+    let db_folder = db_tempdir.path().to_str().unwrap().to_string();
+    let db_path = format!("{}/{}", db_folder, db_name);
+    let connection = run_migration_and_create_sqlite_connection(db_path, 16).unwrap();
+
+    let mut key = [0u8; size_of::<Key>()];
+    OsRng.fill_bytes(&mut key);
+    let key_ga = Key::from_slice(&key);
+    let cipher = XChaCha20Poly1305::new(key_ga);
+    let db = TransactionDatabase::new(TransactionServiceSqliteDatabase::new(connection, cipher));
+
+    let now = NaiveDateTime::parse_from_str("2024-10-15 12:00", "%Y-%m-%d %H:%M").unwrap();
+    let one_off = ScheduledPayment {
+        id: 0,
+        destination: TariAddress::default(),
+        amount: MicroMinotari::from(1000),
+        fee_per_gram: MicroMinotari::from(5),
+        message: "rent".to_string(),
+        kind: PaymentKind::OneSided,
+        schedule: PaymentSchedule::Once(now),
+        next_run_at: Some(now),
+        created_at: now,
+        cancelled: false,
+        account: None,
+    };
+    let schedule = "0 0 1 * *".parse::<CronSchedule>().unwrap();
+    let recurring = ScheduledPayment {
+        kind: PaymentKind::Interactive,
+        schedule: PaymentSchedule::Recurring(schedule.clone()),
+        next_run_at: schedule.next_after(now),
+        account: Some(3),
+        ..one_off.clone()
+    };
+
+    let one_off = db.insert_scheduled_payment(one_off).unwrap();
+    let recurring = db.insert_scheduled_payment(recurring).unwrap();
+    assert_eq!(one_off.id, 1);
+    assert_eq!(recurring.id, 2);
+    assert_eq!(db.get_scheduled_payments().unwrap(), vec![
+        one_off.clone(),
+        recurring.clone()
+    ]);
+
+    // Only the one-off payment is due
+    assert_eq!(db.get_due_scheduled_payments(now).unwrap(), vec![one_off.clone()]);
+    let next_month = recurring.next_run_at.unwrap();
+    assert_eq!(db.get_due_scheduled_payments(next_month).unwrap().len(), 2);
+
+    db.set_scheduled_payment_next_run(one_off.id, None).unwrap();
+    db.insert_scheduled_payment_run(ScheduledPaymentRun {
+        scheduled_payment_id: one_off.id,
+        run_at: now,
+        tx_id: Some(TxId::from(42u64)),
+        error: None,
+    })
+    .unwrap();
+    assert_eq!(db.get_due_scheduled_payments(next_month).unwrap(), vec![
+        recurring.clone()
+    ]);
+    let runs = db.get_scheduled_payment_runs(one_off.id).unwrap();
+    assert_eq!(runs.len(), 1);
+    assert_eq!(runs[0].tx_id, Some(TxId::from(42u64)));
+
+    db.cancel_scheduled_payment(recurring.id).unwrap();
+    assert!(db.get_due_scheduled_payments(next_month).unwrap().is_empty());
+    assert!(db.get_scheduled_payments().unwrap()[1].cancelled);
+    assert!(db.cancel_scheduled_payment(3).is_err());
+}
+
+#[tokio::test]
+async fn transaction_history_is_filtered_by_time_and_status() {
+    let db_name = format!("{}.sqlite3", random::string(8));
+    let db_tempdir = tempdir().unwrap();
+    let db_folder = db_tempdir.path().to_str().unwrap().to_string();
+    let db_path = format!("{}/{}", db_folder, db_name);
+    let connection = run_migration_and_create_sqlite_connection(db_path, 16).unwrap();
+
+    let mut key = [0u8; size_of::<Key>()];
+    OsRng.fill_bytes(&mut key);
+    let key_ga = Key::from_slice(&key);
+    let cipher = XChaCha20Poly1305::new(key_ga);
+    let db = TransactionDatabase::new(TransactionServiceSqliteDatabase::new(connection, cipher));
+
+    let own_address = TariAddress::default();
+    let counterparty = TariAddress::new_dual_address_with_default_features(
+        PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng)),
+        PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng)),
+        Network::LocalNet,
+    );
+    let day = |d: u32| NaiveDateTime::parse_from_str(&format!("2024-10-{:02} 12:00", d), "%Y-%m-%d %H:%M").unwrap();
+    for (tx_id, direction, timestamp) in [
+        (3u64, TransactionDirection::Outbound, day(3)),
+        (1, TransactionDirection::Inbound, day(1)),
+        (2, TransactionDirection::Outbound, day(2)),
+    ] {
+        let (source, destination) = match direction {
+            TransactionDirection::Outbound => (own_address.clone(), counterparty.clone()),
+            _ => (counterparty.clone(), own_address.clone()),
+        };
+        let transaction = CompletedTransaction::new(
+            TxId::from(tx_id),
+            source,
+            destination,
+            MicroMinotari::from(100_000),
+            MicroMinotari::from(100),
+            Transaction::new(
+                Vec::new(),
+                Vec::new(),
+                Vec::new(),
+                PrivateKey::random(&mut OsRng),
+                PrivateKey::random(&mut OsRng),
+            ),
+            TransactionStatus::MinedConfirmed,
+            "message".to_string(),
+            timestamp,
+            direction,
+            Some(10 + tx_id),
+            Some(timestamp),
+            None,
+        )
+        .unwrap();
+        db.insert_completed_transaction(TxId::from(tx_id), transaction).unwrap();
+    }
+    db.reject_completed_transaction(TxId::from(3u64), TxCancellationReason::UserCancelled)
+        .unwrap();
+
+    let history = db
+        .get_transaction_history(&TransactionHistoryFilter::default())
+        .unwrap();
+    assert_eq!(history.iter().map(|r| r.tx_id.as_u64()).collect::<Vec<_>>(), vec![
+        1, 2, 3
+    ]);
+    assert!(history.iter().all(|r| r.counterparty_address == counterparty));
+    assert_eq!(history[0].fee, MicroMinotari::from(0));
+    assert_eq!(history[1].fee, MicroMinotari::from(100));
+    assert_eq!(history[1].mined_height, Some(12));
+    assert_eq!(history[2].history_status, TransactionHistoryStatus::Cancelled);
+    assert_eq!(
+        history[2].cancellation_reason,
+        Some(TxCancellationReason::UserCancelled)
+    );
+
+    let completed = db
+        .get_transaction_history(&TransactionHistoryFilter {
+            statuses: vec![TransactionHistoryStatus::Completed],
+            ..Default::default()
+        })
+        .unwrap();
+    assert_eq!(completed.iter().map(|r| r.tx_id.as_u64()).collect::<Vec<_>>(), vec![
+        1, 2
+    ]);
+
+    let in_range = db
+        .get_transaction_history(&TransactionHistoryFilter {
+            from: Some(day(2)),
+            to: Some(day(3)),
+            ..Default::default()
+        })
+        .unwrap();
+    assert_eq!(in_range.len(), 1);
+    assert_eq!(in_range[0].tx_id, TxId::from(2u64));
+
+    // The history can be read a page at a time
+    let first_page = db
+        .get_transaction_history(&TransactionHistoryFilter {
+            limit: Some(2),
+            ..Default::default()
+        })
+        .unwrap();
+    assert_eq!(first_page.iter().map(|r| r.tx_id.as_u64()).collect::<Vec<_>>(), vec![
+        1, 2
+    ]);
+    let next_page = TransactionHistoryFilter::default().page_after(first_page[1].position(), 2);
+    let second_page = db.get_transaction_history(&next_page).unwrap();
+    assert_eq!(second_page.iter().map(|r| r.tx_id.as_u64()).collect::<Vec<_>>(), vec![
+        3
+    ]);
+    let last_page = next_page.page_after(second_page[0].position(), 2);
+    assert!(db.get_transaction_history(&last_page).unwrap().is_empty());
+
+    assert!(db
+        .get_transaction_history(&TransactionHistoryFilter {
+            statuses: vec![TransactionHistoryStatus::Pending],
+            ..Default::default()
+        })
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn payment_requests_are_stored_and_updated() {
+    let db_name = format!("{}.sqlite3", random::string(8));
+    let db_tempdir = tempdir().unwrap();
+    let db_folder = db_tempdir.path().to_str().unwrap().to_string();
+    let db_path = format!("{}/{}", db_folder, db_name);
+    let connection = run_migration_and_create_sqlite_connection(db_path, 16).unwrap();
+
+    let mut key = [0u8; size_of::<Key>()];
+    OsRng.fill_bytes(&mut key);
+    let key_ga = Key::from_slice(&key);
+    let cipher = XChaCha20Poly1305::new(key_ga);
+    let db = TransactionDatabase::new(TransactionServiceSqliteDatabase::new(connection, cipher));
+
+    let spend_key = PrivateKey::random(&mut OsRng);
+    let address = TariAddress::new_dual_address_with_default_features(
+        PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng)),
+        PublicKey::from_secret_key(&spend_key),
+        Network::LocalNet,
+    );
+    let now = NaiveDateTime::parse_from_str("2024-11-05 12:00", "%Y-%m-%d %H:%M").unwrap();
+    let request = PaymentRequest::new_signed(
+        address.clone(),
+        MicroMinotari::from(5000),
+        PaymentId::U64(7),
+        "Invoice 7".to_string(),
+        Some(now + chrono::Duration::days(1)),
+        &spend_key,
+    )
+    .unwrap();
+    let issued = db
+        .insert_payment_request(IssuedPaymentRequest {
+            id: 0,
+            request,
+            status: PaymentRequestStatus::Open,
+            amount_received: MicroMinotari::zero(),
+            created_at: now,
+        })
+        .unwrap();
+    assert_eq!(issued.id, 1);
+    assert_eq!(db.get_payment_requests().unwrap(), vec![issued.clone()]);
+
+    // The payment id is unique, the transaction service relies on the unique violation to reject a reused payment id
+    assert!(matches!(
+        db.insert_payment_request(issued.clone()),
+        Err(TransactionStorageError::DieselError(DieselError::DatabaseError(
+            DatabaseErrorKind::UniqueViolation,
+            _
+        )))
+    ));
+
+    let amount_received = MicroMinotari::from(2000);
+    let status = issued.status_for(amount_received, now);
+    assert_eq!(status, PaymentRequestStatus::Underpaid);
+    db.update_payment_request_status(issued.id, status, amount_received)
+        .unwrap();
+    let stored = db.get_payment_requests().unwrap().remove(0);
+    assert_eq!(stored.status, PaymentRequestStatus::Underpaid);
+    assert_eq!(stored.amount_received, amount_received);
+
+    assert_eq!(
+        issued.status_for(MicroMinotari::zero(), now + chrono::Duration::days(2)),
+        PaymentRequestStatus::Expired
+    );
+    assert_eq!(
+        issued.status_for(MicroMinotari::from(6000), now + chrono::Duration::days(2)),
+        PaymentRequestStatus::Paid
+    );
+    assert!(db
+        .update_payment_request_status(2, PaymentRequestStatus::Paid, MicroMinotari::zero())
+        .is_err());
+
+    // Inbound transactions are matched to the request by their payment id, each of them once
+    let payer = TariAddress::new_dual_address_with_default_features(
+        PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng)),
+        PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng)),
+        Network::LocalNet,
+    );
+    let insert_payment = |tx_id: u64, direction: TransactionDirection, payment_id: PaymentId| {
+        let transaction = CompletedTransaction::new(
+            TxId::from(tx_id),
+            payer.clone(),
+            address.clone(),
+            MicroMinotari::from(1500),
+            MicroMinotari::from(0),
+            Transaction::new(
+                Vec::new(),
+                Vec::new(),
+                Vec::new(),
+                PrivateKey::random(&mut OsRng),
+                PrivateKey::random(&mut OsRng),
+            ),
+            TransactionStatus::OneSidedConfirmed,
+            "message".to_string(),
+            now,
+            direction,
+            Some(10),
+            Some(now),
+            Some(payment_id),
+        )
+        .unwrap();
+        db.insert_completed_transaction(TxId::from(tx_id), transaction).unwrap();
+    };
+    insert_payment(1, TransactionDirection::Inbound, PaymentId::U64(7));
+    insert_payment(2, TransactionDirection::Inbound, PaymentId::U64(8));
+    insert_payment(3, TransactionDirection::Outbound, PaymentId::U64(7));
+    assert_eq!(db.update_payment_request_payments().unwrap(), vec![issued.id]);
+    assert_eq!(
+        db.get_payment_requests().unwrap()[0].amount_received,
+        MicroMinotari::from(1500)
+    );
+    assert!(db.update_payment_request_payments().unwrap().is_empty());
+
+    insert_payment(4, TransactionDirection::Inbound, PaymentId::U64(7));
+    assert_eq!(db.update_payment_request_payments().unwrap(), vec![issued.id]);
+    assert_eq!(
+        db.get_payment_requests().unwrap()[0].amount_received,
+        MicroMinotari::from(3000)
+    );
+
+    // A cancelled payment no longer counts
+    db.reject_completed_transaction(TxId::from(1u64), TxCancellationReason::Orphan)
+        .unwrap();
+    assert_eq!(db.update_payment_request_payments().unwrap(), vec![issued.id]);
+    assert_eq!(
+        db.get_payment_requests().unwrap()[0].amount_received,
+        MicroMinotari::from(1500)
+    );
+    assert!(db.update_payment_request_payments().unwrap().is_empty());
+}
@@ -339,6 +339,8 @@ where TBackend: TransactionBackend + 'static
                                 OutputManagerEvent::TxoValidationCommunicationFailure(request_key) => {
                                     self.output_validation_complete_event(request_key,  3);
                                 },
+                                // Submitted by the transaction service, which reports the transaction events
+                                OutputManagerEvent::UtxoConsolidationCreated { .. } => {},
                             }
                         },
                         Err(_e) => error!(target: LOG_TARGET, "Error reading from Output Manager Service event broadcast channel"),
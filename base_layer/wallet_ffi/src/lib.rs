@@ -95,7 +95,12 @@ use minotari_wallet::{
     storage::{
         database::WalletDatabase,
         sqlite_db::wallet::WalletSqliteDatabase,
-        sqlite_utilities::{get_last_network, get_last_version, initialize_sqlite_database_backends},
+        sqlite_utilities::{
+            change_wallet_passphrase,
+            get_last_network,
+            get_last_version,
+            initialize_sqlite_database_backends,
+        },
     },
     transaction_service::{
         config::TransactionServiceConfig,
@@ -122,7 +127,7 @@ use tari_common_types::{
     tari_address::{TariAddress, TariAddressError},
     transaction::{TransactionDirection, TransactionStatus, TxId},
     types::{ComAndPubSignature, Commitment, PublicKey, RangeProof, SignatureWithDomain},
-    wallet_types::WalletType,
+    wallet_types::{ProvidedKeysWallet, WalletType},
 };
 use tari_comms::{
     multiaddr::Multiaddr,
@@ -5715,6 +5720,8 @@ unsafe fn init_logging(
 ///     Orphan,                 // 4
 ///     TimeLocked,             // 5
 ///     InvalidTransaction,     // 6
+///     Oversized,              // 7
+///     Replaced,               // 8
 /// }
 /// `callback_txo_validation_complete` - The callback function pointer matching the function signature. This is called
 /// when a TXO validation process is completed. The request_key is used to identify which request this
@@ -5756,9 +5763,180 @@ unsafe fn init_logging(
 /// # Safety
 /// The ```wallet_destroy``` method must be called when finished with a TariWallet to prevent a memory leak
 #[no_mangle]
+pub unsafe extern "C" fn wallet_create(
+    config: *mut TariCommsConfig,
+    log_path: *const c_char,
+    log_verbosity: c_int,
+    num_rolling_log_files: c_uint,
+    size_per_log_file_bytes: c_uint,
+    passphrase: *const c_char,
+    seed_passphrase: *const c_char,
+    seed_words: *const TariSeedWords,
+    network_str: *const c_char,
+    peer_seed_str: *const c_char,
+    dns_sec: bool,
+
+    callback_received_transaction: unsafe extern "C" fn(*mut TariPendingInboundTransaction),
+    callback_received_transaction_reply: unsafe extern "C" fn(*mut TariCompletedTransaction),
+    callback_received_finalized_transaction: unsafe extern "C" fn(*mut TariCompletedTransaction),
+    callback_transaction_broadcast: unsafe extern "C" fn(*mut TariCompletedTransaction),
+    callback_transaction_mined: unsafe extern "C" fn(*mut TariCompletedTransaction),
+    callback_transaction_mined_unconfirmed: unsafe extern "C" fn(*mut TariCompletedTransaction, u64),
+    callback_faux_transaction_confirmed: unsafe extern "C" fn(*mut TariCompletedTransaction),
+    callback_faux_transaction_unconfirmed: unsafe extern "C" fn(*mut TariCompletedTransaction, u64),
+    callback_transaction_send_result: unsafe extern "C" fn(c_ulonglong, *mut TariTransactionSendStatus),
+    callback_transaction_cancellation: unsafe extern "C" fn(*mut TariCompletedTransaction, u64),
+    callback_txo_validation_complete: unsafe extern "C" fn(u64, u64),
+    callback_contacts_liveness_data_updated: unsafe extern "C" fn(*mut TariContactsLivenessData),
+    callback_balance_updated: unsafe extern "C" fn(*mut TariBalance),
+    callback_transaction_validation_complete: unsafe extern "C" fn(u64, u64),
+    callback_saf_messages_received: unsafe extern "C" fn(),
+    callback_connectivity_status: unsafe extern "C" fn(u64),
+    callback_wallet_scanned_height: unsafe extern "C" fn(u64),
+    callback_base_node_state: unsafe extern "C" fn(*mut TariBaseNodeState),
+    recovery_in_progress: *mut bool,
+    error_out: *mut c_int,
+) -> *mut TariWallet {
+    create_wallet(
+        config,
+        log_path,
+        log_verbosity,
+        num_rolling_log_files,
+        size_per_log_file_bytes,
+        passphrase,
+        seed_passphrase,
+        seed_words,
+        ptr::null(),
+        ptr::null(),
+        network_str,
+        peer_seed_str,
+        dns_sec,
+        callback_received_transaction,
+        callback_received_transaction_reply,
+        callback_received_finalized_transaction,
+        callback_transaction_broadcast,
+        callback_transaction_mined,
+        callback_transaction_mined_unconfirmed,
+        callback_faux_transaction_confirmed,
+        callback_faux_transaction_unconfirmed,
+        callback_transaction_send_result,
+        callback_transaction_cancellation,
+        callback_txo_validation_complete,
+        callback_contacts_liveness_data_updated,
+        callback_balance_updated,
+        callback_transaction_validation_complete,
+        callback_saf_messages_received,
+        callback_connectivity_status,
+        callback_wallet_scanned_height,
+        callback_base_node_state,
+        recovery_in_progress,
+        error_out,
+    )
+}
+
+/// Creates a watch-only TariWallet, which tracks the funds of a wallet using only its view key and public spend key
+/// and cannot spend them. Requests that need the spend key, such as sending a transaction, fail with error code 213.
+///
+/// ## Arguments
+/// `view_private_key` - The TariPrivateKey pointer of the private view key of the watched wallet, may not be null.
+/// `public_spend_key` - The TariPublicKey pointer of the public spend key of the watched wallet, may not be null.
+/// All other arguments are as for [wallet_create].
+///
+/// ## Returns
+/// `*mut TariWallet` - Returns a pointer to a TariWallet, note that it returns ptr::null_mut()
+/// if config or either key is null, a wallet error was encountered or if the runtime could not be created
+///
+/// # Safety
+/// The ```wallet_destroy``` method must be called when finished with a TariWallet to prevent a memory leak
+#[no_mangle]
+pub unsafe extern "C" fn wallet_create_watch_only(
+    config: *mut TariCommsConfig,
+    log_path: *const c_char,
+    log_verbosity: c_int,
+    num_rolling_log_files: c_uint,
+    size_per_log_file_bytes: c_uint,
+    passphrase: *const c_char,
+    view_private_key: *const TariPrivateKey,
+    public_spend_key: *const TariPublicKey,
+    network_str: *const c_char,
+    peer_seed_str: *const c_char,
+    dns_sec: bool,
+
+    callback_received_transaction: unsafe extern "C" fn(*mut TariPendingInboundTransaction),
+    callback_received_transaction_reply: unsafe extern "C" fn(*mut TariCompletedTransaction),
+    callback_received_finalized_transaction: unsafe extern "C" fn(*mut TariCompletedTransaction),
+    callback_transaction_broadcast: unsafe extern "C" fn(*mut TariCompletedTransaction),
+    callback_transaction_mined: unsafe extern "C" fn(*mut TariCompletedTransaction),
+    callback_transaction_mined_unconfirmed: unsafe extern "C" fn(*mut TariCompletedTransaction, u64),
+    callback_faux_transaction_confirmed: unsafe extern "C" fn(*mut TariCompletedTransaction),
+    callback_faux_transaction_unconfirmed: unsafe extern "C" fn(*mut TariCompletedTransaction, u64),
+    callback_transaction_send_result: unsafe extern "C" fn(c_ulonglong, *mut TariTransactionSendStatus),
+    callback_transaction_cancellation: unsafe extern "C" fn(*mut TariCompletedTransaction, u64),
+    callback_txo_validation_complete: unsafe extern "C" fn(u64, u64),
+    callback_contacts_liveness_data_updated: unsafe extern "C" fn(*mut TariContactsLivenessData),
+    callback_balance_updated: unsafe extern "C" fn(*mut TariBalance),
+    callback_transaction_validation_complete: unsafe extern "C" fn(u64, u64),
+    callback_saf_messages_received: unsafe extern "C" fn(),
+    callback_connectivity_status: unsafe extern "C" fn(u64),
+    callback_wallet_scanned_height: unsafe extern "C" fn(u64),
+    callback_base_node_state: unsafe extern "C" fn(*mut TariBaseNodeState),
+    recovery_in_progress: *mut bool,
+    error_out: *mut c_int,
+) -> *mut TariWallet {
+    let mut error = 0;
+    ptr::swap(error_out, &mut error as *mut c_int);
+    if view_private_key.is_null() {
+        error = LibWalletError::from(InterfaceError::NullError("view_private_key".to_string())).code;
+        ptr::swap(error_out, &mut error as *mut c_int);
+        return ptr::null_mut();
+    }
+    if public_spend_key.is_null() {
+        error = LibWalletError::from(InterfaceError::NullError("public_spend_key".to_string())).code;
+        ptr::swap(error_out, &mut error as *mut c_int);
+        return ptr::null_mut();
+    }
+    create_wallet(
+        config,
+        log_path,
+        log_verbosity,
+        num_rolling_log_files,
+        size_per_log_file_bytes,
+        passphrase,
+        ptr::null(),
+        ptr::null(),
+        view_private_key,
+        public_spend_key,
+        network_str,
+        peer_seed_str,
+        dns_sec,
+        callback_received_transaction,
+        callback_received_transaction_reply,
+        callback_received_finalized_transaction,
+        callback_transaction_broadcast,
+        callback_transaction_mined,
+        callback_transaction_mined_unconfirmed,
+        callback_faux_transaction_confirmed,
+        callback_faux_transaction_unconfirmed,
+        callback_transaction_send_result,
+        callback_transaction_cancellation,
+        callback_txo_validation_complete,
+        callback_contacts_liveness_data_updated,
+        callback_balance_updated,
+        callback_transaction_validation_complete,
+        callback_saf_messages_received,
+        callback_connectivity_status,
+        callback_wallet_scanned_height,
+        callback_base_node_state,
+        recovery_in_progress,
+        error_out,
+    )
+}
+
+/// Creates the wallet for [wallet_create] and [wallet_create_watch_only]. A watch-only wallet is created if
+/// `view_private_key` and `public_spend_key` are provided.
 #[allow(clippy::cognitive_complexity)]
 #[allow(clippy::too_many_lines)]
-pub unsafe extern "C" fn wallet_create(
+unsafe fn create_wallet(
     config: *mut TariCommsConfig,
     log_path: *const c_char,
     log_verbosity: c_int,
@@ -5767,6 +5945,8 @@ pub unsafe extern "C" fn wallet_create(
     passphrase: *const c_char,
     seed_passphrase: *const c_char,
     seed_words: *const TariSeedWords,
+    view_private_key: *const TariPrivateKey,
+    public_spend_key: *const TariPublicKey,
     network_str: *const c_char,
     peer_seed_str: *const c_char,
     dns_sec: bool,
@@ -5869,6 +6049,34 @@ pub unsafe extern "C" fn wallet_create(
         }
     };
 
+    let wallet_type = match (view_private_key.is_null(), public_spend_key.is_null()) {
+        (true, true) => WalletType::default(),
+        (false, false) => {
+            if recovery_seed.is_some() {
+                error = LibWalletError::from(InterfaceError::InvalidArgument(
+                    "seed words cannot be provided for a watch-only wallet".to_string(),
+                ))
+                .code;
+                ptr::swap(error_out, &mut error as *mut c_int);
+                return ptr::null_mut();
+            }
+            WalletType::ProvidedKeys(ProvidedKeysWallet::watch_only(
+                (*public_spend_key).clone(),
+                (*view_private_key).clone(),
+            ))
+        },
+        (true, false) => {
+            error = LibWalletError::from(InterfaceError::NullError("view_private_key".to_string())).code;
+            ptr::swap(error_out, &mut error as *mut c_int);
+            return ptr::null_mut();
+        },
+        (false, true) => {
+            error = LibWalletError::from(InterfaceError::NullError("public_spend_key".to_string())).code;
+            ptr::swap(error_out, &mut error as *mut c_int);
+            return ptr::null_mut();
+        },
+    };
+
     let network = if network_str.is_null() {
         error = LibWalletError::from(InterfaceError::NullError("network".to_string())).code;
         ptr::swap(error_out, &mut error as *mut c_int);
@@ -6044,7 +6252,7 @@ pub unsafe extern "C" fn wallet_create(
         key_manager_backend,
         shutdown.to_signal(),
         master_seed,
-        Some(WalletType::default()),
+        Some(wallet_type),
         user_agent,
     ));
 
@@ -6190,6 +6398,84 @@ pub unsafe extern "C" fn wallet_get_last_network(config: *mut TariCommsConfig, e
     }
 }
 
+/// Changes the passphrase of the wallet database and re-encrypts all of its encrypted data under a new key. The change
+/// is atomic: if anything fails, the database is left unchanged and the existing passphrase remains valid.
+///
+/// ## Arguments
+/// `config` - The TariCommsConfig pointer
+/// `existing_passphrase` - The current passphrase of the wallet database, may not be null
+/// `new_passphrase` - The new passphrase for the wallet database, may not be null
+/// `error_out` - Pointer to an int which will be modified to an error code should one occur, may not be null. Functions
+/// as an out parameter.
+/// ## Returns
+/// `bool` - Returns true if the passphrase was changed, false otherwise
+///
+/// # Safety
+/// This function must not be called while a wallet is running on the database, it will fail to acquire the database
+/// lock.
+#[no_mangle]
+pub unsafe extern "C" fn wallet_change_passphrase(
+    config: *mut TariCommsConfig,
+    existing_passphrase: *const c_char,
+    new_passphrase: *const c_char,
+    error_out: *mut c_int,
+) -> bool {
+    let mut error = 0;
+    ptr::swap(error_out, &mut error as *mut c_int);
+    if config.is_null() {
+        error = LibWalletError::from(InterfaceError::NullError("config".to_string())).code;
+        ptr::swap(error_out, &mut error as *mut c_int);
+        return false;
+    }
+    if existing_passphrase.is_null() {
+        error = LibWalletError::from(InterfaceError::NullError("existing_passphrase".to_string())).code;
+        ptr::swap(error_out, &mut error as *mut c_int);
+        return false;
+    }
+    let existing_passphrase = match CStr::from_ptr(existing_passphrase).to_str() {
+        Ok(v) => SafePassword::from(v.to_owned()),
+        _ => {
+            error = LibWalletError::from(InterfaceError::PointerError("existing_passphrase".to_string())).code;
+            ptr::swap(error_out, &mut error as *mut c_int);
+            return false;
+        },
+    };
+    if new_passphrase.is_null() {
+        error = LibWalletError::from(InterfaceError::NullError("new_passphrase".to_string())).code;
+        ptr::swap(error_out, &mut error as *mut c_int);
+        return false;
+    }
+    let new_passphrase = match CStr::from_ptr(new_passphrase).to_str() {
+        Ok(v) => SafePassword::from(v.to_owned()),
+        _ => {
+            error = LibWalletError::from(InterfaceError::PointerError("new_passphrase".to_string())).code;
+            ptr::swap(error_out, &mut error as *mut c_int);
+            return false;
+        },
+    };
+
+    let sql_database_path = (*config)
+        .datastore_path
+        .join((*config).peer_database_name.clone())
+        .with_extension("sqlite3");
+    if !sql_database_path.exists() {
+        error = LibWalletError::from(InterfaceError::InvalidArgument(
+            "config: no wallet database found".to_string(),
+        ))
+        .code;
+        ptr::swap(error_out, &mut error as *mut c_int);
+        return false;
+    }
+    match change_wallet_passphrase(sql_database_path, existing_passphrase, &new_passphrase) {
+        Ok(_) => true,
+        Err(e) => {
+            error = LibWalletError::from(WalletError::WalletStorageError(e)).code;
+            ptr::swap(error_out, &mut error as *mut c_int);
+            false
+        },
+    }
+}
+
 /// Retrieves the balance from a wallet
 ///
 /// ## Arguments
@@ -8394,6 +8680,32 @@ pub unsafe extern "C" fn wallet_restart_transaction_broadcast(wallet: *mut TariW
     }
 }
 
+/// Checks if the provided `TariWallet` is a watch-only wallet, which tracks funds but cannot spend them
+///
+/// ## Arguments
+/// `wallet` - The TariWallet pointer
+/// `error_out` - Pointer to an int which will be modified to an error code should one occur, may not be null. Functions
+/// as an out parameter.
+///
+/// ## Returns
+/// `bool` - Returns true if the wallet is watch-only. An error will also result in a false result.
+///
+/// # Safety
+/// None
+#[no_mangle]
+pub unsafe extern "C" fn wallet_is_watch_only(wallet: *mut TariWallet, error_out: *mut c_int) -> bool {
+    let mut error = 0;
+    ptr::swap(error_out, &mut error as *mut c_int);
+
+    if wallet.is_null() {
+        error = LibWalletError::from(InterfaceError::NullError("wallet".to_string())).code;
+        ptr::swap(error_out, &mut error as *mut c_int);
+        return false;
+    }
+
+    (*wallet).wallet.is_watch_only()
+}
+
 /// Gets the seed words representing the seed private key of the provided `TariWallet`.
 ///
 /// ## Arguments
@@ -10430,6 +10742,185 @@ mod test {
         }
     }
 
+    #[test]
+    #[allow(clippy::too_many_lines)]
+    fn test_wallet_change_passphrase() {
+        unsafe {
+            let mut error = 0;
+            let error_ptr = &mut error as *mut c_int;
+            let mut recovery_in_progress = true;
+            let recovery_in_progress_ptr = &mut recovery_in_progress as *mut bool;
+
+            let db_name = random::string(8);
+            let db_name_alice = CString::new(db_name.as_str()).unwrap();
+            let db_name_alice_str: *const c_char = CString::into_raw(db_name_alice) as *const c_char;
+            let alice_temp_dir = tempdir().unwrap();
+            let db_path_alice = CString::new(alice_temp_dir.path().to_str().unwrap()).unwrap();
+            let db_path_alice_str: *const c_char = CString::into_raw(db_path_alice) as *const c_char;
+            let transport_config_alice = transport_memory_create();
+            let address_alice = transport_memory_get_address(transport_config_alice, error_ptr);
+            let address_alice_str = CStr::from_ptr(address_alice).to_str().unwrap().to_owned();
+            let address_alice_str: *const c_char = CString::new(address_alice_str).unwrap().into_raw() as *const c_char;
+            let network = CString::new(NETWORK_STRING).unwrap();
+            let network_str: *const c_char = CString::into_raw(network) as *const c_char;
+
+            let sql_database_path = Path::new(alice_temp_dir.path().to_str().unwrap())
+                .join(db_name)
+                .with_extension("sqlite3");
+
+            let alice_config = comms_config_create(
+                address_alice_str,
+                transport_config_alice,
+                db_name_alice_str,
+                db_path_alice_str,
+                20,
+                10800,
+                false,
+                error_ptr,
+            );
+
+            let old_passphrase: *const c_char =
+                CString::into_raw(CString::new("old passphrase").unwrap()) as *const c_char;
+            let new_passphrase: *const c_char =
+                CString::into_raw(CString::new("new passphrase").unwrap()) as *const c_char;
+            let wrong_passphrase: *const c_char =
+                CString::into_raw(CString::new("wrong passphrase").unwrap()) as *const c_char;
+            let dns_string: *const c_char = CString::into_raw(CString::new("").unwrap()) as *const c_char;
+            let create_wallet = |passphrase: *const c_char| {
+                wallet_create(
+                    alice_config,
+                    ptr::null(),
+                    0,
+                    0,
+                    0,
+                    passphrase,
+                    ptr::null(),
+                    ptr::null(),
+                    network_str,
+                    dns_string,
+                    false,
+                    received_tx_callback,
+                    received_tx_reply_callback,
+                    received_tx_finalized_callback,
+                    broadcast_callback,
+                    mined_callback,
+                    mined_unconfirmed_callback,
+                    scanned_callback,
+                    scanned_unconfirmed_callback,
+                    transaction_send_result_callback,
+                    tx_cancellation_callback,
+                    txo_validation_complete_callback,
+                    contacts_liveness_data_updated_callback,
+                    balance_updated_callback,
+                    transaction_validation_complete_callback,
+                    saf_messages_received_callback,
+                    connectivity_status_callback,
+                    wallet_scanned_height_callback,
+                    base_node_state_callback,
+                    recovery_in_progress_ptr,
+                    error_ptr,
+                )
+            };
+
+            let alice_wallet = create_wallet(old_passphrase);
+            assert_eq!(*error_ptr, 0, "No error expected");
+            let key: *const c_char = CString::into_raw(CString::new("key1").unwrap()) as *const c_char;
+            let value: *const c_char = CString::into_raw(CString::new("value1").unwrap()) as *const c_char;
+            assert!(wallet_set_key_value(alice_wallet, key, value, error_ptr));
+            wallet_destroy(alice_wallet);
+
+            let connection =
+                run_migration_and_create_sqlite_connection(&sql_database_path, 16).expect("Could not open Sqlite db");
+            let wallet_backend = WalletDatabase::new(
+                WalletSqliteDatabase::new(connection, "old passphrase".to_string().into()).unwrap(),
+            );
+            let stored_seed = wallet_backend.get_master_seed().unwrap().unwrap();
+            drop(wallet_backend);
+
+            // Missing and wrong passphrases are rejected and leave the database as it was
+            assert!(!wallet_change_passphrase(
+                alice_config,
+                ptr::null(),
+                new_passphrase,
+                error_ptr
+            ));
+            assert_eq!(
+                *error_ptr,
+                LibWalletError::from(InterfaceError::NullError("existing_passphrase".to_string())).code
+            );
+            assert!(!wallet_change_passphrase(
+                alice_config,
+                old_passphrase,
+                ptr::null(),
+                error_ptr
+            ));
+            assert_eq!(
+                *error_ptr,
+                LibWalletError::from(InterfaceError::NullError("new_passphrase".to_string())).code
+            );
+            assert!(!wallet_change_passphrase(
+                alice_config,
+                wrong_passphrase,
+                new_passphrase,
+                error_ptr
+            ));
+            assert_ne!(*error_ptr, 0, "An error is expected");
+
+            // The passphrase cannot be changed while a wallet is running on the database
+            let alice_wallet = create_wallet(old_passphrase);
+            assert_eq!(*error_ptr, 0, "No error expected");
+            assert!(!wallet_change_passphrase(
+                alice_config,
+                old_passphrase,
+                new_passphrase,
+                error_ptr
+            ));
+            assert_ne!(*error_ptr, 0, "An error is expected");
+            wallet_destroy(alice_wallet);
+
+            assert!(wallet_change_passphrase(
+                alice_config,
+                old_passphrase,
+                new_passphrase,
+                error_ptr
+            ));
+            assert_eq!(*error_ptr, 0, "No error expected");
+
+            // Only the new passphrase opens the database, and the data encrypted under the old key is still there
+            let connection =
+                run_migration_and_create_sqlite_connection(&sql_database_path, 16).expect("Could not open Sqlite db");
+            assert!(WalletSqliteDatabase::new(connection.clone(), "old passphrase".to_string().into()).is_err());
+            let wallet_backend = WalletDatabase::new(
+                WalletSqliteDatabase::new(connection, "new passphrase".to_string().into()).unwrap(),
+            );
+            assert_eq!(wallet_backend.get_master_seed().unwrap().unwrap(), stored_seed);
+            drop(wallet_backend);
+
+            let alice_wallet = create_wallet(old_passphrase);
+            assert!(alice_wallet.is_null());
+            assert_ne!(*error_ptr, 0, "An error is expected");
+
+            let alice_wallet = create_wallet(new_passphrase);
+            assert_eq!(*error_ptr, 0, "No error expected");
+            let found_value = wallet_get_value(alice_wallet, key, error_ptr);
+            assert_eq!(CString::from_raw(found_value).to_str().unwrap(), "value1");
+            wallet_destroy(alice_wallet);
+
+            string_destroy(network_str as *mut c_char);
+            string_destroy(db_name_alice_str as *mut c_char);
+            string_destroy(db_path_alice_str as *mut c_char);
+            string_destroy(address_alice_str as *mut c_char);
+            string_destroy(old_passphrase as *mut c_char);
+            string_destroy(new_passphrase as *mut c_char);
+            string_destroy(wrong_passphrase as *mut c_char);
+            string_destroy(dns_string as *mut c_char);
+            string_destroy(key as *mut c_char);
+            string_destroy(value as *mut c_char);
+            transport_config_destroy(transport_config_alice);
+            comms_config_destroy(alice_config);
+        }
+    }
+
     #[test]
     #[allow(clippy::too_many_lines)]
     fn test_wallet_client_key_value_store() {
@@ -11679,6 +12170,162 @@ mod test {
         }
     }
 
+    #[test]
+    #[allow(clippy::too_many_lines)]
+    fn test_wallet_create_watch_only() {
+        unsafe {
+            let mut error = 0;
+            let error_ptr = &mut error as *mut c_int;
+            let mut recovery_in_progress = true;
+            let recovery_in_progress_ptr = &mut recovery_in_progress as *mut bool;
+
+            let db_name = CString::new(random::string(8).as_str()).unwrap();
+            let db_name_str: *const c_char = CString::into_raw(db_name) as *const c_char;
+            let temp_dir = tempdir().unwrap();
+            let db_path = CString::new(temp_dir.path().to_str().unwrap()).unwrap();
+            let db_path_str: *const c_char = CString::into_raw(db_path) as *const c_char;
+            let transport_config = transport_memory_create();
+            let address = transport_memory_get_address(transport_config, error_ptr);
+            let address_str = CStr::from_ptr(address).to_str().unwrap().to_owned();
+            let address_str: *const c_char = CString::new(address_str).unwrap().into_raw() as *const c_char;
+            let network = CString::new(NETWORK_STRING).unwrap();
+            let network_str: *const c_char = CString::into_raw(network) as *const c_char;
+
+            let config = comms_config_create(
+                address_str,
+                transport_config,
+                db_name_str,
+                db_path_str,
+                20,
+                10800,
+                false,
+                error_ptr,
+            );
+
+            let passphrase: *const c_char = CString::into_raw(CString::new("niao").unwrap()) as *const c_char;
+            let dns_string: *const c_char = CString::into_raw(CString::new("").unwrap()) as *const c_char;
+            let view_key = private_key_generate();
+            let spend_key = public_key_from_private_key(private_key_generate(), error_ptr);
+
+            // Both keys are required
+            let wallet = wallet_create_watch_only(
+                config,
+                ptr::null(),
+                0,
+                0,
+                0,
+                passphrase,
+                view_key,
+                ptr::null(),
+                network_str,
+                dns_string,
+                false,
+                received_tx_callback,
+                received_tx_reply_callback,
+                received_tx_finalized_callback,
+                broadcast_callback,
+                mined_callback,
+                mined_unconfirmed_callback,
+                scanned_callback,
+                scanned_unconfirmed_callback,
+                transaction_send_result_callback,
+                tx_cancellation_callback,
+                txo_validation_complete_callback,
+                contacts_liveness_data_updated_callback,
+                balance_updated_callback,
+                transaction_validation_complete_callback,
+                saf_messages_received_callback,
+                connectivity_status_callback,
+                wallet_scanned_height_callback,
+                base_node_state_callback,
+                recovery_in_progress_ptr,
+                error_ptr,
+            );
+            assert!(wallet.is_null());
+            assert_eq!(
+                error,
+                LibWalletError::from(InterfaceError::NullError("public_spend_key".to_string())).code
+            );
+
+            let wallet = wallet_create_watch_only(
+                config,
+                ptr::null(),
+                0,
+                0,
+                0,
+                passphrase,
+                view_key,
+                spend_key,
+                network_str,
+                dns_string,
+                false,
+                received_tx_callback,
+                received_tx_reply_callback,
+                received_tx_finalized_callback,
+                broadcast_callback,
+                mined_callback,
+                mined_unconfirmed_callback,
+                scanned_callback,
+                scanned_unconfirmed_callback,
+                transaction_send_result_callback,
+                tx_cancellation_callback,
+                txo_validation_complete_callback,
+                contacts_liveness_data_updated_callback,
+                balance_updated_callback,
+                transaction_validation_complete_callback,
+                saf_messages_received_callback,
+                connectivity_status_callback,
+                wallet_scanned_height_callback,
+                base_node_state_callback,
+                recovery_in_progress_ptr,
+                error_ptr,
+            );
+            assert_eq!(error, 0);
+            assert!(wallet_is_watch_only(wallet, error_ptr));
+            assert_eq!(error, 0);
+
+            // The address of a watch-only wallet is built from the provided keys
+            let wallet_address = (*wallet)
+                .runtime
+                .block_on((*wallet).wallet.get_wallet_one_sided_address())
+                .unwrap();
+            assert_eq!(wallet_address.public_spend_key(), &*spend_key);
+            assert_eq!(
+                wallet_address.public_view_key(),
+                Some(&PublicKey::from_secret_key(&*view_key))
+            );
+
+            // Requests that need the spend key are refused
+            let destination = Box::into_raw(Box::new(TariWalletAddress::new_single_address_with_interactive_only(
+                PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng)),
+                Network::default(),
+            )));
+            let tx_id = wallet_send_transaction(
+                wallet,
+                destination,
+                1000,
+                ptr::null_mut(),
+                5,
+                ptr::null(),
+                true,
+                ptr::null(),
+                error_ptr,
+            );
+            assert_eq!(tx_id, 0);
+            assert_eq!(error, 213);
+
+            tari_address_destroy(destination);
+            string_destroy(db_name_str as *mut c_char);
+            string_destroy(db_path_str as *mut c_char);
+            string_destroy(address_str as *mut c_char);
+            private_key_destroy(view_key);
+            public_key_destroy(spend_key);
+            transport_config_destroy(transport_config);
+            comms_config_destroy(config);
+            wallet_destroy(wallet);
+        }
+    }
+
     #[test]
     fn test_tari_vector() {
         let mut error = 0;
@@ -2906,6 +2906,8 @@ TariPublicKey *public_keys_get_at(const struct TariPublicKeys *public_keys,
  *     Orphan,                 // 4
  *     TimeLocked,             // 5
  *     InvalidTransaction,     // 6
+ *     Oversized,              // 7
+ *     Replaced,               // 8
  * }
  * `callback_txo_validation_complete` - The callback function pointer matching the function signature. This is called
  * when a TXO validation process is completed. The request_key is used to identify which request this
@@ -2983,6 +2985,58 @@ struct TariWallet *wallet_create(TariCommsConfig *config,
                                  bool *recovery_in_progress,
                                  int *error_out);
 
+/**
+ * Creates a watch-only TariWallet, which tracks the funds of a wallet using only its view key and public spend key
+ * and cannot spend them. Requests that need the spend key, such as sending a transaction, fail with error code 213.
+ *
+ * ## Arguments
+ * `view_private_key` - The TariPrivateKey pointer of the private view key of the watched wallet, may not be null.
+ * `public_spend_key` - The TariPublicKey pointer of the public spend key of the watched wallet, may not be null.
+ * All other arguments are as for [wallet_create].
+ *
+ * ## Returns
+ * `*mut TariWallet` - Returns a pointer to a TariWallet, note that it returns ptr::null_mut()
+ * if config or either key is null, a wallet error was encountered or if the runtime could not be created
+ *
+ * # Safety
+ * The ```wallet_destroy``` method must be called when finished with a TariWallet to prevent a memory leak
+ */
+struct TariWallet *wallet_create_watch_only(TariCommsConfig *config,
+                                            const char *log_path,
+                                            int log_verbosity,
+                                            unsigned int num_rolling_log_files,
+                                            unsigned int size_per_log_file_bytes,
+                                            const char *passphrase,
+                                            const TariPrivateKey *view_private_key,
+                                            const TariPublicKey *public_spend_key,
+                                            const char *network_str,
+                                            const char *peer_seed_str,
+                                            bool dns_sec,
+                                            void (*callback_received_transaction)(TariPendingInboundTransaction*),
+                                            void (*callback_received_transaction_reply)(TariCompletedTransaction*),
+                                            void (*callback_received_finalized_transaction)(TariCompletedTransaction*),
+                                            void (*callback_transaction_broadcast)(TariCompletedTransaction*),
+                                            void (*callback_transaction_mined)(TariCompletedTransaction*),
+                                            void (*callback_transaction_mined_unconfirmed)(TariCompletedTransaction*,
+                                                                                           uint64_t),
+                                            void (*callback_faux_transaction_confirmed)(TariCompletedTransaction*),
+                                            void (*callback_faux_transaction_unconfirmed)(TariCompletedTransaction*,
+                                                                                          uint64_t),
+                                            void (*callback_transaction_send_result)(unsigned long long,
+                                                                                     TariTransactionSendStatus*),
+                                            void (*callback_transaction_cancellation)(TariCompletedTransaction*,
+                                                                                      uint64_t),
+                                            void (*callback_txo_validation_complete)(uint64_t, uint64_t),
+                                            void (*callback_contacts_liveness_data_updated)(TariContactsLivenessData*),
+                                            void (*callback_balance_updated)(TariBalance*),
+                                            void (*callback_transaction_validation_complete)(uint64_t, uint64_t),
+                                            void (*callback_saf_messages_received)(void),
+                                            void (*callback_connectivity_status)(uint64_t),
+                                            void (*callback_wallet_scanned_height)(uint64_t),
+                                            void (*callback_base_node_state)(struct TariBaseNodeState*),
+                                            bool *recovery_in_progress,
+                                            int *error_out);
+
 /**
  * Retrieves the version of an app that last accessed the wallet database
  *
@@ -3015,6 +3069,28 @@ char *wallet_get_last_version(TariCommsConfig *config,
 char *wallet_get_last_network(TariCommsConfig *config,
                               int *error_out);
 
+/**
+ * Changes the passphrase of the wallet database and re-encrypts all of its encrypted data under a new key. The change
+ * is atomic: if anything fails, the database is left unchanged and the existing passphrase remains valid.
+ *
+ * ## Arguments
+ * `config` - The TariCommsConfig pointer
+ * `existing_passphrase` - The current passphrase of the wallet database, may not be null
+ * `new_passphrase` - The new passphrase for the wallet database, may not be null
+ * `error_out` - Pointer to an int which will be modified to an error code should one occur, may not be null. Functions
+ * as an out parameter.
+ * ## Returns
+ * `bool` - Returns true if the passphrase was changed, false otherwise
+ *
+ * # Safety
+ * This function must not be called while a wallet is running on the database, it will fail to acquire the database
+ * lock.
+ */
+bool wallet_change_passphrase(TariCommsConfig *config,
+                              const char *existing_passphrase,
+                              const char *new_passphrase,
+                              int *error_out);
+
 /**
  * Retrieves the balance from a wallet
  *
@@ -3780,6 +3856,23 @@ unsigned long long wallet_start_transaction_validation(struct TariWallet *wallet
 bool wallet_restart_transaction_broadcast(struct TariWallet *wallet,
                                           int *error_out);
 
+/**
+ * Checks if the provided `TariWallet` is a watch-only wallet, which tracks funds but cannot spend them
+ *
+ * ## Arguments
+ * `wallet` - The TariWallet pointer
+ * `error_out` - Pointer to an int which will be modified to an error code should one occur, may not be null. Functions
+ * as an out parameter.
+ *
+ * ## Returns
+ * `bool` - Returns true if the wallet is watch-only. An error will also result in a false result.
+ *
+ * # Safety
+ * None
+ */
+bool wallet_is_watch_only(struct TariWallet *wallet,
+                          int *error_out);
+
 /**
  * Gets the seed words representing the seed private key of the provided `TariWallet`.
  *
@@ -22,6 +22,7 @@
 
 use std::time::Duration;
 
-// TODO: consolidate ban durations
+// Fixed ban durations for connection-level failures (e.g. handshakes) where the peer is banned outright. Other
+// misbehaviour should be reported with `ConnectivityRequester::report_peer` so that bans follow the peer's reputation.
 pub const BAN_DURATION_LONG: Duration = Duration::from_secs(2 * 60 * 60);
 pub const BAN_DURATION_SHORT: Duration = Duration::from_secs(2 * 60);
@@ -42,11 +42,11 @@ use tokio::sync::{broadcast, mpsc};
 
 use crate::{
     backoff::{Backoff, BoxedBackoff, ConstantBackoff},
-    connection_manager::{ConnectionManagerConfig, ConnectionManagerRequester},
+    connection_manager::{BandwidthConfig, ConnectionManagerConfig, ConnectionManagerRequester},
     connectivity::{ConnectivityConfig, ConnectivityRequester},
     multiaddr::Multiaddr,
     net_address::MultiaddrRange,
-    peer_manager::{NodeIdentity, PeerManager},
+    peer_manager::{NodeIdentity, PeerManager, ReputationConfig},
     peer_validator::PeerValidatorConfig,
     protocol::{NodeNetworkInfo, ProtocolExtensions},
     tor,
@@ -267,12 +267,24 @@ impl CommsBuilder {
         self
     }
 
+    /// Sets the global, per-peer and per-protocol bandwidth limits applied to peer substreams
+    pub fn with_bandwidth_config(mut self, config: BandwidthConfig) -> Self {
+        self.connection_manager_config.bandwidth = config;
+        self
+    }
+
     /// Sets the minimum required connectivity as a percentage of peers added to the connectivity manager peer set.
     pub fn with_min_connectivity(mut self, min_connectivity: usize) -> Self {
         self.connectivity_config.min_connectivity = min_connectivity;
         self
     }
 
+    /// Sets the reputation thresholds used to ban misbehaving peers
+    pub fn with_reputation_config(mut self, config: ReputationConfig) -> Self {
+        self.connectivity_config.reputation = config;
+        self
+    }
+
     /// Call to disable connection reaping. Usually you would want to have this enabled, however there are some test
     /// cases where disabling this is desirable.
     pub fn disable_connection_reaping(mut self) -> Self {
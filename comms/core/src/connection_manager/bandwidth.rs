@@ -0,0 +1,583 @@
+// Copyright 2024 The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+//! Token-bucket bandwidth limiting for peer substreams.
+//!
+//! A [BandwidthLimiter] holds a global budget, a budget for each connected peer and a budget for each negotiated
+//! protocol. Every substream is assigned a `StreamRateLimit` once its protocol has been negotiated, and each read or
+//! write on the substream draws from all of the budgets that apply to it. A budget without a limit only meters the
+//! bytes that pass through it, so that current usage can always be reported.
+
+use std::{
+    cmp,
+    collections::HashMap,
+    fmt,
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+use tokio::time::{self, Sleep};
+
+use crate::{peer_manager::NodeId, protocol::ProtocolId};
+
+/// The smallest bucket capacity. Limits lower than this still allow a reasonably sized frame to be sent in one write.
+const MIN_BUCKET_CAPACITY: u64 = 4 * 1024;
+/// Once a bucket is empty, wait until at least this many bytes are available to avoid waking for every few bytes.
+const MIN_WAKE_BYTES: u64 = 1024;
+/// The period over which the current rate is measured
+const RATE_WINDOW: Duration = Duration::from_secs(1);
+/// The budget of a peer without open substreams is kept for at least this long, and until its buckets have refilled,
+/// so that closing and reopening substreams does not reset the peer's budget.
+const PEER_BUDGET_IDLE_TTL: Duration = Duration::from_secs(60);
+
+/// Upload and download limits in bytes per second. `None` means unlimited.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BandwidthLimit {
+    /// The maximum number of bytes per second that may be sent. Default: None (unlimited)
+    pub upload_bytes_per_sec: Option<u64>,
+    /// The maximum number of bytes per second that may be received. Default: None (unlimited)
+    pub download_bytes_per_sec: Option<u64>,
+}
+
+impl BandwidthLimit {
+    /// A limit that does not restrict bandwidth
+    pub const fn unlimited() -> Self {
+        Self {
+            upload_bytes_per_sec: None,
+            download_bytes_per_sec: None,
+        }
+    }
+
+    pub const fn new(upload_bytes_per_sec: Option<u64>, download_bytes_per_sec: Option<u64>) -> Self {
+        Self {
+            upload_bytes_per_sec,
+            download_bytes_per_sec,
+        }
+    }
+
+    pub fn is_unlimited(&self) -> bool {
+        self.upload_bytes_per_sec.is_none() && self.download_bytes_per_sec.is_none()
+    }
+}
+
+impl fmt::Display for BandwidthLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn fmt_rate(rate: Option<u64>) -> String {
+            rate.map(|r| format!("{}B/s", r))
+                .unwrap_or_else(|| "unlimited".to_string())
+        }
+        write!(
+            f,
+            "up: {}, down: {}",
+            fmt_rate(self.upload_bytes_per_sec),
+            fmt_rate(self.download_bytes_per_sec)
+        )
+    }
+}
+
+/// Bandwidth budgets applied to peer substreams
+#[derive(Debug, Clone, Default)]
+pub struct BandwidthConfig {
+    /// Limit on the total bandwidth used by all peer connections. Default: unlimited
+    pub global: BandwidthLimit,
+    /// Limit applied to each peer individually, across all of that peer's connections and substreams.
+    /// Default: unlimited
+    pub per_peer: BandwidthLimit,
+    /// Limits applied to all substreams of a protocol, across all peers. Default: empty (unlimited)
+    pub per_protocol: HashMap<ProtocolId, BandwidthLimit>,
+}
+
+/// The direction of data flow on a substream
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BandwidthDirection {
+    Upload,
+    Download,
+}
+
+/// A token bucket that refills at `rate` bytes per second up to `capacity` bytes
+#[derive(Debug)]
+struct TokenBucket {
+    rate: u64,
+    capacity: u64,
+    // Signed so that a read larger than the available tokens puts the bucket in debt
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(rate: u64, now: Instant) -> Self {
+        let rate = cmp::max(rate, 1);
+        let capacity = cmp::max(rate, MIN_BUCKET_CAPACITY);
+        Self {
+            rate,
+            capacity,
+            tokens: capacity as f64,
+            last_refill: now,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_refill);
+        self.tokens = (self.tokens + elapsed.as_secs_f64() * self.rate as f64).min(self.capacity as f64);
+        self.last_refill = now;
+    }
+
+    /// Returns the number of bytes available, or the time to wait until enough bytes are available
+    #[allow(clippy::cast_possible_truncation)]
+    fn available(&mut self, now: Instant) -> Result<u64, Duration> {
+        self.refill(now);
+        if self.tokens >= 1.0 {
+            return Ok(self.tokens as u64);
+        }
+        let wake_at = cmp::min(MIN_WAKE_BYTES, self.capacity) as f64;
+        Err(Duration::from_secs_f64((wake_at - self.tokens) / self.rate as f64))
+    }
+
+    fn consume(&mut self, num_bytes: u64, now: Instant) {
+        self.refill(now);
+        self.tokens -= num_bytes as f64;
+    }
+
+    fn is_full(&mut self, now: Instant) -> bool {
+        self.refill(now);
+        self.tokens >= self.capacity as f64
+    }
+}
+
+/// Measures the total bytes and the rate over the last complete window
+#[derive(Debug)]
+struct RateMeter {
+    total: u64,
+    window_start: Instant,
+    window_bytes: u64,
+    last_rate: u64,
+}
+
+impl RateMeter {
+    fn new(now: Instant) -> Self {
+        Self {
+            total: 0,
+            window_start: now,
+            window_bytes: 0,
+            last_rate: 0,
+        }
+    }
+
+    fn record(&mut self, num_bytes: u64, now: Instant) {
+        self.roll(now);
+        self.total = self.total.saturating_add(num_bytes);
+        self.window_bytes = self.window_bytes.saturating_add(num_bytes);
+    }
+
+    fn rate(&mut self, now: Instant) -> u64 {
+        self.roll(now);
+        self.last_rate
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn roll(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.window_start);
+        if elapsed < RATE_WINDOW {
+            return;
+        }
+        // If more than one window has passed without any activity, the rate has dropped to zero
+        self.last_rate = if elapsed < RATE_WINDOW * 2 {
+            (self.window_bytes as f64 / elapsed.as_secs_f64()) as u64
+        } else {
+            0
+        };
+        self.window_start = now;
+        self.window_bytes = 0;
+    }
+}
+
+#[derive(Debug)]
+struct Throttle {
+    bucket: Option<TokenBucket>,
+    meter: RateMeter,
+}
+
+impl Throttle {
+    fn new(rate: Option<u64>, now: Instant) -> Self {
+        Self {
+            bucket: rate.map(|rate| TokenBucket::new(rate, now)),
+            meter: RateMeter::new(now),
+        }
+    }
+
+    fn available(&mut self, now: Instant) -> Result<u64, Duration> {
+        match self.bucket.as_mut() {
+            Some(bucket) => bucket.available(now),
+            None => Ok(u64::MAX),
+        }
+    }
+
+    fn consume(&mut self, num_bytes: u64, now: Instant) {
+        if let Some(bucket) = self.bucket.as_mut() {
+            bucket.consume(num_bytes, now);
+        }
+        self.meter.record(num_bytes, now);
+    }
+
+    fn is_replenished(&mut self, now: Instant) -> bool {
+        self.bucket.as_mut().map_or(true, |bucket| bucket.is_full(now))
+    }
+}
+
+/// Upload and download throttles for a single budget (global, a peer or a protocol)
+#[derive(Debug)]
+struct Budget {
+    limit: BandwidthLimit,
+    upload: Mutex<Throttle>,
+    download: Mutex<Throttle>,
+}
+
+impl Budget {
+    fn new(limit: BandwidthLimit) -> Self {
+        let now = Instant::now();
+        Self {
+            limit,
+            upload: Mutex::new(Throttle::new(limit.upload_bytes_per_sec, now)),
+            download: Mutex::new(Throttle::new(limit.download_bytes_per_sec, now)),
+        }
+    }
+
+    fn throttle(&self, direction: BandwidthDirection) -> std::sync::MutexGuard<'_, Throttle> {
+        let throttle = match direction {
+            BandwidthDirection::Upload => &self.upload,
+            BandwidthDirection::Download => &self.download,
+        };
+        lock(throttle)
+    }
+
+    /// Returns true if both buckets are full, so that replacing this budget with a new one would not lift a limit
+    fn is_replenished(&self, now: Instant) -> bool {
+        self.throttle(BandwidthDirection::Upload).is_replenished(now) &&
+            self.throttle(BandwidthDirection::Download).is_replenished(now)
+    }
+
+    fn stats(&self) -> BandwidthStats {
+        let now = Instant::now();
+        let (bytes_uploaded, upload_rate) = {
+            let mut upload = self.throttle(BandwidthDirection::Upload);
+            (upload.meter.total, upload.meter.rate(now))
+        };
+        let (bytes_downloaded, download_rate) = {
+            let mut download = self.throttle(BandwidthDirection::Download);
+            (download.meter.total, download.meter.rate(now))
+        };
+        BandwidthStats {
+            limit: self.limit,
+            bytes_uploaded,
+            bytes_downloaded,
+            upload_rate,
+            download_rate,
+        }
+    }
+}
+
+/// Bandwidth usage for a budget
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BandwidthStats {
+    /// The configured limit
+    pub limit: BandwidthLimit,
+    /// Total bytes sent
+    pub bytes_uploaded: u64,
+    /// Total bytes received
+    pub bytes_downloaded: u64,
+    /// Bytes sent per second, measured over the last second
+    pub upload_rate: u64,
+    /// Bytes received per second, measured over the last second
+    pub download_rate: u64,
+}
+
+/// A snapshot of the current bandwidth usage
+#[derive(Debug, Clone, Default)]
+pub struct BandwidthUsage {
+    /// Usage across all peer connections
+    pub global: BandwidthStats,
+    /// Usage for each peer that has open substreams or had them recently
+    pub per_peer: Vec<(NodeId, BandwidthStats)>,
+    /// Usage for each protocol that has been negotiated
+    pub per_protocol: Vec<(ProtocolId, BandwidthStats)>,
+}
+
+/// Allocates bandwidth budgets to substreams. This is cheap to clone and all clones share the same budgets.
+#[derive(Debug, Clone)]
+pub struct BandwidthLimiter {
+    inner: Arc<LimiterInner>,
+}
+
+#[derive(Debug)]
+struct LimiterInner {
+    config: BandwidthConfig,
+    global: Arc<Budget>,
+    peers: Mutex<HashMap<NodeId, PeerBudget>>,
+    protocols: Mutex<HashMap<ProtocolId, Arc<Budget>>>,
+}
+
+/// The budget of a peer, shared by all of the peer's substreams
+#[derive(Debug)]
+struct PeerBudget {
+    budget: Arc<Budget>,
+    /// When the peer was first seen without open substreams
+    idle_since: Option<Instant>,
+}
+
+/// Drops the budgets of peers that have had no open substreams for `PEER_BUDGET_IDLE_TTL` and whose buckets have
+/// refilled
+fn prune_idle_peers(peers: &mut HashMap<NodeId, PeerBudget>, now: Instant) {
+    peers.retain(|_, peer| {
+        if Arc::strong_count(&peer.budget) > 1 {
+            peer.idle_since = None;
+            return true;
+        }
+        let idle_since = *peer.idle_since.get_or_insert(now);
+        now.saturating_duration_since(idle_since) < PEER_BUDGET_IDLE_TTL || !peer.budget.is_replenished(now)
+    });
+}
+
+impl BandwidthLimiter {
+    pub fn new(config: BandwidthConfig) -> Self {
+        Self {
+            inner: Arc::new(LimiterInner {
+                global: Arc::new(Budget::new(config.global)),
+                peers: Mutex::new(HashMap::new()),
+                protocols: Mutex::new(HashMap::new()),
+                config,
+            }),
+        }
+    }
+
+    pub fn config(&self) -> &BandwidthConfig {
+        &self.inner.config
+    }
+
+    /// Returns the rate limit for a substream to the given peer on the given protocol
+    pub(crate) fn for_stream(&self, node_id: &NodeId, protocol: &ProtocolId) -> StreamRateLimit {
+        let peer = {
+            let mut peers = lock(&self.inner.peers);
+            prune_idle_peers(&mut peers, Instant::now());
+            let peer = peers.entry(node_id.clone()).or_insert_with(|| PeerBudget {
+                budget: Arc::new(Budget::new(self.inner.config.per_peer)),
+                idle_since: None,
+            });
+            peer.idle_since = None;
+            peer.budget.clone()
+        };
+        let protocol = lock(&self.inner.protocols)
+            .entry(protocol.clone())
+            .or_insert_with(|| {
+                let limit = self
+                    .inner
+                    .config
+                    .per_protocol
+                    .get(protocol)
+                    .copied()
+                    .unwrap_or_default();
+                Arc::new(Budget::new(limit))
+            })
+            .clone();
+
+        StreamRateLimit {
+            budgets: [self.inner.global.clone(), peer, protocol],
+            upload_delay: None,
+            download_delay: None,
+        }
+    }
+
+    /// Returns the current bandwidth usage
+    pub fn usage(&self) -> BandwidthUsage {
+        let per_peer = {
+            let mut peers = lock(&self.inner.peers);
+            prune_idle_peers(&mut peers, Instant::now());
+            peers
+                .iter()
+                .map(|(node_id, peer)| (node_id.clone(), peer.budget.stats()))
+                .collect()
+        };
+        let per_protocol = lock(&self.inner.protocols)
+            .iter()
+            .map(|(protocol, budget)| (protocol.clone(), budget.stats()))
+            .collect();
+
+        BandwidthUsage {
+            global: self.inner.global.stats(),
+            per_peer,
+            per_protocol,
+        }
+    }
+}
+
+impl Default for BandwidthLimiter {
+    fn default() -> Self {
+        Self::new(BandwidthConfig::default())
+    }
+}
+
+/// Locks the mutex, ignoring poisoning. A panic while holding one of these locks cannot leave the budgets in an
+/// invalid state.
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+/// The budgets that apply to a single substream
+#[derive(Debug)]
+pub(crate) struct StreamRateLimit {
+    budgets: [Arc<Budget>; 3],
+    upload_delay: Option<Pin<Box<Sleep>>>,
+    download_delay: Option<Pin<Box<Sleep>>>,
+}
+
+impl StreamRateLimit {
+    /// Resolves to the number of bytes that may be transferred in the given direction once all budgets have capacity
+    pub fn poll_acquire(&mut self, direction: BandwidthDirection, cx: &mut Context<'_>) -> Poll<usize> {
+        loop {
+            let delay = match direction {
+                BandwidthDirection::Upload => &mut self.upload_delay,
+                BandwidthDirection::Download => &mut self.download_delay,
+            };
+            if let Some(sleep) = delay.as_mut() {
+                futures::ready!(sleep.as_mut().poll(cx));
+                *delay = None;
+            }
+
+            let now = Instant::now();
+            let mut allowed = u64::MAX;
+            let mut wait = Duration::ZERO;
+            for budget in &self.budgets {
+                match budget.throttle(direction).available(now) {
+                    Ok(available) => allowed = cmp::min(allowed, available),
+                    Err(duration) => wait = cmp::max(wait, duration),
+                }
+            }
+
+            if wait.is_zero() {
+                return Poll::Ready(usize::try_from(allowed).unwrap_or(usize::MAX));
+            }
+            *delay = Some(Box::pin(time::sleep(wait)));
+        }
+    }
+
+    /// Records bytes that were transferred in the given direction against all budgets
+    pub fn record(&self, direction: BandwidthDirection, num_bytes: usize) {
+        if num_bytes == 0 {
+            return;
+        }
+        let now = Instant::now();
+        let num_bytes = num_bytes as u64;
+        for budget in &self.budgets {
+            budget.throttle(direction).consume(num_bytes, now);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::node_id;
+
+    #[test]
+    fn it_limits_and_refills_the_bucket() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(8 * 1024, start);
+        assert_eq!(bucket.available(start).unwrap(), 8 * 1024);
+        bucket.consume(8 * 1024, start);
+        let wait = bucket.available(start).unwrap_err();
+        assert_eq!(wait, Duration::from_millis(125));
+        // Half a second refills half of the capacity
+        let later = start + Duration::from_millis(500);
+        assert_eq!(bucket.available(later).unwrap(), 4 * 1024);
+        // The bucket never exceeds its capacity
+        let much_later = start + Duration::from_secs(10);
+        assert_eq!(bucket.available(much_later).unwrap(), 8 * 1024);
+    }
+
+    #[test]
+    fn it_allows_debt_and_a_minimum_capacity() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(100, start);
+        assert_eq!(bucket.available(start).unwrap(), MIN_BUCKET_CAPACITY);
+        bucket.consume(MIN_BUCKET_CAPACITY + 100, start);
+        // 100 bytes of debt plus MIN_WAKE_BYTES at 100 bytes/s
+        let wait = bucket.available(start).unwrap_err();
+        assert_eq!(wait, Duration::from_secs_f64(11.24));
+    }
+
+    #[test]
+    fn it_measures_the_rate() {
+        let start = Instant::now();
+        let mut meter = RateMeter::new(start);
+        meter.record(1000, start);
+        meter.record(1000, start + Duration::from_millis(500));
+        assert_eq!(meter.rate(start + Duration::from_millis(900)), 0);
+        assert_eq!(meter.rate(start + Duration::from_secs(1)), 2000);
+        assert_eq!(meter.rate(start + Duration::from_secs(5)), 0);
+        assert_eq!(meter.total, 2000);
+    }
+
+    #[tokio::test]
+    async fn it_shares_budgets_between_streams() {
+        let protocol = ProtocolId::from_static(b"/tari/test/1");
+        let limiter = BandwidthLimiter::new(BandwidthConfig {
+            per_peer: BandwidthLimit::new(Some(MIN_BUCKET_CAPACITY), None),
+            ..Default::default()
+        });
+        let peer = node_id::random();
+        let stream1 = limiter.for_stream(&peer, &protocol);
+        let mut stream2 = limiter.for_stream(&peer, &protocol);
+        stream1.record(BandwidthDirection::Upload, 4 * 1024);
+        stream1.record(BandwidthDirection::Download, 1024);
+
+        let waker = futures::task::noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        assert!(stream2.poll_acquire(BandwidthDirection::Upload, &mut cx).is_pending());
+        assert!(stream2.poll_acquire(BandwidthDirection::Download, &mut cx).is_ready());
+
+        let usage = limiter.usage();
+        assert_eq!(usage.global.bytes_uploaded, 4 * 1024);
+        assert_eq!(usage.per_peer.len(), 1);
+        assert_eq!(usage.per_peer[0].1.bytes_downloaded, 1024);
+        assert_eq!(usage.per_protocol[0].0, protocol);
+
+        drop(stream1);
+        drop(stream2);
+        // The budget of a peer without substreams is kept for a while
+        assert_eq!(limiter.usage().per_peer.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn it_keeps_the_peer_budget_when_a_substream_is_reopened() {
+        let protocol = ProtocolId::from_static(b"/tari/test/1");
+        let limiter = BandwidthLimiter::new(BandwidthConfig {
+            per_peer: BandwidthLimit::new(Some(MIN_BUCKET_CAPACITY), None),
+            ..Default::default()
+        });
+        let peer = node_id::random();
+        let stream = limiter.for_stream(&peer, &protocol);
+        stream.record(BandwidthDirection::Upload, 4 * 1024);
+        drop(stream);
+
+        // Reopening a substream does not give the peer a fresh budget
+        let mut stream = limiter.for_stream(&peer, &protocol);
+        let waker = futures::task::noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        assert!(stream.poll_acquire(BandwidthDirection::Upload, &mut cx).is_pending());
+        let usage = limiter.usage();
+        assert_eq!(usage.per_peer[0].1.bytes_uploaded, 4 * 1024);
+        drop(stream);
+
+        // Once the peer has been idle for long enough and the budget has refilled, it is dropped
+        let mut peers = lock(&limiter.inner.peers);
+        let now = Instant::now();
+        prune_idle_peers(&mut peers, now);
+        assert_eq!(peers.len(), 1);
+        prune_idle_peers(&mut peers, now + PEER_BUDGET_IDLE_TTL);
+        assert!(peers.is_empty());
+    }
+}
@@ -47,16 +47,17 @@ use crate::connection_manager::metrics;
 use crate::{
     backoff::Backoff,
     connection_manager::{
+        bandwidth::BandwidthLimiter,
         common,
         common::ValidatedPeerIdentityExchange,
         dial_state::DialState,
         manager::{ConnectionManagerConfig, ConnectionManagerEvent},
         peer_connection,
+        secure_socket::SecureSocket,
     },
     multiaddr::Multiaddr,
-    multiplexing::Yamux,
     net_address::{MultiaddrRange, PeerAddressSource},
-    noise::{NoiseConfig, NoiseSocket},
+    noise::NoiseConfig,
     peer_manager::{NodeId, NodeIdentity, Peer, PeerManager},
     protocol::ProtocolId,
     transports::Transport,
@@ -65,7 +66,7 @@ use crate::{
 
 const LOG_TARGET: &str = "comms::connection_manager::dialer";
 
-type DialResult<TSocket> = Result<(NoiseSocket<TSocket>, Multiaddr), ConnectionManagerError>;
+type DialResult<TSocket> = Result<(SecureSocket<TSocket>, Multiaddr), ConnectionManagerError>;
 type DialFuturesUnordered = FuturesUnordered<
     BoxFuture<
         'static,
@@ -100,6 +101,7 @@ pub struct Dialer<TTransport, TBackoff> {
     shutdown: Option<ShutdownSignal>,
     pending_dial_requests: HashMap<NodeId, Vec<oneshot::Sender<Result<PeerConnection, ConnectionManagerError>>>>,
     our_supported_protocols: Arc<Vec<ProtocolId>>,
+    bandwidth_limiter: BandwidthLimiter,
 }
 
 impl<TTransport, TBackoff> Dialer<TTransport, TBackoff>
@@ -132,6 +134,7 @@ where
             shutdown: Some(shutdown),
             pending_dial_requests: Default::default(),
             our_supported_protocols: Arc::new(Vec::new()),
+            bandwidth_limiter: BandwidthLimiter::default(),
         }
     }
 
@@ -141,6 +144,12 @@ where
         self
     }
 
+    /// Set the bandwidth limiter that is applied to substreams of connections established by this dialer
+    pub fn set_bandwidth_limiter(&mut self, bandwidth_limiter: BandwidthLimiter) -> &mut Self {
+        self.bandwidth_limiter = bandwidth_limiter;
+        self
+    }
+
     pub fn spawn(self) -> JoinHandle<()> {
         tokio::spawn(self.run())
     }
@@ -342,6 +351,7 @@ where
         let node_identity = Arc::clone(&self.node_identity);
         let conn_man_notifier = self.conn_man_notifier.clone();
         let supported_protocols = self.our_supported_protocols.clone();
+        let bandwidth_limiter = self.bandwidth_limiter.clone();
         let noise_config = self.noise_config.clone();
         let config = self.config.clone();
         let peer_manager = self.peer_manager.clone();
@@ -349,7 +359,7 @@ where
         let span = span!(Level::TRACE, "handle_dial_peer_request_inner1");
         let dial_fut = async move {
             let (dial_state, dial_result) =
-                Self::dial_peer_with_retry(dial_state, noise_config, transport, backoff, &config).await;
+                Self::dial_peer_with_retry(dial_state, noise_config, &node_identity, transport, backoff, &config).await;
 
             let cancel_signal = dial_state.get_cancel_signal();
 
@@ -376,6 +386,7 @@ where
                         authenticated_public_key,
                         conn_man_notifier,
                         supported_protocols,
+                        bandwidth_limiter,
                         &config,
                         cancel_signal,
                     )
@@ -401,7 +412,7 @@ where
     }
 
     fn check_authenticated_public_key(
-        socket: &NoiseSocket<TTransport::Output>,
+        socket: &SecureSocket<TTransport::Output>,
         expected_public_key: &CommsPublicKey,
     ) -> Result<CommsPublicKey, ConnectionManagerError> {
         let authenticated_public_key = socket
@@ -421,11 +432,12 @@ where
     async fn perform_socket_upgrade_procedure(
         peer_manager: &PeerManager,
         node_identity: &NodeIdentity,
-        mut socket: NoiseSocket<TTransport::Output>,
+        mut socket: SecureSocket<TTransport::Output>,
         dialed_addr: Multiaddr,
         authenticated_public_key: CommsPublicKey,
         conn_man_notifier: mpsc::Sender<ConnectionManagerEvent>,
         our_supported_protocols: Arc<Vec<ProtocolId>>,
+        bandwidth_limiter: BandwidthLimiter,
         config: &ConnectionManagerConfig,
         cancel_signal: ShutdownSignal,
     ) -> Result<(PeerConnection, ValidatedPeerIdentityExchange), ConnectionManagerError> {
@@ -462,11 +474,10 @@ where
             return Err(ConnectionManagerError::DialCancelled);
         }
 
-        let muxer = Yamux::upgrade_connection(socket, CONNECTION_DIRECTION)
-            .map_err(|err| ConnectionManagerError::YamuxUpgradeFailure(err.to_string()))?;
+        let muxer = socket.into_multiplexed(CONNECTION_DIRECTION)?;
 
         if cancel_signal.is_terminated() {
-            muxer.get_yamux_control().close().await?;
+            muxer.control().close().await?;
             return Err(ConnectionManagerError::DialCancelled);
         }
 
@@ -479,6 +490,7 @@ where
             conn_man_notifier,
             our_supported_protocols,
             peer_identity.metadata.supported_protocols.clone(),
+            bandwidth_limiter,
         );
 
         Ok((peer_connection, peer_identity))
@@ -487,6 +499,7 @@ where
     async fn dial_peer_with_retry(
         dial_state: DialState,
         noise_config: NoiseConfig,
+        node_identity: &NodeIdentity,
         transport: TTransport,
         backoff: Arc<TBackoff>,
         config: &ConnectionManagerConfig,
@@ -516,13 +529,22 @@ where
             tokio::select! {
                 _ = delay => {
                     debug!(target: LOG_TARGET, "[Attempt {}] Connecting to peer '{}'", current_state.num_attempts(), current_state.peer().node_id.short_str());
-                    match Self::dial_peer(current_state, &noise_config, &current_transport, config.network_info.network_wire_byte, config.excluded_dial_addresses.clone()).await {
+                    let dial_result = Self::dial_peer(
+                        current_state,
+                        &noise_config,
+                        node_identity,
+                        &current_transport,
+                        config.network_info.network_wire_byte,
+                        config.excluded_dial_addresses.clone(),
+                    ).await;
+                    match dial_result {
                         (state, Ok((socket, addr))) => {
                             debug!(target: LOG_TARGET, "Dial succeeded for peer '{}' after {} attempt(s)", state.peer().node_id.short_str(), state.num_attempts());
                             break (state, Ok((socket, addr)));
                         },
                         // Connection went stale, propagate error to enable starting a fresh connection
                         (state, Err(ConnectionManagerError::NoiseHandshakeError(e))) => break (state, Err(ConnectionManagerError::NoiseHandshakeError(e))),
+                        (state, Err(ConnectionManagerError::QuicHandshakeError(e))) => break (state, Err(ConnectionManagerError::QuicHandshakeError(e))),
                         // Inflight dial was cancelled
                         (state, Err(ConnectionManagerError::DialCancelled)) => break (state, Err(ConnectionManagerError::DialCancelled)),
                         // All public addresses for this peer are excluded
@@ -555,13 +577,11 @@ where
     async fn dial_peer(
         mut dial_state: DialState,
         noise_config: &NoiseConfig,
+        node_identity: &NodeIdentity,
         transport: &TTransport,
         network_byte: u8,
         excluded_dial_addresses: Vec<MultiaddrRange>,
-    ) -> (
-        DialState,
-        Result<(NoiseSocket<TTransport::Output>, Multiaddr), ConnectionManagerError>,
-    ) {
+    ) -> (DialState, DialResult<TTransport::Output>) {
         let addresses = dial_state
             .peer()
             .addresses
@@ -621,7 +641,7 @@ where
                 );
                 trace!(
                     target: LOG_TARGET,
-                    "Dial - Socket established on '{}'. Performing noise upgrade protocol", moved_address
+                    "Dial - Socket established on '{}'. Securing the connection", moved_address
                 );
                 timer = Instant::now();
 
@@ -630,42 +650,46 @@ where
                     .await
                     .map_err(|_| ConnectionManagerError::WireFormatSendFailed)?;
 
-                let noise_socket = noise_config
-                    .upgrade_socket(socket, ConnectionDirection::Outbound)
-                    .await
-                    .map_err(|err| {
-                        warn!(
-                            target: LOG_TARGET,
-                            "Dial - failed to upgrade noise: {} on address: {} ({})",
-                            node_id,
-                            moved_address,
-                            err
-                        );
+                let secure_socket = SecureSocket::upgrade::<TTransport>(
+                    socket,
+                    noise_config,
+                    node_identity,
+                    ConnectionDirection::Outbound,
+                )
+                .await
+                .map_err(|err| {
+                    warn!(
+                        target: LOG_TARGET,
+                        "Dial - failed to secure connection: {} on address: {} ({})",
+                        node_id,
+                        moved_address,
                         err
-                    })?;
+                    );
+                    err
+                })?;
 
-                let noise_upgrade_time = timer.elapsed();
+                let upgrade_time = timer.elapsed();
                 trace!(
-                    "Dial - upgraded noise: {} on address: {} on tcp after: {} ms",
+                    "Dial - secured connection: {} on address: {} on tcp after: {} ms",
                     node_id.short_str(),
                     moved_address,
                     timer.elapsed().as_millis()
                 );
 
-                Result::<_, ConnectionManagerError>::Ok((initial_dial_time, noise_upgrade_time, noise_socket))
+                Result::<_, ConnectionManagerError>::Ok((initial_dial_time, upgrade_time, secure_socket))
             };
 
             pin_mut!(dial_fut);
             let either = future::select(dial_fut, cancel_signal.clone()).await;
             match either {
-                Either::Left((Ok((initial_dial_time, noise_upgrade_time, noise_socket)), _)) => {
+                Either::Left((Ok((initial_dial_time, upgrade_time, secure_socket)), _)) => {
                     dial_state.peer_mut().addresses.mark_last_seen_now(&address);
                     dial_state.peer_mut().addresses.update_address_stats(&address, |addr| {
                         // Initial dial time can be much slower due to tor discovery.
                         addr.update_initial_dial_time(initial_dial_time);
-                        addr.update_latency(noise_upgrade_time);
+                        addr.update_latency(upgrade_time);
                     });
-                    return (dial_state, Ok((noise_socket, address.clone())));
+                    return (dial_state, Ok((secure_socket, address.clone())));
                 },
                 Either::Left((Err(err), _)) => {
                     debug!(
@@ -686,6 +710,9 @@ where
                     if let ConnectionManagerError::NoiseHandshakeError(msg) = err {
                         return (dial_state, Err(ConnectionManagerError::NoiseHandshakeError(msg)));
                     }
+                    if let ConnectionManagerError::QuicHandshakeError(msg) = err {
+                        return (dial_state, Err(ConnectionManagerError::QuicHandshakeError(msg)));
+                    }
                     // Try the next address
                     continue;
                 },
@@ -74,6 +74,10 @@ pub enum ConnectionManagerError {
     // send the same response to multiple requesters
     #[error("Noise handshake error: {0}")]
     NoiseHandshakeError(String),
+    // This is a String because we need this error to be clone-able so that we can
+    // send the same response to multiple requesters
+    #[error("QUIC handshake error: {0}")]
+    QuicHandshakeError(String),
     #[error("Peer is banned, denying connection")]
     PeerBanned,
     #[error("Identity protocol failed: {0}")]
@@ -43,10 +43,12 @@ use tokio_stream::StreamExt;
 use tracing::{span, Instrument, Level};
 
 use super::{
+    bandwidth::BandwidthLimiter,
     common,
     direction::ConnectionDirection,
     error::ConnectionManagerError,
     peer_connection::{self, PeerConnection},
+    secure_socket::SecureSocket,
     ConnectionManagerConfig,
     ConnectionManagerEvent,
 };
@@ -59,12 +61,11 @@ use crate::{
         wire_mode::{WireMode, LIVENESS_WIRE_MODE},
     },
     multiaddr::Multiaddr,
-    multiplexing::Yamux,
     noise::NoiseConfig,
     peer_manager::NodeIdentity,
     protocol::ProtocolId,
     transports::Transport,
-    utils::multiaddr::multiaddr_to_socketaddr,
+    utils::multiaddr::{multiaddr_to_quic_socketaddr, multiaddr_to_socketaddr},
     PeerManager,
 };
 
@@ -82,6 +83,7 @@ pub struct PeerListener<TTransport> {
     peer_manager: Arc<PeerManager>,
     node_identity: Arc<NodeIdentity>,
     our_supported_protocols: Arc<Vec<ProtocolId>>,
+    bandwidth_limiter: BandwidthLimiter,
     liveness_session_count: Arc<AtomicUsize>,
     on_listening: OneshotTrigger<Result<Multiaddr, ConnectionManagerError>>,
 }
@@ -110,6 +112,7 @@ where
             node_identity,
             shutdown_signal,
             our_supported_protocols: Arc::new(Vec::new()),
+            bandwidth_limiter: BandwidthLimiter::default(),
             bounded_executor: BoundedExecutor::new(config.max_simultaneous_inbound_connects),
             liveness_session_count: Arc::new(AtomicUsize::new(config.liveness_max_sessions)),
             config,
@@ -132,6 +135,12 @@ where
         self
     }
 
+    /// Set the bandwidth limiter that is applied to substreams of connections established by this listener
+    pub fn set_bandwidth_limiter(&mut self, bandwidth_limiter: BandwidthLimiter) -> &mut Self {
+        self.bandwidth_limiter = bandwidth_limiter;
+        self
+    }
+
     pub async fn listen(self) -> Result<Multiaddr, ConnectionManagerError> {
         let on_listening = self.on_listening();
         tokio::spawn(self.run());
@@ -203,12 +212,12 @@ where
     }
 
     fn is_address_in_liveness_cidr_range(addr: &Multiaddr, allowlist: &[cidr::AnyIpCidr]) -> bool {
-        match multiaddr_to_socketaddr(addr) {
+        match multiaddr_to_socketaddr(addr).or_else(|_| multiaddr_to_quic_socketaddr(addr)) {
             Ok(socket_addr) => allowlist.iter().any(|cidr| cidr.contains(&socket_addr.ip())),
             Err(_) => {
                 warn!(
                     target: LOG_TARGET,
-                    "Peer address '{}' is invalid for liveness checks. It must be an TCP/IP address.", addr
+                    "Peer address '{}' is invalid for liveness checks. It must be a TCP/IP or QUIC address.", addr
                 );
                 false
             },
@@ -236,6 +245,7 @@ where
         let noise_config = self.noise_config.clone();
         let config = self.config.clone();
         let our_supported_protocols = self.our_supported_protocols.clone();
+        let bandwidth_limiter = self.bandwidth_limiter.clone();
         let liveness_session_count = self.liveness_session_count.clone();
         let shutdown_signal = self.shutdown_signal.clone();
 
@@ -254,6 +264,7 @@ where
                         socket,
                         peer_addr,
                         our_supported_protocols,
+                        bandwidth_limiter,
                         &config,
                     )
                     .await;
@@ -345,37 +356,38 @@ where
         socket: TTransport::Output,
         peer_addr: Multiaddr,
         our_supported_protocols: Arc<Vec<ProtocolId>>,
+        bandwidth_limiter: BandwidthLimiter,
         config: &ConnectionManagerConfig,
     ) -> Result<PeerConnection, ConnectionManagerError> {
         const CONNECTION_DIRECTION: ConnectionDirection = ConnectionDirection::Inbound;
         trace!(
             target: LOG_TARGET,
-            "Listen - starting noise protocol upgrade for peer at address '{}'", peer_addr
+            "Listen - securing the connection for peer at address '{}'", peer_addr
         );
 
         let timer = Instant::now();
-        let mut noise_socket = noise_config
-            .upgrade_socket(socket, CONNECTION_DIRECTION)
-            .await
-            .map_err(|err| {
-                warn!(
-                    target: LOG_TARGET,
-                    "Listen - failed to upgrade noise: {} on address: {} ({})",
-                    node_identity.node_id(),
-                    peer_addr,
+        let mut secure_socket =
+            SecureSocket::upgrade::<TTransport>(socket, &noise_config, node_identity, CONNECTION_DIRECTION)
+                .await
+                .map_err(|err| {
+                    warn!(
+                        target: LOG_TARGET,
+                        "Listen - failed to secure connection: {} on address: {} ({})",
+                        node_identity.node_id(),
+                        peer_addr,
+                        err
+                    );
                     err
-                );
-                err
-            })?;
+                })?;
 
-        let authenticated_public_key = noise_socket
+        let authenticated_public_key = secure_socket
             .get_remote_public_key()
             .ok_or(ConnectionManagerError::InvalidStaticPublicKey)?;
         let latency = timer.elapsed();
 
         trace!(
             target: LOG_TARGET,
-            "Listen - noise socket upgrade completed in {:.2?} with public key '{}'",
+            "Listen - connection secured in {:.2?} with public key '{}'",
             latency,
             authenticated_public_key
         );
@@ -390,7 +402,7 @@ where
         );
 
         let peer_identity_result = common::perform_identity_exchange(
-            &mut noise_socket,
+            &mut secure_socket,
             node_identity,
             &*our_supported_protocols,
             config.network_info.clone(),
@@ -416,8 +428,7 @@ where
             latency,
         );
 
-        let muxer = Yamux::upgrade_connection(noise_socket, CONNECTION_DIRECTION)
-            .map_err(|err| ConnectionManagerError::YamuxUpgradeFailure(err.to_string()))?;
+        let muxer = secure_socket.into_multiplexed(CONNECTION_DIRECTION)?;
 
         let conn = peer_connection::create(
             muxer,
@@ -428,6 +439,7 @@ where
             conn_man_notifier,
             our_supported_protocols,
             valid_peer_identity.metadata.supported_protocols,
+            bandwidth_limiter,
         );
 
         peer_manager.add_peer(peer).await?;
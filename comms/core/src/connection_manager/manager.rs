@@ -35,6 +35,7 @@ use tokio::{
 use tracing::{span, Instrument, Level};
 
 use super::{
+    bandwidth::{BandwidthConfig, BandwidthLimiter},
     dialer::{Dialer, DialerRequest},
     error::ConnectionManagerError,
     listener::PeerListener,
@@ -136,6 +137,8 @@ pub struct ConnectionManagerConfig {
     pub peer_validation_config: PeerValidatorConfig,
     /// Addresses that should never be dialed
     pub excluded_dial_addresses: Vec<MultiaddrRange>,
+    /// Bandwidth limits applied to peer substreams. Default: unlimited
+    pub bandwidth: BandwidthConfig,
 }
 
 impl Default for ConnectionManagerConfig {
@@ -158,6 +161,7 @@ impl Default for ConnectionManagerConfig {
             peer_validation_config: PeerValidatorConfig::default(),
             noise_handshake_recv_timeout: Duration::from_secs(6),
             excluded_dial_addresses: vec![],
+            bandwidth: BandwidthConfig::default(),
         }
     }
 }
@@ -197,6 +201,7 @@ pub(crate) struct ConnectionManager<TTransport, TBackoff> {
     listening_notifiers: Vec<oneshot::Sender<ListenerInfo>>,
     connection_manager_events_tx: broadcast::Sender<Arc<ConnectionManagerEvent>>,
     complete_trigger: Shutdown,
+    bandwidth_limiter: BandwidthLimiter,
 }
 
 impl<TTransport, TBackoff> ConnectionManager<TTransport, TBackoff>
@@ -220,8 +225,9 @@ where
 
         let noise_config =
             NoiseConfig::new(node_identity.clone()).with_recv_timeout(config.noise_handshake_recv_timeout);
+        let bandwidth_limiter = BandwidthLimiter::new(config.bandwidth.clone());
 
-        let listener = PeerListener::new(
+        let mut listener = PeerListener::new(
             config.clone(),
             config.listener_address.clone(),
             transport.clone(),
@@ -231,6 +237,7 @@ where
             node_identity.clone(),
             shutdown_signal.clone(),
         );
+        listener.set_bandwidth_limiter(bandwidth_limiter.clone());
 
         let aux_listener = config.auxiliary_tcp_listener_address.take().map(|addr| {
             info!(target: LOG_TARGET, "Starting auxiliary listener on {}", addr);
@@ -239,7 +246,7 @@ where
                 self_liveness_self_check_interval: None,
                 ..config.clone()
             };
-            PeerListener::new(
+            let mut aux_listener = PeerListener::new(
                 aux_config,
                 addr,
                 TcpTransport::new(),
@@ -248,10 +255,12 @@ where
                 peer_manager.clone(),
                 node_identity.clone(),
                 shutdown_signal.clone(),
-            )
+            );
+            aux_listener.set_bandwidth_limiter(bandwidth_limiter.clone());
+            aux_listener
         });
 
-        let dialer = Dialer::new(
+        let mut dialer = Dialer::new(
             config,
             node_identity,
             peer_manager.clone(),
@@ -262,6 +271,7 @@ where
             internal_event_tx,
             shutdown_signal.clone(),
         );
+        dialer.set_bandwidth_limiter(bandwidth_limiter.clone());
 
         Self {
             shutdown_signal: Some(shutdown_signal),
@@ -277,6 +287,7 @@ where
             listening_notifiers: Vec::new(),
             connection_manager_events_tx,
             complete_trigger: Shutdown::new(),
+            bandwidth_limiter,
         }
     }
 
@@ -382,7 +393,7 @@ where
     }
 
     async fn handle_request(&mut self, request: ConnectionManagerRequest) {
-        use ConnectionManagerRequest::{CancelDial, DialPeer, NotifyListening};
+        use ConnectionManagerRequest::{CancelDial, DialPeer, GetBandwidthUsage, NotifyListening};
         trace!(target: LOG_TARGET, "Connection manager got request: {:?}", request);
         match request {
             DialPeer { node_id, reply_tx } => {
@@ -407,6 +418,9 @@ where
                     self.listening_notifiers.push(reply);
                 },
             },
+            GetBandwidthUsage(reply) => {
+                let _result = reply.send(self.bandwidth_limiter.usage());
+            },
         }
     }
 
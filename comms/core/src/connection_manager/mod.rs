@@ -27,11 +27,16 @@
 //! - performing connection upgrades (noise protocol, identity and multiplexing),
 //! - and, notifying the connectivity manager of changes in connection state (new connections, disconnects, etc)
 
+mod bandwidth;
+pub use bandwidth::{BandwidthConfig, BandwidthLimit, BandwidthLimiter, BandwidthStats, BandwidthUsage};
+pub(crate) use bandwidth::{BandwidthDirection, StreamRateLimit};
+
 mod dial_state;
 mod dialer;
 mod listener;
 #[cfg(feature = "metrics")]
 mod metrics;
+mod secure_socket;
 
 mod common;
 
@@ -40,7 +40,12 @@ use tokio::{
 use tokio_stream::StreamExt;
 use tracing::{span, Instrument, Level};
 
-use super::{direction::ConnectionDirection, error::PeerConnectionError, manager::ConnectionManagerEvent};
+use super::{
+    bandwidth::BandwidthLimiter,
+    direction::ConnectionDirection,
+    error::PeerConnectionError,
+    manager::ConnectionManagerEvent,
+};
 #[cfg(feature = "rpc")]
 use crate::protocol::rpc::{
     pool::RpcClientPool,
@@ -54,7 +59,7 @@ use crate::protocol::rpc::{
 use crate::{
     framing,
     framing::CanonicalFraming,
-    multiplexing::{Control, IncomingSubstreams, Substream, Yamux, YamuxControlError},
+    multiplexing::{Control, IncomingSubstreams, MultiplexedConnection, Substream, YamuxControlError},
     peer_manager::{NodeId, PeerFeatures},
     protocol::{ProtocolId, ProtocolNegotiation},
     utils::atomic_ref_counter::AtomicRefCounter,
@@ -68,7 +73,7 @@ const PROTOCOL_NEGOTIATION_TIMEOUT: Duration = Duration::from_secs(10);
 static ID_COUNTER: AtomicUsize = AtomicUsize::new(0);
 
 pub fn create(
-    connection: Yamux,
+    connection: MultiplexedConnection,
     peer_addr: Multiaddr,
     peer_node_id: NodeId,
     peer_features: PeerFeatures,
@@ -76,6 +81,7 @@ pub fn create(
     event_notifier: mpsc::Sender<ConnectionManagerEvent>,
     our_supported_protocols: Arc<Vec<ProtocolId>>,
     their_supported_protocols: Vec<ProtocolId>,
+    bandwidth_limiter: BandwidthLimiter,
 ) -> PeerConnection {
     trace!(
         target: LOG_TARGET,
@@ -104,6 +110,7 @@ pub fn create(
         event_notifier,
         our_supported_protocols,
         their_supported_protocols,
+        bandwidth_limiter,
     );
     tokio::spawn(peer_actor.run());
 
@@ -333,6 +340,7 @@ struct PeerConnectionActor {
     inbound_protocol_negotiations:
         FuturesUnordered<BoxFuture<'static, Result<(ProtocolId, Substream), PeerConnectionError>>>,
     their_supported_protocols: Vec<ProtocolId>,
+    bandwidth_limiter: BandwidthLimiter,
 }
 
 impl PeerConnectionActor {
@@ -340,23 +348,25 @@ impl PeerConnectionActor {
         id: ConnectionId,
         peer_node_id: NodeId,
         direction: ConnectionDirection,
-        connection: Yamux,
+        connection: MultiplexedConnection,
         request_rx: mpsc::Receiver<PeerConnectionRequest>,
         event_notifier: mpsc::Sender<ConnectionManagerEvent>,
         our_supported_protocols: Arc<Vec<ProtocolId>>,
         their_supported_protocols: Vec<ProtocolId>,
+        bandwidth_limiter: BandwidthLimiter,
     ) -> Self {
         Self {
             id,
             peer_node_id,
             direction,
-            control: connection.get_yamux_control(),
+            control: connection.control(),
             incoming_substreams: connection.into_incoming(),
             request_rx,
             event_notifier,
             our_supported_protocols,
             inbound_protocol_negotiations: FuturesUnordered::new(),
             their_supported_protocols,
+            bandwidth_limiter,
         }
     }
 
@@ -448,7 +458,11 @@ impl PeerConnectionActor {
         result: Result<(ProtocolId, Substream), PeerConnectionError>,
     ) {
         match result {
-            Ok((selected_protocol, stream)) => {
+            Ok((selected_protocol, mut stream)) => {
+                stream.set_rate_limit(
+                    self.bandwidth_limiter
+                        .for_stream(&self.peer_node_id, &selected_protocol),
+                );
                 self.notify_event(ConnectionManagerEvent::NewInboundSubstream(
                     self.peer_node_id.clone(),
                     selected_protocol,
@@ -507,6 +521,10 @@ impl PeerConnectionActor {
             time::timeout(PROTOCOL_NEGOTIATION_TIMEOUT, fut).await??
         };
 
+        stream.set_rate_limit(
+            self.bandwidth_limiter
+                .for_stream(&self.peer_node_id, &selected_protocol),
+        );
         Ok(NegotiatedSubstream::new(selected_protocol, stream))
     }
 
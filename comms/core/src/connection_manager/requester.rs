@@ -24,7 +24,7 @@ use std::sync::Arc;
 
 use tokio::sync::{broadcast, mpsc, oneshot};
 
-use super::{error::ConnectionManagerError, peer_connection::PeerConnection};
+use super::{bandwidth::BandwidthUsage, error::ConnectionManagerError, peer_connection::PeerConnection};
 use crate::{
     connection_manager::manager::{ConnectionManagerEvent, ListenerInfo},
     peer_manager::NodeId,
@@ -42,6 +42,8 @@ pub enum ConnectionManagerRequest {
     CancelDial(NodeId),
     /// Register a oneshot to get triggered when the node is listening, or has failed to listen
     NotifyListening(oneshot::Sender<ListenerInfo>),
+    /// Returns the current bandwidth usage of peer connections
+    GetBandwidthUsage(oneshot::Sender<BandwidthUsage>),
 }
 
 /// Responsible for constructing requests to the ConnectionManagerService
@@ -117,4 +119,14 @@ impl ConnectionManagerRequester {
             .map_err(|_| ConnectionManagerError::SendToActorFailed)?;
         reply_rx.await.map_err(|_| ConnectionManagerError::ActorRequestCanceled)
     }
+
+    /// Return the current global, per-peer and per-protocol bandwidth usage
+    pub async fn get_bandwidth_usage(&mut self) -> Result<BandwidthUsage, ConnectionManagerError> {
+        let (reply_tx, reply_rx) = oneshot::channel();
+        self.sender
+            .send(ConnectionManagerRequest::GetBandwidthUsage(reply_tx))
+            .await
+            .map_err(|_| ConnectionManagerError::SendToActorFailed)?;
+        reply_rx.await.map_err(|_| ConnectionManagerError::ActorRequestCanceled)
+    }
 }
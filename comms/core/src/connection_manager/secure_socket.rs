@@ -0,0 +1,129 @@
+// Copyright 2024 The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+use std::{
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+use super::{direction::ConnectionDirection, error::ConnectionManagerError};
+#[cfg(feature = "quic")]
+use crate::{multiplexing::Quic, transports::QuicConnection};
+use crate::{
+    multiplexing::{MultiplexedConnection, Yamux},
+    noise::{NoiseConfig, NoiseSocket},
+    peer_manager::NodeIdentity,
+    transports::Transport,
+    types::CommsPublicKey,
+};
+
+/// A socket over which the peer has been authenticated and all traffic is encrypted. This is either a noise socket, or
+/// a QUIC connection which is authenticated and encrypted by the transport itself.
+pub(super) enum SecureSocket<TSocket> {
+    Noise(NoiseSocket<TSocket>),
+    #[cfg(feature = "quic")]
+    Quic {
+        connection: QuicConnection,
+        remote_public_key: CommsPublicKey,
+    },
+}
+
+impl<TSocket> SecureSocket<TSocket>
+where TSocket: AsyncRead + AsyncWrite + Send + Sync + Unpin + 'static
+{
+    /// Secure a socket returned by the given transport. QUIC connections are authenticated by binding the node
+    /// identities to the TLS certificates, all other sockets are upgraded to the noise protocol.
+    #[cfg_attr(not(feature = "quic"), allow(unused_variables))]
+    pub async fn upgrade<TTransport>(
+        socket: TSocket,
+        noise_config: &NoiseConfig,
+        node_identity: &NodeIdentity,
+        direction: ConnectionDirection,
+    ) -> Result<Self, ConnectionManagerError>
+    where
+        TTransport: Transport<Output = TSocket>,
+    {
+        #[cfg(feature = "quic")]
+        let socket = match TTransport::into_quic_connection(socket) {
+            Ok(mut connection) => {
+                let remote_public_key = connection
+                    .authenticate(node_identity)
+                    .await
+                    .map_err(|err| ConnectionManagerError::QuicHandshakeError(err.to_string()))?;
+                return Ok(SecureSocket::Quic {
+                    connection,
+                    remote_public_key,
+                });
+            },
+            Err(socket) => socket,
+        };
+        Ok(SecureSocket::Noise(
+            noise_config.upgrade_socket(socket, direction).await?,
+        ))
+    }
+
+    pub fn get_remote_public_key(&self) -> Option<CommsPublicKey> {
+        match self {
+            SecureSocket::Noise(socket) => socket.get_remote_public_key(),
+            #[cfg(feature = "quic")]
+            SecureSocket::Quic { remote_public_key, .. } => Some(remote_public_key.clone()),
+        }
+    }
+
+    /// Multiplex substreams over this socket, using yamux for noise sockets and native streams for QUIC
+    pub fn into_multiplexed(
+        self,
+        direction: ConnectionDirection,
+    ) -> Result<MultiplexedConnection, ConnectionManagerError> {
+        match self {
+            SecureSocket::Noise(socket) => Yamux::upgrade_connection(socket, direction)
+                .map(Into::into)
+                .map_err(|err| ConnectionManagerError::YamuxUpgradeFailure(err.to_string())),
+            #[cfg(feature = "quic")]
+            SecureSocket::Quic { connection, .. } => Ok(Quic::upgrade_connection(connection.into_connection())),
+        }
+    }
+}
+
+impl<TSocket> AsyncRead for SecureSocket<TSocket>
+where TSocket: AsyncRead + AsyncWrite + Unpin
+{
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            SecureSocket::Noise(socket) => Pin::new(socket).poll_read(cx, buf),
+            #[cfg(feature = "quic")]
+            SecureSocket::Quic { connection, .. } => Pin::new(connection).poll_read(cx, buf),
+        }
+    }
+}
+
+impl<TSocket> AsyncWrite for SecureSocket<TSocket>
+where TSocket: AsyncRead + AsyncWrite + Unpin
+{
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        match self.get_mut() {
+            SecureSocket::Noise(socket) => Pin::new(socket).poll_write(cx, buf),
+            #[cfg(feature = "quic")]
+            SecureSocket::Quic { connection, .. } => Pin::new(connection).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            SecureSocket::Noise(socket) => Pin::new(socket).poll_flush(cx),
+            #[cfg(feature = "quic")]
+            SecureSocket::Quic { connection, .. } => Pin::new(connection).poll_flush(cx),
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            SecureSocket::Noise(socket) => Pin::new(socket).poll_shutdown(cx),
+            #[cfg(feature = "quic")]
+            SecureSocket::Quic { connection, .. } => Pin::new(connection).poll_shutdown(cx),
+        }
+    }
+}
@@ -22,15 +22,17 @@
 
 use std::{error::Error, time::Duration};
 
-use multiaddr::Protocol;
+use multiaddr::{Multiaddr, Protocol};
 use tari_shutdown::Shutdown;
 use tari_test_utils::unpack_enum;
 use tokio::{
-    io::{AsyncReadExt, AsyncWriteExt},
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
     sync::{mpsc, oneshot},
     time::timeout,
 };
 
+#[cfg(feature = "quic")]
+use crate::transports::{QuicConfig, QuicTransport};
 use crate::{
     backoff::ConstantBackoff,
     connection_manager::{
@@ -45,10 +47,15 @@ use crate::{
     peer_manager::PeerFeatures,
     protocol::ProtocolId,
     test_utils::{build_peer_manager, node_identity::build_node_identity},
-    transports::MemoryTransport,
+    transports::{MemoryTransport, Transport},
     Minimized,
 };
 
+#[cfg(feature = "quic")]
+fn quic_test_transport() -> QuicTransport {
+    QuicTransport::new(QuicConfig::default()).unwrap()
+}
+
 #[tokio::test]
 async fn listen() -> Result<(), Box<dyn Error>> {
     let (event_tx, _) = mpsc::channel(1);
@@ -79,6 +86,25 @@ async fn listen() -> Result<(), Box<dyn Error>> {
 
 #[tokio::test]
 async fn smoke() {
+    smoke_test(MemoryTransport, MemoryTransport, "/memory/0".parse().unwrap()).await;
+}
+
+#[cfg(feature = "quic")]
+#[tokio::test]
+async fn smoke_quic() {
+    smoke_test(
+        quic_test_transport(),
+        quic_test_transport(),
+        "/ip4/127.0.0.1/udp/0/quic".parse().unwrap(),
+    )
+    .await;
+}
+
+async fn smoke_test<TTransport>(listener_transport: TTransport, dialer_transport: TTransport, listen_addr: Multiaddr)
+where
+    TTransport: Transport + Unpin + Send + Sync + Clone + 'static,
+    TTransport::Output: AsyncRead + AsyncWrite + Send + Sync + Unpin + 'static,
+{
     // This test sets up Dialer and Listener components, uses the Dialer to dial the Listener,
     // asserts the emitted events are correct, opens a substream, sends a small message over the substream,
     // receives and checks the message and then disconnects and shuts down.
@@ -92,8 +118,8 @@ async fn smoke() {
     let peer_manager1 = build_peer_manager();
     let mut listener = PeerListener::new(
         Default::default(),
-        "/memory/0".parse().unwrap(),
-        MemoryTransport,
+        listen_addr,
+        listener_transport,
         noise_config1,
         event_tx.clone(),
         peer_manager1.clone(),
@@ -113,7 +139,7 @@ async fn smoke() {
         ConnectionManagerConfig::default(),
         node_identity2.clone(),
         peer_manager2.clone(),
-        MemoryTransport,
+        dialer_transport,
         noise_config2,
         ConstantBackoff::new(Duration::from_millis(100)),
         request_rx,
@@ -185,6 +211,25 @@ async fn smoke() {
 
 #[tokio::test]
 async fn banned() {
+    banned_test(MemoryTransport, MemoryTransport, "/memory/0".parse().unwrap()).await;
+}
+
+#[cfg(feature = "quic")]
+#[tokio::test]
+async fn banned_quic() {
+    banned_test(
+        quic_test_transport(),
+        quic_test_transport(),
+        "/ip4/127.0.0.1/udp/0/quic".parse().unwrap(),
+    )
+    .await;
+}
+
+async fn banned_test<TTransport>(listener_transport: TTransport, dialer_transport: TTransport, listen_addr: Multiaddr)
+where
+    TTransport: Transport + Unpin + Send + Sync + Clone + 'static,
+    TTransport::Output: AsyncRead + AsyncWrite + Send + Sync + Unpin + 'static,
+{
     let (event_tx, mut event_rx) = mpsc::channel(10);
     let mut shutdown = Shutdown::new();
 
@@ -195,8 +240,8 @@ async fn banned() {
     let peer_manager1 = build_peer_manager();
     let mut listener = PeerListener::new(
         Default::default(),
-        "/memory/0".parse().unwrap(),
-        MemoryTransport,
+        listen_addr,
+        listener_transport,
         noise_config1,
         event_tx.clone(),
         peer_manager1.clone(),
@@ -221,7 +266,7 @@ async fn banned() {
         ConnectionManagerConfig::default(),
         node_identity2.clone(),
         peer_manager2.clone(),
-        MemoryTransport,
+        dialer_transport,
         noise_config2,
         ConstantBackoff::new(Duration::from_millis(100)),
         request_rx,
@@ -259,6 +304,28 @@ async fn banned() {
 
 #[tokio::test]
 async fn excluded_yes() {
+    excluded_yes_test(MemoryTransport, MemoryTransport, "/memory/0".parse().unwrap()).await;
+}
+
+#[cfg(feature = "quic")]
+#[tokio::test]
+async fn excluded_yes_quic() {
+    excluded_yes_test(
+        quic_test_transport(),
+        quic_test_transport(),
+        "/ip4/127.0.0.1/udp/0/quic".parse().unwrap(),
+    )
+    .await;
+}
+
+async fn excluded_yes_test<TTransport>(
+    listener_transport: TTransport,
+    dialer_transport: TTransport,
+    listen_addr: Multiaddr,
+) where
+    TTransport: Transport + Unpin + Send + Sync + Clone + 'static,
+    TTransport::Output: AsyncRead + AsyncWrite + Send + Sync + Unpin + 'static,
+{
     let (event_tx, _event_rx) = mpsc::channel(10);
     let mut shutdown = Shutdown::new();
 
@@ -269,8 +336,8 @@ async fn excluded_yes() {
     let peer_manager1 = build_peer_manager();
     let mut listener = PeerListener::new(
         Default::default(),
-        "/memory/0".parse().unwrap(),
-        MemoryTransport,
+        listen_addr,
+        listener_transport,
         noise_config1.clone(),
         event_tx.clone(),
         peer_manager1.clone(),
@@ -294,7 +361,7 @@ async fn excluded_yes() {
         connection_manager_config,
         node_identity2.clone(),
         peer_manager2.clone(),
-        MemoryTransport,
+        dialer_transport,
         noise_config2.clone(),
         ConstantBackoff::new(Duration::from_millis(100)),
         request_rx,
@@ -328,6 +395,28 @@ async fn excluded_yes() {
 
 #[tokio::test]
 async fn excluded_no() {
+    excluded_no_test(MemoryTransport, MemoryTransport, "/memory/0".parse().unwrap()).await;
+}
+
+#[cfg(feature = "quic")]
+#[tokio::test]
+async fn excluded_no_quic() {
+    excluded_no_test(
+        quic_test_transport(),
+        quic_test_transport(),
+        "/ip4/127.0.0.1/udp/0/quic".parse().unwrap(),
+    )
+    .await;
+}
+
+async fn excluded_no_test<TTransport>(
+    listener_transport: TTransport,
+    dialer_transport: TTransport,
+    listen_addr: Multiaddr,
+) where
+    TTransport: Transport + Unpin + Send + Sync + Clone + 'static,
+    TTransport::Output: AsyncRead + AsyncWrite + Send + Sync + Unpin + 'static,
+{
     let (event_tx, _event_rx) = mpsc::channel(10);
     let mut shutdown = Shutdown::new();
 
@@ -338,8 +427,8 @@ async fn excluded_no() {
     let peer_manager1 = build_peer_manager();
     let mut listener = PeerListener::new(
         Default::default(),
-        "/memory/0".parse().unwrap(),
-        MemoryTransport,
+        listen_addr,
+        listener_transport,
         noise_config1.clone(),
         event_tx.clone(),
         peer_manager1.clone(),
@@ -363,7 +452,7 @@ async fn excluded_no() {
         connection_manager_config,
         node_identity2.clone(),
         peer_manager2.clone(),
-        MemoryTransport,
+        dialer_transport,
         noise_config2.clone(),
         ConstantBackoff::new(Duration::from_millis(100)),
         request_rx,
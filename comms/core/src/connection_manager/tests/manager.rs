@@ -23,14 +23,17 @@
 use std::time::Duration;
 
 use futures::future;
+use multiaddr::Multiaddr;
 use tari_shutdown::Shutdown;
 use tari_test_utils::{collect_try_recv, unpack_enum};
 use tokio::{
-    io::{AsyncReadExt, AsyncWriteExt},
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
     runtime::Handle,
     sync::{broadcast, mpsc, oneshot},
 };
 
+#[cfg(feature = "quic")]
+use crate::transports::{QuicConfig, QuicTransport};
 use crate::{
     backoff::ConstantBackoff,
     connection_manager::{
@@ -48,7 +51,7 @@ use crate::{
         node_identity::{build_node_identity, ordered_node_identities},
         test_node::{build_connection_manager, TestNodeConfig},
     },
-    transports::{MemoryTransport, TcpTransport},
+    transports::{MemoryTransport, TcpTransport, Transport},
     PeerConnectionError,
 };
 
@@ -83,8 +86,26 @@ async fn connect_to_nonexistent_peer() {
 }
 
 #[tokio::test]
-#[allow(clippy::similar_names)]
 async fn dial_success() {
+    dial_success_test(MemoryTransport, "/memory/0".parse().unwrap()).await;
+}
+
+#[cfg(feature = "quic")]
+#[tokio::test]
+async fn dial_success_quic() {
+    dial_success_test(
+        QuicTransport::new(QuicConfig::default()).unwrap(),
+        "/ip4/127.0.0.1/udp/0/quic".parse().unwrap(),
+    )
+    .await;
+}
+
+#[allow(clippy::similar_names)]
+async fn dial_success_test<TTransport>(transport: TTransport, listen_addr: Multiaddr)
+where
+    TTransport: Transport + Unpin + Send + Sync + Clone + 'static,
+    TTransport::Output: AsyncRead + AsyncWrite + Send + Sync + Unpin + 'static,
+{
     static TEST_PROTO: ProtocolId = ProtocolId::from_static(b"/test/valid");
     let shutdown = Shutdown::new();
 
@@ -106,9 +127,10 @@ async fn dial_success() {
                 ..Default::default()
             };
             config.connection_manager_config.network_info.user_agent = "node1".to_string();
+            config.connection_manager_config.listener_address = listen_addr.clone();
             config
         },
-        MemoryTransport,
+        transport.clone(),
         peer_manager1.clone(),
         protocols,
         shutdown.to_signal(),
@@ -126,9 +148,10 @@ async fn dial_success() {
                 ..Default::default()
             };
             config.connection_manager_config.network_info.user_agent = "node2".to_string();
+            config.connection_manager_config.listener_address = listen_addr.clone();
             config
         },
-        MemoryTransport,
+        transport.clone(),
         peer_manager2.clone(),
         protocols,
         shutdown.to_signal(),
@@ -292,6 +315,24 @@ async fn dial_success_aux_tcp_listener() {
 
 #[tokio::test]
 async fn simultaneous_dial_events() {
+    simultaneous_dial_events_test(MemoryTransport, "/memory/0".parse().unwrap()).await;
+}
+
+#[cfg(feature = "quic")]
+#[tokio::test]
+async fn simultaneous_dial_events_quic() {
+    simultaneous_dial_events_test(
+        QuicTransport::new(QuicConfig::default()).unwrap(),
+        "/ip4/127.0.0.1/udp/0/quic".parse().unwrap(),
+    )
+    .await;
+}
+
+async fn simultaneous_dial_events_test<TTransport>(transport: TTransport, listen_addr: Multiaddr)
+where
+    TTransport: Transport + Unpin + Send + Sync + Clone + 'static,
+    TTransport::Output: AsyncRead + AsyncWrite + Send + Sync + Unpin + 'static,
+{
     let mut shutdown = Shutdown::new();
 
     let node_identities = ordered_node_identities(2, Default::default());
@@ -299,11 +340,15 @@ async fn simultaneous_dial_events() {
     // Setup connection manager 1
     let peer_manager1 = build_peer_manager();
     let mut conn_man1 = build_connection_manager(
-        TestNodeConfig {
-            node_identity: node_identities[0].clone(),
-            ..Default::default()
+        {
+            let mut config = TestNodeConfig {
+                node_identity: node_identities[0].clone(),
+                ..Default::default()
+            };
+            config.connection_manager_config.listener_address = listen_addr.clone();
+            config
         },
-        MemoryTransport,
+        transport.clone(),
         peer_manager1.clone(),
         Protocols::new(),
         shutdown.to_signal(),
@@ -315,11 +360,15 @@ async fn simultaneous_dial_events() {
 
     let peer_manager2 = build_peer_manager();
     let mut conn_man2 = build_connection_manager(
-        TestNodeConfig {
-            node_identity: node_identities[1].clone(),
-            ..Default::default()
+        {
+            let mut config = TestNodeConfig {
+                node_identity: node_identities[1].clone(),
+                ..Default::default()
+            };
+            config.connection_manager_config.listener_address = listen_addr.clone();
+            config
         },
-        MemoryTransport,
+        transport.clone(),
         peer_manager2.clone(),
         Protocols::new(),
         shutdown.to_signal(),
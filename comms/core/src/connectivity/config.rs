@@ -22,6 +22,8 @@
 
 use std::time::Duration;
 
+use crate::peer_manager::ReputationConfig;
+
 /// Connectivity actor configuration
 #[derive(Debug, Clone, Copy)]
 pub struct ConnectivityConfig {
@@ -52,6 +54,8 @@ pub struct ConnectivityConfig {
     /// The closest number of peer connections to maintain; connections above the threshold will be removed
     /// (default: disabled)
     pub maintain_n_closest_connections_only: Option<usize>,
+    /// Thresholds used to ban peers based on their reputation score
+    pub reputation: ReputationConfig,
 }
 
 impl Default for ConnectivityConfig {
@@ -66,6 +70,7 @@ impl Default for ConnectivityConfig {
             connection_tie_break_linger: Duration::from_secs(2),
             expire_peer_last_seen_duration: Duration::from_secs(24 * 60 * 60),
             maintain_n_closest_connections_only: None,
+            reputation: ReputationConfig::default(),
         }
     }
 }
@@ -53,7 +53,7 @@ use crate::{
         ConnectionManagerEvent,
         ConnectionManagerRequester,
     },
-    peer_manager::NodeId,
+    peer_manager::{NodeId, PeerManagerError, PeerReputation, ReputationEvent},
     utils::datetime::format_duration,
     Minimized,
     NodeIdentity,
@@ -96,6 +96,7 @@ impl ConnectivityManager {
             #[cfg(feature = "metrics")]
             uptime: Some(Instant::now()),
             allow_list: vec![],
+            reputations: HashMap::new(),
         }
         .spawn()
     }
@@ -153,6 +154,8 @@ struct ConnectivityManagerActor {
     #[cfg(feature = "metrics")]
     uptime: Option<Instant>,
     allow_list: Vec<NodeId>,
+    /// Reputations of connected peers, used to prefer reputable peers when selecting connections
+    reputations: HashMap<NodeId, PeerReputation>,
 }
 
 impl ConnectivityManagerActor {
@@ -270,6 +273,11 @@ impl ConnectivityManagerActor {
                     // we banned the peer
                 }
             },
+            ReportPeer(node_id, event, reason) => {
+                if let Err(err) = self.report_peer(&node_id, event, reason).await {
+                    error!(target: LOG_TARGET, "Error when reporting peer: {:?}", err);
+                }
+            },
             AddPeerToAllowList(node_id) => {
                 if !self.allow_list.contains(&node_id) {
                     self.allow_list.push(node_id.clone());
@@ -529,7 +537,7 @@ impl ConnectivityManagerActor {
             self.pool.count_connected_nodes()
         );
 
-        let conns = selection.select(&self.pool);
+        let conns = selection.select(&self.pool, &self.reputations);
         debug!(target: LOG_TARGET, "Selected {} connections(s)", conns.len());
 
         Ok(conns.into_iter().cloned().collect())
@@ -645,9 +653,9 @@ impl ConnectivityManagerActor {
                 }
             },
             PeerViolation { peer_node_id, details } => {
-                self.ban_peer(
+                self.report_peer(
                     peer_node_id,
-                    Duration::from_secs(2 * 60 * 60),
+                    ReputationEvent::MaliciousBehaviour,
                     format!("Peer violation: {details}"),
                 )
                 .await?;
@@ -727,6 +735,7 @@ impl ConnectivityManagerActor {
             (_, Connected) => match self.pool.get_connection_mut(&node_id).cloned() {
                 Some(conn) => {
                     self.mark_connection_success(conn.peer_node_id().clone());
+                    self.load_peer_reputation(&node_id).await;
                     self.publish_event(ConnectivityEvent::PeerConnected(conn.into()));
                 },
                 None => unreachable!(
@@ -735,6 +744,7 @@ impl ConnectivityManagerActor {
                 ),
             },
             (Connected, Disconnected(..)) => {
+                self.reputations.remove(&node_id);
                 self.publish_event(ConnectivityEvent::PeerDisconnected(node_id, match new_status {
                     ConnectionStatus::Disconnected(reason) => reason,
                     _ => Minimized::No,
@@ -1005,6 +1015,65 @@ impl ConnectivityManagerActor {
         Ok(())
     }
 
+    async fn report_peer(
+        &mut self,
+        node_id: &NodeId,
+        event: ReputationEvent,
+        reason: String,
+    ) -> Result<(), ConnectivityError> {
+        let reputation = match self.peer_manager.adjust_reputation(node_id, event).await {
+            Ok(reputation) => reputation,
+            Err(PeerManagerError::PeerNotFoundError) => {
+                debug!(
+                    target: LOG_TARGET,
+                    "Ignoring {} report for unknown peer {} ({})", event, node_id, reason
+                );
+                return Ok(());
+            },
+            Err(err) => return Err(err.into()),
+        };
+        debug!(
+            target: LOG_TARGET,
+            "Peer {} reputation adjusted to {} ({}: {})",
+            node_id,
+            reputation.score(),
+            event,
+            reason
+        );
+        if let Some(existing) = self.reputations.get_mut(node_id) {
+            *existing = reputation;
+        }
+
+        let Some(duration) = reputation.ban_duration(&self.config.reputation) else {
+            return Ok(());
+        };
+        if self.allow_list.contains(node_id) {
+            info!(
+                target: LOG_TARGET,
+                "Peer is excluded from being banned as it was found in the AllowList, NodeId: {:?}", node_id
+            );
+            return Ok(());
+        }
+        self.ban_peer(
+            node_id,
+            duration,
+            format!("Reputation score {} ({}: {})", reputation.score(), event, reason),
+        )
+        .await
+    }
+
+    async fn load_peer_reputation(&mut self, node_id: &NodeId) {
+        match self.peer_manager.find_by_node_id(node_id).await {
+            Ok(Some(peer)) => {
+                self.reputations.insert(node_id.clone(), peer.reputation);
+            },
+            Ok(None) => {},
+            Err(err) => {
+                error!(target: LOG_TARGET, "Error when retrieving peer reputation: {:?}", err);
+            },
+        }
+    }
+
     fn cleanup_connection_stats(&mut self) {
         let mut to_remove = Vec::new();
         for node_id in self.connection_stats.keys() {
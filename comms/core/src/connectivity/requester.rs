@@ -40,7 +40,7 @@ use super::{
 };
 use crate::{
     connection_manager::ConnectionManagerError,
-    peer_manager::{NodeId, Peer},
+    peer_manager::{NodeId, Peer, ReputationEvent},
     Minimized,
     NodeIdentity,
     PeerConnection,
@@ -101,6 +101,7 @@ pub enum ConnectivityRequest {
     GetMinimizeConnectionsThreshold(oneshot::Sender<Option<usize>>),
     GetActiveConnections(oneshot::Sender<Vec<PeerConnection>>),
     BanPeer(NodeId, Duration, String),
+    ReportPeer(NodeId, ReputationEvent, String),
     AddPeerToAllowList(NodeId),
     RemovePeerFromAllowList(NodeId),
     GetAllowList(oneshot::Sender<Vec<NodeId>>),
@@ -272,6 +273,21 @@ impl ConnectivityRequester {
         Ok(())
     }
 
+    /// Report peer behaviour that adjusts the peer's reputation score. The peer is banned if its score falls to or
+    /// below the configured ban threshold.
+    pub async fn report_peer<T: Into<String>>(
+        &mut self,
+        node_id: NodeId,
+        event: ReputationEvent,
+        reason: T,
+    ) -> Result<(), ConnectivityError> {
+        self.sender
+            .send(ConnectivityRequest::ReportPeer(node_id, event, reason.into()))
+            .await
+            .map_err(|_| ConnectivityError::ActorDisconnected)?;
+        Ok(())
+    }
+
     /// Ban the peer indefinitely.
     pub async fn ban_peer(&mut self, node_id: NodeId, reason: String) -> Result<(), ConnectivityError> {
         self.ban_peer_until(node_id, Duration::from_secs(u64::MAX), reason)
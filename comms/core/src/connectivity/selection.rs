@@ -20,12 +20,16 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::{fmt, fmt::Display};
+use std::{cmp, collections::HashMap, fmt, fmt::Display};
 
 use rand::{rngs::OsRng, seq::SliceRandom};
 
 use super::connection_pool::ConnectionPool;
-use crate::{connectivity::connection_pool::ConnectionStatus, peer_manager::NodeId, PeerConnection};
+use crate::{
+    connectivity::connection_pool::ConnectionStatus,
+    peer_manager::{NodeId, PeerReputation},
+    PeerConnection,
+};
 
 /// Selection query for PeerConnections.
 ///
@@ -60,7 +64,8 @@ impl ConnectivitySelection {
     }
 
     /// Returns a query that will return `n` connections for peers with `PeerFeatures::COMMUNICATION_NODES` excluding
-    /// the given [NodeId]s.
+    /// the given [NodeId]s. Peers with a better reputation are preferred, peers with a similar reputation are chosen
+    /// at random.
     ///
     /// [NodeId](crate::peer_manager::NodeId)
     pub fn random_nodes(n: usize, exclude: Vec<NodeId>) -> Self {
@@ -81,11 +86,15 @@ impl ConnectivitySelection {
     }
 
     /// Select peers from the pool according to the ConnectivitySelection
-    pub fn select<'a>(&self, pool: &'a ConnectionPool) -> Vec<&'a PeerConnection> {
+    pub fn select<'a>(
+        &self,
+        pool: &'a ConnectionPool,
+        reputations: &HashMap<NodeId, PeerReputation>,
+    ) -> Vec<&'a PeerConnection> {
         use SelectionMode::{AllNodes, ClosestTo, RandomNodes};
         match &self.selection_mode {
             AllNodes => select_connected_nodes(pool, &self.excluded_peers),
-            RandomNodes(n) => select_random_nodes(pool, *n, &self.excluded_peers),
+            RandomNodes(n) => select_random_nodes(pool, *n, &self.excluded_peers, reputations),
             ClosestTo(dest_node_id, n) => {
                 let mut connections = select_closest(pool, dest_node_id, &self.excluded_peers);
                 connections.truncate(*n);
@@ -119,9 +128,25 @@ fn select_closest<'a>(pool: &'a ConnectionPool, node_id: &NodeId, exclude: &[Nod
     nodes
 }
 
-fn select_random_nodes<'a>(pool: &'a ConnectionPool, n: usize, exclude: &[NodeId]) -> Vec<&'a PeerConnection> {
-    let nodes = select_connected_nodes(pool, exclude);
-    nodes.choose_multiple(&mut OsRng, n).copied().collect()
+fn select_random_nodes<'a>(
+    pool: &'a ConnectionPool,
+    n: usize,
+    exclude: &[NodeId],
+    reputations: &HashMap<NodeId, PeerReputation>,
+) -> Vec<&'a PeerConnection> {
+    let mut nodes = select_connected_nodes(pool, exclude);
+    nodes.shuffle(&mut OsRng);
+    // The sort is stable, so peers within the same reputation tier remain in random order
+    nodes.sort_by_cached_key(|conn| {
+        cmp::Reverse(
+            reputations
+                .get(conn.peer_node_id())
+                .map(PeerReputation::tier)
+                .unwrap_or_default(),
+        )
+    });
+    nodes.truncate(n);
+    nodes
 }
 
 impl Display for ConnectivitySelection {
@@ -155,7 +180,7 @@ mod test {
     use super::*;
     use crate::{
         connection_manager::PeerConnectionRequest,
-        peer_manager::NodeDistance,
+        peer_manager::{NodeDistance, ReputationEvent},
         test_utils::{mocks::create_dummy_peer_connection, node_id, node_identity::build_node_identity},
     };
 
@@ -175,15 +200,39 @@ mod test {
     #[test]
     fn select_random() {
         let (pool, _receivers) = create_pool_with_connections(10);
-        let conns = select_random_nodes(&pool, 500, &[]);
+        let conns = select_random_nodes(&pool, 500, &[], &HashMap::new());
         assert_eq!(conns.len(), 10);
 
         let first_node = conns.first().unwrap().peer_node_id().clone();
-        let conns = select_random_nodes(&pool, 10, &[first_node.clone()]);
+        let conns = select_random_nodes(&pool, 10, &[first_node.clone()], &HashMap::new());
         assert_eq!(conns.len(), 9);
         assert!(conns.iter().all(|c| c.peer_node_id() != &first_node));
     }
 
+    #[test]
+    fn select_random_prefers_reputable_peers() {
+        let (pool, _receivers) = create_pool_with_connections(10);
+        let conns = select_connected_nodes(&pool, &[]);
+        let reputable = conns[0].peer_node_id().clone();
+        let disreputable = conns[1].peer_node_id().clone();
+
+        let mut reputations = HashMap::new();
+        let mut reputation = PeerReputation::new();
+        (0..20).for_each(|_| {
+            reputation.apply(ReputationEvent::GoodBehaviour);
+        });
+        reputations.insert(reputable.clone(), reputation);
+        let mut reputation = PeerReputation::new();
+        reputation.apply(ReputationEvent::ProtocolViolation);
+        reputations.insert(disreputable.clone(), reputation);
+
+        for _ in 0..10 {
+            let conns = select_random_nodes(&pool, 10, &[], &reputations);
+            assert_eq!(conns.first().unwrap().peer_node_id(), &reputable);
+            assert_eq!(conns.last().unwrap().peer_node_id(), &disreputable);
+        }
+    }
+
     #[test]
     fn select_closest_ordering() {
         let (pool, _receivers) = create_pool_with_connections(10);
@@ -37,7 +37,7 @@ use super::{
 use crate::{
     connection_manager::{ConnectionManagerError, ConnectionManagerEvent},
     connectivity::ConnectivityEventRx,
-    peer_manager::{Peer, PeerFeatures},
+    peer_manager::{Peer, PeerFeatures, ReputationEvent},
     test_utils::{
         build_peer_manager,
         mocks::{create_connection_manager_mock, create_peer_connection_mock_pair, ConnectionManagerMockState},
@@ -313,6 +313,35 @@ async fn ban_peer() {
     assert!(conn.is_none());
 }
 
+#[tokio::test]
+async fn report_peer_bans_below_threshold() {
+    let (mut connectivity, mut event_stream, _node_identity, peer_manager, _cm_mock_state, _shutdown) =
+        setup_connectivity_manager(Default::default());
+    let peer = add_test_peers(&peer_manager, 1).await.pop().unwrap();
+
+    let mut events = collect_try_recv!(event_stream, take = 1, timeout = Duration::from_secs(10));
+    unpack_enum!(ConnectivityEvent::ConnectivityStateInitialized = events.remove(0));
+
+    connectivity
+        .report_peer(peer.node_id.clone(), ReputationEvent::SlowResponse, "slow")
+        .await
+        .unwrap();
+    connectivity
+        .report_peer(peer.node_id.clone(), ReputationEvent::InvalidBlock, "invalid block")
+        .await
+        .unwrap();
+
+    let event = collect_try_recv!(event_stream, take = 1, timeout = Duration::from_secs(10))
+        .pop()
+        .unwrap();
+    unpack_enum!(ConnectivityEvent::PeerBanned(node_id) = event);
+    assert_eq!(node_id, peer.node_id);
+
+    let peer = peer_manager.find_by_node_id(&peer.node_id).await.unwrap().unwrap();
+    assert!(peer.is_banned());
+    assert_eq!(peer.reputation_score(), -210);
+}
+
 #[tokio::test]
 async fn peer_selection() {
     let config = ConnectivityConfig {
@@ -47,6 +47,18 @@ impl From<ConnectionError> for YamuxControlError {
     }
 }
 
+#[cfg(feature = "quic")]
+impl From<quinn::ConnectionError> for YamuxControlError {
+    fn from(err: quinn::ConnectionError) -> Self {
+        match err {
+            quinn::ConnectionError::ApplicationClosed(_) | quinn::ConnectionError::LocallyClosed => {
+                Self::ConnectionClosed
+            },
+            _ => Self::ConnectionError(err.to_string()),
+        }
+    }
+}
+
 impl<T> From<SendError<T>> for YamuxControlError {
     fn from(err: SendError<T>) -> Self {
         Self::RequestSendError(err.to_string())
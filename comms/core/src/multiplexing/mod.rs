@@ -20,14 +20,21 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-//! Stream multiplexers typically used to allow multiplexed substreams over an ordered reliable byte stream.
+//! Stream multiplexers typically used to allow multiplexed substreams over an ordered reliable byte stream, or the
+//! native streams of a QUIC connection.
 
 #[cfg(feature = "metrics")]
 mod metrics;
 
 mod error;
+#[cfg(feature = "quic")]
+mod quic;
+mod substream;
 mod yamux;
+#[cfg(feature = "quic")]
+pub use self::quic::{Quic, QuicStream};
 pub use self::{
     error::YamuxControlError,
-    yamux::{Control, IncomingSubstreams, Substream, Yamux},
+    substream::{Control, IncomingSubstreams, MultiplexedConnection, Substream},
+    yamux::Yamux,
 };
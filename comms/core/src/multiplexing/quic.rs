@@ -0,0 +1,179 @@
+// Copyright 2024 The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+use std::{
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use quinn::{ConnectionError, RecvStream, SendStream, VarInt};
+use tokio::{
+    io::{AsyncRead, AsyncWrite, ReadBuf},
+    sync::mpsc,
+};
+use tracing::{debug, error, warn};
+
+use super::substream::{Control, IncomingSubstreams, MultiplexedConnection, MuxedStream, MuxerRequest, Substream};
+use crate::{stream_id, utils::atomic_ref_counter::AtomicRefCounter};
+
+const LOG_TARGET: &str = "comms::multiplexing::quic";
+
+/// Uses the native bidirectional streams of a QUIC connection as substreams. Unlike yamux substreams, QUIC streams are
+/// flow controlled and retransmitted independently, so a stalled substream does not hold up the others.
+pub struct Quic;
+
+impl Quic {
+    /// Multiplex substreams over the given (authenticated) QUIC connection
+    pub fn upgrade_connection(connection: quinn::Connection) -> MultiplexedConnection {
+        let substream_counter = AtomicRefCounter::new();
+        let (incoming_tx, incoming_rx) = mpsc::channel(10);
+        let (request_tx, request_rx) = mpsc::channel(1);
+        let worker = QuicWorker {
+            connection,
+            incoming_substreams: incoming_tx,
+            request_rx,
+            counter: substream_counter.clone(),
+        };
+        tokio::spawn(worker.run());
+        MultiplexedConnection::new(
+            Control::new(request_tx),
+            IncomingSubstreams::new(incoming_rx, substream_counter.clone()),
+            substream_counter,
+        )
+    }
+}
+
+/// A bidirectional QUIC stream that can be read from and written to.
+#[derive(Debug)]
+pub struct QuicStream {
+    send: SendStream,
+    recv: RecvStream,
+}
+
+impl QuicStream {
+    pub(crate) fn new(send: SendStream, recv: RecvStream) -> Self {
+        Self { send, recv }
+    }
+
+    pub fn id(&self) -> quinn::StreamId {
+        self.send.id()
+    }
+}
+
+impl AsyncRead for QuicStream {
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.recv).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for QuicStream {
+    fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.send).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.send).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.send).poll_shutdown(cx)
+    }
+}
+
+impl From<quinn::StreamId> for stream_id::Id {
+    fn from(id: quinn::StreamId) -> Self {
+        // QUIC stream IDs are 62-bit, a connection will never open anywhere near 2^32 streams
+        stream_id::Id::new(u32::try_from(VarInt::from(id).into_inner()).unwrap_or(u32::MAX))
+    }
+}
+
+struct QuicWorker {
+    connection: quinn::Connection,
+    incoming_substreams: mpsc::Sender<MuxedStream>,
+    request_rx: mpsc::Receiver<MuxerRequest>,
+    counter: AtomicRefCounter,
+}
+
+impl QuicWorker {
+    async fn run(mut self) {
+        loop {
+            tokio::select! {
+                biased;
+
+                _ = self.incoming_substreams.closed() => {
+                    debug!(
+                        target: LOG_TARGET,
+                        "{} Incoming peer substream task is stopping because the internal stream sender channel was \
+                         closed",
+                        self.counter.get()
+                    );
+                    self.close();
+                    break
+                },
+
+                Some(request) = self.request_rx.recv() => self.handle_request(request).await,
+
+                result = self.connection.accept_bi() => {
+                    match result {
+                        Ok((send, recv)) => {
+                            let stream = MuxedStream::Quic(QuicStream::new(send, recv));
+                            if self.incoming_substreams.send(stream).await.is_err() {
+                                debug!(
+                                    target: LOG_TARGET,
+                                    "{} Incoming peer substream task is stopping because the internal stream sender \
+                                     channel was closed",
+                                    self.counter.get()
+                                );
+                                break;
+                            }
+                        },
+                        Err(ConnectionError::ApplicationClosed(_) | ConnectionError::LocallyClosed) => {
+                            debug!(target: LOG_TARGET, "{} QUIC connection closed.", self.counter.get());
+                            break;
+                        },
+                        Err(err) => {
+                            error!(
+                                target: LOG_TARGET,
+                                "{} Incoming peer substream task received an error because '{}'",
+                                self.counter.get(),
+                                err
+                            );
+                            break;
+                        },
+                    }
+                }
+            }
+        }
+    }
+
+    async fn handle_request(&self, request: MuxerRequest) {
+        match request {
+            MuxerRequest::OpenStream { reply } => {
+                let result = self
+                    .connection
+                    .open_bi()
+                    .await
+                    .map(|(send, recv)| {
+                        Substream::new(MuxedStream::Quic(QuicStream::new(send, recv)), self.counter.new_guard())
+                    })
+                    .map_err(Into::into);
+                if reply.send(result).is_err() {
+                    warn!(target: LOG_TARGET, "Request to open substream was aborted before reply was sent");
+                }
+            },
+            MuxerRequest::Close { reply } => {
+                self.close();
+                if reply.send(Ok(())).is_err() {
+                    warn!(target: LOG_TARGET, "Request to close substream was aborted before reply was sent");
+                }
+            },
+        }
+    }
+
+    fn close(&self) {
+        // Closing a QUIC connection is immediate: any stream data the peer has not yet acknowledged is abandoned
+        self.connection.close(VarInt::from_u32(0), b"closed");
+        debug!(target: LOG_TARGET, "QUIC connection has closed");
+    }
+}
@@ -0,0 +1,246 @@
+// Copyright 2024 The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+use std::{io, pin::Pin, task::Poll};
+
+use futures::{channel::oneshot, task::Context, Stream};
+use tokio::{
+    io::{AsyncRead, AsyncWrite, ReadBuf},
+    sync::mpsc,
+};
+use tokio_util::compat::{Compat, FuturesAsyncReadCompatExt};
+
+#[cfg(feature = "quic")]
+use super::quic::QuicStream;
+use super::YamuxControlError;
+use crate::{
+    connection_manager::{BandwidthDirection, StreamRateLimit},
+    stream_id,
+    stream_id::StreamId,
+    utils::atomic_ref_counter::{AtomicRefCounter, AtomicRefCounterGuard},
+};
+
+/// A connection multiplexed by either yamux or QUIC, from which substreams are opened and accepted
+pub struct MultiplexedConnection {
+    control: Control,
+    incoming: IncomingSubstreams,
+    substream_counter: AtomicRefCounter,
+}
+
+impl MultiplexedConnection {
+    pub(super) fn new(control: Control, incoming: IncomingSubstreams, substream_counter: AtomicRefCounter) -> Self {
+        Self {
+            control,
+            incoming,
+            substream_counter,
+        }
+    }
+
+    /// Get the control struct used to open substreams and close the connection
+    pub fn control(&self) -> Control {
+        self.control.clone()
+    }
+
+    /// Consumes this object and returns a `Stream` that emits substreams initiated by the remote
+    pub fn into_incoming(self) -> IncomingSubstreams {
+        self.incoming
+    }
+
+    /// Return the number of active substreams
+    pub fn substream_count(&self) -> usize {
+        self.substream_counter.get()
+    }
+
+    /// Return a SubstreamCounter for this connection
+    pub(crate) fn substream_counter(&self) -> AtomicRefCounter {
+        self.substream_counter.clone()
+    }
+}
+
+/// Requests made through [Control](self::Control) to the worker that drives a multiplexed connection
+#[derive(Debug)]
+pub enum MuxerRequest {
+    OpenStream {
+        reply: oneshot::Sender<Result<Substream, YamuxControlError>>,
+    },
+    Close {
+        reply: oneshot::Sender<Result<(), YamuxControlError>>,
+    },
+}
+
+#[derive(Clone)]
+pub struct Control {
+    request_tx: mpsc::Sender<MuxerRequest>,
+}
+
+impl Control {
+    pub fn new(request_tx: mpsc::Sender<MuxerRequest>) -> Self {
+        Self { request_tx }
+    }
+
+    /// Open a new stream to the remote.
+    pub async fn open_stream(&mut self) -> Result<Substream, YamuxControlError> {
+        let (reply, reply_rx) = oneshot::channel();
+        self.request_tx.send(MuxerRequest::OpenStream { reply }).await?;
+        let stream = reply_rx.await??;
+        Ok(stream)
+    }
+
+    /// Close the connection.
+    pub async fn close(&mut self) -> Result<(), YamuxControlError> {
+        let (reply, reply_rx) = oneshot::channel();
+        self.request_tx.send(MuxerRequest::Close { reply }).await?;
+        reply_rx.await?
+    }
+}
+
+/// A stream accepted or opened by one of the multiplexers, before it is counted as an active substream
+#[derive(Debug)]
+pub(super) enum MuxedStream {
+    Yamux(yamux::Stream),
+    #[cfg(feature = "quic")]
+    Quic(QuicStream),
+}
+
+pub struct IncomingSubstreams {
+    inner: mpsc::Receiver<MuxedStream>,
+    substream_counter: AtomicRefCounter,
+}
+
+impl IncomingSubstreams {
+    pub(super) fn new(inner: mpsc::Receiver<MuxedStream>, substream_counter: AtomicRefCounter) -> Self {
+        Self {
+            inner,
+            substream_counter,
+        }
+    }
+
+    pub fn substream_count(&self) -> usize {
+        self.substream_counter.get()
+    }
+}
+
+impl Stream for IncomingSubstreams {
+    type Item = Substream;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        match futures::ready!(Pin::new(&mut self.inner).poll_recv(cx)) {
+            Some(stream) => Poll::Ready(Some(Substream::new(stream, self.substream_counter.new_guard()))),
+            None => Poll::Ready(None),
+        }
+    }
+}
+
+/// A multiplexed stream wrapper that can be read from and written to.
+#[derive(Debug)]
+pub struct Substream {
+    stream: SubstreamStream,
+    rate_limit: Option<StreamRateLimit>,
+    _counter_guard: AtomicRefCounterGuard,
+}
+
+#[derive(Debug)]
+enum SubstreamStream {
+    Yamux(Compat<yamux::Stream>),
+    #[cfg(feature = "quic")]
+    Quic(QuicStream),
+}
+
+impl Substream {
+    pub(super) fn new(stream: MuxedStream, counter_guard: AtomicRefCounterGuard) -> Self {
+        let stream = match stream {
+            MuxedStream::Yamux(stream) => SubstreamStream::Yamux(stream.compat()),
+            #[cfg(feature = "quic")]
+            MuxedStream::Quic(stream) => SubstreamStream::Quic(stream),
+        };
+        Self {
+            stream,
+            rate_limit: None,
+            _counter_guard: counter_guard,
+        }
+    }
+
+    /// Apply bandwidth limits to this substream. All subsequent reads and writes are metered and throttled.
+    pub(crate) fn set_rate_limit(&mut self, rate_limit: StreamRateLimit) {
+        self.rate_limit = Some(rate_limit);
+    }
+}
+
+impl StreamId for Substream {
+    fn stream_id(&self) -> stream_id::Id {
+        match &self.stream {
+            SubstreamStream::Yamux(stream) => stream.get_ref().id().into(),
+            #[cfg(feature = "quic")]
+            SubstreamStream::Quic(stream) => stream.id().into(),
+        }
+    }
+}
+
+impl AsyncRead for Substream {
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        let this = &mut *self;
+        // Reads are not capped to the available budget. A read that exceeds the budget puts it into debt, which delays
+        // subsequent reads until the debt has been repaid.
+        if let Some(rate_limit) = this.rate_limit.as_mut() {
+            futures::ready!(rate_limit.poll_acquire(BandwidthDirection::Download, cx));
+        }
+        let filled_before = buf.filled().len();
+        let result = match &mut this.stream {
+            SubstreamStream::Yamux(stream) => Pin::new(stream).poll_read(cx, buf),
+            #[cfg(feature = "quic")]
+            SubstreamStream::Quic(stream) => Pin::new(stream).poll_read(cx, buf),
+        };
+        match result {
+            Poll::Ready(Ok(())) => {
+                let num_read = buf.filled().len() - filled_before;
+                #[cfg(feature = "metrics")]
+                super::metrics::TOTAL_BYTES_READ.inc_by(num_read as u64);
+                if let Some(rate_limit) = this.rate_limit.as_ref() {
+                    rate_limit.record(BandwidthDirection::Download, num_read);
+                }
+                Poll::Ready(Ok(()))
+            },
+            res => res,
+        }
+    }
+}
+
+impl AsyncWrite for Substream {
+    fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        let this = &mut *self;
+        let buf = match this.rate_limit.as_mut() {
+            Some(rate_limit) => {
+                let allowed = futures::ready!(rate_limit.poll_acquire(BandwidthDirection::Upload, cx));
+                &buf[..buf.len().min(allowed)]
+            },
+            None => buf,
+        };
+        #[cfg(feature = "metrics")]
+        super::metrics::TOTAL_BYTES_WRITTEN.inc_by(buf.len() as u64);
+        let result = match &mut this.stream {
+            SubstreamStream::Yamux(stream) => Pin::new(stream).poll_write(cx, buf),
+            #[cfg(feature = "quic")]
+            SubstreamStream::Quic(stream) => Pin::new(stream).poll_write(cx, buf),
+        };
+        if let (Poll::Ready(Ok(num_written)), Some(rate_limit)) = (&result, this.rate_limit.as_ref()) {
+            rate_limit.record(BandwidthDirection::Upload, *num_written);
+        }
+        result
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match &mut self.stream {
+            SubstreamStream::Yamux(stream) => Pin::new(stream).poll_flush(cx),
+            #[cfg(feature = "quic")]
+            SubstreamStream::Quic(stream) => Pin::new(stream).poll_flush(cx),
+        }
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match &mut self.stream {
+            SubstreamStream::Yamux(stream) => Pin::new(stream).poll_shutdown(cx),
+            #[cfg(feature = "quic")]
+            SubstreamStream::Quic(stream) => Pin::new(stream).poll_shutdown(cx),
+        }
+    }
+}
@@ -20,25 +20,19 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::{future::poll_fn, io, marker::PhantomData, pin::Pin, task::Poll};
+use std::{future::poll_fn, io, marker::PhantomData};
 
-use futures::{channel::oneshot, task::Context, Stream};
 use tokio::{
-    io::{AsyncRead, AsyncWrite, ReadBuf},
+    io::{AsyncRead, AsyncWrite},
     sync::mpsc,
 };
-use tokio_util::compat::{Compat, FuturesAsyncReadCompatExt, TokioAsyncReadCompatExt};
+use tokio_util::compat::TokioAsyncReadCompatExt;
 use tracing::{debug, error, warn};
 // Reexport
 use yamux::Mode;
 
-use crate::{
-    connection_manager::ConnectionDirection,
-    multiplexing::YamuxControlError,
-    stream_id,
-    stream_id::StreamId,
-    utils::atomic_ref_counter::{AtomicRefCounter, AtomicRefCounterGuard},
-};
+use super::substream::{Control, IncomingSubstreams, MultiplexedConnection, MuxedStream, MuxerRequest, Substream};
+use crate::{connection_manager::ConnectionDirection, stream_id, utils::atomic_ref_counter::AtomicRefCounter};
 
 const LOG_TARGET: &str = "comms::multiplexing::yamux";
 
@@ -113,113 +107,9 @@ impl Yamux {
     }
 }
 
-#[derive(Debug)]
-pub enum YamuxRequest {
-    OpenStream {
-        reply: oneshot::Sender<yamux::Result<Substream>>,
-    },
-    Close {
-        reply: oneshot::Sender<yamux::Result<()>>,
-    },
-}
-
-#[derive(Clone)]
-pub struct Control {
-    request_tx: mpsc::Sender<YamuxRequest>,
-}
-
-impl Control {
-    pub fn new(request_tx: mpsc::Sender<YamuxRequest>) -> Self {
-        Self { request_tx }
-    }
-
-    /// Open a new stream to the remote.
-    pub async fn open_stream(&mut self) -> Result<Substream, YamuxControlError> {
-        let (reply, reply_rx) = oneshot::channel();
-        self.request_tx.send(YamuxRequest::OpenStream { reply }).await?;
-        let stream = reply_rx.await??;
-        Ok(stream)
-    }
-
-    /// Close the connection.
-    pub async fn close(&mut self) -> Result<(), YamuxControlError> {
-        let (reply, reply_rx) = oneshot::channel();
-        self.request_tx.send(YamuxRequest::Close { reply }).await?;
-        Ok(reply_rx.await??)
-    }
-}
-
-pub struct IncomingSubstreams {
-    inner: mpsc::Receiver<yamux::Stream>,
-    substream_counter: AtomicRefCounter,
-}
-
-impl IncomingSubstreams {
-    pub(self) fn new(inner: mpsc::Receiver<yamux::Stream>, substream_counter: AtomicRefCounter) -> Self {
-        Self {
-            inner,
-            substream_counter,
-        }
-    }
-
-    pub fn substream_count(&self) -> usize {
-        self.substream_counter.get()
-    }
-}
-
-impl Stream for IncomingSubstreams {
-    type Item = Substream;
-
-    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        match futures::ready!(Pin::new(&mut self.inner).poll_recv(cx)) {
-            Some(stream) => Poll::Ready(Some(Substream {
-                stream: stream.compat(),
-                _counter_guard: self.substream_counter.new_guard(),
-            })),
-            None => Poll::Ready(None),
-        }
-    }
-}
-
-/// A yamux stream wrapper that can be read from and written to.
-#[derive(Debug)]
-pub struct Substream {
-    stream: Compat<yamux::Stream>,
-    _counter_guard: AtomicRefCounterGuard,
-}
-
-impl StreamId for Substream {
-    fn stream_id(&self) -> stream_id::Id {
-        self.stream.get_ref().id().into()
-    }
-}
-
-impl tokio::io::AsyncRead for Substream {
-    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
-        match Pin::new(&mut self.stream).poll_read(cx, buf) {
-            Poll::Ready(Ok(())) => {
-                #[cfg(feature = "metrics")]
-                super::metrics::TOTAL_BYTES_READ.inc_by(buf.filled().len() as u64);
-                Poll::Ready(Ok(()))
-            },
-            res => res,
-        }
-    }
-}
-
-impl tokio::io::AsyncWrite for Substream {
-    fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
-        #[cfg(feature = "metrics")]
-        super::metrics::TOTAL_BYTES_WRITTEN.inc_by(buf.len() as u64);
-        Pin::new(&mut self.stream).poll_write(cx, buf)
-    }
-
-    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
-        Pin::new(&mut self.stream).poll_flush(cx)
-    }
-
-    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
-        Pin::new(&mut self.stream).poll_shutdown(cx)
+impl From<Yamux> for MultiplexedConnection {
+    fn from(yamux: Yamux) -> Self {
+        MultiplexedConnection::new(yamux.control, yamux.incoming, yamux.substream_counter)
     }
 }
 
@@ -230,8 +120,8 @@ impl From<yamux::StreamId> for stream_id::Id {
 }
 
 struct YamuxWorker<TSocket> {
-    incoming_substreams: mpsc::Sender<yamux::Stream>,
-    request_rx: mpsc::Receiver<YamuxRequest>,
+    incoming_substreams: mpsc::Sender<MuxedStream>,
+    request_rx: mpsc::Receiver<MuxerRequest>,
     counter: AtomicRefCounter,
     _phantom: PhantomData<TSocket>,
 }
@@ -240,8 +130,8 @@ impl<TSocket> YamuxWorker<TSocket>
 where TSocket: futures::AsyncRead + futures::AsyncWrite + Unpin + Send + Sync + 'static
 {
     pub fn new(
-        incoming_substreams: mpsc::Sender<yamux::Stream>,
-        request_rx: mpsc::Receiver<YamuxRequest>,
+        incoming_substreams: mpsc::Sender<MuxedStream>,
+        request_rx: mpsc::Receiver<MuxerRequest>,
         counter: AtomicRefCounter,
     ) -> Self {
         Self {
@@ -279,7 +169,7 @@ where TSocket: futures::AsyncRead + futures::AsyncWrite + Unpin + Send + Sync +
                 result = Self::next_inbound_stream(&mut connection) => {
                      match result {
                         Some(Ok(stream)) => {
-                            if self.incoming_substreams.send(stream).await.is_err() {
+                            if self.incoming_substreams.send(MuxedStream::Yamux(stream)).await.is_err() {
                                 debug!(
                                     target: LOG_TARGET,
                                     "{} Incoming peer substream task is stopping because the internal stream sender channel was closed",
@@ -314,23 +204,27 @@ where TSocket: futures::AsyncRead + futures::AsyncWrite + Unpin + Send + Sync +
     async fn handle_request(
         &self,
         connection_mut: &mut yamux::Connection<TSocket>,
-        request: YamuxRequest,
+        request: MuxerRequest,
     ) -> io::Result<()> {
         match request {
-            YamuxRequest::OpenStream { reply } => {
+            MuxerRequest::OpenStream { reply } => {
                 let result = poll_fn(move |cx| connection_mut.poll_new_outbound(cx)).await;
                 if reply
-                    .send(result.map(|stream| Substream {
-                        stream: stream.compat(),
-                        _counter_guard: self.counter.new_guard(),
-                    }))
+                    .send(
+                        result
+                            .map(|stream| Substream::new(MuxedStream::Yamux(stream), self.counter.new_guard()))
+                            .map_err(Into::into),
+                    )
                     .is_err()
                 {
                     warn!(target: LOG_TARGET, "Request to open substream was aborted before reply was sent");
                 }
             },
-            YamuxRequest::Close { reply } => {
-                if reply.send(Self::close(connection_mut).await).is_err() {
+            MuxerRequest::Close { reply } => {
+                if reply
+                    .send(Self::close(connection_mut).await.map_err(Into::into))
+                    .is_err()
+                {
                     warn!(target: LOG_TARGET, "Request to close substream was aborted before reply was sent");
                 }
             },
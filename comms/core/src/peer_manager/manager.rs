@@ -41,6 +41,8 @@ use crate::{
         PeerFeatures,
         PeerManagerError,
         PeerQuery,
+        PeerReputation,
+        ReputationEvent,
     },
     types::{CommsDatabase, CommsPublicKey},
 };
@@ -320,6 +322,26 @@ impl PeerManager {
         self.peer_storage.read().await.is_peer_banned(node_id)
     }
 
+    /// Adjusts the peer's reputation score according to the event and returns the updated reputation. This does not
+    /// ban the peer, use `ConnectivityRequester::report_peer` to have the peer banned once its score crosses the ban
+    /// threshold.
+    pub async fn adjust_reputation(
+        &self,
+        node_id: &NodeId,
+        event: ReputationEvent,
+    ) -> Result<PeerReputation, PeerManagerError> {
+        self.peer_storage.write().await.adjust_reputation(node_id, event)
+    }
+
+    /// Returns the peer's current reputation score
+    pub async fn get_reputation_score(&self, node_id: &NodeId) -> Result<i32, PeerManagerError> {
+        let peer = self
+            .find_by_node_id(node_id)
+            .await?
+            .ok_or(PeerManagerError::PeerNotFoundError)?;
+        Ok(peer.reputation_score())
+    }
+
     pub async fn update_each<F>(&self, mut f: F) -> Result<usize, PeerManagerError>
     where F: FnMut(Peer) -> Option<Peer> {
         let mut lock = self.peer_storage.write().await;
@@ -657,4 +679,27 @@ mod test {
 
         assert!(!peer.is_offline());
     }
+
+    #[tokio::test]
+    async fn test_adjust_reputation() {
+        let peer_manager = PeerManager::new(HashmapDatabase::new(), None).unwrap();
+        let peer = create_test_peer(false, PeerFeatures::COMMUNICATION_NODE);
+        peer_manager.add_peer(peer.clone()).await.unwrap();
+
+        let reputation = peer_manager
+            .adjust_reputation(&peer.node_id, ReputationEvent::ProtocolViolation)
+            .await
+            .unwrap();
+        assert_eq!(reputation.score(), -100);
+
+        // Re-adding a peer with a default reputation does not reset the persisted reputation
+        peer_manager.add_peer(peer.clone()).await.unwrap();
+        assert_eq!(peer_manager.get_reputation_score(&peer.node_id).await.unwrap(), -100);
+
+        let err = peer_manager
+            .adjust_reputation(&NodeId::default(), ReputationEvent::GoodBehaviour)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, PeerManagerError::PeerNotFoundError));
+    }
 }
@@ -0,0 +1,110 @@
+// Copyright 2024 The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+use std::collections::HashMap;
+
+use chrono::NaiveDateTime;
+use log::*;
+use serde::Deserialize;
+use tari_storage::{
+    lmdb_store::{LMDBDatabase, LMDBError},
+    IterationResult,
+};
+
+use crate::{
+    net_address::MultiaddressesWithStats,
+    peer_manager::{
+        migrations::MIGRATION_VERSION_KEY,
+        node_id::deserialize_node_id_from_hex,
+        NodeId,
+        Peer,
+        PeerFeatures,
+        PeerFlags,
+        PeerId,
+        PeerReputation,
+    },
+    protocol::ProtocolId,
+    types::CommsPublicKey,
+};
+
+const LOG_TARGET: &str = "comms::peer_manager::migrations::v8";
+
+/// Adds a reputation score to every peer
+pub struct Migration;
+
+impl super::Migration<LMDBDatabase> for Migration {
+    type Error = LMDBError;
+
+    fn get_version(&self) -> u32 {
+        8
+    }
+
+    fn migrate(&self, db: &LMDBDatabase) -> Result<(), Self::Error> {
+        let mut peers = Vec::new();
+        let mut num_failed = 0usize;
+        db.for_each::<PeerId, PeerV7, _>(|old_peer| {
+            match old_peer {
+                Ok((key, peer)) if key != MIGRATION_VERSION_KEY => peers.push((key, Peer::from(peer))),
+                Ok(_) => {},
+                // The migration version entry does not deserialize to a peer, so one failure is expected
+                Err(_) => num_failed += 1,
+            }
+            IterationResult::Continue
+        })?;
+
+        if num_failed > 1 {
+            error!(
+                target: LOG_TARGET,
+                "Failed to deserialize {} peer(s). ** Database may be corrupt **",
+                num_failed - 1
+            );
+        }
+
+        debug!(target: LOG_TARGET, "Adding reputation to {} peer(s)", peers.len());
+        for (key, peer) in peers {
+            db.insert(&key, &peer)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The peer structure prior to version 8
+#[derive(Debug, Clone, Deserialize)]
+struct PeerV7 {
+    id: Option<PeerId>,
+    public_key: CommsPublicKey,
+    #[serde(deserialize_with = "deserialize_node_id_from_hex")]
+    node_id: NodeId,
+    addresses: MultiaddressesWithStats,
+    flags: PeerFlags,
+    banned_until: Option<NaiveDateTime>,
+    banned_reason: String,
+    features: PeerFeatures,
+    supported_protocols: Vec<ProtocolId>,
+    added_at: NaiveDateTime,
+    user_agent: String,
+    metadata: HashMap<u8, Vec<u8>>,
+    deleted_at: Option<NaiveDateTime>,
+}
+
+impl From<PeerV7> for Peer {
+    fn from(peer: PeerV7) -> Self {
+        Peer {
+            id: peer.id,
+            public_key: peer.public_key,
+            node_id: peer.node_id,
+            addresses: peer.addresses,
+            flags: peer.flags,
+            banned_until: peer.banned_until,
+            banned_reason: peer.banned_reason,
+            features: peer.features,
+            supported_protocols: peer.supported_protocols,
+            added_at: peer.added_at,
+            user_agent: peer.user_agent,
+            metadata: peer.metadata,
+            deleted_at: peer.deleted_at,
+            reputation: PeerReputation::new(),
+        }
+    }
+}
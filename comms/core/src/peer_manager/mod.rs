@@ -93,6 +93,9 @@ pub use peer::{Peer, PeerFlags};
 mod peer_features;
 pub use peer_features::PeerFeatures;
 
+pub mod reputation;
+pub use reputation::{PeerReputation, ReputationConfig, ReputationEvent};
+
 mod peer_id;
 pub(crate) use peer_id::PeerId;
 
@@ -38,6 +38,7 @@ use tari_utilities::hex::serialize_to_hex;
 use super::{
     node_id::{deserialize_node_id_from_hex, NodeId},
     peer_id::PeerId,
+    reputation::{PeerReputation, ReputationEvent},
     PeerFeatures,
 };
 use crate::{
@@ -89,6 +90,8 @@ pub struct Peer {
     pub metadata: HashMap<u8, Vec<u8>>,
     /// If this peer has been deleted.
     pub deleted_at: Option<NaiveDateTime>,
+    /// The reputation score of the peer
+    pub reputation: PeerReputation,
 }
 
 impl Peer {
@@ -116,6 +119,7 @@ impl Peer {
             user_agent,
             metadata: HashMap::new(),
             deleted_at: None,
+            reputation: PeerReputation::new(),
         }
     }
 
@@ -148,6 +152,9 @@ impl Peer {
         if !other.user_agent.is_empty() {
             self.user_agent = other.user_agent.clone();
         }
+        if other.reputation.updated_at() > self.reputation.updated_at() {
+            self.reputation = other.reputation;
+        }
     }
 
     pub fn is_persisted(&self) -> bool {
@@ -246,6 +253,16 @@ impl Peer {
         self.banned_until.as_ref().filter(|dt| *dt > &Utc::now().naive_utc())
     }
 
+    /// Returns the current (decayed) reputation score of the peer
+    pub fn reputation_score(&self) -> i32 {
+        self.reputation.score()
+    }
+
+    /// Adjusts the peer's reputation according to the event, returning the new score
+    pub fn adjust_reputation(&mut self, event: ReputationEvent) -> i32 {
+        self.reputation.apply(event)
+    }
+
     /// This will store metadata inside of the metadata field in the peer.
     /// It will return None if the value was empty and the old value if the value was updated
     pub fn set_metadata(&mut self, key: u8, data: Vec<u8>) -> Option<Vec<u8>> {
@@ -38,6 +38,8 @@ use crate::{
         PeerManagerError,
         PeerQuery,
         PeerQuerySortBy,
+        PeerReputation,
+        ReputationEvent,
     },
     types::{CommsDatabase, CommsPublicKey},
 };
@@ -484,6 +486,34 @@ where DS: KeyValueStore<PeerId, Peer>
         Ok(peer.is_banned())
     }
 
+    /// Adjusts the reputation of the peer according to the given event and returns the updated reputation
+    pub fn adjust_reputation(
+        &mut self,
+        node_id: &NodeId,
+        event: ReputationEvent,
+    ) -> Result<PeerReputation, PeerManagerError> {
+        let peer_key = *self
+            .node_id_index
+            .get(node_id)
+            .ok_or(PeerManagerError::PeerNotFoundError)?;
+        let mut peer: Peer = self
+            .peer_db
+            .get(&peer_key)
+            .map_err(PeerManagerError::DatabaseError)?
+            .ok_or_else(|| {
+                PeerManagerError::DataInconsistency(format!(
+                    "node_id_index and peer database are out of sync! (key={}, node_id={})",
+                    peer_key, node_id
+                ))
+            })?;
+        peer.adjust_reputation(event);
+        let reputation = peer.reputation;
+        self.peer_db
+            .insert(peer_key, peer)
+            .map_err(PeerManagerError::DatabaseError)?;
+        Ok(reputation)
+    }
+
     /// This will store metadata inside of the metadata field in the peer provided by the nodeID.
     /// It will return None if the value was empty and the old value if the value was updated
     pub fn set_peer_metadata(
@@ -0,0 +1,251 @@
+// Copyright 2024 The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+//! Peer reputation scoring.
+//!
+//! Every peer carries a [PeerReputation] that is adjusted by [ReputationEvent]s reported by protocols. Scores decay
+//! back towards zero with a fixed half-life, so a peer that misbehaved once is gradually forgiven while repeat
+//! offenders accumulate a score that takes longer to recover. Positive scores decay in the same way, so a peer has to
+//! keep behaving well to keep a good reputation. A peer is banned once its score falls to or below the configured
+//! [ReputationConfig::ban_threshold], for as long as it takes for the score to decay back above the threshold.
+
+use std::{cmp, fmt, time::Duration};
+
+use chrono::{NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// The time it takes for a peer's score to decay to half of its value.
+pub const REPUTATION_HALF_LIFE: Duration = Duration::from_secs(60 * 60);
+/// The maximum score a peer can attain
+pub const MAX_REPUTATION_SCORE: i32 = 100;
+/// The minimum score a peer can attain
+pub const MIN_REPUTATION_SCORE: i32 = -1000;
+/// Scores are grouped into tiers of this width when ordering peers by reputation. Peers within the same tier are
+/// considered equally reputable so that other criteria (e.g. latency, randomness) still take effect.
+const REPUTATION_TIER_WIDTH: i32 = 25;
+
+/// Peer behaviour that affects the peer's reputation score
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReputationEvent {
+    /// The peer provided a valid, timely response
+    GoodBehaviour,
+    /// The peer responded slowly or timed out
+    SlowResponse,
+    /// The peer sent a malformed or unexpected message, or otherwise did not follow the protocol. A single violation
+    /// does not reach the default ban threshold, a second one before the first has decayed does.
+    ProtocolViolation,
+    /// The peer sent an invalid block, header or chain data
+    InvalidBlock,
+    /// The peer acted in a way that can only be malicious
+    MaliciousBehaviour,
+}
+
+impl ReputationEvent {
+    /// The amount that this event adjusts the peer's score by
+    pub fn score_delta(self) -> i32 {
+        #[allow(clippy::enum_glob_use)]
+        use ReputationEvent::*;
+        match self {
+            GoodBehaviour => 2,
+            SlowResponse => -10,
+            ProtocolViolation => -100,
+            InvalidBlock => -200,
+            MaliciousBehaviour => -400,
+        }
+    }
+}
+
+impl fmt::Display for ReputationEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        #[allow(clippy::enum_glob_use)]
+        use ReputationEvent::*;
+        match self {
+            GoodBehaviour => write!(f, "GoodBehaviour"),
+            SlowResponse => write!(f, "SlowResponse"),
+            ProtocolViolation => write!(f, "ProtocolViolation"),
+            InvalidBlock => write!(f, "InvalidBlock"),
+            MaliciousBehaviour => write!(f, "MaliciousBehaviour"),
+        }
+    }
+}
+
+/// Thresholds that determine when a peer is banned based on its reputation
+#[derive(Debug, Clone, Copy)]
+pub struct ReputationConfig {
+    /// A peer is banned once its score is at or below this value.
+    /// Default: -150
+    pub ban_threshold: i32,
+    /// The minimum duration of a reputation ban.
+    /// Default: 2 minutes
+    pub min_ban_duration: Duration,
+    /// The maximum duration of a reputation ban.
+    /// Default: 6 hours
+    pub max_ban_duration: Duration,
+}
+
+impl Default for ReputationConfig {
+    fn default() -> Self {
+        Self {
+            ban_threshold: -150,
+            min_ban_duration: Duration::from_secs(2 * 60),
+            max_ban_duration: Duration::from_secs(6 * 60 * 60),
+        }
+    }
+}
+
+/// A peer's persisted reputation score
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct PeerReputation {
+    /// The score at `updated_at`. Use [PeerReputation::score] to obtain the decayed score.
+    score: i32,
+    /// The time of the last adjustment. This is the unix epoch if the reputation has never been adjusted.
+    updated_at: NaiveDateTime,
+}
+
+impl PeerReputation {
+    /// A neutral reputation that has never been adjusted
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the current score, taking decay since the last update into account
+    pub fn score(&self) -> i32 {
+        self.score_at(Utc::now().naive_utc())
+    }
+
+    /// Returns the last time the score was adjusted
+    pub fn updated_at(&self) -> NaiveDateTime {
+        self.updated_at
+    }
+
+    /// Applies the event to the score and returns the new score
+    pub fn apply(&mut self, event: ReputationEvent) -> i32 {
+        let now = Utc::now().naive_utc();
+        self.score = self
+            .score_at(now)
+            .saturating_add(event.score_delta())
+            .clamp(MIN_REPUTATION_SCORE, MAX_REPUTATION_SCORE);
+        self.updated_at = now;
+        self.score
+    }
+
+    /// Returns true if the current score is at or below the ban threshold
+    pub fn is_below_threshold(&self, config: &ReputationConfig) -> bool {
+        self.score() <= config.ban_threshold
+    }
+
+    /// Returns the duration that a peer with this reputation should be banned for, or None if the score is above the
+    /// ban threshold. The duration is the time it takes for the score to decay back above the threshold, clamped to
+    /// the configured minimum and maximum.
+    pub fn ban_duration(&self, config: &ReputationConfig) -> Option<Duration> {
+        let score = self.score();
+        if score > config.ban_threshold {
+            return None;
+        }
+        let threshold = cmp::min(config.ban_threshold, -1);
+        // score * 0.5^(t / half_life) = threshold  =>  t = half_life * log2(score / threshold)
+        let half_lives = (f64::from(score) / f64::from(threshold)).log2().max(0.0);
+        let duration = REPUTATION_HALF_LIFE.mul_f64(half_lives);
+        Some(duration.clamp(config.min_ban_duration, config.max_ban_duration))
+    }
+
+    /// Coarse grouping of the score used to order peers by reputation. Higher is better.
+    pub fn tier(&self) -> i32 {
+        Self::score_tier(self.score())
+    }
+
+    /// Coarse grouping of the given score used to order peers by reputation. Higher is better.
+    pub fn score_tier(score: i32) -> i32 {
+        score.div_euclid(REPUTATION_TIER_WIDTH)
+    }
+
+    // The score is within MIN_REPUTATION_SCORE..=MAX_REPUTATION_SCORE so the cast cannot truncate
+    #[allow(clippy::cast_possible_truncation)]
+    fn score_at(&self, now: NaiveDateTime) -> i32 {
+        let Ok(elapsed) = now.signed_duration_since(self.updated_at).to_std() else {
+            return self.score;
+        };
+        let half_lives = elapsed.as_secs_f64() / REPUTATION_HALF_LIFE.as_secs_f64();
+        (f64::from(self.score) * 0.5f64.powf(half_lives)).round() as i32
+    }
+}
+
+impl fmt::Display for PeerReputation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.score())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn reputation_with(score: i32, age: Duration) -> PeerReputation {
+        PeerReputation {
+            score,
+            updated_at: Utc::now().naive_utc() - chrono::Duration::from_std(age).unwrap(),
+        }
+    }
+
+    #[test]
+    fn it_applies_events_within_bounds() {
+        let mut reputation = PeerReputation::new();
+        assert_eq!(reputation.apply(ReputationEvent::ProtocolViolation), -100);
+        for _ in 0..10 {
+            reputation.apply(ReputationEvent::MaliciousBehaviour);
+        }
+        assert_eq!(reputation.score(), MIN_REPUTATION_SCORE);
+
+        let mut reputation = PeerReputation::new();
+        for _ in 0..100 {
+            reputation.apply(ReputationEvent::GoodBehaviour);
+        }
+        assert_eq!(reputation.score(), MAX_REPUTATION_SCORE);
+    }
+
+    #[test]
+    fn it_decays_towards_zero() {
+        let reputation = reputation_with(-400, REPUTATION_HALF_LIFE);
+        assert_eq!(reputation.score(), -200);
+        let reputation = reputation_with(80, REPUTATION_HALF_LIFE * 2);
+        assert_eq!(reputation.score(), 20);
+        let reputation = reputation_with(-400, REPUTATION_HALF_LIFE * 20);
+        assert_eq!(reputation.score(), 0);
+    }
+
+    #[test]
+    fn it_calculates_the_ban_duration() {
+        let config = ReputationConfig::default();
+        assert!(reputation_with(-100, Duration::ZERO).ban_duration(&config).is_none());
+        // Two half-lives to decay from -600 to -150
+        let duration = reputation_with(-600, Duration::ZERO).ban_duration(&config).unwrap();
+        assert!(duration > REPUTATION_HALF_LIFE * 2 - Duration::from_secs(1));
+        assert!(duration <= REPUTATION_HALF_LIFE * 2);
+        // Exactly at the threshold uses the minimum ban duration
+        let duration = reputation_with(-150, Duration::ZERO).ban_duration(&config).unwrap();
+        assert_eq!(duration, config.min_ban_duration);
+        let duration = reputation_with(MIN_REPUTATION_SCORE, Duration::ZERO)
+            .ban_duration(&config)
+            .unwrap();
+        assert!(duration <= config.max_ban_duration);
+    }
+
+    #[test]
+    fn it_does_not_ban_for_a_single_protocol_violation() {
+        let config = ReputationConfig::default();
+        let mut reputation = PeerReputation::new();
+        reputation.apply(ReputationEvent::ProtocolViolation);
+        assert!(!reputation.is_below_threshold(&config));
+        assert!(reputation.ban_duration(&config).is_none());
+        reputation.apply(ReputationEvent::ProtocolViolation);
+        assert!(reputation.is_below_threshold(&config));
+        assert!(reputation.ban_duration(&config).is_some());
+    }
+
+    #[test]
+    fn it_orders_scores_into_tiers() {
+        assert_eq!(PeerReputation::score_tier(0), PeerReputation::score_tier(24));
+        assert!(PeerReputation::score_tier(25) > PeerReputation::score_tier(0));
+        assert!(PeerReputation::score_tier(-1) < PeerReputation::score_tier(0));
+    }
+}
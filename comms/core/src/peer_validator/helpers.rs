@@ -86,14 +86,7 @@ fn validate_address(addr: &Multiaddr, allow_test_addrs: bool) -> Result<(), Peer
     }
 
     match proto {
-        Protocol::Dns4(_) | Protocol::Dns6(_) | Protocol::Dnsaddr(_) => {
-            let tcp = addr_iter.next().ok_or_else(|| {
-                PeerValidatorError::InvalidMultiaddr("Address does not include a TCP port".to_string())
-            })?;
-
-            validate_tcp_port(tcp)?;
-            expect_end_of_address(addr_iter)
-        },
+        Protocol::Dns4(_) | Protocol::Dns6(_) | Protocol::Dnsaddr(_) => validate_port_and_transport(addr_iter),
 
         Protocol::Ip4(addr)
             if !allow_test_addrs && (addr.is_loopback() || addr.is_link_local() || addr.is_unspecified()) =>
@@ -109,14 +102,7 @@ fn validate_address(addr: &Multiaddr, allow_test_addrs: bool) -> Result<(), Peer
                 "Non-global IP addresses are invalid".to_string(),
             ))
         },
-        Protocol::Ip4(_) | Protocol::Ip6(_) => {
-            let tcp = addr_iter.next().ok_or_else(|| {
-                PeerValidatorError::InvalidMultiaddr("Address does not include a TCP port".to_string())
-            })?;
-
-            validate_tcp_port(tcp)?;
-            expect_end_of_address(addr_iter)
-        },
+        Protocol::Ip4(_) | Protocol::Ip6(_) => validate_port_and_transport(addr_iter),
         Protocol::Memory(0) => Err(PeerValidatorError::InvalidMultiaddr(
             "Cannot connect to a zero memory port".to_string(),
         )),
@@ -153,6 +139,29 @@ fn expect_end_of_address(mut iter: multiaddr::Iter<'_>) -> Result<(), PeerValida
     }
 }
 
+/// Validates the components following a DNS name or IP address: either a TCP port, or a UDP port followed by QUIC
+fn validate_port_and_transport(mut iter: multiaddr::Iter<'_>) -> Result<(), PeerValidatorError> {
+    let proto = iter
+        .next()
+        .ok_or_else(|| PeerValidatorError::InvalidMultiaddr("Address does not include a TCP port".to_string()))?;
+
+    match proto {
+        Protocol::Udp(0) => Err(PeerValidatorError::InvalidMultiaddr(
+            "Cannot connect to a zero UDP port".to_string(),
+        )),
+        Protocol::Udp(_) => match iter.next() {
+            Some(Protocol::Quic) => expect_end_of_address(iter),
+            _ => Err(PeerValidatorError::InvalidMultiaddr(
+                "UDP addresses are only supported for QUIC".to_string(),
+            )),
+        },
+        tcp => {
+            validate_tcp_port(tcp)?;
+            expect_end_of_address(iter)
+        },
+    }
+}
+
 fn validate_tcp_port(expected_tcp: Protocol) -> Result<(), PeerValidatorError> {
     match expected_tcp {
         Protocol::Tcp(0) => Err(PeerValidatorError::InvalidMultiaddr(
@@ -215,11 +224,17 @@ mod test {
                 .parse()
                 .unwrap(),
             multiaddr!(Dnsaddr("mike-magic-nodes.com"), Tcp(1u16)),
+            multiaddr!(Ip4([172, 0, 0, 1]), Udp(1u16), Quic),
+            multiaddr!(Dns4("mike-magic-nodes.com"), Udp(1u16), Quic),
         ];
 
         let invalid = &[
             "/onion/aaimaq4ygg2iegci:1234".parse().unwrap(),
             multiaddr!(Ip4([127, 0, 0, 1]), Tcp(1u16)),
+            multiaddr!(Ip4([127, 0, 0, 1]), Udp(1u16), Quic),
+            multiaddr!(Ip4([172, 0, 0, 1]), Udp(1u16)),
+            multiaddr!(Ip4([172, 0, 0, 1]), Udp(0u16), Quic),
+            multiaddr!(Ip4([172, 0, 0, 1]), Udp(1u16), Quic, Tcp(1u16)),
             multiaddr!(Ip4([169, 254, 0, 1]), Tcp(1u16)),
             multiaddr!(Ip4([172, 0, 0, 1])),
             "/onion/aaimaq4ygg2iegci:1234/http".parse().unwrap(),
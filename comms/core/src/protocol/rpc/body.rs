@@ -36,7 +36,7 @@ use tokio::sync::mpsc;
 
 use crate::{
     message::MessageExt,
-    protocol::rpc::{Response, RpcStatus},
+    protocol::rpc::{message::RpcMethod, Response, RpcStatus},
     Bytes,
 };
 
@@ -55,6 +55,7 @@ impl<T: prost::Message> IntoBody for T {
 pub struct Body {
     #[pin]
     kind: BodyKind,
+    offset: u64,
     is_complete: bool,
     is_terminated: bool,
 }
@@ -63,6 +64,7 @@ impl Body {
     pub fn single<T: Into<Bytes>>(body: T) -> Self {
         Self {
             kind: BodyKind::Single(Some(body.into())),
+            offset: 0,
             is_complete: false,
             is_terminated: false,
         }
@@ -72,11 +74,39 @@ impl Body {
     where S: Stream<Item = Result<Bytes, RpcStatus>> + Send + 'static {
         Self {
             kind: BodyKind::Streaming(stream.boxed()),
+            offset: 0,
             is_complete: false,
             is_terminated: false,
         }
     }
 
+    /// Indicates that the first item of this body is the item at `offset` in the complete stream
+    pub fn with_offset(mut self, offset: u64) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Holds on to `guard` until a streaming body is dropped. A single body releases the guard immediately.
+    pub fn with_guard<G: Send + 'static>(mut self, guard: G) -> Self {
+        self.kind = match self.kind {
+            BodyKind::Streaming(stream) => BodyKind::Streaming(
+                stream
+                    .map(move |item| {
+                        let _guard = &guard;
+                        item
+                    })
+                    .boxed(),
+            ),
+            kind => kind,
+        };
+        self
+    }
+
+    /// The position in the complete stream of the first item of this body
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
     pub fn is_single(&self) -> bool {
         matches!(self.kind, BodyKind::Single(_))
     }
@@ -224,16 +254,25 @@ impl Buf for BodyBytes {
 #[derive(Debug)]
 pub struct Streaming<T> {
     inner: mpsc::Receiver<Result<T, RpcStatus>>,
+    offset: u64,
 }
 
 impl<T> Streaming<T> {
     pub fn new(inner: mpsc::Receiver<Result<T, RpcStatus>>) -> Self {
-        Self { inner }
+        Self { inner, offset: 0 }
     }
 
     pub fn empty() -> Self {
         let (_, rx) = mpsc::channel(1);
-        Self { inner: rx }
+        Self::new(rx)
+    }
+
+    /// Indicates that the service has already skipped the first `offset` items of the stream, typically in response to
+    /// [Request::resume_from](super::Request::resume_from). The server skips any remaining items that the client has
+    /// already received.
+    pub fn with_offset(mut self, offset: u64) -> Self {
+        self.offset = offset;
+        self
     }
 
     pub fn into_inner(self) -> mpsc::Receiver<Result<T, RpcStatus>> {
@@ -257,13 +296,66 @@ impl<T: prost::Message> Stream for Streaming<T> {
 
 impl<T: prost::Message + 'static> IntoBody for Streaming<T> {
     fn into_body(self) -> Body {
-        Body::streaming(self)
+        let offset = self.offset;
+        Body::streaming(self).with_offset(offset)
+    }
+}
+
+/// Records the position of a client stream so that it can be resumed on the same or another peer. The token contains
+/// the original request, so the stream can only be resumed on peers that would produce an identical stream for that
+/// request, e.g. a sync stream between fixed points in the chain.
+pub struct ResumptionToken<T> {
+    method: RpcMethod,
+    request: Bytes,
+    position: u64,
+    _out: PhantomData<fn() -> T>,
+}
+
+impl<T> ResumptionToken<T> {
+    pub(super) fn new(method: RpcMethod, request: Bytes, position: u64) -> Self {
+        Self {
+            method,
+            request,
+            position,
+            _out: PhantomData,
+        }
+    }
+
+    pub fn method(&self) -> RpcMethod {
+        self.method
+    }
+
+    /// The encoded request that started the stream
+    pub fn request(&self) -> &Bytes {
+        &self.request
+    }
+
+    /// The number of items that have been received by the consumer of the stream
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+}
+
+impl<T> Clone for ResumptionToken<T> {
+    fn clone(&self) -> Self {
+        Self::new(self.method, self.request.clone(), self.position)
+    }
+}
+
+impl<T> fmt::Debug for ResumptionToken<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResumptionToken")
+            .field("method", &self.method)
+            .field("request", &format!("{} byte(s)", self.request.len()))
+            .field("position", &self.position)
+            .finish()
     }
 }
 
 #[derive(Debug)]
 pub struct ClientStreaming<T> {
     inner: mpsc::Receiver<Result<Response<Bytes>, RpcStatus>>,
+    resumption_token: Option<ResumptionToken<T>>,
     _out: PhantomData<T>,
 }
 
@@ -271,9 +363,21 @@ impl<T> ClientStreaming<T> {
     pub fn new(inner: mpsc::Receiver<Result<Response<Bytes>, RpcStatus>>) -> Self {
         Self {
             inner,
+            resumption_token: None,
             _out: PhantomData,
         }
     }
+
+    pub(super) fn with_resumption_token(mut self, token: ResumptionToken<T>) -> Self {
+        self.resumption_token = Some(token);
+        self
+    }
+
+    /// Returns a token that can be used to resume this stream from the last item that was received. Returns None if
+    /// the stream is not resumable.
+    pub fn resumption_token(&self) -> Option<ResumptionToken<T>> {
+        self.resumption_token.clone()
+    }
 }
 
 impl<T: prost::Message + Default + Unpin> Stream for ClientStreaming<T> {
@@ -288,6 +392,11 @@ impl<T: prost::Message + Default + Unpin> Stream for ClientStreaming<T> {
                     return Poll::Ready(None);
                 }
                 let result = T::decode(resp.into_message()).map_err(Into::into);
+                if result.is_ok() {
+                    if let Some(token) = self.resumption_token.as_mut() {
+                        token.position += 1;
+                    }
+                }
                 Poll::Ready(Some(result))
             },
             Some(Err(err)) => Poll::Ready(Some(Err(err))),
@@ -67,7 +67,8 @@ use crate::{
     protocol::{
         rpc,
         rpc::{
-            body::ClientStreaming,
+            body::{ClientStreaming, ResumptionToken},
+            handshake::RPC_STREAM_CONTROL_VERSION,
             message::{BaseRequest, RpcMessageFlags},
             Handshake,
             NamedProtocolService,
@@ -87,6 +88,7 @@ const LOG_TARGET: &str = "comms::rpc::client";
 #[derive(Clone)]
 pub struct RpcClient {
     connector: ClientConnector,
+    protocol_version: u32,
 }
 
 impl RpcClient {
@@ -129,10 +131,13 @@ impl RpcClient {
             .run()
             .instrument(span)
         });
-        ready_rx
+        let protocol_version = ready_rx
             .await
             .expect("ready_rx oneshot is never dropped without a reply")?;
-        Ok(Self { connector })
+        Ok(Self {
+            connector,
+            protocol_version,
+        })
     }
 
     /// Perform a single request and single response
@@ -159,12 +164,33 @@ impl RpcClient {
         R: prost::Message + Default,
         M: Into<RpcMethod>,
     {
-        let req_bytes = request.to_encoded_bytes();
-        let request = BaseRequest::new(method.into(), req_bytes.into());
+        let method = method.into();
+        let req_bytes = Bytes::from(request.to_encoded_bytes());
+        let request = BaseRequest::new(method, req_bytes.clone());
+
+        let resp = self.call_inner(request).await?;
+
+        Ok(ClientStreaming::new(resp).with_resumption_token(ResumptionToken::new(method, req_bytes, 0)))
+    }
+
+    /// Resume a streaming response from the position recorded in the resumption token. The token may have been obtained
+    /// from a session with another peer.
+    pub async fn resume_streaming<R>(&mut self, token: ResumptionToken<R>) -> Result<ClientStreaming<R>, RpcError> {
+        if token.position() > 0 && self.protocol_version < RPC_STREAM_CONTROL_VERSION {
+            return Err(RpcError::StreamResumptionNotSupported {
+                version: self.protocol_version,
+            });
+        }
+        let request = BaseRequest::new(token.method(), token.request().clone()).with_resume_from(token.position());
 
         let resp = self.call_inner(request).await?;
 
-        Ok(ClientStreaming::new(resp))
+        Ok(ClientStreaming::new(resp).with_resumption_token(token))
+    }
+
+    /// The RPC protocol version negotiated with the peer
+    pub fn protocol_version(&self) -> u32 {
+        self.protocol_version
     }
 
     /// Close the RPC session. Any subsequent calls will error.
@@ -261,6 +287,16 @@ impl<TClient> RpcClientBuilder<TClient> {
         self
     }
 
+    /// Sets the number of streamed responses that the server may send before the client has received them. Credit is
+    /// granted back to the server as the responses are consumed, so a slow consumer slows down the server rather than
+    /// buffering responses. This only applies to peers that support RPC v1.
+    ///
+    /// Default: 32
+    pub fn with_stream_credits(mut self, credits: u32) -> Self {
+        self.config.stream_credits = credits;
+        self
+    }
+
     /// Set the node_id for logging/metrics purposes
     pub fn with_node_id(mut self, node_id: NodeId) -> Self {
         self.node_id = Some(node_id);
@@ -293,6 +329,7 @@ pub struct RpcClientConfig {
     pub deadline: Option<Duration>,
     pub deadline_grace_period: Duration,
     pub handshake_timeout: Duration,
+    pub stream_credits: u32,
 }
 
 impl RpcClientConfig {
@@ -313,6 +350,7 @@ impl Default for RpcClientConfig {
             deadline: Some(Duration::from_secs(120)),
             deadline_grace_period: Duration::from_secs(60),
             handshake_timeout: Duration::from_secs(90),
+            stream_credits: 32,
         }
     }
 }
@@ -401,8 +439,9 @@ struct RpcClientWorker<TSubstream> {
     // Request ids are limited to u16::MAX because varint encoding is used over the wire and the magnitude of the value
     // sent determines the byte size. A u16 will be more than enough for the purpose
     next_request_id: u16,
-    ready_tx: Option<oneshot::Sender<Result<(), RpcError>>>,
+    ready_tx: Option<oneshot::Sender<Result<u32, RpcError>>>,
     protocol_id: ProtocolId,
+    protocol_version: u32,
     shutdown_signal: ShutdownSignal,
 }
 
@@ -415,7 +454,7 @@ where TSubstream: AsyncRead + AsyncWrite + Unpin + Send + StreamId
         request_rx: mpsc::Receiver<ClientRequest>,
         last_request_latency_tx: watch::Sender<Option<Duration>>,
         framed: CanonicalFraming<TSubstream>,
-        ready_tx: oneshot::Sender<Result<(), RpcError>>,
+        ready_tx: oneshot::Sender<Result<u32, RpcError>>,
         protocol_id: ProtocolId,
         shutdown_signal: ShutdownSignal,
     ) -> Self {
@@ -428,6 +467,7 @@ where TSubstream: AsyncRead + AsyncWrite + Unpin + Send + StreamId
             ready_tx: Some(ready_tx),
             last_request_latency_tx,
             protocol_id,
+            protocol_version: 0,
             shutdown_signal,
         }
     }
@@ -450,18 +490,20 @@ where TSubstream: AsyncRead + AsyncWrite + Unpin + Send + StreamId
         let start = Instant::now();
         let mut handshake = Handshake::new(&mut self.framed).with_timeout(self.config.handshake_timeout());
         match handshake.perform_client_handshake().await {
-            Ok(_) => {
+            Ok(version) => {
                 let latency = start.elapsed();
                 debug!(
                     target: LOG_TARGET,
-                    "(stream={}) RPC Session ({}) negotiation completed. Latency: {:.0?}",
+                    "(stream={}) RPC Session ({}) v{} negotiation completed. Latency: {:.0?}",
                     self.stream_id(),
                     self.protocol_name(),
+                    version,
                     latency
                 );
+                self.protocol_version = version;
                 let _ = self.last_request_latency_tx.send(Some(latency));
                 if let Some(r) = self.ready_tx.take() {
-                    let _result = r.send(Ok(()));
+                    let _result = r.send(Ok(version));
                 }
                 #[cfg(feature = "metrics")]
                 metrics::handshake_counter(&self.node_id, &self.protocol_id).inc();
@@ -617,13 +659,24 @@ where TSubstream: AsyncRead + AsyncWrite + Unpin + Send + StreamId
 
         let request_id = self.next_request_id();
         let method = request.method.into();
+        // Peers that do not support flow control send the stream as fast as the substream allows
+        let credits = if self.protocol_version >= RPC_STREAM_CONTROL_VERSION {
+            self.config.stream_credits
+        } else {
+            0
+        };
         let req = proto::rpc::RpcRequest {
             request_id: u32::from(request_id),
             method,
             deadline: self.config.deadline.map(|t| t.as_secs()).unwrap_or(0),
             flags: 0,
+            credits,
+            resume_from: request.resume_from,
             payload: request.message.to_vec(),
         };
+        // Credit is granted back to the server in batches
+        let credit_batch_size = (credits / 2).max(1);
+        let mut num_ungranted = 0;
 
         trace!(target: LOG_TARGET, "Sending request: {}", req);
 
@@ -737,6 +790,9 @@ where TSubstream: AsyncRead + AsyncWrite + Unpin + Send + StreamId
                     break;
                 },
                 Err(err) => {
+                    if !response_tx.is_closed() {
+                        let _result = response_tx.send(Err(RpcStatus::interrupted(&err))).await;
+                    }
                     return Err(err);
                 },
             };
@@ -755,6 +811,13 @@ where TSubstream: AsyncRead + AsyncWrite + Unpin + Send + StreamId
                     if is_finished {
                         break;
                     }
+                    if credits > 0 {
+                        num_ungranted += 1;
+                        if num_ungranted >= credit_batch_size {
+                            self.grant_credits(request_id, method, num_ungranted).await?;
+                            num_ungranted = 0;
+                        }
+                    }
                 },
                 Ok(Err(err)) => {
                     debug!(target: LOG_TARGET, "Remote service returned error: {}", err);
@@ -801,6 +864,26 @@ where TSubstream: AsyncRead + AsyncWrite + Unpin + Send + StreamId
         Ok(())
     }
 
+    /// Grants the server credit to send more responses for a flow controlled stream
+    async fn grant_credits(&mut self, request_id: u16, method: u32, credits: u32) -> Result<(), RpcError> {
+        trace!(
+            target: LOG_TARGET,
+            "(stream={}) Granting {} credit(s) for request {}",
+            self.stream_id(),
+            credits,
+            request_id
+        );
+        let req = proto::rpc::RpcRequest {
+            request_id: u32::from(request_id),
+            method,
+            flags: RpcMessageFlags::CREDIT.bits().into(),
+            deadline: self.config.deadline.map(|d| d.as_secs()).unwrap_or(0),
+            credits,
+            ..Default::default()
+        };
+        self.send_request(req).await
+    }
+
     async fn send_request(&mut self, req: proto::rpc::RpcRequest) -> Result<(), RpcError> {
         let payload = req.to_encoded_bytes();
         if payload.len() > rpc::max_request_size() {
@@ -72,6 +72,8 @@ pub enum RpcError {
     RemotePeerExceededMaxChunkCount { expected: usize },
     #[error("Request body was too large. Expected <= {expected} but got {got}")]
     MaxRequestSizeExceeded { got: usize, expected: usize },
+    #[error("The peer does not support resuming streams (RPC v{version})")]
+    StreamResumptionNotSupported { version: u32 },
     #[error(transparent)]
     UnknownError(#[from] anyhow::Error),
 }
@@ -107,12 +109,30 @@ impl RpcError {
             RpcError::ConnectivityError(_) |
             RpcError::InvalidPingResponse |
             RpcError::MaxRequestSizeExceeded { .. } |
+            RpcError::StreamResumptionNotSupported { .. } |
             RpcError::HandshakeError(RpcHandshakeError::Io(_)) |
             RpcError::HandshakeError(RpcHandshakeError::ClientNoSupportedVersion) |
             RpcError::HandshakeError(RpcHandshakeError::ClientClosed) |
             RpcError::UnknownError(_) => false,
         }
     }
+
+    /// Returns true if the peer did not respond in time, otherwise false
+    pub fn is_timeout(&self) -> bool {
+        match self {
+            RpcError::ReplyTimeout | RpcError::HandshakeError(RpcHandshakeError::TimedOut) => true,
+            RpcError::RequestFailed(status) => status.is_timeout(),
+            _ => false,
+        }
+    }
+
+    /// Returns true if the peer rejected the request because it is too busy, otherwise false
+    pub fn is_busy(&self) -> bool {
+        match self {
+            RpcError::RequestFailed(status) => status.is_busy(),
+            _ => false,
+        }
+    }
 }
 
 #[derive(Debug, Error, Clone, Copy)]
@@ -35,9 +35,12 @@ use crate::{framing::CanonicalFraming, message::MessageExt, proto, protocol::rpc
 
 const LOG_TARGET: &str = "comms::rpc::handshake";
 
-/// Supported RPC protocol versions.
-/// Currently only v0 is supported
-pub(super) const SUPPORTED_RPC_VERSIONS: &[u32] = &[0];
+/// Supported RPC protocol versions in order of preference.
+/// v1 adds credit-based flow control and resumption of streamed responses.
+pub(super) const SUPPORTED_RPC_VERSIONS: &[u32] = &[1, 0];
+
+/// The first RPC protocol version that supports flow control and stream resumption
+pub(super) const RPC_STREAM_CONTROL_VERSION: u32 = 1;
 
 #[derive(Debug, thiserror::Error)]
 pub enum RpcHandshakeError {
@@ -131,8 +134,8 @@ where T: AsyncRead + AsyncWrite + Unpin
         Ok(())
     }
 
-    /// Client-side handshake protocol
-    pub async fn perform_client_handshake(&mut self) -> Result<(), RpcHandshakeError> {
+    /// Client-side handshake protocol. Returns the RPC version selected by the server.
+    pub async fn perform_client_handshake(&mut self) -> Result<u32, RpcHandshakeError> {
         let msg = proto::rpc::RpcSession {
             supported_versions: SUPPORTED_RPC_VERSIONS.to_vec(),
         };
@@ -153,7 +156,7 @@ where T: AsyncRead + AsyncWrite + Unpin
                 let msg = proto::rpc::RpcSessionReply::decode(&mut msg.freeze())?;
                 let version = msg.result()?;
                 debug!(target: LOG_TARGET, "Server accepted version {}", version);
-                Ok(())
+                Ok(version)
             },
             Ok(Some(Err(err))) => {
                 error!(target: LOG_TARGET, "Error during handshake: {}", err);
@@ -53,7 +53,7 @@ impl Request<Bytes> {
         let message = T::decode(&mut self.inner.message)?;
         Ok(Request {
             context: self.context,
-            inner: BaseRequest::new(self.inner.method, message),
+            inner: BaseRequest::new(self.inner.method, message).with_resume_from(self.inner.resume_from),
         })
     }
 }
@@ -77,6 +77,18 @@ impl<T> Request<T> {
         self.inner.method
     }
 
+    /// The number of streamed responses that the client has already received for this request. A service may use this
+    /// to seek to the correct position in the stream and indicate that it has done so with
+    /// [Streaming::with_offset](super::Streaming::with_offset). Otherwise, the server skips this many responses.
+    pub fn resume_from(&self) -> u64 {
+        self.inner.resume_from
+    }
+
+    pub(super) fn with_resume_from(mut self, resume_from: u64) -> Self {
+        self.inner.resume_from = resume_from;
+        self
+    }
+
     #[inline]
     pub fn message(&self) -> &T {
         &self.inner.message
@@ -116,12 +128,23 @@ impl<T> Request<T> {
 #[derive(Debug, Clone)]
 pub struct BaseRequest<T> {
     pub(super) method: RpcMethod,
+    pub(super) resume_from: u64,
     pub message: T,
 }
 
 impl<T> BaseRequest<T> {
     pub fn new(method: RpcMethod, message: T) -> Self {
-        Self { method, message }
+        Self {
+            method,
+            resume_from: 0,
+            message,
+        }
+    }
+
+    /// Request that the server skips the first `resume_from` streamed responses
+    pub fn with_resume_from(mut self, resume_from: u64) -> Self {
+        self.resume_from = resume_from;
+        self
     }
 
     #[allow(dead_code)]
@@ -208,6 +231,8 @@ bitflags! {
         const FIN = 0x01;
         /// Typically sent with empty contents and used to confirm a substream is alive.
         const ACK = 0x02;
+        /// (v1) Sent by the client to grant the server more credits for a flow controlled stream.
+        const CREDIT = 0x04;
     }
 }
 impl RpcMessageFlags {
@@ -218,6 +243,10 @@ impl RpcMessageFlags {
     pub fn is_ack(self) -> bool {
         self.contains(Self::ACK)
     }
+
+    pub fn is_credit(self) -> bool {
+        self.contains(Self::CREDIT)
+    }
 }
 
 impl Default for RpcMessageFlags {
@@ -49,12 +49,12 @@ const fn max_response_payload_size() -> usize {
 }
 
 mod body;
-pub use body::{Body, ClientStreaming, IntoBody, Streaming};
+pub use body::{Body, ClientStreaming, IntoBody, ResumptionToken, Streaming};
 
 mod context;
 
 mod server;
-pub use server::{mock, NamedProtocolService, RpcServer, RpcServerBuilder, RpcServerError, RpcServerHandle};
+pub use server::{middleware, mock, NamedProtocolService, RpcServer, RpcServerBuilder, RpcServerError, RpcServerHandle};
 
 mod client;
 pub use client::{
@@ -97,6 +97,7 @@ pub mod __macro_reexports {
                 Body,
                 ClientStreaming,
                 IntoBody,
+                ResumptionToken,
                 RpcClient,
                 RpcClientBuilder,
                 RpcError,
@@ -117,3 +117,16 @@ pub fn outbound_response_bytes(node_id: &NodeId, protocol: &ProtocolId) -> Histo
 
     METER.with_label_values(&[node_id.to_string().as_str(), String::from_utf8_lossy(protocol).as_ref()])
 }
+
+pub fn request_latency(protocol: &ProtocolId, method: u32) -> Histogram {
+    static METER: Lazy<HistogramVec> = Lazy::new(|| {
+        tari_metrics::register_histogram_vec(
+            "comms::rpc::server::request_latency",
+            "Time taken in seconds to handle a request per protocol per method",
+            &["protocol", "method"],
+        )
+        .unwrap()
+    });
+
+    METER.with_label_values(&[String::from_utf8_lossy(protocol).as_ref(), method.to_string().as_str()])
+}
@@ -0,0 +1,187 @@
+// Copyright 2024 The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+use std::{collections::HashSet, sync::Arc};
+
+use log::*;
+
+use super::{Next, RpcFuture, RpcMiddleware};
+use crate::{
+    peer_manager::{NodeId, PeerFeatures},
+    protocol::rpc::{context::RequestContext, message::Request, RpcStatus},
+    Bytes,
+};
+
+const LOG_TARGET: &str = "comms::rpc::server::middleware::access_control";
+
+/// Determines which peers may call the methods of an RPC service.
+///
+/// A peer is permitted if it is on the allow list, or if it has all of the required features. If neither an allow list
+/// nor required features are set, all peers are permitted.
+#[derive(Debug, Clone, Default)]
+pub struct RpcAccessPolicy {
+    allowed_peers: Option<HashSet<NodeId>>,
+    required_features: Option<PeerFeatures>,
+    methods: Option<HashSet<u32>>,
+}
+
+impl RpcAccessPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Always permit the given peers
+    pub fn allow_peers<I: IntoIterator<Item = NodeId>>(mut self, peers: I) -> Self {
+        self.allowed_peers.get_or_insert_with(HashSet::new).extend(peers);
+        self
+    }
+
+    /// Permit peers that have all of the given features
+    pub fn require_features(mut self, features: PeerFeatures) -> Self {
+        self.required_features = Some(features);
+        self
+    }
+
+    /// Only apply this policy to the given method ids. By default, the policy applies to all methods.
+    pub fn for_methods<I: IntoIterator<Item = u32>>(mut self, methods: I) -> Self {
+        self.methods.get_or_insert_with(HashSet::new).extend(methods);
+        self
+    }
+
+    fn applies_to(&self, method: u32) -> bool {
+        self.methods.as_ref().map_or(true, |methods| methods.contains(&method))
+    }
+
+    async fn check(&self, context: &RequestContext) -> Result<(), RpcStatus> {
+        let node_id = context.peer_node_id();
+        if self.allowed_peers.as_ref().is_some_and(|peers| peers.contains(node_id)) {
+            return Ok(());
+        }
+
+        match self.required_features {
+            Some(required) => {
+                let peer = context
+                    .fetch_peer()
+                    .await
+                    .map_err(|_| RpcStatus::forbidden("Peer is not known to this node"))?;
+                if peer.features.contains(required) {
+                    Ok(())
+                } else {
+                    Err(RpcStatus::forbidden(&format!(
+                        "This method is only available to {} peers",
+                        required.as_role_str()
+                    )))
+                }
+            },
+            None if self.allowed_peers.is_some() => {
+                Err(RpcStatus::forbidden("Peer is not permitted to call this method"))
+            },
+            None => Ok(()),
+        }
+    }
+}
+
+/// Middleware that rejects requests from peers that are not permitted by the [RpcAccessPolicy]
+#[derive(Debug, Clone)]
+pub struct AccessControl {
+    policy: Arc<RpcAccessPolicy>,
+}
+
+impl AccessControl {
+    pub fn new(policy: RpcAccessPolicy) -> Self {
+        Self {
+            policy: Arc::new(policy),
+        }
+    }
+}
+
+impl RpcMiddleware for AccessControl {
+    fn handle(&self, request: Request<Bytes>, next: Next) -> RpcFuture {
+        let method = request.method().id();
+        if !self.policy.applies_to(method) {
+            return next.run(request);
+        }
+
+        let policy = self.policy.clone();
+        Box::pin(async move {
+            if let Err(status) = policy.check(request.context()).await {
+                debug!(
+                    target: LOG_TARGET,
+                    "Rejected request for method {} on protocol `{}` from peer `{}`: {}",
+                    method,
+                    String::from_utf8_lossy(next.protocol()),
+                    request.context().peer_node_id(),
+                    status
+                );
+                return Err(status);
+            }
+            next.run(request).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use tower::Service;
+
+    use super::*;
+    use crate::{
+        protocol::rpc::{
+            server::middleware::{
+                test::{request_from, service_with_middleware},
+                RpcMiddlewareStack,
+            },
+            RpcStatusCode,
+        },
+        test_utils::{build_peer_manager, node_identity::build_node_identity},
+    };
+
+    #[tokio::test]
+    async fn it_permits_peers_with_the_required_features() {
+        let peer_manager = build_peer_manager();
+        let base_node = build_node_identity(PeerFeatures::COMMUNICATION_NODE);
+        let wallet = build_node_identity(PeerFeatures::COMMUNICATION_CLIENT);
+        peer_manager.add_peer(base_node.to_peer()).await.unwrap();
+        peer_manager.add_peer(wallet.to_peer()).await.unwrap();
+
+        let policy = RpcAccessPolicy::new()
+            .require_features(PeerFeatures::COMMUNICATION_NODE)
+            .for_methods([1]);
+        let mut service = service_with_middleware(RpcMiddlewareStack::new().layer(AccessControl::new(policy)));
+
+        service
+            .call(request_from(&peer_manager, base_node.node_id(), 1))
+            .await
+            .unwrap();
+        let err = service
+            .call(request_from(&peer_manager, wallet.node_id(), 1))
+            .await
+            .unwrap_err();
+        assert_eq!(err.as_status_code(), RpcStatusCode::Forbidden);
+        // The policy does not apply to other methods
+        service
+            .call(request_from(&peer_manager, wallet.node_id(), 2))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn it_permits_allow_listed_peers() {
+        let peer_manager = build_peer_manager();
+        let allowed = build_node_identity(PeerFeatures::COMMUNICATION_CLIENT);
+        let other = build_node_identity(PeerFeatures::COMMUNICATION_NODE);
+
+        let policy = RpcAccessPolicy::new().allow_peers([allowed.node_id().clone()]);
+        let mut service = service_with_middleware(RpcMiddlewareStack::new().layer(AccessControl::new(policy)));
+
+        service
+            .call(request_from(&peer_manager, allowed.node_id(), 1))
+            .await
+            .unwrap();
+        let err = service
+            .call(request_from(&peer_manager, other.node_id(), 1))
+            .await
+            .unwrap_err();
+        assert_eq!(err.as_status_code(), RpcStatusCode::Forbidden);
+    }
+}
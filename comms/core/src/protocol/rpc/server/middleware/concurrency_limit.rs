@@ -0,0 +1,197 @@
+// Copyright 2024 The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use log::*;
+
+use super::{Next, RpcFuture, RpcMiddleware};
+use crate::{
+    peer_manager::NodeId,
+    protocol::rpc::{message::Request, RpcStatus},
+    Bytes,
+};
+
+const LOG_TARGET: &str = "comms::rpc::server::middleware::concurrency_limit";
+
+type InFlightKey = (u32, Option<NodeId>);
+
+/// The number of requests for a method that may be in flight at the same time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RequestConcurrencyLimit {
+    max_in_flight: usize,
+    per_peer: bool,
+}
+
+impl RequestConcurrencyLimit {
+    /// Allow each peer to have `max_in_flight` requests in flight
+    pub fn per_peer(max_in_flight: usize) -> Self {
+        Self {
+            max_in_flight,
+            per_peer: true,
+        }
+    }
+
+    /// Allow all peers combined to have `max_in_flight` requests in flight
+    pub fn global(max_in_flight: usize) -> Self {
+        Self {
+            max_in_flight,
+            per_peer: false,
+        }
+    }
+}
+
+/// Middleware that rejects requests for a method once its [RequestConcurrencyLimit] is reached. A request is in
+/// flight until its response has been sent, and a streaming request until its stream ends. Requests for methods without
+/// a limit are not affected. Rejected requests receive a [busy](RpcStatus::busy) status, which peers do not penalise.
+#[derive(Debug, Clone, Default)]
+pub struct ConcurrencyLimit {
+    limits: HashMap<u32, RequestConcurrencyLimit>,
+    in_flight: Arc<Mutex<HashMap<InFlightKey, usize>>>,
+}
+
+impl ConcurrencyLimit {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the concurrency limit for the given method id
+    pub fn with_method_limit(mut self, method: u32, limit: RequestConcurrencyLimit) -> Self {
+        self.limits.insert(method, limit);
+        self
+    }
+
+    fn try_acquire(&self, method: u32, node_id: &NodeId) -> Result<Option<InFlightGuard>, RpcStatus> {
+        let Some(limit) = self.limits.get(&method) else {
+            return Ok(None);
+        };
+
+        let key = (method, Some(node_id.clone()).filter(|_| limit.per_peer));
+        let mut in_flight = self.in_flight.lock().unwrap_or_else(|err| err.into_inner());
+        let count = in_flight.get(&key).copied().unwrap_or(0);
+        if count >= limit.max_in_flight {
+            return Err(RpcStatus::busy(&format!(
+                "Limit of {} concurrent requests reached",
+                limit.max_in_flight
+            )));
+        }
+        in_flight.insert(key.clone(), count + 1);
+
+        Ok(Some(InFlightGuard {
+            key,
+            in_flight: self.in_flight.clone(),
+        }))
+    }
+}
+
+impl RpcMiddleware for ConcurrencyLimit {
+    fn handle(&self, request: Request<Bytes>, next: Next) -> RpcFuture {
+        let method = request.method().id();
+        match self.try_acquire(method, request.context().peer_node_id()) {
+            Ok(Some(guard)) => {
+                let fut = next.run(request);
+                Box::pin(async move {
+                    let mut response = fut.await?;
+                    response.payload = response.payload.with_guard(guard);
+                    Ok(response)
+                })
+            },
+            Ok(None) => next.run(request),
+            Err(status) => {
+                debug!(
+                    target: LOG_TARGET,
+                    "Rejected request for method {} on protocol `{}` from peer `{}`: {}",
+                    method,
+                    String::from_utf8_lossy(next.protocol()),
+                    request.context().peer_node_id(),
+                    status
+                );
+                Box::pin(futures::future::ready(Err(status)))
+            },
+        }
+    }
+}
+
+/// Counts a request as in flight until dropped
+#[derive(Debug)]
+struct InFlightGuard {
+    key: InFlightKey,
+    in_flight: Arc<Mutex<HashMap<InFlightKey, usize>>>,
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        let mut in_flight = self.in_flight.lock().unwrap_or_else(|err| err.into_inner());
+        if let Some(count) = in_flight.get_mut(&self.key) {
+            *count = count.saturating_sub(1);
+            if *count == 0 {
+                in_flight.remove(&self.key);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use futures::stream;
+    use tower::{service_fn, util::BoxCloneService, Service};
+
+    use super::*;
+    use crate::{
+        protocol::{
+            rpc::{
+                message::Response,
+                server::middleware::{test::request_from, MiddlewareService, RpcMiddlewareStack},
+                Body,
+                RpcStatusCode,
+            },
+            ProtocolId,
+        },
+        test_utils::{build_peer_manager, node_id},
+    };
+
+    fn streaming_service_with_middleware(middleware: RpcMiddlewareStack) -> MiddlewareService {
+        // Streams never end, so a request is in flight until its response is dropped
+        let service = service_fn(|_: Request<Bytes>| async move {
+            let body = Body::streaming(stream::pending::<Result<Bytes, RpcStatus>>());
+            Ok::<_, RpcStatus>(Response::new(body))
+        });
+        MiddlewareService {
+            protocol: ProtocolId::from_static(b"t/test/1"),
+            stack: middleware.stack.into(),
+            service: BoxCloneService::new(service),
+        }
+    }
+
+    #[tokio::test]
+    async fn it_limits_requests_in_flight() {
+        let peer_manager = build_peer_manager();
+        let (peer1, peer2) = (node_id::random(), node_id::random());
+        let concurrency_limit = ConcurrencyLimit::new()
+            .with_method_limit(1, RequestConcurrencyLimit::per_peer(1))
+            .with_method_limit(2, RequestConcurrencyLimit::global(1));
+        let mut service = streaming_service_with_middleware(RpcMiddlewareStack::new().layer(concurrency_limit));
+
+        let stream1 = service.call(request_from(&peer_manager, &peer1, 1)).await.unwrap();
+        let err = service.call(request_from(&peer_manager, &peer1, 1)).await.unwrap_err();
+        assert_eq!(err.as_status_code(), RpcStatusCode::Busy);
+        let _stream2 = service.call(request_from(&peer_manager, &peer2, 1)).await.unwrap();
+
+        // The request is no longer in flight once the stream is dropped
+        drop(stream1);
+        service.call(request_from(&peer_manager, &peer1, 1)).await.unwrap();
+
+        let _stream3 = service.call(request_from(&peer_manager, &peer1, 2)).await.unwrap();
+        let err = service.call(request_from(&peer_manager, &peer2, 2)).await.unwrap_err();
+        assert_eq!(err.as_status_code(), RpcStatusCode::Busy);
+
+        // Methods without a limit are unaffected
+        let mut streams = Vec::new();
+        for _ in 0..5 {
+            streams.push(service.call(request_from(&peer_manager, &peer1, 3)).await.unwrap());
+        }
+    }
+}
@@ -0,0 +1,36 @@
+// Copyright 2024 The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+use super::{Next, RpcFuture, RpcMiddleware};
+use crate::{protocol::rpc::message::Request, Bytes};
+
+/// Middleware that records the time taken to handle each request in the `comms::rpc::server::request_latency`
+/// histogram, labelled by protocol and method. For streaming methods, this is the time taken to start the stream.
+///
+/// Requests are passed through unchanged if comms is built without the `metrics` feature.
+#[derive(Debug, Clone, Default)]
+pub struct LatencyHistogram;
+
+impl LatencyHistogram {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl RpcMiddleware for LatencyHistogram {
+    #[cfg(feature = "metrics")]
+    fn handle(&self, request: Request<Bytes>, next: Next) -> RpcFuture {
+        let histogram = super::super::metrics::request_latency(next.protocol(), request.method().id());
+        let timer = std::time::Instant::now();
+        Box::pin(async move {
+            let result = next.run(request).await;
+            histogram.observe(timer.elapsed().as_secs_f64());
+            result
+        })
+    }
+
+    #[cfg(not(feature = "metrics"))]
+    fn handle(&self, request: Request<Bytes>, next: Next) -> RpcFuture {
+        next.run(request)
+    }
+}
@@ -0,0 +1,53 @@
+// Copyright 2024 The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+use std::time::Instant;
+
+use log::*;
+
+use super::{Next, RpcFuture, RpcMiddleware};
+use crate::{protocol::rpc::message::Request, Bytes};
+
+const LOG_TARGET: &str = "comms::rpc::server::middleware::logging";
+
+/// Middleware that logs each request along with its outcome and how long it took to handle
+#[derive(Debug, Clone, Default)]
+pub struct RequestLogger;
+
+impl RequestLogger {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl RpcMiddleware for RequestLogger {
+    fn handle(&self, request: Request<Bytes>, next: Next) -> RpcFuture {
+        let method = request.method().id();
+        let node_id = request.context().peer_node_id().clone();
+        let protocol = String::from_utf8_lossy(next.protocol()).into_owned();
+        let timer = Instant::now();
+        Box::pin(async move {
+            let result = next.run(request).await;
+            match &result {
+                Ok(_) => debug!(
+                    target: LOG_TARGET,
+                    "[{}] method {} for peer `{}` completed in {:.2?}",
+                    protocol,
+                    method,
+                    node_id,
+                    timer.elapsed()
+                ),
+                Err(status) => debug!(
+                    target: LOG_TARGET,
+                    "[{}] method {} for peer `{}` failed in {:.2?}: {}",
+                    protocol,
+                    method,
+                    node_id,
+                    timer.elapsed(),
+                    status
+                ),
+            }
+            result
+        })
+    }
+}
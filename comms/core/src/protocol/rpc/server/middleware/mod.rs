@@ -0,0 +1,287 @@
+// Copyright 2024 The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+//! # RPC server middleware
+//!
+//! Middleware runs for every request made to an RPC service, before the request reaches the service. Each middleware
+//! may inspect the request, reject it with an [RpcStatus] or pass it on to the [Next] middleware in the chain, and
+//! may inspect the response on the way back.
+//!
+//! Middleware is attached to a service when building the [RpcServer](super::RpcServer):
+//!
+//! ```ignore
+//! let middleware = RpcMiddlewareStack::new()
+//!     .layer(RequestLogger::new())
+//!     .layer(AccessControl::new(RpcAccessPolicy::new().require_features(PeerFeatures::COMMUNICATION_NODE)))
+//!     .layer(RateLimit::new().with_method_limit(1, RequestRateLimit::per_peer(10, Duration::from_secs(60))));
+//! let rpc_server = RpcServer::new().add_service(MyServer::new(service).with_middleware(middleware));
+//! ```
+
+use std::{
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use futures::future::BoxFuture;
+use tower::{util::BoxCloneService, Service, ServiceExt};
+
+use crate::{
+    protocol::{
+        rpc::{
+            message::{Request, Response},
+            server::{NamedProtocolService, RpcServerError},
+            Body,
+            RpcStatus,
+        },
+        ProtocolId,
+    },
+    Bytes,
+};
+
+mod access_control;
+pub use access_control::{AccessControl, RpcAccessPolicy};
+
+mod concurrency_limit;
+pub use concurrency_limit::{ConcurrencyLimit, RequestConcurrencyLimit};
+
+mod latency;
+pub use latency::LatencyHistogram;
+
+mod logging;
+pub use logging::RequestLogger;
+
+mod rate_limit;
+pub use rate_limit::{RateLimit, RequestRateLimit};
+
+/// The future returned by middleware and RPC services
+pub type RpcFuture = BoxFuture<'static, Result<Response<Body>, RpcStatus>>;
+
+/// Middleware that is invoked for every request made to an RPC service
+pub trait RpcMiddleware: Send + Sync + 'static {
+    /// Handle the request. Call `next.run(request)` to pass the request on to the rest of the chain.
+    fn handle(&self, request: Request<Bytes>, next: Next) -> RpcFuture;
+}
+
+/// The remainder of the middleware chain, ending with the RPC service
+pub struct Next {
+    protocol: ProtocolId,
+    stack: Arc<[Arc<dyn RpcMiddleware>]>,
+    index: usize,
+    service: BoxCloneService<Request<Bytes>, Response<Body>, RpcStatus>,
+}
+
+impl Next {
+    /// The protocol of the service that is handling the request
+    pub fn protocol(&self) -> &ProtocolId {
+        &self.protocol
+    }
+
+    /// Pass the request on to the next middleware, or to the service if this is the end of the chain
+    pub fn run(mut self, request: Request<Bytes>) -> RpcFuture {
+        match self.stack.get(self.index).cloned() {
+            Some(middleware) => {
+                self.index += 1;
+                middleware.handle(request, self)
+            },
+            None => Box::pin(self.service.oneshot(request)),
+        }
+    }
+}
+
+/// An ordered collection of middleware. Requests pass through middleware in the order that it was added.
+#[derive(Clone, Default)]
+pub struct RpcMiddlewareStack {
+    stack: Vec<Arc<dyn RpcMiddleware>>,
+}
+
+impl RpcMiddlewareStack {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add middleware to the end of the chain
+    pub fn layer<M: RpcMiddleware>(mut self, middleware: M) -> Self {
+        self.stack.push(Arc::new(middleware));
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.stack.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stack.is_empty()
+    }
+}
+
+/// Extension trait that attaches middleware to an RPC service
+pub trait RpcMiddlewareExt: NamedProtocolService + Sized {
+    fn with_middleware(self, middleware: RpcMiddlewareStack) -> WithMiddleware<Self> {
+        WithMiddleware::new(self, middleware)
+    }
+}
+
+impl<T: NamedProtocolService> RpcMiddlewareExt for T {}
+
+/// An RPC service maker that applies a middleware chain to the services it makes
+pub struct WithMiddleware<S> {
+    service: S,
+    stack: Arc<[Arc<dyn RpcMiddleware>]>,
+}
+
+impl<S> WithMiddleware<S> {
+    pub fn new(service: S, middleware: RpcMiddlewareStack) -> Self {
+        Self {
+            service,
+            stack: middleware.stack.into(),
+        }
+    }
+}
+
+impl<S: NamedProtocolService> NamedProtocolService for WithMiddleware<S> {
+    const PROTOCOL_NAME: &'static [u8] = S::PROTOCOL_NAME;
+}
+
+impl<S> Service<ProtocolId> for WithMiddleware<S>
+where
+    S: Service<ProtocolId, Error = RpcServerError>,
+    S::Future: Send + 'static,
+    S::Response: Service<Request<Bytes>, Response = Response<Body>, Error = RpcStatus> + Clone + Send + 'static,
+    <S::Response as Service<Request<Bytes>>>::Future: Send + 'static,
+{
+    type Error = RpcServerError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+    type Response = MiddlewareService;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(&mut self, protocol: ProtocolId) -> Self::Future {
+        let stack = self.stack.clone();
+        let fut = self.service.call(protocol.clone());
+        Box::pin(async move {
+            let service = fut.await?;
+            Ok(MiddlewareService {
+                protocol,
+                stack,
+                service: BoxCloneService::new(service),
+            })
+        })
+    }
+}
+
+/// An RPC service that passes each request through a middleware chain
+#[derive(Clone)]
+pub struct MiddlewareService {
+    protocol: ProtocolId,
+    stack: Arc<[Arc<dyn RpcMiddleware>]>,
+    service: BoxCloneService<Request<Bytes>, Response<Body>, RpcStatus>,
+}
+
+impl Service<Request<Bytes>> for MiddlewareService {
+    type Error = RpcStatus;
+    type Future = RpcFuture;
+    type Response = Response<Body>;
+
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        // Readiness of the inner service is checked when the request reaches the end of the chain
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: Request<Bytes>) -> Self::Future {
+        let next = Next {
+            protocol: self.protocol.clone(),
+            stack: self.stack.clone(),
+            index: 0,
+            service: self.service.clone(),
+        };
+        next.run(request)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Mutex;
+
+    use tower::service_fn;
+
+    use super::*;
+    use crate::{
+        peer_manager::NodeId,
+        protocol::rpc::{
+            context::{RequestContext, RpcCommsBackend},
+            message::RpcMethod,
+            RpcStatusCode,
+        },
+        test_utils::mocks::create_connectivity_mock,
+        PeerManager,
+    };
+
+    pub fn request_from(peer_manager: &Arc<PeerManager>, node_id: &NodeId, method: u32) -> Request<Bytes> {
+        let (connectivity, _) = create_connectivity_mock();
+        let backend = RpcCommsBackend::new(peer_manager.clone(), connectivity);
+        let context = RequestContext::new(0, node_id.clone(), Box::new(backend));
+        Request::with_context(context, method.into(), Bytes::new())
+    }
+
+    pub fn service_with_middleware(middleware: RpcMiddlewareStack) -> MiddlewareService {
+        let service = service_fn(|req: Request<Bytes>| async move {
+            Ok::<_, RpcStatus>(Response::new(Body::single(req.into_message())))
+        });
+        MiddlewareService {
+            protocol: ProtocolId::from_static(b"t/test/1"),
+            stack: middleware.stack.into(),
+            service: BoxCloneService::new(service),
+        }
+    }
+
+    struct Record(&'static str, Arc<Mutex<Vec<&'static str>>>);
+
+    impl RpcMiddleware for Record {
+        fn handle(&self, request: Request<Bytes>, next: Next) -> RpcFuture {
+            let (name, log) = (self.0, self.1.clone());
+            log.lock().unwrap().push(name);
+            Box::pin(async move {
+                let response = next.run(request).await;
+                log.lock().unwrap().push(name);
+                response
+            })
+        }
+    }
+
+    struct Reject;
+
+    impl RpcMiddleware for Reject {
+        fn handle(&self, _: Request<Bytes>, _: Next) -> RpcFuture {
+            Box::pin(async { Err(RpcStatus::forbidden("Rejected")) })
+        }
+    }
+
+    #[tokio::test]
+    async fn it_runs_middleware_in_order() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let mut service = service_with_middleware(
+            RpcMiddlewareStack::new()
+                .layer(Record("outer", log.clone()))
+                .layer(Record("inner", log.clone())),
+        );
+        let request = Request::new(RpcMethod::from(1), Bytes::from_static(b"hello"));
+        service.call(request).await.unwrap();
+        assert_eq!(*log.lock().unwrap(), vec!["outer", "inner", "inner", "outer"]);
+    }
+
+    #[tokio::test]
+    async fn it_stops_the_chain_when_rejected() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let mut service = service_with_middleware(
+            RpcMiddlewareStack::new()
+                .layer(Reject)
+                .layer(Record("inner", log.clone())),
+        );
+        let request = Request::new(RpcMethod::from(1), Bytes::from_static(b"hello"));
+        let err = service.call(request).await.unwrap_err();
+        assert_eq!(err.as_status_code(), RpcStatusCode::Forbidden);
+        assert!(log.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,208 @@
+// Copyright 2024 The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use log::*;
+
+use super::{Next, RpcFuture, RpcMiddleware};
+use crate::{
+    peer_manager::NodeId,
+    protocol::rpc::{message::Request, RpcStatus},
+    Bytes,
+};
+
+const LOG_TARGET: &str = "comms::rpc::server::middleware::rate_limit";
+
+/// Once this many per-peer buckets are tracked, buckets that have fully refilled are discarded
+const MAX_TRACKED_BUCKETS: usize = 1024;
+
+/// The number of requests allowed for a method within an interval
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RequestRateLimit {
+    max_requests: u32,
+    interval: Duration,
+    per_peer: bool,
+}
+
+impl RequestRateLimit {
+    /// Allow each peer to make `max_requests` requests every `interval`
+    pub fn per_peer(max_requests: u32, interval: Duration) -> Self {
+        Self {
+            max_requests,
+            interval,
+            per_peer: true,
+        }
+    }
+
+    /// Allow all peers combined to make `max_requests` requests every `interval`
+    pub fn global(max_requests: u32, interval: Duration) -> Self {
+        Self {
+            max_requests,
+            interval,
+            per_peer: false,
+        }
+    }
+}
+
+/// Middleware that rejects requests for a method once its [RequestRateLimit] is exceeded. Requests for methods without
+/// a limit are not affected. Rejected requests receive a [busy](RpcStatus::busy) status, which peers do not penalise.
+#[derive(Debug, Clone, Default)]
+pub struct RateLimit {
+    limits: HashMap<u32, RequestRateLimit>,
+    buckets: Arc<Mutex<HashMap<(u32, Option<NodeId>), Bucket>>>,
+}
+
+impl RateLimit {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the rate limit for the given method id
+    pub fn with_method_limit(mut self, method: u32, limit: RequestRateLimit) -> Self {
+        self.limits.insert(method, limit);
+        self
+    }
+
+    fn try_acquire(&self, method: u32, node_id: &NodeId) -> Result<(), RpcStatus> {
+        let Some(limit) = self.limits.get(&method) else {
+            return Ok(());
+        };
+
+        let now = Instant::now();
+        let key = (method, Some(node_id.clone()).filter(|_| limit.per_peer));
+        let mut buckets = self.buckets.lock().unwrap_or_else(|err| err.into_inner());
+        if buckets.len() >= MAX_TRACKED_BUCKETS {
+            buckets.retain(|(method, _), bucket| {
+                self.limits
+                    .get(method)
+                    .map_or(false, |limit| !bucket.is_full(limit, now))
+            });
+        }
+
+        let bucket = buckets.entry(key).or_insert_with(|| Bucket::new(limit, now));
+        if bucket.try_take(limit, now) {
+            Ok(())
+        } else {
+            Err(RpcStatus::busy(&format!(
+                "Rate limit of {} requests per {:.0?} exceeded",
+                limit.max_requests, limit.interval
+            )))
+        }
+    }
+}
+
+impl RpcMiddleware for RateLimit {
+    fn handle(&self, request: Request<Bytes>, next: Next) -> RpcFuture {
+        let method = request.method().id();
+        if let Err(status) = self.try_acquire(method, request.context().peer_node_id()) {
+            debug!(
+                target: LOG_TARGET,
+                "Rejected request for method {} on protocol `{}` from peer `{}`: {}",
+                method,
+                String::from_utf8_lossy(next.protocol()),
+                request.context().peer_node_id(),
+                status
+            );
+            return Box::pin(futures::future::ready(Err(status)));
+        }
+        next.run(request)
+    }
+}
+
+/// A token bucket that holds up to `max_requests` tokens and refills completely every `interval`
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl Bucket {
+    fn new(limit: &RequestRateLimit, now: Instant) -> Self {
+        Self {
+            tokens: f64::from(limit.max_requests),
+            last_refill: now,
+        }
+    }
+
+    fn refill(&mut self, limit: &RequestRateLimit, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_refill);
+        let max = f64::from(limit.max_requests);
+        let rate = max / limit.interval.as_secs_f64().max(f64::EPSILON);
+        self.tokens = (self.tokens + elapsed.as_secs_f64() * rate).min(max);
+        self.last_refill = now;
+    }
+
+    fn try_take(&mut self, limit: &RequestRateLimit, now: Instant) -> bool {
+        self.refill(limit, now);
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn is_full(&mut self, limit: &RequestRateLimit, now: Instant) -> bool {
+        self.refill(limit, now);
+        self.tokens >= f64::from(limit.max_requests)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use tower::Service;
+
+    use super::*;
+    use crate::{
+        protocol::rpc::{
+            server::middleware::{
+                test::{request_from, service_with_middleware},
+                RpcMiddlewareStack,
+            },
+            RpcStatusCode,
+        },
+        test_utils::{build_peer_manager, node_id},
+    };
+
+    #[test]
+    fn it_refills_the_bucket() {
+        let limit = RequestRateLimit::per_peer(2, Duration::from_secs(10));
+        let start = Instant::now();
+        let mut bucket = Bucket::new(&limit, start);
+        assert!(bucket.try_take(&limit, start));
+        assert!(bucket.try_take(&limit, start));
+        assert!(!bucket.try_take(&limit, start));
+        // One token every 5 seconds
+        assert!(bucket.try_take(&limit, start + Duration::from_secs(5)));
+        assert!(!bucket.try_take(&limit, start + Duration::from_secs(5)));
+        assert!(bucket.is_full(&limit, start + Duration::from_secs(15)));
+    }
+
+    #[tokio::test]
+    async fn it_limits_requests_per_peer() {
+        let peer_manager = build_peer_manager();
+        let (peer1, peer2) = (node_id::random(), node_id::random());
+        let rate_limit = RateLimit::new()
+            .with_method_limit(1, RequestRateLimit::per_peer(1, Duration::from_secs(60)))
+            .with_method_limit(2, RequestRateLimit::global(1, Duration::from_secs(60)));
+        let mut service = service_with_middleware(RpcMiddlewareStack::new().layer(rate_limit));
+
+        service.call(request_from(&peer_manager, &peer1, 1)).await.unwrap();
+        let err = service.call(request_from(&peer_manager, &peer1, 1)).await.unwrap_err();
+        assert_eq!(err.as_status_code(), RpcStatusCode::Busy);
+        service.call(request_from(&peer_manager, &peer2, 1)).await.unwrap();
+
+        service.call(request_from(&peer_manager, &peer1, 2)).await.unwrap();
+        service.call(request_from(&peer_manager, &peer2, 2)).await.unwrap_err();
+
+        // Methods without a limit are unaffected
+        for _ in 0..5 {
+            service.call(request_from(&peer_manager, &peer1, 3)).await.unwrap();
+        }
+    }
+}
@@ -32,6 +32,8 @@ use handle::RpcServerRequest;
 #[cfg(feature = "metrics")]
 mod metrics;
 
+pub mod middleware;
+
 pub mod mock;
 
 mod early_close;
@@ -656,6 +658,14 @@ where
             debug!(target: LOG_TARGET, "({}) Client sent FIN.", self.logging_context_string);
             return Ok(());
         }
+        if msg_flags.is_credit() {
+            // Credit may be granted after the stream has completed
+            trace!(
+                target: LOG_TARGET,
+                "({}) Ignoring credit for completed request {}.", self.logging_context_string, request_id
+            );
+            return Ok(());
+        }
         if msg_flags.contains(RpcMessageFlags::ACK) {
             debug!(
                 target: LOG_TARGET,
@@ -679,11 +689,14 @@ where
             method.id()
         );
 
+        let credits = decoded_msg.credits;
+        let resume_from = decoded_msg.resume_from;
         let req = Request::with_context(
             self.create_request_context(request_id),
             method,
             decoded_msg.payload.into(),
-        );
+        )
+        .with_resume_from(resume_from);
 
         let service_call = log_timing(
             self.logging_context_string.clone(),
@@ -715,7 +728,8 @@ where
 
         match service_result {
             Ok(body) => {
-                self.process_body(request_id, deadline, body).await?;
+                self.process_body(request_id, deadline, credits, resume_from, body)
+                    .await?;
             },
             Err(err) => {
                 debug!(
@@ -742,10 +756,16 @@ where
         String::from_utf8_lossy(&self.protocol)
     }
 
+    /// Sends the response body to the client. If `credits` is non-zero, the stream is flow controlled and the server
+    /// waits for the client to grant more credits once they are used up. The first `resume_from` items are skipped,
+    /// less any that the service has already skipped.
+    #[allow(clippy::too_many_lines)]
     async fn process_body(
         &mut self,
         request_id: u32,
         deadline: Duration,
+        credits: u32,
+        resume_from: u64,
         body: Response<Body>,
     ) -> Result<(), RpcServerError> {
         trace!(target: LOG_TARGET, "Service call succeeded");
@@ -754,8 +774,23 @@ where
         let node_id = self.node_id.clone();
         #[cfg(feature = "metrics")]
         let protocol = self.protocol.clone();
+        let mut credits = Some(credits).filter(|c| *c > 0);
+        let mut num_to_skip = resume_from.saturating_sub(body.payload.offset());
+        if num_to_skip > 0 {
+            debug!(
+                target: LOG_TARGET,
+                "({}) Resuming stream, skipping {} item(s)", self.logging_context_string, num_to_skip
+            );
+        }
         let mut stream = body
             .into_message()
+            .filter(move |item| {
+                let is_skipped = num_to_skip > 0 && matches!(item, Ok(bytes) if !bytes.is_finished());
+                if is_skipped {
+                    num_to_skip -= 1;
+                }
+                future::ready(!is_skipped)
+            })
             .map(|result| into_response(request_id, result))
             .map(move |mut message| {
                 if message.payload.len() > rpc::max_response_payload_size() {
@@ -778,21 +813,31 @@ where
             );
             let timeout = time::sleep(deadline);
 
+            let is_out_of_credit = credits == Some(0);
+
             tokio::select! {
-                // Check if the client interrupted the outgoing stream
-                Err(err) = self.check_interruptions() => {
-                    match err {
-                        err @ RpcServerError::ClientInterruptedStream => {
+                // Check if the client interrupted the outgoing stream or granted more credit. If we are out of credit,
+                // wait for the client.
+                Some(signal) = self.next_client_signal(request_id, is_out_of_credit) => {
+                    match signal {
+                        Ok(granted) => {
+                            trace!(
+                                target: LOG_TARGET,
+                                "({}) Client granted {} credit(s)", self.logging_context_string, granted
+                            );
+                            credits = credits.map(|c| c.saturating_add(granted));
+                        },
+                        Err(err @ RpcServerError::ClientInterruptedStream) => {
                             debug!(target: LOG_TARGET, "Stream was interrupted by client: {}", err);
                             break;
                         },
-                        err => {
+                        Err(err) => {
                             error!(target: LOG_TARGET, "Stream was interrupted: {}", err);
                             return Err(err);
                         },
                     }
                 },
-                msg = next_item => {
+                msg = next_item, if !is_out_of_credit => {
                      match msg {
                          Some(msg) => {
                             #[cfg(feature = "metrics")]
@@ -805,6 +850,9 @@ where
                             );
 
                             self.framed.send(msg).await?;
+                            if let Some(c) = credits.as_mut() {
+                                *c = c.saturating_sub(1);
+                            }
                         },
                         None => {
                             trace!(target: LOG_TARGET, "{} Request complete", self.logging_context_string,);
@@ -816,9 +864,10 @@ where
                 _ = timeout => {
                      debug!(
                         target: LOG_TARGET,
-                        "({}) Failed to return result within client deadline ({:.0?})",
+                        "({}) Failed to return result within client deadline ({:.0?}){}",
                         self.logging_context_string,
-                        deadline
+                        deadline,
+                        if is_out_of_credit { " waiting for credit" } else { "" }
                     );
 
                     #[cfg(feature = "metrics")]
@@ -835,24 +884,26 @@ where
         Ok(())
     }
 
-    async fn check_interruptions(&mut self) -> Result<(), RpcServerError> {
-        let check = future::poll_fn(|cx| match Pin::new(&mut self.framed).poll_next(cx) {
+    /// Checks for a message from the client during a stream. Returns the number of credits granted by the client or an
+    /// error if the client interrupted the stream. If `wait` is false, None is returned if no message is available.
+    async fn next_client_signal(&mut self, request_id: u32, wait: bool) -> Option<Result<u32, RpcServerError>> {
+        future::poll_fn(|cx| match Pin::new(&mut self.framed).poll_next(cx) {
             Poll::Ready(Some(Ok(mut msg))) => {
                 let decoded_msg = match proto::rpc::RpcRequest::decode(&mut msg) {
                     Ok(msg) => msg,
                     Err(err) => {
                         error!(target: LOG_TARGET, "Client send MALFORMED response: {}", err);
-                        return Poll::Ready(Some(RpcServerError::UnexpectedIncomingMessageMalformed));
+                        return Poll::Ready(Some(Err(RpcServerError::UnexpectedIncomingMessageMalformed)));
                     },
                 };
                 let u8_bits = match u8::try_from(decoded_msg.flags) {
                     Ok(bits) => bits,
                     Err(err) => {
                         error!(target: LOG_TARGET, "Client send MALFORMED flags: {}", err);
-                        return Poll::Ready(Some(RpcServerError::ProtocolError(format!(
+                        return Poll::Ready(Some(Err(RpcServerError::ProtocolError(format!(
                             "invalid message flag: must be less than {}",
                             u8::MAX
-                        ))));
+                        )))));
                     },
                 };
 
@@ -860,28 +911,33 @@ where
                     Some(flags) => flags,
                     None => {
                         error!(target: LOG_TARGET, "Client send MALFORMED flags: {}", u8_bits);
-                        return Poll::Ready(Some(RpcServerError::ProtocolError(format!(
+                        return Poll::Ready(Some(Err(RpcServerError::ProtocolError(format!(
                             "invalid message flag, does not match any flags ({})",
                             u8_bits
-                        ))));
+                        )))));
                     },
                 };
                 if msg_flags.is_fin() {
-                    Poll::Ready(Some(RpcServerError::ClientInterruptedStream))
+                    Poll::Ready(Some(Err(RpcServerError::ClientInterruptedStream)))
+                } else if msg_flags.is_credit() {
+                    // Ignore credit granted for a previous request
+                    let granted = if decoded_msg.request_id == request_id {
+                        decoded_msg.credits
+                    } else {
+                        0
+                    };
+                    Poll::Ready(Some(Ok(granted)))
                 } else {
-                    Poll::Ready(Some(RpcServerError::UnexpectedIncomingMessage(decoded_msg)))
+                    Poll::Ready(Some(Err(RpcServerError::UnexpectedIncomingMessage(decoded_msg))))
                 }
             },
             Poll::Ready(Some(Err(err))) if err.kind() == io::ErrorKind::WouldBlock => Poll::Ready(None),
-            Poll::Ready(Some(Err(err))) => Poll::Ready(Some(RpcServerError::from(err))),
-            Poll::Ready(None) => Poll::Ready(Some(RpcServerError::StreamClosedByRemote)),
+            Poll::Ready(Some(Err(err))) => Poll::Ready(Some(Err(RpcServerError::from(err)))),
+            Poll::Ready(None) => Poll::Ready(Some(Err(RpcServerError::StreamClosedByRemote))),
+            Poll::Pending if wait => Poll::Pending,
             Poll::Pending => Poll::Ready(None),
         })
-        .await;
-        match check {
-            Some(err) => Err(err),
-            None => Ok(()),
-        }
+        .await
     }
 
     fn create_request_context(&self, request_id: u32) -> RequestContext {
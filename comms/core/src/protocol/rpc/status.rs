@@ -106,6 +106,24 @@ impl RpcStatus {
         }
     }
 
+    /// Returns an error indicating that the server is too busy to handle the request, e.g. because a rate or
+    /// concurrency limit was reached. The request may be retried later and the peer should not be penalised.
+    pub fn busy<T: ToString + ?Sized>(details: &T) -> Self {
+        Self {
+            code: RpcStatusCode::Busy,
+            details: details.to_string(),
+        }
+    }
+
+    /// Returns an error indicating that a stream ended before it completed. The stream may be resumed using its
+    /// [ResumptionToken](super::ResumptionToken).
+    pub fn interrupted<T: ToString + ?Sized>(details: &T) -> Self {
+        Self {
+            code: RpcStatusCode::Interrupted,
+            details: details.to_string(),
+        }
+    }
+
     /// Returns a closure that logs the given error and returns a generic general error that does not leak any
     /// potentially sensitive error information. Use this function with map_err to catch "miscellaneous" errors.
     pub fn log_internal_error<'a, E: std::error::Error + 'a>(target: &'a str) -> impl Fn(E) -> Self + 'a {
@@ -145,6 +163,24 @@ impl RpcStatus {
     pub fn is_not_found(&self) -> bool {
         self.code.is_not_found()
     }
+
+    pub fn is_interrupted(&self) -> bool {
+        self.code.is_interrupted()
+    }
+
+    pub fn is_timeout(&self) -> bool {
+        self.code.is_timeout()
+    }
+
+    pub fn is_busy(&self) -> bool {
+        self.code.is_busy()
+    }
+
+    /// Returns true if a stream failed because the session was lost or timed out rather than because of the peer's
+    /// response. The stream may be resumed using its [ResumptionToken](super::ResumptionToken).
+    pub fn is_resumable(&self) -> bool {
+        self.is_interrupted() || self.is_timeout()
+    }
 }
 
 impl Display for RpcStatus {
@@ -239,6 +275,10 @@ pub enum RpcStatusCode {
     Forbidden = 9,
     /// RPC conflict error
     Conflict = 10,
+    /// The stream ended before it completed, e.g. because the session was lost (client only)
+    Interrupted = 11,
+    /// The server is too busy to handle the request, try again later
+    Busy = 12,
     // The following status represents anything that is not recognised (i.e not one of the above codes).
     /// Unrecognised RPC status code
     InvalidRpcStatusCode,
@@ -257,6 +297,14 @@ impl RpcStatusCode {
         self == Self::Timeout
     }
 
+    pub fn is_interrupted(self) -> bool {
+        self == Self::Interrupted
+    }
+
+    pub fn is_busy(self) -> bool {
+        self == Self::Busy
+    }
+
     pub fn as_u32(&self) -> u32 {
         *self as u32
     }
@@ -282,6 +330,8 @@ impl From<u32> for RpcStatusCode {
             8 => ProtocolError,
             9 => Forbidden,
             10 => Conflict,
+            11 => Interrupted,
+            12 => Busy,
             _ => InvalidRpcStatusCode,
         }
     }
@@ -307,6 +357,8 @@ mod test {
         assert_eq!(RpcStatusCode::from(ProtocolError as u32), ProtocolError);
         assert_eq!(RpcStatusCode::from(Forbidden as u32), Forbidden);
         assert_eq!(RpcStatusCode::from(Conflict as u32), Conflict);
+        assert_eq!(RpcStatusCode::from(Interrupted as u32), Interrupted);
+        assert_eq!(RpcStatusCode::from(Busy as u32), Busy);
         assert_eq!(RpcStatusCode::from(123), InvalidRpcStatusCode);
     }
 
@@ -450,6 +450,13 @@ impl GreetingClient {
         self.inner.server_streaming(request, 8).await
     }
 
+    pub async fn resume_stream<R>(
+        &mut self,
+        token: __rpc_deps::ResumptionToken<R>,
+    ) -> Result<__rpc_deps::ClientStreaming<R>, RpcError> {
+        self.inner.resume_streaming(token).await
+    }
+
     pub fn get_last_request_latency(&mut self) -> Option<Duration> {
         self.inner.get_last_request_latency()
     }
@@ -22,7 +22,8 @@
 
 use std::{sync::Arc, time::Duration};
 
-use futures::StreamExt;
+use futures::{SinkExt, StreamExt};
+use prost::Message;
 use tari_shutdown::Shutdown;
 use tari_test_utils::unpack_enum;
 use tari_utilities::hex::Hex;
@@ -36,14 +37,18 @@ use tokio_stream::Stream;
 
 use crate::{
     framing,
+    framing::CanonicalFraming,
+    message::MessageExt,
     multiplexing::{Control, Yamux},
     peer_manager::NodeId,
+    proto,
     protocol::{
         rpc,
         rpc::{
             context::RpcCommsBackend,
             error::HandshakeRejectReason,
-            handshake::RpcHandshakeError,
+            handshake::{RpcHandshakeError, RPC_STREAM_CONTROL_VERSION},
+            message::RpcMessageFlags,
             server::NamedProtocolService,
             test::{
                 greeting_service::{
@@ -57,6 +62,7 @@ use crate::{
                 },
                 mock::create_mocked_rpc_context,
             },
+            Handshake,
             RpcError,
             RpcServer,
             RpcServerBuilder,
@@ -491,6 +497,112 @@ async fn stream_interruption_handling() {
         .unwrap();
 }
 
+/// Returns the next response sent by the server, or None if no response is received within a short time
+async fn next_response(framed: &mut CanonicalFraming<Substream>) -> Option<proto::rpc::RpcResponse> {
+    let msg = time::timeout(Duration::from_millis(500), framed.next()).await.ok()?;
+    Some(proto::rpc::RpcResponse::decode(msg.unwrap().unwrap()).unwrap())
+}
+
+#[tokio::test]
+async fn flow_controlled_stream() {
+    let (_inbound, outbound, _, _, _shutdown) = setup(GreetingService::default(), 2).await;
+    let socket = outbound.get_yamux_control().open_stream().await.unwrap();
+
+    let framed = framing::canonical(socket, 1024);
+    let mut client = GreetingClient::builder()
+        .with_deadline(Duration::from_secs(5))
+        .with_stream_credits(1)
+        .connect(framed)
+        .await
+        .unwrap();
+
+    let resp = client
+        .slow_stream(SlowStreamRequest {
+            num_items: 100,
+            item_size: 100,
+            delay_ms: 0,
+        })
+        .await
+        .unwrap();
+    let items = resp.collect::<Vec<_>>().await;
+    assert_eq!(items.len(), 100);
+    assert!(items.iter().all(|item| item.as_ref().unwrap().len() == 100));
+
+    // Credit granted for the completed stream does not affect subsequent requests
+    let resp = client
+        .say_hello(SayHelloRequest {
+            name: "Yathvan".to_string(),
+            language: 1,
+        })
+        .await
+        .unwrap();
+    assert_eq!(resp.greeting, "Jambo Yathvan");
+
+    // The server stops streaming when it runs out of credit and continues once the client grants more
+    let socket = outbound.get_yamux_control().open_stream().await.unwrap();
+    let mut framed = framing::canonical(socket, 1024);
+    let version = Handshake::new(&mut framed).perform_client_handshake().await.unwrap();
+    assert_eq!(version, RPC_STREAM_CONTROL_VERSION);
+    let request = proto::rpc::RpcRequest {
+        request_id: 1,
+        method: 8,
+        deadline: 5,
+        credits: 2,
+        payload: SlowStreamRequest {
+            num_items: 10,
+            item_size: 100,
+            delay_ms: 0,
+        }
+        .to_encoded_bytes(),
+        ..Default::default()
+    };
+    framed.send(request.to_encoded_bytes().into()).await.unwrap();
+    for _ in 0..2 {
+        let resp = next_response(&mut framed).await.unwrap();
+        assert_eq!(resp.status, 0);
+        assert!(!resp.payload.is_empty());
+    }
+    assert!(next_response(&mut framed).await.is_none());
+
+    let credit = proto::rpc::RpcRequest {
+        request_id: 1,
+        method: 8,
+        flags: RpcMessageFlags::CREDIT.bits().into(),
+        deadline: 5,
+        credits: 3,
+        ..Default::default()
+    };
+    framed.send(credit.to_encoded_bytes().into()).await.unwrap();
+    for _ in 0..3 {
+        let resp = next_response(&mut framed).await.unwrap();
+        assert_eq!(resp.status, 0);
+        assert!(!resp.payload.is_empty());
+    }
+    assert!(next_response(&mut framed).await.is_none());
+}
+
+#[tokio::test]
+async fn resume_stream_in_new_session() {
+    let (_inbound, outbound, _, _, _shutdown) = setup(GreetingService::default(), 2).await;
+    let socket = outbound.get_yamux_control().open_stream().await.unwrap();
+    let mut client = GreetingClient::connect(framing::canonical(socket, 1024)).await.unwrap();
+
+    let mut resp = client.get_greetings(8).await.unwrap();
+    for _ in 0..3 {
+        resp.next().await.unwrap().unwrap();
+    }
+    let token = resp.resumption_token().unwrap();
+    assert_eq!(token.position(), 3);
+    drop(resp);
+    client.close().await;
+
+    let socket = outbound.get_yamux_control().open_stream().await.unwrap();
+    let mut client = GreetingClient::connect(framing::canonical(socket, 1024)).await.unwrap();
+    let resp = client.resume_stream(token).await.unwrap();
+    let greetings = resp.map(|r| r.unwrap()).collect::<Vec<_>>().await;
+    assert_eq!(greetings, GreetingService::DEFAULT_GREETINGS[3..]);
+}
+
 #[tokio::test]
 async fn max_global_sessions() {
     let builder = RpcServer::builder().with_maximum_simultaneous_sessions(1);
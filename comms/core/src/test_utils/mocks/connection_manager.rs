@@ -127,7 +127,7 @@ impl ConnectionManagerMock {
     }
 
     async fn handle_request(&self, req: ConnectionManagerRequest) {
-        use ConnectionManagerRequest::{CancelDial, DialPeer, NotifyListening};
+        use ConnectionManagerRequest::{CancelDial, DialPeer, GetBandwidthUsage, NotifyListening};
         self.state.inc_call_count();
         self.state.add_call(format!("{:?}", req)).await;
         match req {
@@ -145,6 +145,9 @@ impl ConnectionManagerMock {
             },
             CancelDial(_) => {},
             NotifyListening(_reply_tx) => {},
+            GetBandwidthUsage(reply_tx) => {
+                let _result = reply_tx.send(Default::default());
+            },
         }
     }
 }
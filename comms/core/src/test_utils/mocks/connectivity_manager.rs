@@ -37,7 +37,7 @@ use crate::{
         ConnectivityRequester,
         ConnectivityStatus,
     },
-    peer_manager::NodeId,
+    peer_manager::{NodeId, ReputationEvent},
 };
 
 pub fn create_connectivity_mock() -> (ConnectivityRequester, ConnectivityManagerMock) {
@@ -63,6 +63,7 @@ struct State {
     pending_conns: HashMap<NodeId, Vec<oneshot::Sender<Result<PeerConnection, ConnectionManagerError>>>>,
     selected_connections: Vec<PeerConnection>,
     banned_peers: Vec<(NodeId, Duration, String)>,
+    reported_peers: Vec<(NodeId, ReputationEvent, String)>,
     connectivity_status: ConnectivityStatus,
 }
 
@@ -194,6 +195,10 @@ impl ConnectivityManagerMockState {
         self.with_state(|state| state.banned_peers.drain(..).collect()).await
     }
 
+    pub async fn take_reported_peers(&self) -> Vec<(NodeId, ReputationEvent, String)> {
+        self.with_state(|state| state.reported_peers.drain(..).collect()).await
+    }
+
     pub(self) async fn with_state<F, R>(&self, f: F) -> R
     where F: FnOnce(&mut State) -> R {
         let mut lock = self.inner.lock().await;
@@ -289,6 +294,13 @@ impl ConnectivityManagerMock {
                     })
                     .await
             },
+            ReportPeer(node_id, event, reason) => {
+                self.state
+                    .with_state(|state| {
+                        state.reported_peers.push((node_id, event, reason));
+                    })
+                    .await
+            },
             AddPeerToAllowList(_) => {},
             RemovePeerFromAllowList(_) => {},
             GetActiveConnections(reply) => {
@@ -29,6 +29,8 @@
 //! Provides an abstraction for [Transport](self::Transport)s and several implemenations:
 //! - [TCP](self::TcpTransport) - communication over TCP and IP4/IP6 and DNS
 //! - [SOCKS](self::SocksTransport) - communication over a SOCKS5 proxy.
+//! - [QUIC](self::QuicTransport) - communication over QUIC, which provides its own encryption and stream multiplexing.
+//!   Only available with the `quic` feature.
 //! - [Memory](self::MemoryTransport) - in-process communication (mpsc channel), typically for testing.
 
 use multiaddr::Multiaddr;
@@ -41,6 +43,11 @@ pub mod predicate;
 mod memory;
 pub use memory::MemoryTransport;
 
+#[cfg(feature = "quic")]
+mod quic;
+#[cfg(feature = "quic")]
+pub use quic::{QuicConfig, QuicConnection, QuicTransport, QuicTransportError};
+
 mod socks;
 pub use socks::{SocksConfig, SocksTransport};
 
@@ -67,4 +74,11 @@ pub trait Transport {
 
     /// Connect (dial) to the given multiaddr
     async fn dial(&self, addr: &Multiaddr) -> Result<Self::Output, Self::Error>;
+
+    /// Returns the connection if this transport authenticates, encrypts and multiplexes connections itself, in which
+    /// case the noise and yamux upgrades are skipped. Other transports return the output unchanged.
+    #[cfg(feature = "quic")]
+    fn into_quic_connection(output: Self::Output) -> Result<QuicConnection, Self::Output> {
+        Err(output)
+    }
 }
@@ -0,0 +1,150 @@
+// Copyright 2024 The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+use std::{
+    io,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use blake2::Blake2b;
+use digest::consts::U64;
+use rand::rngs::OsRng;
+use tari_crypto::{hashing::DomainSeparatedHasher, keys::PublicKey};
+use tari_utilities::ByteArray;
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, ReadBuf},
+    time,
+};
+
+use super::{tls::TlsCertificate, QuicTransportError};
+use crate::{
+    multiplexing::QuicStream,
+    peer_manager::NodeIdentity,
+    types::{CommsCoreHashDomain, CommsPublicKey, CommsSecretKey, Signature},
+};
+
+/// public key || public nonce || signature
+const IDENTITY_BINDING_LEN: usize = 3 * 32;
+const AUTHENTICATION_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// An established QUIC connection. Reads and writes go over the first bidirectional stream (the control stream), which
+/// carries the wire mode byte, the identity binding and the peer identity exchange before the connection is
+/// multiplexed.
+pub struct QuicConnection {
+    connection: quinn::Connection,
+    control_stream: QuicStream,
+    local_certificate: Arc<TlsCertificate>,
+}
+
+impl QuicConnection {
+    pub(super) fn new(
+        connection: quinn::Connection,
+        control_stream: QuicStream,
+        local_certificate: Arc<TlsCertificate>,
+    ) -> Self {
+        Self {
+            connection,
+            control_stream,
+            local_certificate,
+        }
+    }
+
+    /// Binds the node identities of both sides to the TLS certificates used for the connection. Each side signs its
+    /// own certificate with its node identity and checks the peer's signature against the certificate the peer
+    /// presented in the TLS handshake. Returns the authenticated public key of the peer.
+    pub async fn authenticate(&mut self, node_identity: &NodeIdentity) -> Result<CommsPublicKey, QuicTransportError> {
+        time::timeout(AUTHENTICATION_TIMEOUT, self.exchange_identity_binding(node_identity))
+            .await
+            .map_err(|_| QuicTransportError::AuthenticationTimeout)?
+    }
+
+    async fn exchange_identity_binding(
+        &mut self,
+        node_identity: &NodeIdentity,
+    ) -> Result<CommsPublicKey, QuicTransportError> {
+        let (secret_nonce, public_nonce) = CommsPublicKey::random_keypair(&mut OsRng);
+        let challenge = construct_challenge(
+            node_identity.public_key(),
+            &public_nonce,
+            self.local_certificate.as_der(),
+        )
+        .finalize();
+        let signature = Signature::sign_raw_uniform(node_identity.secret_key(), secret_nonce, challenge.as_ref())
+            .expect("unreachable panic: challenge hash digest is the correct length");
+
+        let mut msg = Vec::with_capacity(IDENTITY_BINDING_LEN);
+        msg.extend_from_slice(node_identity.public_key().as_bytes());
+        msg.extend_from_slice(signature.get_public_nonce().as_bytes());
+        msg.extend_from_slice(signature.get_signature().as_bytes());
+        self.control_stream.write_all(&msg).await?;
+        self.control_stream.flush().await?;
+
+        let mut buf = [0u8; IDENTITY_BINDING_LEN];
+        self.control_stream.read_exact(&mut buf).await?;
+        let public_key = CommsPublicKey::from_canonical_bytes(&buf[..32])
+            .map_err(|_| QuicTransportError::InvalidIdentityBinding("invalid public key"))?;
+        let public_nonce = CommsPublicKey::from_canonical_bytes(&buf[32..64])
+            .map_err(|_| QuicTransportError::InvalidIdentityBinding("invalid public nonce"))?;
+        let signature = CommsSecretKey::from_canonical_bytes(&buf[64..])
+            .map_err(|_| QuicTransportError::InvalidIdentityBinding("invalid signature"))?;
+        let signature = Signature::new(public_nonce, signature);
+
+        let peer_certificate = self.peer_certificate()?;
+        let challenge = construct_challenge(&public_key, signature.get_public_nonce(), &peer_certificate).finalize();
+        if !signature.verify_raw_uniform(&public_key, challenge.as_ref()) {
+            return Err(QuicTransportError::InvalidIdentityBinding(
+                "signature does not match the TLS certificate",
+            ));
+        }
+
+        Ok(public_key)
+    }
+
+    /// The DER encoded certificate the peer presented in the TLS handshake
+    fn peer_certificate(&self) -> Result<Vec<u8>, QuicTransportError> {
+        self.connection
+            .peer_identity()
+            .and_then(|identity| identity.downcast::<Vec<rustls::Certificate>>().ok())
+            .and_then(|certs| certs.first().map(|cert| cert.0.clone()))
+            .ok_or(QuicTransportError::NoPeerCertificate)
+    }
+
+    /// Consumes this connection, closing the control stream, and returns the underlying QUIC connection
+    pub fn into_connection(self) -> quinn::Connection {
+        self.connection
+    }
+}
+
+fn construct_challenge(
+    public_key: &CommsPublicKey,
+    public_nonce: &CommsPublicKey,
+    certificate: &[u8],
+) -> DomainSeparatedHasher<Blake2b<U64>, CommsCoreHashDomain> {
+    DomainSeparatedHasher::<Blake2b<U64>, CommsCoreHashDomain>::new_with_label("quic.identity_binding")
+        .chain(public_key.as_bytes())
+        .chain(public_nonce.as_bytes())
+        .chain(certificate)
+}
+
+impl AsyncRead for QuicConnection {
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.control_stream).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for QuicConnection {
+    fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.control_stream).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.control_stream).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.control_stream).poll_shutdown(cx)
+    }
+}
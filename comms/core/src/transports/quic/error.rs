@@ -0,0 +1,30 @@
+// Copyright 2024 The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+use std::io;
+
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum QuicTransportError {
+    #[error("IO error: {0}")]
+    Io(#[from] io::Error),
+    #[error("Failed to generate TLS certificate: {0}")]
+    CertificateGenerationFailed(#[from] rcgen::RcgenError),
+    #[error("TLS configuration error: {0}")]
+    TlsError(#[from] rustls::Error),
+    #[error("Invalid transport configuration: {0}")]
+    InvalidConfig(String),
+    #[error("Failed to connect: {0}")]
+    ConnectError(#[from] quinn::ConnectError),
+    #[error("Connection error: {0}")]
+    ConnectionError(#[from] quinn::ConnectionError),
+    #[error("The peer did not open the control stream in time")]
+    ControlStreamTimeout,
+    #[error("The peer did not present a TLS certificate")]
+    NoPeerCertificate,
+    #[error("The peer failed to bind its node identity to its TLS certificate: {0}")]
+    InvalidIdentityBinding(&'static str),
+    #[error("Timed out waiting for the peer to authenticate")]
+    AuthenticationTimeout,
+}
@@ -0,0 +1,251 @@
+// Copyright 2024 The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+//! QUIC transport. Connections are encrypted by TLS 1.3 using a self-signed certificate, to which each node binds its
+//! node identity, and substreams map directly onto QUIC streams instead of being multiplexed by yamux.
+
+mod connection;
+pub use connection::QuicConnection;
+
+mod error;
+pub use error::QuicTransportError;
+
+mod tls;
+
+use std::{
+    net::{Ipv4Addr, Ipv6Addr, SocketAddr},
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use log::*;
+use multiaddr::Multiaddr;
+use quinn::{IdleTimeout, VarInt};
+use tokio::{
+    sync::{mpsc, Mutex},
+    time,
+};
+use tokio_stream::Stream;
+
+use self::tls::TlsCertificate;
+use super::Transport;
+use crate::{
+    multiplexing::QuicStream,
+    utils::multiaddr::{multiaddr_to_quic_socketaddr, socketaddr_to_quic_multiaddr},
+};
+
+const LOG_TARGET: &str = "comms::transports::quic";
+
+/// The dialer opens the control stream and writes its wire mode byte as soon as the connection is established
+const CONTROL_STREAM_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Configuration for the [QuicTransport]
+#[derive(Debug, Clone)]
+pub struct QuicConfig {
+    /// Interval at which keep-alive packets are sent on idle connections
+    pub keep_alive_interval: Duration,
+    /// A connection is closed when nothing has been received from the peer for this long
+    pub max_idle_timeout: Duration,
+    /// The maximum number of substreams the peer may have open on a connection at once
+    pub max_concurrent_substreams: u32,
+}
+
+impl Default for QuicConfig {
+    fn default() -> Self {
+        Self {
+            keep_alive_interval: Duration::from_secs(10),
+            max_idle_timeout: Duration::from_secs(60),
+            max_concurrent_substreams: 512,
+        }
+    }
+}
+
+/// Transport implementation for QUIC
+#[derive(Clone)]
+pub struct QuicTransport {
+    certificate: Arc<TlsCertificate>,
+    transport_config: Arc<quinn::TransportConfig>,
+    endpoint: Arc<Mutex<Option<quinn::Endpoint>>>,
+}
+
+impl QuicTransport {
+    /// Create a new QuicTransport with a freshly generated TLS certificate
+    pub fn new(config: QuicConfig) -> Result<Self, QuicTransportError> {
+        let max_idle_timeout = IdleTimeout::try_from(config.max_idle_timeout)
+            .map_err(|_| QuicTransportError::InvalidConfig("max_idle_timeout is too large".to_string()))?;
+        let mut transport_config = quinn::TransportConfig::default();
+        transport_config
+            .keep_alive_interval(Some(config.keep_alive_interval))
+            .max_idle_timeout(Some(max_idle_timeout))
+            .max_concurrent_bidi_streams(VarInt::from_u32(config.max_concurrent_substreams))
+            .max_concurrent_uni_streams(VarInt::from_u32(0));
+
+        Ok(Self {
+            certificate: Arc::new(TlsCertificate::generate()?),
+            transport_config: Arc::new(transport_config),
+            endpoint: Arc::new(Mutex::new(None)),
+        })
+    }
+
+    /// Outbound connections are made from the listening endpoint if there is one, so that peers see the UDP port this
+    /// node listens on. Otherwise, or if the address family differs, a client-only endpoint is used.
+    async fn outbound_endpoint(&self, remote_addr: &SocketAddr) -> Result<quinn::Endpoint, QuicTransportError> {
+        let mut lock = self.endpoint.lock().await;
+        if let Some(endpoint) = lock.as_ref() {
+            if endpoint.local_addr()?.is_ipv4() == remote_addr.is_ipv4() {
+                return Ok(endpoint.clone());
+            }
+        }
+
+        let bind_addr: SocketAddr = if remote_addr.is_ipv4() {
+            (Ipv4Addr::UNSPECIFIED, 0).into()
+        } else {
+            (Ipv6Addr::UNSPECIFIED, 0).into()
+        };
+        let mut endpoint = quinn::Endpoint::client(bind_addr)?;
+        endpoint.set_default_client_config(self.certificate.client_config(self.transport_config.clone())?);
+        if lock.is_none() {
+            *lock = Some(endpoint.clone());
+        }
+        Ok(endpoint)
+    }
+}
+
+#[crate::async_trait]
+impl Transport for QuicTransport {
+    type Error = QuicTransportError;
+    type Listener = QuicInbound;
+    type Output = QuicConnection;
+
+    async fn listen(&self, addr: &Multiaddr) -> Result<(Self::Listener, Multiaddr), Self::Error> {
+        let socket_addr = multiaddr_to_quic_socketaddr(addr)?;
+        let mut endpoint = quinn::Endpoint::server(
+            self.certificate.server_config(self.transport_config.clone())?,
+            socket_addr,
+        )?;
+        endpoint.set_default_client_config(self.certificate.client_config(self.transport_config.clone())?);
+        let local_addr = socketaddr_to_quic_multiaddr(&endpoint.local_addr()?);
+        *self.endpoint.lock().await = Some(endpoint.clone());
+        Ok((QuicInbound::spawn(endpoint, self.certificate.clone()), local_addr))
+    }
+
+    async fn dial(&self, addr: &Multiaddr) -> Result<Self::Output, Self::Error> {
+        let socket_addr = multiaddr_to_quic_socketaddr(addr)?;
+        let endpoint = self.outbound_endpoint(&socket_addr).await?;
+        let connection = endpoint.connect(socket_addr, tls::SERVER_NAME)?.await?;
+        let (send, recv) = connection.open_bi().await?;
+        Ok(QuicConnection::new(
+            connection,
+            QuicStream::new(send, recv),
+            self.certificate.clone(),
+        ))
+    }
+
+    fn into_quic_connection(output: Self::Output) -> Result<QuicConnection, Self::Output> {
+        Ok(output)
+    }
+}
+
+/// Emits inbound QUIC connections once the TLS handshake has completed and the dialer has opened the control stream
+pub struct QuicInbound {
+    incoming: mpsc::Receiver<Result<(QuicConnection, Multiaddr), QuicTransportError>>,
+}
+
+impl QuicInbound {
+    fn spawn(endpoint: quinn::Endpoint, certificate: Arc<TlsCertificate>) -> Self {
+        let (tx, rx) = mpsc::channel(10);
+        tokio::spawn(accept_connections(endpoint, certificate, tx));
+        Self { incoming: rx }
+    }
+}
+
+impl Stream for QuicInbound {
+    type Item = Result<(QuicConnection, Multiaddr), QuicTransportError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.incoming.poll_recv(cx)
+    }
+}
+
+async fn accept_connections(
+    endpoint: quinn::Endpoint,
+    certificate: Arc<TlsCertificate>,
+    tx: mpsc::Sender<Result<(QuicConnection, Multiaddr), QuicTransportError>>,
+) {
+    loop {
+        tokio::select! {
+            _ = tx.closed() => break,
+            connecting = endpoint.accept() => {
+                match connecting {
+                    Some(connecting) => {
+                        let tx = tx.clone();
+                        let certificate = certificate.clone();
+                        // Handshakes are completed concurrently so that a slow peer does not hold up the others
+                        tokio::spawn(async move {
+                            let _result = tx.send(accept_connection(connecting, certificate).await).await;
+                        });
+                    },
+                    None => {
+                        debug!(target: LOG_TARGET, "QUIC endpoint closed, no longer accepting connections");
+                        break;
+                    },
+                }
+            },
+        }
+    }
+}
+
+async fn accept_connection(
+    connecting: quinn::Connecting,
+    certificate: Arc<TlsCertificate>,
+) -> Result<(QuicConnection, Multiaddr), QuicTransportError> {
+    let peer_addr = socketaddr_to_quic_multiaddr(&connecting.remote_address());
+    let connection = connecting.await?;
+    let (send, recv) = time::timeout(CONTROL_STREAM_TIMEOUT, connection.accept_bi())
+        .await
+        .map_err(|_| QuicTransportError::ControlStreamTimeout)??;
+    Ok((
+        QuicConnection::new(connection, QuicStream::new(send, recv), certificate),
+        peer_addr,
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio_stream::StreamExt;
+
+    use super::*;
+    use crate::{peer_manager::PeerFeatures, test_utils::node_identity::build_node_identity};
+
+    #[tokio::test]
+    async fn authenticate() {
+        let transport = QuicTransport::new(QuicConfig::default()).unwrap();
+        let (mut listener, addr) = transport
+            .listen(&"/ip4/127.0.0.1/udp/0/quic".parse().unwrap())
+            .await
+            .unwrap();
+        let node_identity1 = build_node_identity(PeerFeatures::COMMUNICATION_NODE);
+        let node_identity2 = build_node_identity(PeerFeatures::COMMUNICATION_NODE);
+
+        let dialer = QuicTransport::new(QuicConfig::default()).unwrap();
+        let dial_task = tokio::spawn({
+            let node_identity1 = node_identity1.clone();
+            async move {
+                let mut connection = dialer.dial(&addr).await.unwrap();
+                connection.write_all(&[123]).await.unwrap();
+                connection.authenticate(&node_identity1).await.unwrap()
+            }
+        });
+
+        let (mut connection, _) = listener.next().await.unwrap().unwrap();
+        let mut buf = [0u8; 1];
+        connection.read_exact(&mut buf).await.unwrap();
+        assert_eq!(buf[0], 123);
+        let public_key = connection.authenticate(&node_identity2).await.unwrap();
+        assert_eq!(&public_key, node_identity1.public_key());
+        assert_eq!(&dial_task.await.unwrap(), node_identity2.public_key());
+    }
+}
@@ -0,0 +1,124 @@
+// Copyright 2024 The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+use std::{sync::Arc, time::SystemTime};
+
+use rustls::{
+    client::{ServerCertVerified, ServerCertVerifier},
+    server::{ClientCertVerified, ClientCertVerifier},
+    Certificate,
+    DistinguishedNames,
+    PrivateKey,
+    ServerName,
+};
+
+/// ALPN protocol identifier negotiated by Tari QUIC connections
+const ALPN_PROTOCOL: &[u8] = b"tari-comms/1";
+/// The server name sent by dialers. Certificates are self-signed, so this is never checked.
+pub(super) const SERVER_NAME: &str = "tari";
+
+/// A self-signed certificate used for the TLS handshake. It is not issued by any authority: peers bind their node
+/// identity to it by signing it after the handshake (see [QuicConnection::authenticate](super::QuicConnection)).
+pub(super) struct TlsCertificate {
+    certificate: Certificate,
+    private_key: PrivateKey,
+}
+
+impl TlsCertificate {
+    /// Generate a new random self-signed certificate
+    pub fn generate() -> Result<Self, rcgen::RcgenError> {
+        let cert = rcgen::generate_simple_self_signed(vec![SERVER_NAME.to_string()])?;
+        Ok(Self {
+            certificate: Certificate(cert.serialize_der()?),
+            private_key: PrivateKey(cert.serialize_private_key_der()),
+        })
+    }
+
+    /// The DER encoding of the certificate, as the peer receives it in the TLS handshake
+    pub fn as_der(&self) -> &[u8] {
+        &self.certificate.0
+    }
+
+    pub fn server_config(
+        &self,
+        transport_config: Arc<quinn::TransportConfig>,
+    ) -> Result<quinn::ServerConfig, rustls::Error> {
+        let mut crypto = rustls::ServerConfig::builder()
+            .with_safe_default_cipher_suites()
+            .with_safe_default_kx_groups()
+            .with_protocol_versions(&[&rustls::version::TLS13])?
+            .with_client_cert_verifier(Arc::new(SelfSignedCertificateVerifier))
+            .with_single_cert(vec![self.certificate.clone()], self.private_key.clone())?;
+        crypto.alpn_protocols = vec![ALPN_PROTOCOL.to_vec()];
+        let mut config = quinn::ServerConfig::with_crypto(Arc::new(crypto));
+        config.transport_config(transport_config);
+        Ok(config)
+    }
+
+    pub fn client_config(
+        &self,
+        transport_config: Arc<quinn::TransportConfig>,
+    ) -> Result<quinn::ClientConfig, rustls::Error> {
+        let mut crypto = rustls::ClientConfig::builder()
+            .with_safe_default_cipher_suites()
+            .with_safe_default_kx_groups()
+            .with_protocol_versions(&[&rustls::version::TLS13])?
+            .with_custom_certificate_verifier(Arc::new(SelfSignedCertificateVerifier))
+            .with_single_cert(vec![self.certificate.clone()], self.private_key.clone())?;
+        crypto.alpn_protocols = vec![ALPN_PROTOCOL.to_vec()];
+        let mut config = quinn::ClientConfig::new(Arc::new(crypto));
+        config.transport_config(transport_config);
+        Ok(config)
+    }
+}
+
+/// Accepts any single self-signed certificate, from both servers and clients. The TLS handshake still proves that the
+/// peer holds the certificate's private key; which node the certificate belongs to is established afterwards.
+struct SelfSignedCertificateVerifier;
+
+impl SelfSignedCertificateVerifier {
+    fn check_chain(intermediates: &[Certificate]) -> Result<(), rustls::Error> {
+        if intermediates.is_empty() {
+            Ok(())
+        } else {
+            Err(rustls::Error::General(
+                "expected a single self-signed certificate".to_string(),
+            ))
+        }
+    }
+}
+
+impl ServerCertVerifier for SelfSignedCertificateVerifier {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &Certificate,
+        intermediates: &[Certificate],
+        _server_name: &ServerName,
+        _scts: &mut dyn Iterator<Item = &[u8]>,
+        _ocsp_response: &[u8],
+        _now: SystemTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Self::check_chain(intermediates)?;
+        Ok(ServerCertVerified::assertion())
+    }
+}
+
+impl ClientCertVerifier for SelfSignedCertificateVerifier {
+    fn client_auth_mandatory(&self) -> Option<bool> {
+        Some(true)
+    }
+
+    fn client_auth_root_subjects(&self) -> Option<DistinguishedNames> {
+        Some(DistinguishedNames::new())
+    }
+
+    fn verify_client_cert(
+        &self,
+        _end_entity: &Certificate,
+        intermediates: &[Certificate],
+        _now: SystemTime,
+    ) -> Result<ClientCertVerified, rustls::Error> {
+        Self::check_chain(intermediates)?;
+        Ok(ClientCertVerified::assertion())
+    }
+}
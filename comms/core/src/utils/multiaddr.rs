@@ -79,6 +79,39 @@ pub fn socketaddr_to_multiaddr(socket_addr: &SocketAddr) -> Multiaddr {
     addr
 }
 
+/// Convert a QUIC multiaddr (e.g. `/ip4/127.0.0.1/udp/18189/quic`) to the UDP socket address of the QUIC endpoint.
+/// This function resolves DNS4 addresses to an ip address.
+pub fn multiaddr_to_quic_socketaddr(addr: &Multiaddr) -> io::Result<SocketAddr> {
+    let invalid = || io::Error::new(io::ErrorKind::InvalidInput, format!("Invalid QUIC address '{}'", addr));
+    let mut addr_iter = addr.iter();
+    let socket_addr = match (addr_iter.next(), addr_iter.next()) {
+        (Some(Protocol::Dns4(domain)), Some(Protocol::Udp(port))) => format!("{}:{}", domain, port)
+            .to_socket_addrs()
+            .map_err(|_e| io::Error::new(io::ErrorKind::InvalidInput, format!("Invalid domain '{}'", domain)))?
+            .next()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, format!("Invalid domain '{}'", domain)))?,
+        (Some(Protocol::Ip4(host)), Some(Protocol::Udp(port))) => (host, port).into(),
+        (Some(Protocol::Ip6(host)), Some(Protocol::Udp(port))) => (host, port).into(),
+        _ => return Err(invalid()),
+    };
+
+    match (addr_iter.next(), addr_iter.next()) {
+        (Some(Protocol::Quic), None) => Ok(socket_addr),
+        _ => Err(invalid()),
+    }
+}
+
+/// Convert a UDP socket address to a QUIC multiaddress
+pub fn socketaddr_to_quic_multiaddr(socket_addr: &SocketAddr) -> Multiaddr {
+    let mut addr: Multiaddr = match socket_addr.ip() {
+        IpAddr::V4(addr) => Protocol::Ip4(addr).into(),
+        IpAddr::V6(addr) => Protocol::Ip6(addr).into(),
+    };
+    addr.push(Protocol::Udp(socket_addr.port()));
+    addr.push(Protocol::Quic);
+    addr
+}
+
 #[cfg(test)]
 mod test {
     use std::{net::Ipv4Addr, str::FromStr};
@@ -122,6 +155,28 @@ mod test {
         expect_fail("/dns4/doesntexist.theresnotldlikethis/tcp/1234")
     }
 
+    #[test]
+    fn quic_multiaddr_to_socketaddr() {
+        let addr = Multiaddr::from_str("/ip4/127.0.0.1/udp/18189/quic").unwrap();
+        let sock_addr = super::multiaddr_to_quic_socketaddr(&addr).unwrap();
+        assert_eq!(sock_addr, "127.0.0.1:18189".parse().unwrap());
+        assert_eq!(super::socketaddr_to_quic_multiaddr(&sock_addr), addr);
+
+        let addr = Multiaddr::from_str("/ip6/::1/udp/1234/quic").unwrap();
+        let sock_addr = super::multiaddr_to_quic_socketaddr(&addr).unwrap();
+        assert_eq!(super::socketaddr_to_quic_multiaddr(&sock_addr), addr);
+
+        for addr in [
+            "/ip4/127.0.0.1/tcp/1234",
+            "/ip4/127.0.0.1/udp/1234",
+            "/ip4/127.0.0.1/udp/1234/quic/quic",
+        ] {
+            let addr = Multiaddr::from_str(addr).unwrap();
+            let err = super::multiaddr_to_quic_socketaddr(&addr).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        }
+    }
+
     #[test]
     fn multiaddr_from_components() {
         let ip: Ipv4Addr = "127.0.0.1".parse().unwrap();
@@ -36,7 +36,16 @@ use tari_comms::{
     connection_manager::ConnectionManagerError,
     connectivity::{ConnectivityError, ConnectivityRequester, ConnectivitySelection},
     net_address::MultiaddrRange,
-    peer_manager::{NodeId, NodeIdentity, PeerFeatures, PeerManager, PeerManagerError, PeerQuery, PeerQuerySortBy},
+    peer_manager::{
+        NodeId,
+        NodeIdentity,
+        PeerFeatures,
+        PeerManager,
+        PeerManagerError,
+        PeerQuery,
+        PeerQuerySortBy,
+        ReputationEvent,
+    },
     types::CommsPublicKey,
     PeerConnection,
 };
@@ -135,6 +144,16 @@ pub enum OffenceSeverity {
     High,
 }
 
+impl OffenceSeverity {
+    /// Returns the reputation event that is reported for an offence of this severity
+    pub fn reputation_event(self) -> ReputationEvent {
+        match self {
+            OffenceSeverity::Low | OffenceSeverity::Medium => ReputationEvent::ProtocolViolation,
+            OffenceSeverity::High => ReputationEvent::MaliciousBehaviour,
+        }
+    }
+}
+
 impl Display for DhtRequest {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         #[allow(clippy::enum_glob_use)]
@@ -519,10 +538,13 @@ impl DhtActor {
                 reason,
             } => {
                 let mut connectivity = self.connectivity.clone();
-                let ban_duration = self.config.ban_duration_from_severity(severity);
                 Box::pin(async move {
                     connectivity
-                        .ban_peer_until(NodeId::from_public_key(&public_key), ban_duration, reason)
+                        .report_peer(
+                            NodeId::from_public_key(&public_key),
+                            severity.reputation_event(),
+                            reason,
+                        )
                         .await?;
                     Ok(())
                 })
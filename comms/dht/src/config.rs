@@ -27,7 +27,6 @@ use tari_common::configuration::serializers;
 use tari_comms::{net_address::MultiaddrRangeList, peer_validator::PeerValidatorConfig};
 
 use crate::{
-    actor::OffenceSeverity,
     network_discovery::NetworkDiscoveryConfig,
     storage::DbConnectionUrl,
     store_forward::SafConfig,
@@ -86,16 +85,12 @@ pub struct DhtConfig {
     pub connectivity: DhtConnectivityConfig,
     /// Network discovery config
     pub network_discovery: NetworkDiscoveryConfig,
-    /// Length of time to ban a peer if the peer misbehaves at the DHT-level.
-    /// Default: 2 hrs
-    #[serde(with = "serializers::seconds")]
-    pub ban_duration: Duration,
-    /// Length of time to ban a peer for a "short" duration.
+    /// Length of time to ban a peer that violates the messaging protocol.
     /// Default: 10 mins
     #[serde(with = "serializers::seconds")]
     pub ban_duration_short: Duration,
-    /// The maximum number of messages over `flood_ban_timespan` to allow before banning the peer (for
-    /// `ban_duration_short`) Default: 100_000 messages
+    /// The maximum number of messages over `flood_ban_timespan` to allow before reporting the peer for a protocol
+    /// violation. Default: 100_000 messages
     pub flood_ban_max_msg_count: usize,
     /// The timespan over which to calculate the max message rate.
     /// `flood_ban_max_count / flood_ban_timespan (as seconds) = avg. messages per second over the timespan`
@@ -160,14 +155,6 @@ impl DhtConfig {
     pub fn set_base_path<P: AsRef<Path>>(&mut self, base_path: P) {
         self.database_url.set_base_path(base_path);
     }
-
-    /// Returns a ban duration from the given severity
-    pub fn ban_duration_from_severity(&self, severity: OffenceSeverity) -> Duration {
-        match severity {
-            OffenceSeverity::Low | OffenceSeverity::Medium => self.ban_duration_short,
-            OffenceSeverity::High => self.ban_duration,
-        }
-    }
 }
 
 impl Default for DhtConfig {
@@ -191,7 +178,6 @@ impl Default for DhtConfig {
             auto_join: false,
             join_cooldown_interval: Duration::from_secs(10 * 60),
             network_discovery: Default::default(),
-            ban_duration: Duration::from_secs(2 * 60 * 60),
             ban_duration_short: Duration::from_secs(10 * 60),
             flood_ban_max_msg_count: 100_000,
             flood_ban_timespan: Duration::from_secs(100),
@@ -50,7 +50,7 @@ use tari_comms::{
         ConnectivitySelection,
     },
     multiaddr,
-    peer_manager::{NodeDistance, NodeId, Peer, PeerManagerError, PeerQuery, PeerQuerySortBy},
+    peer_manager::{NodeDistance, NodeId, Peer, PeerManagerError, PeerQuery, PeerQuerySortBy, ReputationEvent},
     Minimized,
     NodeIdentity,
     PeerConnection,
@@ -319,12 +319,12 @@ impl DhtConnectivity {
         for (peer, mps) in nodes {
             warn!(
                 target: LOG_TARGET,
-                "Banning peer `{}` because of flooding. Message rate: {:.2}m/s", peer, mps
+                "Reporting peer `{}` for flooding. Message rate: {:.2}m/s", peer, mps
             );
             self.connectivity
-                .ban_peer_until(
+                .report_peer(
                     peer,
-                    self.config.ban_duration_short,
+                    ReputationEvent::ProtocolViolation,
                     format!(
                         "Exceeded maximum message rate. Config: {}/{:#?}. Rate: {:.2} m/s",
                         self.config.flood_ban_max_msg_count, self.config.flood_ban_timespan, mps
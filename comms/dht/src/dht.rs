@@ -302,7 +302,6 @@ impl Dht {
             .layer(filter::FilterLayer::new(self.unsupported_saf_messages_filter()))
             .layer(filter::FilterLayer::new(discard_expired_messages))
             .layer(inbound::DecryptionLayer::new(
-                self.config.clone(),
                 self.node_identity.clone(),
                 self.connectivity.clone(),
             ))
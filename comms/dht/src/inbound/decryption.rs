@@ -20,7 +20,7 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::{convert::TryInto, sync::Arc, task::Poll, time::Duration};
+use std::{convert::TryInto, sync::Arc, task::Poll};
 
 use futures::{future::BoxFuture, task::Context};
 use log::*;
@@ -28,7 +28,7 @@ use prost::Message;
 use tari_comms::{
     connectivity::ConnectivityRequester,
     message::EnvelopeBody,
-    peer_manager::NodeIdentity,
+    peer_manager::{NodeIdentity, ReputationEvent},
     pipeline::PipelineError,
     types::CommsDHKE,
     BytesMut,
@@ -41,7 +41,6 @@ use crate::{
     crypt,
     inbound::message::{DecryptedDhtMessage, DhtInboundMessage, ValidatedDhtInboundMessage},
     message_signature::{MessageSignature, ProtoMessageSignature},
-    DhtConfig,
 };
 
 const LOG_TARGET: &str = "comms::middleware::decryption";
@@ -64,15 +63,13 @@ enum DecryptionError {
 pub struct DecryptionLayer {
     node_identity: Arc<NodeIdentity>,
     connectivity: ConnectivityRequester,
-    config: Arc<DhtConfig>,
 }
 
 impl DecryptionLayer {
-    pub fn new(config: Arc<DhtConfig>, node_identity: Arc<NodeIdentity>, connectivity: ConnectivityRequester) -> Self {
+    pub fn new(node_identity: Arc<NodeIdentity>, connectivity: ConnectivityRequester) -> Self {
         Self {
             node_identity,
             connectivity,
-            config,
         }
     }
 }
@@ -81,35 +78,23 @@ impl<S> Layer<S> for DecryptionLayer {
     type Service = DecryptionService<S>;
 
     fn layer(&self, service: S) -> Self::Service {
-        DecryptionService::new(
-            self.config.clone(),
-            self.node_identity.clone(),
-            self.connectivity.clone(),
-            service,
-        )
+        DecryptionService::new(self.node_identity.clone(), self.connectivity.clone(), service)
     }
 }
 
 /// Responsible for decrypting InboundMessages and passing a DecryptedInboundMessage to the given service
 #[derive(Clone)]
 pub struct DecryptionService<S> {
-    config: Arc<DhtConfig>,
     node_identity: Arc<NodeIdentity>,
     connectivity: ConnectivityRequester,
     inner: S,
 }
 
 impl<S> DecryptionService<S> {
-    pub fn new(
-        config: Arc<DhtConfig>,
-        node_identity: Arc<NodeIdentity>,
-        connectivity: ConnectivityRequester,
-        service: S,
-    ) -> Self {
+    pub fn new(node_identity: Arc<NodeIdentity>, connectivity: ConnectivityRequester, service: S) -> Self {
         Self {
             node_identity,
             connectivity,
-            config,
             inner: service,
         }
     }
@@ -133,7 +118,6 @@ where
             self.inner.clone(),
             Arc::clone(&self.node_identity),
             self.connectivity.clone(),
-            self.config.ban_duration,
             msg,
         ))
     }
@@ -146,7 +130,6 @@ where S: Service<DecryptedDhtMessage, Response = (), Error = PipelineError>
         next_service: S,
         node_identity: Arc<NodeIdentity>,
         mut connectivity: ConnectivityRequester,
-        ban_duration: Duration,
         message: DhtInboundMessage,
     ) -> Result<(), PipelineError> {
         use DecryptionError::*;
@@ -164,10 +147,14 @@ where S: Service<DecryptedDhtMessage, Response = (), Error = PipelineError>
                     target: LOG_TARGET,
                     "SECURITY: {} ({}, peer={}, trace={}). Message discarded", err, tag, source.node_id, trace_id
                 );
-                // This message should not have been propagated, or has been manipulated in some way. Ban the source of
-                // this message.
+                // This message should not have been propagated, or has been manipulated in some way. Report the source
+                // of this message.
                 connectivity
-                    .ban_peer_until(source.node_id.clone(), ban_duration, err.to_string())
+                    .report_peer(
+                        source.node_id.clone(),
+                        ReputationEvent::MaliciousBehaviour,
+                        err.to_string(),
+                    )
                     .await?;
                 Err(err.into())
             },
@@ -402,7 +389,7 @@ where S: Service<DecryptedDhtMessage, Response = (), Error = PipelineError>
 
 #[cfg(test)]
 mod test {
-    use std::sync::Mutex;
+    use std::{sync::Mutex, time::Duration};
 
     use futures::{executor::block_on, future};
     use tari_comms::{message::MessageExt, test_utils::mocks::create_connectivity_mock, wrap_in_envelope_body};
@@ -434,7 +421,7 @@ mod test {
                 future::ready(Result::<(), PipelineError>::Ok(()))
             }
         });
-        let mut service = DecryptionService::new(Default::default(), node_identity, connectivity, service);
+        let mut service = DecryptionService::new(node_identity, connectivity, service);
 
         // Receive the message and check for the expected error
         let err = service.call(message).await.unwrap_err();
@@ -445,11 +432,11 @@ mod test {
         // Assert the expected ban status
         if ban {
             mock_state.await_call_count(1).await;
-            assert_eq!(mock_state.count_calls_containing("BanPeer").await, 1);
+            assert_eq!(mock_state.count_calls_containing("ReportPeer").await, 1);
         } else {
             // Waiting like this isn't a guarantee that the peer won't be banned
             sleep(Duration::from_secs(1)).await;
-            assert_eq!(mock_state.count_calls_containing("BanPeer").await, 0);
+            assert_eq!(mock_state.count_calls_containing("ReportPeer").await, 0);
         }
     }
 
@@ -470,7 +457,7 @@ mod test {
                 future::ready(Result::<(), PipelineError>::Ok(()))
             }
         });
-        let mut service = DecryptionService::new(Default::default(), node_identity, connectivity, service);
+        let mut service = DecryptionService::new(node_identity, connectivity, service);
 
         // Receive the message and assert there were no errors
         block_on(service.call(message)).unwrap();
@@ -485,7 +472,7 @@ mod test {
         // Don't ban the peer
         // Waiting like this isn't a guarantee that the peer won't be banned later
         sleep(Duration::from_secs(1)).await;
-        assert_eq!(mock_state.count_calls_containing("BanPeer").await, 0);
+        assert_eq!(mock_state.count_calls_containing("ReportPeer").await, 0);
 
         // Return the decrypted message for further handling; decryption may have failed
         decrypted
@@ -496,7 +483,7 @@ mod test {
         let service = service_fn(|_: DecryptedDhtMessage| future::ready(Result::<(), PipelineError>::Ok(())));
         let node_identity = make_node_identity();
         let (connectivity, _) = create_connectivity_mock();
-        let mut service = DecryptionService::new(Default::default(), node_identity, connectivity, service);
+        let mut service = DecryptionService::new(node_identity, connectivity, service);
 
         counter_context!(cx, counter);
 
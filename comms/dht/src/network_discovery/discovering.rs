@@ -268,11 +268,7 @@ impl Discovering {
         if let Err(e) = self
             .context
             .connectivity
-            .ban_peer_until(
-                peer.clone(),
-                self.config().ban_duration_from_severity(severity),
-                err.to_string(),
-            )
+            .report_peer(peer.clone(), severity.reputation_event(), err.to_string())
             .await
         {
             warn!(
@@ -24,7 +24,11 @@ use std::convert::TryInto;
 
 use futures::StreamExt;
 use log::*;
-use tari_comms::{connectivity::ConnectivityEvent, peer_manager::NodeId, PeerConnection};
+use tari_comms::{
+    connectivity::ConnectivityEvent,
+    peer_manager::{NodeId, ReputationEvent},
+    PeerConnection,
+};
 use tokio::sync::broadcast;
 
 use crate::{
@@ -84,13 +88,13 @@ impl OnConnect {
                     match self.sync_peers(*conn.clone()).await {
                         Ok(_) => continue,
                         Err(err @ NetworkDiscoveryError::PeerValidationError(_)) => {
-                            warn!(target: LOG_TARGET, "{}. Banning peer.", err);
+                            warn!(target: LOG_TARGET, "{}. Reporting peer.", err);
                             if let Err(err) = self
                                 .context
                                 .connectivity
-                                .ban_peer_until(
+                                .report_peer(
                                     conn.peer_node_id().clone(),
-                                    self.config().ban_duration,
+                                    ReputationEvent::MaliciousBehaviour,
                                     err.to_string(),
                                 )
                                 .await
@@ -41,15 +41,44 @@ impl RpcCodeGenerator {
     }
 
     pub fn generate(self) -> TokenStream {
+        let method_ids = self.generate_method_ids();
         let server_code = self.generate_server_code();
         let client_code = self.generate_client_code();
 
         quote! {
+            #method_ids
             #server_code
             #client_code
         }
     }
 
+    fn generate_method_ids(&self) -> TokenStream {
+        let mod_ident = syn::Ident::new(
+            &format!("{}_methods", to_snake_case(&self.trait_ident.to_string())),
+            self.trait_ident.span(),
+        );
+        let doc = format!("Method identifiers for the `{}` RPC service", self.trait_ident);
+        let consts = self
+            .rpc_methods
+            .iter()
+            .map(|m| {
+                let name = syn::Ident::new(&m.method_ident.to_string().to_uppercase(), m.method_ident.span());
+                let method_num = m.method_num;
+                quote! {
+                    pub const #name: u32 = #method_num;
+                }
+            })
+            .collect::<TokenStream>();
+
+        quote! {
+            #[doc = #doc]
+            #[allow(dead_code)]
+            pub mod #mod_ident {
+                #consts
+            }
+        }
+    }
+
     fn generate_server_code(&self) -> TokenStream {
         let server_struct = self.options.server_struct.as_ref().unwrap();
         let trait_ident = &self.trait_ident;
@@ -209,6 +238,11 @@ impl RpcCodeGenerator {
 
             #client_methods
 
+            /// Resume a streaming response from the position recorded in the resumption token
+            pub async fn resume_stream<R>(&mut self, token: #dep_mod::ResumptionToken<R>) -> Result<#dep_mod::ClientStreaming<R>, #dep_mod::RpcError> {
+                self.inner.resume_streaming(token).await
+            }
+
             pub fn get_last_request_latency(&mut self) -> Option<std::time::Duration> {
                 self.inner.get_last_request_latency()
             }
@@ -251,6 +285,21 @@ impl RpcCodeGenerator {
     }
 }
 
+fn to_snake_case(s: &str) -> String {
+    let mut snake = String::with_capacity(s.len() + 4);
+    for (i, ch) in s.chars().enumerate() {
+        if ch.is_uppercase() {
+            if i > 0 {
+                snake.push('_');
+            }
+            snake.extend(ch.to_lowercase());
+        } else {
+            snake.push(ch);
+        }
+    }
+    snake
+}
+
 fn is_unit_type(ty: &syn::Type) -> bool {
     match ty {
         syn::Type::Tuple(tuple) => tuple.elems.is_empty(),
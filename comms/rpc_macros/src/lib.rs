@@ -69,6 +69,9 @@ mod options;
 /// `rpc` attribute
 /// - `method` is a unique number that uniquely identifies each function within the service. Once a `method` is used it
 ///   should never be reused (think protobuf field numbers).
+///
+/// The method numbers are also generated as constants in a module named after the trait, e.g.
+/// `greeting_rpc_methods::SAY_HELLO` for the trait above. Use these to refer to methods in RPC middleware.
 #[proc_macro_attribute]
 pub fn tari_rpc(attr: TokenStream, item: TokenStream) -> TokenStream {
     let options = syn::parse_macro_input!(attr as options::RpcTraitOptions);
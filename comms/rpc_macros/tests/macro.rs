@@ -105,6 +105,13 @@ fn it_sets_the_protocol_name() {
     assert_eq!(TestClient::PROTOCOL_NAME, b"/test/protocol/123");
 }
 
+#[test]
+fn it_generates_method_id_constants() {
+    assert_eq!(test_methods::REQUEST_RESPONSE, 1);
+    assert_eq!(test_methods::SERVER_STREAMING, 2);
+    assert_eq!(test_methods::UNIT, 3);
+}
+
 #[tokio::test]
 async fn it_returns_the_correct_type() {
     let mut server = TestServer::new(TestService::default());
@@ -154,6 +154,10 @@ async fn send_from_cli(world: &mut TariWorld, amount: u64, wallet_a: String, wal
         amount: MicroMinotari(amount),
         message: format!("Send amount {} from {} to {}", amount, wallet_a, wallet_b),
         destination: wallet_b_address,
+        fee_per_gram: None,
+        account: None,
+        labels: vec![],
+        avoid_mixing_labels: false,
     };
     cli.command2 = Some(CliCommands::SendMinotari(args));
 
@@ -111,7 +111,7 @@ async fn wait_for_wallet_to_have_micro_tari(world: &mut TariWorld, wallet: Strin
     for _ in 0..=num_retries {
         let _result = client.validate_all_transactions(ValidateRequest {}).await;
         curr_amount = client
-            .get_balance(GetBalanceRequest {})
+            .get_balance(GetBalanceRequest::default())
             .await
             .unwrap()
             .into_inner()
@@ -158,7 +158,7 @@ async fn wallet_detects_all_txs_as_mined_status(world: &mut TariWorld, wallet_na
     let mut client = create_wallet_client(world, wallet_name.clone()).await.unwrap();
 
     let mut completed_tx_stream = client
-        .get_completed_transactions(GetCompletedTransactionsRequest {})
+        .get_completed_transactions(GetCompletedTransactionsRequest::default())
         .await
         .unwrap()
         .into_inner();
@@ -492,7 +492,7 @@ async fn list_all_txs_for_wallet(world: &mut TariWorld, transaction_type: String
     }
     let mut client = create_wallet_client(world, wallet.clone()).await.unwrap();
 
-    let request = GetCompletedTransactionsRequest {};
+    let request = GetCompletedTransactionsRequest::default();
     let mut completed_txs = client.get_completed_transactions(request).await.unwrap().into_inner();
 
     while let Some(tx) = completed_txs.next().await {
@@ -540,7 +540,7 @@ async fn wallet_has_at_least_num_txs(world: &mut TariWorld, wallet: String, num_
 
     for _ in 0..num_retries {
         let mut txs = client
-            .get_completed_transactions(grpc::GetCompletedTransactionsRequest {})
+            .get_completed_transactions(grpc::GetCompletedTransactionsRequest::default())
             .await
             .unwrap()
             .into_inner();
@@ -622,7 +622,7 @@ async fn wait_for_wallet_to_have_less_than_micro_tari(world: &mut TariWorld, wal
     println!("Waiting for wallet {} to have less than {} uT", wallet, amount);
 
     let num_retries = 100;
-    let request = GetBalanceRequest {};
+    let request = GetBalanceRequest::default();
 
     for _ in 0..num_retries {
         let balance_res = client.get_balance(request.clone()).await.unwrap().into_inner();
@@ -713,6 +713,7 @@ async fn send_amount_from_source_wallet_to_dest_wallet_without_broadcast(
     };
     let transfer_req = TransferRequest {
         recipients: vec![payment_recipient],
+        ..Default::default()
     };
     let tx_res = source_client.transfer(transfer_req).await.unwrap().into_inner();
     let tx_res = tx_res.results;
@@ -774,6 +775,7 @@ async fn send_one_sided_transaction_from_source_wallet_to_dest_wallt(
     };
     let transfer_req = TransferRequest {
         recipients: vec![payment_recipient],
+        ..Default::default()
     };
     let tx_res = source_client.transfer(transfer_req).await.unwrap().into_inner();
     let tx_res = tx_res.results;
@@ -874,6 +876,7 @@ async fn send_amount_from_wallet_to_wallet_at_fee(
     };
     let transfer_req = TransferRequest {
         recipients: vec![payment_recipient],
+        ..Default::default()
     };
     let tx_res = sender_wallet_client.transfer(transfer_req).await.unwrap().into_inner();
     let tx_res = tx_res.results;
@@ -948,7 +951,7 @@ async fn send_amount_from_wallet_to_wallet_at_fee(
 async fn wallet_detects_at_least_coinbase_transactions(world: &mut TariWorld, wallet_name: String, coinbases: u64) {
     let mut client = create_wallet_client(world, wallet_name.clone()).await.unwrap();
     let mut completed_tx_res = client
-        .get_completed_transactions(GetCompletedTransactionsRequest {})
+        .get_completed_transactions(GetCompletedTransactionsRequest::default())
         .await
         .unwrap()
         .into_inner();
@@ -1004,7 +1007,7 @@ async fn wallet_detects_at_least_coinbase_unconfirmed_transactions(
 ) {
     let mut client = create_wallet_client(world, wallet_name.clone()).await.unwrap();
     let mut completed_tx_res = client
-        .get_completed_transactions(GetCompletedTransactionsRequest {})
+        .get_completed_transactions(GetCompletedTransactionsRequest::default())
         .await
         .unwrap()
         .into_inner();
@@ -1219,7 +1222,7 @@ async fn wallets_should_have_at_least_num_spendable_coinbase_outs(
 
         'inner: for _ in 0..num_retries {
             let mut stream = client
-                .get_completed_transactions(GetCompletedTransactionsRequest {})
+                .get_completed_transactions(GetCompletedTransactionsRequest::default())
                 .await
                 .unwrap()
                 .into_inner();
@@ -1304,6 +1307,7 @@ async fn send_num_transactions_to_wallets_at_fee(
         };
         let transfer_req = TransferRequest {
             recipients: vec![payment_recipient],
+            ..Default::default()
         };
         let transfer_res = sender_wallet_client.transfer(transfer_req).await.unwrap().into_inner();
         let transfer_res = transfer_res.results.first().unwrap();
@@ -1446,6 +1450,7 @@ async fn transfer_tari_from_wallet_to_receiver(world: &mut TariWorld, amount: u6
     };
     let transfer_req = TransferRequest {
         recipients: vec![payment_recipient],
+        ..Default::default()
     };
     let tx_res = sender_wallet_client.transfer(transfer_req).await.unwrap().into_inner();
     let tx_res = tx_res.results;
@@ -1528,7 +1533,7 @@ async fn wallet_has_tari(world: &mut TariWorld, wallet: String, amount: u64) {
     for _ in 0..num_retries {
         let _result = wallet_client.validate_all_transactions(ValidateRequest {}).await;
         let balance_res = wallet_client
-            .get_balance(GetBalanceRequest {})
+            .get_balance(GetBalanceRequest::default())
             .await
             .unwrap()
             .into_inner();
@@ -1595,7 +1600,7 @@ async fn wallet_with_tari_connected_to_base_node(
     for _ in 0..num_retries {
         let _result = wallet_client.validate_all_transactions(ValidateRequest {}).await;
         let balance_res = wallet_client
-            .get_balance(GetBalanceRequest {})
+            .get_balance(GetBalanceRequest::default())
             .await
             .unwrap()
             .into_inner();
@@ -1655,6 +1660,7 @@ async fn transfer_from_wallet_to_two_recipients_at_fee(
     };
     let transfer_req = TransferRequest {
         recipients: vec![payment_recipient1, payment_recipient2],
+        ..Default::default()
     };
     let tx_res = sender_client.transfer(transfer_req).await.unwrap().into_inner();
     let tx_res = tx_res.results;
@@ -1767,6 +1773,7 @@ async fn transfer_tari_to_self(world: &mut TariWorld, amount: u64, sender: Strin
     };
     let transfer_req = TransferRequest {
         recipients: vec![payment_recipient],
+        ..Default::default()
     };
     let tx_res = sender_wallet_client.transfer(transfer_req).await.unwrap().into_inner();
     let tx_res = tx_res.results;
@@ -2080,7 +2087,7 @@ async fn wait_for_wallet_to_have_less_than_amount(world: &mut TariWorld, wallet:
     for _ in 0..=num_retries {
         let _result = client.validate_all_transactions(ValidateRequest {}).await;
         curr_amount = client
-            .get_balance(GetBalanceRequest {})
+            .get_balance(GetBalanceRequest::default())
             .await
             .unwrap()
             .into_inner()
@@ -2143,6 +2150,7 @@ async fn send_one_sided_stealth_transaction(
     };
     let transfer_req = TransferRequest {
         recipients: vec![payment_recipient],
+        ..Default::default()
     };
     let tx_res = sender_client.transfer(transfer_req).await.unwrap().into_inner();
     let tx_res = tx_res.results;
@@ -2568,7 +2576,7 @@ async fn restart_wallet(world: &mut TariWorld, wallet: String) {
 async fn check_if_wallet_has_num_transactions(world: &mut TariWorld, wallet: String, num_txs: u64) {
     let mut client = create_wallet_client(world, wallet.clone()).await.unwrap();
     let mut get_completed_txs_res = client
-        .get_completed_transactions(GetCompletedTransactionsRequest {})
+        .get_completed_transactions(GetCompletedTransactionsRequest::default())
         .await
         .unwrap()
         .into_inner();
@@ -2636,6 +2644,7 @@ async fn multi_send_txs_from_wallet(
 
         let transfer_req = TransferRequest {
             recipients: vec![payment_recipient],
+            ..Default::default()
         };
         let tx_res = sender_wallet_client.transfer(transfer_req).await.unwrap().into_inner();
         let tx_res = tx_res.results;
@@ -2716,7 +2725,7 @@ async fn multi_send_txs_from_wallet(
 async fn check_if_last_imported_txs_are_invalid_in_wallet(world: &mut TariWorld, wallet: String) {
     let mut client = create_wallet_client(world, wallet.clone()).await.unwrap();
     let mut get_completed_txs_res = client
-        .get_completed_transactions(GetCompletedTransactionsRequest {})
+        .get_completed_transactions(GetCompletedTransactionsRequest::default())
         .await
         .unwrap()
         .into_inner();
@@ -2739,7 +2748,7 @@ async fn check_if_last_imported_txs_are_invalid_in_wallet(world: &mut TariWorld,
 async fn check_if_last_imported_txs_are_valid_in_wallet(world: &mut TariWorld, wallet: String) {
     let mut client = create_wallet_client(world, wallet.clone()).await.unwrap();
     let mut get_completed_txs_res = client
-        .get_completed_transactions(GetCompletedTransactionsRequest {})
+        .get_completed_transactions(GetCompletedTransactionsRequest::default())
         .await
         .unwrap()
         .into_inner();